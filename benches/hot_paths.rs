@@ -0,0 +1,212 @@
+//! 热点路径基准测试
+//!
+//! 运行方式：
+//!
+//! ```text
+//! cargo bench --bench hot_paths                 # 运行全部基准
+//! cargo bench --bench hot_paths -- tag_filter   # 只运行名称匹配的分组
+//! cargo bench --bench hot_paths -- --save-baseline before
+//! cargo bench --bench hot_paths -- --baseline before
+//! ```
+//!
+//! 重构（如 SQL 分页、类型化枚举）前先保存基线，重构后与基线对比，
+//! 报告输出在 `target/criterion/`。
+
+use std::{collections::HashMap, hint::black_box};
+
+use chrono::{TimeZone, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use server_api_rt::{
+    entities::{files, server, server_stats},
+    schemas::pagination::Paginated,
+    services::server::ServerService,
+};
+
+const LIST_SIZES: [usize; 3] = [20, 200, 2000];
+const TAG_POOL: [&str; 8] = [
+    "生存",
+    "纯净",
+    "生电",
+    "红石",
+    "RPG",
+    "PVP",
+    "小游戏",
+    "建筑",
+];
+
+fn build_servers(count: usize) -> Vec<server::Model> {
+    (0..count)
+        .map(|i| server::Model {
+            id: i as i32 + 1,
+            name: format!("基准服务器 {i}"),
+            r#type: if i % 5 == 0 { "BEDROCK" } else { "JAVA" }.to_string(),
+            version: "1.20.1".to_string(),
+            desc: "我们提供丰富的游戏内容和友好的社区环境。".repeat(8),
+            link: format!("https://example.com/{i}"),
+            ip: format!("mc{i}.example.com"),
+            is_member: i % 2 == 0,
+            is_hide: i % 20 == 0,
+            auth_mode: "OFFICIAL".to_string(),
+            tags: json!([
+                TAG_POOL[i % TAG_POOL.len()],
+                TAG_POOL[(i * 3 + 1) % TAG_POOL.len()]
+            ]),
+            cover_hash_id: Some(format!("cover-{i}")),
+            icon_hash_id: None,
+            gallery_id: None,
+            push_secret: None,
+            deactivated_at: None,
+            slug: Some(format!("server-{i}")),
+            slug_edited: false,
+            visibility: "public".to_string(),
+            player_search_opt_out: false,
+            offline_flagged_at: None,
+            delisted_at: None,
+            delisting_exempt: false,
+            tenant_id: "default".to_string(),
+            updated_at: None,
+            version_range: None,
+            version_min_code: None,
+            version_max_code: None,
+            status: "published".to_string(),
+            review_note: None,
+        })
+        .collect()
+}
+
+fn stat_data(online: i64) -> Value {
+    json!({
+        "players": {"online": online, "max": 200},
+        "delay": 42.5,
+        "version": "Paper 1.20.1",
+        "motd": {
+            "plain": "欢迎来到我的世界服务器 | 生存 · 红石 · 小游戏",
+            "html": "<span style='color: #55FF55;'>欢迎来到</span><span style='color: #FFAA00; font-weight: bold;'>我的世界服务器</span> <span style='color: #AAAAAA;'>|</span> <span style='color: #55FFFF;'>生存 · 红石 · 小游戏</span>",
+            "minecraft": "§a欢迎来到§6§l我的世界服务器 §7| §b生存 · 红石 · 小游戏",
+            "ansi": "\u{1b}[92m欢迎来到\u{1b}[33;1m我的世界服务器 \u{1b}[0;37m| \u{1b}[96m生存 · 红石 · 小游戏\u{1b}[0m"
+        },
+        "icon": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAA"
+    })
+}
+
+fn build_stats(servers: &[server::Model]) -> Vec<server_stats::Model> {
+    let timestamp = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    servers
+        .iter()
+        .map(|s| server_stats::Model {
+            id: s.id,
+            timestamp,
+            stat_data: Some(stat_data(s.id as i64 % 200)),
+            server_id: s.id,
+        })
+        .collect()
+}
+
+fn bench_tag_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tag_filter");
+    let required = vec!["红石".to_string(), "小游戏".to_string()];
+
+    for size in LIST_SIZES {
+        let servers = build_servers(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &servers, |b, servers| {
+            b.iter(|| {
+                servers
+                    .iter()
+                    .filter(|s| ServerService::server_has_required_tags(&s.tags, &required))
+                    .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_stats_parsing(c: &mut Criterion) {
+    let data = stat_data(64);
+    c.bench_function("stats_parse", |b| {
+        b.iter(|| ServerService::parse_server_stats(black_box(&data)))
+    });
+}
+
+/// MOTD 目前由外部写入四种格式，这里衡量带大量颜色代码的 MOTD 在解析中的开销
+fn bench_motd(c: &mut Criterion) {
+    let mut group = c.benchmark_group("motd");
+
+    for segments in [1usize, 16, 64] {
+        let minecraft = "§a欢迎§6§l来到§r§b我的世界 ".repeat(segments);
+        let html = "<span style='color: #55FF55;'>欢迎</span><span style='color: #FFAA00; font-weight: bold;'>来到</span><span style='color: #55FFFF;'>我的世界 </span>".repeat(segments);
+        let ansi = "\u{1b}[92m欢迎\u{1b}[33;1m来到\u{1b}[0;96m我的世界 \u{1b}[0m".repeat(segments);
+        let mut data = stat_data(64);
+        data["motd"] = json!({
+            "plain": "欢迎来到我的世界 ".repeat(segments),
+            "html": html,
+            "minecraft": minecraft,
+            "ansi": ansi,
+        });
+
+        group.bench_with_input(BenchmarkId::from_parameter(segments), &data, |b, data| {
+            b.iter(|| ServerService::parse_server_stats(black_box(data)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_list_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_serialization");
+
+    for size in LIST_SIZES {
+        let servers = build_servers(size);
+        let stats = build_stats(&servers);
+        let stats_map: HashMap<i32, &server_stats::Model> =
+            stats.iter().map(|s| (s.server_id, s)).collect();
+        let cover_files: Vec<files::Model> = servers
+            .iter()
+            .filter_map(|s| s.cover_hash_id.clone())
+            .map(|hash| files::Model {
+                file_path: format!("https://cdn.example.com/{hash}.webp"),
+                hash_value: hash,
+                scan_status: None,
+                scan_engine: None,
+                scan_detail: None,
+                scanned_at: None,
+                has_variants: true,
+                uploaded_at: None,
+            })
+            .collect();
+        let cover_file_map: HashMap<String, &files::Model> = cover_files
+            .iter()
+            .map(|file| (file.hash_value.clone(), file))
+            .collect();
+        let permissions = HashMap::new();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &servers, |b, servers| {
+            b.iter(|| {
+                let data = ServerService::convert_servers_to_details(
+                    servers.clone(),
+                    &stats_map,
+                    &permissions,
+                    &cover_file_map,
+                )
+                .unwrap();
+                let response = Paginated::new(data, 1, size as u64, size as u64);
+                serde_json::to_vec(&response).unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_tag_filter,
+    bench_stats_parsing,
+    bench_motd,
+    bench_list_serialization
+);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! 合成压测数据生成命令行工具
+//!
+//! 用法: `cargo run --features dev-tools --bin seed -- [服务器数量] [每台历史状态条数] [每台相册图片数]`
+
+use server_api_rt::{
+    config::Config, logging::init_logging, schemas::dev_tools::SeedRequest,
+    services::database::establish_connection, services::dev_tools::SyntheticDataService,
+};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logging()?;
+
+    let mut args = std::env::args().skip(1).map(|arg| arg.parse::<u32>());
+    let defaults = SeedRequest::default();
+    let request = SeedRequest {
+        count: args.next().transpose()?.unwrap_or(defaults.count),
+        stats_per_server: args
+            .next()
+            .transpose()?
+            .unwrap_or(defaults.stats_per_server),
+        gallery_images_per_server: args
+            .next()
+            .transpose()?
+            .unwrap_or(defaults.gallery_images_per_server),
+        ..defaults
+    };
+
+    let config = Config::from_env()?;
+    let db = establish_connection(&config.database).await?;
+
+    let summary = SyntheticDataService::generate(&db, &request)
+        .await
+        .map_err(|e| anyhow::anyhow!("生成合成数据失败: {e}"))?;
+
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
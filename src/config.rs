@@ -1,15 +1,48 @@
-use anyhow::Result;
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, bail, Result};
 use serde::Deserialize;
 
+use crate::errors::{ApiError, ApiResult};
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub database: DatabaseConfig,
     pub server: ServerConfig,
+    pub cors: CorsConfig,
     pub jwt: JwtConfig,
+    pub password: PasswordConfig,
     pub redis: RedisConfig,
-    pub s3: S3Config,
-    pub email: EmailConfig,
+    /// 文件存储，未配置时图片上传不可用
+    pub storage: Option<StorageConfig>,
+    /// 发信服务，未配置时邮件验证码与邮件通知不可用
+    pub email: Option<EmailConfig>,
     pub meilisearch: MeilisearchConfig,
+    pub signing: SigningConfig,
+    pub sandbox: SandboxConfig,
+    pub internal: InternalConfig,
+    pub archive: ArchiveConfig,
+    pub registration_guard: RegistrationGuardConfig,
+    pub scan_guard: ScanGuardConfig,
+    pub rate_limit: RateLimitConfig,
+    pub throttle: ThrottleConfig,
+    pub translation: TranslationConfig,
+    pub spam_guard: SpamGuardConfig,
+    pub name_policy: NamePolicyConfig,
+    pub account_link: AccountLinkConfig,
+    pub oauth: OAuthConfig,
+    pub embedding: EmbeddingConfig,
+    pub status: StatusConfig,
+    pub upload_scan: UploadScanConfig,
+    pub tenant: TenantConfig,
+    pub sitemap: SitemapConfig,
+    pub notification: NotificationConfig,
+    pub delisting: DelistingConfig,
+    pub ping: PingConfig,
+    pub file_gc: FileGcConfig,
+    /// MOTD 图片，未配置字体时不可用
+    pub motd_image: Option<MotdImageConfig>,
+    pub jobs: JobsConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -20,18 +53,57 @@ pub struct DatabaseConfig {
     pub connect_timeout: u64,
     pub acquire_timeout: u64,
     pub idle_timeout: u64,
+    /// 连接池饱和时是否直接返回 503
+    pub shed_on_saturation: bool,
+    /// 只读副本连接地址，为空时所有查询走主库
+    pub replica_urls: Vec<String>,
+    /// 用户写入后读请求固定走主库的时长（秒）
+    pub replica_sticky_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// 启动时输出全部路由的鉴权要求与限流分类
+    pub log_routes: bool,
+    /// 服务前方可信的反向代理层数，用于从 `X-Forwarded-For` 中取出客户端 IP，
+    /// 0 表示直接对外提供服务
+    pub trusted_proxy_hops: usize,
+}
+
+/// 跨域策略
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CorsMode {
+    /// 允许任意来源，不携带凭据，用于本地开发
+    Permissive,
+    /// 只允许列出的来源
+    Strict,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CorsConfig {
+    pub mode: CorsMode,
+    /// 严格模式下允许的来源，如 `https://example.com`
+    pub allowed_origins: Vec<String>,
+    /// 严格模式下是否允许携带凭据（Cookie、Authorization 等）
+    pub allow_credentials: bool,
+    /// 预检请求结果的缓存时间（秒）
+    pub max_age: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct JwtConfig {
     pub secret: String,
     pub expiration: u64,
+    /// 管理员代入用户身份时签发的令牌有效期（秒）
+    pub impersonation_ttl_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PasswordConfig {
+    /// 新密码哈希使用的 bcrypt 成本，登录时会把成本不同的旧哈希重新计算
+    pub bcrypt_cost: u32,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -47,6 +119,50 @@ pub struct S3Config {
     pub access_key: String,
     pub secret_key: String,
     pub bucket: String,
+    /// 请求失败后的最大重试次数（仅限可重试的错误）
+    pub max_retries: u32,
+    /// 首次重试前的基础等待时间（毫秒），之后按指数增长并加入随机抖动
+    pub retry_base_delay_ms: u64,
+    /// 启动时是否检查存储桶可访问，检查失败时拒绝启动
+    pub verify_on_startup: bool,
+    /// 所有 S3 请求共用的 HTTP 客户端，随配置克隆时共享连接池
+    #[serde(skip)]
+    pub http: reqwest::Client,
+}
+
+/// 文件存储方式
+#[derive(Debug, Deserialize, Clone)]
+pub enum StorageConfig {
+    /// S3 兼容的对象存储
+    S3(S3Config),
+    /// 本地磁盘，文件通过 `/static` 路由对外提供，适合没有对象存储的小型部署
+    Local(LocalStorageConfig),
+}
+
+impl StorageConfig {
+    /// S3 配置；相册归档依赖 S3 的存储类型，只在 S3 存储下可用
+    pub fn s3(&self) -> Option<&S3Config> {
+        match self {
+            StorageConfig::S3(s3) => Some(s3),
+            StorageConfig::Local(_) => None,
+        }
+    }
+
+    /// 启动时是否检查存储可用，检查失败时拒绝启动
+    pub fn verify_on_startup(&self) -> bool {
+        match self {
+            StorageConfig::S3(s3) => s3.verify_on_startup,
+            StorageConfig::Local(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LocalStorageConfig {
+    /// 文件保存目录，对象键即相对该目录的路径
+    pub root: PathBuf,
+    /// `/static` 响应的缓存时间（秒）；文件名随机生成且内容不会改变，可以设置得较长
+    pub cache_max_age_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -57,10 +173,451 @@ pub struct EmailConfig {
     pub smtp_password: String,
 }
 
+impl EmailConfig {
+    /// 取出发信配置，未配置时返回功能未启用
+    pub fn require(config: Option<&EmailConfig>) -> ApiResult<&EmailConfig> {
+        config.ok_or_else(|| ApiError::FeatureDisabled("未配置发信服务，无法发送邮件".to_string()))
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct MeilisearchConfig {
     pub url: String,
     pub api_key: String,
+    /// 索引任务失败后的最大重试次数
+    pub task_max_retries: u32,
+    /// 任务未完成超过该时长（秒）时告警
+    pub task_stale_secs: u64,
+    /// 索引与数据库文档数差异超过该值时告警
+    pub lag_alert_threshold: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SigningConfig {
+    /// 签名时间戳允许的最大偏差（秒），超出视为重放
+    pub replay_window_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SandboxConfig {
+    /// 是否挂载 `/v2/sandbox` 沙盒接口
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct InternalConfig {
+    /// 内部接口（采集器等）使用的访问令牌，为空时内部接口不可用
+    pub api_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ArchiveConfig {
+    /// 是否启用相册归档任务
+    pub enabled: bool,
+    /// 归档对象在存储桶中的前缀
+    pub prefix: String,
+    /// 归档对象使用的存储类型，需为可直接读取的类型（如 STANDARD_IA、GLACIER_IR）
+    pub storage_class: String,
+    /// 服务器停用多少天后归档相册
+    pub after_days: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegistrationGuardConfig {
+    /// 同一 IP 在窗口期内注册超过该数量时标记
+    pub ip_burst_threshold: i64,
+    /// IP 注册计数窗口（秒）
+    pub ip_burst_window_secs: u64,
+    /// 24 小时内同前缀、数字结尾的用户名超过该数量时标记
+    pub sequential_username_threshold: u64,
+    /// 需要标记的邮箱域名
+    pub flagged_email_domains: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScanGuardConfig {
+    /// 是否启用服务器详情防遍历保护
+    pub enabled: bool,
+    /// 连续访问相邻 ID 超过该次数时封禁
+    pub sequential_threshold: i64,
+    /// 连续访问的判定窗口（秒）
+    pub sequential_window_secs: u64,
+    /// 单个 IP 在统计窗口内可查看的详情总数
+    pub total_cap: i64,
+    /// 总数统计窗口（秒）
+    pub total_cap_window_secs: u64,
+    /// 触发后的封禁时长（秒）
+    pub block_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitConfig {
+    /// 是否启用登录与注册验证码限流
+    pub enabled: bool,
+    /// 登录失败次数限制
+    pub login: RateLimitRule,
+    /// 注册验证码请求次数限制
+    pub email_code: RateLimitRule,
+    /// 首次锁定时长（秒），一天内再次锁定时翻倍
+    pub lockout_secs: u64,
+    /// 锁定时长上限（秒）
+    pub max_lockout_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitRule {
+    /// 窗口期内同一账户允许的次数，同一 IP 允许其 4 倍
+    pub max_attempts: i64,
+    /// 计数窗口（秒）
+    pub window_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ThrottleConfig {
+    /// 是否启用按路由分组的令牌桶限流
+    pub enabled: bool,
+    /// 搜索接口
+    pub search: TokenBucketRule,
+    /// 相册图片上传
+    pub gallery_upload: TokenBucketRule,
+    /// 发送邮件的接口（注册验证码、密码重置）
+    pub email_send: TokenBucketRule,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TokenBucketRule {
+    /// 桶容量，即允许的突发请求数
+    pub burst: u32,
+    /// 每分钟补充的令牌数
+    pub per_minute: u32,
+}
+
+impl TokenBucketRule {
+    fn from_env(prefix: &str, burst: u32, per_minute: u32) -> Self {
+        Self {
+            burst: std::env::var(format!("{prefix}_BURST"))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(burst),
+            per_minute: std::env::var(format!("{prefix}_PER_MINUTE"))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(per_minute),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TranslationConfig {
+    /// 机器翻译服务提供方（libretranslate / deepl），为空时不启用
+    pub provider: Option<String>,
+    /// 翻译服务地址
+    pub api_url: String,
+    /// 翻译服务密钥
+    pub api_key: Option<String>,
+    /// 服务器描述的原始语言
+    pub source_locale: String,
+    /// 需要自动补全的目标语言
+    pub target_locales: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SpamGuardConfig {
+    /// 评分达到该值时内容被自动扣留待审核（0~100）
+    pub hold_threshold: i32,
+    /// 单个用户在窗口期内可发布的内容数，超出后计入评分
+    pub rate_limit: i64,
+    /// 发布频率统计窗口（秒）
+    pub rate_window_secs: u64,
+    /// 重复内容判定窗口（秒）
+    pub duplicate_window_secs: u64,
+    /// 注册不足该小时数的账户视为新账户
+    pub new_account_hours: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NamePolicyConfig {
+    /// 保留名称，用户名与显示名称不能等于或包含这些名称（忽略大小写与分隔符）
+    pub reserved_names: Vec<String>,
+    /// 与管理人员名称的最大编辑距离，不超过该值视为仿冒
+    pub staff_similarity_distance: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AccountLinkConfig {
+    /// 允许绑定的外部平台
+    pub providers: Vec<String>,
+    /// 绑定凭证有效期（秒）
+    pub proof_ttl_secs: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OAuthConfig {
+    /// GitHub 登录，未配置时不可用
+    pub github: Option<OAuthClientConfig>,
+    /// Microsoft 登录，未配置时不可用
+    pub microsoft: Option<OAuthClientConfig>,
+    /// Microsoft 允许登录的账户类型：consumers（个人账户）、organizations、common 或目录租户 ID
+    pub microsoft_tenant: String,
+    /// 第三方平台授权后跳回的地址，`{provider}` 会替换为平台标识；
+    /// 该地址需要把 `code` 与 `state` 原样交给回调接口
+    pub redirect_url: String,
+    /// 授权请求的有效期（秒）
+    pub state_ttl_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmbeddingConfig {
+    /// 向量服务提供方（openai，兼容 OpenAI Embeddings 接口的服务均可），为空时不启用
+    pub provider: Option<String>,
+    /// 向量服务地址
+    pub api_url: String,
+    /// 向量服务密钥
+    pub api_key: Option<String>,
+    /// 向量模型
+    pub model: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StatusConfig {
+    /// 每个依赖在 Redis 中保留的采样条数
+    pub history_size: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UploadScanConfig {
+    /// 扫描方式（clamav / icap），为空时不扫描
+    pub backend: Option<String>,
+    /// 扫描服务地址（host:port）
+    pub address: String,
+    /// ICAP 服务名
+    pub icap_service: String,
+    /// 单个文件的扫描超时（秒）
+    pub timeout_secs: u64,
+    /// 扫描服务不可用时是否仍允许上传
+    pub fail_open: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TenantConfig {
+    /// 多租户模式（off / host / path），off 时所有数据归属默认租户
+    pub mode: String,
+    /// 默认租户，path 模式下未带前缀的请求与 off 模式下的全部请求使用该租户
+    pub default_tenant: String,
+    /// 已配置的租户
+    pub tenants: Vec<TenantDefinition>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TenantDefinition {
+    /// 租户标识，写入 servers / users 的 tenant_id
+    pub id: String,
+    /// host 模式下归属该租户的域名
+    pub hosts: Vec<String>,
+    /// 展示名称
+    pub name: Option<String>,
+    /// 租户使用的搜索索引
+    pub search_index: String,
+    /// 是否开放注册
+    pub allow_registration: bool,
+}
+
+impl TenantDefinition {
+    /// 读取 `TENANT_<ID>_*` 环境变量构建租户配置
+    fn from_env(id: &str, is_default: bool) -> Self {
+        let prefix = format!("TENANT_{}", id.to_uppercase().replace('-', "_"));
+        Self {
+            id: id.to_string(),
+            hosts: std::env::var(format!("{prefix}_HOSTS"))
+                .unwrap_or_default()
+                .split(',')
+                .map(|host| host.trim().to_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+            name: std::env::var(format!("{prefix}_NAME"))
+                .ok()
+                .filter(|s| !s.is_empty()),
+            search_index: std::env::var(format!("{prefix}_SEARCH_INDEX"))
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| {
+                    if is_default {
+                        "servers".to_string()
+                    } else {
+                        format!("servers_{}", id.replace('-', "_"))
+                    }
+                }),
+            allow_registration: std::env::var(format!("{prefix}_ALLOW_REGISTRATION"))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SitemapConfig {
+    /// 站点前端地址，用于拼接站点地图中的服务器页面链接
+    pub base_url: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NotificationConfig {
+    /// 未设置偏好时使用的语言
+    pub default_locale: String,
+    /// 每个用户保留的站内通知条数
+    pub inbox_size: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DelistingConfig {
+    /// 是否启用长期离线服务器自动下架任务
+    pub enabled: bool,
+    /// 连续多少天没有状态数据视为长期离线并通知服主
+    pub offline_days: i64,
+    /// 通知后再等待多少天仍未恢复则自动下架
+    pub grace_days: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PingConfig {
+    /// 是否启用内置的服务器状态采集
+    pub enabled: bool,
+    /// 单个服务器的探测超时
+    pub timeout: Duration,
+    /// 同时探测的服务器数量
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MotdImageConfig {
+    /// GNU Unifont 格式（`.hex`）的点阵字体文件
+    pub font_path: PathBuf,
+    /// 未指定宽度时的图片宽度（像素）
+    pub default_width: u32,
+    /// 渲染结果的缓存时间（秒）；缓存键包含 MOTD 内容，MOTD 变化后不会返回旧图片
+    pub cache_ttl_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FileGcConfig {
+    /// 是否启用未引用文件清理任务
+    pub enabled: bool,
+    /// 上传后多少小时内的文件不清理，留给调用方写入引用
+    pub grace_hours: i64,
+    /// 每轮最多清理的文件数
+    pub batch_size: u64,
+}
+
+/// 后台任务执行间隔
+///
+/// 启动时统一读取并校验，超出范围或格式错误时拒绝启动，避免误配置导致任务空转或长期不执行。
+#[derive(Debug, Deserialize, Clone)]
+pub struct JobsConfig {
+    /// 搜索索引增量同步，补充同步其他实例修改过的服务器
+    pub search_sync: Duration,
+    /// 搜索索引全量同步，同时清理索引中多余的文档
+    pub search_full_sync: Duration,
+    /// 一言句子队列补充检查
+    pub sentence_queue: Duration,
+    /// 搜索索引任务状态轮询
+    pub search_task_poll: Duration,
+    /// 连接池采样
+    pub pool_monitor: Duration,
+    /// 相册归档
+    pub gallery_archive: Duration,
+    /// 服务器描述向量刷新
+    pub embedding_refresh: Duration,
+    /// 依赖健康采样
+    pub status_sample: Duration,
+    /// 待发送摘要邮件检查
+    pub notification_digest: Duration,
+    /// 长期离线服务器检查
+    pub delisting: Duration,
+    /// 内置服务器状态采集
+    pub server_ping: Duration,
+    /// 未引用文件清理
+    pub file_gc: Duration,
+}
+
+impl JobsConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            search_sync: interval_from_env("SEARCH_SYNC_INTERVAL", 60, 5..=86_400)?,
+            search_full_sync: interval_from_env("SEARCH_FULL_SYNC_INTERVAL", 3600, 300..=604_800)?,
+            sentence_queue: interval_from_env("SENTENCE_QUEUE_INTERVAL", 5, 1..=300)?,
+            search_task_poll: interval_from_env("MEILISEARCH_TASK_POLL_INTERVAL", 10, 1..=300)?,
+            pool_monitor: interval_from_env("DB_POOL_MONITOR_INTERVAL", 15, 1..=3600)?,
+            gallery_archive: interval_from_env("GALLERY_ARCHIVE_INTERVAL", 3600, 60..=604_800)?,
+            embedding_refresh: interval_from_env("EMBEDDING_REFRESH_INTERVAL", 3600, 60..=604_800)?,
+            status_sample: interval_from_env("STATUS_SAMPLE_INTERVAL", 60, 10..=3600)?,
+            notification_digest: interval_from_env(
+                "NOTIFICATION_DIGEST_CHECK_INTERVAL",
+                3600,
+                60..=86_400,
+            )?,
+            delisting: interval_from_env("SERVER_DELISTING_INTERVAL", 3600, 60..=604_800)?,
+            server_ping: interval_from_env("SERVER_PING_INTERVAL", 60, 10..=3600)?,
+            file_gc: interval_from_env("FILE_GC_INTERVAL", 3600, 60..=604_800)?,
+        })
+    }
+}
+
+/// 读取可选配置段的必填项
+///
+/// 全部设置时按顺序返回；全部未设置或只设置了一部分时记录警告并视为未配置，
+/// 依赖该配置的接口返回功能未启用，不影响服务启动。
+fn optional_vars<const N: usize>(section: &str, keys: [&str; N]) -> Option<[String; N]> {
+    let values = keys.map(|key| {
+        std::env::var(key)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    });
+    let missing: Vec<&str> = keys
+        .iter()
+        .zip(&values)
+        .filter(|(_, value)| value.is_none())
+        .map(|(key, _)| *key)
+        .collect();
+
+    if missing.is_empty() {
+        Some(values.map(Option::unwrap_or_default))
+    } else if missing.len() == N {
+        tracing::warn!("⚠️  未配置{}，相关功能不可用", section);
+        None
+    } else {
+        tracing::warn!(
+            "⚠️  {}配置不完整，缺少 {}，相关功能不可用",
+            section,
+            missing.join(", ")
+        );
+        None
+    }
+}
+
+/// 读取以秒为单位的任务间隔，未设置时使用默认值
+fn interval_from_env(key: &str, default: u64, bounds: RangeInclusive<u64>) -> Result<Duration> {
+    let secs = match std::env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("{key} 必须是以秒为单位的整数，当前为 {value:?}"))?,
+        _ => default,
+    };
+    if !bounds.contains(&secs) {
+        bail!(
+            "{key} 需在 {}~{} 秒之间，当前为 {secs}",
+            bounds.start(),
+            bounds.end()
+        );
+    }
+    Ok(Duration::from_secs(secs))
 }
 
 impl Config {
@@ -89,6 +646,22 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(600),
+            shed_on_saturation: std::env::var("DB_SHED_ON_SATURATION")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            replica_urls: std::env::var("DATABASE_REPLICA_URLS")
+                .map(|s| {
+                    s.split(',')
+                        .map(|url| url.trim().to_string())
+                        .filter(|url| !url.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            replica_sticky_secs: std::env::var("DB_REPLICA_STICKY_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
         };
 
         let server = ServerConfig {
@@ -96,14 +669,72 @@ impl Config {
             port: std::env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()?,
+            log_routes: std::env::var("SERVER_LOG_ROUTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            trusted_proxy_hops: std::env::var("SERVER_TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1),
         };
 
+        let cors_mode = match std::env::var("CORS_MODE") {
+            Ok(value) if !value.trim().is_empty() => match value.trim() {
+                "permissive" => CorsMode::Permissive,
+                "strict" => CorsMode::Strict,
+                _ => bail!("CORS_MODE 只能是 permissive 或 strict，当前为 {value:?}"),
+            },
+            _ => CorsMode::Permissive,
+        };
+        let cors = CorsConfig {
+            mode: cors_mode,
+            allowed_origins: std::env::var("CORS_ALLOWED_ORIGINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|origin| origin.trim().trim_end_matches('/').to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+            allow_credentials: std::env::var("CORS_ALLOW_CREDENTIALS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            max_age: std::env::var("CORS_MAX_AGE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+        };
+        if cors.mode == CorsMode::Strict {
+            if cors.allowed_origins.is_empty() {
+                bail!("CORS_MODE=strict 时必须设置 CORS_ALLOWED_ORIGINS");
+            }
+            if let Some(origin) = cors.allowed_origins.iter().find(|origin| {
+                !(origin.starts_with("http://") || origin.starts_with("https://"))
+                    || origin.contains('*')
+                    || origin.contains(char::is_whitespace)
+            }) {
+                bail!("CORS_ALLOWED_ORIGINS 中的来源 {origin:?} 无效，需为完整的 http(s) 地址且不含通配符");
+            }
+        }
+
         let jwt = JwtConfig {
             secret: std::env::var("JWT_SECRET")?,
             expiration: std::env::var("JWT_EXPIRATION")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(30 * 24 * 60 * 60),
+            impersonation_ttl_secs: std::env::var("JWT_IMPERSONATION_TTL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(15 * 60),
+        };
+
+        let password = PasswordConfig {
+            bcrypt_cost: std::env::var("BCRYPT_COST")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10)
+                .clamp(4, 31),
         };
 
         let redis = RedisConfig {
@@ -114,35 +745,499 @@ impl Config {
             password: std::env::var("REDIS_PASSWORD").ok(),
         };
 
-        let s3 = S3Config {
-            endpoint_url: std::env::var("S3_ENDPOINT_URL")?,
-            access_key: std::env::var("S3_ACCESS_KEY")?,
-            secret_key: std::env::var("S3_SECRET_KEY")?,
-            bucket: std::env::var("S3_BUCKET")?,
+        let s3 = optional_vars(
+            "对象存储",
+            [
+                "S3_ENDPOINT_URL",
+                "S3_ACCESS_KEY",
+                "S3_SECRET_KEY",
+                "S3_BUCKET",
+            ],
+        )
+        .map(|[endpoint_url, access_key, secret_key, bucket]| S3Config {
+            endpoint_url,
+            access_key,
+            secret_key,
+            bucket,
+            max_retries: std::env::var("S3_MAX_RETRIES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            retry_base_delay_ms: std::env::var("S3_RETRY_BASE_DELAY_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(200),
+            verify_on_startup: std::env::var("S3_VERIFY_ON_STARTUP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            http: reqwest::Client::new(),
+        });
+
+        let storage = match std::env::var("STORAGE_BACKEND") {
+            Ok(value) if !value.trim().is_empty() => match value.trim() {
+                "s3" => s3.map(StorageConfig::S3),
+                "local" => Some(StorageConfig::Local(LocalStorageConfig {
+                    root: std::env::var("LOCAL_STORAGE_DIR")
+                        .unwrap_or_else(|_| "./storage".to_string())
+                        .into(),
+                    cache_max_age_secs: std::env::var("STATIC_CACHE_MAX_AGE")
+                        .ok()
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(30 * 24 * 60 * 60),
+                })),
+                _ => bail!("STORAGE_BACKEND 只能是 s3 或 local，当前为 {value:?}"),
+            },
+            _ => s3.map(StorageConfig::S3),
         };
 
-        let email = EmailConfig {
-            smtp_server: std::env::var("SMTP_SERVER")?,
-            smtp_port: std::env::var("SMTP_PORT")
-                .unwrap_or_else(|_| "465".to_string())
-                .parse()?,
-            smtp_username: std::env::var("SMTP_USERNAME")?,
-            smtp_password: std::env::var("SMTP_PASSWORD")?,
+        let email = match optional_vars(
+            "发信服务",
+            ["SMTP_SERVER", "SMTP_USERNAME", "SMTP_PASSWORD"],
+        ) {
+            Some([smtp_server, smtp_username, smtp_password]) => Some(EmailConfig {
+                smtp_server,
+                smtp_port: std::env::var("SMTP_PORT")
+                    .unwrap_or_else(|_| "465".to_string())
+                    .parse()?,
+                smtp_username,
+                smtp_password,
+            }),
+            None => None,
         };
 
         let meilisearch = MeilisearchConfig {
             url: std::env::var("MEILISEARCH_URL")?,
             api_key: std::env::var("MEILISEARCH_API_KEY")?,
+            task_max_retries: std::env::var("MEILISEARCH_TASK_MAX_RETRIES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            task_stale_secs: std::env::var("MEILISEARCH_TASK_STALE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+            lag_alert_threshold: std::env::var("MEILISEARCH_LAG_ALERT_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+        };
+
+        let signing = SigningConfig {
+            replay_window_secs: std::env::var("SIGNATURE_REPLAY_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+        };
+
+        let sandbox = SandboxConfig {
+            enabled: std::env::var("SANDBOX_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+        };
+
+        let internal = InternalConfig {
+            api_token: std::env::var("INTERNAL_API_TOKEN")
+                .ok()
+                .filter(|s| !s.is_empty()),
+        };
+
+        let archive = ArchiveConfig {
+            enabled: std::env::var("GALLERY_ARCHIVE_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            prefix: std::env::var("GALLERY_ARCHIVE_PREFIX")
+                .unwrap_or_else(|_| "archive".to_string())
+                .trim_matches('/')
+                .to_string(),
+            storage_class: std::env::var("GALLERY_ARCHIVE_STORAGE_CLASS")
+                .unwrap_or_else(|_| "STANDARD_IA".to_string()),
+            after_days: std::env::var("GALLERY_ARCHIVE_AFTER_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(90),
+        };
+
+        let registration_guard = RegistrationGuardConfig {
+            ip_burst_threshold: std::env::var("REGISTRATION_IP_BURST_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            ip_burst_window_secs: std::env::var("REGISTRATION_IP_BURST_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3600),
+            sequential_username_threshold: std::env::var("REGISTRATION_SEQUENTIAL_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            flagged_email_domains: std::env::var("REGISTRATION_FLAGGED_DOMAINS")
+                .map(|s| {
+                    s.split(',')
+                        .map(|domain| domain.trim().to_lowercase())
+                        .filter(|domain| !domain.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        let scan_guard = ScanGuardConfig {
+            enabled: std::env::var("SCAN_GUARD_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            sequential_threshold: std::env::var("SCAN_GUARD_SEQUENTIAL_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            sequential_window_secs: std::env::var("SCAN_GUARD_SEQUENTIAL_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            total_cap: std::env::var("SCAN_GUARD_TOTAL_CAP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1000),
+            total_cap_window_secs: std::env::var("SCAN_GUARD_TOTAL_CAP_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(86400),
+            block_secs: std::env::var("SCAN_GUARD_BLOCK_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(900),
+        };
+
+        let rate_limit = RateLimitConfig {
+            enabled: std::env::var("RATE_LIMIT_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            login: RateLimitRule {
+                max_attempts: std::env::var("RATE_LIMIT_LOGIN_MAX_FAILURES")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5),
+                window_secs: std::env::var("RATE_LIMIT_LOGIN_WINDOW")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(900),
+            },
+            email_code: RateLimitRule {
+                max_attempts: std::env::var("RATE_LIMIT_EMAIL_CODE_MAX_REQUESTS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5),
+                window_secs: std::env::var("RATE_LIMIT_EMAIL_CODE_WINDOW")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3600),
+            },
+            lockout_secs: std::env::var("RATE_LIMIT_LOCKOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            max_lockout_secs: std::env::var("RATE_LIMIT_MAX_LOCKOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3600),
+        };
+
+        let throttle = ThrottleConfig {
+            enabled: std::env::var("THROTTLE_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(true),
+            search: TokenBucketRule::from_env("THROTTLE_SEARCH", 30, 60),
+            gallery_upload: TokenBucketRule::from_env("THROTTLE_GALLERY_UPLOAD", 10, 10),
+            email_send: TokenBucketRule::from_env("THROTTLE_EMAIL_SEND", 3, 2),
+        };
+
+        let translation = TranslationConfig {
+            provider: std::env::var("TRANSLATION_PROVIDER")
+                .ok()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            api_url: std::env::var("TRANSLATION_API_URL")
+                .unwrap_or_else(|_| "https://libretranslate.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: std::env::var("TRANSLATION_API_KEY")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            source_locale: std::env::var("TRANSLATION_SOURCE_LOCALE")
+                .unwrap_or_else(|_| "zh".to_string()),
+            target_locales: std::env::var("TRANSLATION_TARGET_LOCALES")
+                .map(|s| {
+                    s.split(',')
+                        .map(|locale| locale.trim().to_string())
+                        .filter(|locale| !locale.is_empty())
+                        .collect()
+                })
+                .unwrap_or_else(|_| vec!["en".to_string()]),
+        };
+
+        let spam_guard = SpamGuardConfig {
+            hold_threshold: std::env::var("SPAM_HOLD_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            rate_limit: std::env::var("SPAM_RATE_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            rate_window_secs: std::env::var("SPAM_RATE_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+            duplicate_window_secs: std::env::var("SPAM_DUPLICATE_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(86400),
+            new_account_hours: std::env::var("SPAM_NEW_ACCOUNT_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(24),
+        };
+
+        let name_policy = NamePolicyConfig {
+            reserved_names: std::env::var("RESERVED_NAMES")
+                .unwrap_or_else(|_| {
+                    "admin,administrator,mscpo,official,moderator,system,staff,support,官方,管理员"
+                        .to_string()
+                })
+                .split(',')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            staff_similarity_distance: std::env::var("STAFF_NAME_SIMILARITY_DISTANCE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1),
+        };
+
+        let account_link = AccountLinkConfig {
+            providers: std::env::var("ACCOUNT_LINK_PROVIDERS")
+                .unwrap_or_else(|_| "mscpo_forum".to_string())
+                .split(',')
+                .map(|provider| provider.trim().to_lowercase())
+                .filter(|provider| !provider.is_empty())
+                .collect(),
+            proof_ttl_secs: std::env::var("ACCOUNT_LINK_PROOF_TTL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+        };
+
+        let oauth_client = |prefix: &str| {
+            let client_id = std::env::var(format!("OAUTH_{prefix}_CLIENT_ID")).ok()?;
+            let client_secret = std::env::var(format!("OAUTH_{prefix}_CLIENT_SECRET")).ok()?;
+            (!client_id.trim().is_empty() && !client_secret.trim().is_empty()).then(|| {
+                OAuthClientConfig {
+                    client_id: client_id.trim().to_string(),
+                    client_secret: client_secret.trim().to_string(),
+                }
+            })
+        };
+        let oauth = OAuthConfig {
+            github: oauth_client("GITHUB"),
+            microsoft: oauth_client("MICROSOFT"),
+            microsoft_tenant: std::env::var("OAUTH_MICROSOFT_TENANT")
+                .ok()
+                .filter(|tenant| !tenant.trim().is_empty())
+                .unwrap_or_else(|| "consumers".to_string()),
+            redirect_url: std::env::var("OAUTH_REDIRECT_URL").unwrap_or_else(|_| {
+                "http://localhost:3000/v2/auth/oauth/{provider}/callback".to_string()
+            }),
+            state_ttl_secs: std::env::var("OAUTH_STATE_TTL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+        };
+
+        let embedding = EmbeddingConfig {
+            provider: std::env::var("EMBEDDING_PROVIDER")
+                .ok()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            api_url: std::env::var("EMBEDDING_API_URL")
+                .unwrap_or_else(|_| "https://api.openai.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: std::env::var("EMBEDDING_API_KEY")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            model: std::env::var("EMBEDDING_MODEL")
+                .unwrap_or_else(|_| "text-embedding-3-small".to_string()),
+        };
+
+        let status = StatusConfig {
+            history_size: std::env::var("STATUS_HISTORY_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1440),
+        };
+
+        let upload_scan = UploadScanConfig {
+            backend: std::env::var("UPLOAD_SCAN_BACKEND")
+                .ok()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            address: std::env::var("UPLOAD_SCAN_ADDRESS")
+                .unwrap_or_else(|_| "127.0.0.1:3310".to_string()),
+            icap_service: std::env::var("UPLOAD_SCAN_ICAP_SERVICE")
+                .unwrap_or_else(|_| "avscan".to_string()),
+            timeout_secs: std::env::var("UPLOAD_SCAN_TIMEOUT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            fail_open: std::env::var("UPLOAD_SCAN_FAIL_OPEN")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+        };
+
+        let default_tenant = std::env::var("TENANT_DEFAULT")
+            .map(|s| s.trim().to_lowercase())
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "default".to_string());
+        let tenant = TenantConfig {
+            mode: std::env::var("TENANT_MODE")
+                .map(|s| s.trim().to_lowercase())
+                .unwrap_or_else(|_| "off".to_string()),
+            default_tenant: default_tenant.clone(),
+            tenants: std::env::var("TENANTS")
+                .unwrap_or_else(|_| default_tenant.clone())
+                .split(',')
+                .map(|id| id.trim().to_lowercase())
+                .filter(|id| !id.is_empty())
+                .chain(std::iter::once(default_tenant.clone()))
+                .fold(Vec::<String>::new(), |mut ids, id| {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                    ids
+                })
+                .into_iter()
+                .map(|id| TenantDefinition::from_env(&id, id == default_tenant))
+                .collect(),
+        };
+
+        let sitemap = SitemapConfig {
+            base_url: std::env::var("SITEMAP_BASE_URL")
+                .unwrap_or_else(|_| "http://localhost:3000".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        };
+
+        let notification = NotificationConfig {
+            default_locale: std::env::var("NOTIFICATION_DEFAULT_LOCALE")
+                .unwrap_or_else(|_| "zh-CN".to_string()),
+            inbox_size: std::env::var("NOTIFICATION_INBOX_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(100),
+        };
+
+        let delisting = DelistingConfig {
+            enabled: std::env::var("SERVER_DELISTING_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            offline_days: std::env::var("SERVER_DELISTING_OFFLINE_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+            grace_days: std::env::var("SERVER_DELISTING_GRACE_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(7),
+        };
+
+        let ping = PingConfig {
+            enabled: std::env::var("SERVER_PING_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            timeout: Duration::from_millis(
+                std::env::var("SERVER_PING_TIMEOUT_MS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5000),
+            ),
+            concurrency: std::env::var("SERVER_PING_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(32),
         };
 
+        let file_gc = FileGcConfig {
+            enabled: std::env::var("FILE_GC_ENABLED")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(false),
+            grace_hours: std::env::var("FILE_GC_GRACE_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(24),
+            batch_size: std::env::var("FILE_GC_BATCH_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(500),
+        };
+
+        let motd_image = std::env::var("MOTD_FONT_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(|font_path| MotdImageConfig {
+                font_path: font_path.trim().into(),
+                default_width: std::env::var("MOTD_IMAGE_WIDTH")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(640),
+                cache_ttl_secs: std::env::var("MOTD_IMAGE_CACHE_TTL")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(600),
+            });
+
         Ok(Config {
             database,
             server,
+            cors,
             jwt,
+            password,
             redis,
-            s3,
+            storage,
             email,
             meilisearch,
+            signing,
+            sandbox,
+            internal,
+            archive,
+            registration_guard,
+            scan_guard,
+            rate_limit,
+            throttle,
+            translation,
+            spam_guard,
+            name_policy,
+            account_link,
+            oauth,
+            embedding,
+            status,
+            upload_scan,
+            tenant,
+            sitemap,
+            notification,
+            delisting,
+            ping,
+            file_gc,
+            motd_image,
+            jobs: JobsConfig::from_env()?,
         })
     }
 }
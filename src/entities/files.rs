@@ -10,6 +10,18 @@ pub struct Model {
     pub hash_value: String,
     #[sea_orm(unique)]
     pub file_path: String,
+    /// 安全扫描结果（clean / infected / error），为空表示未扫描
+    pub scan_status: Option<String>,
+    /// 扫描引擎
+    pub scan_engine: Option<String>,
+    /// 命中的病毒特征或扫描失败原因
+    pub scan_detail: Option<String>,
+    /// 扫描时间
+    pub scanned_at: Option<DateTimeUtc>,
+    /// 是否已在原图旁生成缩略图，见 [`crate::services::file_upload::ImageVariant`]
+    pub has_variants: bool,
+    /// 最近一次上传时间，上传内容与已有文件相同时也会更新；清理任务不删除刚上传的文件
+    pub uploaded_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -50,4 +62,4 @@ impl Model {
         hasher.update(file_content);
         format!("{:x}", hasher.finalize())
     }
-}
\ No newline at end of file
+}
@@ -13,6 +13,11 @@ pub struct Model {
     pub description: String,
     pub gallery_id: i32,
     pub image_hash_id: String,
+    /// 展示顺序，越小越靠前，相同时按 ID 排列
+    #[sea_orm(default_value = 0)]
+    pub sort_order: i32,
+    /// 发布时间，早期上传的图片没有记录
+    pub created_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
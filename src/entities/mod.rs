@@ -2,14 +2,33 @@
 
 pub mod prelude;
 
+pub mod activity;
+pub mod api_keys;
 pub mod ban_records;
+pub mod email_suppressions;
+pub mod external_identities;
+pub mod feature_flags;
 pub mod files;
 pub mod gallery;
 pub mod gallery_image;
+pub mod notification_opt_outs;
+pub mod notifications;
+pub mod registration_flags;
 pub mod server;
+pub mod server_custom_field;
+pub mod server_embeddings;
 pub mod server_log;
+pub mod server_revision;
 pub mod server_stats;
+pub mod server_timeline;
+pub mod server_translation;
+pub mod server_uptime;
+pub mod spam_holds;
+pub mod status_incidents;
+pub mod tag_vocabulary;
 pub mod ticket;
 pub mod ticket_log;
+pub mod user_favorite;
+pub mod user_preferences;
 pub mod user_server;
 pub mod users;
@@ -1,13 +1,32 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.14
 
+pub use super::activity::Entity as Activity;
+pub use super::api_keys::Entity as ApiKeys;
 pub use super::ban_records::Entity as BanRecords;
+pub use super::email_suppressions::Entity as EmailSuppressions;
+pub use super::external_identities::Entity as ExternalIdentities;
+pub use super::feature_flags::Entity as FeatureFlags;
 pub use super::files::Entity as Files;
 pub use super::gallery::Entity as Gallery;
 pub use super::gallery_image::Entity as GalleryImage;
+pub use super::notification_opt_outs::Entity as NotificationOptOuts;
+pub use super::notifications::Entity as Notifications;
+pub use super::registration_flags::Entity as RegistrationFlags;
 pub use super::server::Entity as Server;
+pub use super::server_custom_field::Entity as ServerCustomField;
+pub use super::server_embeddings::Entity as ServerEmbeddings;
 pub use super::server_log::Entity as ServerLog;
+pub use super::server_revision::Entity as ServerRevision;
 pub use super::server_stats::Entity as ServerStats;
+pub use super::server_timeline::Entity as ServerTimeline;
+pub use super::server_translation::Entity as ServerTranslation;
+pub use super::server_uptime::Entity as ServerUptime;
+pub use super::spam_holds::Entity as SpamHolds;
+pub use super::status_incidents::Entity as StatusIncidents;
+pub use super::tag_vocabulary::Entity as TagVocabulary;
 pub use super::ticket::Entity as Ticket;
 pub use super::ticket_log::Entity as TicketLog;
+pub use super::user_favorite::Entity as UserFavorite;
+pub use super::user_preferences::Entity as UserPreferences;
 pub use super::user_server::Entity as UserServer;
 pub use super::users::Entity as Users;
@@ -44,7 +44,57 @@ pub struct Model {
     #[sea_orm(column_type = "custom(\"LONGTEXT\")", format = "json")]
     pub tags: Json,
     pub cover_hash_id: Option<String>,
+    /// 方形图标的文件哈希，与 16:9 的封面分开上传
+    pub icon_hash_id: Option<String>,
     pub gallery_id: Option<i32>,
+    /// 数据推送签名密钥，不对外序列化
+    #[serde(skip)]
+    pub push_secret: Option<String>,
+    /// 停用时间（软删除或长期封禁），为空表示正常
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub deactivated_at: Option<DateTimeUtc>,
+    /// 对外展示的短链接标识
+    #[sea_orm(unique)]
+    pub slug: Option<String>,
+    /// 短链接是否已被手动修改过（只允许修改一次）
+    pub slug_edited: bool,
+    /// 可见性（public / listed_without_ip / hidden）
+    #[sea_orm(default_value = "public")]
+    pub visibility: String,
+    /// 是否退出玩家名称搜索，退出后不再索引该服务器推送的在线玩家
+    #[sea_orm(default_value = false)]
+    pub player_search_opt_out: bool,
+    /// 被标记为长期离线的时间，已通知服主
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub offline_flagged_at: Option<DateTimeUtc>,
+    /// 因长期离线被自动下架的时间，下架期间不出现在列表与搜索中
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub delisted_at: Option<DateTimeUtc>,
+    /// 管理员设置的豁免，豁免的服务器不会被自动下架
+    #[sea_orm(default_value = false)]
+    pub delisting_exempt: bool,
+    /// 所属租户
+    #[sea_orm(default_value = "default")]
+    #[serde(skip)]
+    pub tenant_id: String,
+    /// 最后修改时间，搜索索引据此增量同步；早期创建且未修改过的服务器为空
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub updated_at: Option<DateTimeUtc>,
+    /// 支持的游戏版本范围（`VersionRange` 的 JSON），为空表示只支持 `version`
+    #[sea_orm(column_type = "custom(\"LONGTEXT\")", format = "json", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub version_range: Option<Json>,
+    /// 版本区间下限的编码，用于兼容版本筛选；列表形式的范围为空
+    #[serde(skip)]
+    pub version_min_code: Option<i64>,
+    /// 版本区间上限的编码，用于兼容版本筛选；列表形式的范围为空
+    #[serde(skip)]
+    pub version_max_code: Option<i64>,
+    /// 发布状态（draft / pending_review / published / rejected / archived）
+    #[sea_orm(default_value = "published")]
+    pub status: String,
+    /// 最近一次审核未通过的原因
+    pub review_note: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -65,12 +115,26 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Gallery,
+    #[sea_orm(has_many = "super::server_custom_field::Entity")]
+    ServerCustomField,
+    #[sea_orm(has_one = "super::server_embeddings::Entity")]
+    ServerEmbeddings,
     #[sea_orm(has_many = "super::server_log::Entity")]
     ServerLog,
+    #[sea_orm(has_many = "super::server_revision::Entity")]
+    ServerRevision,
     #[sea_orm(has_many = "super::server_stats::Entity")]
     ServerStats,
+    #[sea_orm(has_many = "super::server_timeline::Entity")]
+    ServerTimeline,
+    #[sea_orm(has_many = "super::server_translation::Entity")]
+    ServerTranslation,
+    #[sea_orm(has_many = "super::server_uptime::Entity")]
+    ServerUptime,
     #[sea_orm(has_many = "super::ticket::Entity")]
     Ticket,
+    #[sea_orm(has_many = "super::user_favorite::Entity")]
+    UserFavorite,
     #[sea_orm(has_many = "super::user_server::Entity")]
     UserServer,
 }
@@ -87,28 +151,80 @@ impl Related<super::gallery::Entity> for Entity {
     }
 }
 
+impl Related<super::server_custom_field::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerCustomField.def()
+    }
+}
+
+impl Related<super::server_embeddings::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerEmbeddings.def()
+    }
+}
+
 impl Related<super::server_log::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ServerLog.def()
     }
 }
 
+impl Related<super::server_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerRevision.def()
+    }
+}
+
 impl Related<super::server_stats::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ServerStats.def()
     }
 }
 
+impl Related<super::server_timeline::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerTimeline.def()
+    }
+}
+
+impl Related<super::server_translation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerTranslation.def()
+    }
+}
+
+impl Related<super::server_uptime::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServerUptime.def()
+    }
+}
+
 impl Related<super::ticket::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Ticket.def()
     }
 }
 
+impl Related<super::user_favorite::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserFavorite.def()
+    }
+}
+
 impl Related<super::user_server::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserServer.def()
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    /// 通过 `ActiveModel` 写入时自动更新修改时间，批量更新需自行设置
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.updated_at = sea_orm::Set(Some(chrono::Utc::now()));
+        Ok(self)
+    }
+}
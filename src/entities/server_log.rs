@@ -10,7 +10,7 @@ pub struct Model {
     pub id: i32,
     #[sea_orm(column_type = "custom(\"LONGTEXT\")")]
     pub changed_fields: String,
-    pub created_at: DateTime,
+    pub created_at: DateTimeUtc,
     pub server_id: i32,
     pub user_id: Option<i32>,
 }
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
-    pub timestamp: DateTime,
+    pub timestamp: DateTimeUtc,
     #[sea_orm(column_type = "Json", nullable)]
     pub stat_data: Option<serde_json::Value>,
     pub server_id: i32,
@@ -13,8 +13,8 @@ pub struct Model {
     pub description: Option<String>,
     pub status: i16,
     pub priority: i16,
-    pub created_at: DateTime,
-    pub updated_at: DateTime,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
     pub reported_content_id: Option<i32>,
     #[sea_orm(column_type = "custom(\"LONGTEXT\")", nullable)]
     pub report_reason: Option<String>,
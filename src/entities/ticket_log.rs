@@ -10,7 +10,7 @@ pub struct Model {
     pub id: i32,
     pub old_status: i16,
     pub new_status: i16,
-    pub changed_at: DateTime,
+    pub changed_at: DateTimeUtc,
     pub changed_by_id: i32,
     pub ticket_id: i32,
 }
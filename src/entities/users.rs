@@ -33,12 +33,21 @@ pub struct Model {
     pub last_login: Option<DateTime<Utc>>,
     pub last_login_ip: Option<String>,
     pub avatar_hash_id: Option<String>,
+    /// 小尺寸头像文件，用于列表等小图场景
+    pub avatar_small_hash_id: Option<String>,
+    /// 所属租户
+    #[sea_orm(default_value = "default")]
+    pub tenant_id: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::activity::Entity")]
+    Activity,
     #[sea_orm(has_many = "super::ban_records::Entity")]
     BanRecords,
+    #[sea_orm(has_many = "super::external_identities::Entity")]
+    ExternalIdentities,
     #[sea_orm(
         belongs_to = "super::files::Entity",
         from = "Column::AvatarHashId",
@@ -47,38 +56,82 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     Files,
+    #[sea_orm(has_many = "super::registration_flags::Entity")]
+    RegistrationFlags,
     #[sea_orm(has_many = "super::server_log::Entity")]
     ServerLog,
+    #[sea_orm(has_many = "super::spam_holds::Entity")]
+    SpamHolds,
     #[sea_orm(has_many = "super::ticket_log::Entity")]
     TicketLog,
+    #[sea_orm(has_many = "super::user_favorite::Entity")]
+    UserFavorite,
+    #[sea_orm(has_one = "super::user_preferences::Entity")]
+    UserPreferences,
     #[sea_orm(has_many = "super::user_server::Entity")]
     UserServer,
 }
 
+impl Related<super::activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+
 impl Related<super::ban_records::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::BanRecords.def()
     }
 }
 
+impl Related<super::external_identities::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExternalIdentities.def()
+    }
+}
+
 impl Related<super::files::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Files.def()
     }
 }
 
+impl Related<super::registration_flags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RegistrationFlags.def()
+    }
+}
+
 impl Related<super::server_log::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ServerLog.def()
     }
 }
 
+impl Related<super::spam_holds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SpamHolds.def()
+    }
+}
+
 impl Related<super::ticket_log::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::TicketLog.def()
     }
 }
 
+impl Related<super::user_favorite::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserFavorite.def()
+    }
+}
+
+impl Related<super::user_preferences::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserPreferences.def()
+    }
+}
+
 impl Related<super::user_server::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserServer.def()
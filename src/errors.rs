@@ -1,12 +1,17 @@
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use thiserror::Error;
-use utoipa::ToSchema;
+use utoipa::{
+    openapi::{path::Operation, ContentBuilder, OpenApi, Ref, RefOr, ResponseBuilder},
+    Modify, ToSchema,
+};
+
+use crate::routes::{self, RouteAuth};
 
 /// API 错误响应模型，用于 OpenAPI 文档
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -14,11 +19,266 @@ pub struct ApiErrorResponse {
     /// 错误信息
     #[schema(example = "找不到！什么都没有，一片空气")]
     pub error: String,
+    /// 机器可读的错误码，客户端应据此判断错误类型而不是匹配错误信息
+    #[schema(example = "SERVER_NOT_FOUND")]
+    pub code: ErrorCode,
     /// HTTP 状态码
     #[schema(example = 404)]
     pub status: u16,
 }
 
+/// 机器可读的错误码
+///
+/// 每种错误类型都有通用错误码；常见的具体错误在构造时通过 [`ApiError::with_code`] 携带更细的错误码。
+/// 已发布的错误码不应改名。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 请求参数错误
+    BadRequest,
+    /// 参数校验失败
+    ValidationFailed,
+    /// 请求体无法解析
+    InvalidRequestBody,
+    /// 查询参数无法解析
+    InvalidQuery,
+    /// 接口不支持该请求方法
+    MethodNotAllowed,
+    /// 未登录或登录信息无效
+    Unauthorized,
+    /// 令牌无效
+    TokenInvalid,
+    /// 令牌已过期
+    TokenExpired,
+    /// 令牌已被吊销（已退出登录）
+    TokenRevoked,
+    /// 令牌不属于当前站点
+    TokenTenantMismatch,
+    /// 用户名或密码错误
+    InvalidCredentials,
+    /// 请求签名无效
+    SignatureInvalid,
+    /// 请求签名已过期
+    SignatureExpired,
+    /// 请求签名已被使用（重放）
+    SignatureReplayed,
+    /// 没有权限
+    Forbidden,
+    /// 需要管理员权限
+    AdminRequired,
+    /// 需要管理人员权限
+    StaffRequired,
+    /// 需要服务器管理员权限
+    ServerManagerRequired,
+    /// 需要服务器所有者权限
+    ServerOwnerRequired,
+    /// 账户已停用
+    AccountDeactivated,
+    /// 账户已被封禁
+    AccountBanned,
+    /// 账户已被禁言
+    AccountMuted,
+    /// 当前站点未开放注册
+    RegistrationClosed,
+    /// 资源不存在
+    NotFound,
+    /// 服务器不存在
+    ServerNotFound,
+    /// 用户不存在
+    UserNotFound,
+    /// 图片不存在
+    ImageNotFound,
+    /// 服务器没有画册
+    GalleryNotFound,
+    /// 服务器没有封面
+    CoverNotFound,
+    /// 服务器没有图标
+    IconNotFound,
+    /// 资源冲突
+    Conflict,
+    /// 用户已存在
+    UserExists,
+    /// 用户名已被占用
+    UsernameTaken,
+    /// 验证码无效
+    InvalidVerificationCode,
+    /// 验证码输错次数过多，已作废，需要重新获取
+    VerificationCodeExhausted,
+    /// 确认令牌无效或已过期
+    InvalidConfirmationToken,
+    /// 图片文件无效
+    InvalidImage,
+    /// 图片格式不支持
+    InvalidImageFormat,
+    /// 图片比例不符合要求
+    InvalidImageRatio,
+    /// 文件未通过安全扫描
+    FileRejected,
+    /// 请求过于频繁
+    TooManyRequests,
+    /// 依赖服务暂不可用
+    ServiceUnavailable,
+    /// 功能未启用
+    FeatureDisabled,
+    /// 服务器内部错误
+    InternalError,
+    /// 故障注入产生的错误，仅在测试环境出现
+    FaultInjected,
+}
+
+/// 账户封禁信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BanNotice {
+    /// 封禁类型（mute / ban / temp_ban）
+    #[schema(example = "temp_ban")]
+    pub ban_type: String,
+    /// 封禁原因
+    #[schema(example = "发布广告")]
+    pub reason: Option<String>,
+    /// 解封时间（UTC），为空表示直到管理人员解除
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 账户被封禁时的错误响应模型，用于 OpenAPI 文档
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BannedErrorResponse {
+    /// 错误信息
+    #[schema(example = "账户已被封禁")]
+    pub error: String,
+    /// 机器可读的错误码（ACCOUNT_BANNED / ACCOUNT_MUTED）
+    #[schema(example = "ACCOUNT_BANNED")]
+    pub code: ErrorCode,
+    /// HTTP 状态码
+    #[schema(example = 403)]
+    pub status: u16,
+    #[serde(flatten)]
+    pub ban: BanNotice,
+}
+
+/// 限流锁定信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitNotice {
+    /// 距离解除锁定的秒数，与 `Retry-After` 响应头一致
+    #[schema(example = 60)]
+    pub retry_after: u64,
+}
+
+/// 触发限流时的错误响应模型，用于 OpenAPI 文档
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitedErrorResponse {
+    /// 错误信息
+    #[schema(example = "尝试次数过多，请稍后再试")]
+    pub error: String,
+    /// 机器可读的错误码（TOO_MANY_REQUESTS）
+    #[schema(example = "TOO_MANY_REQUESTS")]
+    pub code: ErrorCode,
+    /// HTTP 状态码
+    #[schema(example = 429)]
+    pub status: u16,
+    #[serde(flatten)]
+    pub limit: RateLimitNotice,
+}
+
+/// 文档中通用的错误响应
+///
+/// 合并接口文档后补充到每个接口：所有接口都可能返回 500 与 503（连接池饱和或依赖不可用），
+/// 带参数或请求体的接口可能返回 400，需要登录或签名的接口可能返回 401，管理接口可能返回 403。
+/// 接口自己声明了同一状态码时保留接口的说明。
+pub struct ErrorResponses;
+
+impl ErrorResponses {
+    /// 响应组件名、状态码与说明
+    const RESPONSES: [(&'static str, &'static str, &'static str); 5] = [
+        ("BadRequest", "400", "请求参数无法解析或校验失败"),
+        ("Unauthorized", "401", "未登录、令牌无效或签名错误"),
+        ("Forbidden", "403", "没有权限执行该操作"),
+        ("InternalError", "500", "服务器内部错误"),
+        (
+            "ServiceUnavailable",
+            "503",
+            "服务繁忙或依赖不可用，请稍后重试",
+        ),
+    ];
+
+    fn applicable(auth: Option<RouteAuth>, operation: &Operation) -> Vec<&'static str> {
+        let mut names = vec!["InternalError", "ServiceUnavailable"];
+        if operation.request_body.is_some()
+            || operation
+                .parameters
+                .as_ref()
+                .is_some_and(|params| !params.is_empty())
+        {
+            names.push("BadRequest");
+        }
+        if matches!(
+            auth,
+            Some(
+                RouteAuth::User
+                    | RouteAuth::Staff
+                    | RouteAuth::Admin
+                    | RouteAuth::Internal
+                    | RouteAuth::Signed
+            )
+        ) {
+            names.push("Unauthorized");
+        }
+        if matches!(auth, Some(RouteAuth::Staff | RouteAuth::Admin)) {
+            names.push("Forbidden");
+        }
+        names
+    }
+}
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (name, _, description) in Self::RESPONSES {
+            let response = ResponseBuilder::new()
+                .description(description)
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name("ApiErrorResponse")))
+                        .build(),
+                )
+                .build();
+            components
+                .responses
+                .insert(name.to_string(), RefOr::T(response));
+        }
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            for (method, operation) in [
+                ("get", &mut item.get),
+                ("post", &mut item.post),
+                ("put", &mut item.put),
+                ("patch", &mut item.patch),
+                ("delete", &mut item.delete),
+            ] {
+                let Some(operation) = operation else {
+                    continue;
+                };
+                let auth = routes::inventory()
+                    .find(|route| route.method == method && route.path == path)
+                    .map(|route| route.auth);
+                for name in Self::applicable(auth, operation) {
+                    let (_, status, _) = Self::RESPONSES
+                        .iter()
+                        .find(|(candidate, _, _)| *candidate == name)
+                        .expect("未登记的错误响应");
+                    operation
+                        .responses
+                        .responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| RefOr::Ref(Ref::from_response_name(name)));
+                }
+            }
+        }
+    }
+}
+
 #[derive(Error, Debug, ToSchema, Serialize, Deserialize)]
 #[serde(tag = "type", content = "message")]
 pub enum ApiError {
@@ -54,11 +314,78 @@ pub enum ApiError {
 
     #[error("Internal server error: {0}")]
     InternalServerError(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Feature disabled: {0}")]
+    FeatureDisabled(String),
+
+    #[error("Banned: {}", .0.ban_type)]
+    Banned(BanNotice),
+
+    #[error("Rate limited: retry after {}s", .0.retry_after)]
+    RateLimited(RateLimitNotice),
+
+    /// 携带具体错误码的错误，状态码与错误信息沿用内层错误
+    #[error("{error}")]
+    Coded {
+        code: ErrorCode,
+        #[schema(value_type = Object)]
+        error: Box<ApiError>,
+    },
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
+impl ApiError {
+    /// 附加具体错误码，响应的状态码与错误信息不变
+    pub fn with_code(self, code: ErrorCode) -> Self {
+        match self {
+            ApiError::Coded { error, .. } => ApiError::Coded { code, error },
+            error => ApiError::Coded {
+                code,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// 服务器不存在
+    pub fn server_not_found() -> Self {
+        ApiError::NotFound("服务器不存在".to_string()).with_code(ErrorCode::ServerNotFound)
+    }
+
+    /// 用户不存在
+    pub fn user_not_found() -> Self {
+        ApiError::NotFound("用户不存在".to_string()).with_code(ErrorCode::UserNotFound)
+    }
+
+    /// 机器可读的错误码，构造时未附加具体错误码的按错误类型返回通用错误码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::Coded { code, .. } => *code,
+            ApiError::Database(_) | ApiError::Internal(_) | ApiError::InternalServerError(_) => {
+                ErrorCode::InternalError
+            }
+            ApiError::Banned(ban) if ban.ban_type == "mute" => ErrorCode::AccountMuted,
+            ApiError::Banned(_) => ErrorCode::AccountBanned,
+            ApiError::RateLimited(_) => ErrorCode::TooManyRequests,
+            ApiError::Validation(_) => ErrorCode::ValidationFailed,
+            ApiError::Authentication(_) | ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::Authorization(_) | ApiError::Forbidden(_) => ErrorCode::Forbidden,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::BadRequest(_) => ErrorCode::BadRequest,
+            ApiError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            ApiError::TooManyRequests(_) => ErrorCode::TooManyRequests,
+            ApiError::FeatureDisabled(_) => ErrorCode::FeatureDisabled,
+        }
+    }
+
+    /// 按错误类型生成响应，`code` 为最外层携带的错误码
+    fn respond(self, code: ErrorCode) -> Response {
+        let (status, error_message) = match self {
             ApiError::Database(msg) => {
                 tracing::error!("Database error: {}", msg);
                 (
@@ -66,11 +393,11 @@ impl IntoResponse for ApiError {
                     "Database error".to_string(),
                 )
             }
-            ApiError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::Authentication(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
-            ApiError::Authorization(msg) => (StatusCode::FORBIDDEN, msg.clone()),
-            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            ApiError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::Authentication(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ApiError::Authorization(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (
@@ -78,9 +405,9 @@ impl IntoResponse for ApiError {
                     "Internal server error".to_string(),
                 )
             }
-            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
-            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             ApiError::InternalServerError(msg) => {
                 tracing::error!("Internal server error: {}", msg);
                 (
@@ -88,17 +415,58 @@ impl IntoResponse for ApiError {
                     "Internal server error".to_string(),
                 )
             }
+            ApiError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            ApiError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
+            ApiError::FeatureDisabled(msg) => (StatusCode::NOT_IMPLEMENTED, msg),
+            ApiError::Banned(ban) => return banned_response(ban, code),
+            ApiError::RateLimited(limit) => return rate_limited_response(limit, code),
+            ApiError::Coded { error, .. } => return error.respond(code),
         };
 
-        let body = Json(json!({
-            "error": error_message,
-            "status": status.as_u16()
-        }));
+        let body = Json(ApiErrorResponse {
+            error: error_message,
+            code,
+            status: status.as_u16(),
+        });
 
         (status, body).into_response()
     }
 }
 
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let code = self.code();
+        self.respond(code)
+    }
+}
+
+fn banned_response(ban: BanNotice, code: ErrorCode) -> Response {
+    let error = if ban.ban_type == "mute" {
+        "账户已被禁言"
+    } else {
+        "账户已被封禁"
+    };
+    let body = BannedErrorResponse {
+        error: error.to_string(),
+        code,
+        status: StatusCode::FORBIDDEN.as_u16(),
+        ban,
+    };
+    (StatusCode::FORBIDDEN, Json(body)).into_response()
+}
+
+fn rate_limited_response(limit: RateLimitNotice, code: ErrorCode) -> Response {
+    let status = StatusCode::TOO_MANY_REQUESTS;
+    let retry_after = limit.retry_after.to_string();
+    let body = RateLimitedErrorResponse {
+        error: "尝试次数过多，请稍后再试".to_string(),
+        code,
+        status: status.as_u16(),
+        limit,
+    };
+    (status, [(RETRY_AFTER, retry_after)], Json(body)).into_response()
+}
+
 // From implementations for compatibility
 impl From<sea_orm::DbErr> for ApiError {
     fn from(err: sea_orm::DbErr) -> Self {
@@ -0,0 +1,140 @@
+//! 请求提取器
+//!
+//! 包装 axum 的 [`axum::Json`] 与 [`axum::extract::Query`]，解析失败时返回统一的
+//! [`ApiErrorResponse`] 结构而不是 axum 默认的纯文本，错误信息中尽量带上出错的字段路径。
+//! 处理函数应使用本模块的 `Json` / `Query` 代替 axum 的同名类型。
+
+use axum::{
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        FromRequest, FromRequestParts, OptionalFromRequest, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::ops::Deref;
+
+use crate::errors::{ApiErrorResponse, ErrorCode};
+
+/// JSON 请求体提取器，同时可作为 JSON 响应
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            Err(rejection) => Err(json_rejection(rejection)),
+        }
+    }
+}
+
+impl<T, S> OptionalFromRequest<S> for Json<T>
+where
+    axum::Json<T>: OptionalFromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        match <axum::Json<T> as OptionalFromRequest<S>>::from_request(req, state).await {
+            Ok(value) => Ok(value.map(|axum::Json(value)| Json(value))),
+            Err(rejection) => Err(json_rejection(rejection)),
+        }
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// 查询参数提取器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    axum::extract::Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Query(value)) => Ok(Query(value)),
+            Err(rejection) => Err(query_rejection(rejection)),
+        }
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> Response {
+    let status = rejection.status();
+    let message = match &rejection {
+        JsonRejection::JsonDataError(_) => format!(
+            "请求体字段无效: {}",
+            detail(&rejection.body_text(), "target type: ")
+        ),
+        JsonRejection::JsonSyntaxError(_) => format!(
+            "请求体不是合法的 JSON: {}",
+            detail(&rejection.body_text(), "JSON: ")
+        ),
+        JsonRejection::MissingJsonContentType(_) => {
+            "请求头 Content-Type 必须为 application/json".to_string()
+        }
+        _ => format!("无法读取请求体: {}", rejection.body_text()),
+    };
+    error_response(status, ErrorCode::InvalidRequestBody, message)
+}
+
+fn query_rejection(rejection: QueryRejection) -> Response {
+    let message = format!(
+        "查询参数无效: {}",
+        detail(&rejection.body_text(), "query string: ")
+    );
+    error_response(rejection.status(), ErrorCode::InvalidQuery, message)
+}
+
+/// 去掉 axum 错误信息中的英文前缀，保留 `字段路径: 原因` 部分
+fn detail(text: &str, marker: &str) -> String {
+    match text.split_once(marker) {
+        Some((_, rest)) => rest.to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn error_response(status: StatusCode, code: ErrorCode, error: String) -> Response {
+    (
+        status,
+        axum::Json(ApiErrorResponse {
+            error,
+            code,
+            status: status.as_u16(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,1501 @@
+use axum::extract::{OriginalUri, Path, State};
+use axum_typed_multipart::TypedMultipart;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use validator::Validate;
+
+use crate::{
+    entities::{prelude::Users, users},
+    errors::{ApiError, ApiErrorResponse, ApiResult, ErrorCode},
+    extract::{Json, Query},
+    middleware::{Admin, CurrentTenant, Moderator, RequireSiteRole},
+    schemas::{
+        admin::{
+            AdminServerInfo, AdminServerQuery, AdminUserDetail, AdminVisibilityRequest, BanInfo,
+            BanQuery, CreateBanRequest, DelistingInfo, DelistingListResponse, FeatureFlagInfo,
+            FeatureFlagListResponse, ImpersonateRequest, ImpersonationToken, IncidentInfo,
+            IncidentListResponse, MergeTagsRequest, MergeTagsResponse, RegistrationFlagInfo,
+            RegistrationFlagQuery, RegistrationFlagStats, RegistrationFlagStatus,
+            ReviewRegistrationFlagRequest, ReviewSpamHoldRequest, ServerReviewRequest,
+            SpamHoldInfo, SpamHoldQuery, SpamHoldStatus, TagVocabularyEntry,
+            TagVocabularyListResponse, UpdateDelistingRequest, UpdateFeatureFlagRequest,
+            UpdateIncidentRequest, UpdateUserNamesRequest, UpsertTagVocabularyRequest,
+        },
+        pagination::{Page, Paginated},
+        servers::{SuccessResponse, UpdateServerRequest},
+        users::ActivityAction,
+    },
+    services::{
+        activity::{
+            ActivityService, TARGET_REGISTRATION_FLAG, TARGET_SERVER, TARGET_SPAM_HOLD, TARGET_USER,
+        },
+        auth::{AuthService, JwtData},
+        ban::BanService,
+        delisting::DelistingService,
+        email::suppression::EmailSuppressionService,
+        feature_flags::FeatureFlagService,
+        moderation::ServerModerationService,
+        registration_guard::RegistrationGuardService,
+        server::ServerService,
+        spam_guard::SpamGuardService,
+        status::StatusService,
+        tags::TagService,
+    },
+    AppState,
+};
+
+/// 管理后台接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        list_registration_flags,
+        review_registration_flag,
+        registration_flag_stats,
+        list_spam_holds,
+        review_spam_hold,
+        update_user_names,
+        get_user,
+        impersonate_user,
+        ban_user,
+        unban_user,
+        list_bans,
+        clear_email_suppression,
+        merge_tags,
+        list_tag_vocabulary,
+        upsert_tag_vocabulary,
+        delete_tag_vocabulary,
+        list_delisting,
+        update_delisting,
+        list_servers,
+        force_update_server,
+        set_server_visibility,
+        approve_server,
+        reject_server,
+        remove_gallery_image,
+        list_feature_flags,
+        upsert_feature_flag,
+        delete_feature_flag,
+        list_incidents,
+        upsert_incident,
+        delete_incident
+    ),
+    components(schemas(
+        AdminServerQuery,
+        BanQuery,
+        RegistrationFlagQuery,
+        SpamHoldQuery
+    )),
+    tags((name = "admin", description = "Administration and moderation endpoints"))
+)]
+pub struct AdminApi;
+
+/// 获取可疑注册列表
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_registration_flags",
+    path = "/v2/admin/registration-flags",
+    summary = "获取可疑注册列表",
+    description = "列出当前租户内被规则标记的新注册账户，供管理人员审核；默认只返回待审核的标记",
+    responses(
+        (status = 200, description = "成功获取标记列表", body = Paginated<RegistrationFlagInfo>),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    params(RegistrationFlagQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_registration_flags(
+    _staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<RegistrationFlagQuery>,
+) -> ApiResult<Page<Paginated<RegistrationFlagInfo>>> {
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let status = query.status.unwrap_or(RegistrationFlagStatus::Pending);
+    let (data, total) = RegistrationGuardService::list_flags(
+        &app_state.db,
+        tenant.id(),
+        status,
+        query.page,
+        query.page_size,
+    )
+    .await?;
+
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 审核可疑注册
+#[utoipa::path(
+    post,
+    operation_id = "admin_review_registration_flag",
+    path = "/v2/admin/registration-flags/{flag_id}/review",
+    summary = "审核可疑注册",
+    description = "确认可疑后该账户会被停用；忽略则仅记录为误报，两者都会计入规则准确率统计",
+    request_body(content = ReviewRegistrationFlagRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "审核完成", body = RegistrationFlagInfo),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "标记不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "标记不存在", "code": "NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "该标记已处理",
+            body = ApiErrorResponse,
+            example = json!({"error": "该标记已处理", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "admin",
+    params(("flag_id" = i32, Path, description = "标记 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn review_registration_flag(
+    staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(flag_id): Path<i32>,
+    Json(request): Json<ReviewRegistrationFlagRequest>,
+) -> ApiResult<Json<RegistrationFlagInfo>> {
+    let result = RegistrationGuardService::review_flag(
+        &app_state.db,
+        tenant.id(),
+        flag_id,
+        staff.id,
+        request.confirmed,
+    )
+    .await?;
+    ActivityService::record(
+        &app_state.db,
+        staff.id,
+        ActivityAction::RegistrationFlagReviewed,
+        Some((TARGET_REGISTRATION_FLAG, flag_id)),
+        Some(serde_json::json!({ "confirmed": request.confirmed })),
+    )
+    .await;
+    Ok(Json(result))
+}
+
+/// 获取可疑注册规则准确率
+#[utoipa::path(
+    get,
+    operation_id = "admin_registration_flag_stats",
+    path = "/v2/admin/registration-flags/stats",
+    summary = "获取可疑注册规则准确率",
+    description = "按规则统计已确认与已忽略的数量，准确率 = 已确认 / 已审核",
+    responses(
+        (status = 200, description = "成功获取统计", body = RegistrationFlagStats),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn registration_flag_stats(
+    _staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+) -> ApiResult<Json<RegistrationFlagStats>> {
+    Ok(Json(RegistrationGuardService::stats(&app_state.db).await?))
+}
+
+/// 获取被扣留的内容列表
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_spam_holds",
+    path = "/v2/admin/spam-holds",
+    summary = "获取被扣留的内容列表",
+    description = "列出当前租户内垃圾内容评分达到阈值而被自动扣留的评价、工单与公告，按评分从高到低排列；默认只返回待审核的内容",
+    responses(
+        (status = 200, description = "成功获取扣留列表", body = Paginated<SpamHoldInfo>),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    params(SpamHoldQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_spam_holds(
+    _staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<SpamHoldQuery>,
+) -> ApiResult<Page<Paginated<SpamHoldInfo>>> {
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let status = query.status.unwrap_or(SpamHoldStatus::Pending);
+    let (data, total) = SpamGuardService::list_holds(
+        &app_state.db,
+        tenant.id(),
+        status,
+        query.content_type,
+        query.page,
+        query.page_size,
+    )
+    .await?;
+
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 审核被扣留的内容
+#[utoipa::path(
+    post,
+    operation_id = "admin_review_spam_hold",
+    path = "/v2/admin/spam-holds/{hold_id}/review",
+    summary = "审核被扣留的内容",
+    description = "放行后内容正常发布；拒绝则确认为垃圾内容",
+    request_body(content = ReviewSpamHoldRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "审核完成", body = SpamHoldInfo),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "扣留记录不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "扣留记录不存在", "code": "NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "该内容已审核",
+            body = ApiErrorResponse,
+            example = json!({"error": "该内容已审核", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "admin",
+    params(("hold_id" = i32, Path, description = "扣留记录 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn review_spam_hold(
+    staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(hold_id): Path<i32>,
+    Json(request): Json<ReviewSpamHoldRequest>,
+) -> ApiResult<Json<SpamHoldInfo>> {
+    let result = SpamGuardService::review_hold(
+        &app_state.db,
+        tenant.id(),
+        hold_id,
+        staff.id,
+        request.approved,
+    )
+    .await?;
+    ActivityService::record(
+        &app_state.db,
+        staff.id,
+        ActivityAction::SpamHoldReviewed,
+        Some((TARGET_SPAM_HOLD, hold_id)),
+        Some(serde_json::json!({ "approved": request.approved })),
+    )
+    .await;
+    Ok(Json(result))
+}
+
+/// 修改用户名称
+#[utoipa::path(
+    patch,
+    operation_id = "admin_update_user_names",
+    path = "/v2/admin/users/{user_id}/names",
+    summary = "修改用户名称",
+    description = "管理员修改用户名或显示名称，不受保留名称与管理人员相似度限制，用于为官方账户设置名称",
+    request_body(content = UpdateUserNamesRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "修改成功", body = SuccessResponse),
+        (
+            status = 400,
+            description = "参数验证失败",
+            body = ApiErrorResponse,
+            example = json!({"error": "参数验证失败: 用户名只能包含字母、数字和下划线", "code": "VALIDATION_FAILED", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "用户名已被占用",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户名已被占用", "code": "USERNAME_TAKEN", "status": 409})
+        )
+    ),
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_user_names(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+    Json(request): Json<UpdateUserNamesRequest>,
+) -> ApiResult<Json<SuccessResponse>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let db = app_state.db.as_ref();
+    let user = Users::find_by_id(user_id)
+        .filter(users::Column::TenantId.eq(tenant.id()))
+        .one(db)
+        .await?
+        .ok_or_else(ApiError::user_not_found)?;
+
+    let mut active: users::ActiveModel = user.into();
+    if let Some(username) = request.username {
+        let taken = Users::find()
+            .filter(users::Column::Username.eq(&username))
+            .filter(users::Column::Id.ne(user_id))
+            .one(db)
+            .await?
+            .is_some();
+        if taken {
+            return Err(ApiError::Conflict("用户名已被占用".to_string())
+                .with_code(ErrorCode::UsernameTaken));
+        }
+        active.username = Set(username);
+    }
+    if let Some(display_name) = request.display_name {
+        active.display_name = Set(display_name);
+    }
+    active.update(db).await?;
+
+    Ok(Json(SuccessResponse {
+        message: "用户名称已更新".to_string(),
+    }))
+}
+
+/// 获取用户详情
+#[utoipa::path(
+    get,
+    operation_id = "admin_get_user",
+    path = "/v2/admin/users/{user_id}",
+    summary = "获取用户详情",
+    description = "包含邮箱投递状态：收到永久退信或投诉的邮箱会被标记为无法投递",
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "成功获取用户详情", body = AdminUserDetail),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+) -> ApiResult<Json<AdminUserDetail>> {
+    let result = EmailSuppressionService::user_detail(&app_state.db, tenant.id(), user_id).await?;
+    Ok(Json(result))
+}
+
+/// 清除用户邮箱的无法投递标记
+#[utoipa::path(
+    delete,
+    operation_id = "admin_clear_email_suppression",
+    path = "/v2/admin/users/{user_id}/email-suppression",
+    summary = "清除用户邮箱的无法投递标记",
+    description = "用户修复邮箱后恢复向其发送邮件",
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "已清除", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn clear_email_suppression(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+) -> ApiResult<Json<SuccessResponse>> {
+    EmailSuppressionService::clear_for_user(&app_state.db, tenant.id(), user_id).await?;
+    Ok(Json(SuccessResponse {
+        message: "已恢复向该邮箱发送邮件".to_string(),
+    }))
+}
+
+/// 封禁用户
+#[utoipa::path(
+    post,
+    operation_id = "admin_ban_user",
+    path = "/v2/admin/users/{user_id}/ban",
+    summary = "封禁用户",
+    description = "新封禁会替换该用户仍生效的封禁。`ban` 直到手动解除，`temp_ban` 必须指定解封时间，`mute` 可选解封时间。被封禁的用户所有带令牌的请求都会收到包含原因与解封时间的 403，被禁言的用户只能发起读请求；不能封禁管理人员",
+    request_body(content = CreateBanRequest, content_type = "application/json"),
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "已封禁", body = BanInfo),
+        (
+            status = 400,
+            description = "参数无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "临时封禁需要指定解封时间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理人员权限或不能封禁管理人员",
+            body = ApiErrorResponse,
+            example = json!({"error": "不能封禁管理人员", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn ban_user(
+    staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+    Json(request): Json<CreateBanRequest>,
+) -> ApiResult<Json<BanInfo>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let ban = BanService::ban(&app_state.db, tenant.id(), user_id, request).await?;
+    ActivityService::record(
+        &app_state.db,
+        staff.id,
+        ActivityAction::UserBanned,
+        Some((TARGET_USER, user_id)),
+        Some(serde_json::json!({
+            "ban_id": ban.id,
+            "ban_type": ban.ban_type,
+            "ended_at": ban.ended_at,
+        })),
+    )
+    .await;
+    Ok(Json(ban))
+}
+
+/// 解除封禁
+#[utoipa::path(
+    delete,
+    operation_id = "admin_unban_user",
+    path = "/v2/admin/users/{user_id}/ban",
+    summary = "解除封禁",
+    description = "结束该用户所有仍生效的封禁与禁言，立即生效",
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "已解除", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在或没有生效的封禁",
+            body = ApiErrorResponse,
+            examples(
+                ("用户不存在" = (value = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404}))),
+                ("没有生效的封禁" = (value = json!({"error": "该用户没有生效的封禁", "code": "NOT_FOUND", "status": 404})))
+            )
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn unban_user(
+    staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let lifted = BanService::lift(&app_state.db, tenant.id(), user_id).await?;
+    ActivityService::record(
+        &app_state.db,
+        staff.id,
+        ActivityAction::UserUnbanned,
+        Some((TARGET_USER, user_id)),
+        Some(serde_json::json!({ "lifted": lifted })),
+    )
+    .await;
+    Ok(Json(SuccessResponse {
+        message: "已解除封禁".to_string(),
+    }))
+}
+
+/// 获取封禁记录
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_bans",
+    path = "/v2/admin/bans",
+    summary = "获取封禁记录",
+    description = "默认只返回仍生效的封禁，传 `active=false` 查看包括已结束在内的全部记录",
+    params(BanQuery),
+    responses(
+        (status = 200, description = "成功获取封禁记录", body = Paginated<BanInfo>),
+        (
+            status = 400,
+            description = "无效的分页参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 不能小于 1，page_size 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理人员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理人员权限", "code": "STAFF_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_bans(
+    _staff: RequireSiteRole<Moderator>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<BanQuery>,
+) -> ApiResult<Page<Paginated<BanInfo>>> {
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let (data, total) = BanService::list(&app_state.db, tenant.id(), &query).await?;
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 代入用户身份
+#[utoipa::path(
+    post,
+    operation_id = "admin_impersonate_user",
+    path = "/v2/admin/users/{user_id}/impersonate",
+    summary = "代入用户身份",
+    description = "签发以目标用户身份访问的短期令牌，用于复现用户反馈的问题。令牌中带有 `impersonator` 声明，使用该令牌的请求会在响应头 `X-Impersonated-By` 中返回管理员 ID，期间的操作记录都会标注管理员；只能代入当前租户的用户，不能代入管理人员或已停用的账户",
+    request_body(content = ImpersonateRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "签发成功", body = ImpersonationToken),
+        (
+            status = 400,
+            description = "参数验证失败或目标账户已停用",
+            body = ApiErrorResponse,
+            example = json!({"error": "目标账户已停用", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限或不能代入管理人员",
+            body = ApiErrorResponse,
+            example = json!({"error": "不能代入管理人员的身份", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "用户不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "用户 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn impersonate_user(
+    admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(user_id): Path<i32>,
+    Json(request): Json<ImpersonateRequest>,
+) -> ApiResult<Json<ImpersonationToken>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let target =
+        AuthService::impersonation_target(app_state.db.as_ref(), tenant.id(), user_id).await?;
+
+    let (access_token, expires_in) = AuthService::create_impersonation_token(
+        &JwtData {
+            user_id: target.id,
+            username: target.username.clone(),
+            tenant_id: target.tenant_id.clone(),
+        },
+        admin.id,
+        &app_state.config,
+    )
+    .await
+    .map_err(|e| ApiError::Internal(format!("签发代入令牌失败: {e}")))?;
+
+    tracing::warn!(
+        "👤 管理员代入用户身份: admin_id={}, user_id={}, reason={}",
+        admin.id,
+        target.id,
+        request.reason
+    );
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::UserImpersonated,
+        Some((TARGET_USER, target.id)),
+        Some(serde_json::json!({
+            "reason": request.reason,
+            "expires_in": expires_in,
+        })),
+    )
+    .await;
+
+    Ok(Json(ImpersonationToken {
+        access_token,
+        expires_in,
+        user_id: target.id,
+        impersonator_id: admin.id,
+    }))
+}
+
+/// 合并或重命名标签
+#[utoipa::path(
+    post,
+    operation_id = "admin_merge_tags",
+    path = "/v2/admin/tags/merge",
+    summary = "合并或重命名标签",
+    description = "在一个事务内把所有服务器上的 `from` 标签替换为 `to`（只传一个标签即为重命名），每个受影响的服务器都会产生修订版本，完成后重新同步搜索索引并记录操作日志",
+    request_body(content = MergeTagsRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "合并完成", body = MergeTagsResponse),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "tags 长度限制为 1~4", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn merge_tags(
+    admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Json(request): Json<MergeTagsRequest>,
+) -> ApiResult<Json<MergeTagsResponse>> {
+    let result = TagService::merge_tags(&app_state.db, request.from, request.to, admin.id).await?;
+    Ok(Json(result))
+}
+
+/// 获取标签词表
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_tag_vocabulary",
+    path = "/v2/admin/tags/vocabulary",
+    summary = "获取标签词表",
+    description = "列出当前租户的标签词表；词表为空时服主可以自由填写标签",
+    responses(
+        (status = 200, description = "成功获取标签词表", body = TagVocabularyListResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_tag_vocabulary(
+    _admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+) -> ApiResult<Json<TagVocabularyListResponse>> {
+    let data = TagService::list_vocabulary(&app_state.db, tenant.id()).await?;
+    Ok(Json(TagVocabularyListResponse { data }))
+}
+
+/// 添加或修改标签词表条目
+#[utoipa::path(
+    put,
+    operation_id = "admin_upsert_tag_vocabulary",
+    path = "/v2/admin/tags/vocabulary/{tag}",
+    summary = "添加或修改标签词表条目",
+    description = "把标签加入当前租户的词表，已存在时更新说明。词表不为空后，创建或编辑服务器时新增的标签必须在词表中",
+    request_body(content = UpsertTagVocabularyRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "保存成功", body = TagVocabularyEntry),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "tags 长度限制为 1~4", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    params(("tag" = String, Path, description = "标签名")),
+    security(("bearer_auth" = []))
+)]
+pub async fn upsert_tag_vocabulary(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(tag): Path<String>,
+    Json(request): Json<UpsertTagVocabularyRequest>,
+) -> ApiResult<Json<TagVocabularyEntry>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+    let entry =
+        TagService::upsert_vocabulary(&app_state.db, tenant.id(), &tag, request, admin.id).await?;
+    Ok(Json(entry))
+}
+
+/// 从标签词表移除标签
+#[utoipa::path(
+    delete,
+    operation_id = "admin_delete_tag_vocabulary",
+    path = "/v2/admin/tags/vocabulary/{tag}",
+    summary = "从标签词表移除标签",
+    description = "移除后服主不能再新增该标签，已使用该标签的服务器不受影响",
+    responses(
+        (status = 200, description = "删除成功", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "标签不在词表中",
+            body = ApiErrorResponse,
+            example = json!({"error": "标签不在词表中", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    params(("tag" = String, Path, description = "标签名")),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_tag_vocabulary(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(tag): Path<String>,
+) -> ApiResult<Json<SuccessResponse>> {
+    TagService::delete_vocabulary(&app_state.db, tenant.id(), &tag, admin.id).await?;
+    Ok(Json(SuccessResponse {
+        message: "标签已从词表移除".to_string(),
+    }))
+}
+
+/// 获取离线下架状态
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_delisting",
+    path = "/v2/admin/delisting",
+    summary = "获取离线下架状态",
+    description = "列出当前租户内因长期离线被标记、已自动下架或被豁免的服务器",
+    responses(
+        (status = 200, description = "成功获取离线下架状态", body = DelistingListResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_delisting(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+) -> ApiResult<Json<DelistingListResponse>> {
+    let result = DelistingService::list(&app_state.db, tenant.id()).await?;
+    Ok(Json(result))
+}
+
+/// 调整服务器的离线下架状态
+#[utoipa::path(
+    put,
+    operation_id = "admin_update_delisting",
+    path = "/v2/admin/servers/{server_id}/delisting",
+    summary = "调整服务器的离线下架状态",
+    description = "设置服务器是否豁免自动下架，或清除离线标记并立即重新上架",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    request_body(content = UpdateDelistingRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "更新成功", body = DelistingInfo),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_delisting(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Json(request): Json<UpdateDelistingRequest>,
+) -> ApiResult<Json<DelistingInfo>> {
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+    let result = DelistingService::update(&app_state.db, server_id, request).await?;
+    Ok(Json(result))
+}
+
+/// 获取全部服务器
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_servers",
+    path = "/v2/admin/servers",
+    summary = "获取全部服务器",
+    description = "列出当前租户的全部服务器，包括隐藏、自动下架与已停用的服务器，按 ID 倒序",
+    params(AdminServerQuery),
+    responses(
+        (status = 200, description = "成功获取服务器列表", body = Paginated<AdminServerInfo>),
+        (
+            status = 400,
+            description = "无效的分页参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 不能小于 1，page_size 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_servers(
+    _admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<AdminServerQuery>,
+) -> ApiResult<Page<Paginated<AdminServerInfo>>> {
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let (data, total) = ServerModerationService::list(&app_state.db, tenant.id(), &query).await?;
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 强制编辑服务器
+#[utoipa::path(
+    put,
+    operation_id = "admin_force_update_server",
+    path = "/v2/admin/servers/{server_id}",
+    summary = "强制编辑服务器",
+    description = "以站点管理员身份编辑服务器，无需是服务器成员。字段与校验规则同服务器编辑接口，短链接仍只能修改一次",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    request_body(content = UpdateServerRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "更新成功", body = AdminServerInfo),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "更新字段不能为空", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "短链接已被占用或已修改过",
+            body = ApiErrorResponse,
+            example = json!({"error": "短链接已被占用", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn force_update_server(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    TypedMultipart(update_data): TypedMultipart<UpdateServerRequest>,
+) -> ApiResult<Json<AdminServerInfo>> {
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+
+    let server = ServerModerationService::force_update(
+        &app_state.db,
+        app_state.storage.as_deref(),
+        server_id,
+        update_data,
+        admin.id,
+    )
+    .await?;
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::ServerModerated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "operation": "edit" })),
+    )
+    .await;
+    Ok(Json(server))
+}
+
+/// 设置服务器可见性
+#[utoipa::path(
+    put,
+    operation_id = "admin_set_server_visibility",
+    path = "/v2/admin/servers/{server_id}/visibility",
+    summary = "设置服务器可见性",
+    description = "以站点管理员身份公开、设为不公开或隐藏服务器，用于处理违规内容；原因会写入操作记录",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    request_body(content = AdminVisibilityRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "设置成功", body = AdminServerInfo),
+        (
+            status = 400,
+            description = "参数无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "参数验证失败: reason: 原因不能超过 200 个字符", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn set_server_visibility(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    Json(request): Json<AdminVisibilityRequest>,
+) -> ApiResult<Json<AdminServerInfo>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+
+    let server = ServerModerationService::set_visibility(
+        &app_state.db,
+        server_id,
+        request.visibility,
+        admin.id,
+    )
+    .await?;
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::ServerModerated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({
+            "operation": "visibility",
+            "visibility": request.visibility,
+            "reason": request.reason,
+        })),
+    )
+    .await;
+    Ok(Json(server))
+}
+
+/// 通过服务器发布申请
+#[utoipa::path(
+    post,
+    operation_id = "admin_approve_server",
+    path = "/v2/admin/servers/{server_id}/approve",
+    summary = "通过服务器发布申请",
+    description = "将待审核的服务器设为已发布，使其出现在列表、搜索与站点地图中，并通知服主",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    responses(
+        (status = 200, description = "审核通过", body = AdminServerInfo),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "服务器不在待审核状态",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不在待审核状态", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn approve_server(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+) -> ApiResult<Json<AdminServerInfo>> {
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+
+    let server = ServerModerationService::review(&app_state.db, server_id, true, None).await?;
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::ServerModerated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "operation": "approve" })),
+    )
+    .await;
+    Ok(Json(server))
+}
+
+/// 驳回服务器发布申请
+#[utoipa::path(
+    post,
+    operation_id = "admin_reject_server",
+    path = "/v2/admin/servers/{server_id}/reject",
+    summary = "驳回服务器发布申请",
+    description = "将待审核的服务器设为未通过，原因会展示给服主并写入操作记录；服主修改后可以重新提交",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    request_body(content = ServerReviewRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "已驳回", body = AdminServerInfo),
+        (
+            status = 400,
+            description = "参数无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "参数验证失败: reason: 原因不能超过 200 个字符", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "服务器不在待审核状态",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不在待审核状态", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn reject_server(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    Json(request): Json<ServerReviewRequest>,
+) -> ApiResult<Json<AdminServerInfo>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+
+    let reason = request
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+    let server =
+        ServerModerationService::review(&app_state.db, server_id, false, reason.clone()).await?;
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::ServerModerated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({
+            "operation": "reject",
+            "reason": reason,
+        })),
+    )
+    .await;
+    Ok(Json(server))
+}
+
+/// 移除服务器画册图片
+#[utoipa::path(
+    delete,
+    operation_id = "admin_remove_gallery_image",
+    path = "/v2/admin/servers/{server_id}/gallery/{image_id}",
+    summary = "移除服务器画册图片",
+    description = "以站点管理员身份删除不当的画册图片，同时删除存储中的文件",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ("image_id" = i32, Path, description = "画册图片 ID")
+    ),
+    responses(
+        (status = 200, description = "删除成功", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "图片不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "图片不存在", "code": "IMAGE_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn remove_gallery_image(
+    admin: RequireSiteRole<Admin>,
+    tenant: CurrentTenant,
+    State(app_state): State<AppState>,
+    Path((server_id, image_id)): Path<(i32, i32)>,
+) -> ApiResult<Json<SuccessResponse>> {
+    ServerService::ensure_in_tenant(&app_state.db, server_id, tenant.id()).await?;
+
+    ServerService::delete_gallery_image(
+        &app_state.db,
+        app_state.storage.as_deref(),
+        server_id,
+        image_id,
+    )
+    .await?;
+    ActivityService::record(
+        &app_state.db,
+        admin.id,
+        ActivityAction::ServerModerated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({
+            "operation": "remove_gallery_image",
+            "image_id": image_id,
+        })),
+    )
+    .await;
+    Ok(Json(SuccessResponse {
+        message: "已移除画册图片".to_string(),
+    }))
+}
+
+/// 获取功能开关列表
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_feature_flags",
+    path = "/v2/admin/feature-flags",
+    summary = "获取功能开关列表",
+    responses(
+        (status = 200, description = "成功获取功能开关", body = FeatureFlagListResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_feature_flags(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+) -> ApiResult<Json<FeatureFlagListResponse>> {
+    let data = FeatureFlagService::list(&app_state.db).await?;
+    Ok(Json(FeatureFlagListResponse { data }))
+}
+
+/// 创建或更新功能开关
+#[utoipa::path(
+    put,
+    operation_id = "admin_upsert_feature_flag",
+    path = "/v2/admin/feature-flags/{key}",
+    summary = "创建或更新功能开关",
+    description = "开关关闭时仅白名单用户可见；开启后按用户 ID 稳定分桶灰度放量，匿名用户仅在比例为 100 时可见",
+    request_body(content = UpdateFeatureFlagRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "保存成功", body = FeatureFlagInfo),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "rollout_percentage 需在 0~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    params(("key" = String, Path, description = "开关标识")),
+    security(("bearer_auth" = []))
+)]
+pub async fn upsert_feature_flag(
+    admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+    Json(request): Json<UpdateFeatureFlagRequest>,
+) -> ApiResult<Json<FeatureFlagInfo>> {
+    let flag = FeatureFlagService::upsert(&app_state.db, &key, request, admin.id).await?;
+    Ok(Json(flag))
+}
+
+/// 删除功能开关
+#[utoipa::path(
+    delete,
+    operation_id = "admin_delete_feature_flag",
+    path = "/v2/admin/feature-flags/{key}",
+    summary = "删除功能开关",
+    description = "删除后该功能对所有用户关闭",
+    responses(
+        (status = 200, description = "删除成功", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "功能开关不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "功能开关不存在", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    params(("key" = String, Path, description = "开关标识")),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_feature_flag(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+) -> ApiResult<Json<SuccessResponse>> {
+    FeatureFlagService::delete(&app_state.db, &key).await?;
+    Ok(Json(SuccessResponse {
+        message: "功能开关已删除".to_string(),
+    }))
+}
+
+/// 获取状态页故障列表
+#[utoipa::path(
+    get,
+    operation_id = "admin_list_incidents",
+    path = "/v2/admin/status/incidents",
+    summary = "获取状态页故障列表",
+    description = "列出全部故障记录，进行中的在前",
+    responses(
+        (status = 200, description = "成功获取故障列表", body = IncidentListResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_incidents(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+) -> ApiResult<Json<IncidentListResponse>> {
+    let data = StatusService::list_incidents(&app_state.db).await?;
+    Ok(Json(IncidentListResponse { data }))
+}
+
+/// 创建或更新状态页故障
+#[utoipa::path(
+    put,
+    operation_id = "admin_upsert_incident",
+    path = "/v2/admin/status/incidents/{key}",
+    summary = "创建或更新状态页故障",
+    description = "进行中的故障会显示在 `/v2/meta/status`，`critical` 级别会使整体状态变为不可用；将 active 设为 false 即标记为已恢复",
+    request_body(content = UpdateIncidentRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "保存成功", body = IncidentInfo),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "未知的组件: cdn", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "admin",
+    params(("key" = String, Path, description = "故障标识")),
+    security(("bearer_auth" = []))
+)]
+pub async fn upsert_incident(
+    admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+    Json(request): Json<UpdateIncidentRequest>,
+) -> ApiResult<Json<IncidentInfo>> {
+    let incident = StatusService::upsert_incident(&app_state.db, &key, request, admin.id).await?;
+    Ok(Json(incident))
+}
+
+/// 删除状态页故障
+#[utoipa::path(
+    delete,
+    operation_id = "admin_delete_incident",
+    path = "/v2/admin/status/incidents/{key}",
+    summary = "删除状态页故障",
+    description = "用于删除误发布的故障；已恢复的故障应通过更新接口标记",
+    responses(
+        (status = 200, description = "删除成功", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "故障不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "故障不存在", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "admin",
+    params(("key" = String, Path, description = "故障标识")),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_incident(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+) -> ApiResult<Json<SuccessResponse>> {
+    StatusService::delete_incident(&app_state.db, &key).await?;
+    Ok(Json(SuccessResponse {
+        message: "故障已删除".to_string(),
+    }))
+}
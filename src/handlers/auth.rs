@@ -1,47 +1,64 @@
-use axum::{extract::State, http::HeaderMap, Extension, Json};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension,
+};
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
-use tokio::task;
 use validator::Validate;
 
 use crate::{
+    config::EmailConfig,
     entities::users::{self, RoleEnum},
-    errors::{ApiError, ApiErrorResponse, ApiResult},
-    middleware::UserClaims,
+    errors::{
+        ApiError, ApiErrorResponse, ApiResult, BannedErrorResponse, ErrorCode,
+        RateLimitedErrorResponse,
+    },
+    extract::{Json, Query},
+    middleware::{CurrentTenant, UserClaims},
     schemas::{
-        auth::{AuthToken, UserLoginData, UserRegisterByEmailData, UserRegisterData},
+        admin::BanType,
+        auth::{
+            AuthToken, OAuthAuthorizeResponse, OAuthCallbackQuery, OAuthCallbackResponse,
+            PasswordResetConfirmData, PasswordResetRequestData, UserLoginData,
+            UserRegisterByEmailData, UserRegisterData,
+        },
         servers::SuccessResponse,
     },
-    services::auth::{AuthService, JwtData},
+    services::{
+        auth::{AuthService, CodeCheck, JwtData, SessionClient},
+        ban::BanService,
+        database::DatabaseConnection,
+        email::suppression::EmailSuppressionService,
+        name_policy::NamePolicyService,
+        oauth::OAuthService,
+        password::PasswordService,
+        registration_guard::RegistrationGuardService,
+        utils::client_ip,
+    },
     AppState,
 };
 use anyhow::Context;
-use bcrypt::{hash, verify};
-
-fn get_ip(headers: &HeaderMap) -> Option<String> {
-    headers
-        .get("x-forwarded-for")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .or_else(|| {
-            headers
-                .get("x-real-ip")
-                .and_then(|h| h.to_str().ok())
-                .map(|s| s.to_string())
-                .filter(|s| !s.is_empty())
-        })
-        .or_else(|| {
-            headers
-                .get("x-forwarded-host")
-                .and_then(|h| h.to_str().ok())
-                .map(|s| s.to_string())
-                .filter(|s| !s.is_empty())
-        })
-}
+
+/// 登录注册接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        login,
+        logout,
+        register,
+        register_email_code,
+        request_password_reset,
+        confirm_password_reset,
+        oauth_authorize,
+        oauth_callback
+    ),
+    tags((name = "auth", description = "Authentication and registration endpoints"))
+)]
+pub struct AuthApi;
 
 #[utoipa::path(
     post,
+    operation_id = "login",
     path = "/v2/auth/login",
     summary = "用户登录",
     description = "使用用户名或邮箱和密码进行登录，成功后返回 JWT 访问令牌",
@@ -50,12 +67,15 @@ fn get_ip(headers: &HeaderMap) -> Option<String> {
         (status = 200, description = "登录成功", body = AuthToken),
         (status = 400, description = "用户名或密码不能为空", body = ApiErrorResponse),
         (status = 401, description = "用户不存在", body = ApiErrorResponse),
+        (status = 403, description = "账户已被封禁", body = BannedErrorResponse),
+        (status = 429, description = "登录失败次数过多，账户或 IP 已被暂时锁定", body = RateLimitedErrorResponse),
         (status = 500, description = "服务器错误", body = ApiErrorResponse)
     )
 )]
 pub async fn login(
     headers: HeaderMap,
     State(app_state): State<AppState>,
+    tenant: CurrentTenant,
     Json(user_data): Json<UserLoginData>,
 ) -> ApiResult<Json<AuthToken>> {
     if user_data.username_or_email.is_empty() || user_data.password.is_empty() {
@@ -70,59 +90,77 @@ pub async fn login(
             if user_data.username_or_email.contains('@') {
                 users::Entity::find()
                     .filter(users::Column::Email.eq(&user_data.username_or_email))
+                    .filter(users::Column::TenantId.eq(tenant.id()))
                     .one(db.as_ref())
                     .await
             } else {
                 users::Entity::find()
                     .filter(users::Column::Username.eq(&user_data.username_or_email))
+                    .filter(users::Column::TenantId.eq(tenant.id()))
                     .one(db.as_ref())
                     .await
             }
         },
-        async { get_ip(&headers) }
+        async { client_ip(&headers) }
     );
 
-    let user = user_result?.ok_or(ApiError::Unauthorized("用户不存在".to_string()))?;
+    let user = user_result?.ok_or(
+        ApiError::Unauthorized("用户不存在".to_string()).with_code(ErrorCode::UserNotFound),
+    )?;
 
-    let password = user_data.password;
-    let hashed_password = user.hashed_password.clone();
     let user_id = user.id;
     let username = user.username.clone();
 
-    let verify_result = task::spawn_blocking(move || verify(&password, &hashed_password)) // 煞笔 bcrypt 真他妈慢
-        .await
-        .map_err(|_| ApiError::InternalServerError("密码校验任务失败".to_string()))?;
-
-    // TODO: 写一个 bcrypt 12cost 转 10 cost，不然 12 cost 太慢了
-
-    match verify_result {
-        Ok(true) => {
-            let jwt_data = JwtData {
-                user_id,
-                username: username.clone(),
-            };
-            let token = AuthService::create_access_token(&jwt_data, config)?;
-
-            let db_clone = db.clone();
-            tokio::spawn(async move {
-                if let Err(e) = AuthService::update_last_login(&db_clone, user_id, client_ip).await
-                {
-                    eprintln!("更新最后登录时间失败: {e:?}");
-                }
-            });
-
-            Ok(Json(AuthToken {
-                access_token: token,
-                expires_in: config.jwt.expiration,
-            }))
+    let password = user_data.password;
+    if !PasswordService::verify(password.clone(), user.hashed_password.clone()).await? {
+        return Err(
+            ApiError::Unauthorized("密码错误".to_string()).with_code(ErrorCode::InvalidCredentials)
+        );
+    }
+
+    // 禁言不影响登录，只限制写操作
+    if let Some(ban) = BanService::active_ban(db, user_id).await {
+        if ban.ban_type != BanType::Mute.as_str() {
+            return Err(ApiError::Banned(ban));
         }
-        Ok(false) => Err(ApiError::Unauthorized("密码错误".to_string())),
-        Err(_) => Err(ApiError::InternalServerError("密码校验失败".to_string())),
     }
+
+    let jwt_data = JwtData {
+        user_id,
+        username: username.clone(),
+        tenant_id: tenant.0.id.clone(),
+    };
+    let token =
+        AuthService::create_access_token(&jwt_data, &SessionClient::from_headers(&headers), config)
+            .await?;
+
+    let db_clone = db.clone();
+    let bcrypt_cost = config.password.bcrypt_cost;
+    let stale_hash = PasswordService::needs_rehash(&user.hashed_password, bcrypt_cost)
+        .then_some(user.hashed_password);
+    tokio::spawn(async move {
+        if let Err(e) = AuthService::update_last_login(&db_clone, user_id, client_ip).await {
+            eprintln!("更新最后登录时间失败: {e:?}");
+        }
+        // 只有登录时能拿到明文密码，借机把旧成本的哈希换成当前配置的成本
+        if let Some(stale_hash) = stale_hash {
+            if let Err(e) =
+                rehash_password(&db_clone, user_id, &stale_hash, password, bcrypt_cost).await
+            {
+                tracing::warn!("⚠️  用户 {} 密码哈希升级失败: {}", user_id, e);
+            }
+        }
+    });
+
+    Ok(Json(AuthToken {
+        access_token: token,
+        expires_in: config.jwt.expiration,
+    }))
 }
 
 #[utoipa::path(
     post,
+    operation_id = "logout",
     path = "/v2/auth/logout",
     summary = "用户登出",
     description = "登出当前用户，清除 JWT 访问令牌",
@@ -142,6 +180,12 @@ pub async fn logout(
 ) -> ApiResult<Json<SuccessResponse>> {
     if let Some(claims) = user_claims {
         AuthService::blacklist_token(&claims.raw_token, &app_state.config).await?;
+        // 黑名单已让令牌失效，移出会话列表失败不影响登出
+        if let Some(jti) = claims.claims.jti.as_deref() {
+            if let Err(e) = AuthService::revoke_session(claims.claims.id, jti).await {
+                tracing::warn!("⚠️  移除会话 {} 失败: {}", jti, e);
+            }
+        }
 
         Ok(Json(SuccessResponse {
             message: "登出成功".to_string(),
@@ -154,21 +198,30 @@ pub async fn logout(
 /// 邮箱验证码
 #[utoipa::path(
     post,
+    operation_id = "register_email_code",
     path = "/v2/auth/register/email-code",
     summary = "使用邮箱注册用户",
     description = "使用邮箱注册用户，发送验证码到用户邮箱",
     tag = "auth",
     responses(
         (status = 200, description = "注册成功", body = SuccessResponse),
-        (status = 400, description = "请求数据不合法", body = ApiErrorResponse),
-        (status = 400, description = "用户已存在", body = ApiErrorResponse),
-        (status = 500, description = "服务器错误", body = ApiErrorResponse)
+        (status = 400, description = "请求数据不合法或用户已存在", body = ApiErrorResponse,
+         example = json!({"error": "用户已存在", "code": "USER_EXISTS", "status": 400})),
+        (status = 403, description = "当前站点未开放注册", body = ApiErrorResponse,
+         example = json!({"error": "当前站点未开放注册", "code": "REGISTRATION_CLOSED", "status": 403})),
+        (status = 429, description = "验证码请求过于频繁：同一邮箱 60 秒内只能发送一次，或邮箱、IP 已被暂时锁定", body = RateLimitedErrorResponse),
+        (status = 503, description = "验证码服务不可用", body = ApiErrorResponse),
+        (status = 500, description = "服务器错误", body = ApiErrorResponse),
+        (status = 501, description = "未配置发信服务", body = ApiErrorResponse,
+         example = json!({"error": "未配置发信服务，无法发送邮件", "code": "FEATURE_DISABLED", "status": 501}))
     )
 )]
 pub async fn register_email_code(
     State(app_state): State<AppState>,
+    tenant: CurrentTenant,
     Json(user_data): Json<UserRegisterByEmailData>,
 ) -> ApiResult<Json<SuccessResponse>> {
+    ensure_registration_open(&tenant)?;
     if user_data.email.is_empty() {
         return Err(ApiError::BadRequest("邮箱不能为空".to_string()));
     }
@@ -184,10 +237,18 @@ pub async fn register_email_code(
         .context("检查用户是否存在失败")?;
 
     if user_exists {
-        return Err(ApiError::BadRequest("用户已存在".to_string()));
+        return Err(ApiError::BadRequest("用户已存在".to_string()).with_code(ErrorCode::UserExists));
     }
 
-    AuthService::send_email_code(&user_data.email, &app_state.config)
+    if EmailSuppressionService::is_suppressed(&app_state.db, &user_data.email).await {
+        return Err(ApiError::BadRequest(
+            "该邮箱多次退信或投诉，无法接收邮件，请更换邮箱".to_string(),
+        ));
+    }
+
+    let email_config = EmailConfig::require(app_state.config.email.as_ref())?;
+    AuthService::acquire_email_code_cooldown(&user_data.email).await?;
+    AuthService::send_email_code(&user_data.email, email_config)
         .await
         .map_err(|e| ApiError::InternalServerError(format!("发送验证码失败: {e}")))?;
 
@@ -198,45 +259,70 @@ pub async fn register_email_code(
 
 #[utoipa::path(
     post,
+    operation_id = "register",
     path = "/v2/auth/register",
     summary = "用户注册",
-    description = "使用邮箱和密码注册新用户",
+    description = "使用邮箱验证码和密码注册新用户，成功后直接返回 JWT 访问令牌",
     tag = "auth",
     responses(
-        (status = 200, description = "注册成功", body = SuccessResponse),
-        (status = 400, description = "请求数据不合法", body = ApiErrorResponse),
-        (status = 400, description = "验证码无效", body = ApiErrorResponse),
-        (status = 400, description = "用户已存在", body = ApiErrorResponse),
+        (status = 200, description = "注册成功", body = AuthToken),
+        (status = 400, description = "请求数据不合法、验证码无效或已作废（输错 5 次）、用户已存在、用户名已被使用，或名称包含保留字、与管理人员过于相似", body = ApiErrorResponse,
+         examples(
+             ("验证码无效" = (value = json!({"error": "验证码无效", "code": "INVALID_VERIFICATION_CODE", "status": 400}))),
+             ("验证码已作废" = (value = json!({"error": "验证码错误次数过多，请重新获取", "code": "VERIFICATION_CODE_EXHAUSTED", "status": 400}))),
+             ("用户已存在" = (value = json!({"error": "用户已存在", "code": "USER_EXISTS", "status": 400}))),
+             ("用户名已被使用" = (value = json!({"error": "用户名已被使用", "code": "USERNAME_TAKEN", "status": 400})))
+         )),
+        (status = 403, description = "当前站点未开放注册", body = ApiErrorResponse,
+         example = json!({"error": "当前站点未开放注册", "code": "REGISTRATION_CLOSED", "status": 403})),
+        (status = 500, description = "服务器错误", body = ApiErrorResponse),
+        (status = 503, description = "验证码服务不可用", body = ApiErrorResponse)
     )
 )]
 pub async fn register(
     State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    headers: HeaderMap,
     Json(user_data): Json<UserRegisterData>,
-) -> ApiResult<Json<SuccessResponse>> {
+) -> ApiResult<Json<AuthToken>> {
+    ensure_registration_open(&tenant)?;
     if let Err(e) = user_data.validate() {
         return Err(ApiError::BadRequest(format!("请求数据不合法: {}", e)));
     }
 
-    if AuthService::validate_email_code(&user_data.email, &user_data.code)
-        .await
-        .is_err()
-    {
-        return Err(ApiError::BadRequest("验证码无效".to_string()));
-    }
+    let config = &app_state.config;
+    let db = &app_state.db;
 
-    let user_exists = users::Entity::find()
-        .filter(users::Column::Email.eq(&user_data.email))
-        .one(app_state.db.as_ref())
-        .await
-        .map(|user| user.is_some())
-        .context("检查用户是否存在失败")?;
+    NamePolicyService::check(
+        db,
+        &config.name_policy,
+        &[&user_data.username, &user_data.display_name],
+        None,
+    )
+    .await?;
 
-    if user_exists {
-        return Err(ApiError::BadRequest("用户已存在".to_string()));
+    let (email_taken, username_taken) = tokio::join!(
+        users::Entity::find()
+            .filter(users::Column::Email.eq(&user_data.email))
+            .one(db.as_ref()),
+        users::Entity::find()
+            .filter(users::Column::Username.eq(&user_data.username))
+            .one(db.as_ref())
+    );
+    if email_taken.context("检查用户是否存在失败")?.is_some() {
+        return Err(ApiError::BadRequest("用户已存在".to_string()).with_code(ErrorCode::UserExists));
     }
+    if username_taken.context("检查用户名是否存在失败")?.is_some() {
+        return Err(
+            ApiError::BadRequest("用户名已被使用".to_string()).with_code(ErrorCode::UsernameTaken)
+        );
+    }
+
+    // 放在其他检查之后，避免验证码因用户名冲突等可修正的错误被提前消耗
+    ensure_code_valid(AuthService::validate_email_code(&user_data.email, &user_data.code).await)?;
 
-    let hashed_password = hash(&user_data.password, 10)
-        .map_err(|e| ApiError::InternalServerError(format!("密码加密失败: {}", e)))?;
+    let hashed_password =
+        PasswordService::hash(user_data.password, config.password.bcrypt_cost).await?;
 
     let new_user = users::ActiveModel {
         username: sea_orm::Set(user_data.username),
@@ -245,15 +331,284 @@ pub async fn register(
         display_name: sea_orm::Set(user_data.display_name),
         role: sea_orm::Set(RoleEnum::User),
         is_active: sea_orm::Set(true),
+        tenant_id: sea_orm::Set(tenant.0.id.clone()),
         ..Default::default()
     };
 
-    new_user
-        .insert(app_state.db.as_ref())
+    let user = new_user
+        .insert(db.as_ref())
         .await
         .map_err(|e| ApiError::InternalServerError(format!("注册用户失败: {}", e)))?;
 
+    let jwt_data = JwtData {
+        user_id: user.id,
+        username: user.username.clone(),
+        tenant_id: tenant.0.id.clone(),
+    };
+    let token =
+        AuthService::create_access_token(&jwt_data, &SessionClient::from_headers(&headers), config)
+            .await?;
+
+    // 注册后直接登录，同时记录登录信息；可疑注册只做标记，不影响本次注册结果
+    let db = db.clone();
+    let guard_config = config.registration_guard.clone();
+    let client_ip = client_ip(&headers);
+    tokio::spawn(async move {
+        if let Err(e) = AuthService::update_last_login(&db, user.id, client_ip.clone()).await {
+            tracing::warn!("⚠️  更新最后登录时间失败: {}", e);
+        }
+        if let Err(e) =
+            RegistrationGuardService::inspect(&db, &guard_config, &user, client_ip.as_deref()).await
+        {
+            tracing::warn!("⚠️  可疑注册检查失败: {}", e);
+        }
+    });
+
+    Ok(Json(AuthToken {
+        access_token: token,
+        expires_in: config.jwt.expiration,
+    }))
+}
+
+/// 申请密码重置
+#[utoipa::path(
+    post,
+    operation_id = "request_password_reset",
+    path = "/v2/auth/password-reset/request",
+    summary = "申请密码重置",
+    description = "向邮箱发送密码重置验证码，验证码有效期 5 分钟，同一邮箱 60 秒内只能申请一次。\
+                   为避免泄露邮箱是否已注册，邮箱不存在时同样返回成功",
+    tag = "auth",
+    responses(
+        (status = 200, description = "验证码已发送", body = SuccessResponse),
+        (status = 400, description = "请求数据不合法", body = ApiErrorResponse),
+        (status = 429, description = "邮件发送过于频繁", body = RateLimitedErrorResponse),
+        (status = 500, description = "服务器错误", body = ApiErrorResponse),
+        (status = 503, description = "验证码服务不可用", body = ApiErrorResponse),
+        (status = 501, description = "未配置发信服务", body = ApiErrorResponse,
+         example = json!({"error": "未配置发信服务，无法发送邮件", "code": "FEATURE_DISABLED", "status": 501}))
+    )
+)]
+pub async fn request_password_reset(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Json(data): Json<PasswordResetRequestData>,
+) -> ApiResult<Json<SuccessResponse>> {
+    if data.validate().is_err() {
+        return Err(ApiError::BadRequest("请求数据不合法".to_string()));
+    }
+    let email_config = EmailConfig::require(app_state.config.email.as_ref())?;
+    // 无论邮箱是否注册都占用冷却，避免通过 429 判断邮箱是否存在
+    AuthService::acquire_password_reset_cooldown(&data.email).await?;
+
+    let user = users::Entity::find()
+        .filter(users::Column::Email.eq(&data.email))
+        .filter(users::Column::TenantId.eq(tenant.id()))
+        .filter(users::Column::IsActive.eq(true))
+        .one(app_state.db.as_ref())
+        .await
+        .context("查询用户失败")?;
+
+    if user.is_some() && !EmailSuppressionService::is_suppressed(&app_state.db, &data.email).await {
+        AuthService::send_password_reset_code(&data.email, email_config)
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("发送验证码失败: {e}")))?;
+    }
+
+    Ok(Json(SuccessResponse {
+        message: format!("如果该邮箱已注册，验证码已发送到 {}", data.email),
+    }))
+}
+
+/// 确认密码重置
+#[utoipa::path(
+    post,
+    operation_id = "confirm_password_reset",
+    path = "/v2/auth/password-reset/confirm",
+    summary = "确认密码重置",
+    description = "校验邮件中的验证码并设置新密码，验证码使用一次后失效，输错 5 次后作废",
+    tag = "auth",
+    responses(
+        (status = 200, description = "密码已重置", body = SuccessResponse),
+        (status = 400, description = "请求数据不合法、验证码无效或已作废（输错 5 次）", body = ApiErrorResponse,
+         examples(
+             ("验证码无效" = (value = json!({"error": "验证码无效", "code": "INVALID_VERIFICATION_CODE", "status": 400}))),
+             ("验证码已作废" = (value = json!({"error": "验证码错误次数过多，请重新获取", "code": "VERIFICATION_CODE_EXHAUSTED", "status": 400})))
+         )),
+        (status = 500, description = "服务器错误", body = ApiErrorResponse),
+        (status = 503, description = "验证码服务不可用", body = ApiErrorResponse)
+    )
+)]
+pub async fn confirm_password_reset(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Json(data): Json<PasswordResetConfirmData>,
+) -> ApiResult<Json<SuccessResponse>> {
+    if let Err(e) = data.validate() {
+        return Err(ApiError::BadRequest(format!("请求数据不合法: {}", e)));
+    }
+
+    ensure_code_valid(AuthService::validate_password_reset_code(&data.email, &data.code).await)?;
+
+    // 验证码只会发给本租户内已启用的账号，查不到说明账号在此期间被停用或删除
+    let user = users::Entity::find()
+        .filter(users::Column::Email.eq(&data.email))
+        .filter(users::Column::TenantId.eq(tenant.id()))
+        .filter(users::Column::IsActive.eq(true))
+        .one(app_state.db.as_ref())
+        .await
+        .context("查询用户失败")?
+        .ok_or_else(|| {
+            ApiError::BadRequest("验证码无效".to_string())
+                .with_code(ErrorCode::InvalidVerificationCode)
+        })?;
+
+    let hashed_password =
+        PasswordService::hash(data.new_password, app_state.config.password.bcrypt_cost).await?;
+
+    let user_id = user.id;
+    let mut active: users::ActiveModel = user.into();
+    active.hashed_password = sea_orm::Set(hashed_password);
+    active
+        .update(app_state.db.as_ref())
+        .await
+        .map_err(|e| ApiError::InternalServerError(format!("重置密码失败: {}", e)))?;
+
+    // 重置密码通常意味着账户可能已泄露，旧会话一并失效
+    if let Err(e) = AuthService::revoke_user_tokens(user_id).await {
+        tracing::warn!("⚠️  吊销用户 {} 的令牌失败: {}", user_id, e);
+    }
+
     Ok(Json(SuccessResponse {
-        message: "注册成功".to_string(),
+        message: "密码已重置，请使用新密码登录".to_string(),
     }))
 }
+
+/// 发起第三方登录
+#[utoipa::path(
+    get,
+    operation_id = "oauth_authorize",
+    path = "/v2/auth/oauth/{provider}/authorize",
+    summary = "发起第三方登录",
+    description = "生成第三方平台（github、microsoft）的授权地址，客户端跳转到该地址完成授权；state 只能使用一次，过期后需要重新发起",
+    tag = "auth",
+    params(("provider" = String, Path, description = "登录平台：github 或 microsoft")),
+    responses(
+        (status = 200, description = "授权地址", body = OAuthAuthorizeResponse),
+        (status = 400, description = "不支持的登录方式", body = ApiErrorResponse,
+         example = json!({"error": "不支持的登录方式: example", "code": "BAD_REQUEST", "status": 400})),
+        (status = 501, description = "未配置该平台的登录", body = ApiErrorResponse),
+        (status = 503, description = "第三方登录暂时不可用", body = ApiErrorResponse)
+    )
+)]
+pub async fn oauth_authorize(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(provider): Path<String>,
+) -> ApiResult<Json<OAuthAuthorizeResponse>> {
+    let provider = OAuthService::provider(&provider)?;
+    let response =
+        OAuthService::authorize(&app_state.config.oauth, provider, tenant.id(), None).await?;
+
+    Ok(Json(response))
+}
+
+/// 第三方登录回调
+#[utoipa::path(
+    get,
+    operation_id = "oauth_callback",
+    path = "/v2/auth/oauth/{provider}/callback",
+    summary = "第三方登录回调",
+    description = "第三方平台授权完成后的回调。已绑定的账户直接登录；平台确认过的邮箱与已有账户一致时自动绑定并登录；\
+                   否则在开放注册的站点用平台确认过的邮箱创建新账户。由账户设置发起的绑定需要携带发起绑定的用户的令牌，\
+                   只返回绑定信息，不签发令牌",
+    tag = "auth",
+    params(
+        ("provider" = String, Path, description = "登录平台：github 或 microsoft"),
+        OAuthCallbackQuery
+    ),
+    responses(
+        (status = 200, description = "登录、注册或绑定成功", body = OAuthCallbackResponse),
+        (status = 400, description = "授权请求无效或已过期，或第三方账户没有已验证的邮箱", body = ApiErrorResponse,
+         example = json!({"error": "授权请求无效或已过期", "code": "BAD_REQUEST", "status": 400})),
+        (status = 403, description = "账户已被封禁", body = BannedErrorResponse),
+        (status = 403, description = "当前站点未开放注册，或绑定时未携带发起绑定的用户的令牌", body = ApiErrorResponse),
+        (status = 501, description = "未配置该平台的登录", body = ApiErrorResponse),
+        (status = 409, description = "第三方账户已绑定其他用户，或邮箱已被注册", body = ApiErrorResponse,
+         example = json!({"error": "该邮箱已注册，请使用密码登录后在账户设置中绑定", "code": "CONFLICT", "status": 409})),
+        (status = 503, description = "第三方平台暂时无法访问", body = ApiErrorResponse)
+    )
+)]
+pub async fn oauth_callback(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    user_claims: Option<Extension<UserClaims>>,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> ApiResult<Json<OAuthCallbackResponse>> {
+    let provider = OAuthService::provider(&provider)?;
+    let response = OAuthService::callback(
+        &app_state.db,
+        &app_state.config,
+        provider,
+        tenant.id(),
+        tenant.0.allow_registration,
+        // 代入身份的令牌不能完成绑定
+        user_claims
+            .map(|user_claims| user_claims.0.claims)
+            .filter(|claims| !claims.is_impersonated())
+            .map(|claims| claims.id),
+        query,
+        SessionClient::from_headers(&headers),
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+pub(crate) fn ensure_code_valid(check: anyhow::Result<CodeCheck>) -> ApiResult<()> {
+    match check {
+        Ok(CodeCheck::Valid) => Ok(()),
+        Ok(CodeCheck::Invalid) => Err(ApiError::BadRequest("验证码无效".to_string())
+            .with_code(ErrorCode::InvalidVerificationCode)),
+        Ok(CodeCheck::Exhausted) => Err(ApiError::BadRequest(
+            "验证码错误次数过多，请重新获取".to_string(),
+        )
+        .with_code(ErrorCode::VerificationCodeExhausted)),
+        Err(e) => {
+            tracing::error!("验证码服务不可用: {}", e);
+            Err(ApiError::ServiceUnavailable("验证码服务不可用".to_string()))
+        }
+    }
+}
+
+/// 按新成本重新计算密码哈希，哈希在此期间被修改过（改密、重置）时不覆盖
+async fn rehash_password(
+    db: &DatabaseConnection,
+    user_id: i32,
+    old_hash: &str,
+    password: String,
+    cost: u32,
+) -> ApiResult<()> {
+    let hashed_password = PasswordService::hash(password, cost).await?;
+    users::Entity::update_many()
+        .col_expr(
+            users::Column::HashedPassword,
+            sea_orm::sea_query::Expr::value(hashed_password),
+        )
+        .filter(users::Column::Id.eq(user_id))
+        .filter(users::Column::HashedPassword.eq(old_hash))
+        .exec(db.as_ref())
+        .await?;
+    Ok(())
+}
+
+fn ensure_registration_open(tenant: &CurrentTenant) -> ApiResult<()> {
+    if tenant.0.allow_registration {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden("当前站点未开放注册".to_string())
+            .with_code(ErrorCode::RegistrationClosed))
+    }
+}
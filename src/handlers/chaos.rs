@@ -0,0 +1,100 @@
+use crate::{
+    errors::{ApiErrorResponse, ApiResult},
+    extract::Json,
+    middleware::{Admin, RequireSiteRole},
+    schemas::{chaos::ChaosRules, servers::SuccessResponse},
+    services::chaos::ChaosService,
+};
+
+/// 故障注入接口文档，仅在启用 `chaos` 特性时存在
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        get_chaos_rules,
+        update_chaos_rules,
+        clear_chaos_rules
+    ),
+    tags((name = "chaos", description = "Fault injection for resilience testing, only available with the chaos feature"))
+)]
+pub struct ChaosApi;
+
+/// 获取故障注入规则
+#[utoipa::path(
+    get,
+    operation_id = "get_chaos_rules",
+    path = "/v2/admin/chaos",
+    summary = "获取故障注入规则",
+    description = "仅在启用 `chaos` 特性时存在，返回本实例当前生效的规则",
+    responses(
+        (status = 200, description = "成功获取规则", body = ChaosRules),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "chaos",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_chaos_rules(_admin: RequireSiteRole<Admin>) -> ApiResult<Json<ChaosRules>> {
+    Ok(Json(ChaosService::list()))
+}
+
+/// 替换故障注入规则
+#[utoipa::path(
+    put,
+    operation_id = "update_chaos_rules",
+    path = "/v2/admin/chaos",
+    summary = "替换故障注入规则",
+    description = "整体替换本实例的规则，立即生效。`/v2/admin/chaos` 本身以及健康检查、指标接口不会被注入故障",
+    request_body(content = ChaosRules, content_type = "application/json"),
+    responses(
+        (status = 200, description = "保存成功", body = ChaosRules),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "percentage 需在 0~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "chaos",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_chaos_rules(
+    _admin: RequireSiteRole<Admin>,
+    Json(request): Json<ChaosRules>,
+) -> ApiResult<Json<ChaosRules>> {
+    Ok(Json(ChaosService::replace(request)?))
+}
+
+/// 清空故障注入规则
+#[utoipa::path(
+    delete,
+    operation_id = "clear_chaos_rules",
+    path = "/v2/admin/chaos",
+    summary = "清空故障注入规则",
+    responses(
+        (status = 200, description = "已清空", body = SuccessResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "chaos",
+    security(("bearer_auth" = []))
+)]
+pub async fn clear_chaos_rules(_admin: RequireSiteRole<Admin>) -> ApiResult<Json<SuccessResponse>> {
+    ChaosService::clear();
+    Ok(Json(SuccessResponse {
+        message: "故障注入规则已清空".to_string(),
+    }))
+}
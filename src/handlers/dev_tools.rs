@@ -0,0 +1,55 @@
+use axum::extract::State;
+use validator::Validate;
+
+use crate::{
+    errors::{ApiError, ApiErrorResponse, ApiResult},
+    extract::Json,
+    middleware::{Admin, RequireSiteRole},
+    schemas::dev_tools::{SeedRequest, SeedSummary},
+    services::dev_tools::SyntheticDataService,
+    AppState,
+};
+
+/// 开发工具接口文档，仅在启用 `dev-tools` 特性时存在
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        seed
+    ),
+    tags((name = "dev", description = "Development tools, only available with the dev-tools feature"))
+)]
+pub struct DevToolsApi;
+
+/// 生成合成压测数据
+#[utoipa::path(
+    post,
+    operation_id = "dev_seed",
+    path = "/v2/dev/seed",
+    summary = "生成合成压测数据",
+    description = "仅在启用 `dev-tools` 特性时存在，批量生成服务器、历史状态与相册占位图；需要管理员权限",
+    request_body(content = SeedRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "生成成功", body = SeedSummary),
+        (status = 400, description = "请求参数错误", body = ApiErrorResponse),
+        (
+            status = 403,
+            description = "需要管理员权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要管理员权限", "code": "ADMIN_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "dev",
+    security(("bearer_auth" = []))
+)]
+pub async fn seed(
+    _admin: RequireSiteRole<Admin>,
+    State(app_state): State<AppState>,
+    Json(request): Json<SeedRequest>,
+) -> ApiResult<Json<SeedSummary>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let summary = SyntheticDataService::generate(&app_state.db, &request).await?;
+    Ok(Json(summary))
+}
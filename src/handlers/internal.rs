@@ -0,0 +1,242 @@
+use axum::extract::{Path, State};
+
+use crate::{
+    errors::{ApiErrorResponse, ApiResult},
+    extract::Json,
+    middleware::InternalCaller,
+    schemas::{
+        internal::{
+            ConfirmLinkRequest, EmailEventsRequest, EmailEventsResponse, LinkedAccount,
+            StatsBatchRequest, StatsBatchResponse,
+        },
+        servers::SuccessResponse,
+    },
+    services::{
+        account_link::AccountLinkService, email::suppression::EmailSuppressionService,
+        server::ServerService,
+    },
+    AppState,
+};
+
+/// 内部接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        ingest_stats_batch,
+        confirm_link,
+        get_linked_account,
+        revoke_linked_account,
+        ingest_email_events
+    ),
+    tags((name = "internal", description = "Internal endpoints for worker processes"))
+)]
+pub struct InternalApi;
+
+/// 批量上报服务器状态
+#[utoipa::path(
+    post,
+    operation_id = "internal_ingest_stats_batch",
+    path = "/v2/internal/stats/batch",
+    summary = "批量上报服务器状态",
+    description = "供外部采集器使用，一次请求上报多个服务器的状态，单次最多 2000 条；请求需携带 `X-Internal-Token` 头。\
+                   服务器不存在、采集时间晚于当前时间或数值为负的条目被拒绝，其余条目写入后清除相关缓存并向实时推送的订阅者发送状态刷新事件",
+    request_body(content = StatsBatchRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "上报完成，返回写入与拒绝的条目", body = StatsBatchResponse),
+        (
+            status = 400,
+            description = "请求数据不合法",
+            body = ApiErrorResponse,
+            example = json!({"error": "单次最多上报 2000 条状态数据", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "内部访问令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部访问令牌无效", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "内部接口未启用",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部接口未启用", "code": "FORBIDDEN", "status": 403})
+        )
+    ),
+    tag = "internal",
+    security(("internal_token" = []))
+)]
+pub async fn ingest_stats_batch(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+    Json(request): Json<StatsBatchRequest>,
+) -> ApiResult<Json<StatsBatchResponse>> {
+    let result = ServerService::ingest_stats_batch(&app_state.db, request.items).await?;
+    Ok(Json(result))
+}
+
+/// 确认外部账户绑定
+#[utoipa::path(
+    post,
+    operation_id = "internal_confirm_link",
+    path = "/v2/internal/links/confirm",
+    summary = "确认外部账户绑定",
+    description = "供论坛等第一方系统使用：提交用户在本站获取的绑定凭证与外部账户 ID，确认后返回对应的本站用户；请求需携带 `X-Internal-Token` 头",
+    request_body(content = ConfirmLinkRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "绑定成功", body = LinkedAccount),
+        (
+            status = 400,
+            description = "绑定凭证无效或已过期",
+            body = ApiErrorResponse,
+            example = json!({"error": "绑定凭证无效或已过期", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "内部访问令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部访问令牌无效", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "内部接口未启用",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部接口未启用", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 409,
+            description = "该外部账户已绑定其他用户",
+            body = ApiErrorResponse,
+            example = json!({"error": "该外部账户已绑定其他用户", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "internal",
+    security(("internal_token" = []))
+)]
+pub async fn confirm_link(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+    Json(request): Json<ConfirmLinkRequest>,
+) -> ApiResult<Json<LinkedAccount>> {
+    let result = AccountLinkService::confirm(
+        &app_state.db,
+        &app_state.config.account_link,
+        &request.provider,
+        &request.external_id,
+        &request.proof_token,
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+/// 查询外部账户绑定的用户
+#[utoipa::path(
+    get,
+    operation_id = "internal_get_linked_account",
+    path = "/v2/internal/links/{provider}/{external_id}",
+    summary = "查询外部账户绑定的用户",
+    description = "根据外部平台的账户 ID 查找已绑定的本站用户；请求需携带 `X-Internal-Token` 头",
+    responses(
+        (status = 200, description = "成功获取绑定用户", body = LinkedAccount),
+        (
+            status = 401,
+            description = "内部访问令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部访问令牌无效", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 404,
+            description = "未找到绑定的账户",
+            body = ApiErrorResponse,
+            example = json!({"error": "未找到绑定的账户", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "internal",
+    params(
+        ("provider" = String, Path, description = "外部平台标识"),
+        ("external_id" = String, Path, description = "外部平台的账户 ID")
+    ),
+    security(("internal_token" = []))
+)]
+pub async fn get_linked_account(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+    Path((provider, external_id)): Path<(String, String)>,
+) -> ApiResult<Json<LinkedAccount>> {
+    let result = AccountLinkService::find_linked(&app_state.db, &provider, &external_id).await?;
+    Ok(Json(result))
+}
+
+/// 外部平台解除绑定
+#[utoipa::path(
+    delete,
+    operation_id = "internal_revoke_linked_account",
+    path = "/v2/internal/links/{provider}/{external_id}",
+    summary = "外部平台解除绑定",
+    description = "外部账户注销或在外部平台解除绑定时调用；请求需携带 `X-Internal-Token` 头",
+    responses(
+        (status = 200, description = "已解除绑定", body = SuccessResponse),
+        (
+            status = 401,
+            description = "内部访问令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部访问令牌无效", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 404,
+            description = "未找到绑定的账户",
+            body = ApiErrorResponse,
+            example = json!({"error": "未找到绑定的账户", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "internal",
+    params(
+        ("provider" = String, Path, description = "外部平台标识"),
+        ("external_id" = String, Path, description = "外部平台的账户 ID")
+    ),
+    security(("internal_token" = []))
+)]
+pub async fn revoke_linked_account(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+    Path((provider, external_id)): Path<(String, String)>,
+) -> ApiResult<Json<SuccessResponse>> {
+    AccountLinkService::revoke_external(&app_state.db, &provider, &external_id).await?;
+    Ok(Json(SuccessResponse {
+        message: "已解除绑定".to_string(),
+    }))
+}
+
+/// 邮件退信与投诉回调
+#[utoipa::path(
+    post,
+    operation_id = "internal_ingest_email_events",
+    path = "/v2/internal/email/events",
+    summary = "邮件退信与投诉回调",
+    description = "供 SMTP 服务商推送退信与投诉事件：永久退信和投诉会把地址标记为无法投递，之后不再向其发送验证码与通知邮件，临时退信会被忽略；请求需携带 `X-Internal-Token` 头",
+    request_body(content = EmailEventsRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "处理完成", body = EmailEventsResponse),
+        (
+            status = 401,
+            description = "内部访问令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部访问令牌无效", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "内部接口未启用",
+            body = ApiErrorResponse,
+            example = json!({"error": "内部接口未启用", "code": "FORBIDDEN", "status": 403})
+        )
+    ),
+    tag = "internal",
+    security(("internal_token" = []))
+)]
+pub async fn ingest_email_events(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+    Json(request): Json<EmailEventsRequest>,
+) -> ApiResult<Json<EmailEventsResponse>> {
+    let result = EmailSuppressionService::record(&app_state.db, request.events).await?;
+    Ok(Json(result))
+}
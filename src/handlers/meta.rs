@@ -0,0 +1,136 @@
+use axum::{
+    extract::State,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::DateTime;
+
+use crate::{
+    errors::ApiResult,
+    extract::Json,
+    middleware::{CurrentTenant, ReadDb},
+    schemas::meta::{ComponentState, HealthReport, StatusPageResponse, VersionInfo},
+    services::{health::HealthService, sitemap::SitemapService, status::StatusService},
+    AppState,
+};
+
+/// 服务元数据接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        get_version,
+        health_live,
+        health_ready,
+        get_status,
+        get_sitemap
+    ),
+    tags((name = "meta", description = "Service metadata endpoints"))
+)]
+pub struct MetaApi;
+
+/// 当前支持的 API 版本
+const API_VERSIONS: &[&str] = &["v2"];
+
+#[utoipa::path(
+    get,
+    operation_id = "get_version",
+    summary = "获取服务版本信息",
+    description = "返回构建版本、提交号、构建时间、启用的特性与支持的 API 版本",
+    path = "/v2/meta/version",
+    tag = "meta",
+    responses(
+        (status = 200, description = "版本信息", body = VersionInfo),
+    )
+)]
+pub async fn get_version() -> Json<VersionInfo> {
+    Json(VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: env!("BUILD_GIT_COMMIT").to_string(),
+        build_time: env!("BUILD_TIMESTAMP")
+            .parse()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+        features: env!("BUILD_FEATURES")
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        api_versions: API_VERSIONS.iter().map(|v| v.to_string()).collect(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "health_live",
+    summary = "存活检查",
+    description = "进程能处理请求即返回 200，不检查任何依赖，供编排系统的存活探针使用",
+    path = "/health/live",
+    tag = "meta",
+    responses(
+        (status = 200, description = "进程存活", body = String, example = "OK"),
+    )
+)]
+pub async fn health_live() -> &'static str {
+    "OK"
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "health_ready",
+    summary = "就绪检查",
+    description = "实时检查数据库（`SELECT 1`）、Redis、搜索引擎与对象存储并返回各自耗时，供编排系统的就绪探针使用。数据库不可用时返回 503；其他依赖不可用时整体状态为 `degraded`，仍返回 200。`/health` 与本接口相同",
+    path = "/health/ready",
+    tag = "meta",
+    responses(
+        (status = 200, description = "实例可以接收流量", body = HealthReport),
+        (status = 503, description = "关键依赖不可用", body = HealthReport),
+    )
+)]
+pub async fn health_ready(State(app_state): State<AppState>) -> Response {
+    let report = HealthService::readiness(&app_state.db, app_state.storage.as_deref()).await;
+    let status = if report.status == ComponentState::Outage {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(report)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "get_status",
+    summary = "获取服务状态",
+    description = "供状态页使用：返回本实例最近 5 分钟与 1 小时的请求错误率、数据库/Redis/搜索引擎的健康采样记录，对象存储与发信服务等可选功能是否已配置，以及管理员发布的进行中故障。结果缓存 10 秒",
+    path = "/v2/meta/status",
+    tag = "meta",
+    responses(
+        (status = 200, description = "服务状态", body = StatusPageResponse),
+    )
+)]
+pub async fn get_status(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+) -> ApiResult<Json<StatusPageResponse>> {
+    let page = StatusService::status_page(&db, &app_state.config).await?;
+    Ok(Json(page))
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "get_sitemap",
+    summary = "获取站点地图",
+    description = "返回当前站点的 sitemap.xml，收录可见性不是 `hidden` 且未停用的服务器详情页",
+    path = "/v2/meta/sitemap.xml",
+    tag = "meta",
+    responses(
+        (status = 200, description = "站点地图", body = String, content_type = "application/xml"),
+    )
+)]
+pub async fn get_sitemap(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+) -> ApiResult<Response> {
+    let xml = SitemapService::render(&db, &app_state.config.sitemap, &tenant.0).await?;
+    Ok(([(CONTENT_TYPE, "application/xml; charset=utf-8")], xml).into_response())
+}
@@ -0,0 +1,28 @@
+use axum::{extract::State, http::header::CONTENT_TYPE, response::IntoResponse};
+
+use crate::{
+    middleware::InternalCaller,
+    services::{
+        database::{pool_status, record_pool_metrics},
+        metrics::MetricsService,
+    },
+    AppState,
+};
+
+/// 以 Prometheus 文本格式导出运行指标
+///
+/// 指标包含连接池、路由与依赖的运行状况，与内部接口一样需要携带 `X-Internal-Token` 头。
+pub async fn metrics(
+    _caller: InternalCaller,
+    State(app_state): State<AppState>,
+) -> impl IntoResponse {
+    // 导出前刷新一次连接池状态，避免采样间隔内的数据过旧
+    if let Some(status) = pool_status(&app_state.db) {
+        record_pool_metrics(&status);
+    }
+
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        MetricsService::render(),
+    )
+}
@@ -1,3 +1,15 @@
 pub mod auth;
 pub mod servers;
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod sandbox;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(feature = "dev-tools")]
+pub mod dev_tools;
+pub mod metrics;
+pub mod internal;
+pub mod admin;
+pub mod meta;
+pub mod users;
+pub mod storage;
+pub mod tags;
\ No newline at end of file
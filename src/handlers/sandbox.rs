@@ -0,0 +1,240 @@
+use axum::extract::{OriginalUri, Path};
+use axum_typed_multipart::TypedMultipart;
+use validator::Validate;
+
+use crate::{
+    errors::{ApiError, ApiErrorResponse, ApiResult},
+    extract::{Json, Query},
+    handlers::servers::ListQuery,
+    schemas::{
+        auth::UserRegisterByEmailData,
+        pagination::{Page, Paginated},
+        search::{SearchParams, SearchResponse},
+        servers::{
+            ServerDetail, ServerGallery, ServerManagersResponse, ServerTotalPlayers,
+            SuccessResponse, UpdateServerRequest,
+        },
+    },
+    services::sandbox::{SandboxService, SANDBOX_EMAIL_CODE},
+};
+
+/// 沙盒接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        list_servers,
+        get_server_detail,
+        update_server,
+        get_server_managers,
+        get_server_gallery,
+        get_total_players,
+        search_server,
+        register_email_code
+    ),
+    tags((name = "sandbox", description = "Sandbox endpoints with fixed fixtures and no side effects"))
+)]
+pub struct SandboxApi;
+
+/// 获取沙盒服务器列表
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/servers",
+    operation_id = "sandbox_list_servers",
+    summary = "获取沙盒服务器列表",
+    description = "与 `/v2/servers` 行为一致，但返回固定的示例数据；未指定 seed 时使用固定种子",
+    responses(
+        (status = 200, description = "成功获取服务器列表", body = Paginated<ServerDetail>),
+        (
+            status = 400,
+            description = "请求参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 与 page_size 不能小于 1", "code": "BAD_REQUEST", "status": 400})
+        )
+    ),
+    tag = "sandbox",
+    params(ListQuery)
+)]
+pub async fn list_servers(
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Page<Paginated<ServerDetail>>> {
+    if query.page < 1 || query.page_size < 1 {
+        return Err(ApiError::BadRequest(
+            "page 与 page_size 不能小于 1".to_string(),
+        ));
+    }
+
+    let result = SandboxService::list_servers(&query);
+    Ok(Paginated::new(
+        result.data,
+        query.page,
+        query.page_size,
+        result.total as u64,
+    )
+    .with_links(&uri))
+}
+
+/// 获取沙盒服务器详情
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/servers/{server_id}",
+    operation_id = "sandbox_get_server_detail",
+    summary = "获取沙盒服务器详情",
+    responses(
+        (status = 200, description = "成功获取服务器详细信息", body = ServerDetail),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "sandbox",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn get_server_detail(Path(server_id): Path<i32>) -> ApiResult<Json<ServerDetail>> {
+    Ok(Json(SandboxService::get_server_detail(server_id)?))
+}
+
+/// 模拟更新沙盒服务器
+#[utoipa::path(
+    put,
+    path = "/v2/sandbox/servers/{server_id}",
+    operation_id = "sandbox_update_server",
+    summary = "模拟更新沙盒服务器",
+    description = "执行与正式接口相同的参数校验并返回更新后的结果，但不会持久化，也不会上传封面",
+    request_body(content = UpdateServerRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "校验通过，返回模拟更新结果", body = ServerDetail),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "参数验证失败", "code": "VALIDATION_FAILED", "status": 400})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "sandbox",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn update_server(
+    Path(server_id): Path<i32>,
+    TypedMultipart(update_data): TypedMultipart<UpdateServerRequest>,
+) -> ApiResult<Json<ServerDetail>> {
+    Ok(Json(SandboxService::update_server(server_id, update_data)?))
+}
+
+/// 获取沙盒服务器管理员列表
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/servers/{server_id}/managers",
+    operation_id = "sandbox_get_server_managers",
+    summary = "获取沙盒服务器管理员列表",
+    responses(
+        (status = 200, description = "成功获取服务器管理员列表", body = ServerManagersResponse),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "sandbox",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn get_server_managers(
+    Path(server_id): Path<i32>,
+) -> ApiResult<Json<ServerManagersResponse>> {
+    Ok(Json(SandboxService::get_server_managers(server_id)?))
+}
+
+/// 获取沙盒服务器相册
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/servers/{server_id}/gallery",
+    operation_id = "sandbox_get_server_gallery",
+    summary = "获取沙盒服务器相册",
+    responses(
+        (status = 200, description = "成功获取服务器相册", body = ServerGallery),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "sandbox",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn get_server_gallery(Path(server_id): Path<i32>) -> ApiResult<Json<ServerGallery>> {
+    Ok(Json(SandboxService::get_server_gallery(server_id)?))
+}
+
+/// 获取沙盒服务器玩家总数
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/servers/players",
+    operation_id = "sandbox_get_total_players",
+    summary = "获取沙盒服务器玩家总数",
+    responses(
+        (status = 200, description = "成功获取所有服务器玩家总数", body = ServerTotalPlayers)
+    ),
+    tag = "sandbox"
+)]
+pub async fn get_total_players() -> Json<ServerTotalPlayers> {
+    Json(SandboxService::total_players())
+}
+
+/// 搜索沙盒服务器
+#[utoipa::path(
+    get,
+    path = "/v2/sandbox/search",
+    operation_id = "sandbox_search_server",
+    summary = "搜索沙盒服务器",
+    description = "在固定数据中按名称与简介进行关键词匹配",
+    responses(
+        (status = 200, description = "搜索结果", body = SearchResponse)
+    ),
+    tag = "sandbox",
+    params(SearchParams)
+)]
+pub async fn search_server(
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<SearchParams>,
+) -> Page<SearchResponse> {
+    let results = SandboxService::search_servers(&params);
+    Page::new(results.page.links(&uri), results)
+}
+
+/// 模拟发送邮箱验证码
+#[utoipa::path(
+    post,
+    path = "/v2/sandbox/auth/register/email-code",
+    operation_id = "sandbox_register_email_code",
+    summary = "模拟发送邮箱验证码",
+    description = "仅校验请求数据，不会发送邮件；沙盒中验证码固定为 `123456`",
+    responses(
+        (status = 200, description = "模拟发送成功", body = SuccessResponse),
+        (status = 400, description = "请求数据不合法", body = ApiErrorResponse)
+    ),
+    tag = "sandbox"
+)]
+pub async fn register_email_code(
+    Json(user_data): Json<UserRegisterByEmailData>,
+) -> ApiResult<Json<SuccessResponse>> {
+    if user_data.validate().is_err() {
+        return Err(ApiError::BadRequest("请求数据不合法".to_string()));
+    }
+
+    Ok(Json(SuccessResponse {
+        message: format!(
+            "沙盒模式未发送邮件，{} 的验证码为 {}",
+            user_data.email, SANDBOX_EMAIL_CODE
+        ),
+    }))
+}
@@ -1,28 +1,186 @@
-use axum::{
-    extract::{Query},
-    Json,
-};
+use axum::extract::{Extension, OriginalUri, State};
+
 use crate::{
-    errors::ApiResult,
-    schemas::search::{SearchParams, SearchResponse},
-    services::search::client::MeilisearchClient,
+    errors::{ApiError, ApiErrorResponse, ApiResult, RateLimitedErrorResponse},
+    extract::{Json, Query},
+    middleware::{CurrentTenant, ReadDb},
+    schemas::pagination::Page,
+    schemas::search::{
+        PlayerSearchQuery, PlayerSearchResponse, SearchFacetsResponse, SearchParams,
+        SearchResponse, SuggestQuery, SuggestResponse,
+    },
+    schemas::servers::GameVersion,
+    services::{
+        auth::Claims,
+        cache::ServerCacheService,
+        feature_flags::{FeatureFlagService, FLAG_SEARCH_RANKING_V2},
+        player_index::PlayerIndexService,
+        search::client::MeilisearchClient,
+    },
+    AppState,
 };
 
+/// 搜索接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        search_server,
+        search_facets,
+        search_suggest,
+        search_players
+    ),
+    components(schemas(
+        SearchParams,
+        crate::schemas::search::SearchFilters,
+        crate::schemas::search::ServerResult
+    )),
+    tags((name = "search", description = "Server and player search endpoints"))
+)]
+pub struct SearchApi;
+
 #[utoipa::path(
     get,
+    operation_id = "search_server",
     summary = "搜索服务器",
     path = "/v2/search",
     tag = "search",
     responses(
-        (status = 200, description = "搜索结果", body = SearchResponse),
+        (status = 200, description = "搜索结果，`Link` 响应头中给出相邻页的链接", body = SearchResponse),
+        (status = 400, description = "过滤参数无效", body = ApiErrorResponse,
+         example = json!({"error": "无效的游戏版本号: \"1.x\"", "code": "BAD_REQUEST", "status": 400})),
+        (status = 429, description = "搜索过于频繁", body = RateLimitedErrorResponse),
     ),
     params(
         SearchParams
     )
 )]
-pub async fn search_server(Query(params): Query<SearchParams>) -> ApiResult<Json<SearchResponse>> {
-    // 构建搜索查询
-    let results = MeilisearchClient::search_servers(Query(params)).await?;
+pub async fn search_server(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    user_claims: Option<Extension<Claims>>,
+    OriginalUri(uri): OriginalUri,
+    Query(mut params): Query<SearchParams>,
+) -> ApiResult<Page<SearchResponse>> {
+    // 新版排序：未指定排序时成员服务器优先
+    if params.sort.is_none() {
+        let user_id = user_claims.map(|Extension(claims)| claims.id);
+        if FeatureFlagService::is_enabled(&app_state.db, FLAG_SEARCH_RANKING_V2, user_id).await {
+            params.sort = Some("member_first".to_string());
+        }
+    }
 
+    ensure_valid_filters(&params)?;
+    // 构建搜索查询，每个租户使用各自的索引
+    let results = MeilisearchClient::search_servers(params, &tenant.0.search_index).await?;
+
+    Ok(Page::new(results.page.links(&uri), results))
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "search_facets",
+    summary = "搜索分面统计",
+    description = "返回符合关键词与过滤条件的服务器在标签、类型、认证模式与版本上的数量分布，用于筛选侧栏显示各选项的服务器数。参数与 `/v2/search` 相同，分页与排序参数被忽略。",
+    path = "/v2/search/facets",
+    tag = "search",
+    responses(
+        (status = 200, description = "分面统计", body = SearchFacetsResponse),
+        (status = 400, description = "过滤参数无效", body = ApiErrorResponse,
+         example = json!({"error": "无效的游戏版本号: \"1.x\"", "code": "BAD_REQUEST", "status": 400})),
+        (status = 429, description = "搜索过于频繁", body = RateLimitedErrorResponse),
+    ),
+    params(
+        SearchParams
+    )
+)]
+pub async fn search_facets(
+    tenant: CurrentTenant,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Json<SearchFacetsResponse>> {
+    ensure_valid_filters(&params)?;
+    let results = MeilisearchClient::search_facets(params, &tenant.0.search_index).await?;
     Ok(Json(results))
 }
+
+/// 过滤参数格式错误时返回 400，而不是在构建 Meilisearch 查询时失败
+fn ensure_valid_filters(params: &SearchParams) -> ApiResult<()> {
+    match params.compatible_with.as_deref() {
+        Some(value) if GameVersion::parse(value).is_none() => {
+            Err(ApiError::BadRequest(format!("无效的游戏版本号: {value:?}")))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 搜索建议关键词的最大字符数
+const SUGGEST_MAX_QUERY_CHARS: usize = 50;
+/// 搜索建议默认返回数量
+const SUGGEST_DEFAULT_LIMIT: u32 = 5;
+/// 搜索建议最大返回数量
+const SUGGEST_MAX_LIMIT: u32 = 10;
+
+#[utoipa::path(
+    get,
+    operation_id = "search_suggest",
+    summary = "搜索建议",
+    description = "输入联想：按名称与短链接匹配服务器，最后一个词按前缀匹配并容许拼写错误，只返回 ID、名称、短链接与图标。结果按关键词缓存 5 分钟，不受搜索令牌桶限流。",
+    path = "/v2/search/suggest",
+    tag = "search",
+    params(SuggestQuery),
+    responses(
+        (status = 200, description = "搜索建议", body = SuggestResponse),
+        (status = 400, description = "关键词过长", body = ApiErrorResponse),
+    )
+)]
+pub async fn search_suggest(
+    tenant: CurrentTenant,
+    Query(query): Query<SuggestQuery>,
+) -> ApiResult<Json<SuggestResponse>> {
+    let q = query.q.trim().to_lowercase();
+    if q.chars().count() > SUGGEST_MAX_QUERY_CHARS {
+        return Err(ApiError::BadRequest(format!(
+            "关键词不能超过 {SUGGEST_MAX_QUERY_CHARS} 个字符"
+        )));
+    }
+    if q.is_empty() {
+        return Ok(Json(SuggestResponse {
+            suggestions: Vec::new(),
+        }));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(SUGGEST_DEFAULT_LIMIT)
+        .clamp(1, SUGGEST_MAX_LIMIT) as usize;
+
+    if let Some(cached) = ServerCacheService::get_suggestions(tenant.id(), &q, limit).await {
+        return Ok(Json(cached));
+    }
+    let suggestions = MeilisearchClient::suggest(&q, limit, &tenant.0.search_index).await?;
+    let response = SuggestResponse { suggestions };
+    ServerCacheService::set_suggestions(tenant.id(), &q, limit, &response).await;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "search_players",
+    summary = "搜索玩家所在的服务器",
+    description = "按完整玩家名称（不区分大小写）查询该玩家当前在哪些服务器在线，数据来自服务器推送的在线玩家样本。已退出玩家搜索、隐藏或停用的服务器不会出现在结果中。",
+    path = "/v2/search/players",
+    tag = "search",
+    params(PlayerSearchQuery),
+    responses(
+        (status = 200, description = "玩家当前所在的服务器", body = PlayerSearchResponse),
+        (status = 400, description = "玩家名称格式无效", body = ApiErrorResponse),
+        (status = 429, description = "搜索过于频繁", body = RateLimitedErrorResponse),
+        (status = 503, description = "玩家索引不可用", body = ApiErrorResponse),
+    )
+)]
+pub async fn search_players(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Query(query): Query<PlayerSearchQuery>,
+) -> ApiResult<Json<PlayerSearchResponse>> {
+    let result = PlayerIndexService::search(&db, tenant.id(), &query.name).await?;
+    Ok(Json(result))
+}
@@ -1,19 +1,134 @@
 use crate::{
-    errors::{ApiError, ApiErrorResponse, ApiResult},
+    errors::{ApiError, ApiErrorResponse, ApiResult, ErrorCode, RateLimitedErrorResponse},
+    extract::{Json, Query},
+    middleware::{Admin, CurrentTenant, Owner, ReadDb, RequireServerRole},
+    schemas::confirm::{ConfirmQuery, ConfirmationRequired, DestructiveAction},
+    schemas::pagination::{Page, Paginated},
     schemas::servers::{
-        GalleryImageRequest, GalleryImageSchema, ServerDetail, ServerGallery, ServerListResponse,
-        ServerManagersResponse, ServerTotalPlayers, SuccessResponse, UpdateServerRequest,
+        AddManagerRequest, CreateServerRequest, CustomFieldListResponse, DescRender,
+        GalleryBatchDeleteQuery, GalleryFeedQuery, GalleryImage, GalleryImageRequest,
+        GalleryImageSchema, LiveUpdatesQuery, MotdImageQuery, PushSecretResponse,
+        ReorderGalleryRequest, ServerDetail, ServerGallery, ServerManagersResponse,
+        ServerRevisionListResponse, ServerStats, ServerSummary, ServerTimelineQuery,
+        ServerTimelineResponse, ServerTotalPlayers, ServerUptimeResponse, SimilarServersQuery,
+        SimilarServersResponse, StatsHistoryQuery, StatsHistoryResponse, SuccessResponse,
+        TagSuggestRequest, TagSuggestionResponse, UpdateCustomFieldsRequest,
+        UpdateGalleryImageRequest, UpdateManagerRequest, UpdateServerRequest,
+        UploadServerIconRequest,
+    },
+    schemas::users::ActivityAction,
+    services::{
+        activity::{ActivityService, TARGET_SERVER},
+        auth::Claims,
+        cache::ServerCacheService,
+        confirm::ConfirmationService,
+        custom_fields::CustomFieldService,
+        database::DatabaseConnection,
+        favorite::FavoriteService,
+        feature_flags::{FeatureFlagService, FLAG_SERVER_DETAIL_V2, FLAG_SIMILAR_EMBEDDINGS},
+        feed::GalleryFeedService,
+        live::{LiveUpdate, LiveUpdateService},
+        markdown::MarkdownService,
+        motd_image::MotdImageService,
+        revision::ServerRevisionService,
+        server::{ServerDetailView, ServerService},
+        similar::SimilarServerService,
+        stats_history::StatsHistoryService,
+        storage,
+        tag_suggest::TagSuggestionService,
+        timeline::ServerTimelineService,
+        uptime::ServerUptimeService,
     },
-    services::{auth::Claims, server::ServerService},
     AppState,
 };
 use axum::{
-    extract::{Extension, Path, Query, State},
-    Json,
+    body::Bytes,
+    extract::{Extension, OriginalUri, Path, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, LOCATION},
+        HeaderMap, StatusCode,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
 };
 use axum_typed_multipart::TypedMultipart;
+use futures_util::stream::{self, Stream};
 use serde::Deserialize;
 
+/// 服务器相关接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        list_servers,
+        get_server_detail,
+        get_server_detail_by_slug,
+        get_server_summary,
+        create_server,
+        update_server,
+        get_server_managers,
+        add_server_manager,
+        update_server_manager,
+        remove_server_manager,
+        transfer_server_ownership,
+        get_server_gallery,
+        get_gallery_feed,
+        upload_gallery_image,
+        delete_gallery_image,
+        update_gallery_image,
+        get_server_cover,
+        delete_server_cover,
+        get_server_motd_image,
+        upload_server_icon,
+        delete_server_icon,
+        reorder_gallery_images,
+        delete_server,
+        delete_gallery_images,
+        get_total_players,
+        push_server_stats,
+        rotate_push_secret,
+        update_custom_fields,
+        list_server_revisions,
+        rollback_server_revision,
+        submit_server_for_review,
+        archive_server,
+        suggest_server_tags,
+        get_similar_servers,
+        get_server_timeline,
+        get_server_uptime,
+        get_server_stats_history,
+        favorite_server,
+        unfavorite_server,
+        live_updates
+    ),
+    components(schemas(
+        ListQuery,
+        ServerDetailQuery,
+        LiveUpdatesQuery,
+        GalleryFeedQuery,
+        MotdImageQuery,
+        crate::schemas::servers::MotdTheme,
+        GalleryBatchDeleteQuery,
+        StatsHistoryQuery,
+        ServerTimelineQuery,
+        SimilarServersQuery,
+        DescRender,
+        crate::schemas::servers::FeedFormat,
+        ConfirmQuery,
+        crate::entities::server::AuthModeEnum,
+        crate::entities::server::ServerTypeEnum
+    )),
+    tags((name = "servers", description = "Server management endpoints"))
+)]
+pub struct ServersApi;
+
+/// 封面重定向的缓存时长，封面更换后最多延迟这么久生效
+const COVER_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// MOTD 图片的客户端缓存时长，与状态上报的间隔相当
+const MOTD_IMAGE_CACHE_CONTROL: &str = "public, max-age=60";
+
 fn default_is_member() -> bool {
     true
 }
@@ -54,25 +169,34 @@ pub struct ListQuery {
     #[schema(example = 114514, default = 114514)]
     #[serde(default)]
     pub seed: Option<i64>,
+    /// 只返回兼容该游戏版本的服务器：版本范围包含该版本，未设置版本范围的服务器按 `version` 精确匹配
+    #[schema(example = "1.20.1")]
+    #[serde(default)]
+    pub compatible_with: Option<String>,
 }
 
 #[derive(Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
 pub struct ServerDetailQuery {
-    /// 是否返回完整信息(需要登录)
+    /// 是否附带管理信息（`private` 字段），需要登录且是该服务器成员
     #[schema(example = false, default = false)]
     #[serde(default)]
     pub full_info: Option<bool>,
+    /// 描述的返回格式，`html` 时额外返回渲染后的 `desc_html`
+    #[schema(example = "markdown", default = "markdown")]
+    #[serde(default)]
+    pub render: DescRender,
 }
 
 /// 获取服务器列表
 #[utoipa::path(
     get,
+    operation_id = "list_servers",
     path = "/v2/servers",
     responses(
         (
             status = 200,
-            description = "成功获取服务器列表",
-            body = ServerListResponse,
+            description = "成功获取服务器列表，`Link` 响应头中给出相邻页的链接",
+            body = Paginated<ServerDetail>,
         ),
         (
             status = 400,
@@ -80,6 +204,7 @@ pub struct ServerDetailQuery {
             body = ApiErrorResponse,
             example = json!({
              "error": "page 与 page_size 不能小于 1",
+             "code": "BAD_REQUEST",
              "status": 400
          }),
         )
@@ -92,54 +217,92 @@ pub struct ServerDetailQuery {
     )
 )]
 pub async fn list_servers(
-    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    OriginalUri(uri): OriginalUri,
     Query(query): Query<ListQuery>,
     user_claims: Option<Extension<Claims>>,
-) -> ApiResult<Json<ServerListResponse>> {
+) -> ApiResult<Page<Paginated<ServerDetail>>> {
     if query.page < 1 || query.page_size < 1 {
         return Err(ApiError::BadRequest(
             "page 与 page_size 不能小于 1".to_string(),
         ));
     }
-    let db = &app_state.db;
     let user_id = user_claims.map(|Extension(claims)| claims.id);
 
-    let result = ServerService::get_servers_with_filters(db, user_id, &query).await?;
+    let result = ServerService::get_servers_with_filters(&db, tenant.id(), user_id, &query).await?;
 
-    let total = result.total;
-    let total_pages = ((total as f64) / (query.page_size as f64)).ceil() as i64;
-
-    Ok(Json(ServerListResponse {
-        data: result.data,
-        total,
-        total_pages,
-    }))
+    Ok(Paginated::new(
+        result.data,
+        query.page,
+        query.page_size,
+        result.total as u64,
+    )
+    .with_links(&uri))
 }
 
 /// 获取特定服务器的详细信息
 #[utoipa::path(
     get,
+    operation_id = "get_server_detail",
     path = "/v2/servers/{server_id}",
+    description = "默认返回公开视图，任何调用方（包括未登录）都可以访问，登录时额外返回是否已收藏（`is_favorited`）；`full_info=true` 时额外返回 `private` 管理信息，只对该服务器的成员开放；`render=html` 时额外返回由服务端渲染并转义的描述 HTML（`desc_html`）",
     responses(
         (status = 200,
          description = "成功获取服务器详细信息",
          body = ServerDetail,
+         examples(
+             ("public" = (
+                 summary = "公开视图",
+                 value = json!({
+                     "id": 1, "name": "我的世界服务器", "ip": "mc.example.com:25565", "type": "JAVA",
+                     "version": "1.20.1", "desc": "一个有趣的生存服务器", "link": "https://example.com",
+                     "is_member": true, "auth_mode": "OFFICIAL", "is_hide": false, "visibility": "public",
+                     "tags": ["生存", "PVP"], "stats": null, "permission": "guest", "cover_url": null, "slug": "my-server-k3x9qa"
+                 })
+             )),
+             ("private" = (
+                 summary = "管理视图（full_info=true）",
+                 value = json!({
+                     "id": 1, "name": "我的世界服务器", "ip": null, "type": "JAVA",
+                     "version": "1.20.1", "desc": "一个有趣的生存服务器", "link": "https://example.com",
+                     "is_member": true, "auth_mode": "OFFICIAL", "is_hide": true, "visibility": "listed_without_ip",
+                     "tags": ["生存", "PVP"], "stats": null, "permission": "owner", "cover_url": null, "slug": "my-server-k3x9qa",
+                     "private": {
+                         "ip": "mc.example.com:25565", "pending_tickets": 2,
+                         "push_secret_configured": true, "slug_editable": false, "deactivated_at": null
+                     }
+                 })
+             ))
+         )
         ),
         (status = 404,
          description = "服务器不存在",
          body = ApiErrorResponse,
          example = json!(serde_json::to_value(ApiErrorResponse {
              error: "服务器不存在".to_string(),
+             code: ErrorCode::ServerNotFound,
              status: 404,
          }).unwrap())
         ),
         (status = 401,
-         description = "未登录或无权限访问",
+         description = "请求管理信息但未登录",
          body = ApiErrorResponse,
          example = json!(serde_json::to_value(ApiErrorResponse {
              error: "未登录，禁止访问".to_string(),
+             code: ErrorCode::Unauthorized,
              status: 401,
          }).unwrap())
+        ),
+        (status = 403,
+         description = "请求管理信息但不是服务器成员",
+         body = ApiErrorResponse,
+         example = json!({"error": "只有服务器成员可以查看管理信息", "code": "FORBIDDEN", "status": 403})
+        ),
+        (status = 429,
+         description = "匿名访问过于频繁（顺序遍历服务器 ID）",
+         body = ApiErrorResponse,
+         example = json!({"error": "访问过于频繁，请稍后再试", "code": "TOO_MANY_REQUESTS", "status": 429})
         )
     ),
     tag = "servers",
@@ -151,24 +314,238 @@ pub async fn list_servers(
     )
 )]
 pub async fn get_server_detail(
-    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
     Path(server_id): Path<i32>,
     Query(query): Query<ServerDetailQuery>,
     user_claims: Option<Extension<Claims>>,
 ) -> ApiResult<Json<ServerDetail>> {
+    ServerService::ensure_in_tenant(&db, server_id, tenant.id()).await?;
     let user_id = user_claims.map(|Extension(claims)| claims.id);
 
-    let full_info = query.full_info.unwrap_or(false);
-    let db = &app_state.db;
+    let view = if query.full_info.unwrap_or(false) {
+        ServerDetailView::Private
+    } else {
+        ServerDetailView::Public
+    };
 
-    let result = ServerService::get_server_detail(db, user_id, server_id, full_info).await?;
+    let mut result = load_detail(&db, user_id, server_id, view).await?;
+
+    if FeatureFlagService::is_enabled(&db, FLAG_SERVER_DETAIL_V2, user_id).await {
+        match ServerService::get_server_managers(&db, server_id).await {
+            Ok(managers) => result.managers = Some(managers),
+            Err(e) => tracing::warn!("⚠️  新版详情获取管理员失败: {}", e),
+        }
+    }
+    if query.render == DescRender::Html {
+        result.desc_html = Some(MarkdownService::render_html(&result.desc));
+    }
+    if let Some(user_id) = user_id {
+        result.is_favorited = Some(FavoriteService::is_favorited(&db, user_id, server_id).await?);
+    }
 
     Ok(Json(result))
 }
 
+/// 通过短链接获取服务器详细信息
+#[utoipa::path(
+    get,
+    operation_id = "get_server_detail_by_slug",
+    path = "/v2/servers/slug/{slug}",
+    summary = "通过短链接获取服务器详细信息",
+    description = "与按 ID 获取详情的返回一致，供门户使用可读的地址",
+    responses(
+        (status = 200, description = "成功获取服务器详细信息", body = ServerDetail),
+        (status = 404,
+         description = "服务器不存在",
+         body = ApiErrorResponse,
+         example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (status = 401,
+         description = "请求管理信息但未登录",
+         body = ApiErrorResponse,
+         example = json!({"error": "未登录，禁止访问", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (status = 403,
+         description = "请求管理信息但不是服务器成员",
+         body = ApiErrorResponse,
+         example = json!({"error": "只有服务器成员可以查看管理信息", "code": "FORBIDDEN", "status": 403})
+        )
+    ),
+    tag = "servers",
+    params(("slug" = String, Path, description = "服务器短链接"),
+           ServerDetailQuery),
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_server_detail_by_slug(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(slug): Path<String>,
+    query: Query<ServerDetailQuery>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<ServerDetail>> {
+    let server_id = ServerService::find_id_by_slug(&db, tenant.id(), &slug).await?;
+    get_server_detail(ReadDb(db), tenant, Path(server_id), query, user_claims).await
+}
+
+/// 获取服务器详情，匿名访问的公开详情与调用方无关，走缓存
+async fn load_detail(
+    db: &DatabaseConnection,
+    user_id: Option<i32>,
+    server_id: i32,
+    view: ServerDetailView,
+) -> ApiResult<ServerDetail> {
+    let cacheable = user_id.is_none() && view == ServerDetailView::Public;
+    if cacheable {
+        if let Some(detail) = ServerCacheService::get_detail(server_id).await {
+            return Ok(detail);
+        }
+    }
+    let detail = ServerService::get_server_detail(db, user_id, server_id, view).await?;
+    if cacheable {
+        ServerCacheService::set_detail(&detail).await;
+    }
+    Ok(detail)
+}
+
+/// 获取服务器概要
+#[utoipa::path(
+    get,
+    operation_id = "get_server_summary",
+    path = "/v2/servers/{server_id}/summary",
+    summary = "获取服务器概要",
+    description = "一次返回详情页首屏所需的信息：名称、一句话简介、图标、登记信息（是否成员服、标签、版本范围）、Markdown 描述与相册，以及最近一次状态（MOTD、延迟、在线人数）。可见性规则与公开详情一致",
+    responses(
+        (status = 200,
+         description = "成功获取服务器概要",
+         body = ServerSummary,
+         example = json!({
+             "id": 1, "name": "我的世界服务器", "intro": "一个有趣的生存服务器", "icon": null,
+             "registration": {"is_member": true, "tags": ["生存", "PVP"], "version": "1.20.1",
+                              "version_range": {"min": "1.8", "max": "1.20"}},
+             "desc": "一个有趣的生存服务器\n\n## 玩法\n- 生存", "gallery": [],
+             "ping": {"motd": {"plain": "欢迎", "html": "<span>欢迎</span>", "minecraft": "欢迎", "ansi": "欢迎"},
+                      "delay": 50.5, "online_players": 10, "max_players": 100}
+         })
+        ),
+        (status = 404,
+         description = "服务器不存在",
+         body = ApiErrorResponse,
+         example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (status = 429,
+         description = "匿名访问过于频繁（顺序遍历服务器 ID）",
+         body = ApiErrorResponse,
+         example = json!({"error": "访问过于频繁，请稍后再试", "code": "TOO_MANY_REQUESTS", "status": 429})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_server_summary(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<ServerSummary>> {
+    ServerService::ensure_in_tenant(&db, server_id, tenant.id()).await?;
+    let user_id = user_claims.map(|Extension(claims)| claims.id);
+
+    let (detail, gallery) = tokio::try_join!(
+        load_detail(&db, user_id, server_id, ServerDetailView::Public),
+        ServerService::get_server_gallery(&db, server_id),
+    )?;
+    let mut summary = ServerService::summary_of(detail, gallery.gallery_images);
+    if app_state.motd_font.is_some() {
+        if let Some(ping) = summary.ping.as_mut() {
+            ping.motd_image_url = Some(format!("/v2/servers/{server_id}/motd.png"));
+        }
+    }
+    Ok(Json(summary))
+}
+
+/// 创建服务器
+#[utoipa::path(
+    post,
+    operation_id = "create_server",
+    path = "/v2/servers",
+    summary = "创建服务器",
+    description = "登记新服务器，创建者成为服主；新服务器为草稿，提交审核并通过后才会出现在列表与搜索中；封面可选，上传封面需要配置对象存储",
+    request_body(content = CreateServerRequest, content_type = "multipart/form-data"),
+    responses(
+        (
+            status = 201,
+            description = "成功创建服务器",
+            body = ServerDetail,
+        ),
+        (
+            status = 400,
+            description = "无效的请求参数",
+            body = ApiErrorResponse,
+            examples(
+                ("参数验证失败" = (value = json!({"error": "参数验证失败: desc: 简介必须大于 100 字", "code": "VALIDATION_FAILED", "status": 400}))),
+                ("未知的服务器类型" = (value = json!({"error": "未知的服务器类型: PE", "code": "BAD_REQUEST", "status": 400})))
+            ),
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401}),
+        ),
+        (
+            status = 501,
+            description = "上传了封面但未配置对象存储",
+            body = ApiErrorResponse,
+            example = json!({"error": "未配置对象存储，图片上传不可用", "code": "FEATURE_DISABLED", "status": 501}),
+        )
+    ),
+    tag = "servers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_server(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    user_claims: Option<Extension<Claims>>,
+    TypedMultipart(request): TypedMultipart<CreateServerRequest>,
+) -> ApiResult<(StatusCode, Json<ServerDetail>)> {
+    let user = user_claims.ok_or_else(|| ApiError::Unauthorized("未授权".to_string()))?;
+    let db = &app_state.db;
+
+    let server = ServerService::create_server(
+        db,
+        app_state.storage.as_deref(),
+        tenant.id(),
+        request,
+        user.id,
+    )
+    .await?;
+    ActivityService::record(
+        db,
+        user.id,
+        ActivityAction::ServerCreated,
+        Some((TARGET_SERVER, server.id)),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(server)))
+}
+
 /// 更新对应服务器具体信息
 #[utoipa::path(
     put,
+    operation_id = "update_server",
     path = "/v2/servers/{server_id}",
     request_body(content = UpdateServerRequest, content_type = "multipart/form-data"),
     responses(
@@ -182,29 +559,44 @@ pub async fn get_server_detail(
             description = "无效的请求参数",
             body = ApiErrorResponse,
             examples(
-                ("更新字段不能为空" = (value = json!({"error": "更新字段不能为空", "status": 400}))),
-                ("tags数量不能超过7个" = (value = json!({"error": "tags 数量不能超过 7 个", "status": 400}))),
-                ("tags长度限制为1~4" = (value = json!({"error": "tags 长度限制为 1~4", "status": 400}))),
-                ("简介必须大于100字" = (value = json!({"error": "简介必须大于 100 字", "status": 400})))
+                ("更新字段不能为空" = (value = json!({"error": "更新字段不能为空", "code": "BAD_REQUEST", "status": 400}))),
+                ("tags数量不能超过7个" = (value = json!({"error": "tags 数量不能超过 7 个", "code": "BAD_REQUEST", "status": 400}))),
+                ("tags长度限制为1~4" = (value = json!({"error": "tags 长度限制为 1~4", "code": "BAD_REQUEST", "status": 400}))),
+                ("简介必须大于100字" = (value = json!({"error": "简介必须大于 100 字", "code": "BAD_REQUEST", "status": 400})))
             ),
         ),
         (
             status = 401,
             description = "未授权",
             body = ApiErrorResponse,
-            example = json!({"error": "未授权", "status": 401}),
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401}),
         ),
         (
             status = 403,
-            description = "无权限编辑该服务器",
+            description = "不是服务器管理员",
             body = ApiErrorResponse,
-            example = json!({"error": "无权限编辑该服务器", "status": 403}),
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403}),
         ),
         (
             status = 404,
             description = "未找到该服务器",
             body = ApiErrorResponse,
-            example = json!({"error": "未找到该服务器", "status": 404}),
+            example = json!({"error": "未找到该服务器", "code": "SERVER_NOT_FOUND", "status": 404}),
+        ),
+        (
+            status = 409,
+            description = "短链接已被占用或已修改过",
+            body = ApiErrorResponse,
+            examples(
+                ("短链接已被占用" = (value = json!({"error": "短链接已被占用", "code": "CONFLICT", "status": 409}))),
+                ("短链接只能修改一次" = (value = json!({"error": "短链接只能修改一次", "code": "CONFLICT", "status": 409})))
+            ),
+        ),
+        (
+            status = 501,
+            description = "上传了封面但未配置对象存储",
+            body = ApiErrorResponse,
+            example = json!({"error": "未配置对象存储，图片上传不可用", "code": "FEATURE_DISABLED", "status": 501}),
         )
     ),
     tag = "servers",
@@ -216,28 +608,28 @@ pub async fn get_server_detail(
 pub async fn update_server(
     State(app_state): State<AppState>,
     Path(server_id): Path<i32>,
-    user_claims: Option<Extension<Claims>>,
+    manager: RequireServerRole<Admin>,
     TypedMultipart(update_data): TypedMultipart<UpdateServerRequest>,
 ) -> ApiResult<Json<ServerDetail>> {
-    // 检查用户是否已登录
-    let user = user_claims.ok_or_else(|| ApiError::Unauthorized("未授权".to_string()))?;
-
-    // 从环境变量获取 S3 配置
-    let s3_config = crate::config::S3Config {
-        endpoint_url: std::env::var("S3_ENDPOINT_URL")
-            .map_err(|_| ApiError::Internal("S3配置缺失".to_string()))?,
-        access_key: std::env::var("S3_ACCESS_KEY")
-            .map_err(|_| ApiError::Internal("S3配置缺失".to_string()))?,
-        secret_key: std::env::var("S3_SECRET_KEY")
-            .map_err(|_| ApiError::Internal("S3配置缺失".to_string()))?,
-        bucket: std::env::var("S3_BUCKET")
-            .map_err(|_| ApiError::Internal("S3配置缺失".to_string()))?,
-    };
     let db = &app_state.db;
 
     // 调用服务层更新服务器
-    let updated_server =
-        ServerService::update_server_by_id(db, &s3_config, server_id, update_data, user.id).await?;
+    let updated_server = ServerService::update_server_by_id(
+        db,
+        app_state.storage.as_deref(),
+        server_id,
+        update_data,
+        manager.id,
+    )
+    .await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerUpdated,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
 
     Ok(Json(updated_server))
 }
@@ -245,6 +637,7 @@ pub async fn update_server(
 /// 获取服务器管理员列表
 #[utoipa::path(
     get,
+    operation_id = "get_server_managers",
     path = "/v2/servers/{server_id}/managers",
     responses(
         (
@@ -258,6 +651,7 @@ pub async fn update_server(
             body = ApiErrorResponse,
             example = json!({
                 "error": "服务器不存在",
+                "code": "SERVER_NOT_FOUND",
                 "status": 404
             }),
         )
@@ -266,185 +660,499 @@ pub async fn update_server(
     params(("server_id" = i32, Path, description = "服务器 ID"))
 )]
 pub async fn get_server_managers(
-    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
     Path(server_id): Path<i32>,
 ) -> ApiResult<Json<ServerManagersResponse>> {
-    let db = &app_state.db;
-    let result = ServerService::get_server_managers(db, server_id).await?;
+    let result = ServerService::get_server_managers(&db, server_id).await?;
     Ok(Json(result))
 }
 
-/// 获取服务器相册
+/// 添加服务器管理员
 #[utoipa::path(
-    get,
-    path = "/v2/servers/{server_id}/gallery",
-    summary = "获取服务器相册",
-    description = "获取指定服务器的所有相册图片信息",
+    post,
+    operation_id = "add_server_manager",
+    path = "/v2/servers/{server_id}/managers",
+    summary = "添加服务器管理员",
+    description = "按用户名把同一站点的用户添加为管理员或服主，只有服主可以操作",
+    request_body(content = AddManagerRequest, content_type = "application/json"),
     responses(
+        (status = 200, description = "更新后的管理员列表", body = ServerManagersResponse),
         (
-            status = 200,
-            description = "成功获取服务器相册",
-            body = ServerGallery,
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "不是服务器所有者",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403})
         ),
         (
             status = 404,
-            description = "服务器不存在",
+            description = "服务器或用户不存在",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "服务器不存在",
-                "status": 404
-            })
+            example = json!({"error": "用户不存在", "code": "USER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "该用户已是管理员",
+            body = ApiErrorResponse,
+            example = json!({"error": "该用户已是服务器管理员", "code": "CONFLICT", "status": 409})
         )
     ),
     tag = "servers",
-    params(("server_id" = i32, Path, description = "服务器ID"))
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
 )]
-pub async fn get_server_gallery(
+pub async fn add_server_manager(
     State(app_state): State<AppState>,
+    tenant: CurrentTenant,
     Path(server_id): Path<i32>,
-) -> ApiResult<Json<ServerGallery>> {
+    manager: RequireServerRole<Owner>,
+    Json(request): Json<AddManagerRequest>,
+) -> ApiResult<Json<ServerManagersResponse>> {
     let db = &app_state.db;
-    let result = ServerService::get_server_gallery(db, server_id).await?;
-    Ok(Json(result))
+
+    let user_id = ServerService::add_manager(db, tenant.id(), server_id, &request).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ManagerAdded,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "user_id": user_id, "role": request.role.as_str() })),
+    )
+    .await;
+
+    Ok(Json(
+        ServerService::get_server_managers(db, server_id).await?,
+    ))
 }
 
-/// 添加服务器画册图片
+/// 修改服务器管理员角色
 #[utoipa::path(
-    post,
-    path = "/v2/servers/{server_id}/gallery",
-    summary = "添加服务器画册图片",
-    description = "为指定服务器添加画册图片，需要服务器管理员权限",
-    request_body(
-        content = GalleryImageRequest,
-        content_type = "multipart/form-data"
-    ),
+    put,
+    operation_id = "update_server_manager",
+    path = "/v2/servers/{server_id}/managers/{user_id}",
+    summary = "修改服务器管理员角色",
+    description = "把管理员提升为服主或把服主降为管理员，只有服主可以操作。服务器至少需要保留一名服主",
+    request_body(content = UpdateManagerRequest, content_type = "application/json"),
     responses(
-        (
-            status = 201,
-            description = "成功添加服务器画册图片",
-            body = SuccessResponse,
-            example = json!({
-                "message": "成功添加服务器画册图片"
-            })
-        ),
+        (status = 200, description = "更新后的管理员列表", body = ServerManagersResponse),
         (
             status = 401,
-            description = "无权限操作",
+            description = "未登录",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "未授权",
-                "status": 401
-            })
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
         ),
         (
             status = 403,
-            description = "权限不足",
+            description = "不是服务器所有者",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "权限不足，只有服务器管理员可以添加画册图片",
-                "status": 403
-            })
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403})
         ),
         (
             status = 404,
-            description = "未找到服务器",
+            description = "服务器不存在或该用户不是管理员",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "服务器不存在",
-                "status": 404
-            })
+            example = json!({"error": "该用户不是服务器管理员", "code": "NOT_FOUND", "status": 404})
         ),
         (
-            status = 400,
-            description = "请求参数错误",
+            status = 409,
+            description = "会导致服务器没有服主",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "图片文件格式无效",
-                "status": 400
-            })
+            example = json!({"error": "服务器至少需要保留一名所有者，请先转让所有权", "code": "CONFLICT", "status": 409})
         )
     ),
     tag = "servers",
-    params(("server_id" = i32, Path, description = "服务器ID")),
-    security(
-        ("bearer_auth" = [])
-    )
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ("user_id" = i32, Path, description = "管理员的用户 ID")
+    ),
+    security(("bearer_auth" = []))
 )]
-pub async fn upload_gallery_image(
+pub async fn update_server_manager(
     State(app_state): State<AppState>,
-    Path(server_id): Path<i32>,
-    user_claims: Option<Extension<Claims>>,
-    TypedMultipart(gallery_data): TypedMultipart<GalleryImageSchema>,
-) -> ApiResult<Json<serde_json::Value>> {
-    // 检查用户是否登录
-    let claims = user_claims
-        .ok_or_else(|| ApiError::Unauthorized("未授权".to_string()))?
-        .0;
+    Path((server_id, user_id)): Path<(i32, i32)>,
+    manager: RequireServerRole<Owner>,
+    Json(request): Json<UpdateManagerRequest>,
+) -> ApiResult<Json<ServerManagersResponse>> {
     let db = &app_state.db;
 
-    // 检查用户是否有这个服务器的编辑权
-    let has_permission =
-        ServerService::has_server_edit_permission(db, claims.id, server_id).await?;
-    if !has_permission {
-        return Err(ApiError::Forbidden(
-            "权限不足，只有服务器管理员可以添加画册图片".to_string(),
-        ));
-    }
-
-    // 从环境变量获取S3配置
-    let config = crate::config::Config::from_env()
-        .map_err(|e| ApiError::Internal(format!("配置加载失败: {e}")))?;
-
-    // 添加画册图片
-    ServerService::add_gallery_image(db, &config.s3, server_id, &gallery_data).await?;
+    ServerService::update_manager_role(db, server_id, user_id, request.role).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ManagerRoleChanged,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "user_id": user_id, "role": request.role.as_str() })),
+    )
+    .await;
 
-    Ok(Json(serde_json::json!({
-        "message": "成功添加服务器画册图片"
-    })))
+    Ok(Json(
+        ServerService::get_server_managers(db, server_id).await?,
+    ))
 }
 
-/// 删除服务器画册图片
+/// 移除服务器管理员
 #[utoipa::path(
     delete,
-    path = "/v2/servers/{server_id}/gallery/{image_id}",
-    summary = "删除服务器画册图片",
-    description = "删除指定服务器的画册图片，需要服务器管理员权限",
+    operation_id = "remove_server_manager",
+    path = "/v2/servers/{server_id}/managers/{user_id}",
+    summary = "移除服务器管理员",
+    description = "服主可以移除任何管理员，其他管理员只能移除自己（退出管理）。服务器至少需要保留一名服主",
     responses(
-        (
-            status = 200,
-            description = "成功删除服务器画册图片",
-            body = SuccessResponse,
-            example = json!({
-                "message": "成功删除服务器画册图片"
-            })
-        ),
+        (status = 200, description = "更新后的管理员列表", body = ServerManagersResponse),
         (
             status = 401,
-            description = "无权限操作",
+            description = "未登录",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "未授权",
-                "status": 401
-            })
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
         ),
         (
             status = 403,
-            description = "权限不足",
+            description = "不是服务器管理员，或非服主移除其他管理员",
             body = ApiErrorResponse,
-            example = json!({
-                "error": "权限不足，只有服务器管理员可以删除画册图片",
-                "status": 403
-            })
+            examples(
+                ("不是服务器管理员" = (value = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403}))),
+                ("不是服务器所有者" = (value = json!({"error": "只有服务器所有者可以管理管理员", "code": "FORBIDDEN", "status": 403})))
+            )
         ),
         (
             status = 404,
-            description = "未找到服务器或图片",
+            description = "服务器不存在或该用户不是管理员",
             body = ApiErrorResponse,
-            examples(
-                ("服务器不存在" = (value = json!({"error": "服务器不存在", "status": 404}))),
-                ("图片不存在" = (value = json!({"error": "图片不存在", "status": 404}))),
-                ("该服务器没有画册" = (value = json!({"error": "该服务器没有画册", "status": 404})))
-            )
+            example = json!({"error": "该用户不是服务器管理员", "code": "NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "会导致服务器没有服主",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器至少需要保留一名所有者，请先转让所有权", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ("user_id" = i32, Path, description = "管理员的用户 ID")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn remove_server_manager(
+    State(app_state): State<AppState>,
+    Path((server_id, user_id)): Path<(i32, i32)>,
+    manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<ServerManagersResponse>> {
+    let db = &app_state.db;
+
+    ServerService::remove_manager(db, server_id, manager.id, manager.role, user_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ManagerRemoved,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "user_id": user_id })),
+    )
+    .await;
+
+    Ok(Json(
+        ServerService::get_server_managers(db, server_id).await?,
+    ))
+}
+
+/// 转让服务器所有权
+#[utoipa::path(
+    post,
+    operation_id = "transfer_server_ownership",
+    path = "/v2/servers/{server_id}/managers/{user_id}/transfer",
+    summary = "转让服务器所有权",
+    description = "把服主身份转让给该服务器的另一名管理员，转让后当前服主成为管理员",
+    responses(
+        (status = 200, description = "更新后的管理员列表", body = ServerManagersResponse),
+        (
+            status = 400,
+            description = "不能转让给自己",
+            body = ApiErrorResponse,
+            example = json!({"error": "不能把服务器转让给自己", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "不是服务器所有者",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在或该用户不是管理员",
+            body = ApiErrorResponse,
+            example = json!({"error": "该用户不是服务器管理员", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ("user_id" = i32, Path, description = "接收所有权的管理员用户 ID")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn transfer_server_ownership(
+    State(app_state): State<AppState>,
+    Path((server_id, user_id)): Path<(i32, i32)>,
+    manager: RequireServerRole<Owner>,
+) -> ApiResult<Json<ServerManagersResponse>> {
+    let db = &app_state.db;
+
+    ServerService::transfer_ownership(db, server_id, manager.id, user_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::OwnershipTransferred,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "user_id": user_id })),
+    )
+    .await;
+
+    Ok(Json(
+        ServerService::get_server_managers(db, server_id).await?,
+    ))
+}
+
+/// 获取服务器相册
+#[utoipa::path(
+    get,
+    operation_id = "get_server_gallery",
+    path = "/v2/servers/{server_id}/gallery",
+    summary = "获取服务器相册",
+    description = "获取指定服务器的所有相册图片信息",
+    responses(
+        (
+            status = 200,
+            description = "成功获取服务器相册",
+            body = ServerGallery,
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "服务器不存在",
+                "code": "SERVER_NOT_FOUND",
+                "status": 404
+            })
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID"))
+)]
+pub async fn get_server_gallery(
+    ReadDb(db): ReadDb,
+    Path(server_id): Path<i32>,
+) -> ApiResult<Json<ServerGallery>> {
+    let result = ServerService::get_server_gallery(&db, server_id).await?;
+    Ok(Json(result))
+}
+
+/// 获取服务器相册订阅源
+#[utoipa::path(
+    get,
+    operation_id = "get_gallery_feed",
+    path = "/v2/servers/{server_id}/gallery/feed",
+    summary = "获取服务器相册订阅源",
+    description = "以 RSS 2.0 或 JSON Feed 1.1 格式输出服务器最近发布的 50 张相册图片，可在阅读器中订阅服务器动态；隐藏或停用的服务器不提供订阅源",
+    responses(
+        (status = 200, description = "RSS 订阅源", body = String, content_type = "application/rss+xml"),
+        (status = 200, description = "JSON Feed 订阅源", body = String, content_type = "application/feed+json"),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        GalleryFeedQuery
+    )
+)]
+pub async fn get_gallery_feed(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Query(query): Query<GalleryFeedQuery>,
+) -> ApiResult<Response> {
+    let feed = GalleryFeedService::render(
+        &db,
+        &app_state.config.sitemap,
+        &tenant.0,
+        server_id,
+        query.format,
+    )
+    .await?;
+    Ok(([(CONTENT_TYPE, feed.content_type)], feed.body).into_response())
+}
+
+/// 添加服务器画册图片
+#[utoipa::path(
+    post,
+    operation_id = "upload_gallery_image",
+    path = "/v2/servers/{server_id}/gallery",
+    summary = "添加服务器画册图片",
+    description = "为指定服务器添加画册图片，需要服务器管理员权限",
+    request_body(
+        content = GalleryImageRequest,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (
+            status = 201,
+            description = "成功添加服务器画册图片",
+            body = SuccessResponse,
+            example = json!({
+                "message": "成功添加服务器画册图片"
+            })
+        ),
+        (
+            status = 401,
+            description = "无权限操作",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "未授权",
+                "code": "UNAUTHORIZED",
+                "status": 401
+            })
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "需要服务器管理员权限",
+                "code": "SERVER_MANAGER_REQUIRED",
+                "status": 403
+            })
+        ),
+        (
+            status = 404,
+            description = "未找到服务器",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "服务器不存在",
+                "code": "SERVER_NOT_FOUND",
+                "status": 404
+            })
+        ),
+        (
+            status = 400,
+            description = "请求参数错误",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "图片文件格式无效",
+                "code": "INVALID_IMAGE_FORMAT",
+                "status": 400
+            })
+        ),
+        (
+            status = 429,
+            description = "上传过于频繁",
+            body = RateLimitedErrorResponse
+        ),
+        (
+            status = 501,
+            description = "未配置对象存储",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "未配置对象存储，图片上传不可用",
+                "code": "FEATURE_DISABLED",
+                "status": 501
+            })
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upload_gallery_image(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+    TypedMultipart(gallery_data): TypedMultipart<GalleryImageSchema>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let db = &app_state.db;
+
+    let storage = storage::require(app_state.storage.as_deref())?;
+
+    // 添加画册图片
+    ServerService::add_gallery_image(db, storage, server_id, &gallery_data).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::GalleryImageAdded,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "成功添加服务器画册图片"
+    })))
+}
+
+/// 删除服务器画册图片
+#[utoipa::path(
+    delete,
+    operation_id = "delete_gallery_image",
+    path = "/v2/servers/{server_id}/gallery/{image_id}",
+    summary = "删除服务器画册图片",
+    description = "删除指定服务器的画册图片，需要服务器管理员权限",
+    responses(
+        (
+            status = 200,
+            description = "成功删除服务器画册图片",
+            body = SuccessResponse,
+            example = json!({
+                "message": "成功删除服务器画册图片"
+            })
+        ),
+        (
+            status = 401,
+            description = "无权限操作",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "未授权",
+                "code": "UNAUTHORIZED",
+                "status": 401
+            })
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "需要服务器管理员权限",
+                "code": "SERVER_MANAGER_REQUIRED",
+                "status": 403
+            })
+        ),
+        (
+            status = 404,
+            description = "未找到服务器或图片",
+            body = ApiErrorResponse,
+            examples(
+                ("服务器不存在" = (value = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}))),
+                ("图片不存在" = (value = json!({"error": "图片不存在", "code": "IMAGE_NOT_FOUND", "status": 404}))),
+                ("该服务器没有画册" = (value = json!({"error": "该服务器没有画册", "code": "GALLERY_NOT_FOUND", "status": 404})))
+            )
         ),
         (
             status = 403,
@@ -452,6 +1160,7 @@ pub async fn upload_gallery_image(
             body = ApiErrorResponse,
             example = json!({
                 "error": "图片不属于该服务器",
+                "code": "FORBIDDEN",
                 "status": 403
             })
         )
@@ -468,56 +1177,1491 @@ pub async fn upload_gallery_image(
 pub async fn delete_gallery_image(
     State(app_state): State<AppState>,
     Path((server_id, image_id)): Path<(i32, i32)>,
-    user_claims: Option<Extension<Claims>>,
+    manager: RequireServerRole<Admin>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    // 检查用户是否登录
-    let claims = user_claims
-        .ok_or_else(|| ApiError::Unauthorized("未授权".to_string()))?
-        .0;
     let db = &app_state.db;
-    // 检查用户是否有这个服务器的编辑权
-    let has_permission =
-        ServerService::has_server_edit_permission(db, claims.id, server_id).await?;
-    if !has_permission {
-        return Err(ApiError::Forbidden(
-            "权限不足，只有服务器管理员可以删除画册图片".to_string(),
-        ));
-    }
-
-    // 从环境变量获取S3配置
-    let config = crate::config::Config::from_env()
-        .map_err(|e| ApiError::Internal(format!("配置加载失败: {e}")))?;
 
     // 删除画册图片
-    ServerService::delete_gallery_image(db, &config.s3, server_id, image_id).await?;
+    ServerService::delete_gallery_image(db, app_state.storage.as_deref(), server_id, image_id)
+        .await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::GalleryImageDeleted,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "image_id": image_id })),
+    )
+    .await;
 
     Ok(Json(serde_json::json!({
         "message": "成功删除服务器画册图片"
     })))
 }
 
-/// 获取所有服务器玩家总数
+/// 编辑服务器画册图片
 #[utoipa::path(
-    get,
-    path = "/v2/servers/players",
+    patch,
+    operation_id = "update_gallery_image",
+    path = "/v2/servers/{server_id}/gallery/{image_id}",
+    summary = "编辑服务器画册图片",
+    description = "修改画册图片的标题或描述，未传的字段保持不变，需要服务器管理员权限",
+    request_body = UpdateGalleryImageRequest,
     responses(
+        (status = 200, description = "编辑后的图片信息", body = GalleryImage),
         (
-            status = 200,
-            description = "成功获取所有服务器玩家总数",
-            body = ServerTotalPlayers,
+            status = 400,
+            description = "参数无效",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "参数验证失败: title: 标题长度必须在1-100个字符之间",
+                "code": "BAD_REQUEST",
+                "status": 400
+            })
         ),
         (
-            status = 500,
-            description = "服务器内部错误",
+            status = 401,
+            description = "无权限操作",
             body = ApiErrorResponse,
-        )
-    ),
-    tag = "servers"
-)]
-pub async fn get_total_players(
-    State(app_state): State<AppState>,
-) -> ApiResult<Json<ServerTotalPlayers>> {
-    let db = &app_state.db;
-    let result = ServerService::total_players(db).await?;
-    Ok(Json(result))
+            example = json!({
+                "error": "未授权",
+                "code": "UNAUTHORIZED",
+                "status": 401
+            })
+        ),
+        (
+            status = 403,
+            description = "权限不足或图片不属于该服务器",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "需要服务器管理员权限",
+                "code": "SERVER_MANAGER_REQUIRED",
+                "status": 403
+            })
+        ),
+        (
+            status = 404,
+            description = "未找到服务器或图片",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "图片不存在",
+                "code": "IMAGE_NOT_FOUND",
+                "status": 404
+            })
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器ID"),
+        ("image_id" = i32, Path, description = "图片ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_gallery_image(
+    State(app_state): State<AppState>,
+    Path((server_id, image_id)): Path<(i32, i32)>,
+    manager: RequireServerRole<Admin>,
+    Json(request): Json<UpdateGalleryImageRequest>,
+) -> ApiResult<Json<GalleryImage>> {
+    let db = &app_state.db;
+
+    let image = ServerService::update_gallery_image(db, server_id, image_id, &request).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::GalleryImageUpdated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "image_id": image_id })),
+    )
+    .await;
+
+    Ok(Json(image))
+}
+
+/// 调整服务器画册图片顺序
+#[utoipa::path(
+    put,
+    operation_id = "reorder_gallery_images",
+    path = "/v2/servers/{server_id}/gallery/order",
+    summary = "调整服务器画册图片顺序",
+    description = "按请求中的顺序重排画册图片，列表必须恰好包含画册中的全部图片，需要服务器管理员权限。\
+                   新上传的图片排在最后",
+    request_body = ReorderGalleryRequest,
+    responses(
+        (status = 200, description = "重排后的相册", body = ServerGallery),
+        (
+            status = 400,
+            description = "图片 ID 列表与画册不一致",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "图片 ID 列表必须恰好包含画册中的全部图片",
+                "code": "BAD_REQUEST",
+                "status": 400
+            })
+        ),
+        (
+            status = 401,
+            description = "无权限操作",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "未授权",
+                "code": "UNAUTHORIZED",
+                "status": 401
+            })
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "需要服务器管理员权限",
+                "code": "SERVER_MANAGER_REQUIRED",
+                "status": 403
+            })
+        ),
+        (
+            status = 404,
+            description = "未找到服务器或画册",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "该服务器没有画册",
+                "code": "GALLERY_NOT_FOUND",
+                "status": 404
+            })
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reorder_gallery_images(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+    Json(request): Json<ReorderGalleryRequest>,
+) -> ApiResult<Json<ServerGallery>> {
+    let db = &app_state.db;
+
+    let gallery = ServerService::reorder_gallery(db, server_id, &request.image_ids).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::GalleryReordered,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    Ok(Json(gallery))
+}
+
+/// 获取服务器封面
+#[utoipa::path(
+    get,
+    operation_id = "get_server_cover",
+    path = "/v2/servers/{server_id}/cover",
+    summary = "获取服务器封面",
+    description = "302 重定向到封面图片地址，可直接用作 `<img>` 的 src。隐藏或停用的服务器返回 404",
+    responses(
+        (
+            status = 302,
+            description = "重定向到封面图片",
+            headers(("Location" = String, description = "封面图片地址"))
+        ),
+        (
+            status = 404,
+            description = "服务器不存在或没有封面",
+            body = ApiErrorResponse,
+            examples(
+                ("服务器不存在" = (value = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}))),
+                ("服务器没有封面" = (value = json!({"error": "服务器没有封面", "code": "COVER_NOT_FOUND", "status": 404})))
+            )
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID"))
+)]
+pub async fn get_server_cover(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+) -> ApiResult<Response> {
+    let url = ServerService::cover_url(&db, tenant.id(), server_id).await?;
+    Ok((
+        StatusCode::FOUND,
+        [
+            (LOCATION, url),
+            (CACHE_CONTROL, COVER_CACHE_CONTROL.to_string()),
+        ],
+    )
+        .into_response())
+}
+
+/// 获取服务器 MOTD 图片
+#[utoipa::path(
+    get,
+    operation_id = "get_server_motd_image",
+    path = "/v2/servers/{server_id}/motd.png",
+    summary = "获取服务器 MOTD 图片",
+    description = "把最近一次状态中的 MOTD 按 Minecraft 颜色代码渲染为 PNG 横幅，可直接用作 `<img>` 的 src。\
+                   相同的 MOTD、宽度与配色命中服务端缓存；隐藏或停用的服务器返回 404",
+    responses(
+        (
+            status = 200,
+            description = "MOTD 图片",
+            content_type = "image/png",
+            body = Vec<u8>,
+        ),
+        (
+            status = 404,
+            description = "服务器不存在或暂无状态数据",
+            body = ApiErrorResponse,
+            examples(
+                ("服务器不存在" = (value = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}))),
+                ("暂无状态数据" = (value = json!({"error": "服务器暂无状态数据", "code": "NOT_FOUND", "status": 404})))
+            )
+        ),
+        (
+            status = 501,
+            description = "未配置 MOTD 字体",
+            body = ApiErrorResponse,
+            example = json!({"error": "未配置 MOTD 字体，MOTD 图片不可用", "code": "FEATURE_DISABLED", "status": 501})
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        MotdImageQuery
+    )
+)]
+pub async fn get_server_motd_image(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Query(query): Query<MotdImageQuery>,
+) -> ApiResult<Response> {
+    let (Some(font), Some(config)) = (&app_state.motd_font, &app_state.config.motd_image) else {
+        return Err(ApiError::FeatureDisabled(
+            "未配置 MOTD 字体，MOTD 图片不可用".to_string(),
+        ));
+    };
+    let width = query
+        .width
+        .unwrap_or(config.default_width)
+        .clamp(MotdImageService::MIN_WIDTH, MotdImageService::MAX_WIDTH);
+
+    let legacy = MotdImageService::latest_motd(&db, tenant.id(), server_id).await?;
+    let key = MotdImageService::cache_key(server_id, &legacy, width, query.theme);
+    let png = match ServerCacheService::get_motd_image(&key).await {
+        Some(png) => png,
+        None => {
+            let png = MotdImageService::render(font, &legacy, width, query.theme)?;
+            ServerCacheService::set_motd_image(&key, &png, config.cache_ttl_secs).await;
+            png
+        }
+    };
+
+    Ok((
+        [
+            (CONTENT_TYPE, "image/png"),
+            (CACHE_CONTROL, MOTD_IMAGE_CACHE_CONTROL),
+        ],
+        png,
+    )
+        .into_response())
+}
+
+/// 删除服务器封面
+#[utoipa::path(
+    delete,
+    operation_id = "delete_server_cover",
+    path = "/v2/servers/{server_id}/cover",
+    summary = "删除服务器封面",
+    description = "移除服务器封面，需要服务器管理员权限。封面文件不再被其他服务器、画册或头像引用时，\
+                   同时从对象存储中删除",
+    responses(
+        (
+            status = 200,
+            description = "成功删除服务器封面",
+            body = SuccessResponse,
+            example = json!({
+                "message": "成功删除服务器封面"
+            })
+        ),
+        (
+            status = 401,
+            description = "无权限操作",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "未授权",
+                "code": "UNAUTHORIZED",
+                "status": 401
+            })
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({
+                "error": "需要服务器管理员权限",
+                "code": "SERVER_MANAGER_REQUIRED",
+                "status": 403
+            })
+        ),
+        (
+            status = 404,
+            description = "服务器不存在或没有封面",
+            body = ApiErrorResponse,
+            examples(
+                ("服务器不存在" = (value = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}))),
+                ("服务器没有封面" = (value = json!({"error": "服务器没有封面", "code": "COVER_NOT_FOUND", "status": 404})))
+            )
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_server_cover(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let db = &app_state.db;
+
+    ServerService::delete_cover(db, app_state.storage.as_deref(), server_id, manager.id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerUpdated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "cover": "deleted" })),
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "成功删除服务器封面"
+    })))
+}
+
+/// 上传服务器图标
+#[utoipa::path(
+    post,
+    operation_id = "upload_server_icon",
+    path = "/v2/servers/{server_id}/icon",
+    summary = "上传服务器图标",
+    description = "上传与 16:9 封面分开的方形图标，替换原有图标，需要服务器管理员权限。\
+                   图片必须为正方形，边长 64 到 512 像素，不超过 1 MB，保存为 WebP",
+    request_body(content = UploadServerIconRequest, content_type = "multipart/form-data"),
+    responses(
+        (
+            status = 200,
+            description = "成功上传服务器图标",
+            example = json!({
+                "message": "成功上传服务器图标",
+                "icon_url": "https://cdn.example.com/uploads/icon.webp"
+            })
+        ),
+        (
+            status = 400,
+            description = "图片不符合要求",
+            body = ApiErrorResponse,
+            example = json!({"error": "图标必须为正方形", "code": "INVALID_IMAGE_RATIO", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 501,
+            description = "未配置对象存储",
+            body = ApiErrorResponse,
+            example = json!({"error": "未配置对象存储，图片上传不可用", "code": "FEATURE_DISABLED", "status": 501})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upload_server_icon(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+    TypedMultipart(request): TypedMultipart<UploadServerIconRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let db = &app_state.db;
+    let storage = storage::require(app_state.storage.as_deref())?;
+
+    let icon_url =
+        ServerService::update_icon(db, storage, server_id, request.icon.contents.to_vec()).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerUpdated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "icon": "updated" })),
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "成功上传服务器图标",
+        "icon_url": icon_url
+    })))
+}
+
+/// 删除服务器图标
+#[utoipa::path(
+    delete,
+    operation_id = "delete_server_icon",
+    path = "/v2/servers/{server_id}/icon",
+    summary = "删除服务器图标",
+    description = "移除服务器图标，需要服务器管理员权限。图标文件不再被其他服务器、画册或头像引用时，\
+                   同时从对象存储中删除",
+    responses(
+        (
+            status = 200,
+            description = "成功删除服务器图标",
+            body = SuccessResponse,
+            example = json!({
+                "message": "成功删除服务器图标"
+            })
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在或没有图标",
+            body = ApiErrorResponse,
+            examples(
+                ("服务器不存在" = (value = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}))),
+                ("服务器没有图标" = (value = json!({"error": "服务器没有图标", "code": "ICON_NOT_FOUND", "status": 404})))
+            )
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_server_icon(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let db = &app_state.db;
+
+    ServerService::delete_icon(db, app_state.storage.as_deref(), server_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerUpdated,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "icon": "deleted" })),
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "成功删除服务器图标"
+    })))
+}
+
+/// 获取所有服务器玩家总数
+#[utoipa::path(
+    get,
+    operation_id = "get_total_players",
+    path = "/v2/servers/players",
+    responses(
+        (
+            status = 200,
+            description = "成功获取所有服务器玩家总数",
+            body = ServerTotalPlayers,
+        ),
+        (
+            status = 500,
+            description = "服务器内部错误",
+            body = ApiErrorResponse,
+        )
+    ),
+    tag = "servers"
+)]
+pub async fn get_total_players(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+) -> ApiResult<Json<ServerTotalPlayers>> {
+    if let Some(cached) = ServerCacheService::get_players(tenant.id()).await {
+        return Ok(Json(cached));
+    }
+
+    let result = ServerService::total_players(&db, tenant.id()).await?;
+    ServerCacheService::set_players(tenant.id(), &result).await;
+    Ok(Json(result))
+}
+
+/// 推送服务器状态数据
+#[utoipa::path(
+    post,
+    operation_id = "push_server_stats",
+    path = "/v2/servers/{server_id}/stats",
+    summary = "推送服务器状态数据",
+    description = "由服务器端插件推送当前状态，请求需携带 `X-Signature-Timestamp` 与 `X-Signature` 头，签名为 HMAC-SHA256(密钥, \"{timestamp}.{body}\")；同一签名只能使用一次",
+    request_body(content = ServerStats, content_type = "application/json"),
+    responses(
+        (
+            status = 200,
+            description = "推送成功",
+            body = SuccessResponse,
+            example = json!({"message": "推送成功"})
+        ),
+        (
+            status = 400,
+            description = "状态数据格式无效",
+            body = ApiErrorResponse,
+        ),
+        (
+            status = 401,
+            description = "签名无效、已过期或已被使用",
+            body = ApiErrorResponse,
+            examples(
+                ("缺少签名" = (value = json!({"error": "缺少签名", "code": "SIGNATURE_INVALID", "status": 401}))),
+                ("签名已过期" = (value = json!({"error": "签名已过期", "code": "SIGNATURE_EXPIRED", "status": 401}))),
+                ("签名已被使用" = (value = json!({"error": "签名已被使用", "code": "SIGNATURE_REPLAYED", "status": 401}))),
+                ("签名校验失败" = (value = json!({"error": "签名校验失败", "code": "SIGNATURE_INVALID", "status": 401})))
+            )
+        ),
+        (
+            status = 403,
+            description = "该服务器未启用数据推送",
+            body = ApiErrorResponse,
+            example = json!({"error": "该服务器未启用数据推送", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn push_server_stats(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<SuccessResponse>> {
+    let db = &app_state.db;
+    ServerService::push_server_stats(db, &app_state.config.signing, server_id, &headers, &body)
+        .await?;
+
+    Ok(Json(SuccessResponse {
+        message: "推送成功".to_string(),
+    }))
+}
+
+/// 重新生成数据推送密钥
+#[utoipa::path(
+    post,
+    operation_id = "rotate_push_secret",
+    path = "/v2/servers/{server_id}/push-secret",
+    summary = "重新生成数据推送密钥",
+    description = "生成新的数据推送签名密钥，旧密钥立即失效，需要服务器管理员权限",
+    responses(
+        (status = 200, description = "成功生成密钥", body = PushSecretResponse),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "无权限编辑该服务器",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn rotate_push_secret(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<PushSecretResponse>> {
+    let db = &app_state.db;
+
+    let secret = ServerService::rotate_push_secret(db, server_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::PushSecretRotated,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    Ok(Json(PushSecretResponse { secret }))
+}
+
+/// 替换服务器自定义字段
+#[utoipa::path(
+    put,
+    operation_id = "update_custom_fields",
+    path = "/v2/servers/{server_id}/custom-fields",
+    summary = "替换服务器自定义字段",
+    description = "整体替换服务器的自定义字段（如 QQ 群、资源包、出生点指令），最多 10 个，字段名不能重复；`searchable` 为 true 的字段值会写入搜索索引。需要服务器管理员权限",
+    request_body = UpdateCustomFieldsRequest,
+    responses(
+        (status = 200, description = "替换后的自定义字段", body = CustomFieldListResponse),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "字段名重复: QQ群", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "无权限编辑该服务器",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_custom_fields(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    _manager: RequireServerRole<Admin>,
+    Json(request): Json<UpdateCustomFieldsRequest>,
+) -> ApiResult<Json<CustomFieldListResponse>> {
+    let db = &app_state.db;
+
+    let data = CustomFieldService::replace(db, server_id, request).await?;
+    Ok(Json(CustomFieldListResponse { data }))
+}
+
+/// 获取服务器信息修订版本
+#[utoipa::path(
+    get,
+    operation_id = "list_server_revisions",
+    path = "/v2/servers/{server_id}/revisions",
+    summary = "获取服务器信息修订版本",
+    description = "返回服务器名称、描述与标签的历史版本，最多保留最近 50 个；仅服务器管理员可见",
+    responses(
+        (status = 200, description = "成功获取修订版本", body = ServerRevisionListResponse),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401}),
+        ),
+        (
+            status = 403,
+            description = "无权限查看",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403}),
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_server_revisions(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    _manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<ServerRevisionListResponse>> {
+    let db = &app_state.db;
+
+    let data = ServerRevisionService::list(db, server_id).await?;
+    Ok(Json(ServerRevisionListResponse { data }))
+}
+
+/// 回滚服务器信息到指定版本
+#[utoipa::path(
+    post,
+    operation_id = "rollback_server_revision",
+    path = "/v2/servers/{server_id}/revisions/{revision_id}/rollback",
+    summary = "回滚服务器信息",
+    description = "将名称、描述与标签恢复为指定版本的内容，回滚本身也会产生一个新版本；仅服主可操作",
+    responses(
+        (status = 200, description = "回滚成功", body = ServerDetail),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401}),
+        ),
+        (
+            status = 403,
+            description = "只有服主可以回滚",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403}),
+        ),
+        (
+            status = 404,
+            description = "版本不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "版本不存在", "code": "NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ("revision_id" = i32, Path, description = "修订记录 ID")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn rollback_server_revision(
+    State(app_state): State<AppState>,
+    Path((server_id, revision_id)): Path<(i32, i32)>,
+    manager: RequireServerRole<Owner>,
+) -> ApiResult<Json<ServerDetail>> {
+    let db = &app_state.db;
+
+    ServerRevisionService::rollback(db, server_id, revision_id, manager.id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerRolledBack,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "revision_id": revision_id })),
+    )
+    .await;
+
+    let detail = ServerService::get_server_detail(
+        db,
+        Some(manager.id),
+        server_id,
+        ServerDetailView::Private,
+    )
+    .await?;
+    Ok(Json(detail))
+}
+
+/// 提交服务器发布审核
+#[utoipa::path(
+    post,
+    operation_id = "submit_server_for_review",
+    path = "/v2/servers/{server_id}/submit",
+    summary = "提交服务器发布审核",
+    description = "将草稿、未通过或已归档的服务器提交给 MSCPO 审核，审核通过后才会出现在列表与搜索中；需要服务器管理员权限",
+    responses(
+        (status = 200, description = "提交成功", body = ServerDetail),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "无权限编辑该服务器",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "服务器已在等待审核或已发布",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器已在等待审核", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn submit_server_for_review(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+) -> ApiResult<Json<ServerDetail>> {
+    let db = &app_state.db;
+
+    ServerService::submit_for_review(db, server_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerSubmitted,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    let detail = ServerService::get_server_detail(
+        db,
+        Some(manager.id),
+        server_id,
+        ServerDetailView::Private,
+    )
+    .await?;
+    Ok(Json(detail))
+}
+
+/// 归档服务器
+#[utoipa::path(
+    post,
+    operation_id = "archive_server",
+    path = "/v2/servers/{server_id}/archive",
+    summary = "归档服务器",
+    description = "将服务器从列表、搜索与站点地图中撤下但保留全部数据，重新提交审核并通过后恢复发布；仅服主可操作",
+    responses(
+        (status = 200, description = "归档成功", body = ServerDetail),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "只有服主可以归档",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn archive_server(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Owner>,
+) -> ApiResult<Json<ServerDetail>> {
+    let db = &app_state.db;
+
+    ServerService::archive(db, server_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerArchived,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    let detail = ServerService::get_server_detail(
+        db,
+        Some(manager.id),
+        server_id,
+        ServerDetailView::Private,
+    )
+    .await?;
+    Ok(Json(detail))
+}
+
+/// 推荐服务器标签
+#[utoipa::path(
+    post,
+    operation_id = "suggest_server_tags",
+    path = "/v2/servers/{server_id}/tags/suggest",
+    summary = "推荐服务器标签",
+    description = "根据描述中的关键词与全站标签共现情况推荐最多 7 个标签；请求体可选，可传入编辑中的描述与已选标签，缺省时使用服务器当前信息",
+    request_body(content = Option<TagSuggestRequest>, content_type = "application/json"),
+    responses(
+        (status = 200, description = "推荐结果", body = TagSuggestionResponse),
+        (
+            status = 401,
+            description = "未授权",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401}),
+        ),
+        (
+            status = 403,
+            description = "无权限",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403}),
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn suggest_server_tags(
+    ReadDb(db): ReadDb,
+    Path(server_id): Path<i32>,
+    _manager: RequireServerRole<Admin>,
+    request: Option<Json<TagSuggestRequest>>,
+) -> ApiResult<Json<TagSuggestionResponse>> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let data = TagSuggestionService::suggest_for_server(&db, server_id, request.desc, request.tags)
+        .await?;
+    Ok(Json(TagSuggestionResponse { data }))
+}
+
+/// 获取相似服务器
+#[utoipa::path(
+    get,
+    operation_id = "get_similar_servers",
+    path = "/v2/servers/{server_id}/similar",
+    summary = "获取相似服务器",
+    description = "推荐与指定服务器相似的服务器。开启向量推荐时按描述向量相似度排序，否则按标签重合度排序；不包含隐藏与停用的服务器",
+    responses(
+        (status = 200, description = "相似服务器", body = SimilarServersResponse),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "limit 需在 1~20 之间", "code": "BAD_REQUEST", "status": 400}),
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        SimilarServersQuery
+    )
+)]
+pub async fn get_similar_servers(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Query(query): Query<SimilarServersQuery>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<SimilarServersResponse>> {
+    if !(1..=20).contains(&query.limit) {
+        return Err(ApiError::BadRequest("limit 需在 1~20 之间".to_string()));
+    }
+
+    let user_id = user_claims.map(|claims| claims.0.id);
+    let use_embeddings =
+        FeatureFlagService::is_enabled(&db, FLAG_SIMILAR_EMBEDDINGS, user_id).await;
+    let response = SimilarServerService::find_similar(
+        &db,
+        tenant.id(),
+        server_id,
+        query.limit as usize,
+        use_embeddings,
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+/// 获取服务器 MOTD 与版本变化时间线
+#[utoipa::path(
+    get,
+    operation_id = "get_server_timeline",
+    path = "/v2/servers/{server_id}/timeline",
+    summary = "获取服务器 MOTD 与版本变化时间线",
+    description = "根据状态数据推送与采集的差异，返回服务器 MOTD 与上报版本的变化记录，可以看到服务器何时升级版本或更换宣传语；每个字段最多保留最近 100 条",
+    responses(
+        (status = 200, description = "变化记录", body = ServerTimelineResponse),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "limit 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400}),
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        ServerTimelineQuery
+    )
+)]
+pub async fn get_server_timeline(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Query(query): Query<ServerTimelineQuery>,
+) -> ApiResult<Json<ServerTimelineResponse>> {
+    if !(1..=100).contains(&query.limit) {
+        return Err(ApiError::BadRequest("limit 需在 1~100 之间".to_string()));
+    }
+
+    let response =
+        ServerTimelineService::list(&db, tenant.id(), server_id, query.field, query.limit).await?;
+    Ok(Json(response))
+}
+
+/// 获取服务器历史在线人数
+#[utoipa::path(
+    get,
+    operation_id = "get_server_stats_history",
+    path = "/v2/servers/{server_id}/stats/history",
+    summary = "获取服务器历史在线人数",
+    description = "返回时间范围内的在线人数采样，用于绘制在线人数与在线率曲线。指定 buckets 时把时间范围等分后按区间聚合；不指定时返回原始采样，采样超过 2000 条时自动按 2000 个区间聚合",
+    responses(
+        (status = 200, description = "历史状态", body = StatsHistoryResponse),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "buckets 需在 1~1000 之间", "code": "BAD_REQUEST", "status": 400}),
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器 ID"),
+        StatsHistoryQuery
+    )
+)]
+pub async fn get_server_stats_history(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    Query(query): Query<StatsHistoryQuery>,
+) -> ApiResult<Json<StatsHistoryResponse>> {
+    if query
+        .buckets
+        .is_some_and(|buckets| !(1..=1000).contains(&buckets))
+    {
+        return Err(ApiError::BadRequest("buckets 需在 1~1000 之间".to_string()));
+    }
+
+    let response =
+        StatsHistoryService::history(&db, tenant.id(), server_id, query.range, query.buckets)
+            .await?;
+    Ok(Json(response))
+}
+
+/// 获取服务器在线率
+#[utoipa::path(
+    get,
+    operation_id = "get_server_uptime",
+    path = "/v2/servers/{server_id}/uptime",
+    summary = "获取服务器在线率",
+    description = "返回服务器当前的在线状态、连续在线时长，以及最近 24 小时、7 天与 30 天的在线率。在线率按状态采集记录的在线与离线切换计算，两次切换之间视为保持前一次的状态，只统计开始记录之后的时长",
+    responses(
+        (status = 200, description = "在线率", body = ServerUptimeResponse),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID"))
+)]
+pub async fn get_server_uptime(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+) -> ApiResult<Json<ServerUptimeResponse>> {
+    let response = ServerUptimeService::uptime(&db, tenant.id(), server_id).await?;
+    Ok(Json(response))
+}
+
+/// 收藏服务器
+#[utoipa::path(
+    post,
+    operation_id = "favorite_server",
+    path = "/v2/servers/{server_id}/favorite",
+    summary = "收藏服务器",
+    description = "把服务器加入当前用户的收藏，已收藏时直接返回成功。隐藏的服务器只有其成员可以收藏",
+    responses(
+        (status = 200, description = "已收藏", body = SuccessResponse, example = json!({"message": "已收藏"})),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn favorite_server(
+    State(app_state): State<AppState>,
+    tenant: CurrentTenant,
+    Path(server_id): Path<i32>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    let db = &app_state.db;
+    ServerService::ensure_in_tenant(db, server_id, tenant.id()).await?;
+
+    FavoriteService::add(db, claims.id, server_id).await?;
+    Ok(Json(SuccessResponse {
+        message: "已收藏".to_string(),
+    }))
+}
+
+/// 取消收藏服务器
+#[utoipa::path(
+    delete,
+    operation_id = "unfavorite_server",
+    path = "/v2/servers/{server_id}/favorite",
+    summary = "取消收藏服务器",
+    description = "把服务器移出当前用户的收藏，未收藏时直接返回成功",
+    responses(
+        (status = 200, description = "已取消收藏", body = SuccessResponse, example = json!({"message": "已取消收藏"})),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "servers",
+    params(("server_id" = i32, Path, description = "服务器 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn unfavorite_server(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    FavoriteService::remove(&app_state.db, claims.id, server_id).await?;
+    Ok(Json(SuccessResponse {
+        message: "已取消收藏".to_string(),
+    }))
+}
+
+/// 订阅服务器实时更新
+#[utoipa::path(
+    get,
+    operation_id = "live_updates",
+    path = "/v2/servers/live",
+    summary = "订阅服务器实时更新",
+    description = "Server-Sent Events 长连接。订阅的服务器资料修改（ServerUpdated）或状态刷新（StatsRefreshed）时推送事件，事件数据中只包含订阅的服务器 ID；推送前相关缓存已清除，收到后重新拉取即可读到最新数据。收到 Resync 事件表示有事件丢失，需要重新拉取全部数据",
+    responses(
+        (status = 200, description = "事件流", content_type = "text/event-stream", body = String),
+        (
+            status = 400,
+            description = "参数错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "ids 数量需在 1~50 之间", "code": "BAD_REQUEST", "status": 400}),
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404}),
+        )
+    ),
+    tag = "servers",
+    params(LiveUpdatesQuery)
+)]
+pub async fn live_updates(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Query(query): Query<LiveUpdatesQuery>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    let ids = query.parse_ids().map_err(ApiError::BadRequest)?;
+    let ids = ServerService::filter_active_ids(&db, tenant.id(), &ids).await?;
+    if ids.is_empty() {
+        return Err(ApiError::server_not_found());
+    }
+
+    let subscription = LiveUpdateService::subscribe();
+    let stream = stream::unfold((subscription, ids), |(mut subscription, ids)| async move {
+        loop {
+            let event = match subscription.next().await? {
+                LiveUpdate::Event(event) => {
+                    let matched: Vec<i32> = event
+                        .server_ids()
+                        .iter()
+                        .copied()
+                        .filter(|id| ids.binary_search(id).is_ok())
+                        .collect();
+                    if matched.is_empty() {
+                        continue;
+                    }
+
+                    let mut data = serde_json::to_value(&event)
+                        .map(|mut value| value["data"].take())
+                        .unwrap_or_default();
+                    data["server_ids"] = serde_json::json!(matched);
+                    Event::default().event(event.name()).json_data(data)
+                }
+                LiveUpdate::Resync => Ok(Event::default().event("Resync").data("{}")),
+            };
+            return Some((event, (subscription, ids)));
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 删除服务器
+#[utoipa::path(
+    delete,
+    operation_id = "delete_server",
+    path = "/v2/servers/{server_id}",
+    summary = "删除服务器",
+    description = "两步确认：不带 `confirm_token` 调用时只返回将被删除的数据条数和 5 分钟内有效的确认令牌（202）；带上令牌再次调用才会删除。令牌只能使用一次，期间数据有变化时需要重新确认。只有服务器所有者可以删除",
+    responses(
+        (status = 200, description = "服务器已删除", body = SuccessResponse),
+        (status = 202, description = "需要确认，返回影响范围与确认令牌", body = ConfirmationRequired),
+        (
+            status = 400,
+            description = "确认令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "确认令牌无效或已过期", "code": "INVALID_CONFIRMATION_TOKEN", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "不是服务器所有者",
+            body = ApiErrorResponse,
+            example = json!({"error": "需要服务器所有者权限", "code": "SERVER_OWNER_REQUIRED", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "服务器不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "服务器不存在", "code": "SERVER_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "确认后数据发生变化",
+            body = ApiErrorResponse,
+            example = json!({"error": "待删除的内容已发生变化，请重新确认", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器ID"),
+        ConfirmQuery
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_server(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Owner>,
+    Query(query): Query<ConfirmQuery>,
+) -> ApiResult<Response> {
+    let db = &app_state.db;
+
+    let impact = ServerService::deletion_impact(db, server_id).await?;
+    if let Some(required) = ConfirmationService::guard(
+        DestructiveAction::DeleteServer,
+        manager.id,
+        &server_id.to_string(),
+        impact,
+        query.confirm_token.as_deref(),
+    )
+    .await?
+    {
+        return Ok((StatusCode::ACCEPTED, Json(required)).into_response());
+    }
+
+    ServerService::delete_server(db, app_state.storage.as_deref(), server_id).await?;
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::ServerDeleted,
+        Some((TARGET_SERVER, server_id)),
+        None,
+    )
+    .await;
+
+    Ok(Json(SuccessResponse {
+        message: "服务器已删除".to_string(),
+    })
+    .into_response())
+}
+
+/// 批量删除画册图片
+#[utoipa::path(
+    delete,
+    operation_id = "delete_gallery_images",
+    path = "/v2/servers/{server_id}/gallery",
+    summary = "批量删除画册图片",
+    description = "两步确认：不带 `confirm_token` 调用时只返回将被删除的图片数和确认令牌（202）；带上令牌并使用相同的 `image_ids` 再次调用才会删除",
+    responses(
+        (status = 200, description = "图片已删除", body = SuccessResponse),
+        (status = 202, description = "需要确认，返回影响范围与确认令牌", body = ConfirmationRequired),
+        (
+            status = 400,
+            description = "参数或确认令牌无效",
+            body = ApiErrorResponse,
+            examples(
+                ("图片 ID 无效" = (value = json!({"error": "image_ids 数量需在 1~100 之间", "code": "BAD_REQUEST", "status": 400}))),
+                ("令牌无效" = (value = json!({"error": "确认令牌无效或已过期", "code": "INVALID_CONFIRMATION_TOKEN", "status": 400})))
+            )
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未授权", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "权限不足",
+            body = ApiErrorResponse,
+            examples(
+                ("权限不足" = (value = json!({"error": "需要服务器管理员权限", "code": "SERVER_MANAGER_REQUIRED", "status": 403}))),
+                ("图片不属于该服务器" = (value = json!({"error": "图片不属于该服务器", "code": "FORBIDDEN", "status": 403})))
+            )
+        ),
+        (
+            status = 404,
+            description = "未找到服务器或图片",
+            body = ApiErrorResponse,
+            example = json!({"error": "图片不存在", "code": "IMAGE_NOT_FOUND", "status": 404})
+        ),
+        (
+            status = 409,
+            description = "确认后数据发生变化",
+            body = ApiErrorResponse,
+            example = json!({"error": "待删除的内容已发生变化，请重新确认", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "servers",
+    params(
+        ("server_id" = i32, Path, description = "服务器ID"),
+        GalleryBatchDeleteQuery
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_gallery_images(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    manager: RequireServerRole<Admin>,
+    Query(query): Query<GalleryBatchDeleteQuery>,
+) -> ApiResult<Response> {
+    let image_ids = query.parse_ids().map_err(ApiError::BadRequest)?;
+    let db = &app_state.db;
+
+    let impact = ServerService::gallery_deletion_impact(db, server_id, &image_ids).await?;
+    let target = format!(
+        "{}:{}",
+        server_id,
+        image_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    if let Some(required) = ConfirmationService::guard(
+        DestructiveAction::DeleteGalleryImages,
+        manager.id,
+        &target,
+        impact,
+        query.confirm_token.as_deref(),
+    )
+    .await?
+    {
+        return Ok((StatusCode::ACCEPTED, Json(required)).into_response());
+    }
+
+    for image_id in &image_ids {
+        ServerService::delete_gallery_image(db, app_state.storage.as_deref(), server_id, *image_id)
+            .await?;
+    }
+    ActivityService::record(
+        db,
+        manager.id,
+        ActivityAction::GalleryImageDeleted,
+        Some((TARGET_SERVER, server_id)),
+        Some(serde_json::json!({ "image_ids": image_ids })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse {
+        message: format!("成功删除 {} 张画册图片", image_ids.len()),
+    })
+    .into_response())
 }
@@ -0,0 +1,86 @@
+use std::time::UNIX_EPOCH;
+
+use axum::{
+    extract::{Path, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, X_CONTENT_TYPE_OPTIONS},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    config::StorageConfig,
+    errors::{ApiError, ApiResult},
+    services::{file_upload::QUARANTINE_PREFIX, storage::LocalStorage},
+    AppState,
+};
+
+/// 提供本地存储中的文件，只在使用本地存储时挂载
+///
+/// 文件名随机生成且内容不会改变，响应带长期缓存与 `ETag`；隔离区中的文件不对外提供。
+pub async fn serve_static(
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let not_found = || ApiError::NotFound("文件不存在".to_string());
+    let Some(StorageConfig::Local(config)) = app_state.config.storage.as_ref() else {
+        return Err(not_found());
+    };
+    if key.starts_with(&format!("{QUARANTINE_PREFIX}/")) {
+        return Err(not_found());
+    }
+    let path = LocalStorage::new(config)
+        .resolve(&key)
+        .ok_or_else(not_found)?;
+
+    let metadata = match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return Err(not_found()),
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let etag = format!("\"{:x}-{:x}\"", metadata.len(), modified);
+    let cache_control = format!("public, max-age={}, immutable", config.cache_max_age_secs);
+
+    let mut response = if headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag))
+    {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let content = tokio::fs::read(&path)
+            .await
+            .map_err(|e| ApiError::Internal(format!("读取文件失败: {e}")))?;
+        ([(CONTENT_TYPE, content_type(&key))], content).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        response_headers.insert(CACHE_CONTROL, value);
+    }
+    // 文件由用户上传，禁止浏览器按内容猜测类型
+    response_headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    Ok(response)
+}
+
+fn content_type(key: &str) -> &'static str {
+    let extension = key
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("webp") => "image/webp",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
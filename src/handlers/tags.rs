@@ -0,0 +1,49 @@
+use crate::{
+    errors::ApiResult,
+    extract::{Json, Query},
+    middleware::{CurrentTenant, ReadDb},
+    schemas::servers::{TagListQuery, TagListResponse},
+    services::tags::TagService,
+};
+
+/// 标签接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        list_tags
+    ),
+    tags((name = "tags", description = "Server tag endpoints"))
+)]
+pub struct TagsApi;
+
+/// 热门标签默认返回数量
+const TAGS_DEFAULT_LIMIT: u32 = 50;
+/// 热门标签最大返回数量
+const TAGS_MAX_LIMIT: u32 = 200;
+
+#[utoipa::path(
+    get,
+    operation_id = "list_tags",
+    summary = "热门标签",
+    description = "返回当前租户公开服务器正在使用的标签及使用次数，按次数降序，并附带管理员维护的标签词表。词表不为空时，创建或编辑服务器只能从词表中选择标签。结果缓存 5 分钟，服务器资料修改或词表变更后清除。",
+    path = "/v2/tags",
+    tag = "tags",
+    params(TagListQuery),
+    responses(
+        (status = 200, description = "标签列表", body = TagListResponse),
+    )
+)]
+pub async fn list_tags(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    Query(query): Query<TagListQuery>,
+) -> ApiResult<Json<TagListResponse>> {
+    let limit = query
+        .limit
+        .unwrap_or(TAGS_DEFAULT_LIMIT)
+        .clamp(1, TAGS_MAX_LIMIT) as usize;
+
+    let mut response = TagService::popular(&db, tenant.id()).await?;
+    response.data.truncate(limit);
+    Ok(Json(response))
+}
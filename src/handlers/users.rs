@@ -0,0 +1,1314 @@
+use axum::{
+    extract::{Extension, OriginalUri, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_typed_multipart::TypedMultipart;
+use validator::Validate;
+
+use crate::{
+    config::EmailConfig,
+    errors::{ApiError, ApiErrorResponse, ApiResult, RateLimitedErrorResponse},
+    extract::{Json, Query},
+    handlers::auth::ensure_code_valid,
+    middleware::{CurrentTenant, ReadDb, UserClaims},
+    schemas::{
+        auth::{AuthToken, OAuthAuthorizeResponse},
+        confirm::{ConfirmQuery, ConfirmationRequired, DestructiveAction},
+        pagination::{Page, Paginated},
+        servers::SuccessResponse,
+        users::{
+            ActivityAction, ActivityInfo, ActivityQuery, ApiKeyListResponse, ChangePasswordRequest,
+            ConfirmEmailChangeRequest, CreateApiKeyRequest, CreatedApiKey, EmailChangeRequest,
+            ExternalIdentityListResponse, FavoriteQuery, FavoriteServer, InitiateLinkRequest,
+            InitiateLinkResponse, MarkNotificationsReadRequest, MarkNotificationsReadResponse,
+            NotificationInfo, NotificationQuery, RevokeSessionsResponse, SessionListResponse,
+            UnreadNotificationCount, UpdatePreferencesRequest, UpdateProfileRequest,
+            UploadAvatarRequest, UserPreferences, UserProfile,
+        },
+    },
+    services::{
+        account::AccountService,
+        account_link::AccountLinkService,
+        activity::ActivityService,
+        api_key::ApiKeyService,
+        auth::{AuthService, Claims, JwtData, SessionClient},
+        confirm::ConfirmationService,
+        email::suppression::EmailSuppressionService,
+        favorite::FavoriteService,
+        notification::NotificationService,
+        oauth::OAuthService,
+        preferences::PreferenceService,
+    },
+    AppState,
+};
+
+/// 当前用户接口文档
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        get_profile,
+        update_profile,
+        upload_avatar,
+        get_my_activity,
+        list_my_favorites,
+        initiate_link,
+        initiate_oauth_link,
+        list_links,
+        revoke_link,
+        list_api_keys,
+        create_api_key,
+        revoke_api_key,
+        list_sessions,
+        revoke_session,
+        revoke_other_sessions,
+        list_notifications,
+        mark_notifications_read,
+        get_unread_notification_count,
+        get_preferences,
+        update_preferences,
+        request_email_change,
+        confirm_email_change,
+        change_password,
+        delete_account
+    ),
+    components(schemas(
+        ActivityQuery,
+        ActivityAction,
+        FavoriteQuery,
+        NotificationQuery,
+        ConfirmQuery
+    )),
+    tags((name = "users", description = "Current user endpoints"))
+)]
+pub struct UsersApi;
+
+/// 获取当前用户资料
+#[utoipa::path(
+    get,
+    operation_id = "get_profile",
+    path = "/v2/users/me",
+    summary = "获取当前用户资料",
+    description = "返回当前登录用户的基本资料与头像地址",
+    responses(
+        (status = 200, description = "成功获取用户资料", body = UserProfile),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_profile(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    Ok(Json(AccountService::profile(&db, claims.id).await?))
+}
+
+/// 更新当前用户资料
+#[utoipa::path(
+    patch,
+    operation_id = "update_profile",
+    path = "/v2/users/me",
+    summary = "更新当前用户资料",
+    description = "修改显示名称或上传新头像，未传的字段保持不变。显示名称同样受保留字与仿冒管理人员检查约束",
+    request_body(content = UpdateProfileRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "更新后的用户资料", body = UserProfile),
+        (
+            status = 400,
+            description = "参数无效或图片不符合要求",
+            body = ApiErrorResponse,
+            example = json!({"error": "头像尺寸不能小于 64*64", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 501,
+            description = "未配置对象存储，无法上传头像",
+            body = ApiErrorResponse
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_profile(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    TypedMultipart(request): TypedMultipart<UpdateProfileRequest>,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let profile = AccountService::update_profile(
+        &app_state.db,
+        app_state.storage.as_deref(),
+        &app_state.config.name_policy,
+        claims.id,
+        request,
+    )
+    .await?;
+    Ok(Json(profile))
+}
+
+/// 上传当前用户头像
+#[utoipa::path(
+    post,
+    operation_id = "upload_avatar",
+    path = "/v2/users/me/avatar",
+    summary = "上传头像",
+    description = "非正方形图片会居中裁剪，之后生成 256px 与 64px 两种尺寸的 WebP 图片，返回更新后的用户资料",
+    request_body(content = UploadAvatarRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "更新后的用户资料", body = UserProfile),
+        (
+            status = 400,
+            description = "图片不符合要求",
+            body = ApiErrorResponse,
+            example = json!({"error": "头像尺寸不能小于 64*64", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 501,
+            description = "未配置对象存储，无法上传头像",
+            body = ApiErrorResponse
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn upload_avatar(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    TypedMultipart(request): TypedMultipart<UploadAvatarRequest>,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let profile = AccountService::update_avatar(
+        &app_state.db,
+        app_state.storage.as_deref(),
+        claims.id,
+        request.avatar.contents.to_vec(),
+    )
+    .await?;
+    Ok(Json(profile))
+}
+
+/// 获取当前用户的操作记录
+#[utoipa::path(
+    get,
+    operation_id = "get_my_activity",
+    path = "/v2/users/me/activity",
+    summary = "获取当前用户的操作记录",
+    description = "按时间倒序分页返回编辑服务器、上传图片、审核等操作记录",
+    responses(
+        (status = 200, description = "成功获取操作记录", body = Paginated<ActivityInfo>),
+        (
+            status = 400,
+            description = "无效的分页参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 不能小于 1，page_size 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    params(ActivityQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_my_activity(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<ActivityQuery>,
+) -> ApiResult<Page<Paginated<ActivityInfo>>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let (data, total) = ActivityService::list(&db, claims.id, query.page, query.page_size).await?;
+
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 获取当前用户收藏的服务器
+#[utoipa::path(
+    get,
+    operation_id = "list_my_favorites",
+    path = "/v2/users/me/favorites",
+    summary = "获取当前用户收藏的服务器",
+    description = "按收藏时间倒序分页返回，服务器信息与服务器列表中的条目一致。已停用的服务器，以及用户不是其成员的隐藏服务器不会返回",
+    responses(
+        (status = 200, description = "成功获取收藏列表", body = Paginated<FavoriteServer>),
+        (
+            status = 400,
+            description = "无效的分页参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 不能小于 1，page_size 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    params(FavoriteQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_my_favorites(
+    ReadDb(db): ReadDb,
+    tenant: CurrentTenant,
+    user_claims: Option<Extension<Claims>>,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<FavoriteQuery>,
+) -> ApiResult<Page<Paginated<FavoriteServer>>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let (data, total) =
+        FavoriteService::list(&db, tenant.id(), claims.id, query.page, query.page_size).await?;
+
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 发起外部账户绑定
+#[utoipa::path(
+    post,
+    operation_id = "initiate_link",
+    path = "/v2/users/me/links",
+    summary = "发起外部账户绑定",
+    description = "生成一次性绑定凭证，用户在外部平台（如 MSCPO 论坛）提交该凭证后由平台确认绑定；同一平台未完成的绑定会被替换",
+    request_body(content = InitiateLinkRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "已生成绑定凭证", body = InitiateLinkResponse),
+        (
+            status = 400,
+            description = "不支持的平台",
+            body = ApiErrorResponse,
+            example = json!({"error": "不支持的平台: example", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 409,
+            description = "已绑定该平台账户",
+            body = ApiErrorResponse,
+            example = json!({"error": "已绑定该平台账户，请先解除绑定", "code": "CONFLICT", "status": 409})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn initiate_link(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<InitiateLinkRequest>,
+) -> ApiResult<Json<InitiateLinkResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let result = AccountLinkService::initiate(
+        &app_state.db,
+        &app_state.config.account_link,
+        claims.id,
+        &request.provider,
+    )
+    .await?;
+
+    Ok(Json(result))
+}
+
+/// 发起第三方账户绑定
+#[utoipa::path(
+    post,
+    operation_id = "initiate_oauth_link",
+    path = "/v2/users/me/links/oauth/{provider}",
+    summary = "发起第三方账户绑定",
+    description = "生成第三方平台（github、microsoft）的授权地址，用户授权后携带当前令牌调用第三方登录回调完成绑定；绑定后即可使用该平台登录。管理员代入身份时不能绑定",
+    params(("provider" = String, Path, description = "登录平台：github 或 microsoft")),
+    responses(
+        (status = 200, description = "授权地址", body = OAuthAuthorizeResponse),
+        (
+            status = 400,
+            description = "不支持的登录方式",
+            body = ApiErrorResponse,
+            example = json!({"error": "不支持的登录方式: example", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能绑定第三方账户",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能绑定第三方账户", "code": "FORBIDDEN", "status": 403})
+        ),
+        (status = 501, description = "未配置该平台的登录", body = ApiErrorResponse),
+        (status = 503, description = "第三方登录暂时不可用", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn initiate_oauth_link(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    tenant: CurrentTenant,
+    Path(provider): Path<String>,
+) -> ApiResult<Json<OAuthAuthorizeResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "绑定第三方账户")?;
+    let provider = OAuthService::provider(&provider)?;
+
+    let response = OAuthService::authorize(
+        &app_state.config.oauth,
+        provider,
+        tenant.id(),
+        Some(claims.id),
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+/// 获取当前用户的外部账户绑定
+#[utoipa::path(
+    get,
+    operation_id = "list_links",
+    path = "/v2/users/me/links",
+    summary = "获取外部账户绑定",
+    description = "返回已绑定与等待确认的外部账户",
+    responses(
+        (status = 200, description = "成功获取绑定列表", body = ExternalIdentityListResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_links(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<ExternalIdentityListResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let data = AccountLinkService::list(&db, claims.id).await?;
+    Ok(Json(ExternalIdentityListResponse { data }))
+}
+
+/// 解除外部账户绑定
+#[utoipa::path(
+    delete,
+    operation_id = "revoke_link",
+    path = "/v2/users/me/links/{link_id}",
+    summary = "解除外部账户绑定",
+    description = "解除已绑定的外部账户，或取消等待确认的绑定",
+    responses(
+        (status = 200, description = "已解除绑定", body = SuccessResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 404,
+            description = "绑定不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "绑定不存在", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "users",
+    params(("link_id" = i32, Path, description = "绑定记录 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_link(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Path(link_id): Path<i32>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    AccountLinkService::revoke_by_user(&app_state.db, claims.id, link_id).await?;
+    Ok(Json(SuccessResponse {
+        message: "已解除绑定".to_string(),
+    }))
+}
+
+/// 获取当前用户的 API 密钥
+#[utoipa::path(
+    get,
+    operation_id = "list_api_keys",
+    path = "/v2/users/me/api-keys",
+    summary = "获取 API 密钥",
+    description = "返回未吊销的 API 密钥，只包含密钥开头几位。API 密钥本身不能调用密钥管理接口",
+    responses(
+        (status = 200, description = "成功获取密钥列表", body = ApiKeyListResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_api_keys(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<ApiKeyListResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let data = ApiKeyService::list(&db, claims.id).await?;
+    Ok(Json(ApiKeyListResponse { data }))
+}
+
+/// 创建 API 密钥
+#[utoipa::path(
+    post,
+    operation_id = "create_api_key",
+    path = "/v2/users/me/api-keys",
+    summary = "创建 API 密钥",
+    description = "创建供自动化工具使用的 API 密钥，放在 `X-Api-Key` 请求头中代替登录令牌。\
+                   `read_only` 只能调用 GET 等只读接口，`manage_server` 还可以修改自己有权管理的服务器；\
+                   两者都不能访问管理后台与密钥管理接口。完整密钥只在本次响应中返回，每个用户最多 20 个；\
+                   管理员代入身份时不能创建密钥",
+    request_body(content = CreateApiKeyRequest, content_type = "application/json"),
+    responses(
+        (status = 201, description = "成功创建密钥", body = CreatedApiKey),
+        (
+            status = 400,
+            description = "请求数据不合法或密钥数量已达上限",
+            body = ApiErrorResponse,
+            example = json!({"error": "最多只能创建 20 个 API 密钥，请先吊销不用的密钥", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能管理 API 密钥",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能管理 API 密钥", "code": "FORBIDDEN", "status": 403})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_api_key(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> ApiResult<(StatusCode, Json<CreatedApiKey>)> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "管理 API 密钥")?;
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let created =
+        ApiKeyService::create(&app_state.db, claims.id, &request.name, request.scope).await?;
+    ActivityService::record(
+        &app_state.db,
+        claims.id,
+        ActivityAction::ApiKeyCreated,
+        None,
+        Some(serde_json::json!({
+            "key_id": created.api_key.id,
+            "scope": request.scope.as_str(),
+        })),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// 吊销 API 密钥
+#[utoipa::path(
+    delete,
+    operation_id = "revoke_api_key",
+    path = "/v2/users/me/api-keys/{key_id}",
+    summary = "吊销 API 密钥",
+    description = "吊销后使用该密钥的请求立即返回 401",
+    responses(
+        (status = 200, description = "已吊销密钥", body = SuccessResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能管理 API 密钥",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能管理 API 密钥", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 404,
+            description = "密钥不存在",
+            body = ApiErrorResponse,
+            example = json!({"error": "API 密钥不存在", "code": "NOT_FOUND", "status": 404})
+        )
+    ),
+    tag = "users",
+    params(("key_id" = i32, Path, description = "密钥 ID")),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_api_key(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Path(key_id): Path<i32>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "管理 API 密钥")?;
+
+    ApiKeyService::revoke(&app_state.db, claims.id, key_id).await?;
+    ActivityService::record(
+        &app_state.db,
+        claims.id,
+        ActivityAction::ApiKeyRevoked,
+        None,
+        Some(serde_json::json!({ "key_id": key_id })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse {
+        message: "已吊销密钥".to_string(),
+    }))
+}
+
+/// 获取当前用户的登录会话
+#[utoipa::path(
+    get,
+    operation_id = "list_sessions",
+    path = "/v2/users/me/sessions",
+    summary = "获取登录会话",
+    description = "返回未过期的登录会话及其 IP、User-Agent 与最近活动时间，当前会话排在最前。API 密钥不能调用会话管理接口",
+    responses(
+        (status = 200, description = "成功获取会话列表", body = SessionListResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (status = 503, description = "会话服务暂时不可用", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_sessions(
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<SessionListResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let data = AuthService::list_sessions(claims.id, claims.jti.as_deref())
+        .await
+        .map_err(session_unavailable)?;
+    Ok(Json(SessionListResponse { data }))
+}
+
+/// 注销一个登录会话
+#[utoipa::path(
+    delete,
+    operation_id = "revoke_session",
+    path = "/v2/users/me/sessions/{session_id}",
+    summary = "注销登录会话",
+    description = "吊销指定会话的令牌，该设备需要重新登录；注销当前会话等同于登出",
+    params(("session_id" = String, Path, description = "会话 ID")),
+    responses(
+        (status = 200, description = "会话已注销", body = SuccessResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (status = 403, description = "代入身份时不能注销会话", body = ApiErrorResponse),
+        (
+            status = 404,
+            description = "会话不存在或已过期",
+            body = ApiErrorResponse,
+            example = json!({"error": "会话不存在或已过期", "code": "NOT_FOUND", "status": 404})
+        ),
+        (status = 503, description = "会话服务暂时不可用", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_session(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "注销会话")?;
+
+    let revoked = AuthService::revoke_session(claims.id, &session_id)
+        .await
+        .map_err(session_unavailable)?;
+    if !revoked {
+        return Err(ApiError::NotFound("会话不存在或已过期".to_string()));
+    }
+    ActivityService::record(
+        &app_state.db,
+        claims.id,
+        ActivityAction::SessionsRevoked,
+        None,
+        Some(serde_json::json!({ "count": 1 })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse {
+        message: "会话已注销".to_string(),
+    }))
+}
+
+/// 注销其他登录会话
+#[utoipa::path(
+    delete,
+    operation_id = "revoke_other_sessions",
+    path = "/v2/users/me/sessions",
+    summary = "注销其他登录会话",
+    description = "吊销除当前会话以外的所有会话，用于怀疑账户在其他设备上被盗用时",
+    responses(
+        (status = 200, description = "其他会话已注销", body = RevokeSessionsResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (status = 403, description = "代入身份时不能注销会话", body = ApiErrorResponse),
+        (status = 503, description = "会话服务暂时不可用", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_other_sessions(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<RevokeSessionsResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "注销会话")?;
+
+    let revoked = AuthService::revoke_sessions_except(claims.id, claims.jti.as_deref())
+        .await
+        .map_err(session_unavailable)?;
+    if revoked > 0 {
+        ActivityService::record(
+            &app_state.db,
+            claims.id,
+            ActivityAction::SessionsRevoked,
+            None,
+            Some(serde_json::json!({ "count": revoked })),
+        )
+        .await;
+    }
+
+    Ok(Json(RevokeSessionsResponse { revoked }))
+}
+
+fn session_unavailable(e: anyhow::Error) -> ApiError {
+    tracing::warn!("⚠️  会话操作失败: {}", e);
+    ApiError::ServiceUnavailable("会话服务暂时不可用".to_string())
+}
+
+/// 获取当前用户的站内通知
+#[utoipa::path(
+    get,
+    operation_id = "list_notifications",
+    path = "/v2/users/me/notifications",
+    summary = "获取站内通知",
+    description = "按时间倒序分页返回站内通知，`unread=true` 时只返回未读通知。每个用户只保留最近的若干条通知，\
+                   关闭站内通知渠道或对应通知类型后不再产生新的站内通知",
+    responses(
+        (status = 200, description = "成功获取通知列表", body = Paginated<NotificationInfo>),
+        (
+            status = 400,
+            description = "无效的分页参数",
+            body = ApiErrorResponse,
+            example = json!({"error": "page 不能小于 1，page_size 需在 1~100 之间", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    params(NotificationQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_notifications(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<NotificationQuery>,
+) -> ApiResult<Page<Paginated<NotificationInfo>>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    if query.page < 1 || query.page_size < 1 || query.page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "page 不能小于 1，page_size 需在 1~100 之间".to_string(),
+        ));
+    }
+
+    let (data, total) =
+        NotificationService::list(&db, claims.id, query.unread, query.page, query.page_size)
+            .await?;
+
+    Ok(Paginated::new(data, query.page, query.page_size, total).with_links(&uri))
+}
+
+/// 标记站内通知为已读
+#[utoipa::path(
+    post,
+    operation_id = "mark_notifications_read",
+    path = "/v2/users/me/notifications/read",
+    summary = "标记通知为已读",
+    description = "标记指定的通知为已读，不传 `ids` 时标记全部未读通知。不属于当前用户或已读的通知会被忽略",
+    request_body(content = MarkNotificationsReadRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "已标记", body = MarkNotificationsReadResponse),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn mark_notifications_read(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<MarkNotificationsReadRequest>,
+) -> ApiResult<Json<MarkNotificationsReadResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let updated = NotificationService::mark_read(&app_state.db, claims.id, request.ids).await?;
+    let unread = NotificationService::unread_count(&app_state.db, claims.id).await?;
+    Ok(Json(MarkNotificationsReadResponse { updated, unread }))
+}
+
+/// 获取未读通知数
+#[utoipa::path(
+    get,
+    operation_id = "get_unread_notification_count",
+    path = "/v2/users/me/notifications/unread-count",
+    summary = "获取未读通知数",
+    description = "返回当前用户的未读站内通知数，供前端显示通知角标",
+    responses(
+        (status = 200, description = "成功获取未读通知数", body = UnreadNotificationCount),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_unread_notification_count(
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<UnreadNotificationCount>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let unread = NotificationService::unread_count(&db, claims.id).await?;
+    Ok(Json(UnreadNotificationCount { unread }))
+}
+
+/// 获取当前用户的偏好设置
+#[utoipa::path(
+    get,
+    operation_id = "get_preferences",
+    path = "/v2/users/me/preferences",
+    summary = "获取偏好设置",
+    description = "返回通知渠道、邮件摘要频率、已关闭的通知类型与语言；未保存过时返回默认值",
+    responses(
+        (status = 200, description = "成功获取偏好设置", body = UserPreferences),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_preferences(
+    State(app_state): State<AppState>,
+    ReadDb(db): ReadDb,
+    user_claims: Option<Extension<Claims>>,
+) -> ApiResult<Json<UserPreferences>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+
+    let preferences =
+        PreferenceService::get(&db, &app_state.config.notification, claims.id).await?;
+    Ok(Json(preferences))
+}
+
+/// 更新当前用户的偏好设置
+#[utoipa::path(
+    put,
+    operation_id = "update_preferences",
+    path = "/v2/users/me/preferences",
+    summary = "更新偏好设置",
+    description = "未传的字段保持不变。所有通知在发送前都会读取这些设置：关闭的渠道与 `muted_kinds` 中的通知类型不发送，`daily` / `weekly` 时邮件通知汇总为摘要发送。`muted_kinds` 传入时整体替换，`account_banned` 不能关闭",
+    request_body(content = UpdatePreferencesRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "更新后的偏好设置", body = UserPreferences),
+        (
+            status = 400,
+            description = "参数无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "通知类型 account_banned 不能关闭", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_preferences(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<UpdatePreferencesRequest>,
+) -> ApiResult<Json<UserPreferences>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+    let preferences = PreferenceService::update(
+        &app_state.db,
+        &app_state.config.notification,
+        claims.id,
+        request,
+    )
+    .await?;
+    Ok(Json(preferences))
+}
+
+/// 申请修改邮箱
+#[utoipa::path(
+    post,
+    operation_id = "request_email_change",
+    path = "/v2/users/me/email",
+    summary = "申请修改邮箱",
+    description = "向新邮箱发送验证码，验证码 5 分钟内有效，同一账户 60 秒内只能申请一次。新邮箱不能已被其他账户使用；管理员代入身份时不能修改邮箱",
+    request_body(content = EmailChangeRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "验证码已发送", body = SuccessResponse),
+        (
+            status = 400,
+            description = "邮箱格式不正确、与当前邮箱相同或无法接收邮件",
+            body = ApiErrorResponse,
+            example = json!({"error": "新邮箱与当前邮箱相同", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能修改邮箱",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能修改邮箱", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 409,
+            description = "邮箱已被其他账户使用",
+            body = ApiErrorResponse,
+            example = json!({"error": "该邮箱已被其他账户使用", "code": "CONFLICT", "status": 409})
+        ),
+        (status = 429, description = "发送过于频繁", body = RateLimitedErrorResponse),
+        (status = 501, description = "未配置发信服务", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn request_email_change(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<EmailChangeRequest>,
+) -> ApiResult<Json<SuccessResponse>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "修改邮箱")?;
+    request
+        .validate()
+        .map_err(|e| ApiError::BadRequest(format!("请求数据不合法: {e}")))?;
+    let new_email = request.new_email;
+
+    let profile = AccountService::profile(&app_state.db, claims.id).await?;
+    if profile.email.eq_ignore_ascii_case(&new_email) {
+        return Err(ApiError::BadRequest("新邮箱与当前邮箱相同".to_string()));
+    }
+    if AccountService::email_taken(&app_state.db, &new_email, claims.id).await? {
+        return Err(ApiError::Conflict("该邮箱已被其他账户使用".to_string()));
+    }
+    if EmailSuppressionService::is_suppressed(&app_state.db, &new_email).await {
+        return Err(ApiError::BadRequest(
+            "该邮箱多次退信或投诉，无法接收邮件，请更换邮箱".to_string(),
+        ));
+    }
+
+    let email_config = EmailConfig::require(app_state.config.email.as_ref())?;
+    AuthService::acquire_email_change_cooldown(claims.id).await?;
+    AuthService::send_email_change_code(claims.id, &new_email, email_config)
+        .await
+        .map_err(|e| ApiError::InternalServerError(format!("发送验证码失败: {e}")))?;
+
+    Ok(Json(SuccessResponse {
+        message: format!("验证码已发送到 {new_email}"),
+    }))
+}
+
+/// 确认修改邮箱
+#[utoipa::path(
+    post,
+    operation_id = "confirm_email_change",
+    path = "/v2/users/me/email/confirm",
+    summary = "确认修改邮箱",
+    description = "使用新邮箱收到的验证码完成修改，并向原邮箱发送修改通知。验证码只对申请时填写的新邮箱有效，输错 5 次后作废",
+    request_body(content = ConfirmEmailChangeRequest, content_type = "application/json"),
+    responses(
+        (status = 200, description = "修改后的用户资料", body = UserProfile),
+        (
+            status = 400,
+            description = "参数无效或验证码错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "验证码无效", "code": "VERIFICATION_CODE_INVALID", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能修改邮箱",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能修改邮箱", "code": "FORBIDDEN", "status": 403})
+        ),
+        (
+            status = 409,
+            description = "邮箱已被其他账户使用",
+            body = ApiErrorResponse,
+            example = json!({"error": "该邮箱已被其他账户使用", "code": "CONFLICT", "status": 409})
+        ),
+        (status = 503, description = "验证码服务不可用", body = ApiErrorResponse)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_email_change(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    Json(request): Json<ConfirmEmailChangeRequest>,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "修改邮箱")?;
+    request
+        .validate()
+        .map_err(|e| ApiError::BadRequest(format!("请求数据不合法: {e}")))?;
+
+    // 放在验证码校验之前，避免邮箱冲突时白白消耗验证码
+    if AccountService::email_taken(&app_state.db, &request.new_email, claims.id).await? {
+        return Err(ApiError::Conflict("该邮箱已被其他账户使用".to_string()));
+    }
+    ensure_code_valid(
+        AuthService::validate_email_change_code(claims.id, &request.new_email, &request.code).await,
+    )?;
+
+    let (old_email, profile) =
+        AccountService::change_email(&app_state.db, claims.id, &request.new_email).await?;
+    ActivityService::record(
+        &app_state.db,
+        claims.id,
+        ActivityAction::EmailChanged,
+        None,
+        None,
+    )
+    .await;
+
+    // 通知原邮箱，便于账户被盗用时及时发现；发送失败不影响修改结果
+    if let Some(email_config) = app_state.config.email.as_ref() {
+        if !EmailSuppressionService::is_suppressed(&app_state.db, &old_email).await {
+            if let Err(e) = AuthService::send_email_changed_notice(
+                &old_email,
+                &profile.username,
+                &profile.email,
+                email_config,
+            )
+            .await
+            {
+                tracing::warn!("⚠️  发送邮箱修改通知失败: {}", e);
+            }
+        }
+    }
+
+    Ok(Json(profile))
+}
+
+fn ensure_not_impersonated(claims: &Claims, action: &str) -> ApiResult<()> {
+    if claims.is_impersonated() {
+        return Err(ApiError::Forbidden(format!("代入身份时不能{action}")));
+    }
+    Ok(())
+}
+
+/// 修改密码
+#[utoipa::path(
+    post,
+    operation_id = "change_password",
+    path = "/v2/users/me/password",
+    summary = "修改密码",
+    description = "校验当前密码后设置新密码。修改成功后该用户此前签发的所有令牌（包括本次请求使用的令牌）都会被吊销，响应中返回新的访问令牌",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "密码已修改，返回新的访问令牌", body = AuthToken),
+        (
+            status = 400,
+            description = "参数无效或当前密码错误",
+            body = ApiErrorResponse,
+            example = json!({"error": "当前密码错误", "code": "BAD_REQUEST", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 403,
+            description = "管理员代入身份时不能修改密码",
+            body = ApiErrorResponse,
+            example = json!({"error": "代入身份时不能修改密码", "code": "FORBIDDEN", "status": 403})
+        )
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn change_password(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<Claims>>,
+    headers: HeaderMap,
+    Json(request): Json<ChangePasswordRequest>,
+) -> ApiResult<Json<AuthToken>> {
+    let claims = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    ensure_not_impersonated(&claims, "修改密码")?;
+    request
+        .validate()
+        .map_err(|e| ApiError::BadRequest(format!("请求数据不合法: {e}")))?;
+
+    let config = &app_state.config;
+    let user = AccountService::change_password(
+        &app_state.db,
+        claims.id,
+        request.current_password,
+        request.new_password,
+        config.password.bcrypt_cost,
+    )
+    .await?;
+
+    // 旧令牌可能已经泄露，全部吊销后为当前客户端签发新令牌
+    if let Err(e) = AuthService::revoke_user_tokens(user.id).await {
+        tracing::warn!("⚠️  吊销用户 {} 的令牌失败: {}", user.id, e);
+    }
+    ActivityService::record(
+        &app_state.db,
+        user.id,
+        ActivityAction::PasswordChanged,
+        None,
+        None,
+    )
+    .await;
+
+    let access_token = AuthService::create_access_token(
+        &JwtData {
+            user_id: user.id,
+            username: user.username,
+            tenant_id: user.tenant_id,
+        },
+        &SessionClient::from_headers(&headers),
+        config,
+    )
+    .await?;
+    Ok(Json(AuthToken {
+        access_token,
+        expires_in: config.jwt.expiration,
+    }))
+}
+
+/// 注销当前账户
+#[utoipa::path(
+    delete,
+    operation_id = "delete_account",
+    path = "/v2/users/me",
+    summary = "注销账户",
+    description = "两步确认：不带 `confirm_token` 调用时只返回将被删除的数据条数和确认令牌（202）；带上令牌再次调用才会删除账户并使当前令牌失效。仍拥有服务器时需要先删除或转让",
+    responses(
+        (status = 200, description = "账户已注销", body = SuccessResponse),
+        (status = 202, description = "需要确认，返回影响范围与确认令牌", body = ConfirmationRequired),
+        (
+            status = 400,
+            description = "确认令牌无效",
+            body = ApiErrorResponse,
+            example = json!({"error": "确认令牌无效或已过期", "code": "INVALID_CONFIRMATION_TOKEN", "status": 400})
+        ),
+        (
+            status = 401,
+            description = "未登录",
+            body = ApiErrorResponse,
+            example = json!({"error": "未登录", "code": "UNAUTHORIZED", "status": 401})
+        ),
+        (
+            status = 409,
+            description = "仍拥有服务器，或确认后数据发生变化",
+            body = ApiErrorResponse,
+            examples(
+                ("仍拥有服务器" = (value = json!({"error": "请先删除或转让您拥有的服务器", "code": "CONFLICT", "status": 409}))),
+                ("数据已变化" = (value = json!({"error": "待删除的内容已发生变化，请重新确认", "code": "CONFLICT", "status": 409})))
+            )
+        )
+    ),
+    tag = "users",
+    params(ConfirmQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_account(
+    State(app_state): State<AppState>,
+    user_claims: Option<Extension<UserClaims>>,
+    Query(query): Query<ConfirmQuery>,
+) -> ApiResult<Response> {
+    let user = user_claims
+        .ok_or_else(|| ApiError::Unauthorized("未登录".to_string()))?
+        .0;
+    let user_id = user.claims.id;
+
+    let impact = AccountService::deletion_impact(&app_state.db, user_id).await?;
+    if let Some(required) = ConfirmationService::guard(
+        DestructiveAction::DeleteAccount,
+        user_id,
+        &user_id.to_string(),
+        impact,
+        query.confirm_token.as_deref(),
+    )
+    .await?
+    {
+        return Ok((StatusCode::ACCEPTED, Json(required)).into_response());
+    }
+
+    AccountService::delete_account(&app_state.db, user_id).await?;
+    if let Err(e) = AuthService::blacklist_token(&user.raw_token, &app_state.config).await {
+        tracing::warn!("⚠️  注销账户后吊销令牌失败: {}", e);
+    }
+
+    Ok(Json(SuccessResponse {
+        message: "账户已注销".to_string(),
+    })
+    .into_response())
+}
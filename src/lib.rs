@@ -1,88 +1,123 @@
 pub mod config;
 pub mod entities;
 pub mod errors;
+pub mod extract;
 pub mod handlers;
 pub mod logging;
 pub mod middleware;
+pub mod routes;
 pub mod schemas;
 pub mod services;
 use anyhow::Result;
 use std::sync::Arc;
 
-use crate::config::Config;
+use crate::config::{Config, StorageConfig};
+use crate::errors::ErrorResponses;
 use crate::handlers::search;
-use crate::handlers::{auth, servers};
-use crate::middleware::{auth::optional_auth_middleware, simple_http_logging_middleware};
+use crate::handlers::{admin, auth, internal, meta, sandbox, servers, tags, users};
+use crate::middleware::{
+    auth::optional_auth_middleware, client_ip_middleware, cors_layer, legacy_envelope_middleware,
+    normalize_request_middleware, pool_guard_middleware, rate_limit_middleware,
+    read_consistency_middleware, scan_guard_middleware, simple_http_logging_middleware,
+    tenant_middleware, throttle_middleware, RateLimitPolicy, ThrottleGroup,
+};
 use crate::services::auth::SecurityAddon;
-use crate::services::database::{establish_connection, DatabaseConnection};
+use crate::services::database::{
+    establish_connection, DatabaseConnection, ReadConsistency, ReadReplicas,
+};
+use crate::services::motd_image::BitmapFont;
+use crate::services::storage::{self, Storage};
+use crate::services::tenant::TenantService;
 use axum::routing::post;
 use axum::{
     middleware as axum_middleware,
-    routing::{delete, get},
+    routing::{delete, get, patch, put},
     Router,
 };
-use tower_http::cors::CorsLayer;
-use utoipa::OpenApi;
+use tower::Layer;
+use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
-#[derive(OpenApi)]
-#[openapi(
-    paths(
-        servers::list_servers,
-        servers::get_server_detail,
-        servers::update_server,
-        servers::get_server_managers,
-        servers::get_server_gallery,
-        servers::upload_gallery_image,
-        servers::delete_gallery_image,
-        servers::get_total_players,
-        auth::login,
-        auth::logout,
-        auth::register,
-        auth::register_email_code,
-        search::search_server
-    ),
-    components(
-        schemas(
-            schemas::servers::ServerListResponse,
-            schemas::servers::ApiServerType,
-            schemas::servers::ServerDetail,
-            schemas::servers::ServerStats,
-            schemas::servers::ApiAuthMode,
-            schemas::servers::Motd,
-            schemas::servers::UpdateServerRequest,
-            schemas::servers::ServerManagersResponse,
-            schemas::servers::ManagerInfo,
-            schemas::servers::ServerGallery,
-            schemas::servers::GalleryImage,
-            schemas::servers::GalleryImageRequest,
-            schemas::servers::SuccessResponse,
-            schemas::servers::ServerTotalPlayers,
-            schemas::auth::AuthToken,
-            schemas::auth::UserRegisterData,
-            schemas::search::SearchParams,
-            schemas::search::ServerResult,
-            schemas::search::SearchResponse,
-            entities::server::AuthModeEnum,
-            entities::server::ServerTypeEnum,
-            errors::ApiErrorResponse,
-            errors::ApiError
-        )
-    ),
-    modifiers(&SecurityAddon),
-    tags((name = "servers", description = "Server management endpoints"))
-)]
+/// 完整的 OpenAPI 文档
+///
+/// 各接口模块在自己的文件中声明 `XxxApi` 文档，列出本模块的接口与需要单独登记的 schema
+/// （如只用作查询参数的类型），这里按模块合并，请求体与响应中引用的 schema 会被自动收集。
+/// 合并后统一补充安全方案与通用错误响应。新增接口模块时在 [`ApiDoc::MODULES`] 中登记，
+/// [`routes::check`] 会发现未写进文档的路由。
 pub struct ApiDoc;
 
+impl ApiDoc {
+    /// 始终挂载的接口模块
+    const MODULES: [fn() -> utoipa::openapi::OpenApi; 9] = [
+        servers::ServersApi::openapi,
+        internal::InternalApi::openapi,
+        admin::AdminApi::openapi,
+        meta::MetaApi::openapi,
+        users::UsersApi::openapi,
+        auth::AuthApi::openapi,
+        search::SearchApi::openapi,
+        tags::TagsApi::openapi,
+        sandbox::SandboxApi::openapi,
+    ];
+}
+
+/// 文档的基础信息与各模块共用的错误 schema
+#[derive(OpenApi)]
+#[openapi(components(schemas(
+    errors::ApiErrorResponse,
+    errors::BanNotice,
+    errors::BannedErrorResponse,
+    errors::ErrorCode,
+    errors::RateLimitNotice,
+    errors::RateLimitedErrorResponse,
+    errors::ApiError
+)))]
+struct BaseApiDoc;
+
+impl OpenApi for ApiDoc {
+    fn openapi() -> utoipa::openapi::OpenApi {
+        let mut doc = BaseApiDoc::openapi();
+        for module in Self::MODULES {
+            doc.merge(module());
+        }
+        finish_openapi(&mut doc);
+        doc
+    }
+}
+
+/// 合并各模块后统一补充的内容
+fn finish_openapi(doc: &mut utoipa::openapi::OpenApi) {
+    SecurityAddon.modify(doc);
+    ErrorResponses.modify(doc);
+}
+
+/// 生成对外提供的完整 OpenAPI 文档，包含按特性启用的接口
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    #[allow(unused_mut)]
+    let mut doc = ApiDoc::openapi();
+    #[cfg(feature = "dev-tools")]
+    doc.merge(handlers::dev_tools::DevToolsApi::openapi());
+    #[cfg(feature = "chaos")]
+    doc.merge(handlers::chaos::ChaosApi::openapi());
+    // 按特性合并的接口同样需要补充通用错误响应
+    finish_openapi(&mut doc);
+    doc
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<Config>,
     pub db: DatabaseConnection,
+    pub replicas: Arc<ReadReplicas>,
+    /// 文件存储，未配置时图片上传不可用
+    pub storage: Option<Storage>,
+    /// MOTD 图片使用的点阵字体，未配置或加载失败时 MOTD 图片不可用
+    pub motd_font: Option<Arc<BitmapFont>>,
 }
 
 impl AppState {
     pub async fn new() -> Result<Self> {
-        let config = Arc::new(Config::from_env()?);
+        let config = Config::from_env()?;
         let db = match establish_connection(&config.database).await {
             Ok(db) => {
                 tracing::info!("数据库初始化成功");
@@ -93,50 +128,423 @@ impl AppState {
                 return Err(e.into());
             }
         };
-        Ok(Self { config, db })
+        let replicas = ReadReplicas::connect(&config.database).await;
+        if !replicas.is_empty() {
+            tracing::info!("已启用 {} 个只读副本", replicas.len());
+        }
+
+        let mut state = Self::with_connection(config, db);
+        state.replicas = Arc::new(replicas);
+        Ok(state)
+    }
+
+    /// 使用已建立的数据库连接构建应用状态（测试中可传入 Mock 连接）
+    pub fn with_connection(config: Config, db: DatabaseConnection) -> Self {
+        let motd_font = config.motd_image.as_ref().and_then(|motd_image| {
+            match BitmapFont::load(&motd_image.font_path) {
+                Ok(font) => Some(Arc::new(font)),
+                Err(e) => {
+                    tracing::error!("MOTD 图片字体加载失败，MOTD 图片不可用: {}", e);
+                    None
+                }
+            }
+        });
+        Self {
+            storage: config.storage.as_ref().map(storage::build),
+            motd_font,
+            config: Arc::new(config),
+            db,
+            replicas: Arc::new(ReadReplicas::default()),
+        }
+    }
+
+    /// 选择只读查询使用的连接，强一致或未配置副本时使用主库
+    pub fn read_db(&self, consistency: ReadConsistency) -> &DatabaseConnection {
+        match consistency {
+            ReadConsistency::Strong => &self.db,
+            ReadConsistency::Eventual => self.replicas.pick().unwrap_or(&self.db),
+        }
     }
 }
 
 pub fn create_app(app_state: AppState) -> Router {
     let server_router = Router::new()
         // Server routes with optional authentication
-        .route("/", get(servers::list_servers))
+        .route("/", get(servers::list_servers).post(servers::create_server))
         .route("/players", get(servers::get_total_players))
+        .route("/live", get(servers::live_updates))
+        .route("/slug/{slug}", get(servers::get_server_detail_by_slug))
         .route(
             "/{server_id}",
-            get(servers::get_server_detail).put(servers::update_server),
+            get(servers::get_server_detail)
+                .put(servers::update_server)
+                .delete(servers::delete_server)
+                .route_layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    scan_guard_middleware,
+                )),
+        )
+        .route(
+            "/{server_id}/summary",
+            get(servers::get_server_summary).route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                scan_guard_middleware,
+            )),
+        )
+        .route(
+            "/{server_id}/managers",
+            get(servers::get_server_managers).post(servers::add_server_manager),
+        )
+        .route(
+            "/{server_id}/managers/{user_id}",
+            put(servers::update_server_manager).delete(servers::remove_server_manager),
+        )
+        .route(
+            "/{server_id}/managers/{user_id}/transfer",
+            post(servers::transfer_server_ownership),
         )
-        .route("/{server_id}/managers", get(servers::get_server_managers))
         .route(
             "/{server_id}/gallery",
-            get(servers::get_server_gallery).post(servers::upload_gallery_image),
+            get(servers::get_server_gallery)
+                .delete(servers::delete_gallery_images)
+                .merge(post(servers::upload_gallery_image).route_layer(
+                    axum_middleware::from_fn_with_state(
+                        (app_state.clone(), ThrottleGroup::GalleryUpload),
+                        throttle_middleware,
+                    ),
+                )),
+        )
+        .route("/{server_id}/gallery/feed", get(servers::get_gallery_feed))
+        .route(
+            "/{server_id}/cover",
+            get(servers::get_server_cover).delete(servers::delete_server_cover),
+        )
+        .route("/{server_id}/motd.png", get(servers::get_server_motd_image))
+        .route(
+            "/{server_id}/icon",
+            post(servers::upload_server_icon).delete(servers::delete_server_icon),
+        )
+        .route(
+            "/{server_id}/gallery/order",
+            put(servers::reorder_gallery_images),
         )
         .route(
             "/{server_id}/gallery/{image_id}",
-            delete(servers::delete_gallery_image),
+            patch(servers::update_gallery_image).delete(servers::delete_gallery_image),
+        )
+        .route("/{server_id}/stats", post(servers::push_server_stats))
+        .route(
+            "/{server_id}/stats/history",
+            get(servers::get_server_stats_history),
+        )
+        .route(
+            "/{server_id}/push-secret",
+            post(servers::rotate_push_secret),
+        )
+        .route(
+            "/{server_id}/custom-fields",
+            put(servers::update_custom_fields),
+        )
+        .route(
+            "/{server_id}/revisions",
+            get(servers::list_server_revisions),
+        )
+        .route(
+            "/{server_id}/revisions/{revision_id}/rollback",
+            post(servers::rollback_server_revision),
+        )
+        .route(
+            "/{server_id}/submit",
+            post(servers::submit_server_for_review),
+        )
+        .route("/{server_id}/archive", post(servers::archive_server))
+        .route(
+            "/{server_id}/tags/suggest",
+            post(servers::suggest_server_tags),
+        )
+        .route("/{server_id}/similar", get(servers::get_similar_servers))
+        .route("/{server_id}/timeline", get(servers::get_server_timeline))
+        .route("/{server_id}/uptime", get(servers::get_server_uptime))
+        .route(
+            "/{server_id}/favorite",
+            post(servers::favorite_server).delete(servers::unfavorite_server),
         );
     let auth_router = Router::new()
-        .route("/login", post(auth::login))
+        .route(
+            "/login",
+            post(auth::login).route_layer(axum_middleware::from_fn_with_state(
+                (app_state.clone(), RateLimitPolicy::Login),
+                rate_limit_middleware,
+            )),
+        )
         .route("/logout", post(auth::logout))
-        .route("/register/email-code", post(auth::register_email_code))
-        .route("/register", post(auth::register));
-    let search_router = Router::new().route("/", get(search::search_server));
+        .route(
+            "/register/email-code",
+            post(auth::register_email_code)
+                .route_layer(axum_middleware::from_fn_with_state(
+                    (app_state.clone(), RateLimitPolicy::EmailCode),
+                    rate_limit_middleware,
+                ))
+                .route_layer(axum_middleware::from_fn_with_state(
+                    (app_state.clone(), ThrottleGroup::EmailSend),
+                    throttle_middleware,
+                )),
+        )
+        .route("/register", post(auth::register))
+        .route(
+            "/password-reset/request",
+            post(auth::request_password_reset).route_layer(axum_middleware::from_fn_with_state(
+                (app_state.clone(), ThrottleGroup::EmailSend),
+                throttle_middleware,
+            )),
+        )
+        .route(
+            "/password-reset/confirm",
+            post(auth::confirm_password_reset),
+        )
+        .route("/oauth/{provider}/authorize", get(auth::oauth_authorize))
+        .route("/oauth/{provider}/callback", get(auth::oauth_callback));
+    let search_router = Router::new()
+        .route("/", get(search::search_server))
+        .route("/facets", get(search::search_facets))
+        .route("/players", get(search::search_players))
+        .route_layer(axum_middleware::from_fn_with_state(
+            (app_state.clone(), ThrottleGroup::Search),
+            throttle_middleware,
+        ))
+        // 输入联想按键触发，由缓存承压，不占用搜索令牌
+        .route("/suggest", get(search::search_suggest));
+    let internal_router = Router::new()
+        .route("/stats/batch", post(internal::ingest_stats_batch))
+        .route("/links/confirm", post(internal::confirm_link))
+        .route("/email/events", post(internal::ingest_email_events))
+        .route(
+            "/links/{provider}/{external_id}",
+            get(internal::get_linked_account).delete(internal::revoke_linked_account),
+        );
+    let admin_router = Router::new()
+        .route("/registration-flags", get(admin::list_registration_flags))
+        .route(
+            "/registration-flags/stats",
+            get(admin::registration_flag_stats),
+        )
+        .route(
+            "/registration-flags/{flag_id}/review",
+            post(admin::review_registration_flag),
+        )
+        .route("/spam-holds", get(admin::list_spam_holds))
+        .route(
+            "/spam-holds/{hold_id}/review",
+            post(admin::review_spam_hold),
+        )
+        .route("/users/{user_id}", get(admin::get_user))
+        .route(
+            "/users/{user_id}/email-suppression",
+            delete(admin::clear_email_suppression),
+        )
+        .route("/users/{user_id}/names", patch(admin::update_user_names))
+        .route(
+            "/users/{user_id}/impersonate",
+            post(admin::impersonate_user),
+        )
+        .route(
+            "/users/{user_id}/ban",
+            post(admin::ban_user).delete(admin::unban_user),
+        )
+        .route("/bans", get(admin::list_bans))
+        .route("/tags/merge", post(admin::merge_tags))
+        .route("/tags/vocabulary", get(admin::list_tag_vocabulary))
+        .route(
+            "/tags/vocabulary/{tag}",
+            put(admin::upsert_tag_vocabulary).delete(admin::delete_tag_vocabulary),
+        )
+        .route("/delisting", get(admin::list_delisting))
+        .route(
+            "/servers/{server_id}/delisting",
+            put(admin::update_delisting),
+        )
+        .route("/servers", get(admin::list_servers))
+        .route("/servers/{server_id}", put(admin::force_update_server))
+        .route(
+            "/servers/{server_id}/visibility",
+            put(admin::set_server_visibility),
+        )
+        .route("/servers/{server_id}/approve", post(admin::approve_server))
+        .route("/servers/{server_id}/reject", post(admin::reject_server))
+        .route(
+            "/servers/{server_id}/gallery/{image_id}",
+            delete(admin::remove_gallery_image),
+        )
+        .route("/feature-flags", get(admin::list_feature_flags))
+        .route(
+            "/feature-flags/{key}",
+            put(admin::upsert_feature_flag).delete(admin::delete_feature_flag),
+        )
+        .route("/status/incidents", get(admin::list_incidents))
+        .route(
+            "/status/incidents/{key}",
+            put(admin::upsert_incident).delete(admin::delete_incident),
+        );
 
-    Router::new()
+    let meta_router = Router::new()
+        .route("/version", get(meta::get_version))
+        .route("/status", get(meta::get_status))
+        .route("/sitemap.xml", get(meta::get_sitemap));
+    let users_router = Router::new()
+        .route(
+            "/me",
+            get(users::get_profile)
+                .patch(users::update_profile)
+                .delete(users::delete_account),
+        )
+        .route("/me/avatar", post(users::upload_avatar))
+        .route("/me/activity", get(users::get_my_activity))
+        .route("/me/favorites", get(users::list_my_favorites))
+        .route(
+            "/me/links",
+            get(users::list_links).post(users::initiate_link),
+        )
+        .route("/me/links/{link_id}", delete(users::revoke_link))
+        .route(
+            "/me/links/oauth/{provider}",
+            post(users::initiate_oauth_link),
+        )
+        .route(
+            "/me/sessions",
+            get(users::list_sessions).delete(users::revoke_other_sessions),
+        )
+        .route("/me/sessions/{session_id}", delete(users::revoke_session))
+        .route(
+            "/me/api-keys",
+            get(users::list_api_keys).post(users::create_api_key),
+        )
+        .route("/me/api-keys/{key_id}", delete(users::revoke_api_key))
+        .route("/me/notifications", get(users::list_notifications))
+        .route(
+            "/me/notifications/read",
+            post(users::mark_notifications_read),
+        )
+        .route(
+            "/me/notifications/unread-count",
+            get(users::get_unread_notification_count),
+        )
+        .route(
+            "/me/preferences",
+            get(users::get_preferences).put(users::update_preferences),
+        )
+        .route(
+            "/me/email",
+            post(users::request_email_change).route_layer(axum_middleware::from_fn_with_state(
+                (app_state.clone(), ThrottleGroup::EmailSend),
+                throttle_middleware,
+            )),
+        )
+        .route("/me/email/confirm", post(users::confirm_email_change))
+        .route("/me/password", post(users::change_password));
+
+    let mut router = Router::new()
         .nest("/v2/servers", server_router)
         .nest("/v2/auth", auth_router)
         .nest("/v2/search", search_router)
+        .nest("/v2/internal", internal_router)
+        .nest("/v2/admin", admin_router)
+        .nest("/v2/meta", meta_router)
+        .nest("/v2/users", users_router)
+        .route("/v2/tags", get(tags::list_tags));
+
+    #[cfg(feature = "dev-tools")]
+    {
+        tracing::warn!("dev-tools 特性已启用，/v2/dev 接口可写入合成数据，请勿在生产环境使用");
+        let dev_router = Router::new().route("/seed", post(handlers::dev_tools::seed));
+        router = router.nest("/v2/dev", dev_router);
+    }
+
+    if app_state.config.sandbox.enabled {
+        let sandbox_router = Router::new()
+            .route("/servers", get(sandbox::list_servers))
+            .route("/servers/players", get(sandbox::get_total_players))
+            .route(
+                "/servers/{server_id}",
+                get(sandbox::get_server_detail).put(sandbox::update_server),
+            )
+            .route(
+                "/servers/{server_id}/managers",
+                get(sandbox::get_server_managers),
+            )
+            .route(
+                "/servers/{server_id}/gallery",
+                get(sandbox::get_server_gallery),
+            )
+            .route("/search", get(sandbox::search_server))
+            .route(
+                "/auth/register/email-code",
+                post(sandbox::register_email_code),
+            );
+        router = router.nest("/v2/sandbox", sandbox_router);
+    }
+
+    #[cfg(feature = "chaos")]
+    {
+        tracing::warn!(
+            "chaos 特性已启用，管理员可通过 /v2/admin/chaos 注入故障，请勿在生产环境使用"
+        );
+        // 只作用于业务路由，健康检查、指标与文档不受影响
+        router = router
+            .route(
+                "/v2/admin/chaos",
+                get(handlers::chaos::get_chaos_rules)
+                    .put(handlers::chaos::update_chaos_rules)
+                    .delete(handlers::chaos::clear_chaos_rules),
+            )
+            .layer(axum_middleware::from_fn(
+                crate::middleware::chaos_middleware,
+            ));
+    }
+
+    // 本地存储的文件由本服务提供，S3 存储的文件地址直接指向存储桶
+    if matches!(app_state.config.storage, Some(StorageConfig::Local(_))) {
+        router = router.route("/static/{*key}", get(handlers::storage::serve_static));
+    }
+
+    let trusted_proxy_hops = app_state.config.server.trusted_proxy_hops;
+    let router = router
         // Health check
-        .route("/health", get(|| async { "OK" }))
+        .route("/health", get(meta::health_ready))
+        .route("/health/live", get(meta::health_live))
+        .route("/health/ready", get(meta::health_ready))
+        .route("/metrics", get(handlers::metrics::metrics))
         // Swagger UI
-        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()))
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", openapi()))
         // CORS configuration
-        .layer(CorsLayer::permissive())
+        .layer(cors_layer(&app_state.config.cors))
+        // Read replica routing, runs after authentication
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            read_consistency_middleware,
+        ))
         // Add HTTP logging middleware
         .layer(axum_middleware::from_fn(simple_http_logging_middleware))
         .layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
             optional_auth_middleware,
         ))
-        .with_state(app_state)
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            pool_guard_middleware,
+        ))
+        .with_state(app_state);
+
+    // 路径归一化需要在路由匹配之前改写路径，405 响应也只能在路由之外改写，因此包裹在整个路由之外
+    let router = axum_middleware::from_fn(normalize_request_middleware).layer(router);
+    // 限流与会话记录依赖的客户端 IP 需要在所有中间件之前确定
+    let router =
+        axum_middleware::from_fn_with_state(trusted_proxy_hops, client_ip_middleware).layer(router);
+    // 旧版响应包装放在最外层，405 与参数解析错误等响应也会被包装
+    let router = axum_middleware::from_fn(legacy_envelope_middleware).layer(router);
+    if !TenantService::is_enabled() {
+        return Router::new().fallback_service(router);
+    }
+
+    // 租户解析需要在路由匹配之前改写路径前缀，因此包裹在整个路由之外
+    Router::new().fallback_service(axum_middleware::from_fn(tenant_middleware).layer(router))
 }
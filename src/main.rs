@@ -1,8 +1,25 @@
 use server_api_rt::{
+    config::StorageConfig,
     create_app,
     logging::{init_logging, log_server_ready, log_shutdown},
+    openapi, routes,
     services::{
-        redis::RedisService, search::client::MeilisearchClient, utils::maintain_sentence_queue,
+        archive::GalleryArchiveService,
+        database::{monitor_connection_pool, ReadConsistency},
+        delisting::DelistingService,
+        embeddings::EmbeddingService,
+        file_gc::FileGcService,
+        live::LiveUpdateService,
+        notification::NotificationService,
+        ping::collector::PingService,
+        redis::RedisService,
+        search::{client::MeilisearchClient, sync::SearchSyncService, tasks::SearchTaskMonitor},
+        server::ServerService,
+        status::StatusService,
+        tenant::TenantService,
+        translation::TranslationService,
+        upload_scan::UploadScanService,
+        utils::maintain_sentence_queue,
     },
     AppState,
 };
@@ -16,6 +33,18 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("启动服务器 API...");
 
+    if let Err(e) = TenantService::init(&app_state.config.tenant) {
+        tracing::error!("租户配置无效: {}", e);
+        return Err(e);
+    }
+    if TenantService::is_enabled() {
+        tracing::info!(
+            "✅ 已启用多租户模式（{}），共 {} 个租户",
+            app_state.config.tenant.mode,
+            TenantService::all().len()
+        );
+    }
+
     tracing::info!("初始化 Redis 连接...");
 
     if let Err(e) = RedisService::init(app_state.config.redis.clone()).await {
@@ -23,7 +52,62 @@ async fn main() -> anyhow::Result<()> {
         return Err(e);
     }
     tracing::info!("启动预热一句话接口");
-    maintain_sentence_queue().await;
+    maintain_sentence_queue(app_state.config.jobs.sentence_queue).await;
+
+    match ServerService::backfill_slugs(&app_state.db).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("已为 {} 个服务器生成短链接", count),
+        Err(e) => tracing::warn!("⚠️  生成服务器短链接失败: {}", e),
+    }
+
+    match TranslationService::init(&app_state.config.translation) {
+        Ok(true) => tracing::info!("✅ 已启用服务器描述自动翻译"),
+        Ok(false) => {}
+        Err(e) => tracing::warn!("⚠️  翻译服务初始化失败，自动翻译不可用: {}", e),
+    }
+
+    if let Err(e) = NotificationService::init(
+        app_state.config.email.as_ref(),
+        &app_state.config.notification,
+    ) {
+        tracing::warn!("⚠️  通知服务初始化失败: {}", e);
+    }
+
+    match UploadScanService::init(&app_state.config.upload_scan) {
+        Ok(true) => tracing::info!("✅ 已启用上传文件安全扫描"),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("上传文件安全扫描初始化失败: {}", e);
+            return Err(e);
+        }
+    }
+
+    let verify_storage = app_state
+        .config
+        .storage
+        .as_ref()
+        .is_some_and(StorageConfig::verify_on_startup);
+    if let Some(storage) = app_state.storage.as_ref().filter(|_| verify_storage) {
+        if let Err(e) = storage.verify().await {
+            tracing::error!("文件存储检查失败: {}", e);
+            return Err(e.into());
+        }
+        tracing::info!("✅ 文件存储可访问: {}", storage.name());
+    }
+
+    match EmbeddingService::init(&app_state.config.embedding) {
+        Ok(true) => {
+            if let Some(service) = EmbeddingService::instance() {
+                tracing::info!("✅ 已启用服务器描述向量");
+                tokio::spawn(service.run_refresh_loop(
+                    app_state.db.clone(),
+                    app_state.config.jobs.embedding_refresh,
+                ));
+            }
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("⚠️  向量服务初始化失败，相似服务器将按标签推荐: {}", e),
+    }
 
     tracing::info!("启动搜索引擎...");
     if let Err(e) = MeilisearchClient::init(
@@ -33,17 +117,100 @@ async fn main() -> anyhow::Result<()> {
     .await
     {
         tracing::error!("Meilisearch 初始化失败: {}", e);
-        return Err(e.into());
+        return Err(e);
     }
-    let client = MeilisearchClient::instance()?;
+
+    // 全量同步读取全部服务器，优先使用只读副本；增量同步读取刚修改的数据，使用主库
+    tokio::spawn(SearchSyncService::run_loop(
+        MeilisearchClient::instance()?,
+        app_state.db.clone(),
+        app_state.read_db(ReadConsistency::Eventual).clone(),
+        app_state.config.jobs.search_sync,
+        app_state.config.jobs.search_full_sync,
+    ));
+
+    tokio::spawn(SearchTaskMonitor::run_monitor_loop(
+        MeilisearchClient::instance()?,
+        app_state.read_db(ReadConsistency::Eventual).clone(),
+        app_state.config.meilisearch.clone(),
+        app_state.config.jobs.search_task_poll,
+    ));
+
+    // 数据变更后统一清除缓存并推送给实时订阅的客户端
+    tokio::spawn(LiveUpdateService::run_dispatch_loop());
+
+    tokio::spawn(StatusService::run_sample_loop(
+        app_state.db.clone(),
+        app_state.config.status.clone(),
+        app_state.config.jobs.status_sample,
+    ));
+
+    tokio::spawn(NotificationService::run_digest_loop(
+        app_state.db.clone(),
+        app_state.config.jobs.notification_digest,
+    ));
 
     let db = app_state.db.clone();
+    let interval = app_state.config.jobs.pool_monitor;
     tokio::spawn(async move {
-        if let Err(e) = client.sync_meilisearch_loop(&db, 60).await {
-            tracing::error!("Meilisearch 同步失败: {}", e);
-        }
+        monitor_connection_pool(db, interval).await;
     });
 
+    let s3_config = app_state
+        .config
+        .storage
+        .as_ref()
+        .and_then(StorageConfig::s3);
+    match (s3_config, app_state.config.archive.enabled) {
+        (Some(s3_config), true) => {
+            tracing::info!("启动相册归档任务...");
+            tokio::spawn(GalleryArchiveService::run_archive_loop(
+                app_state.db.clone(),
+                s3_config.clone(),
+                app_state.config.archive.clone(),
+                app_state.config.jobs.gallery_archive,
+            ));
+        }
+        (None, true) => tracing::warn!("⚠️  未配置 S3 对象存储，相册归档任务未启动"),
+        _ => {}
+    }
+
+    match (&app_state.storage, app_state.config.file_gc.enabled) {
+        (Some(storage), true) => {
+            tracing::info!("启动未引用文件清理任务...");
+            tokio::spawn(FileGcService::run_loop(
+                app_state.db.clone(),
+                storage.clone(),
+                app_state.config.file_gc.clone(),
+                app_state.config.jobs.file_gc,
+            ));
+        }
+        (None, true) => tracing::warn!("⚠️  未配置文件存储，未引用文件清理任务未启动"),
+        _ => {}
+    }
+
+    if app_state.config.delisting.enabled {
+        tracing::info!("启动离线服务器下架任务...");
+        tokio::spawn(DelistingService::run_loop(
+            app_state.db.clone(),
+            app_state.config.delisting.clone(),
+            app_state.config.jobs.delisting,
+        ));
+    }
+
+    if app_state.config.ping.enabled {
+        tracing::info!("启动服务器状态采集任务...");
+        tokio::spawn(PingService::run_loop(
+            app_state.db.clone(),
+            app_state.config.ping.clone(),
+            app_state.config.jobs.server_ping,
+        ));
+    }
+
+    if app_state.config.server.log_routes {
+        routes::log_inventory(&openapi());
+    }
+
     tracing::info!("创建应用程序...");
     let app = create_app(app_state.clone());
 
@@ -54,7 +221,12 @@ async fn main() -> anyhow::Result<()> {
 
     log_server_ready(&addr);
 
-    let result = axum::serve(listener, app).await;
+    // 客户端 IP 中间件需要连接的对端地址
+    let result = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await;
 
     log_shutdown();
     result.map_err(Into::into)
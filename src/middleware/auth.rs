@@ -1,16 +1,31 @@
 use axum::{
     extract::{Request, State},
-    http::header::AUTHORIZATION,
+    http::{header::AUTHORIZATION, HeaderValue},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use tracing::Instrument;
 
 use crate::{
-    errors::ApiError,
-    services::auth::{AuthService, Claims},
+    errors::{ApiError, ErrorCode},
+    middleware::CurrentTenant,
+    schemas::{admin::BanType, users::ApiKeyScope},
+    services::{
+        api_key::ApiKeyService,
+        auth::{AuthService, Claims},
+        ban::BanService,
+        tenant::TenantService,
+        utils::client_ip,
+    },
     AppState,
 };
 
+/// 代入令牌的响应头，值为管理员 ID，客户端据此显示代入提示横幅
+pub const IMPERSONATED_BY_HEADER: &str = "x-impersonated-by";
+
+/// API 密钥请求头，同时携带 Bearer 令牌时以令牌为准
+pub const API_KEY_HEADER: &str = "x-api-key";
+
 #[derive(Debug, Clone)]
 pub struct UserClaims {
     pub claims: Claims,
@@ -25,24 +40,107 @@ fn extract_bearer_token(req: &Request) -> Option<String> {
         .map(|token| token.to_string())
 }
 
+fn extract_api_key(req: &Request) -> Option<String> {
+    req.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .map(|key| key.trim().to_string())
+}
+
+/// 封禁用户的请求被拒绝，禁言只限制写操作
+async fn ban_response(app_state: &AppState, safe_method: bool, user_id: i32) -> Option<Response> {
+    let ban = BanService::active_ban(&app_state.db, user_id).await?;
+    if ban.ban_type == BanType::Mute.as_str() && safe_method {
+        return None;
+    }
+    Some(ApiError::Banned(ban).into_response())
+}
+
 pub async fn optional_auth_middleware(
     State(app_state): State<AppState>,
     mut req: Request,
     next: Next,
 ) -> Response {
+    let mut impersonation = None;
     if let Some(token) = extract_bearer_token(&req) {
         match AuthService::verify_token(&token, &app_state.config).await {
+            Ok(claims) if !token_matches_tenant(&req, &claims) => {
+                return ApiError::Unauthorized("令牌不属于当前站点".to_string())
+                    .with_code(ErrorCode::TokenTenantMismatch)
+                    .into_response();
+            }
             Ok(claims) => {
+                // 代入期间不检查封禁，便于管理员复现被封禁用户遇到的问题
+                if claims.impersonator.is_none() {
+                    if let Some(response) =
+                        ban_response(&app_state, req.method().is_safe(), claims.id).await
+                    {
+                        return response;
+                    }
+                }
+                impersonation = claims.impersonator.map(|admin_id| (admin_id, claims.id));
+                let session = claims.clone();
+                let ip = client_ip(req.headers());
+                tokio::spawn(async move {
+                    if let Err(e) = AuthService::touch_session(&session, ip).await {
+                        tracing::debug!("更新会话活动时间失败: {}", e);
+                    }
+                });
+                req.extensions_mut().insert(claims.clone());
                 req.extensions_mut().insert(UserClaims {
                     claims,
                     raw_token: token,
                 });
             }
-            Err(_) => {
-                return ApiError::Unauthorized("无效的 Token".to_string()).into_response();
-            }
+            Err(e) => return e.into_response(),
+        }
+    } else if let Some(key) = extract_api_key(&req) {
+        let claims = match ApiKeyService::authenticate(&app_state.db, &key).await {
+            Ok(claims) => claims,
+            Err(e) => return e.into_response(),
+        };
+        if !token_matches_tenant(&req, &claims) {
+            return ApiError::Unauthorized("API 密钥不属于当前站点".to_string()).into_response();
+        }
+        let scope = claims.api_key_scope.unwrap_or(ApiKeyScope::ReadOnly);
+        if !ApiKeyService::permits(scope, req.method(), req.uri().path()) {
+            return ApiError::Forbidden("API 密钥无权访问该接口".to_string()).into_response();
+        }
+        if let Some(response) = ban_response(&app_state, req.method().is_safe(), claims.id).await {
+            return response;
         }
+        // 不插入 UserClaims：登出、注销账户等依赖原始令牌的接口不接受 API 密钥
+        req.extensions_mut().insert(claims);
     }
 
-    next.run(req).await
+    let Some((admin_id, user_id)) = impersonation else {
+        return next.run(req).await;
+    };
+
+    tracing::info!(
+        "👤 代入请求: admin_id={}, user_id={}, {} {}",
+        admin_id,
+        user_id,
+        req.method(),
+        req.uri().path()
+    );
+    let span = tracing::info_span!("impersonation", admin_id, user_id);
+    let mut response = AuthService::with_impersonator(admin_id, next.run(req))
+        .instrument(span)
+        .await;
+    response
+        .headers_mut()
+        .insert(IMPERSONATED_BY_HEADER, HeaderValue::from(admin_id));
+    response
+}
+
+/// 令牌只能在签发它的租户下使用，没有租户信息的旧令牌视为默认租户签发
+fn token_matches_tenant(req: &Request, claims: &Claims) -> bool {
+    let Some(current) = req.extensions().get::<CurrentTenant>() else {
+        return true;
+    };
+    match &claims.tenant {
+        Some(tenant) => tenant == current.id(),
+        None => current.id() == TenantService::default_tenant().id,
+    }
 }
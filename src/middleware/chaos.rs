@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::{
+    errors::{ApiErrorResponse, ErrorCode},
+    schemas::chaos::FaultKind,
+    services::{chaos::ChaosService, metrics::MetricsService},
+};
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// 标记响应经过故障注入的响应头
+const FAULT_HEADER: &str = "x-chaos-fault";
+
+/// 故障注入中间件
+///
+/// 命中规则时按类型注入故障：`delay` 等待后继续处理，`error` 直接返回错误响应，
+/// `drop` 在发送响应头后中断响应体，客户端会看到连接被重置。
+/// 注入的响应带有 `x-chaos-fault` 响应头，便于和真实故障区分。
+pub async fn chaos_middleware(req: Request, next: Next) -> Response {
+    let Some(rule) = ChaosService::pick(req.method(), req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let kind = match rule.kind {
+        FaultKind::Delay => "delay",
+        FaultKind::Error => "error",
+        FaultKind::Drop => "drop",
+    };
+    tracing::debug!("注入故障 {}: {} {}", kind, req.method(), req.uri().path());
+    MetricsService::inc_counter(
+        "chaos_faults_injected_total",
+        "故障注入次数",
+        &[("kind", kind)],
+        1.0,
+    );
+
+    let mut response = match rule.kind {
+        FaultKind::Delay => {
+            tokio::time::sleep(Duration::from_millis(rule.delay_ms.unwrap_or_default())).await;
+            next.run(req).await
+        }
+        FaultKind::Error => {
+            let status = rule
+                .status
+                .and_then(|status| StatusCode::from_u16(status).ok())
+                .unwrap_or(StatusCode::SERVICE_UNAVAILABLE);
+            let body = Json(ApiErrorResponse {
+                error: "故障注入".to_string(),
+                code: ErrorCode::FaultInjected,
+                status: status.as_u16(),
+            });
+            (status, body).into_response()
+        }
+        FaultKind::Drop => {
+            let body = futures_util::stream::once(async {
+                Err::<Bytes, _>(std::io::Error::other("故障注入：中断响应"))
+            });
+            Response::new(Body::from_stream(body))
+        }
+    };
+    response
+        .headers_mut()
+        .insert(FAULT_HEADER, HeaderValue::from_static(kind));
+    response
+}
@@ -0,0 +1,43 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::services::utils::trusted_client_ip;
+
+/// 客户端 IP 中间件
+///
+/// 包裹在整个路由之外执行。`X-Forwarded-For` 与 `X-Real-IP` 可以由客户端随意填写，
+/// 这里按连接的对端地址与配置的可信代理层数确定客户端 IP（见 [`trusted_client_ip`]），
+/// 把 `X-Forwarded-For` 改写为该地址并去掉 `X-Real-IP`；之后的限流、会话记录与日志
+/// 通过 [`client_ip`](crate::services::utils::client_ip) 读到的都是可信的值。
+/// 无法确定时两个头都被移除，按匿名请求处理。状态为可信代理层数：
+///
+/// ```ignore
+/// from_fn_with_state(config.server.trusted_proxy_hops, client_ip_middleware).layer(router)
+/// ```
+pub async fn client_ip_middleware(
+    State(trusted_hops): State<usize>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = trusted_client_ip(req.headers(), peer, trusted_hops);
+
+    let headers = req.headers_mut();
+    headers.remove("x-forwarded-for");
+    headers.remove("x-real-ip");
+    if let Some(ip) = ip {
+        if let Ok(value) = HeaderValue::from_str(&ip.to_string()) {
+            headers.insert("x-forwarded-for", value);
+        }
+    }
+    next.run(req).await
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+
+use crate::{
+    config::{CorsConfig, CorsMode},
+    middleware::{throttle::REMAINING_HEADER, IMPERSONATED_BY_HEADER},
+};
+
+/// 按配置构建跨域中间件
+///
+/// 宽松模式允许任意来源但不允许携带凭据；严格模式只回应列出的来源，
+/// 并暴露分页、限流等前端需要读取的响应头。
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let max_age = Duration::from_secs(config.max_age);
+    match config.mode {
+        CorsMode::Permissive => CorsLayer::permissive().max_age(max_age),
+        CorsMode::Strict => {
+            // 来源格式已在读取配置时校验
+            let origins: Vec<HeaderValue> = config
+                .allowed_origins
+                .iter()
+                .filter_map(|origin| origin.parse().ok())
+                .collect();
+            CorsLayer::new()
+                .allow_origin(AllowOrigin::list(origins))
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                ])
+                .allow_headers(AllowHeaders::mirror_request())
+                .expose_headers([
+                    header::LINK,
+                    header::RETRY_AFTER,
+                    HeaderName::from_static(REMAINING_HEADER),
+                    HeaderName::from_static(IMPERSONATED_BY_HEADER),
+                ])
+                .allow_credentials(config.allow_credentials)
+                .max_age(max_age)
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderMap,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::{json, Value};
+
+/// 通过请求头选择响应格式
+pub const ENVELOPE_HEADER: &str = "x-response-envelope";
+/// 通过查询参数选择响应格式（如 `?envelope=legacy`）
+const ENVELOPE_QUERY: &str = "envelope";
+/// 旧版 `{code, msg, data}` 格式的取值
+const LEGACY: &str = "legacy";
+/// 需要包装的响应体最大长度，超出时原样返回
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// 旧版响应包装中间件
+///
+/// 兼容依赖 MSCPO 旧接口格式的客户端：请求头 `X-Response-Envelope: legacy` 或查询参数
+/// `envelope=legacy` 时，把 JSON 响应包装为 `{code, msg, data}`。
+/// 成功响应的 `msg` 为 `success`、`data` 为原响应体；错误响应的 `msg` 取自 `error` 字段、`data` 为 `null`。
+/// HTTP 状态码保持不变，非 JSON 响应（SSE、站点地图等）不做处理。
+pub async fn legacy_envelope_middleware(req: Request, next: Next) -> Response {
+    if !wants_legacy(&req) {
+        return next.run(req).await;
+    }
+
+    let response = next.run(req).await;
+    if !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("⚠️  读取响应体失败，无法包装为旧版格式: {}", e);
+            return parts.status.into_response();
+        }
+    };
+    let Ok(data) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let status = parts.status;
+    let envelope = if status.is_success() {
+        json!({ "code": status.as_u16(), "msg": "success", "data": data })
+    } else {
+        let msg = data
+            .get("error")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or_default().to_string());
+        json!({ "code": status.as_u16(), "msg": msg, "data": Value::Null })
+    };
+
+    let body = envelope.to_string();
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn wants_legacy(req: &Request) -> bool {
+    let by_header = req
+        .headers()
+        .get(ENVELOPE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case(LEGACY));
+    let by_query = req.uri().query().is_some_and(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .any(|(key, value)| key == ENVELOPE_QUERY && value.eq_ignore_ascii_case(LEGACY))
+    });
+    by_header || by_query
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
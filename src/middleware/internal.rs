@@ -0,0 +1,46 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{errors::ApiError, AppState};
+
+/// 内部接口访问令牌请求头
+pub const INTERNAL_TOKEN_HEADER: &str = "x-internal-token";
+
+/// 内部调用方（采集器等）
+///
+/// 作为提取器使用，请求头中的令牌与 `INTERNAL_API_TOKEN` 一致时才会放行。
+pub struct InternalCaller;
+
+impl FromRequestParts<AppState> for InternalCaller {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let expected = state
+            .config
+            .internal
+            .api_token
+            .as_deref()
+            .ok_or_else(|| ApiError::Forbidden("内部接口未启用".to_string()))?;
+
+        let provided = parts
+            .headers
+            .get(INTERNAL_TOKEN_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .ok_or_else(|| ApiError::Unauthorized("缺少内部访问令牌".to_string()))?;
+
+        if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            return Err(ApiError::Unauthorized("内部访问令牌无效".to_string()));
+        }
+
+        Ok(InternalCaller)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
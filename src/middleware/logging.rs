@@ -6,7 +6,7 @@ use axum::{
 };
 use std::{net::SocketAddr, time::Instant};
 
-use crate::logging::HttpLogFormatter;
+use crate::{logging::HttpLogFormatter, services::status::StatusService};
 
 /// 获取真实的客户端 IP 地址
 fn get_real_ip(addr: Option<SocketAddr>, headers: &HeaderMap) -> Option<String> {
@@ -56,6 +56,7 @@ pub async fn http_logging_middleware(
 
     let duration = start.elapsed();
     let status = response.status().as_u16();
+    StatusService::record_request(status);
 
     // 记录 HTTP 请求日志
     let log_message =
@@ -81,6 +82,7 @@ pub async fn simple_http_logging_middleware(request: Request, next: Next) -> Res
 
     let duration = start.elapsed();
     let status = response.status().as_u16();
+    StatusService::record_request(status);
 
     // 记录 HTTP 请求日志
     let log_message =
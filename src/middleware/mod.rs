@@ -1,5 +1,33 @@
 pub mod auth;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod client_ip;
+pub mod cors;
+pub mod envelope;
+pub mod internal;
 pub mod logging;
+pub mod normalize;
+pub mod permission;
+pub mod pool_guard;
+pub mod rate_limit;
+pub mod replica;
+pub mod scan_guard;
+pub mod tenant;
+pub mod throttle;
 
 pub use auth::*;
+#[cfg(feature = "chaos")]
+pub use chaos::*;
+pub use client_ip::*;
+pub use cors::*;
+pub use envelope::*;
+pub use internal::*;
 pub use logging::*;
+pub use normalize::*;
+pub use permission::*;
+pub use pool_guard::*;
+pub use rate_limit::*;
+pub use replica::*;
+pub use scan_guard::*;
+pub use tenant::*;
+pub use throttle::*;
@@ -0,0 +1,87 @@
+use axum::{
+    extract::Request,
+    http::{
+        header::{ALLOW, CONTENT_TYPE},
+        StatusCode, Uri,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::errors::{ApiErrorResponse, ErrorCode};
+
+/// 不做末尾斜杠归一化的路径前缀（Swagger UI 依赖 `/docs/` 跳转）
+const SKIP_PREFIXES: &[&str] = &["/docs"];
+
+/// 请求归一化中间件
+///
+/// 包裹在整个路由之外执行：去掉路径末尾的斜杠，使 `/v2/servers/` 与 `/v2/servers` 命中同一路由；
+/// 把 axum 默认的纯文本 405 响应改写为 [`ApiErrorResponse`]，并在错误信息与 `Allow` 头中给出可用方法。
+pub async fn normalize_request_middleware(mut req: Request, next: Next) -> Response {
+    if let Some(uri) = trim_trailing_slash(req.uri()) {
+        *req.uri_mut() = uri;
+    }
+    let method = req.method().clone();
+
+    let response = next.run(req).await;
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED || is_json(&response) {
+        return response;
+    }
+
+    let allow = response.headers().get(ALLOW).cloned();
+    let allowed = allow
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let error = if allowed.is_empty() {
+        format!("该接口不支持 {method} 请求")
+    } else {
+        format!("该接口不支持 {method} 请求，可用方法: {allowed}")
+    };
+
+    let mut response = (
+        StatusCode::METHOD_NOT_ALLOWED,
+        Json(ApiErrorResponse {
+            error,
+            code: ErrorCode::MethodNotAllowed,
+            status: StatusCode::METHOD_NOT_ALLOWED.as_u16(),
+        }),
+    )
+        .into_response();
+    if let Some(allow) = allow {
+        response.headers_mut().insert(ALLOW, allow);
+    }
+    response
+}
+
+/// 去掉末尾斜杠后的 URI，无需改写时返回 `None`
+fn trim_trailing_slash(uri: &Uri) -> Option<Uri> {
+    let path = uri.path();
+    if path.len() <= 1 || !path.ends_with('/') {
+        return None;
+    }
+    if SKIP_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        return None;
+    }
+
+    let trimmed = path.trim_end_matches('/');
+    let trimmed = if trimmed.is_empty() { "/" } else { trimmed };
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{trimmed}?{query}"),
+        None => trimmed.to_string(),
+    };
+
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
@@ -0,0 +1,242 @@
+//! 基于角色的权限提取器
+//!
+//! 处理函数通过参数声明所需角色，例如 `RequireSiteRole<Moderator>` 或
+//! `RequireServerRole<Admin>`：未登录返回 401，角色不足返回 403。
+//! 当前用户与其服务器角色在请求内缓存，同一请求中多个提取器只查询一次数据库。
+
+use std::{collections::HashMap, marker::PhantomData, ops::Deref};
+
+use axum::{
+    extract::{FromRequestParts, RawPathParams},
+    http::request::Parts,
+};
+use sea_orm::EntityTrait;
+
+use crate::{
+    entities::users::{self, RoleEnum},
+    errors::{ApiError, ErrorCode},
+    middleware::CurrentTenant,
+    schemas::servers::ServerManagerRole,
+    services::{
+        auth::{AuthService, Claims},
+        server::ServerService,
+    },
+    AppState,
+};
+
+/// 站点角色要求
+pub trait SiteRole: Send + Sync + 'static {
+    /// 角色不足时的提示
+    const DENIED: &'static str;
+    /// 角色不足时的错误码
+    const DENIED_CODE: ErrorCode;
+
+    fn allows(user: &users::Model) -> bool;
+}
+
+/// 服务器角色要求
+pub trait ServerRole: Send + Sync + 'static {
+    /// 角色不足时的提示
+    const DENIED: &'static str;
+    /// 角色不足时的错误码
+    const DENIED_CODE: ErrorCode;
+
+    fn allows(role: ServerManagerRole) -> bool;
+}
+
+/// 管理员：作为站点角色时仅 admin，作为服务器角色时包含管理员与服主
+pub struct Admin;
+
+/// 站点管理人员（admin 或 moderator），用于审核类接口
+pub struct Moderator;
+
+/// 服务器所有者
+pub struct Owner;
+
+impl SiteRole for Admin {
+    const DENIED: &'static str = "需要管理员权限";
+    const DENIED_CODE: ErrorCode = ErrorCode::AdminRequired;
+
+    fn allows(user: &users::Model) -> bool {
+        AuthService::is_site_admin(user)
+    }
+}
+
+impl SiteRole for Moderator {
+    const DENIED: &'static str = "需要管理人员权限";
+    const DENIED_CODE: ErrorCode = ErrorCode::StaffRequired;
+
+    fn allows(user: &users::Model) -> bool {
+        matches!(user.role, RoleEnum::Admin | RoleEnum::Moderator)
+    }
+}
+
+impl ServerRole for Admin {
+    const DENIED: &'static str = "需要服务器管理员权限";
+    const DENIED_CODE: ErrorCode = ErrorCode::ServerManagerRequired;
+
+    fn allows(_role: ServerManagerRole) -> bool {
+        true
+    }
+}
+
+impl ServerRole for Owner {
+    const DENIED: &'static str = "需要服务器所有者权限";
+    const DENIED_CODE: ErrorCode = ErrorCode::ServerOwnerRequired;
+
+    fn allows(role: ServerManagerRole) -> bool {
+        role == ServerManagerRole::Owner
+    }
+}
+
+/// 请求内缓存的当前用户与服务器角色，`None` 表示已查询但不是该服务器的管理员
+#[derive(Clone, Default)]
+struct RoleCache {
+    user: Option<users::Model>,
+    servers: HashMap<i32, Option<ServerManagerRole>>,
+}
+
+fn current_claims(parts: &Parts) -> Result<Claims, ApiError> {
+    parts
+        .extensions
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| ApiError::Unauthorized("未授权".to_string()))
+}
+
+async fn load_current_user(parts: &mut Parts, state: &AppState) -> Result<users::Model, ApiError> {
+    let claims = current_claims(parts)?;
+    if let Some(user) = parts
+        .extensions
+        .get::<RoleCache>()
+        .and_then(|cache| cache.user.clone())
+    {
+        return Ok(user);
+    }
+
+    let user = users::Entity::find_by_id(claims.id)
+        .one(state.db.as_ref())
+        .await?
+        .ok_or_else(|| {
+            ApiError::Unauthorized("用户不存在".to_string()).with_code(ErrorCode::UserNotFound)
+        })?;
+    if !user.is_active {
+        return Err(
+            ApiError::Forbidden("账户已停用".to_string()).with_code(ErrorCode::AccountDeactivated)
+        );
+    }
+
+    let mut cache = parts.extensions.remove::<RoleCache>().unwrap_or_default();
+    cache.user = Some(user.clone());
+    parts.extensions.insert(cache);
+    Ok(user)
+}
+
+async fn load_server_role(
+    parts: &mut Parts,
+    state: &AppState,
+    server_id: i32,
+    user_id: i32,
+) -> Result<Option<ServerManagerRole>, ApiError> {
+    if let Some(role) = parts
+        .extensions
+        .get::<RoleCache>()
+        .and_then(|cache| cache.servers.get(&server_id).copied())
+    {
+        return Ok(role);
+    }
+
+    let role = ServerService::server_role(&state.db, server_id, user_id).await?;
+    let mut cache = parts.extensions.remove::<RoleCache>().unwrap_or_default();
+    cache.servers.insert(server_id, role);
+    parts.extensions.insert(cache);
+    Ok(role)
+}
+
+/// 路径中的 `server_id` 参数
+async fn path_server_id(parts: &mut Parts, state: &AppState) -> Result<i32, ApiError> {
+    let params = RawPathParams::from_request_parts(parts, state)
+        .await
+        .map_err(|e| ApiError::Internal(format!("读取路径参数失败: {e}")))?;
+    let raw = params
+        .iter()
+        .find(|(key, _)| *key == "server_id")
+        .map(|(_, value)| value)
+        .ok_or_else(|| ApiError::Internal("路由缺少 server_id 参数".to_string()))?;
+    raw.parse()
+        .map_err(|_| ApiError::BadRequest("服务器ID必须为整数".to_string()))
+}
+
+/// 要求当前用户具有站点角色 `R`，解引用为用户记录
+pub struct RequireSiteRole<R: SiteRole> {
+    pub user: users::Model,
+    role: PhantomData<R>,
+}
+
+impl<R: SiteRole> Deref for RequireSiteRole<R> {
+    type Target = users::Model;
+
+    fn deref(&self) -> &users::Model {
+        &self.user
+    }
+}
+
+impl<R: SiteRole> FromRequestParts<AppState> for RequireSiteRole<R> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user = load_current_user(parts, state).await?;
+        if !R::allows(&user) {
+            return Err(ApiError::Forbidden(R::DENIED.to_string()).with_code(R::DENIED_CODE));
+        }
+        Ok(Self {
+            user,
+            role: PhantomData,
+        })
+    }
+}
+
+/// 要求当前用户在路径 `server_id` 指定的服务器上具有角色 `R`，解引用为令牌声明
+///
+/// 服务器不属于当前租户时返回 404。
+pub struct RequireServerRole<R: ServerRole> {
+    pub claims: Claims,
+    pub server_id: i32,
+    pub role: ServerManagerRole,
+    required: PhantomData<R>,
+}
+
+impl<R: ServerRole> Deref for RequireServerRole<R> {
+    type Target = Claims;
+
+    fn deref(&self) -> &Claims {
+        &self.claims
+    }
+}
+
+impl<R: ServerRole> FromRequestParts<AppState> for RequireServerRole<R> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let claims = current_claims(parts)?;
+        let server_id = path_server_id(parts, state).await?;
+        let tenant = CurrentTenant::from_request_parts(parts, state).await?;
+        ServerService::ensure_in_tenant(&state.db, server_id, tenant.id()).await?;
+
+        match load_server_role(parts, state, server_id, claims.id).await? {
+            Some(role) if R::allows(role) => Ok(Self {
+                claims,
+                server_id,
+                role,
+                required: PhantomData,
+            }),
+            _ => Err(ApiError::Forbidden(R::DENIED.to_string()).with_code(R::DENIED_CODE)),
+        }
+    }
+}
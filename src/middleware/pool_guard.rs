@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    errors::ApiError,
+    services::{
+        database::{pool_status, record_pool_metrics},
+        metrics::MetricsService,
+    },
+    AppState,
+};
+
+/// 不受连接池保护影响的运维路径
+const EXEMPT_PATHS: [&str; 4] = ["/health", "/health/live", "/health/ready", "/metrics"];
+
+/// 连接池保护中间件
+///
+/// 请求进入时检查连接池，饱和时记录等待中的请求并计数；
+/// 开启 `DB_SHED_ON_SATURATION` 后直接返回 503，避免请求堆积到获取连接超时。
+pub async fn pool_guard_middleware(
+    State(app_state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path();
+    if EXEMPT_PATHS.contains(&path) {
+        return next.run(req).await;
+    }
+
+    if let Some(status) = pool_status(&app_state.db) {
+        if status.is_saturated() {
+            record_pool_metrics(&status);
+            let shed = app_state.config.database.shed_on_saturation;
+            tracing::warn!(
+                "⚠️  数据库连接池已耗尽，请求等待连接: {} {} (使用中={}, 最大={}, 拒绝={})",
+                req.method(),
+                path,
+                status.in_use,
+                status.max,
+                shed
+            );
+            MetricsService::inc_counter(
+                "db_pool_saturated_requests_total",
+                "连接池耗尽时到达的请求数",
+                &[("shed", if shed { "true" } else { "false" })],
+                1.0,
+            );
+
+            if shed {
+                return ApiError::ServiceUnavailable("服务繁忙，请稍后重试".to_string())
+                    .into_response();
+            }
+        }
+    }
+
+    next.run(req).await
+}
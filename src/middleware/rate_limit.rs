@@ -0,0 +1,308 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::{RateLimitConfig, RateLimitRule},
+    errors::{ApiError, RateLimitNotice},
+    middleware::CurrentTenant,
+    services::{
+        metrics::MetricsService, redis::RedisService, tenant::TenantService, utils::client_ip,
+    },
+    AppState,
+};
+
+/// Redis 键前缀
+const KEY_PREFIX: &str = "rate_limit";
+/// 读取请求体的上限，限流的接口请求体都很小
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// 同一 IP 可能有多个用户（NAT、校园网），按 IP 计数的上限为账户上限的倍数
+const IP_ALLOWANCE_FACTOR: i64 = 4;
+/// 锁定次数的保留时间（秒），期间再次锁定时长翻倍
+const LEVEL_TTL_SECS: u64 = 86_400;
+
+/// 限流策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// 登录：只统计失败的请求，登录成功后清除该账户的计数
+    Login,
+    /// 注册验证码：每次请求都会发送邮件，所有请求都计数
+    EmailCode,
+}
+
+impl RateLimitPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitPolicy::Login => "login",
+            RateLimitPolicy::EmailCode => "email_code",
+        }
+    }
+
+    /// 请求体中标识账户的字段
+    fn account_field(&self) -> &'static str {
+        match self {
+            RateLimitPolicy::Login => "username_or_email",
+            RateLimitPolicy::EmailCode => "email",
+        }
+    }
+
+    fn rule<'a>(&self, config: &'a RateLimitConfig) -> &'a RateLimitRule {
+        match self {
+            RateLimitPolicy::Login => &config.login,
+            RateLimitPolicy::EmailCode => &config.email_code,
+        }
+    }
+
+    fn counts(&self, status: StatusCode) -> bool {
+        match self {
+            RateLimitPolicy::Login => {
+                status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS
+            }
+            RateLimitPolicy::EmailCode => status != StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+/// 一个计数对象：来源 IP 或账户
+struct Subject {
+    scope: &'static str,
+    id: String,
+    max_attempts: i64,
+}
+
+/// 登录与验证码限流中间件
+///
+/// 按来源 IP（由 [`client_ip_middleware`](crate::middleware::client_ip_middleware) 按可信代理确定）
+/// 与账户（请求体中的用户名或邮箱）分别计数。计数在处理请求之前自增，窗口期内超过上限的
+/// 请求被拒绝并锁定该对象，锁定期间返回 429 并在 `Retry-After` 中给出剩余秒数。同一对象
+/// 在一天内再次被锁定时，锁定时长翻倍直至上限。通过 `route_layer` 挂载到单个路由，状态为 `(AppState, 策略)`：
+///
+/// ```ignore
+/// post(auth::login).route_layer(from_fn_with_state(
+///     (app_state.clone(), RateLimitPolicy::Login),
+///     rate_limit_middleware,
+/// ))
+/// ```
+///
+/// Redis 不可用时放行。
+pub async fn rate_limit_middleware(
+    State((app_state, policy)): State<(AppState, RateLimitPolicy)>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let config = &app_state.config.rate_limit;
+    if !config.enabled {
+        return next.run(req).await;
+    }
+    let Some(redis) = RedisService::instance() else {
+        return next.run(req).await;
+    };
+
+    let tenant = req
+        .extensions()
+        .get::<CurrentTenant>()
+        .cloned()
+        .unwrap_or_else(|| CurrentTenant(TenantService::default_tenant()));
+    let ip = client_ip(req.headers());
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return ApiError::BadRequest("请求体过大".to_string()).into_response(),
+    };
+    let account = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|data| {
+            data.get(policy.account_field())?
+                .as_str()
+                .map(|value| value.trim().to_lowercase())
+        })
+        .filter(|value| !value.is_empty());
+    let req = Request::from_parts(parts, Body::from(bytes));
+
+    let rule = policy.rule(config);
+    let mut subjects = Vec::with_capacity(2);
+    if let Some(ip) = ip {
+        subjects.push(Subject {
+            scope: "ip",
+            id: format!("{}:{}", tenant.id(), ip),
+            max_attempts: rule.max_attempts.saturating_mul(IP_ALLOWANCE_FACTOR),
+        });
+    }
+    if let Some(account) = account {
+        subjects.push(Subject {
+            scope: "account",
+            id: format!("{}:{}", tenant.id(), hex::encode(Sha256::digest(account))),
+            max_attempts: rule.max_attempts,
+        });
+    }
+
+    match reserve_attempt(&redis, config, policy, &subjects).await {
+        Ok(Some(retry_after)) => {
+            MetricsService::inc_counter(
+                "rate_limit_rejected_total",
+                "因锁定被拒绝的请求数",
+                &[("policy", policy.as_str())],
+                1.0,
+            );
+            return ApiError::RateLimited(RateLimitNotice { retry_after }).into_response();
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!("⚠️  限流检查失败: {}", e);
+            return next.run(req).await;
+        }
+    }
+
+    let response = next.run(req).await;
+    let status = response.status();
+    let result = if policy.counts(status) {
+        Ok(())
+    } else if status.is_success() {
+        async {
+            refund_attempt(&redis, policy, &subjects).await?;
+            reset_account(&redis, policy, &subjects).await
+        }
+        .await
+    } else {
+        refund_attempt(&redis, policy, &subjects).await
+    };
+    if let Err(e) = result {
+        tracing::warn!("⚠️  更新限流计数失败: {}", e);
+    }
+    response
+}
+
+fn key(policy: RateLimitPolicy, kind: &str, subject: &Subject) -> String {
+    format!(
+        "{KEY_PREFIX}:{}:{kind}:{}:{}",
+        policy.as_str(),
+        subject.scope,
+        subject.id
+    )
+}
+
+/// 任一对象处于锁定中时返回剩余秒数
+async fn locked_for(
+    redis: &RedisService,
+    policy: RateLimitPolicy,
+    subjects: &[Subject],
+) -> anyhow::Result<Option<u64>> {
+    let mut retry_after = None;
+    for subject in subjects {
+        let ttl = redis.ttl(&key(policy, "lock", subject)).await?;
+        if ttl > 0 {
+            retry_after = retry_after.max(Some(ttl as u64));
+        }
+    }
+    Ok(retry_after)
+}
+
+/// 在处理请求之前计入本次请求，任一对象处于锁定中或计数超过上限时返回剩余秒数
+///
+/// 计数在进入处理函数之前原子地自增，并发的请求无法越过上限；被拒绝的请求与
+/// 不计入的请求（如登录成功）随后通过 [`refund_attempt`] 退还计数。
+async fn reserve_attempt(
+    redis: &RedisService,
+    config: &RateLimitConfig,
+    policy: RateLimitPolicy,
+    subjects: &[Subject],
+) -> anyhow::Result<Option<u64>> {
+    let rule = policy.rule(config);
+    let mut over_limit = false;
+    for subject in subjects {
+        let count = redis
+            .incr_ex(&key(policy, "count", subject), rule.window_secs)
+            .await?;
+        if count > subject.max_attempts {
+            over_limit = true;
+            // 只由恰好越过上限的请求锁定，并发的请求不会重复累加锁定次数
+            if count == subject.max_attempts + 1 {
+                lock(redis, config, policy, subject).await?;
+            }
+        }
+    }
+
+    // 锁定写入后才清空计数，因此在自增之后检查锁定，清空后才自增的请求也会被拒绝
+    let mut retry_after = locked_for(redis, policy, subjects).await?;
+    if over_limit && retry_after.is_none() {
+        // 其他请求正在写入锁定
+        retry_after = Some(config.lockout_secs.max(1));
+    }
+    if retry_after.is_some() {
+        refund_attempt(redis, policy, subjects).await?;
+    }
+    Ok(retry_after)
+}
+
+/// 锁定计数超过上限的对象并清空其计数，同一对象一天内再次锁定时锁定时长翻倍
+async fn lock(
+    redis: &RedisService,
+    config: &RateLimitConfig,
+    policy: RateLimitPolicy,
+    subject: &Subject,
+) -> anyhow::Result<()> {
+    let level = redis
+        .incr_ex(&key(policy, "level", subject), LEVEL_TTL_SECS)
+        .await?;
+    let lockout = config
+        .lockout_secs
+        .saturating_mul(1u64 << (level - 1).clamp(0, 20))
+        .min(config.max_lockout_secs);
+    redis
+        .set_ex(&key(policy, "lock", subject), "1", lockout)
+        .await?;
+    redis.del(&key(policy, "count", subject)).await?;
+
+    tracing::warn!(
+        "⚠️  {} 限流触发，锁定{} {} 秒: {}",
+        policy.as_str(),
+        if subject.scope == "ip" {
+            " IP"
+        } else {
+            "账户"
+        },
+        lockout,
+        subject.id
+    );
+    MetricsService::inc_counter(
+        "rate_limit_lockouts_total",
+        "限流锁定次数",
+        &[("policy", policy.as_str()), ("scope", subject.scope)],
+        1.0,
+    );
+    Ok(())
+}
+
+/// 退还 [`reserve_attempt`] 计入的次数
+async fn refund_attempt(
+    redis: &RedisService,
+    policy: RateLimitPolicy,
+    subjects: &[Subject],
+) -> anyhow::Result<()> {
+    for subject in subjects {
+        redis.decr_existing(&key(policy, "count", subject)).await?;
+    }
+    Ok(())
+}
+
+/// 登录成功后清除该账户的失败计数与锁定次数，IP 的计数保留以防撞库
+async fn reset_account(
+    redis: &RedisService,
+    policy: RateLimitPolicy,
+    subjects: &[Subject],
+) -> anyhow::Result<()> {
+    if policy != RateLimitPolicy::Login {
+        return Ok(());
+    }
+    for subject in subjects.iter().filter(|subject| subject.scope == "account") {
+        redis.del(&key(policy, "count", subject)).await?;
+        redis.del(&key(policy, "level", subject)).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{request::Parts, Method},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    errors::ApiError,
+    services::{
+        auth::Claims,
+        database::{mark_recent_write, wrote_recently, DatabaseConnection, ReadConsistency},
+    },
+    AppState,
+};
+
+/// 客户端可通过该请求头要求读取主库（值为 `strong`）
+pub const READ_CONSISTENCY_HEADER: &str = "x-read-consistency";
+
+/// 读一致性中间件
+///
+/// 在认证中间件之后执行：请求头要求强一致、或当前用户刚写入过数据时，
+/// 本次请求的只读查询固定走主库；写请求成功后记录写入时间。
+pub async fn read_consistency_middleware(
+    State(app_state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let sticky_window = Duration::from_secs(app_state.config.database.replica_sticky_secs);
+    let user_id = req.extensions().get::<Claims>().map(|claims| claims.id);
+
+    let requested_strong = req
+        .headers()
+        .get(READ_CONSISTENCY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("strong"));
+    let consistency =
+        if requested_strong || user_id.is_some_and(|id| wrote_recently(id, sticky_window)) {
+            ReadConsistency::Strong
+        } else {
+            ReadConsistency::Eventual
+        };
+    req.extensions_mut().insert(consistency);
+
+    let is_write = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let response = next.run(req).await;
+
+    if let (true, Some(user_id)) = (is_write && response.status().is_success(), user_id) {
+        mark_recent_write(user_id, sticky_window);
+    }
+
+    response
+}
+
+/// 只读查询使用的数据库连接
+///
+/// 根据本次请求的一致性要求在主库与只读副本之间选择。
+pub struct ReadDb(pub DatabaseConnection);
+
+impl FromRequestParts<AppState> for ReadDb {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let consistency = parts
+            .extensions
+            .get::<ReadConsistency>()
+            .copied()
+            .unwrap_or_default();
+
+        Ok(ReadDb(state.read_db(consistency).clone()))
+    }
+}
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use crate::{
+    config::ScanGuardConfig,
+    errors::ApiError,
+    services::{auth::Claims, metrics::MetricsService, redis::RedisService, utils::client_ip},
+    AppState,
+};
+
+/// Redis 键前缀
+const KEY_PREFIX: &str = "scan_guard";
+/// 与上一次访问的 ID 相差不超过该值时视为顺序访问
+const SEQUENTIAL_GAP: i64 = 2;
+
+/// 服务器详情防遍历中间件
+///
+/// 仅作用于匿名的 `GET /v2/servers/{server_id}`：按 IP 记录最近访问的 ID，
+/// 连续访问相邻 ID 次数过多或窗口期内访问总数超限时，封禁该 IP 一段时间并返回 429。
+/// 已登录用户不受限制；Redis 不可用或无法识别 IP 时放行。
+pub async fn scan_guard_middleware(
+    State(app_state): State<AppState>,
+    Path(server_id): Path<i32>,
+    user_claims: Option<Extension<Claims>>,
+    headers: HeaderMap,
+    req: Request,
+    next: Next,
+) -> Response {
+    let config = &app_state.config.scan_guard;
+    if !config.enabled || req.method() != Method::GET || user_claims.is_some() {
+        return next.run(req).await;
+    }
+    let (Some(redis), Some(ip)) = (RedisService::instance(), client_ip(&headers)) else {
+        return next.run(req).await;
+    };
+
+    match check(&redis, config, &ip, server_id).await {
+        Ok(None) => next.run(req).await,
+        Ok(Some(reason)) => {
+            MetricsService::inc_counter(
+                "server_scan_blocked_total",
+                "被防遍历保护拒绝的详情请求数",
+                &[("reason", reason)],
+                1.0,
+            );
+            ApiError::TooManyRequests("访问过于频繁，请稍后再试".to_string()).into_response()
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  防遍历检查失败: {}", e);
+            next.run(req).await
+        }
+    }
+}
+
+/// 返回拒绝原因，`None` 表示放行
+async fn check(
+    redis: &RedisService,
+    config: &ScanGuardConfig,
+    ip: &str,
+    server_id: i32,
+) -> anyhow::Result<Option<&'static str>> {
+    let block_key = format!("{KEY_PREFIX}:block:{ip}");
+    if redis.exists(&block_key).await? {
+        return Ok(Some("blocked"));
+    }
+
+    let total = redis
+        .incr_ex(
+            &format!("{KEY_PREFIX}:total:{ip}"),
+            config.total_cap_window_secs,
+        )
+        .await?;
+    if total > config.total_cap {
+        block(redis, config, &block_key, ip, "total_cap").await?;
+        return Ok(Some("total_cap"));
+    }
+
+    let last_key = format!("{KEY_PREFIX}:last:{ip}");
+    let seq_key = format!("{KEY_PREFIX}:seq:{ip}");
+    let last = redis
+        .get(&last_key)
+        .await?
+        .and_then(|s| s.parse::<i64>().ok());
+    redis
+        .set_ex(
+            &last_key,
+            &server_id.to_string(),
+            config.sequential_window_secs,
+        )
+        .await?;
+
+    let gap = last.map(|last| (server_id as i64 - last).abs());
+    match gap {
+        // 重复访问同一服务器不计入
+        Some(0) => {}
+        Some(gap) if gap <= SEQUENTIAL_GAP => {
+            let sequential = redis
+                .incr_ex(&seq_key, config.sequential_window_secs)
+                .await?;
+            if sequential >= config.sequential_threshold {
+                block(redis, config, &block_key, ip, "sequential").await?;
+                return Ok(Some("sequential"));
+            }
+        }
+        _ => redis.del(&seq_key).await?,
+    }
+
+    Ok(None)
+}
+
+async fn block(
+    redis: &RedisService,
+    config: &ScanGuardConfig,
+    block_key: &str,
+    ip: &str,
+    reason: &str,
+) -> anyhow::Result<()> {
+    tracing::warn!(
+        "⚠️  检测到服务器详情遍历，封禁 IP {} {} 秒: {}",
+        ip,
+        config.block_secs,
+        reason
+    );
+    redis.set_ex(block_key, reason, config.block_secs).await
+}
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header::HOST, request::Parts, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    config::TenantDefinition,
+    errors::ApiError,
+    services::tenant::{TenantMode, TenantService},
+    AppState,
+};
+
+/// 当前请求所属的租户
+///
+/// 由 [`tenant_middleware`] 写入请求扩展；未启用多租户时为默认租户。
+#[derive(Debug, Clone)]
+pub struct CurrentTenant(pub TenantDefinition);
+
+impl CurrentTenant {
+    pub fn id(&self) -> &str {
+        &self.0.id
+    }
+}
+
+impl FromRequestParts<AppState> for CurrentTenant {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<CurrentTenant>()
+            .cloned()
+            .unwrap_or_else(|| CurrentTenant(TenantService::default_tenant())))
+    }
+}
+
+/// 租户解析中间件
+///
+/// 包裹在整个路由之外执行：host 模式按 Host 请求头解析租户，未配置的域名使用默认租户；
+/// path 模式识别 `/t/{tenant}` 前缀并在路由前将其去掉，未带前缀的请求使用默认租户。
+pub async fn tenant_middleware(mut req: Request, next: Next) -> Response {
+    let tenant = match TenantService::mode() {
+        TenantMode::Off => TenantService::default_tenant(),
+        TenantMode::Host => request_host(&req)
+            .and_then(|host| TenantService::resolve_host(&host))
+            .unwrap_or_else(TenantService::default_tenant),
+        TenantMode::Path => match strip_tenant_prefix(req.uri()) {
+            Some((tenant_id, uri)) => {
+                let Some(tenant) = TenantService::find(&tenant_id) else {
+                    return ApiError::NotFound("站点不存在".to_string()).into_response();
+                };
+                *req.uri_mut() = uri;
+                tenant
+            }
+            None => TenantService::default_tenant(),
+        },
+    };
+
+    req.extensions_mut().insert(CurrentTenant(tenant));
+    next.run(req).await
+}
+
+/// 请求域名（去掉端口）
+fn request_host(req: &Request) -> Option<String> {
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| req.uri().host())?;
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    Some(host.trim_end_matches('.').to_lowercase())
+}
+
+/// 拆出 `/t/{tenant}` 前缀，返回租户标识与去掉前缀后的 URI
+fn strip_tenant_prefix(uri: &Uri) -> Option<(String, Uri)> {
+    let rest = uri.path().strip_prefix(TenantService::PATH_PREFIX)?;
+    let (tenant_id, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    if tenant_id.is_empty() {
+        return None;
+    }
+
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_string(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    let uri = Uri::from_parts(parts).ok()?;
+
+    Some((tenant_id.to_lowercase(), uri))
+}
@@ -0,0 +1,608 @@
+use std::{collections::BTreeSet, fmt};
+
+use utoipa::openapi::OpenApi;
+
+use self::{RateLimitClass::*, RouteAuth::*};
+
+/// 路由的鉴权要求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteAuth {
+    /// 无需登录
+    Public,
+    /// 登录可选，登录后返回更多信息
+    Optional,
+    /// 需要登录
+    User,
+    /// 需要管理人员（admin 或 moderator）
+    Staff,
+    /// 需要管理员
+    Admin,
+    /// 需要内部访问令牌
+    Internal,
+    /// 需要服务器推送密钥签名
+    Signed,
+}
+
+impl RouteAuth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteAuth::Public => "public",
+            RouteAuth::Optional => "optional",
+            RouteAuth::User => "user",
+            RouteAuth::Staff => "staff",
+            RouteAuth::Admin => "admin",
+            RouteAuth::Internal => "internal",
+            RouteAuth::Signed => "signed",
+        }
+    }
+
+    /// 文档中应声明的安全方案
+    fn expected_security(&self) -> Option<&'static str> {
+        match self {
+            RouteAuth::User | RouteAuth::Staff | RouteAuth::Admin => Some("bearer_auth"),
+            RouteAuth::Internal => Some("internal_token"),
+            RouteAuth::Public | RouteAuth::Optional | RouteAuth::Signed => None,
+        }
+    }
+}
+
+/// 路由的限流分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitClass {
+    /// 普通接口
+    Standard,
+    /// 经过扫描防护，按来源限制逐个遍历服务器 ID
+    ScanGuarded,
+    /// 登录、注册与验证码
+    Credentials,
+    /// 状态数据写入，调用方为采集器或服务器插件
+    Ingest,
+    /// 管理后台
+    Backoffice,
+}
+
+impl RateLimitClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitClass::Standard => "standard",
+            RateLimitClass::ScanGuarded => "scan_guarded",
+            RateLimitClass::Credentials => "credentials",
+            RateLimitClass::Ingest => "ingest",
+            RateLimitClass::Backoffice => "backoffice",
+        }
+    }
+}
+
+/// 一条路由的元数据
+#[derive(Debug, Clone, Copy)]
+pub struct RouteMeta {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub auth: RouteAuth,
+    pub rate_limit: RateLimitClass,
+}
+
+const fn route(
+    method: &'static str,
+    path: &'static str,
+    auth: RouteAuth,
+    rate_limit: RateLimitClass,
+) -> RouteMeta {
+    RouteMeta {
+        method,
+        path,
+        auth,
+        rate_limit,
+    }
+}
+
+/// 全部路由的元数据登记表
+///
+/// 新增路由时需要同时在这里登记；[`check`] 会与 OpenAPI 文档逐条比对，
+/// 防止新路由漏登记，或管理、内部路由在文档中缺少对应的鉴权声明。
+const ROUTES: &[RouteMeta] = &[
+    route("get", "/v2/servers", Optional, Standard),
+    route("post", "/v2/servers", User, Standard),
+    route("get", "/v2/servers/players", Public, Standard),
+    route("get", "/v2/servers/live", Public, Standard),
+    route("get", "/v2/servers/slug/{slug}", Optional, Standard),
+    route("get", "/v2/servers/{server_id}", Optional, ScanGuarded),
+    route("put", "/v2/servers/{server_id}", User, ScanGuarded),
+    route("delete", "/v2/servers/{server_id}", User, ScanGuarded),
+    route(
+        "get",
+        "/v2/servers/{server_id}/summary",
+        Optional,
+        ScanGuarded,
+    ),
+    route("get", "/v2/servers/{server_id}/managers", Public, Standard),
+    route("post", "/v2/servers/{server_id}/managers", User, Standard),
+    route(
+        "put",
+        "/v2/servers/{server_id}/managers/{user_id}",
+        User,
+        Standard,
+    ),
+    route(
+        "delete",
+        "/v2/servers/{server_id}/managers/{user_id}",
+        User,
+        Standard,
+    ),
+    route(
+        "post",
+        "/v2/servers/{server_id}/managers/{user_id}/transfer",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/servers/{server_id}/cover", Public, Standard),
+    route("delete", "/v2/servers/{server_id}/cover", User, Standard),
+    route("get", "/v2/servers/{server_id}/motd.png", Public, Standard),
+    route("post", "/v2/servers/{server_id}/icon", User, Standard),
+    route("delete", "/v2/servers/{server_id}/icon", User, Standard),
+    route("get", "/v2/servers/{server_id}/gallery", Public, Standard),
+    route("post", "/v2/servers/{server_id}/gallery", User, Standard),
+    route("delete", "/v2/servers/{server_id}/gallery", User, Standard),
+    route(
+        "get",
+        "/v2/servers/{server_id}/gallery/feed",
+        Public,
+        Standard,
+    ),
+    route(
+        "put",
+        "/v2/servers/{server_id}/gallery/order",
+        User,
+        Standard,
+    ),
+    route(
+        "patch",
+        "/v2/servers/{server_id}/gallery/{image_id}",
+        User,
+        Standard,
+    ),
+    route(
+        "delete",
+        "/v2/servers/{server_id}/gallery/{image_id}",
+        User,
+        Standard,
+    ),
+    route("post", "/v2/servers/{server_id}/stats", Signed, Ingest),
+    route(
+        "get",
+        "/v2/servers/{server_id}/stats/history",
+        Public,
+        Standard,
+    ),
+    route(
+        "post",
+        "/v2/servers/{server_id}/push-secret",
+        User,
+        Standard,
+    ),
+    route(
+        "put",
+        "/v2/servers/{server_id}/custom-fields",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/servers/{server_id}/revisions", User, Standard),
+    route(
+        "post",
+        "/v2/servers/{server_id}/revisions/{revision_id}/rollback",
+        User,
+        Standard,
+    ),
+    route("post", "/v2/servers/{server_id}/submit", User, Standard),
+    route("post", "/v2/servers/{server_id}/archive", User, Standard),
+    route(
+        "post",
+        "/v2/servers/{server_id}/tags/suggest",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/servers/{server_id}/similar", Optional, Standard),
+    route("get", "/v2/servers/{server_id}/timeline", Public, Standard),
+    route("get", "/v2/servers/{server_id}/uptime", Public, Standard),
+    route("post", "/v2/servers/{server_id}/favorite", User, Standard),
+    route("delete", "/v2/servers/{server_id}/favorite", User, Standard),
+    route("post", "/v2/auth/login", Public, Credentials),
+    route("post", "/v2/auth/logout", User, Credentials),
+    route("post", "/v2/auth/register/email-code", Public, Credentials),
+    route("post", "/v2/auth/register", Public, Credentials),
+    route(
+        "post",
+        "/v2/auth/password-reset/request",
+        Public,
+        Credentials,
+    ),
+    route(
+        "post",
+        "/v2/auth/password-reset/confirm",
+        Public,
+        Credentials,
+    ),
+    route(
+        "get",
+        "/v2/auth/oauth/{provider}/authorize",
+        Public,
+        Credentials,
+    ),
+    route(
+        "get",
+        "/v2/auth/oauth/{provider}/callback",
+        Optional,
+        Credentials,
+    ),
+    route("get", "/v2/search", Optional, Standard),
+    route("get", "/v2/search/facets", Public, Standard),
+    route("get", "/v2/search/suggest", Public, Standard),
+    route("get", "/v2/tags", Public, Standard),
+    route("get", "/v2/search/players", Public, Standard),
+    route("post", "/v2/internal/stats/batch", Internal, Ingest),
+    route("post", "/v2/internal/links/confirm", Internal, Standard),
+    route("post", "/v2/internal/email/events", Internal, Ingest),
+    route(
+        "get",
+        "/v2/internal/links/{provider}/{external_id}",
+        Internal,
+        Standard,
+    ),
+    route(
+        "delete",
+        "/v2/internal/links/{provider}/{external_id}",
+        Internal,
+        Standard,
+    ),
+    route("get", "/v2/admin/registration-flags", Staff, Backoffice),
+    route(
+        "get",
+        "/v2/admin/registration-flags/stats",
+        Staff,
+        Backoffice,
+    ),
+    route(
+        "post",
+        "/v2/admin/registration-flags/{flag_id}/review",
+        Staff,
+        Backoffice,
+    ),
+    route("get", "/v2/admin/spam-holds", Staff, Backoffice),
+    route(
+        "post",
+        "/v2/admin/spam-holds/{hold_id}/review",
+        Staff,
+        Backoffice,
+    ),
+    route("get", "/v2/admin/users/{user_id}", Admin, Backoffice),
+    route(
+        "delete",
+        "/v2/admin/users/{user_id}/email-suppression",
+        Admin,
+        Backoffice,
+    ),
+    route(
+        "patch",
+        "/v2/admin/users/{user_id}/names",
+        Admin,
+        Backoffice,
+    ),
+    route(
+        "post",
+        "/v2/admin/users/{user_id}/impersonate",
+        Admin,
+        Backoffice,
+    ),
+    route("post", "/v2/admin/users/{user_id}/ban", Staff, Backoffice),
+    route("delete", "/v2/admin/users/{user_id}/ban", Staff, Backoffice),
+    route("get", "/v2/admin/bans", Staff, Backoffice),
+    route("post", "/v2/admin/tags/merge", Admin, Backoffice),
+    route("get", "/v2/admin/tags/vocabulary", Admin, Backoffice),
+    route("put", "/v2/admin/tags/vocabulary/{tag}", Admin, Backoffice),
+    route(
+        "delete",
+        "/v2/admin/tags/vocabulary/{tag}",
+        Admin,
+        Backoffice,
+    ),
+    route("get", "/v2/admin/delisting", Admin, Backoffice),
+    route(
+        "put",
+        "/v2/admin/servers/{server_id}/delisting",
+        Admin,
+        Backoffice,
+    ),
+    route("get", "/v2/admin/servers", Admin, Backoffice),
+    route("put", "/v2/admin/servers/{server_id}", Admin, Backoffice),
+    route(
+        "put",
+        "/v2/admin/servers/{server_id}/visibility",
+        Admin,
+        Backoffice,
+    ),
+    route(
+        "post",
+        "/v2/admin/servers/{server_id}/approve",
+        Admin,
+        Backoffice,
+    ),
+    route(
+        "post",
+        "/v2/admin/servers/{server_id}/reject",
+        Admin,
+        Backoffice,
+    ),
+    route(
+        "delete",
+        "/v2/admin/servers/{server_id}/gallery/{image_id}",
+        Admin,
+        Backoffice,
+    ),
+    route("get", "/v2/admin/feature-flags", Admin, Backoffice),
+    route("put", "/v2/admin/feature-flags/{key}", Admin, Backoffice),
+    route("delete", "/v2/admin/feature-flags/{key}", Admin, Backoffice),
+    route("get", "/v2/admin/status/incidents", Admin, Backoffice),
+    route("put", "/v2/admin/status/incidents/{key}", Admin, Backoffice),
+    route(
+        "delete",
+        "/v2/admin/status/incidents/{key}",
+        Admin,
+        Backoffice,
+    ),
+    route("get", "/v2/meta/version", Public, Standard),
+    route("get", "/v2/meta/status", Public, Standard),
+    route("get", "/v2/meta/sitemap.xml", Public, Standard),
+    route("get", "/v2/users/me", User, Standard),
+    route("patch", "/v2/users/me", User, Standard),
+    route("delete", "/v2/users/me", User, Standard),
+    route("post", "/v2/users/me/avatar", User, Standard),
+    route("get", "/v2/users/me/activity", User, Standard),
+    route("get", "/v2/users/me/favorites", User, Standard),
+    route("get", "/v2/users/me/links", User, Standard),
+    route("post", "/v2/users/me/links", User, Standard),
+    route("delete", "/v2/users/me/links/{link_id}", User, Standard),
+    route(
+        "post",
+        "/v2/users/me/links/oauth/{provider}",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/users/me/sessions", User, Standard),
+    route("delete", "/v2/users/me/sessions", User, Standard),
+    route(
+        "delete",
+        "/v2/users/me/sessions/{session_id}",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/users/me/api-keys", User, Standard),
+    route("post", "/v2/users/me/api-keys", User, Standard),
+    route("delete", "/v2/users/me/api-keys/{key_id}", User, Standard),
+    route("get", "/v2/users/me/notifications", User, Standard),
+    route("post", "/v2/users/me/notifications/read", User, Standard),
+    route(
+        "get",
+        "/v2/users/me/notifications/unread-count",
+        User,
+        Standard,
+    ),
+    route("get", "/v2/users/me/preferences", User, Standard),
+    route("put", "/v2/users/me/preferences", User, Standard),
+    route("post", "/v2/users/me/email", User, Credentials),
+    route("post", "/v2/users/me/email/confirm", User, Credentials),
+    route("post", "/v2/users/me/password", User, Credentials),
+    route("get", "/v2/sandbox/servers", Public, Standard),
+    route("get", "/v2/sandbox/servers/players", Public, Standard),
+    route("get", "/v2/sandbox/servers/{server_id}", Public, Standard),
+    route("put", "/v2/sandbox/servers/{server_id}", Public, Standard),
+    route(
+        "get",
+        "/v2/sandbox/servers/{server_id}/managers",
+        Public,
+        Standard,
+    ),
+    route(
+        "get",
+        "/v2/sandbox/servers/{server_id}/gallery",
+        Public,
+        Standard,
+    ),
+    route("get", "/v2/sandbox/search", Public, Standard),
+    route(
+        "post",
+        "/v2/sandbox/auth/register/email-code",
+        Public,
+        Credentials,
+    ),
+    route("get", "/health", Public, Standard),
+    route("get", "/health/live", Public, Standard),
+    route("get", "/health/ready", Public, Standard),
+    route("get", "/metrics", Internal, Standard),
+    route("get", "/static/{*key}", Public, Standard),
+];
+
+/// 仅在启用 `dev-tools` 特性时挂载的路由
+#[cfg(feature = "dev-tools")]
+const DEV_ROUTES: &[RouteMeta] = &[route("post", "/v2/dev/seed", Admin, Standard)];
+#[cfg(not(feature = "dev-tools"))]
+const DEV_ROUTES: &[RouteMeta] = &[];
+
+/// 仅在启用 `chaos` 特性时挂载的路由
+#[cfg(feature = "chaos")]
+const CHAOS_ROUTES: &[RouteMeta] = &[
+    route("get", "/v2/admin/chaos", Admin, Backoffice),
+    route("put", "/v2/admin/chaos", Admin, Backoffice),
+    route("delete", "/v2/admin/chaos", Admin, Backoffice),
+];
+#[cfg(not(feature = "chaos"))]
+const CHAOS_ROUTES: &[RouteMeta] = &[];
+
+/// 当前构建中的全部路由
+pub fn inventory() -> impl Iterator<Item = &'static RouteMeta> {
+    ROUTES.iter().chain(DEV_ROUTES).chain(CHAOS_ROUTES)
+}
+
+/// 登记表与 OpenAPI 文档不一致的地方
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteProblem {
+    /// 文档中有、登记表中没有
+    Unregistered { method: String, path: String },
+    /// 登记表中有、文档中没有（不计入 `/v2` 之外的运维路由）
+    Undocumented { method: String, path: String },
+    /// 文档声明的安全方案与登记的鉴权要求不符
+    SecurityMismatch {
+        method: String,
+        path: String,
+        auth: RouteAuth,
+        documented: Vec<String>,
+    },
+    /// 管理或内部前缀下的路由登记为不需要对应权限
+    WeakPrefixAuth {
+        method: String,
+        path: String,
+        auth: RouteAuth,
+    },
+}
+
+impl fmt::Display for RouteProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteProblem::Unregistered { method, path } => {
+                write!(f, "{} {} 未在路由登记表中登记", method.to_uppercase(), path)
+            }
+            RouteProblem::Undocumented { method, path } => {
+                write!(f, "{} {} 缺少 OpenAPI 文档", method.to_uppercase(), path)
+            }
+            RouteProblem::SecurityMismatch {
+                method,
+                path,
+                auth,
+                documented,
+            } => write!(
+                f,
+                "{} {} 登记为 {}，但文档声明的安全方案为 {:?}",
+                method.to_uppercase(),
+                path,
+                auth.as_str(),
+                documented
+            ),
+            RouteProblem::WeakPrefixAuth { method, path, auth } => write!(
+                f,
+                "{} {} 位于受保护的前缀下，但登记为 {}",
+                method.to_uppercase(),
+                path,
+                auth.as_str()
+            ),
+        }
+    }
+}
+
+/// 将登记表与 OpenAPI 文档逐条比对
+pub fn check(doc: &OpenApi) -> Vec<RouteProblem> {
+    let mut problems = Vec::new();
+    let documented = documented_operations(doc);
+    let registered: BTreeSet<(String, String)> = inventory()
+        .map(|r| (r.method.to_string(), r.path.to_string()))
+        .collect();
+
+    for (method, path, _) in &documented {
+        if !registered.contains(&(method.clone(), path.clone())) {
+            problems.push(RouteProblem::Unregistered {
+                method: method.clone(),
+                path: path.clone(),
+            });
+        }
+    }
+
+    for route in inventory() {
+        let weak_prefix = (route.path.starts_with("/v2/admin/")
+            && !matches!(route.auth, RouteAuth::Admin | RouteAuth::Staff))
+            || (route.path.starts_with("/v2/internal/") && route.auth != RouteAuth::Internal);
+        if weak_prefix {
+            problems.push(RouteProblem::WeakPrefixAuth {
+                method: route.method.to_string(),
+                path: route.path.to_string(),
+                auth: route.auth,
+            });
+        }
+
+        let Some((_, _, security)) = documented
+            .iter()
+            .find(|(method, path, _)| method == route.method && path == route.path)
+        else {
+            if route.path.starts_with("/v2/") {
+                problems.push(RouteProblem::Undocumented {
+                    method: route.method.to_string(),
+                    path: route.path.to_string(),
+                });
+            }
+            continue;
+        };
+
+        let consistent = match route.auth.expected_security() {
+            Some(scheme) => security.iter().any(|s| s == scheme),
+            None if route.auth == RouteAuth::Optional => true,
+            None => security.is_empty(),
+        };
+        if !consistent {
+            problems.push(RouteProblem::SecurityMismatch {
+                method: route.method.to_string(),
+                path: route.path.to_string(),
+                auth: route.auth,
+                documented: security.clone(),
+            });
+        }
+    }
+
+    problems
+}
+
+/// 启动时输出全部路由及其鉴权要求、限流分类，并提示与文档不一致的地方
+pub fn log_inventory(doc: &OpenApi) {
+    let mut routes: Vec<_> = inventory().collect();
+    routes.sort_by_key(|route| (route.path, route.method));
+
+    tracing::info!("📋 已挂载 {} 条路由:", routes.len());
+    for route in routes {
+        tracing::info!(
+            "  {:<6} {:<60} auth={:<8} rate_limit={}",
+            route.method.to_uppercase(),
+            route.path,
+            route.auth.as_str(),
+            route.rate_limit.as_str()
+        );
+    }
+
+    for problem in check(doc) {
+        tracing::warn!("⚠️  路由登记问题: {}", problem);
+    }
+}
+
+/// 文档中的 (方法, 路径, 安全方案)
+fn documented_operations(doc: &OpenApi) -> Vec<(String, String, Vec<String>)> {
+    let mut operations = Vec::new();
+    for (path, item) in &doc.paths.paths {
+        for (name, operation) in [
+            ("get", &item.get),
+            ("post", &item.post),
+            ("put", &item.put),
+            ("patch", &item.patch),
+            ("delete", &item.delete),
+        ] {
+            let Some(operation) = operation else {
+                continue;
+            };
+
+            let security = serde_json::to_value(&operation.security)
+                .ok()
+                .and_then(|value| value.as_array().cloned())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|requirement| requirement.as_object())
+                .flat_map(|requirement| requirement.keys().cloned())
+                .collect();
+            operations.push((name.to_string(), path.clone(), security));
+        }
+    }
+    operations
+}
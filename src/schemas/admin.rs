@@ -0,0 +1,746 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::schemas::{
+    auth::{DISPLAY_NAME_REGEX, USERNAME_REGEX},
+    meta::IncidentSeverity,
+    servers::{ServerStatus, ServerVisibility},
+};
+
+fn default_page() -> u64 {
+    1
+}
+fn default_page_size() -> u64 {
+    20
+}
+fn default_true() -> bool {
+    true
+}
+
+/// 注册标记的处理状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationFlagStatus {
+    /// 待审核
+    Pending,
+    /// 确认可疑，账户已停用
+    Confirmed,
+    /// 误报，已忽略
+    Dismissed,
+}
+
+impl RegistrationFlagStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegistrationFlagStatus::Pending => "pending",
+            RegistrationFlagStatus::Confirmed => "confirmed",
+            RegistrationFlagStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+/// 封禁类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BanType {
+    /// 禁言，只能浏览，不能发起写操作
+    Mute,
+    /// 封禁，直到管理人员解除
+    Ban,
+    /// 临时封禁，到期自动解除
+    TempBan,
+}
+
+impl BanType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BanType::Mute => "mute",
+            BanType::Ban => "ban",
+            BanType::TempBan => "temp_ban",
+        }
+    }
+}
+
+/// 封禁用户请求
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateBanRequest {
+    /// 封禁类型
+    pub ban_type: BanType,
+    /// 封禁原因，会展示给被封禁的用户
+    #[validate(length(max = 500, message = "原因不能超过 500 个字符"))]
+    #[schema(example = "发布广告")]
+    pub reason: Option<String>,
+    /// 解封时间（UTC），临时封禁必填，禁言可选
+    #[serde(default, with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// 封禁记录列表查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct BanQuery {
+    /// 只返回仍生效的封禁，缺省为 true
+    #[schema(example = true, default = true)]
+    #[serde(default = "default_true")]
+    pub active: bool,
+    /// 按用户过滤
+    #[schema(example = 42)]
+    pub user_id: Option<i32>,
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 封禁记录
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BanInfo {
+    /// 记录 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 被封禁的用户 ID
+    #[schema(example = 42)]
+    pub user_id: i32,
+    /// 用户名，账户已删除时为空
+    #[schema(example = "player003")]
+    pub username: Option<String>,
+    /// 封禁类型
+    #[schema(example = "temp_ban")]
+    pub ban_type: String,
+    /// 封禁原因
+    #[schema(example = "发布广告")]
+    pub reason: Option<String>,
+    /// 开始时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub started_at: DateTime<Utc>,
+    /// 结束时间（UTC），为空表示直到管理人员解除
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-08T00:00:00Z", format = DateTime)]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// 是否仍生效
+    #[schema(example = true)]
+    pub active: bool,
+}
+
+/// 注册标记列表查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct RegistrationFlagQuery {
+    /// 按状态过滤，缺省为待审核
+    #[schema(example = "pending")]
+    pub status: Option<RegistrationFlagStatus>,
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 可疑注册标记
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RegistrationFlagInfo {
+    /// 标记 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 被标记的用户 ID
+    #[schema(example = 42)]
+    pub user_id: i32,
+    /// 用户名
+    #[schema(example = "player003")]
+    pub username: String,
+    /// 邮箱
+    #[schema(example = "player003@mailinator.com")]
+    pub email: String,
+    /// 触发的规则
+    #[schema(example = json!(["ip_burst", "flagged_domain"]))]
+    pub reasons: Vec<String>,
+    /// 注册 IP
+    #[schema(example = "203.0.113.7")]
+    pub ip: Option<String>,
+    /// 处理状态
+    pub status: RegistrationFlagStatus,
+    /// 标记时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 审核人 ID
+    pub reviewed_by_id: Option<i32>,
+    /// 审核时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// 审核可疑注册
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewRegistrationFlagRequest {
+    /// 是否确认可疑，确认后账户会被停用
+    #[schema(example = true)]
+    pub confirmed: bool,
+}
+
+/// 单条规则的标记准确率
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FlagReasonPrecision {
+    /// 规则名
+    #[schema(example = "ip_burst")]
+    pub reason: String,
+    /// 已确认数
+    #[schema(example = 8)]
+    pub confirmed: u64,
+    /// 已忽略数
+    #[schema(example = 2)]
+    pub dismissed: u64,
+    /// 准确率（已确认 / 已审核），未审核时为空
+    #[schema(example = 0.8)]
+    pub precision: Option<f64>,
+}
+
+/// 可疑注册标记统计
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegistrationFlagStats {
+    /// 待审核数
+    #[schema(example = 3)]
+    pub pending: u64,
+    /// 已确认数
+    #[schema(example = 10)]
+    pub confirmed: u64,
+    /// 已忽略数
+    #[schema(example = 4)]
+    pub dismissed: u64,
+    /// 总体准确率
+    #[schema(example = 0.714)]
+    pub precision: Option<f64>,
+    /// 各规则的准确率
+    pub by_reason: Vec<FlagReasonPrecision>,
+}
+
+/// 功能开关
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeatureFlagInfo {
+    /// 开关标识
+    #[schema(example = "search_ranking_v2")]
+    pub key: String,
+    /// 说明
+    #[schema(example = "新版搜索排序")]
+    pub description: Option<String>,
+    /// 是否启用；关闭时仅白名单用户可见
+    #[schema(example = true)]
+    pub enabled: bool,
+    /// 灰度比例（0~100），按用户 ID 稳定分桶
+    #[schema(example = 10)]
+    pub rollout_percentage: u8,
+    /// 白名单用户 ID，不受开关与比例限制
+    #[schema(example = json!([1, 2]))]
+    pub user_ids: Vec<i32>,
+    /// 最后修改人
+    #[schema(example = 1)]
+    pub updated_by_id: Option<i32>,
+    /// 最后修改时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 功能开关列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeatureFlagListResponse {
+    /// 开关列表
+    pub data: Vec<FeatureFlagInfo>,
+}
+
+/// 创建或更新功能开关
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateFeatureFlagRequest {
+    /// 说明
+    #[schema(example = "新版搜索排序")]
+    pub description: Option<String>,
+    /// 是否启用
+    #[schema(example = true)]
+    pub enabled: bool,
+    /// 灰度比例（0~100）
+    #[schema(example = 10, minimum = 0, maximum = 100)]
+    pub rollout_percentage: u8,
+    /// 白名单用户 ID
+    #[serde(default)]
+    #[schema(example = json!([1, 2]))]
+    pub user_ids: Vec<i32>,
+}
+
+/// 参与垃圾内容检测的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamContentType {
+    /// 服务器评价
+    Review,
+    /// 工单
+    Ticket,
+    /// 服务器公告
+    Announcement,
+}
+
+impl SpamContentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpamContentType::Review => "review",
+            SpamContentType::Ticket => "ticket",
+            SpamContentType::Announcement => "announcement",
+        }
+    }
+}
+
+/// 被扣留内容的处理状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamHoldStatus {
+    /// 待审核
+    Pending,
+    /// 审核通过，内容正常发布
+    Approved,
+    /// 确认为垃圾内容
+    Rejected,
+}
+
+impl SpamHoldStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpamHoldStatus::Pending => "pending",
+            SpamHoldStatus::Approved => "approved",
+            SpamHoldStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// 扣留内容列表查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct SpamHoldQuery {
+    /// 按状态过滤，缺省为待审核
+    #[schema(example = "pending")]
+    pub status: Option<SpamHoldStatus>,
+    /// 按内容类型过滤
+    #[schema(example = "ticket")]
+    pub content_type: Option<SpamContentType>,
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 被扣留待审核的内容
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SpamHoldInfo {
+    /// 扣留记录 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 内容类型
+    pub content_type: SpamContentType,
+    /// 内容 ID
+    #[schema(example = 15)]
+    pub content_id: Option<i32>,
+    /// 作者 ID
+    #[schema(example = 42)]
+    pub author_id: i32,
+    /// 作者用户名
+    #[schema(example = "player003")]
+    pub author_username: String,
+    /// 垃圾内容评分（0~100）
+    #[schema(example = 70)]
+    pub score: i32,
+    /// 命中的规则
+    #[schema(example = json!(["duplicate_content", "link_density"]))]
+    pub reasons: Vec<String>,
+    /// 内容摘要
+    #[schema(example = "免费领取皮肤 https://spam.example.com")]
+    pub excerpt: String,
+    /// 处理状态
+    pub status: SpamHoldStatus,
+    /// 扣留时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 审核人 ID
+    pub reviewed_by_id: Option<i32>,
+    /// 审核时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// 审核扣留内容
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewSpamHoldRequest {
+    /// 是否放行；`false` 表示确认为垃圾内容
+    #[schema(example = false)]
+    pub approved: bool,
+}
+
+/// 管理员修改用户名称，不受保留名称限制
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateUserNamesRequest {
+    /// 新用户名
+    #[validate(length(min = 3, max = 20, message = "用户名长度必须在 3 到 20 个字符之间"))]
+    #[validate(regex(path = "*USERNAME_REGEX", message = "用户名只能包含字母、数字和下划线"))]
+    #[schema(example = "mscpo_official")]
+    pub username: Option<String>,
+    /// 新显示名称
+    #[validate(length(
+        min = 2,
+        max = 16,
+        message = "显示名称不能少于 2 个字符，不能超过 16 个字符"
+    ))]
+    #[validate(regex(
+        path = "*DISPLAY_NAME_REGEX",
+        message = "显示名称只能包含中文、英文、俄文、数字、下划线和短横线"
+    ))]
+    #[schema(example = "MSCPO官方")]
+    pub display_name: Option<String>,
+}
+
+/// 邮箱投递状态
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailDeliveryStatus {
+    /// 是否可以正常发送邮件
+    #[schema(example = false)]
+    pub deliverable: bool,
+    /// 停止发送的原因（`bounce` 或 `complaint`），可正常发送时为空
+    #[schema(example = "bounce")]
+    pub reason: Option<String>,
+    /// 服务商提供的原因说明
+    #[schema(example = "550 5.1.1 user unknown")]
+    pub detail: Option<String>,
+    /// 最近一次收到退信或投诉的时间
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub suppressed_at: Option<DateTime<Utc>>,
+}
+
+/// 管理员查看的用户详情
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminUserDetail {
+    /// 用户 ID
+    #[schema(example = 42)]
+    pub id: i32,
+    /// 用户名
+    #[schema(example = "user123")]
+    pub username: String,
+    /// 显示名称
+    #[schema(example = "张三-Mike")]
+    pub display_name: String,
+    /// 邮箱
+    #[schema(example = "user@example.com")]
+    pub email: String,
+    /// 角色
+    #[schema(example = "user")]
+    pub role: String,
+    /// 账户是否启用
+    #[schema(example = true)]
+    pub is_active: bool,
+    /// 所属租户
+    #[schema(example = "default")]
+    pub tenant_id: String,
+    /// 注册时间
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 最近登录时间
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-02T00:00:00Z", format = DateTime)]
+    pub last_login: Option<DateTime<Utc>>,
+    /// 邮箱投递状态
+    pub email_status: EmailDeliveryStatus,
+}
+
+/// 代入用户身份请求
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ImpersonateRequest {
+    /// 代入原因，如对应的工单或反馈编号，会写入操作记录
+    #[validate(length(min = 4, max = 200, message = "代入原因长度必须在 4 到 200 个字符之间"))]
+    #[schema(example = "复现工单 #1024 中的上传失败问题")]
+    pub reason: String,
+}
+
+/// 代入令牌
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationToken {
+    /// 以目标用户身份访问的短期令牌，令牌中的 `impersonator` 字段为管理员 ID
+    #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
+    pub access_token: String,
+    /// 有效期（秒）
+    #[schema(example = 900)]
+    pub expires_in: u64,
+    /// 被代入的用户 ID
+    #[schema(example = 42)]
+    pub user_id: i32,
+    /// 发起代入的管理员 ID
+    #[schema(example = 1)]
+    pub impersonator_id: i32,
+}
+
+/// 合并或重命名标签
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeTagsRequest {
+    /// 被合并的标签，只传一个即为重命名
+    #[schema(example = json!(["红石"]))]
+    pub from: Vec<String>,
+    /// 目标标签
+    #[schema(example = "生电")]
+    pub to: String,
+}
+
+/// 标签合并结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MergeTagsResponse {
+    /// 被合并的标签
+    #[schema(example = json!(["红石"]))]
+    pub from: Vec<String>,
+    /// 目标标签
+    #[schema(example = "生电")]
+    pub to: String,
+    /// 受影响的服务器数
+    #[schema(example = 12)]
+    pub affected_servers: u64,
+}
+
+/// 标签词表条目
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagVocabularyEntry {
+    /// 标签名
+    #[schema(example = "生电")]
+    pub name: String,
+    /// 标签说明
+    #[schema(example = "红石与生电玩法")]
+    pub description: Option<String>,
+    /// 最后修改人 ID
+    #[schema(example = 1)]
+    pub updated_by_id: Option<i32>,
+    /// 最后修改时间
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 标签词表
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagVocabularyListResponse {
+    /// 词表条目，按标签名排序
+    pub data: Vec<TagVocabularyEntry>,
+}
+
+/// 添加或修改标签词表条目
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpsertTagVocabularyRequest {
+    /// 标签说明
+    #[validate(length(max = 100, message = "标签说明不能超过 100 个字符"))]
+    #[schema(example = "红石与生电玩法")]
+    pub description: Option<String>,
+}
+
+/// 服务器离线下架状态
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DelistingInfo {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub server_id: i32,
+    /// 服务器名称
+    #[schema(example = "MSCPO 生存服")]
+    pub name: String,
+    /// 最近一次上报状态的时间
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// 被标记为长期离线并通知服主的时间
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-31T00:00:00Z", format = DateTime)]
+    pub offline_flagged_at: Option<DateTime<Utc>>,
+    /// 自动下架时间，未下架为空
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-02-07T00:00:00Z", format = DateTime)]
+    pub delisted_at: Option<DateTime<Utc>>,
+    /// 是否豁免自动下架
+    #[schema(example = false)]
+    pub exempt: bool,
+}
+
+/// 离线下架状态列表
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DelistingListResponse {
+    /// 已标记、已下架或被豁免的服务器
+    pub data: Vec<DelistingInfo>,
+}
+
+/// 调整服务器的离线下架状态
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDelistingRequest {
+    /// 是否豁免自动下架，不传则保持不变
+    #[schema(example = true)]
+    pub exempt: Option<bool>,
+    /// 是否清除离线标记并立即重新上架
+    #[serde(default)]
+    #[schema(example = true)]
+    pub relist: bool,
+}
+
+/// 管理员服务器列表查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct AdminServerQuery {
+    /// 按名称、IP 或短链接模糊搜索
+    #[schema(example = "生存")]
+    pub q: Option<String>,
+    /// 按可见性过滤
+    #[schema(example = "hidden")]
+    pub visibility: Option<ServerVisibility>,
+    /// 按发布状态过滤，例如 `pending_review` 列出待审核的服务器
+    #[schema(example = "pending_review")]
+    pub status: Option<ServerStatus>,
+    /// 按是否已停用过滤，不传则全部返回
+    #[schema(example = false)]
+    pub deactivated: Option<bool>,
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 管理员查看的服务器，包括隐藏、下架与停用的服务器
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminServerInfo {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 服务器名称
+    #[schema(example = "MSCPO 生存服")]
+    pub name: String,
+    /// 服务器地址，不受可见性影响
+    #[schema(example = "mc.example.com:25565")]
+    pub ip: String,
+    /// 服务器短链接
+    #[schema(example = "mscpo-k3x9qa")]
+    pub slug: Option<String>,
+    /// 可见性
+    pub visibility: ServerVisibility,
+    /// 发布状态
+    pub status: ServerStatus,
+    /// 最近一次审核未通过的原因
+    #[schema(example = json!(null))]
+    pub review_note: Option<String>,
+    /// 是否为成员服务器
+    #[schema(example = false)]
+    pub is_member: bool,
+    /// 服主的用户 ID
+    #[schema(example = json!([42]))]
+    pub owner_ids: Vec<i32>,
+    /// 停用时间，未停用为空
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = json!(null), format = DateTime)]
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// 因长期离线被自动下架的时间，未下架为空
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = json!(null), format = DateTime)]
+    pub delisted_at: Option<DateTime<Utc>>,
+    /// 最后修改时间
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 管理员设置服务器可见性
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AdminVisibilityRequest {
+    /// 新的可见性，设为 `hidden` 即从列表、搜索与站点地图中隐藏
+    #[schema(example = "hidden")]
+    pub visibility: ServerVisibility,
+    /// 操作原因，会写入操作记录
+    #[validate(length(max = 200, message = "原因不能超过 200 个字符"))]
+    #[schema(example = "简介包含违规内容")]
+    pub reason: Option<String>,
+}
+
+/// 审核服务器发布申请
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ServerReviewRequest {
+    /// 审核意见，驳回时会展示给服主并写入操作记录
+    #[validate(length(max = 200, message = "原因不能超过 200 个字符"))]
+    #[schema(example = "简介过于简略，请补充玩法介绍")]
+    pub reason: Option<String>,
+}
+
+/// 状态页故障信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IncidentInfo {
+    /// 故障标识
+    #[schema(example = "search-degraded")]
+    pub key: String,
+    /// 标题
+    #[schema(example = "搜索服务响应缓慢")]
+    pub title: String,
+    /// 详细说明
+    #[schema(example = "正在排查，搜索结果可能延迟更新")]
+    pub message: Option<String>,
+    /// 严重程度
+    pub severity: IncidentSeverity,
+    /// 受影响的组件
+    #[schema(example = json!(["search"]))]
+    pub components: Vec<String>,
+    /// 是否进行中；仅进行中的故障会显示在状态页
+    #[schema(example = true)]
+    pub active: bool,
+    /// 开始时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub started_at: DateTime<Utc>,
+    /// 恢复时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T01:00:00Z", format = DateTime)]
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// 最后修改人
+    #[schema(example = 1)]
+    pub updated_by_id: Option<i32>,
+    /// 最后修改时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 状态页故障列表
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IncidentListResponse {
+    /// 故障列表，进行中的在前
+    pub data: Vec<IncidentInfo>,
+}
+
+/// 创建或更新状态页故障
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateIncidentRequest {
+    /// 标题
+    #[schema(example = "搜索服务响应缓慢")]
+    pub title: String,
+    /// 详细说明
+    #[schema(example = "正在排查，搜索结果可能延迟更新")]
+    pub message: Option<String>,
+    /// 严重程度
+    pub severity: IncidentSeverity,
+    /// 受影响的组件
+    #[serde(default)]
+    #[schema(example = json!(["search"]))]
+    pub components: Vec<String>,
+    /// 是否进行中，设为 false 即标记为已恢复
+    #[schema(example = true)]
+    pub active: bool,
+}
@@ -1,8 +1,11 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::{Validate, ValidationError};
+
+use crate::schemas::users::ExternalIdentityInfo;
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthToken {
     /// JWT 访问令牌
@@ -27,7 +30,7 @@ pub struct UserLoginData {
     pub password: String,
 }
 
-fn validate_password_complexity(password: &str) -> Result<(), ValidationError> {
+pub(crate) fn validate_password_complexity(password: &str) -> Result<(), ValidationError> {
     let has_letter = password.chars().any(|c| c.is_ascii_alphabetic());
     let has_digit = password.chars().any(|c| c.is_ascii_digit());
 
@@ -85,6 +88,110 @@ pub struct UserRegisterByEmailData {
     pub email: String,
 }
 
+/// 申请密码重置请求数据
+#[derive(Debug, Clone, Serialize, Validate, Deserialize, ToSchema)]
+pub struct PasswordResetRequestData {
+    /// 注册时使用的邮箱
+    #[validate(email(message = "邮箱格式不正确"))]
+    #[schema(example = "user@example.com")]
+    pub email: String,
+}
+
+/// 确认密码重置请求数据
+#[derive(Debug, Clone, Serialize, Validate, Deserialize, ToSchema)]
+pub struct PasswordResetConfirmData {
+    /// 邮箱
+    #[validate(email(message = "邮箱格式不正确"))]
+    #[schema(example = "user@example.com")]
+    pub email: String,
+
+    /// 邮件中的验证码
+    #[validate(length(equal = 6, message = "验证码长度必须为 6 位"))]
+    #[schema(example = "123456")]
+    pub code: String,
+
+    /// 新密码(长度在 8 到 32 个字符之间，必须包含字母和数字)
+    #[validate(length(min = 8, max = 32, message = "密码长度必须在 8 到 32 个字符之间"))]
+    #[validate(custom(function = "validate_password_complexity"))]
+    #[schema(example = "NewPassword123")]
+    pub new_password: String,
+}
+
+/// 第三方登录平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthProvider {
+    Github,
+    Microsoft,
+}
+
+impl OAuthProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OAuthProvider::Github => "github",
+            OAuthProvider::Microsoft => "microsoft",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "github" => Some(OAuthProvider::Github),
+            "microsoft" => Some(OAuthProvider::Microsoft),
+            _ => None,
+        }
+    }
+}
+
+/// 第三方登录授权地址
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OAuthAuthorizeResponse {
+    /// 需要跳转到的第三方授权页面
+    #[schema(
+        example = "https://github.com/login/oauth/authorize?client_id=Iv1.0123456789abcdef&state=Zf3kQ9mB2xLr7TpWc8VnH4sJ6dYq1aEu"
+    )]
+    pub authorize_url: String,
+    /// 本次授权请求的 state，回调时原样带回
+    #[schema(example = "Zf3kQ9mB2xLr7TpWc8VnH4sJ6dYq1aEu")]
+    pub state: String,
+    /// 授权请求的有效期（秒）
+    #[schema(example = 600)]
+    pub expires_in: u64,
+}
+
+/// 第三方平台回调参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct OAuthCallbackQuery {
+    /// 第三方平台返回的授权码
+    pub code: Option<String>,
+    /// 发起授权时生成的 state
+    pub state: String,
+    /// 用户拒绝授权等情况下第三方平台返回的错误码
+    pub error: Option<String>,
+}
+
+/// 第三方登录的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthOutcome {
+    /// 已绑定的账户登录
+    LoggedIn,
+    /// 新注册了账户并登录
+    Registered,
+    /// 绑定到了发起绑定的账户
+    Linked,
+}
+
+/// 第三方平台回调结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OAuthCallbackResponse {
+    /// 本次回调的结果
+    pub outcome: OAuthOutcome,
+    /// 访问令牌，绑定账户时为空
+    pub token: Option<AuthToken>,
+    /// 第三方账户的绑定信息
+    pub identity: ExternalIdentityInfo,
+}
+
 pub static USERNAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]+$").unwrap());
 
 pub static DISPLAY_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
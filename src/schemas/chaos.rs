@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 注入的故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    /// 延迟后正常处理请求
+    Delay,
+    /// 直接返回错误响应，不执行处理函数
+    Error,
+    /// 返回响应头后中断连接，模拟响应丢失
+    Drop,
+}
+
+/// 故障注入规则
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FaultRule {
+    /// 匹配的 HTTP 方法，为空时匹配全部方法
+    #[schema(example = "GET")]
+    pub method: Option<String>,
+    /// 匹配的路径前缀（不含租户前缀）
+    #[schema(example = "/v2/servers")]
+    pub path_prefix: String,
+    /// 故障类型
+    pub kind: FaultKind,
+    /// 命中概率（0~100）
+    #[schema(example = 20.0, minimum = 0, maximum = 100)]
+    pub percentage: f64,
+    /// 延迟毫秒数，`delay` 类型必填
+    #[schema(example = 1500)]
+    pub delay_ms: Option<u64>,
+    /// 返回的状态码，`error` 类型使用，默认 503
+    #[schema(example = 503)]
+    pub status: Option<u16>,
+}
+
+/// 故障注入规则列表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChaosRules {
+    /// 按顺序匹配，第一条命中路径的规则生效
+    pub rules: Vec<FaultRule>,
+}
@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// 需要二次确认的破坏性操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DestructiveAction {
+    /// 删除服务器
+    DeleteServer,
+    /// 批量删除画册图片
+    DeleteGalleryImages,
+    /// 注销账户
+    DeleteAccount,
+}
+
+impl DestructiveAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DestructiveAction::DeleteServer => "delete_server",
+            DestructiveAction::DeleteGalleryImages => "delete_gallery_images",
+            DestructiveAction::DeleteAccount => "delete_account",
+        }
+    }
+}
+
+/// 确认令牌参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ConfirmQuery {
+    /// 第一次调用返回的确认令牌；不传时只返回影响范围，不执行删除
+    pub confirm_token: Option<String>,
+}
+
+/// 破坏性操作的影响范围与确认令牌
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfirmationRequired {
+    /// 操作类型
+    pub action: DestructiveAction,
+    /// 确认令牌，带上该令牌再次调用同一接口才会执行，只能使用一次
+    #[schema(example = "Zf3kQ9mB2xLr7TpWc4NvY8sHq1JdG6eA")]
+    pub confirm_token: String,
+    /// 令牌过期时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:05:00Z", format = DateTime)]
+    pub expires_at: DateTime<Utc>,
+    /// 将被删除或受影响的数据条数
+    #[schema(example = json!({"servers": 1, "gallery_images": 12, "managers": 3}))]
+    pub impact: BTreeMap<String, u64>,
+}
@@ -0,0 +1,66 @@
+//! API 时间字段的统一序列化规则
+//!
+//! 所有对外的时间一律使用 UTC，按 RFC 3339 格式输出到秒并以 `Z` 结尾，
+//! 例如 `2025-01-01T08:00:00Z`。输入时接受任意时区偏移并换算为 UTC，
+//! 不带时区的时间会被拒绝，避免客户端与服务端对时区的理解不一致。
+//!
+//! 用法：`#[serde(with = "crate::schemas::datetime::rfc3339")]`，
+//! 可空字段使用 `rfc3339_option`。
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// 按统一规则格式化时间
+pub fn format(value: &DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// 解析带时区的 RFC 3339 时间并换算为 UTC
+pub fn parse(value: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc))
+}
+
+pub mod rfc3339 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse(&value)
+            .map_err(|e| serde::de::Error::custom(format!("时间需为带时区的 RFC 3339 格式: {e}")))
+    }
+}
+
+pub mod rfc3339_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&format(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| {
+                parse(&value).map_err(|e| {
+                    serde::de::Error::custom(format!("时间需为带时区的 RFC 3339 格式: {e}"))
+                })
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+fn default_count() -> u32 {
+    100
+}
+fn default_stats_per_server() -> u32 {
+    288
+}
+fn default_gallery_images_per_server() -> u32 {
+    3
+}
+fn default_stats_interval_secs() -> u32 {
+    300
+}
+
+/// 合成数据生成请求
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SeedRequest {
+    /// 生成的服务器数量
+    #[schema(example = 100, default = 100)]
+    #[serde(default = "default_count")]
+    #[validate(range(min = 1, max = 10000, message = "服务器数量必须在 1~10000 之间"))]
+    pub count: u32,
+    /// 每个服务器生成的历史状态条数
+    #[schema(example = 288, default = 288)]
+    #[serde(default = "default_stats_per_server")]
+    #[validate(range(max = 10000, message = "历史状态条数不能超过 10000"))]
+    pub stats_per_server: u32,
+    /// 相邻两条历史状态的时间间隔（秒）
+    #[schema(example = 300, default = 300)]
+    #[serde(default = "default_stats_interval_secs")]
+    #[validate(range(min = 1, message = "时间间隔必须大于 0"))]
+    pub stats_interval_secs: u32,
+    /// 每个服务器生成的相册图片数量（占位图，不上传 S3）
+    #[schema(example = 3, default = 3)]
+    #[serde(default = "default_gallery_images_per_server")]
+    #[validate(range(max = 50, message = "相册图片数量不能超过 50"))]
+    pub gallery_images_per_server: u32,
+    /// 随机种子，固定后可重复生成相同的数据
+    #[schema(example = 114514)]
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl Default for SeedRequest {
+    fn default() -> Self {
+        Self {
+            count: default_count(),
+            stats_per_server: default_stats_per_server(),
+            stats_interval_secs: default_stats_interval_secs(),
+            gallery_images_per_server: default_gallery_images_per_server(),
+            seed: None,
+        }
+    }
+}
+
+/// 合成数据生成结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeedSummary {
+    /// 生成的服务器 ID 列表
+    #[schema(example = json!([101, 102, 103]))]
+    pub server_ids: Vec<i32>,
+    /// 写入的历史状态条数
+    #[schema(example = 28800)]
+    pub stats_rows: u64,
+    /// 写入的相册图片数量
+    #[schema(example = 300)]
+    pub gallery_images: u64,
+    /// 耗时（毫秒）
+    #[schema(example = 5321)]
+    pub elapsed_ms: u128,
+}
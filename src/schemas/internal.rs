@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schemas::servers::ServerStats;
+
+/// 单个服务器的状态数据
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct StatsBatchItem {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub server_id: i32,
+    /// 状态数据，为空表示服务器离线；也可使用 `stat_data` 字段名
+    #[serde(alias = "stat_data")]
+    pub stats: Option<ServerStats>,
+    /// 采集时间，需带时区，缺省为接收时间，不能晚于当前时间；也可使用 `timestamp` 字段名
+    #[serde(
+        default,
+        alias = "timestamp",
+        with = "crate::schemas::datetime::rfc3339_option"
+    )]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub collected_at: Option<DateTime<Utc>>,
+}
+
+/// 批量状态上报请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct StatsBatchRequest {
+    /// 状态数据列表
+    pub items: Vec<StatsBatchItem>,
+}
+
+/// 被拒绝的状态数据
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsBatchRejection {
+    /// 服务器 ID
+    #[schema(example = 999)]
+    pub server_id: i32,
+    /// 拒绝原因
+    #[schema(example = "服务器不存在")]
+    pub reason: String,
+}
+
+/// 批量状态上报结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsBatchResponse {
+    /// 成功写入的条数
+    #[schema(example = 120)]
+    pub accepted: usize,
+    /// 被拒绝的条目
+    pub rejected: Vec<StatsBatchRejection>,
+}
+
+/// 外部平台确认账户绑定
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmLinkRequest {
+    /// 外部平台标识
+    #[schema(example = "mscpo_forum")]
+    pub provider: String,
+    /// 外部平台的账户 ID
+    #[schema(example = "10086")]
+    pub external_id: String,
+    /// 用户在本站获取的绑定凭证
+    #[schema(example = "Zf3kQ9mB2xLr7TpW")]
+    pub proof_token: String,
+}
+
+/// 外部账户对应的本站用户
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkedAccount {
+    /// 外部平台标识
+    #[schema(example = "mscpo_forum")]
+    pub provider: String,
+    /// 外部平台的账户 ID
+    #[schema(example = "10086")]
+    pub external_id: String,
+    /// 本站用户 ID
+    #[schema(example = 42)]
+    pub user_id: i32,
+    /// 本站用户名
+    #[schema(example = "user123")]
+    pub username: String,
+    /// 本站显示名称
+    #[schema(example = "张三-Mike")]
+    pub display_name: String,
+    /// 绑定时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:05:00Z", format = DateTime)]
+    pub linked_at: DateTime<Utc>,
+}
+
+/// 邮件投递事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailEventKind {
+    /// 退信
+    Bounce,
+    /// 收件人投诉（标记为垃圾邮件）
+    Complaint,
+}
+
+impl EmailEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailEventKind::Bounce => "bounce",
+            EmailEventKind::Complaint => "complaint",
+        }
+    }
+}
+
+/// 邮件服务商推送的单条投递事件
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct EmailEvent {
+    /// 收件地址
+    #[schema(example = "user@example.com")]
+    pub email: String,
+    /// 事件类型
+    pub kind: EmailEventKind,
+    /// 是否为永久性退信，临时退信（邮箱已满等）不会停止发送；投诉忽略该字段
+    #[serde(default = "default_permanent")]
+    #[schema(example = true)]
+    pub permanent: bool,
+    /// 服务商提供的原因说明
+    #[schema(example = "550 5.1.1 user unknown")]
+    pub detail: Option<String>,
+}
+
+fn default_permanent() -> bool {
+    true
+}
+
+/// 邮件投递事件回调请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct EmailEventsRequest {
+    /// 事件列表
+    pub events: Vec<EmailEvent>,
+}
+
+/// 邮件投递事件处理结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailEventsResponse {
+    /// 被标记为无法投递的地址数
+    #[schema(example = 1)]
+    pub suppressed: usize,
+    /// 被忽略的事件数（临时退信或地址无效）
+    #[schema(example = 0)]
+    pub ignored: usize,
+}
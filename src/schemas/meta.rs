@@ -0,0 +1,235 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 服务版本信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VersionInfo {
+    /// 服务版本号
+    #[schema(example = "0.1.0")]
+    pub version: String,
+    /// 构建时的 Git 提交号，无法获取时为 `unknown`
+    #[schema(example = "23caa8b1f0c2")]
+    pub git_commit: String,
+    /// 构建时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub build_time: Option<DateTime<Utc>>,
+    /// 编译时启用的特性
+    #[schema(example = json!(["dev-tools"]))]
+    pub features: Vec<String>,
+    /// 当前支持的 API 版本
+    #[schema(example = json!(["v2"]))]
+    pub api_versions: Vec<String>,
+}
+
+/// 组件或整体的运行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentState {
+    /// 正常
+    Operational,
+    /// 性能下降
+    Degraded,
+    /// 不可用
+    Outage,
+    /// 暂无采样数据
+    Unknown,
+}
+
+impl ComponentState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentState::Operational => "operational",
+            ComponentState::Degraded => "degraded",
+            ComponentState::Outage => "outage",
+            ComponentState::Unknown => "unknown",
+        }
+    }
+}
+
+/// 故障严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentSeverity {
+    /// 部分功能受影响
+    Minor,
+    /// 主要功能受影响
+    Major,
+    /// 服务不可用
+    Critical,
+}
+
+impl IncidentSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncidentSeverity::Minor => "minor",
+            IncidentSeverity::Major => "major",
+            IncidentSeverity::Critical => "critical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "critical" => IncidentSeverity::Critical,
+            "major" => IncidentSeverity::Major,
+            _ => IncidentSeverity::Minor,
+        }
+    }
+}
+
+/// 滚动窗口内的请求错误率
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorRateWindow {
+    /// 窗口名称
+    #[schema(example = "5m")]
+    pub window: String,
+    /// 窗口时长（秒）
+    #[schema(example = 300)]
+    pub window_secs: u64,
+    /// 窗口内的请求数
+    #[schema(example = 1200)]
+    pub total_requests: u64,
+    /// 窗口内返回 5xx 的请求数
+    #[schema(example = 3)]
+    pub error_requests: u64,
+    /// 错误率（0~1），无请求时为 0
+    #[schema(example = 0.0025)]
+    pub error_rate: f64,
+}
+
+/// 一次依赖健康采样
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthSample {
+    /// 采样时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub checked_at: DateTime<Utc>,
+    /// 是否可用
+    #[schema(example = true)]
+    pub healthy: bool,
+    /// 检查耗时（毫秒）
+    #[schema(example = 3)]
+    pub latency_ms: u64,
+}
+
+/// 就绪检查中单个依赖的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    /// 可用
+    Up,
+    /// 不可用或检查超时
+    Down,
+    /// 未配置（可选依赖）
+    Disabled,
+}
+
+/// 依赖检查结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DependencyHealth {
+    /// 依赖名称（database / redis / search / storage）
+    #[schema(example = "database")]
+    pub name: String,
+    pub status: DependencyStatus,
+    /// 不可用时是否视为整个实例未就绪
+    #[schema(example = true)]
+    pub critical: bool,
+    /// 检查耗时（毫秒），未配置时为空
+    #[schema(example = 2)]
+    pub latency_ms: Option<u64>,
+    /// 检查失败的原因
+    #[schema(example = json!(null))]
+    pub error: Option<String>,
+}
+
+/// 就绪检查结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthReport {
+    /// 整体状态：关键依赖不可用为 `outage`（HTTP 503），其他依赖不可用为 `degraded`
+    pub status: ComponentState,
+    /// 检查时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub checked_at: DateTime<Utc>,
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+/// 依赖组件状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentStatus {
+    /// 组件名称（database / redis / search）
+    #[schema(example = "database")]
+    pub name: String,
+    /// 当前状态
+    pub status: ComponentState,
+    /// 最近一次检查耗时（毫秒）
+    #[schema(example = 3)]
+    pub latency_ms: Option<u64>,
+    /// 已保存采样中的可用比例（0~1）
+    #[schema(example = 0.999)]
+    pub uptime_ratio: Option<f64>,
+    /// 最近一次检查时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// 最近的采样记录，按时间从新到旧排列
+    pub history: Vec<HealthSample>,
+}
+
+/// 进行中的故障
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatusIncident {
+    /// 故障标识
+    #[schema(example = "search-degraded")]
+    pub key: String,
+    /// 标题
+    #[schema(example = "搜索服务响应缓慢")]
+    pub title: String,
+    /// 详细说明
+    #[schema(example = "正在排查，搜索结果可能延迟更新")]
+    pub message: Option<String>,
+    /// 严重程度
+    pub severity: IncidentSeverity,
+    /// 受影响的组件
+    #[schema(example = json!(["search"]))]
+    pub components: Vec<String>,
+    /// 开始时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub started_at: DateTime<Utc>,
+    /// 最后更新时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 依赖可选配置的功能
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Capability {
+    /// 功能名称（image_uploads / email）
+    #[schema(example = "image_uploads")]
+    pub name: String,
+    /// 是否已配置；未配置时相关接口返回 501
+    #[schema(example = true)]
+    pub enabled: bool,
+}
+
+/// 状态页数据
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatusPageResponse {
+    /// 整体状态
+    pub status: ComponentState,
+    /// 生成时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub generated_at: DateTime<Utc>,
+    /// 请求错误率，按窗口从短到长排列
+    pub error_rates: Vec<ErrorRateWindow>,
+    /// 依赖组件状态
+    pub components: Vec<ComponentStatus>,
+    /// 可选功能的启用情况
+    pub capabilities: Vec<Capability>,
+    /// 进行中的故障
+    pub incidents: Vec<StatusIncident>,
+}
@@ -1,3 +1,14 @@
 pub mod auth;
 pub mod servers;
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod internal;
+pub mod admin;
+pub mod confirm;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(feature = "dev-tools")]
+pub mod dev_tools;
+pub mod meta;
+pub mod pagination;
+pub mod datetime;
+pub mod users;
\ No newline at end of file
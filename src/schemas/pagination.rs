@@ -0,0 +1,102 @@
+use axum::{
+    http::{header::LINK, HeaderValue, Uri},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+use utoipa::ToSchema;
+
+use crate::extract::Json;
+
+/// 生成链接时从原查询中去掉的参数，页码由 `page` 指定，`offset` 为搜索接口的旧分页参数
+const PAGE_PARAMS: [&str; 2] = ["page", "offset"];
+
+/// 分页列表
+///
+/// 所有分页的列表接口统一返回该结构，同时在 `Link` 响应头中给出相邻页的链接。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Paginated<T> {
+    /// 当前页码，从 1 开始
+    #[schema(example = 1)]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20)]
+    pub page_size: u64,
+    /// 符合条件的总数
+    #[schema(example = 100)]
+    pub total: u64,
+    /// 总页数，没有数据时为 0
+    #[schema(example = 5)]
+    pub total_pages: u64,
+    /// 当前页的数据
+    pub items: Vec<T>,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(items: Vec<T>, page: u64, page_size: u64, total: u64) -> Self {
+        Self {
+            page,
+            page_size,
+            total,
+            total_pages: total.div_ceil(page_size.max(1)),
+            items,
+        }
+    }
+
+    /// 附上请求地址，响应时据此生成 `Link` 头
+    pub fn with_links(self, uri: &Uri) -> Page<Self> {
+        Page::new(self.links(uri), self)
+    }
+
+    /// RFC 5988 `Link` 头：first / prev / next / last，链接保留请求中除页码外的查询参数
+    pub fn links(&self, uri: &Uri) -> Option<HeaderValue> {
+        let retained: Vec<(String, String)> =
+            form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes())
+                .filter(|(key, _)| !PAGE_PARAMS.contains(&key.as_ref()))
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect();
+        let link = |page: u64, rel: &str| {
+            let query = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&retained)
+                .append_pair("page", &page.to_string())
+                .finish();
+            format!("<{}?{}>; rel=\"{}\"", uri.path(), query, rel)
+        };
+
+        let last = self.total_pages.max(1);
+        let mut links = vec![link(1, "first")];
+        if self.page > 1 {
+            links.push(link((self.page - 1).min(last), "prev"));
+        }
+        if self.page < self.total_pages {
+            links.push(link(self.page + 1, "next"));
+        }
+        links.push(link(last, "last"));
+        HeaderValue::from_str(&links.join(", ")).ok()
+    }
+}
+
+/// 带 `Link` 头的分页列表响应
+///
+/// 响应体通常为 [`Paginated`]，由 [`Paginated::with_links`] 生成；
+/// 在分页字段之外还有其他字段的响应（如搜索耗时）用 [`Page::new`] 包装。
+pub struct Page<B> {
+    body: B,
+    links: Option<HeaderValue>,
+}
+
+impl<B> Page<B> {
+    pub fn new(links: Option<HeaderValue>, body: B) -> Self {
+        Self { body, links }
+    }
+}
+
+impl<B: Serialize> IntoResponse for Page<B> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.body).into_response();
+        if let Some(links) = self.links {
+            response.headers_mut().insert(LINK, links);
+        }
+        response
+    }
+}
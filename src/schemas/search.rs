@@ -1,7 +1,12 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::schemas::servers::{ApiAuthMode, ApiServerType};
+use crate::schemas::{
+    pagination::Paginated,
+    servers::{ApiAuthMode, ApiServerType, VersionRange},
+};
 
 /// 结构化的搜索过滤器
 #[derive(Debug, Clone, Deserialize, Serialize, Default, ToSchema)]
@@ -25,6 +30,9 @@ pub struct SearchFilters {
     /// 版本过滤
     #[schema(example = "1.20.1,1.19.4")]
     pub version: Option<Vec<String>>,
+    /// 兼容的游戏版本
+    #[schema(example = "1.20.1")]
+    pub compatible_with: Option<String>,
 }
 
 /// 搜索参数
@@ -33,11 +41,17 @@ pub struct SearchParams {
     /// 搜索关键词
     #[schema(example = "生存服务器")]
     pub query: Option<String>,
-    /// 返回结果数量限制
-    #[schema(example = 10)]
+    /// 页码，从 1 开始
+    #[schema(example = 1, default = 1)]
+    pub page: Option<u32>,
+    /// 每页数量（1~100）
+    #[schema(example = 10, default = 10)]
+    pub page_size: Option<u32>,
+    /// 已弃用，请使用 `page_size`；未传 `page_size` 时作为每页数量
+    #[schema(example = 10, deprecated)]
     pub limit: Option<u32>,
-    /// 偏移量，用于分页
-    #[schema(example = 0)]
+    /// 已弃用，请使用 `page`；未传 `page` 时按 `offset / page_size + 1` 换算页码
+    #[schema(example = 0, deprecated)]
     pub offset: Option<u32>,
     /// 服务器类型快捷过滤（与 SearchFilters 区分，单值）
     #[serde(rename = "type")]
@@ -52,6 +66,9 @@ pub struct SearchParams {
     /// 是否会员服务器快捷过滤
     #[schema(example = false)]
     pub is_member: Option<bool>,
+    /// 只返回兼容该游戏版本的服务器，规则与服务器列表的 `compatible_with` 相同
+    #[schema(example = "1.20.1")]
+    pub compatible_with: Option<String>,
     /// 排序字段
     #[schema(example = "auth_mode")]
     pub sort: Option<String>,
@@ -75,6 +92,9 @@ pub struct ServerResult {
     /// 服务器版本，服务器运行的版本
     #[schema(example = "1.20.1")]
     pub version: String,
+    /// 支持的游戏版本范围，未设置时只支持 `version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<VersionRange>,
     /// 服务器描述，对服务器的简短描述
     #[schema(example = "一个有趣的生存服务器")]
     pub desc: String,
@@ -93,18 +113,124 @@ pub struct ServerResult {
     /// 服务器标签，与服务器相关的标签
     #[schema(example = json!(["生存", "PVP"]))]
     pub tags: Option<Vec<String>>,
+    /// 服务器短链接
+    #[serde(default)]
+    #[schema(example = "my-server-k3x9qa")]
+    pub slug: Option<String>,
+    /// 服务器封面，服务器的封面图片链接，没有封面时为 None
+    #[serde(default)]
+    #[schema(example = "https://cdn.example.com/static/covers/server1.jpg")]
+    pub cover_url: Option<String>,
+    /// 在线玩家数，取自最近一次状态数据，随索引同步更新，没有状态数据时为 None
+    #[serde(default)]
+    #[schema(example = 42)]
+    pub online_players: Option<i64>,
+    /// 最大玩家数，取自最近一次状态数据
+    #[serde(default)]
+    #[schema(example = 100)]
+    pub max_players: Option<i64>,
 }
 
-/// 搜索响应
+/// 搜索响应，`total` 为搜索引擎估算的命中数
 #[derive(Serialize, Debug, Deserialize, ToSchema)]
 pub struct SearchResponse {
-    pub hits: Vec<ServerResult>,
-    #[schema(example = 1)]
-    pub total: usize,
-    #[schema(example = 10)]
-    pub limit: usize,
-    #[schema(example = 0)]
-    pub offset: usize,
+    #[serde(flatten)]
+    pub page: Paginated<ServerResult>,
     #[schema(example = 12)]
     pub processing_time_ms: u128,
 }
+
+/// 搜索分面统计，各字段为取值到服务器数的映射
+#[derive(Serialize, Deserialize, Debug, Default, ToSchema)]
+pub struct SearchFacets {
+    /// 各标签的服务器数
+    #[schema(example = json!({"生存": 42, "PVP": 17}))]
+    pub tags: BTreeMap<String, usize>,
+    /// 各服务器类型的服务器数
+    #[serde(rename = "type")]
+    #[schema(example = json!({"JAVA": 50, "BEDROCK": 9}))]
+    pub server_type: BTreeMap<String, usize>,
+    /// 各认证模式的服务器数
+    #[schema(example = json!({"OFFICIAL": 38, "OFFLINE": 21}))]
+    pub auth_mode: BTreeMap<String, usize>,
+    /// 各版本的服务器数
+    #[schema(example = json!({"1.20.1": 25, "1.19.4": 8}))]
+    pub version: BTreeMap<String, usize>,
+}
+
+/// 搜索分面统计响应
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SearchFacetsResponse {
+    /// 符合关键词与过滤条件的服务器总数
+    #[schema(example = 59)]
+    pub total: usize,
+    pub facets: SearchFacets,
+    #[schema(example = 3)]
+    pub processing_time_ms: u128,
+}
+
+/// 搜索建议参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuggestQuery {
+    /// 正在输入的关键词，最后一个词按前缀匹配服务器名称与短链接
+    #[param(example = "生存")]
+    pub q: String,
+    /// 返回数量，默认 5，最大 10
+    #[param(example = 5)]
+    pub limit: Option<u32>,
+}
+
+/// 搜索建议
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchSuggestion {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 服务器名称
+    #[schema(example = "我的世界服务器")]
+    pub name: String,
+    /// 服务器短链接
+    #[schema(example = "my-server-k3x9qa")]
+    pub slug: Option<String>,
+    /// 图标，使用服务器封面图片链接，没有封面时为 None
+    #[schema(example = "https://cdn.example.com/static/covers/server1.jpg")]
+    pub icon: Option<String>,
+}
+
+/// 搜索建议响应
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SuggestResponse {
+    pub suggestions: Vec<SearchSuggestion>,
+}
+
+/// 玩家搜索参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PlayerSearchQuery {
+    /// 完整的玩家名称，不区分大小写
+    #[param(example = "Steve")]
+    pub name: String,
+}
+
+/// 玩家当前所在的服务器
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerServer {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 服务器名称
+    #[schema(example = "我的世界服务器")]
+    pub name: String,
+    /// 服务器短链接
+    #[schema(example = "my-server-k3x9qa")]
+    pub slug: Option<String>,
+}
+
+/// 玩家搜索结果
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerSearchResponse {
+    /// 查询的玩家名称
+    #[schema(example = "Steve")]
+    pub name: String,
+    /// 玩家当前所在的服务器，已退出玩家搜索的服务器不会出现
+    pub servers: Vec<PlayerServer>,
+}
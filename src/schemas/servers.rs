@@ -1,8 +1,16 @@
 use axum_typed_multipart::{FieldData, TryFromMultipart};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str::FromStr};
-use utoipa::ToSchema;
-use validator::Validate;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, Ipv6Addr},
+    str::FromStr,
+};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
 
 /// API 层枚举，数据库中存储的是字符串
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -25,6 +33,16 @@ impl FromStr for ApiServerType {
     }
 }
 
+impl ApiServerType {
+    /// 地址中未写端口时客户端连接的端口
+    pub const fn default_port(&self) -> u16 {
+        match self {
+            ApiServerType::Java => 25565,
+            ApiServerType::Bedrock => 19132,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum ApiAuthMode {
     #[serde(rename = "OFFICIAL")]
@@ -48,21 +66,6 @@ impl FromStr for ApiAuthMode {
     }
 }
 
-/// 服务器列表响应
-///
-/// 包含服务器列表和相关统计信息的响应结构体
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct ServerListResponse {
-    /// 服务器列表，显示所有的服务器列表
-    pub data: Vec<ServerDetail>,
-    /// 服务器总数，过滤条件下的所有服务器总数
-    #[schema(example = 100)]
-    pub total: i64,
-    /// 总页数，根据每页数量计算的总页数
-    #[schema(example = 10)]
-    pub total_pages: i64,
-}
-
 /// 服务器详细信息
 ///
 /// 包含服务器完整信息的结构体，用于API响应
@@ -77,15 +80,27 @@ pub struct ServerDetail {
     /// 服务器 IP，服务器的 IP 地址，若隐藏则为 None
     #[schema(example = "mc.example.com:25565")]
     pub ip: Option<String>,
+    /// 地址的 IP 协议族，`ip` 为 IP 字面量时返回，域名或隐藏 IP 时为空；
+    /// 客户端可据此提示没有 IPv6 网络的用户
+    #[serde(default)]
+    #[schema(example = "ipv4")]
+    pub ip_family: Option<IpFamily>,
     /// 服务器类型，服务器所属的类型
     #[schema(example = "JAVA")]
     pub r#type: ApiServerType,
     /// 服务器版本，服务器运行的版本
     #[schema(example = "1.20.1")]
     pub version: String,
-    /// 服务器描述，对服务器的简短描述
+    /// 支持的游戏版本范围，未设置时只支持 `version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<VersionRange>,
+    /// 服务器描述，Markdown 原文
     #[schema(example = "一个有趣的生存服务器")]
     pub desc: String,
+    /// 渲染后的描述 HTML，已转义原文中的 HTML，仅在详情接口传入 `render=html` 时返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "<p>一个有趣的生存服务器</p>\n")]
+    pub desc_html: Option<String>,
     /// 服务器链接，指向服务器详情的链接
     #[schema(example = "https://example.com")]
     pub link: String,
@@ -95,9 +110,12 @@ pub struct ServerDetail {
     /// 认证模式，服务器使用的认证模式
     #[schema(example = "OFFICIAL")]
     pub auth_mode: ApiAuthMode,
-    /// 是否隐藏，服务器是否处于隐藏状态
+    /// 是否隐藏 IP，可见性不是 `public` 时为 true
     #[schema(example = false)]
     pub is_hide: bool,
+    /// 可见性，`listed_without_ip` 与 `hidden` 的服务器不返回 `ip`
+    #[serde(default = "default_visibility")]
+    pub visibility: ServerVisibility,
     /// 服务器标签，与服务器相关的标签
     #[schema(example = json!(["生存", "PVP"]))]
     pub tags: Option<Vec<String>>,
@@ -110,6 +128,182 @@ pub struct ServerDetail {
     /// 服务器封面，服务器的封面图片链接
     #[schema(example = "https://cdn.example.com/static/covers/server1.jpg")]
     pub cover_url: Option<String>,
+    /// 封面的各尺寸地址，列表卡片等小图场景应使用 `thumb`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_urls: Option<ImageUrls>,
+    /// 服务器图标，服主上传的方形图片地址，未上传时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "https://cdn.example.com/uploads/icon.webp")]
+    pub icon_url: Option<String>,
+    /// 服务器短链接，可用于 `/v2/servers/slug/{slug}`
+    #[schema(example = "my-server-k3x9qa")]
+    pub slug: Option<String>,
+    /// 服务器管理员，仅在启用新版详情（`server_detail_v2`）时返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managers: Option<ServerManagersResponse>,
+    /// 服务器描述的其他语言版本，列表接口不返回
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translations: Vec<DescriptionTranslation>,
+    /// 服主添加的自定义字段，按显示顺序排列，列表接口不返回
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_fields: Vec<CustomField>,
+    /// 管理信息，仅在 `full_info=true` 且调用方是该服务器成员时返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private: Option<ServerPrivateDetail>,
+    /// 当前用户是否已收藏，仅详情接口在登录时返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = false)]
+    pub is_favorited: Option<bool>,
+}
+
+/// 服务器概要
+///
+/// 详情页首屏需要的全部信息，一次请求返回，字段与详情、相册接口一致
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ServerSummary {
+    /// 服务器 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 服务器名称
+    #[schema(example = "我的世界服务器")]
+    pub name: String,
+    /// 一句话简介，取描述的第一段纯文本，过长时截断
+    #[schema(example = "一个有趣的生存服务器")]
+    pub intro: String,
+    /// 服务器图标，优先使用服主上传的方形图标，未上传时取最近一次状态中的图标（游戏内列表 MOTD 旁显示的图片），
+    /// 都没有时为 None
+    #[schema(example = "https://cdn.example.com/uploads/icon.webp")]
+    pub icon: Option<String>,
+    /// 登记信息
+    pub registration: ServerRegistration,
+    /// 服务器描述，Markdown 原文
+    #[schema(example = "一个有趣的生存服务器")]
+    pub desc: String,
+    /// 相册图片，按展示顺序排列
+    pub gallery: Vec<GalleryImage>,
+    /// 最近一次状态，尚未采集到时为 None
+    pub ping: Option<ServerPing>,
+}
+
+/// 服务器在本站的登记信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerRegistration {
+    /// 是否为成员服务器
+    #[schema(example = true)]
+    pub is_member: bool,
+    /// 服务器标签
+    #[schema(example = json!(["生存", "PVP"]))]
+    pub tags: Vec<String>,
+    /// 服务器版本
+    #[schema(example = "1.20.1")]
+    pub version: String,
+    /// 支持的游戏版本范围，未设置时只支持 `version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<VersionRange>,
+}
+
+/// 服务器最近一次状态
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerPing {
+    /// MOTD
+    pub motd: Motd,
+    /// 延迟（毫秒）
+    #[schema(example = 50.5)]
+    pub delay: f64,
+    /// 在线玩家数
+    #[schema(example = 10)]
+    pub online_players: Option<i64>,
+    /// 最大玩家数
+    #[schema(example = 100)]
+    pub max_players: Option<i64>,
+    /// MOTD 图片地址，未配置 MOTD 字体时为空
+    #[schema(example = "/v2/servers/1/motd.png")]
+    pub motd_image_url: Option<String>,
+}
+
+/// 服务器管理信息
+///
+/// 只对服务器成员（owner / admin / member 等角色）返回的字段。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerPrivateDetail {
+    /// 服务器真实地址，服务器隐藏时公开详情中不返回
+    #[schema(example = "mc.example.com:25565")]
+    pub ip: String,
+    /// 待处理的工单（申请、举报）数
+    #[schema(example = 2)]
+    pub pending_tickets: u64,
+    /// 是否已生成数据推送密钥，密钥本身不返回
+    #[schema(example = true)]
+    pub push_secret_configured: bool,
+    /// 短链接是否还能修改（只允许修改一次）
+    #[schema(example = false)]
+    pub slug_editable: bool,
+    /// 是否已退出玩家名称搜索
+    #[schema(example = false)]
+    pub player_search_opt_out: bool,
+    /// 因长期离线被自动下架的时间，恢复推送状态后自动重新上架
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = json!(null), format = DateTime)]
+    pub delisted_at: Option<DateTime<Utc>>,
+    /// 停用时间，为空表示正常
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = json!(null), format = DateTime)]
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// 发布状态，只有 `published` 的服务器出现在列表与搜索中
+    #[schema(example = "published")]
+    pub status: ServerStatus,
+    /// 最近一次审核未通过的原因
+    #[schema(example = json!(null))]
+    pub review_note: Option<String>,
+}
+
+fn default_visibility() -> ServerVisibility {
+    ServerVisibility::Public
+}
+
+/// 服务器描述的语言版本
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct DescriptionTranslation {
+    /// 语言代码
+    #[schema(example = "en")]
+    pub locale: String,
+    /// 该语言的描述
+    #[schema(example = "A fun survival server")]
+    pub desc: String,
+    /// 是否为机器翻译，前端据此标注
+    #[schema(example = true)]
+    pub machine_translated: bool,
+}
+
+/// 服务器自定义字段
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CustomField {
+    /// 字段名
+    #[schema(example = "QQ群")]
+    #[validate(length(min = 1, max = 32, message = "字段名长度必须在1-32个字符之间"))]
+    pub key: String,
+    /// 字段值
+    #[schema(example = "123456789")]
+    #[validate(length(min = 1, max = 256, message = "字段值长度必须在1-256个字符之间"))]
+    pub value: String,
+    /// 是否允许通过搜索匹配该字段的值
+    #[serde(default)]
+    #[schema(example = false)]
+    pub searchable: bool,
+}
+
+/// 替换服务器自定义字段请求
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateCustomFieldsRequest {
+    /// 全部自定义字段，按显示顺序排列；传空数组表示清空
+    #[validate(length(max = 10, message = "自定义字段数量不能超过 10 个"), nested)]
+    pub fields: Vec<CustomField>,
+}
+
+/// 服务器自定义字段列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomFieldListResponse {
+    pub data: Vec<CustomField>,
 }
 
 /// 服务器状态信息
@@ -134,6 +328,18 @@ pub struct ServerStats {
     /// 服务器图标，服务器的图标，若无则为 None
     #[schema(example = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAA...")]
     pub icon: Option<String>,
+    /// 在线玩家名称样本，仅在推送时用于玩家搜索索引，不写入历史状态也不对外返回
+    #[serde(default, skip_serializing)]
+    #[schema(example = json!(["Steve", "Alex"]))]
+    pub sample: Option<Vec<String>>,
+}
+
+/// 上传服务器图标请求
+#[derive(Debug, TryFromMultipart, ToSchema)]
+pub struct UploadServerIconRequest {
+    /// 图标文件（不超过 1 MB，支持 JPEG / PNG / WebP，正方形且边长在 64 到 512 像素之间）
+    #[schema(value_type = String, format = Binary)]
+    pub icon: FieldData<axum::body::Bytes>,
 }
 
 /// 服务器MOTD信息
@@ -155,6 +361,62 @@ pub struct Motd {
     pub ansi: String,
 }
 
+/// 创建服务器请求
+///
+/// 用于登记新服务器的请求结构体，创建者成为服主
+#[derive(Debug, TryFromMultipart, Validate, ToSchema)]
+pub struct CreateServerRequest {
+    /// 服务器名称
+    #[schema(example = "我的世界服务器")]
+    #[validate(length(min = 1, max = 50, message = "服务器名称长度必须在1-50个字符之间"))]
+    pub name: String,
+
+    /// 服务器地址，支持域名、IPv4 与 IPv6，带端口的 IPv6 需加方括号（如 `[2001:db8::1]:25565`）
+    #[schema(example = "mc.example.com:25565")]
+    #[validate(custom(function = "validate_server_address"))]
+    pub ip: String,
+
+    /// 服务器类型（JAVA / BEDROCK）
+    #[schema(example = "JAVA")]
+    pub r#type: String,
+
+    /// 正版验证模式（OFFICIAL / OFFLINE / YGGDRASIL）
+    #[schema(example = "OFFICIAL")]
+    pub auth_mode: String,
+
+    /// 服务器描述，Markdown 格式；保存前会移除脚本、内嵌框架与事件属性，最多保留 10 张图片
+    #[schema(
+        example = "这是一个非常有趣的生存服务器，我们提供了丰富的游戏内容和友好的社区环境。玩家可以在这里体验到最纯粹的Minecraft生存乐趣。"
+    )]
+    #[validate(length(min = 100, message = "简介必须大于 100 字"))]
+    pub desc: String,
+
+    /// 服务器标签
+    #[schema(example = json!(["生存", "PVP"]))]
+    #[validate(length(max = 7, message = "tags 数量不能超过 7 个"))]
+    pub tags: Vec<String>,
+
+    /// 服务器版本
+    #[schema(example = "1.20.1")]
+    #[validate(length(min = 1, max = 20, message = "服务器版本长度必须在1-20个字符之间"))]
+    pub version: String,
+
+    /// 支持的游戏版本范围，JSON 格式，如 `{"min": "1.8", "max": "1.21"}` 或
+    /// `{"versions": ["1.12.2", "1.20.1"]}`；不传则只记录 `version`
+    #[schema(example = r#"{"min": "1.8", "max": "1.21"}"#)]
+    #[validate(custom(function = "validate_version_range"))]
+    pub version_range: Option<String>,
+
+    /// 服务器链接
+    #[schema(example = "https://example.com")]
+    #[validate(url(message = "无效的链接格式"))]
+    pub link: String,
+
+    /// 服务器封面文件
+    #[schema(value_type = String, format = Binary)]
+    pub cover: Option<FieldData<axum::body::Bytes>>,
+}
+
 /// 更新服务器请求
 ///
 /// 用于更新服务器信息的请求结构体
@@ -165,12 +427,12 @@ pub struct UpdateServerRequest {
     #[validate(length(min = 1, max = 50, message = "服务器名称长度必须在1-50个字符之间"))]
     pub name: String,
 
-    /// 服务器 IP 地址
+    /// 服务器地址，支持域名、IPv4 与 IPv6，带端口的 IPv6 需加方括号（如 `[2001:db8::1]:25565`）
     #[schema(example = "mc.example.com:25565")]
-    #[validate(ip(message = "无效的 IP 地址格式"))]
+    #[validate(custom(function = "validate_server_address"))]
     pub ip: String,
 
-    /// 服务器描述
+    /// 服务器描述，Markdown 格式；保存前会移除脚本、内嵌框架与事件属性，最多保留 10 张图片
     #[schema(
         example = "这是一个非常有趣的生存服务器，我们提供了丰富的游戏内容和友好的社区环境。玩家可以在这里体验到最纯粹的Minecraft生存乐趣。"
     )]
@@ -187,6 +449,12 @@ pub struct UpdateServerRequest {
     #[validate(length(min = 1, max = 20, message = "服务器版本长度必须在1-20个字符之间"))]
     pub version: String,
 
+    /// 支持的游戏版本范围，JSON 格式，如 `{"min": "1.8", "max": "1.21"}` 或
+    /// `{"versions": ["1.12.2", "1.20.1"]}`；不传则保持不变，传空字符串清除
+    #[schema(example = r#"{"min": "1.8", "max": "1.21"}"#)]
+    #[validate(custom(function = "validate_version_range"))]
+    pub version_range: Option<String>,
+
     /// 服务器链接
     #[schema(example = "https://example.com")]
     #[validate(url(message = "无效的链接格式"))]
@@ -195,9 +463,434 @@ pub struct UpdateServerRequest {
     /// 服务器封面文件
     #[schema(value_type = String, format = Binary)]
     pub cover: Option<FieldData<axum::body::Bytes>>,
+
+    /// 服务器短链接，只能修改一次
+    #[schema(example = "my-server")]
+    #[validate(
+        length(min = 3, max = 64, message = "短链接长度必须在3-64个字符之间"),
+        regex(path = "*SLUG_REGEX", message = "短链接只能包含小写字母、数字和连字符")
+    )]
+    pub slug: Option<String>,
+
+    /// 可见性（public / listed_without_ip / hidden），不传则保持不变
+    #[schema(example = "public")]
+    pub visibility: Option<String>,
+
+    /// 是否退出玩家名称搜索，不传则保持不变
+    #[schema(example = false)]
+    pub player_search_opt_out: Option<bool>,
+}
+
+/// 服务器可见性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerVisibility {
+    /// 出现在列表、搜索与站点地图中，公开 IP
+    Public,
+    /// 出现在列表、搜索与站点地图中，不公开 IP
+    ListedWithoutIp,
+    /// 不出现在列表、搜索与站点地图中，详情仅服务器成员可见
+    Hidden,
+}
+
+impl ServerVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerVisibility::Public => "public",
+            ServerVisibility::ListedWithoutIp => "listed_without_ip",
+            ServerVisibility::Hidden => "hidden",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public" => Some(ServerVisibility::Public),
+            "listed_without_ip" => Some(ServerVisibility::ListedWithoutIp),
+            "hidden" => Some(ServerVisibility::Hidden),
+            _ => None,
+        }
+    }
+
+    /// 服务器当前的可见性；未单独设置时沿用旧的 `is_hide`（隐藏 IP）
+    pub fn of(server: &crate::entities::server::Model) -> Self {
+        match Self::parse(&server.visibility) {
+            Some(ServerVisibility::Public) | None if server.is_hide => {
+                ServerVisibility::ListedWithoutIp
+            }
+            Some(visibility) => visibility,
+            None => ServerVisibility::Public,
+        }
+    }
+
+    /// 是否出现在列表、搜索与站点地图中
+    pub fn is_listed(&self) -> bool {
+        *self != ServerVisibility::Hidden
+    }
+
+    /// 对外公开的 IP
+    pub fn public_ip(&self, ip: String) -> Option<String> {
+        (*self == ServerVisibility::Public).then_some(ip)
+    }
+}
+
+/// 服务器发布状态
+///
+/// 新登记的服务器为草稿，服主提交审核、站点管理员通过后才出现在列表与搜索中。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerStatus {
+    /// 草稿，尚未提交审核
+    Draft,
+    /// 已提交，等待站点管理员审核
+    PendingReview,
+    /// 审核通过，出现在列表与搜索中
+    Published,
+    /// 审核未通过，服主修改后可以重新提交
+    Rejected,
+    /// 服主已归档，重新提交审核后才会再次发布
+    Archived,
+}
+
+impl ServerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerStatus::Draft => "draft",
+            ServerStatus::PendingReview => "pending_review",
+            ServerStatus::Published => "published",
+            ServerStatus::Rejected => "rejected",
+            ServerStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(ServerStatus::Draft),
+            "pending_review" => Some(ServerStatus::PendingReview),
+            "published" => Some(ServerStatus::Published),
+            "rejected" => Some(ServerStatus::Rejected),
+            "archived" => Some(ServerStatus::Archived),
+            _ => None,
+        }
+    }
+
+    /// 服务器当前的发布状态；引入审核流程之前登记的服务器视为已发布
+    pub fn of(server: &crate::entities::server::Model) -> Self {
+        Self::parse(&server.status).unwrap_or(ServerStatus::Published)
+    }
+
+    /// 是否出现在列表、搜索与站点地图中
+    pub fn is_listed(&self) -> bool {
+        *self == ServerStatus::Published
+    }
+
+    /// 服主能否从该状态提交审核
+    pub fn can_submit(&self) -> bool {
+        matches!(
+            self,
+            ServerStatus::Draft | ServerStatus::Rejected | ServerStatus::Archived
+        )
+    }
+}
+
+/// 短链接格式：小写字母、数字，以单个连字符分隔
+pub static SLUG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z0-9]+(?:-[a-z0-9]+)*$").unwrap());
+
+/// 服务器描述的返回格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DescRender {
+    /// 只返回 Markdown 原文
+    #[default]
+    Markdown,
+    /// 额外返回渲染后的 HTML（`desc_html`）
+    Html,
+}
+
+/// IP 协议族
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IpFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl IpFamily {
+    /// 地址为 IP 字面量时返回协议族，域名或无法解析的地址返回 None
+    pub fn of(address: &str) -> Option<Self> {
+        match ServerAddress::parse(address)?.host {
+            AddressHost::Ip(IpAddr::V4(_)) => Some(IpFamily::Ipv4),
+            AddressHost::Ip(IpAddr::V6(_)) => Some(IpFamily::Ipv6),
+            AddressHost::Domain(_) => None,
+        }
+    }
+}
+
+/// 服务器地址的主机部分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressHost {
+    Ip(IpAddr),
+    Domain(String),
+}
+
+/// 解析后的服务器地址
+///
+/// 支持 `host`、`host:port`、`[ipv6]`、`[ipv6]:port` 以及不带端口的裸 IPv6；
+/// 裸 IPv6 后无法区分端口，带端口时必须使用方括号。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerAddress {
+    pub host: AddressHost,
+    pub port: Option<u16>,
+}
+
+impl ServerAddress {
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some(rest) = input.strip_prefix('[') {
+            let (host, rest) = rest.split_once(']')?;
+            let ip = host.parse::<Ipv6Addr>().ok()?;
+            let port = match rest {
+                "" => None,
+                _ => Some(Self::parse_port(rest.strip_prefix(':')?)?),
+            };
+            return Some(Self {
+                host: AddressHost::Ip(IpAddr::V6(ip)),
+                port,
+            });
+        }
+        if let Ok(ip) = input.parse::<Ipv6Addr>() {
+            return Some(Self {
+                host: AddressHost::Ip(IpAddr::V6(ip)),
+                port: None,
+            });
+        }
+
+        let (host, port) = match input.split_once(':') {
+            Some((host, port)) => (host, Some(Self::parse_port(port)?)),
+            None => (input, None),
+        };
+        let host = match host.parse::<IpAddr>() {
+            Ok(ip) => AddressHost::Ip(ip),
+            Err(_) if Self::is_domain(host) => AddressHost::Domain(host.to_ascii_lowercase()),
+            Err(_) => return None,
+        };
+        Some(Self { host, port })
+    }
+
+    /// 地址中的端口，未写端口时按服务器类型取默认端口
+    pub fn port_or_default(&self, server_type: &ApiServerType) -> u16 {
+        self.port.unwrap_or(server_type.default_port())
+    }
+
+    fn parse_port(port: &str) -> Option<u16> {
+        port.parse::<u16>().ok().filter(|port| *port != 0)
+    }
+
+    fn is_domain(host: &str) -> bool {
+        !host.is_empty()
+            && host.len() <= 253
+            && host.split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.host, self.port) {
+            (AddressHost::Ip(IpAddr::V6(ip)), Some(port)) => write!(f, "[{ip}]:{port}"),
+            (AddressHost::Ip(ip), Some(port)) => write!(f, "{ip}:{port}"),
+            (AddressHost::Ip(ip), None) => write!(f, "{ip}"),
+            (AddressHost::Domain(domain), Some(port)) => write!(f, "{domain}:{port}"),
+            (AddressHost::Domain(domain), None) => write!(f, "{domain}"),
+        }
+    }
+}
+
+fn validate_server_address(address: &str) -> Result<(), ValidationError> {
+    match ServerAddress::parse(address) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("invalid_address")
+            .with_message("无效的服务器地址格式，带端口的 IPv6 地址需加方括号".into())),
+    }
+}
+
+/// 游戏版本号，`主版本.次版本` 或 `主版本.次版本.修订号`，如 `1.21`、`1.20.1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: Option<u16>,
+}
+
+impl GameVersion {
+    /// 每一段的上限，版本编码按千进制拼接
+    const SEGMENT_LIMIT: u16 = 1000;
+    /// 版本编码的最大值，即 `999.999.999`
+    pub const MAX_CODE: i64 = 999_999_999;
+
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut segments = input.trim().split('.').map(|segment| {
+            let valid = !segment.is_empty()
+                && segment.len() <= 3
+                && segment.chars().all(|c| c.is_ascii_digit());
+            valid
+                .then(|| segment.parse::<u16>().ok())
+                .flatten()
+                .filter(|value| *value < Self::SEGMENT_LIMIT)
+        });
+        let major = segments.next()??;
+        let minor = segments.next()??;
+        let patch = match segments.next() {
+            Some(patch) => Some(patch?),
+            None => None,
+        };
+        if segments.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// 用于比较与数据库筛选的版本编码；作为上限时省略的修订号按最大值计算，
+    /// 即上限 `1.20` 包含所有 `1.20.x`
+    pub fn code(&self, upper: bool) -> i64 {
+        let limit = i64::from(Self::SEGMENT_LIMIT);
+        let patch = match self.patch {
+            Some(patch) => i64::from(patch),
+            None if upper => limit - 1,
+            None => 0,
+        };
+        (i64::from(self.major) * limit + i64::from(self.minor)) * limit + patch
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.patch {
+            Some(patch) => write!(f, "{}.{}.{}", self.major, self.minor, patch),
+            None => write!(f, "{}.{}", self.major, self.minor),
+        }
+    }
+}
+
+/// 服务器支持的游戏版本范围
+///
+/// 用 `min` / `max` 表示连续区间（至少给出一端，省略的一端不设限），或用 `versions`
+/// 逐个列出支持的版本，两种写法不能混用。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VersionRange {
+    /// 最低支持版本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "1.8")]
+    pub min: Option<String>,
+    /// 最高支持版本，省略修订号时包含该次版本下的所有修订版
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "1.21")]
+    pub max: Option<String>,
+    /// 支持的版本列表
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!([]))]
+    pub versions: Vec<String>,
+}
+
+impl VersionRange {
+    /// 列表形式最多列出的版本数
+    pub const MAX_VERSIONS: usize = 20;
+
+    /// 解析 JSON 形式的版本范围并规范化：版本号去除空白，列表去重并按版本排序
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let range: Self =
+            serde_json::from_str(input).map_err(|e| format!("版本范围格式无效: {e}"))?;
+        range.normalized()
+    }
+
+    /// 校验并规范化
+    pub fn normalized(self) -> Result<Self, String> {
+        let parse = |version: &str| {
+            GameVersion::parse(version).ok_or_else(|| format!("无效的游戏版本号: {version:?}"))
+        };
+
+        if !self.versions.is_empty() {
+            if self.min.is_some() || self.max.is_some() {
+                return Err("版本范围不能同时使用 min/max 与 versions".to_string());
+            }
+            if self.versions.len() > Self::MAX_VERSIONS {
+                return Err(format!("versions 最多列出 {} 个版本", Self::MAX_VERSIONS));
+            }
+            let mut versions = self
+                .versions
+                .iter()
+                .map(|version| parse(version))
+                .collect::<Result<Vec<_>, _>>()?;
+            versions.sort_by_key(|version| version.code(false));
+            versions.dedup();
+            return Ok(Self {
+                min: None,
+                max: None,
+                versions: versions.iter().map(ToString::to_string).collect(),
+            });
+        }
+
+        let min = self.min.as_deref().map(parse).transpose()?;
+        let max = self.max.as_deref().map(parse).transpose()?;
+        match (min, max) {
+            (None, None) => Err("版本范围需要给出 min、max 或 versions".to_string()),
+            (Some(min), Some(max)) if min.code(false) > max.code(true) => {
+                Err(format!("最低版本 {min} 不能高于最高版本 {max}"))
+            }
+            _ => Ok(Self {
+                min: min.map(|version| version.to_string()),
+                max: max.map(|version| version.to_string()),
+                versions: Vec::new(),
+            }),
+        }
+    }
+
+    /// 区间形式的下限与上限编码，不设限的一端取编码的最小、最大值；列表形式返回 `None`
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        if !self.versions.is_empty() {
+            return None;
+        }
+        let code = |version: &Option<String>, upper: bool| {
+            version
+                .as_deref()
+                .and_then(GameVersion::parse)
+                .map(|version| version.code(upper))
+        };
+        Some((
+            code(&self.min, false).unwrap_or(0),
+            code(&self.max, true).unwrap_or(GameVersion::MAX_CODE),
+        ))
+    }
+
+    /// 是否兼容指定版本
+    pub fn contains(&self, version: &GameVersion) -> bool {
+        match self.bounds() {
+            Some((min, max)) => (min..=max).contains(&version.code(false)),
+            None => self.versions.contains(&version.to_string()),
+        }
+    }
 }
+
+fn validate_version_range(input: &str) -> Result<(), ValidationError> {
+    if input.trim().is_empty() {
+        return Ok(());
+    }
+    VersionRange::parse(input).map(|_| ()).map_err(|message| {
+        ValidationError::new("invalid_version_range").with_message(message.into())
+    })
+}
+
 /// 服务器管理员角色
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum ServerManagerRole {
     /// 服主
     #[serde(rename = "owner")]
@@ -207,6 +900,47 @@ pub enum ServerManagerRole {
     Admin,
 }
 
+impl ServerManagerRole {
+    /// `user_server.role` 中保存的值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ServerManagerRole::Owner => "owner",
+            ServerManagerRole::Admin => "admin",
+        }
+    }
+
+    /// 解析 `user_server.role` 中保存的值
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "owner" => Some(ServerManagerRole::Owner),
+            "admin" => Some(ServerManagerRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+fn default_manager_role() -> ServerManagerRole {
+    ServerManagerRole::Admin
+}
+
+/// 添加服务器管理员请求
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddManagerRequest {
+    /// 要添加的用户的用户名
+    #[schema(example = "user123")]
+    pub username: String,
+    /// 角色，默认为管理员
+    #[serde(default = "default_manager_role")]
+    pub role: ServerManagerRole,
+}
+
+/// 修改服务器管理员角色请求
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateManagerRequest {
+    /// 新角色
+    pub role: ServerManagerRole,
+}
+
 /// 管理员信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ManagerInfo {
@@ -216,8 +950,8 @@ pub struct ManagerInfo {
     pub display_name: String,
     /// 是否活跃
     pub is_active: bool,
-    /// 头像URL
-    pub avatar_url: String,
+    /// 64px 头像地址，未设置头像时为空
+    pub avatar_url: Option<String>,
 }
 
 /// 服务器管理员列表响应
@@ -247,6 +981,30 @@ pub struct GalleryImage {
     /// 图片URL地址
     #[schema(example = "https://cdn.example.com/gallery1.png")]
     pub image_url: String,
+
+    /// 图片各尺寸地址
+    pub image_urls: ImageUrls,
+
+    /// 展示顺序，越小越靠前
+    #[schema(example = 0)]
+    pub sort_order: i32,
+}
+
+/// 图片的各尺寸地址
+///
+/// 上传时生成缩略图与中图，原图为 WebP 格式的完整图片；
+/// 没有生成缩略图的旧图片 `thumb` 与 `medium` 均为原图地址。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImageUrls {
+    /// 缩略图，宽度不超过 320 像素
+    #[schema(example = "https://cdn.example.com/uploads/gallery1.thumb.webp")]
+    pub thumb: String,
+    /// 中图，宽度不超过 960 像素
+    #[schema(example = "https://cdn.example.com/uploads/gallery1.medium.webp")]
+    pub medium: String,
+    /// 原图
+    #[schema(example = "https://cdn.example.com/uploads/gallery1.webp")]
+    pub original: String,
 }
 
 /// 服务器相册响应
@@ -298,6 +1056,28 @@ pub struct GalleryImageSchema {
     pub image: FieldData<axum::body::Bytes>,
 }
 
+/// 编辑画册图片请求，未传的字段保持不变
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateGalleryImageRequest {
+    /// 图片标题
+    #[schema(example = "主城建筑")]
+    #[validate(length(min = 1, max = 100, message = "标题长度必须在1-100个字符之间"))]
+    pub title: Option<String>,
+
+    /// 图片描述
+    #[schema(example = "主城夜景")]
+    #[validate(length(min = 1, max = 500, message = "描述长度必须在1-500个字符之间"))]
+    pub description: Option<String>,
+}
+
+/// 调整画册图片顺序请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ReorderGalleryRequest {
+    /// 画册中全部图片的 ID，按期望的展示顺序排列
+    #[schema(example = json!([12, 10, 11]))]
+    pub image_ids: Vec<i32>,
+}
+
 /// 通用成功响应
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SuccessResponse {
@@ -313,3 +1093,433 @@ pub struct ServerTotalPlayers {
     #[schema(example = 1234)]
     pub total_players: i32,
 }
+
+/// 数据推送密钥响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PushSecretResponse {
+    /// 新的签名密钥，仅在生成时返回一次
+    #[schema(example = "k3Jd8sPq0ZxV2mN7bT4yR1wC6hF9gL5aE0uI3oS8dK2jQ7vX")]
+    pub secret: String,
+}
+
+/// 服务器信息修订版本
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerRevision {
+    /// 记录 ID，回滚时使用
+    #[schema(example = 12)]
+    pub id: i32,
+    /// 版本号，每个服务器从 1 开始递增
+    #[schema(example = 3)]
+    pub revision: i32,
+    /// 服务器名称
+    #[schema(example = "我的世界服务器")]
+    pub name: String,
+    /// 服务器描述
+    #[schema(example = "一个有趣的生存服务器")]
+    pub desc: String,
+    /// 服务器标签
+    #[schema(example = json!(["生存", "PVP"]))]
+    pub tags: Option<Vec<String>>,
+    /// 修改人 ID，初始版本为空
+    #[schema(example = 1)]
+    pub editor_id: Option<i32>,
+    /// 由哪个版本回滚而来
+    #[schema(example = json!(null))]
+    pub rolled_back_from: Option<i32>,
+    /// 创建时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+/// 服务器修订版本列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerRevisionListResponse {
+    /// 按版本号倒序排列
+    pub data: Vec<ServerRevision>,
+}
+
+/// 标签推荐请求，字段为空时使用服务器当前的描述与标签
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TagSuggestRequest {
+    /// 待分析的描述（如编辑中尚未保存的描述）
+    #[schema(example = "纯净生存服务器，支持红石生电，定期举办建筑比赛")]
+    pub desc: Option<String>,
+    /// 已选择的标签，不会再次推荐
+    #[schema(example = json!(["生存"]))]
+    pub tags: Option<Vec<String>>,
+}
+
+/// 推荐的标签
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TagSuggestion {
+    /// 标签
+    #[schema(example = "生电")]
+    pub tag: String,
+    /// 推荐分数（0~1），越高越相关
+    #[schema(example = 0.85)]
+    pub score: f64,
+    /// 推荐来源：`keyword` 描述中出现关键词，`co_occurrence` 常与已有标签同时出现
+    #[schema(example = "keyword")]
+    pub source: String,
+}
+
+/// 标签推荐结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TagSuggestionResponse {
+    /// 推荐标签，按分数从高到低，最多 7 个
+    pub data: Vec<TagSuggestion>,
+}
+
+fn default_similar_limit() -> u64 {
+    5
+}
+
+/// 相似服务器查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct SimilarServersQuery {
+    /// 返回数量（1~20）
+    #[schema(example = 5, default = 5)]
+    #[serde(default = "default_similar_limit")]
+    pub limit: u64,
+}
+
+/// 相似服务器
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SimilarServer {
+    /// 服务器 ID
+    #[schema(example = 2)]
+    pub id: i32,
+    /// 服务器名称
+    #[schema(example = "另一个生存服")]
+    pub name: String,
+    /// 服务器短链接
+    #[schema(example = "another-survival-a1b2c3")]
+    pub slug: Option<String>,
+    /// 服务器标签
+    #[schema(example = json!(["生存", "PVP"]))]
+    pub tags: Option<Vec<String>>,
+    /// 相似度（0~1）
+    #[schema(example = 0.82)]
+    pub score: f64,
+}
+
+/// 相似服务器推荐
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SimilarServersResponse {
+    /// 计算方式：`embedding` 描述向量相似度，`tags` 标签重合度
+    #[schema(example = "tags")]
+    pub method: String,
+    /// 按相似度从高到低排列
+    pub data: Vec<SimilarServer>,
+}
+
+fn default_timeline_limit() -> u64 {
+    50
+}
+
+/// 时间线中记录的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineField {
+    /// MOTD 纯文本
+    Motd,
+    /// 服务器上报的版本
+    Version,
+}
+
+impl TimelineField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimelineField::Motd => "motd",
+            TimelineField::Version => "version",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "motd" => Some(TimelineField::Motd),
+            "version" => Some(TimelineField::Version),
+            _ => None,
+        }
+    }
+}
+
+/// 服务器时间线查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ServerTimelineQuery {
+    /// 只返回指定字段的变化，不传则返回全部
+    #[schema(example = "version")]
+    pub field: Option<TimelineField>,
+    /// 返回数量（1~100）
+    #[schema(example = 50, default = 50)]
+    #[serde(default = "default_timeline_limit")]
+    pub limit: u64,
+}
+
+/// 服务器 MOTD 或版本的一次变化
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerTimelineEntry {
+    /// 变化的字段
+    pub field: TimelineField,
+    /// 变化前的值，首次记录时为空
+    #[schema(example = "Paper 1.20.1")]
+    pub previous: Option<String>,
+    /// 变化后的值
+    #[schema(example = "Paper 1.21")]
+    pub current: String,
+    /// 发现变化的时间（UTC），即带有新值的第一条状态数据的采集时间
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub changed_at: DateTime<Utc>,
+}
+
+/// 服务器 MOTD 与版本变化时间线
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerTimelineResponse {
+    /// 按时间倒序排列
+    pub data: Vec<ServerTimelineEntry>,
+}
+
+/// 历史状态的时间范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StatsHistoryRange {
+    /// 最近 24 小时
+    #[default]
+    #[serde(rename = "24h")]
+    Day,
+    /// 最近 7 天
+    #[serde(rename = "7d")]
+    Week,
+    /// 最近 30 天
+    #[serde(rename = "30d")]
+    Month,
+}
+
+impl StatsHistoryRange {
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            StatsHistoryRange::Day => chrono::Duration::hours(24),
+            StatsHistoryRange::Week => chrono::Duration::days(7),
+            StatsHistoryRange::Month => chrono::Duration::days(30),
+        }
+    }
+}
+
+/// 历史状态查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct StatsHistoryQuery {
+    /// 时间范围
+    #[serde(default)]
+    #[schema(example = "24h", default = "24h")]
+    pub range: StatsHistoryRange,
+    /// 把时间范围等分为多少个区间聚合（1~1000），不传则返回原始采样
+    #[schema(example = 96)]
+    pub buckets: Option<u32>,
+}
+
+/// 一个采样点或聚合区间
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsHistoryPoint {
+    /// 采集时间；聚合时为区间起始时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub timestamp: DateTime<Utc>,
+    /// 在线玩家数；聚合时为区间内在线采样的平均值（四舍五入），全部离线时为空
+    #[schema(example = 10)]
+    pub online: Option<i64>,
+    /// 区间内在线玩家数的最大值，全部离线时为空
+    #[schema(example = 15)]
+    pub peak: Option<i64>,
+    /// 最大可容纳的玩家数，全部离线时为空
+    #[schema(example = 100)]
+    pub max: Option<i64>,
+    /// 在线采样所占比例（0~1），原始采样为 0 或 1
+    #[schema(example = 1.0)]
+    pub uptime: f64,
+}
+
+/// 服务器历史状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsHistoryResponse {
+    /// 时间范围
+    pub range: StatsHistoryRange,
+    /// 聚合区间长度（秒），返回原始采样时为空
+    #[schema(example = 900)]
+    pub bucket_seconds: Option<i64>,
+    /// 按时间正序排列，没有采样的区间不返回
+    pub data: Vec<StatsHistoryPoint>,
+}
+
+/// 服务器在线率
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServerUptimeResponse {
+    /// 当前是否在线，尚无状态记录时为空
+    #[schema(example = true)]
+    pub online: Option<bool>,
+    /// 进入当前状态的时间（UTC），尚无状态记录时为空
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub since: Option<DateTime<Utc>>,
+    /// 当前连续在线的秒数，离线时为空
+    #[schema(example = 86400)]
+    pub online_streak_seconds: Option<i64>,
+    /// 最近 24 小时的在线率（0~1），窗口内没有状态记录时为空
+    #[schema(example = 0.995)]
+    pub uptime_24h: Option<f64>,
+    /// 最近 7 天的在线率（0~1）
+    #[schema(example = 0.98)]
+    pub uptime_7d: Option<f64>,
+    /// 最近 30 天的在线率（0~1）
+    #[schema(example = 0.97)]
+    pub uptime_30d: Option<f64>,
+}
+
+/// MOTD 图片的配色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MotdTheme {
+    /// 深色背景，与游戏内服务器列表一致
+    #[default]
+    Dark,
+    /// 浅色背景，过亮的颜色会被加深
+    Light,
+}
+
+impl MotdTheme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MotdTheme::Dark => "dark",
+            MotdTheme::Light => "light",
+        }
+    }
+}
+
+/// MOTD 图片查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct MotdImageQuery {
+    /// 图片宽度（256~1280 像素），超出范围时取边界值，不传则使用站点默认宽度
+    #[schema(example = 640)]
+    pub width: Option<u32>,
+    /// 配色
+    #[serde(default)]
+    #[schema(example = "dark", default = "dark")]
+    pub theme: MotdTheme,
+}
+
+/// 订阅源格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedFormat {
+    /// RSS 2.0
+    #[default]
+    Rss,
+    /// JSON Feed 1.1
+    Json,
+}
+
+/// 相册订阅源查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct GalleryFeedQuery {
+    /// 订阅源格式，默认 `rss`
+    #[serde(default)]
+    #[schema(example = "rss")]
+    pub format: FeedFormat,
+}
+
+/// 实时更新订阅参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct LiveUpdatesQuery {
+    /// 订阅的服务器 ID，逗号分隔，最多 50 个
+    #[schema(example = "1,2,3")]
+    pub ids: String,
+}
+
+impl LiveUpdatesQuery {
+    /// 单个连接最多订阅的服务器数
+    pub const MAX_IDS: usize = 50;
+
+    pub fn parse_ids(&self) -> Result<Vec<i32>, String> {
+        let mut ids = self
+            .ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<i32>()
+                    .map_err(|_| format!("无效的服务器 ID: {id}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.sort_unstable();
+        ids.dedup();
+
+        if ids.is_empty() || ids.len() > Self::MAX_IDS {
+            return Err(format!("ids 数量需在 1~{} 之间", Self::MAX_IDS));
+        }
+        Ok(ids)
+    }
+}
+
+/// 批量删除画册图片参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct GalleryBatchDeleteQuery {
+    /// 要删除的图片 ID，逗号分隔，最多 100 个
+    #[schema(example = "10,11,12")]
+    pub image_ids: String,
+    /// 第一次调用返回的确认令牌；不传时只返回影响范围，不执行删除
+    pub confirm_token: Option<String>,
+}
+
+impl GalleryBatchDeleteQuery {
+    /// 单次最多删除的图片数
+    pub const MAX_IDS: usize = 100;
+
+    pub fn parse_ids(&self) -> Result<Vec<i32>, String> {
+        let mut ids = self
+            .image_ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<i32>()
+                    .map_err(|_| format!("无效的图片 ID: {id}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.sort_unstable();
+        ids.dedup();
+
+        if ids.is_empty() || ids.len() > Self::MAX_IDS {
+            return Err(format!("image_ids 数量需在 1~{} 之间", Self::MAX_IDS));
+        }
+        Ok(ids)
+    }
+}
+
+/// 热门标签查询参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TagListQuery {
+    /// 返回数量，默认 50，最大 200
+    #[param(example = 50)]
+    pub limit: Option<u32>,
+}
+
+/// 标签及使用次数
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagUsage {
+    /// 标签名
+    #[schema(example = "生存")]
+    pub name: String,
+    /// 使用该标签的公开服务器数
+    #[schema(example = 42)]
+    pub count: u64,
+}
+
+/// 标签列表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagListResponse {
+    /// 正在使用的标签，按使用次数降序
+    pub data: Vec<TagUsage>,
+    /// 管理员维护的标签词表；不为空时创建或编辑服务器只能从中选择标签
+    #[schema(example = json!(["生存", "生电", "PVP"]))]
+    pub vocabulary: Vec<String>,
+}
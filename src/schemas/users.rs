@@ -0,0 +1,683 @@
+use axum_typed_multipart::{FieldData, TryFromMultipart};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::schemas::servers::ServerDetail;
+
+fn default_page() -> u64 {
+    1
+}
+fn default_page_size() -> u64 {
+    20
+}
+
+/// 用户操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityAction {
+    /// 创建服务器
+    ServerCreated,
+    /// 编辑服务器信息
+    ServerUpdated,
+    /// 回滚服务器信息
+    ServerRolledBack,
+    /// 上传画册图片
+    GalleryImageAdded,
+    /// 删除画册图片
+    GalleryImageDeleted,
+    /// 编辑画册图片信息
+    GalleryImageUpdated,
+    /// 调整画册图片顺序
+    GalleryReordered,
+    /// 删除服务器
+    ServerDeleted,
+    /// 重置推送密钥
+    PushSecretRotated,
+    /// 审核可疑注册
+    RegistrationFlagReviewed,
+    /// 审核被扣留的内容
+    SpamHoldReviewed,
+    /// 合并或重命名标签
+    TagsMerged,
+    /// 管理员代入用户身份
+    UserImpersonated,
+    /// 添加服务器管理员
+    ManagerAdded,
+    /// 修改服务器管理员角色
+    ManagerRoleChanged,
+    /// 移除服务器管理员
+    ManagerRemoved,
+    /// 转让服务器所有权
+    OwnershipTransferred,
+    /// 封禁用户
+    UserBanned,
+    /// 解除封禁
+    UserUnbanned,
+    /// 管理员处理服务器（强制编辑、调整可见性、移除画册图片、审核发布申请）
+    ServerModerated,
+    /// 修改标签词表
+    TagVocabularyUpdated,
+    /// 修改账户邮箱
+    EmailChanged,
+    /// 修改密码
+    PasswordChanged,
+    /// 创建 API 密钥
+    ApiKeyCreated,
+    /// 吊销 API 密钥
+    ApiKeyRevoked,
+    /// 注销登录会话
+    SessionsRevoked,
+    /// 提交服务器发布审核
+    ServerSubmitted,
+    /// 归档服务器
+    ServerArchived,
+}
+
+impl ActivityAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActivityAction::ServerCreated => "server_created",
+            ActivityAction::ServerUpdated => "server_updated",
+            ActivityAction::ServerRolledBack => "server_rolled_back",
+            ActivityAction::GalleryImageAdded => "gallery_image_added",
+            ActivityAction::GalleryImageDeleted => "gallery_image_deleted",
+            ActivityAction::GalleryImageUpdated => "gallery_image_updated",
+            ActivityAction::GalleryReordered => "gallery_reordered",
+            ActivityAction::ServerDeleted => "server_deleted",
+            ActivityAction::PushSecretRotated => "push_secret_rotated",
+            ActivityAction::RegistrationFlagReviewed => "registration_flag_reviewed",
+            ActivityAction::SpamHoldReviewed => "spam_hold_reviewed",
+            ActivityAction::TagsMerged => "tags_merged",
+            ActivityAction::UserImpersonated => "user_impersonated",
+            ActivityAction::ManagerAdded => "manager_added",
+            ActivityAction::ManagerRoleChanged => "manager_role_changed",
+            ActivityAction::ManagerRemoved => "manager_removed",
+            ActivityAction::OwnershipTransferred => "ownership_transferred",
+            ActivityAction::UserBanned => "user_banned",
+            ActivityAction::UserUnbanned => "user_unbanned",
+            ActivityAction::ServerModerated => "server_moderated",
+            ActivityAction::TagVocabularyUpdated => "tag_vocabulary_updated",
+            ActivityAction::EmailChanged => "email_changed",
+            ActivityAction::PasswordChanged => "password_changed",
+            ActivityAction::ApiKeyCreated => "api_key_created",
+            ActivityAction::ApiKeyRevoked => "api_key_revoked",
+            ActivityAction::SessionsRevoked => "sessions_revoked",
+            ActivityAction::ServerSubmitted => "server_submitted",
+            ActivityAction::ServerArchived => "server_archived",
+        }
+    }
+}
+
+/// 当前用户资料
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserProfile {
+    /// 用户 ID
+    #[schema(example = 42)]
+    pub id: i32,
+    /// 用户名
+    #[schema(example = "user123")]
+    pub username: String,
+    /// 邮箱
+    #[schema(example = "user@example.com")]
+    pub email: String,
+    /// 显示名称
+    #[schema(example = "张三-Mike")]
+    pub display_name: String,
+    /// 头像地址，未设置头像时为空
+    #[schema(example = "https://cdn.example.com/uploads/avatar.webp")]
+    pub avatar_url: Option<String>,
+    /// 64px 小尺寸头像地址，旧头像没有小尺寸时与 `avatar_url` 相同
+    #[schema(example = "https://cdn.example.com/uploads/avatar-small.webp")]
+    pub avatar_small_url: Option<String>,
+    /// 角色
+    #[schema(example = "user")]
+    pub role: String,
+    /// 注册时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 最后登录时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub last_login: Option<DateTime<Utc>>,
+}
+
+/// 更新当前用户资料请求，未传的字段保持不变
+#[derive(Debug, TryFromMultipart, Validate, ToSchema)]
+pub struct UpdateProfileRequest {
+    /// 显示名称(长度在 2 到 16 个字符之间，可以包含中文、英文、俄文、数字、下划线和短横线)
+    #[schema(example = "张三-Mike")]
+    #[validate(length(
+        min = 2,
+        max = 16,
+        message = "显示名称不能少于 2 个字符，不能超过 16 个字符"
+    ))]
+    #[validate(regex(
+        path = "*crate::schemas::auth::DISPLAY_NAME_REGEX",
+        message = "显示名称只能包含中文、英文、俄文、数字、下划线和短横线"
+    ))]
+    pub display_name: Option<String>,
+
+    /// 头像文件，处理方式与上传头像接口相同
+    #[schema(value_type = Option<String>, format = Binary)]
+    pub avatar: Option<FieldData<axum::body::Bytes>>,
+}
+
+/// 上传头像请求
+#[derive(Debug, TryFromMultipart, ToSchema)]
+pub struct UploadAvatarRequest {
+    /// 头像文件（不超过 5 MB，支持 JPEG / PNG / WebP，边长不小于 64px）
+    #[schema(value_type = String, format = Binary)]
+    pub avatar: FieldData<axum::body::Bytes>,
+}
+
+/// 申请修改邮箱
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EmailChangeRequest {
+    /// 新邮箱，验证码发送到该地址
+    #[validate(email(message = "邮箱格式不正确"))]
+    #[schema(example = "new@example.com")]
+    pub new_email: String,
+}
+
+/// 确认修改邮箱
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ConfirmEmailChangeRequest {
+    /// 申请时填写的新邮箱
+    #[validate(email(message = "邮箱格式不正确"))]
+    #[schema(example = "new@example.com")]
+    pub new_email: String,
+    /// 新邮箱收到的验证码
+    #[validate(length(equal = 6, message = "验证码长度必须为 6 位"))]
+    #[schema(example = "123456")]
+    pub code: String,
+}
+
+/// 修改密码
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    /// 当前密码
+    #[schema(example = "Password123")]
+    pub current_password: String,
+    /// 新密码(长度在 8 到 32 个字符之间，必须包含字母和数字)
+    #[validate(length(min = 8, max = 32, message = "密码长度必须在 8 到 32 个字符之间"))]
+    #[validate(custom(function = "crate::schemas::auth::validate_password_complexity"))]
+    #[schema(example = "NewPassword123")]
+    pub new_password: String,
+}
+
+/// 操作记录查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ActivityQuery {
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 单条操作记录
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ActivityInfo {
+    /// 记录 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 操作类型，未知类型原样返回
+    #[schema(example = "server_updated")]
+    pub action: String,
+    /// 操作对象类型
+    #[schema(example = "server")]
+    pub target_type: Option<String>,
+    /// 操作对象 ID
+    #[schema(example = 1)]
+    pub target_id: Option<i32>,
+    /// 附加信息
+    #[schema(value_type = Option<Object>, example = json!({"image_id": 10}))]
+    pub detail: Option<serde_json::Value>,
+    /// 操作时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+/// 收藏列表查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct FavoriteQuery {
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+}
+
+/// 收藏的服务器
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FavoriteServer {
+    /// 收藏时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub favorited_at: DateTime<Utc>,
+    /// 服务器信息，与服务器列表中的条目一致
+    pub server: ServerDetail,
+}
+
+/// 外部账户绑定状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// 已发起，等待外部平台确认
+    Pending,
+    /// 已绑定
+    Active,
+    /// 已解除
+    Revoked,
+}
+
+impl LinkStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkStatus::Pending => "pending",
+            LinkStatus::Active => "active",
+            LinkStatus::Revoked => "revoked",
+        }
+    }
+}
+
+/// 发起外部账户绑定
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InitiateLinkRequest {
+    /// 外部平台标识
+    #[schema(example = "mscpo_forum")]
+    pub provider: String,
+}
+
+/// 外部账户绑定凭证
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InitiateLinkResponse {
+    /// 绑定记录 ID
+    #[schema(example = 1)]
+    pub link_id: i32,
+    /// 外部平台标识
+    #[schema(example = "mscpo_forum")]
+    pub provider: String,
+    /// 绑定凭证，在外部平台提交以完成绑定，只返回一次
+    #[schema(example = "Zf3kQ9mB2xLr7TpW")]
+    pub proof_token: String,
+    /// 凭证过期时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:10:00Z", format = DateTime)]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 外部账户绑定
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExternalIdentityInfo {
+    /// 绑定记录 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 外部平台标识
+    #[schema(example = "mscpo_forum")]
+    pub provider: String,
+    /// 外部平台的账户 ID，确认前为空
+    #[schema(example = "10086")]
+    pub external_id: Option<String>,
+    /// 绑定状态
+    pub status: LinkStatus,
+    /// 发起时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 确认时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:05:00Z", format = DateTime)]
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// 外部账户绑定列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExternalIdentityListResponse {
+    /// 未解除的绑定
+    pub data: Vec<ExternalIdentityInfo>,
+}
+
+/// API 密钥的权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// 只读，只能调用 GET 等安全方法
+    ReadOnly,
+    /// 只读之外，还可以修改自己有权管理的服务器（`/v2/servers` 下的接口）
+    ManageServer,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiKeyScope::ReadOnly => "read_only",
+            ApiKeyScope::ManageServer => "manage_server",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read_only" => Some(ApiKeyScope::ReadOnly),
+            "manage_server" => Some(ApiKeyScope::ManageServer),
+            _ => None,
+        }
+    }
+}
+
+/// 创建 API 密钥请求
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiKeyRequest {
+    /// 密钥名称，便于区分用途
+    #[schema(example = "状态同步脚本")]
+    #[validate(length(min = 1, max = 50, message = "密钥名称长度必须在1-50个字符之间"))]
+    pub name: String,
+    /// 权限范围
+    pub scope: ApiKeyScope,
+}
+
+/// API 密钥
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyInfo {
+    /// 密钥 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 密钥名称
+    #[schema(example = "状态同步脚本")]
+    pub name: String,
+    /// 密钥开头几位，用于辨认密钥
+    #[schema(example = "sak_Zf3kQ9mB")]
+    pub prefix: String,
+    /// 权限范围
+    pub scope: ApiKeyScope,
+    /// 创建时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// 最近使用时间（UTC），精确到分钟
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-02T08:30:00Z", format = DateTime)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// 新创建的 API 密钥
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    /// 完整密钥，放在 `X-Api-Key` 请求头中使用，只返回一次
+    #[schema(example = "sak_Zf3kQ9mB2xLr7TpWc8VnH4sJ6dYq1aEuK0gRtFoP")]
+    pub key: String,
+    /// 密钥信息
+    pub api_key: ApiKeyInfo,
+}
+
+/// API 密钥列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyListResponse {
+    /// 未吊销的密钥
+    pub data: Vec<ApiKeyInfo>,
+}
+
+/// 登录会话，每个未过期、未吊销的访问令牌对应一个会话
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionInfo {
+    /// 会话 ID
+    #[schema(example = "3f2b8c0d9e6a4b17a5c4d2e1f0a9b8c7")]
+    pub id: String,
+    /// 是否为发起本次请求的会话
+    pub current: bool,
+    /// 是否为管理员代入用户身份时签发的会话
+    pub impersonated: bool,
+    /// 最近一次请求的 IP 地址
+    #[schema(example = "203.0.113.10")]
+    pub ip: Option<String>,
+    /// 登录时客户端的 User-Agent
+    #[schema(example = "Mozilla/5.0 (Windows NT 10.0; Win64; x64)")]
+    pub user_agent: Option<String>,
+    /// 登录时间（UTC），会话管理上线前签发的令牌没有记录
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// 最近一次请求的时间（UTC），精确到分钟
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-02T08:30:00Z", format = DateTime)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// 过期时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-31T00:00:00Z", format = DateTime)]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 登录会话列表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionListResponse {
+    /// 未过期的会话，当前会话在前，其余按最近活动时间倒序
+    pub data: Vec<SessionInfo>,
+}
+
+/// 批量注销会话结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevokeSessionsResponse {
+    /// 注销的会话数量
+    #[schema(example = 3)]
+    pub revoked: usize,
+}
+
+/// 通知摘要频率
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    /// 每条通知立即发送邮件
+    Instant,
+    /// 每天汇总发送一封邮件
+    Daily,
+    /// 每周汇总发送一封邮件
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DigestFrequency::Instant => "instant",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "instant" => Some(DigestFrequency::Instant),
+            "daily" => Some(DigestFrequency::Daily),
+            "weekly" => Some(DigestFrequency::Weekly),
+            _ => None,
+        }
+    }
+
+    /// 两次摘要邮件的最小间隔（秒），立即发送时为空
+    pub fn interval_secs(self) -> Option<u64> {
+        match self {
+            DigestFrequency::Instant => None,
+            DigestFrequency::Daily => Some(24 * 3600),
+            DigestFrequency::Weekly => Some(7 * 24 * 3600),
+        }
+    }
+}
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 被扣留的内容审核完成
+    SpamHoldReviewed,
+    /// 服务器从在线变为离线
+    ServerOffline,
+    /// 服务器长期离线，即将下架
+    ServerOfflineWarning,
+    /// 服务器因长期离线被下架
+    ServerDelisted,
+    /// 服务器恢复后重新上架
+    ServerRelisted,
+    /// 账户被封禁或禁言，不能关闭
+    AccountBanned,
+    /// 服务器发布申请审核完成
+    ServerReviewed,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 7] = [
+        NotificationKind::SpamHoldReviewed,
+        NotificationKind::ServerOffline,
+        NotificationKind::ServerOfflineWarning,
+        NotificationKind::ServerDelisted,
+        NotificationKind::ServerRelisted,
+        NotificationKind::AccountBanned,
+        NotificationKind::ServerReviewed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::SpamHoldReviewed => "spam_hold_reviewed",
+            NotificationKind::ServerOffline => "server_offline",
+            NotificationKind::ServerOfflineWarning => "server_offline_warning",
+            NotificationKind::ServerDelisted => "server_delisted",
+            NotificationKind::ServerRelisted => "server_relisted",
+            NotificationKind::AccountBanned => "account_banned",
+            NotificationKind::ServerReviewed => "server_reviewed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// 用户能否关闭该类通知，涉及账户处置的通知始终发送
+    pub fn mutable(self) -> bool {
+        !matches!(self, NotificationKind::AccountBanned)
+    }
+}
+
+/// 站内通知查询参数
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct NotificationQuery {
+    /// 页码
+    #[schema(example = 1, default = 1)]
+    #[serde(default = "default_page")]
+    pub page: u64,
+    /// 每页数量
+    #[schema(example = 20, default = 20)]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+    /// 只返回未读通知
+    #[schema(example = false, default = false)]
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// 单条站内通知
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationInfo {
+    /// 通知 ID
+    #[schema(example = 1)]
+    pub id: i32,
+    /// 通知类型，未知类型原样返回
+    #[schema(example = "server_offline")]
+    pub kind: String,
+    /// 标题
+    #[schema(example = "服务器已离线")]
+    pub title: String,
+    /// 正文
+    #[schema(
+        example = "服务器「MSCPO 生存服」于 2025-01-01 00:00 UTC 起无法连接，请检查服务器运行状态。"
+    )]
+    pub body: String,
+    /// 已读时间（UTC），未读时为空
+    #[serde(with = "crate::schemas::datetime::rfc3339_option")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub read_at: Option<DateTime<Utc>>,
+    /// 通知时间（UTC）
+    #[serde(with = "crate::schemas::datetime::rfc3339")]
+    #[schema(example = "2025-01-01T00:00:00Z", format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+/// 标记通知为已读
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct MarkNotificationsReadRequest {
+    /// 要标记的通知 ID，不传时标记全部未读通知
+    #[schema(example = json!([1, 2]))]
+    pub ids: Option<Vec<i32>>,
+}
+
+/// 标记已读的结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MarkNotificationsReadResponse {
+    /// 本次标记为已读的通知数
+    #[schema(example = 2)]
+    pub updated: u64,
+    /// 剩余的未读通知数
+    #[schema(example = 0)]
+    pub unread: u64,
+}
+
+/// 未读通知数
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnreadNotificationCount {
+    /// 未读通知数
+    #[schema(example = 3)]
+    pub unread: u64,
+}
+
+/// 通知渠道开关
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct NotificationChannels {
+    /// 邮件通知
+    #[schema(example = true)]
+    pub email: bool,
+    /// 站内通知
+    #[schema(example = true)]
+    pub in_app: bool,
+}
+
+/// 用户偏好设置
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserPreferences {
+    /// 通知渠道
+    pub channels: NotificationChannels,
+    /// 邮件通知的摘要频率
+    pub digest_frequency: DigestFrequency,
+    /// 已关闭的通知类型，这些类型在任何渠道都不发送
+    pub muted_kinds: Vec<NotificationKind>,
+    /// 界面与通知使用的语言
+    #[schema(example = "zh-CN")]
+    pub locale: String,
+}
+
+/// 更新用户偏好设置，未传的字段保持不变
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdatePreferencesRequest {
+    /// 通知渠道
+    pub channels: Option<NotificationChannels>,
+    /// 邮件通知的摘要频率
+    pub digest_frequency: Option<DigestFrequency>,
+    /// 要关闭的通知类型，传入时整体替换，传空数组表示全部开启；`account_banned` 不能关闭
+    pub muted_kinds: Option<Vec<NotificationKind>>,
+    /// 界面与通知使用的语言（如 zh-CN、en）
+    #[validate(regex(path = "*LOCALE_REGEX", message = "语言格式无效"))]
+    #[schema(example = "zh-CN")]
+    pub locale: Option<String>,
+}
+
+/// 语言标签格式：语言代码，可带地区
+pub static LOCALE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-zA-Z]{2,3}(?:-[a-zA-Z0-9]{2,8})?$").unwrap());
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sea_orm::*;
+
+use crate::{
+    config::NamePolicyConfig,
+    entities::{
+        activity, external_identities, files,
+        prelude::{Activity, ExternalIdentities, Files, UserFavorite, UserServer, Users},
+        user_favorite, user_server, users,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        servers::ServerManagerRole,
+        users::{UpdateProfileRequest, UserProfile},
+    },
+    services::{
+        database::DatabaseConnection,
+        file_upload::FileUploadService,
+        name_policy::NamePolicyService,
+        password::PasswordService,
+        server::ServerService,
+        storage::{self, StorageBackend},
+    },
+};
+
+/// 账户资料与注销服务
+pub struct AccountService;
+
+impl AccountService {
+    /// 当前用户资料
+    pub async fn profile(db: &DatabaseConnection, user_id: i32) -> ApiResult<UserProfile> {
+        let user = Users::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+        Self::to_profile(db, user).await
+    }
+
+    /// 更新显示名称与头像，未传的字段保持不变
+    pub async fn update_profile(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        name_policy: &NamePolicyConfig,
+        user_id: i32,
+        request: UpdateProfileRequest,
+    ) -> ApiResult<UserProfile> {
+        let user = Users::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+
+        let display_name = request
+            .display_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| *name != user.display_name);
+        if let Some(name) = &display_name {
+            NamePolicyService::check(db, name_policy, &[name], Some(user_id)).await?;
+        }
+
+        let avatar = match request.avatar {
+            Some(avatar) => Some(
+                FileUploadService::validate_and_upload_avatar(
+                    db,
+                    storage::require(storage)?,
+                    avatar.contents.to_vec(),
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        if display_name.is_none() && avatar.is_none() {
+            return Self::to_profile(db, user).await;
+        }
+
+        let mut active: users::ActiveModel = user.into();
+        if let Some(name) = display_name {
+            active.display_name = Set(name);
+        }
+        if let Some((large, small)) = avatar {
+            active.avatar_hash_id = Set(Some(large.hash_value));
+            active.avatar_small_hash_id = Set(Some(small.hash_value));
+        }
+        let user = active.update(db.as_ref()).await?;
+        Self::to_profile(db, user).await
+    }
+
+    /// 上传新头像
+    pub async fn update_avatar(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        user_id: i32,
+        content: Vec<u8>,
+    ) -> ApiResult<UserProfile> {
+        let storage = storage::require(storage)?;
+        let user = Users::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+
+        let (large, small) =
+            FileUploadService::validate_and_upload_avatar(db, storage, content).await?;
+        let mut active: users::ActiveModel = user.into();
+        active.avatar_hash_id = Set(Some(large.hash_value));
+        active.avatar_small_hash_id = Set(Some(small.hash_value));
+        let user = active.update(db.as_ref()).await?;
+        Self::to_profile(db, user).await
+    }
+
+    async fn to_profile(db: &DatabaseConnection, user: users::Model) -> ApiResult<UserProfile> {
+        let hashes: Vec<&String> = [&user.avatar_hash_id, &user.avatar_small_hash_id]
+            .into_iter()
+            .flatten()
+            .collect();
+        let paths: HashMap<String, String> = if hashes.is_empty() {
+            HashMap::new()
+        } else {
+            Files::find()
+                .filter(files::Column::HashValue.is_in(hashes))
+                .all(db.as_ref())
+                .await?
+                .into_iter()
+                .map(|file| (file.hash_value, file.file_path))
+                .collect()
+        };
+        let url = |hash: &Option<String>| {
+            hash.as_ref()
+                .and_then(|hash| paths.get(hash))
+                .map(|path| ServerService::build_image_url(path))
+        };
+        let avatar_url = url(&user.avatar_hash_id);
+        let avatar_small_url = url(&user.avatar_small_hash_id).or_else(|| avatar_url.clone());
+
+        Ok(UserProfile {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            display_name: user.display_name,
+            avatar_url,
+            avatar_small_url,
+            role: user.role.to_value(),
+            created_at: user.created_at,
+            last_login: user.last_login,
+        })
+    }
+
+    /// 注销账户会连带删除的数据条数
+    ///
+    /// 仍是服务器所有者时不允许注销，避免留下无人管理的服务器。
+    pub async fn deletion_impact(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ApiResult<BTreeMap<String, u64>> {
+        let memberships = UserServer::find()
+            .filter(user_server::Column::UserId.eq(user_id))
+            .all(db.as_ref())
+            .await?;
+        if memberships
+            .iter()
+            .any(|us| us.role == ServerManagerRole::Owner.as_str())
+        {
+            return Err(ApiError::Conflict(
+                "请先删除或转让您拥有的服务器".to_string(),
+            ));
+        }
+
+        let (external_links, activity_records, favorites) = tokio::try_join!(
+            ExternalIdentities::find()
+                .filter(external_identities::Column::UserId.eq(user_id))
+                .count(db.as_ref()),
+            Activity::find()
+                .filter(activity::Column::UserId.eq(user_id))
+                .count(db.as_ref()),
+            UserFavorite::find()
+                .filter(user_favorite::Column::UserId.eq(user_id))
+                .count(db.as_ref()),
+        )?;
+
+        Ok(BTreeMap::from([
+            ("accounts".to_string(), 1),
+            ("managed_servers".to_string(), memberships.len() as u64),
+            ("external_links".to_string(), external_links),
+            ("activity_records".to_string(), activity_records),
+            ("favorites".to_string(), favorites),
+        ]))
+    }
+
+    /// 邮箱是否已被其他账户使用，邮箱在所有站点间唯一
+    pub async fn email_taken(
+        db: &DatabaseConnection,
+        email: &str,
+        except_user_id: i32,
+    ) -> ApiResult<bool> {
+        let count = Users::find()
+            .filter(users::Column::Email.eq(email))
+            .filter(users::Column::Id.ne(except_user_id))
+            .count(db.as_ref())
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// 修改账户邮箱，返回修改前的邮箱与修改后的资料
+    ///
+    /// 调用方需已校验发到新邮箱的验证码；写入前再次检查邮箱是否被占用，
+    /// 避免申请与确认之间有其他账户注册了同一邮箱。
+    pub async fn change_email(
+        db: &DatabaseConnection,
+        user_id: i32,
+        new_email: &str,
+    ) -> ApiResult<(String, UserProfile)> {
+        let user = Users::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+        if Self::email_taken(db, new_email, user_id).await? {
+            return Err(ApiError::Conflict("该邮箱已被其他账户使用".to_string()));
+        }
+
+        let old_email = user.email.clone();
+        let mut active: users::ActiveModel = user.into();
+        active.email = Set(new_email.to_string());
+        let user = active.update(db.as_ref()).await?;
+        Ok((old_email, Self::to_profile(db, user).await?))
+    }
+
+    /// 校验当前密码后设置新密码，返回更新后的用户
+    pub async fn change_password(
+        db: &DatabaseConnection,
+        user_id: i32,
+        current_password: String,
+        new_password: String,
+        bcrypt_cost: u32,
+    ) -> ApiResult<users::Model> {
+        let user = Users::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+        if !PasswordService::verify(current_password, user.hashed_password.clone()).await? {
+            return Err(ApiError::BadRequest("当前密码错误".to_string()));
+        }
+
+        let hashed_password = PasswordService::hash(new_password, bcrypt_cost).await?;
+        let mut active: users::ActiveModel = user.into();
+        active.hashed_password = Set(hashed_password);
+        Ok(active.update(db.as_ref()).await?)
+    }
+
+    /// 删除账户，管理员关系、外部绑定、操作记录、收藏与偏好设置随外键级联删除
+    pub async fn delete_account(db: &DatabaseConnection, user_id: i32) -> ApiResult<()> {
+        let result = Users::delete_by_id(user_id).exec(db.as_ref()).await?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::user_not_found());
+        }
+        Ok(())
+    }
+}
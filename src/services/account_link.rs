@@ -0,0 +1,255 @@
+use chrono::{Duration, Utc};
+use rand::{distr::Alphanumeric, Rng};
+use sea_orm::*;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::AccountLinkConfig,
+    entities::{
+        external_identities,
+        prelude::{ExternalIdentities, Users},
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        internal::LinkedAccount,
+        users::{ExternalIdentityInfo, InitiateLinkResponse, LinkStatus},
+    },
+    services::database::DatabaseConnection,
+};
+
+/// 外部账户绑定服务
+///
+/// 用户在本站发起绑定并获得一次性凭证，再到外部平台（论坛等第一方系统）提交凭证；
+/// 外部平台携带自己的账户 ID 与凭证调用内部接口确认后绑定生效。凭证只保存哈希。
+/// 同一平台下，一个用户与一个外部账户一一对应。
+pub struct AccountLinkService;
+
+impl AccountLinkService {
+    /// 发起绑定，同一平台未完成的绑定会被替换
+    pub async fn initiate(
+        db: &DatabaseConnection,
+        config: &AccountLinkConfig,
+        user_id: i32,
+        provider: &str,
+    ) -> ApiResult<InitiateLinkResponse> {
+        let provider = Self::check_provider(config, provider)?;
+
+        let already_linked = ExternalIdentities::find()
+            .filter(external_identities::Column::UserId.eq(user_id))
+            .filter(external_identities::Column::Provider.eq(&provider))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Active.as_str()))
+            .one(db.as_ref())
+            .await?
+            .is_some();
+        if already_linked {
+            return Err(ApiError::Conflict(
+                "已绑定该平台账户，请先解除绑定".to_string(),
+            ));
+        }
+
+        ExternalIdentities::delete_many()
+            .filter(external_identities::Column::UserId.eq(user_id))
+            .filter(external_identities::Column::Provider.eq(&provider))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Pending.as_str()))
+            .exec(db.as_ref())
+            .await?;
+
+        let proof_token: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(config.proof_ttl_secs);
+
+        let link = external_identities::ActiveModel {
+            user_id: Set(user_id),
+            provider: Set(provider.clone()),
+            status: Set(LinkStatus::Pending.as_str().to_string()),
+            proof_hash: Set(Some(Self::hash_proof(&proof_token))),
+            expires_at: Set(Some(expires_at)),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+
+        Ok(InitiateLinkResponse {
+            link_id: link.id,
+            provider,
+            proof_token,
+            expires_at,
+        })
+    }
+
+    /// 外部平台确认绑定
+    pub async fn confirm(
+        db: &DatabaseConnection,
+        config: &AccountLinkConfig,
+        provider: &str,
+        external_id: &str,
+        proof_token: &str,
+    ) -> ApiResult<LinkedAccount> {
+        let provider = Self::check_provider(config, provider)?;
+        let external_id = external_id.trim();
+        if external_id.is_empty() {
+            return Err(ApiError::BadRequest("外部账户 ID 不能为空".to_string()));
+        }
+
+        let txn = db.begin().await?;
+
+        let link = ExternalIdentities::find()
+            .filter(external_identities::Column::Provider.eq(&provider))
+            .filter(external_identities::Column::ProofHash.eq(Self::hash_proof(proof_token)))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Pending.as_str()))
+            .one(&txn)
+            .await?
+            .filter(|link| link.expires_at.is_some_and(|at| at > Utc::now()))
+            .ok_or_else(|| ApiError::BadRequest("绑定凭证无效或已过期".to_string()))?;
+
+        let taken = ExternalIdentities::find()
+            .filter(external_identities::Column::Provider.eq(&provider))
+            .filter(external_identities::Column::ExternalId.eq(external_id))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Active.as_str()))
+            .one(&txn)
+            .await?
+            .is_some();
+        if taken {
+            return Err(ApiError::Conflict("该外部账户已绑定其他用户".to_string()));
+        }
+
+        let mut active: external_identities::ActiveModel = link.into();
+        active.external_id = Set(Some(external_id.to_string()));
+        active.status = Set(LinkStatus::Active.as_str().to_string());
+        active.proof_hash = Set(None);
+        active.expires_at = Set(None);
+        active.confirmed_at = Set(Some(Utc::now()));
+        let link = active.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Self::to_linked_account(db, link).await
+    }
+
+    /// 根据外部账户查找本站用户
+    pub async fn find_linked(
+        db: &DatabaseConnection,
+        provider: &str,
+        external_id: &str,
+    ) -> ApiResult<LinkedAccount> {
+        let link = Self::find_active(db, provider, external_id).await?;
+        Self::to_linked_account(db, link).await
+    }
+
+    /// 列出用户未解除的绑定
+    pub async fn list(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ApiResult<Vec<ExternalIdentityInfo>> {
+        let links = ExternalIdentities::find()
+            .filter(external_identities::Column::UserId.eq(user_id))
+            .filter(external_identities::Column::Status.ne(LinkStatus::Revoked.as_str()))
+            .order_by_desc(external_identities::Column::Id)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(links.into_iter().map(Self::to_info).collect())
+    }
+
+    /// 用户解除自己的绑定
+    pub async fn revoke_by_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        link_id: i32,
+    ) -> ApiResult<()> {
+        let link = ExternalIdentities::find_by_id(link_id)
+            .filter(external_identities::Column::UserId.eq(user_id))
+            .filter(external_identities::Column::Status.ne(LinkStatus::Revoked.as_str()))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::NotFound("绑定不存在".to_string()))?;
+
+        Self::revoke(db, link).await
+    }
+
+    /// 外部平台解除绑定
+    pub async fn revoke_external(
+        db: &DatabaseConnection,
+        provider: &str,
+        external_id: &str,
+    ) -> ApiResult<()> {
+        let link = Self::find_active(db, provider, external_id).await?;
+        Self::revoke(db, link).await
+    }
+
+    async fn revoke(db: &DatabaseConnection, link: external_identities::Model) -> ApiResult<()> {
+        let mut active: external_identities::ActiveModel = link.into();
+        active.status = Set(LinkStatus::Revoked.as_str().to_string());
+        active.proof_hash = Set(None);
+        active.revoked_at = Set(Some(Utc::now()));
+        active.update(db.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn find_active(
+        db: &DatabaseConnection,
+        provider: &str,
+        external_id: &str,
+    ) -> ApiResult<external_identities::Model> {
+        ExternalIdentities::find()
+            .filter(external_identities::Column::Provider.eq(provider.trim().to_lowercase()))
+            .filter(external_identities::Column::ExternalId.eq(external_id))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Active.as_str()))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::NotFound("未找到绑定的账户".to_string()))
+    }
+
+    fn check_provider(config: &AccountLinkConfig, provider: &str) -> ApiResult<String> {
+        let provider = provider.trim().to_lowercase();
+        if !config.providers.contains(&provider) {
+            return Err(ApiError::BadRequest(format!("不支持的平台: {provider}")));
+        }
+        Ok(provider)
+    }
+
+    fn hash_proof(proof_token: &str) -> String {
+        hex::encode(Sha256::digest(proof_token.as_bytes()))
+    }
+
+    async fn to_linked_account(
+        db: &DatabaseConnection,
+        link: external_identities::Model,
+    ) -> ApiResult<LinkedAccount> {
+        let user = Users::find_by_id(link.user_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+
+        Ok(LinkedAccount {
+            provider: link.provider,
+            external_id: link.external_id.unwrap_or_default(),
+            user_id: user.id,
+            username: user.username,
+            display_name: user.display_name,
+            linked_at: link.confirmed_at.unwrap_or(link.created_at),
+        })
+    }
+
+    pub(crate) fn to_info(link: external_identities::Model) -> ExternalIdentityInfo {
+        let status = match link.status.as_str() {
+            "active" => LinkStatus::Active,
+            "revoked" => LinkStatus::Revoked,
+            _ => LinkStatus::Pending,
+        };
+
+        ExternalIdentityInfo {
+            id: link.id,
+            provider: link.provider,
+            external_id: link.external_id,
+            status,
+            created_at: link.created_at,
+            confirmed_at: link.confirmed_at,
+        }
+    }
+}
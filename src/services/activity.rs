@@ -0,0 +1,101 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{activity, prelude::Activity},
+    errors::ApiResult,
+    schemas::users::{ActivityAction, ActivityInfo},
+    services::{auth::AuthService, database::DatabaseConnection},
+};
+
+/// 操作对象：服务器
+pub const TARGET_SERVER: &str = "server";
+/// 操作对象：可疑注册标记
+pub const TARGET_REGISTRATION_FLAG: &str = "registration_flag";
+/// 操作对象：被扣留的内容
+pub const TARGET_SPAM_HOLD: &str = "spam_hold";
+/// 操作对象：用户
+pub const TARGET_USER: &str = "user";
+
+/// 用户操作记录服务
+///
+/// 记录编辑服务器、上传图片、审核等重要操作，供用户在个人主页查看时间线。
+/// 管理员代入用户身份期间的操作会在 `detail.impersonated_by` 中记录管理员 ID。
+/// 写入失败只记录日志，不影响操作本身。
+pub struct ActivityService;
+
+impl ActivityService {
+    /// 记录一次操作
+    pub async fn record(
+        db: &DatabaseConnection,
+        user_id: i32,
+        action: ActivityAction,
+        target: Option<(&str, i32)>,
+        detail: Option<JsonValue>,
+    ) {
+        let detail = match AuthService::current_impersonator() {
+            Some(admin_id) => {
+                let mut detail = match detail {
+                    Some(JsonValue::Object(map)) => map,
+                    Some(other) => [("value".to_string(), other)].into_iter().collect(),
+                    None => Default::default(),
+                };
+                detail.insert("impersonated_by".to_string(), admin_id.into());
+                Some(JsonValue::Object(detail))
+            }
+            None => detail,
+        };
+
+        let result = activity::ActiveModel {
+            user_id: Set(user_id),
+            action: Set(action.as_str().to_string()),
+            target_type: Set(target.map(|(target_type, _)| target_type.to_string())),
+            target_id: Set(target.map(|(_, target_id)| target_id)),
+            detail: Set(detail),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!(
+                "⚠️  记录用户操作失败: user_id={}, action={}, error={}",
+                user_id,
+                action.as_str(),
+                e
+            );
+        }
+    }
+
+    /// 分页获取用户的操作记录，按时间倒序
+    pub async fn list(
+        db: &DatabaseConnection,
+        user_id: i32,
+        page: u64,
+        page_size: u64,
+    ) -> ApiResult<(Vec<ActivityInfo>, u64)> {
+        let paginator = Activity::find()
+            .filter(activity::Column::UserId.eq(user_id))
+            .order_by_desc(activity::Column::CreatedAt)
+            .order_by_desc(activity::Column::Id)
+            .paginate(db.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(|item| ActivityInfo {
+                id: item.id,
+                action: item.action,
+                target_type: item.target_type,
+                target_id: item.target_id,
+                detail: item.detail,
+                created_at: item.created_at,
+            })
+            .collect();
+
+        Ok((data, total))
+    }
+}
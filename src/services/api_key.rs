@@ -0,0 +1,238 @@
+use axum::http::Method;
+use chrono::{Duration, Utc};
+use rand::{distr::Alphanumeric, Rng};
+use sea_orm::*;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    entities::{
+        api_keys,
+        prelude::{ApiKeys, Users},
+    },
+    errors::{ApiError, ApiResult},
+    schemas::users::{ApiKeyInfo, ApiKeyScope, CreatedApiKey},
+    services::{auth::Claims, database::DatabaseConnection},
+};
+
+/// API 密钥服务
+///
+/// 供自动化工具代替 JWT 使用，放在 `X-Api-Key` 请求头中，认证后按用户本人的身份访问，
+/// 并受密钥权限范围限制。密钥只在创建时返回一次，数据库中只保存哈希。
+pub struct ApiKeyService;
+
+impl ApiKeyService {
+    /// 密钥前缀，便于在日志与代码仓库中识别泄露的密钥
+    const KEY_PREFIX: &'static str = "sak_";
+    /// 前缀之后随机部分的长度
+    const KEY_RANDOM_LEN: usize = 40;
+    /// 列表中展示的密钥开头长度（含前缀）
+    const DISPLAY_PREFIX_LEN: usize = 12;
+    /// 每个用户最多持有的未吊销密钥数
+    const MAX_KEYS_PER_USER: u64 = 20;
+    /// 最近使用时间的更新间隔（秒），避免每个请求都写库
+    const TOUCH_INTERVAL_SECS: i64 = 60;
+    /// 密钥不能访问的路径：管理后台、密钥管理与会话管理，避免泄露的密钥自我续命或踢掉用户的登录
+    const RESTRICTED_PREFIXES: [&'static str; 3] = [
+        "/v2/admin",
+        "/v2/users/me/api-keys",
+        "/v2/users/me/sessions",
+    ];
+    /// 管理服务器的密钥可以调用的写接口：资料、图片、自定义字段、提交审核与标签建议。
+    /// 删除服务器、管理员变更、转让、回滚、归档与推送密钥等高风险操作只能登录后进行
+    const MANAGE_SERVER_ROUTES: &'static [(Method, &'static str)] = &[
+        (Method::POST, "/v2/servers"),
+        (Method::PUT, "/v2/servers/{server_id}"),
+        (Method::DELETE, "/v2/servers/{server_id}/cover"),
+        (Method::POST, "/v2/servers/{server_id}/icon"),
+        (Method::DELETE, "/v2/servers/{server_id}/icon"),
+        (Method::POST, "/v2/servers/{server_id}/gallery"),
+        (Method::DELETE, "/v2/servers/{server_id}/gallery"),
+        (Method::PUT, "/v2/servers/{server_id}/gallery/order"),
+        (Method::PATCH, "/v2/servers/{server_id}/gallery/{image_id}"),
+        (Method::DELETE, "/v2/servers/{server_id}/gallery/{image_id}"),
+        (Method::PUT, "/v2/servers/{server_id}/custom-fields"),
+        (Method::POST, "/v2/servers/{server_id}/submit"),
+        (Method::POST, "/v2/servers/{server_id}/tags/suggest"),
+    ];
+
+    /// 创建密钥，返回完整密钥与密钥信息
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: i32,
+        name: &str,
+        scope: ApiKeyScope,
+    ) -> ApiResult<CreatedApiKey> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ApiError::BadRequest("密钥名称不能为空".to_string()));
+        }
+
+        let active = ApiKeys::find()
+            .filter(api_keys::Column::UserId.eq(user_id))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .count(db.as_ref())
+            .await?;
+        if active >= Self::MAX_KEYS_PER_USER {
+            return Err(ApiError::BadRequest(format!(
+                "最多只能创建 {} 个 API 密钥，请先吊销不用的密钥",
+                Self::MAX_KEYS_PER_USER
+            )));
+        }
+
+        let random: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(Self::KEY_RANDOM_LEN)
+            .map(char::from)
+            .collect();
+        let key = format!("{}{random}", Self::KEY_PREFIX);
+
+        let model = api_keys::ActiveModel {
+            user_id: Set(user_id),
+            name: Set(name.to_string()),
+            prefix: Set(key[..Self::DISPLAY_PREFIX_LEN].to_string()),
+            key_hash: Set(Self::hash_key(&key)),
+            scope: Set(scope.as_str().to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+
+        Ok(CreatedApiKey {
+            key,
+            api_key: Self::to_info(model),
+        })
+    }
+
+    /// 列出用户未吊销的密钥
+    pub async fn list(db: &DatabaseConnection, user_id: i32) -> ApiResult<Vec<ApiKeyInfo>> {
+        let keys = ApiKeys::find()
+            .filter(api_keys::Column::UserId.eq(user_id))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .order_by_desc(api_keys::Column::Id)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(keys.into_iter().map(Self::to_info).collect())
+    }
+
+    /// 用户吊销自己的密钥，立即失效
+    pub async fn revoke(db: &DatabaseConnection, user_id: i32, key_id: i32) -> ApiResult<()> {
+        let key = ApiKeys::find_by_id(key_id)
+            .filter(api_keys::Column::UserId.eq(user_id))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::NotFound("API 密钥不存在".to_string()))?;
+
+        let mut active: api_keys::ActiveModel = key.into();
+        active.revoked_at = Set(Some(Utc::now()));
+        active.update(db.as_ref()).await?;
+        Ok(())
+    }
+
+    /// 校验密钥，返回密钥所属用户的声明
+    ///
+    /// 声明中的租户取自用户所属租户，有效期只覆盖本次请求。
+    pub async fn authenticate(db: &DatabaseConnection, key: &str) -> ApiResult<Claims> {
+        let invalid = || ApiError::Unauthorized("API 密钥无效".to_string());
+        if !key.starts_with(Self::KEY_PREFIX) {
+            return Err(invalid());
+        }
+
+        let (api_key, user) = ApiKeys::find()
+            .filter(api_keys::Column::KeyHash.eq(Self::hash_key(key)))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .find_also_related(Users)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(invalid)?;
+        let user = user.ok_or_else(invalid)?;
+        if !user.is_active {
+            return Err(invalid());
+        }
+        let scope = ApiKeyScope::parse(&api_key.scope).ok_or_else(invalid)?;
+
+        let now = Utc::now();
+        if api_key
+            .last_used_at
+            .is_none_or(|at| now - at >= Duration::seconds(Self::TOUCH_INTERVAL_SECS))
+        {
+            let mut active: api_keys::ActiveModel = api_key.into();
+            active.last_used_at = Set(Some(now));
+            if let Err(e) = active.update(db.as_ref()).await {
+                tracing::warn!("⚠️  更新 API 密钥最近使用时间失败: {}", e);
+            }
+        }
+
+        Ok(Claims {
+            sub: user.username,
+            id: user.id,
+            exp: (now + Duration::minutes(5)).timestamp() as usize,
+            tenant: Some(user.tenant_id),
+            impersonator: None,
+            api_key_scope: Some(scope),
+            jti: None,
+        })
+    }
+
+    /// 该路径是否允许使用 API 密钥访问
+    pub fn accepts_path(path: &str) -> bool {
+        !Self::RESTRICTED_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    }
+
+    /// 密钥的权限范围是否允许该请求
+    ///
+    /// 只读密钥只能使用安全方法；管理服务器的密钥还可以调用 `MANAGE_SERVER_ROUTES`
+    /// 中的写接口，是否有权管理具体的服务器仍由接口按用户身份判断。
+    pub fn permits(scope: ApiKeyScope, method: &Method, path: &str) -> bool {
+        if !Self::accepts_path(path) {
+            return false;
+        }
+        if method.is_safe() {
+            return true;
+        }
+        match scope {
+            ApiKeyScope::ReadOnly => false,
+            ApiKeyScope::ManageServer => {
+                Self::MANAGE_SERVER_ROUTES
+                    .iter()
+                    .any(|(allowed, template)| {
+                        allowed == method && Self::matches_route(template, path)
+                    })
+            }
+        }
+    }
+
+    /// 路径是否匹配路由模板，`{…}` 匹配任意一段
+    fn matches_route(template: &str, path: &str) -> bool {
+        let path = path.strip_suffix('/').unwrap_or(path);
+        let mut segments = path.split('/');
+        template.split('/').all(|expected| {
+            segments.next().is_some_and(|segment| {
+                if expected.starts_with('{') {
+                    !segment.is_empty()
+                } else {
+                    segment == expected
+                }
+            })
+        }) && segments.next().is_none()
+    }
+
+    fn hash_key(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+
+    fn to_info(key: api_keys::Model) -> ApiKeyInfo {
+        ApiKeyInfo {
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            scope: ApiKeyScope::parse(&key.scope).unwrap_or(ApiKeyScope::ReadOnly),
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use sea_orm::*;
+
+use crate::{
+    config::{ArchiveConfig, S3Config},
+    entities::{
+        files, gallery_image,
+        prelude::{Files, GalleryImage, Server},
+        server,
+    },
+    errors::ApiResult,
+    services::{
+        database::DatabaseConnection,
+        file_upload::{FileUploadService, ImageVariant},
+        metrics::MetricsService,
+        storage::{S3Storage, StorageBackend},
+    },
+};
+
+/// 恢复时使用的存储类型
+const HOT_STORAGE_CLASS: &str = "STANDARD";
+
+/// 相册归档服务
+///
+/// 停用超过 `after_days` 天的服务器，其相册图片会被移动到归档前缀并降低存储类型；
+/// 服务器恢复（`deactivated_at` 置空）后，下一轮任务会把图片移回原位置。
+/// 被正常服务器引用的同一文件不会被归档。
+pub struct GalleryArchiveService;
+
+impl GalleryArchiveService {
+    /// 定期执行归档与恢复
+    pub async fn run_archive_loop(
+        db: DatabaseConnection,
+        s3_config: S3Config,
+        config: ArchiveConfig,
+        interval: std::time::Duration,
+    ) {
+        if !config.enabled {
+            return;
+        }
+
+        let storage = S3Storage::new(s3_config);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match Self::run_once(&db, &storage, &config).await {
+                Ok((archived, restored)) if archived > 0 || restored > 0 => {
+                    tracing::info!(
+                        "相册归档完成: 归档 {} 个文件, 恢复 {} 个文件",
+                        archived,
+                        restored
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("⚠️  相册归档任务失败: {}", e),
+            }
+        }
+    }
+
+    /// 执行一轮归档与恢复，返回 (归档数量, 恢复数量)
+    pub async fn run_once(
+        db: &DatabaseConnection,
+        storage: &S3Storage,
+        config: &ArchiveConfig,
+    ) -> ApiResult<(usize, usize)> {
+        let hot_hashes = Self::active_file_hashes(db).await?;
+        let archive_base = storage.file_path(&format!("{}/", config.prefix));
+
+        // 恢复：已归档但又被正常服务器引用的文件
+        let archived_files = Files::find()
+            .filter(files::Column::FilePath.starts_with(&archive_base))
+            .all(db.as_ref())
+            .await?;
+        let mut restored = 0;
+        for file in archived_files {
+            if !hot_hashes.contains(&file.hash_value) {
+                continue;
+            }
+            match Self::restore_file(db, storage, config, file).await {
+                Ok(()) => restored += 1,
+                Err(e) => tracing::warn!("⚠️  恢复归档文件失败: {}", e),
+            }
+        }
+
+        // 归档：停用时间超过阈值的服务器相册
+        let cutoff = Utc::now() - Duration::days(config.after_days);
+        let gallery_ids: Vec<i32> = Server::find()
+            .select_only()
+            .column(server::Column::GalleryId)
+            .filter(server::Column::DeactivatedAt.lt(cutoff))
+            .filter(server::Column::GalleryId.is_not_null())
+            .into_tuple::<Option<i32>>()
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        if gallery_ids.is_empty() {
+            return Ok((0, restored));
+        }
+
+        let candidates = Files::find()
+            .inner_join(GalleryImage)
+            .filter(gallery_image::Column::GalleryId.is_in(gallery_ids))
+            .filter(files::Column::FilePath.not_like(format!("{archive_base}%")))
+            .distinct()
+            .all(db.as_ref())
+            .await?;
+        let mut archived = 0;
+        for file in candidates {
+            if hot_hashes.contains(&file.hash_value) {
+                continue;
+            }
+            match Self::archive_file(db, storage, config, file).await {
+                Ok(true) => archived += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("⚠️  归档文件失败: {}", e),
+            }
+        }
+
+        Ok((archived, restored))
+    }
+
+    /// 正常服务器引用的文件（相册图片、封面与图标）
+    async fn active_file_hashes(db: &DatabaseConnection) -> ApiResult<HashSet<String>> {
+        let active: Vec<(Option<i32>, Option<String>, Option<String>)> = Server::find()
+            .select_only()
+            .column(server::Column::GalleryId)
+            .column(server::Column::CoverHashId)
+            .column(server::Column::IconHashId)
+            .filter(server::Column::DeactivatedAt.is_null())
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let mut gallery_ids = Vec::new();
+        let mut hashes = HashSet::new();
+        for (gallery_id, cover_hash, icon_hash) in active {
+            gallery_ids.extend(gallery_id);
+            hashes.extend(cover_hash);
+            hashes.extend(icon_hash);
+        }
+
+        if !gallery_ids.is_empty() {
+            let image_hashes: Vec<String> = GalleryImage::find()
+                .select_only()
+                .column(gallery_image::Column::ImageHashId)
+                .filter(gallery_image::Column::GalleryId.is_in(gallery_ids))
+                .into_tuple()
+                .all(db.as_ref())
+                .await?;
+            hashes.extend(image_hashes);
+        }
+
+        Ok(hashes)
+    }
+
+    /// 复制到归档前缀、更新文件地址后删除原对象；非本存储桶的文件跳过
+    async fn archive_file(
+        db: &DatabaseConnection,
+        storage: &S3Storage,
+        config: &ArchiveConfig,
+        file: files::Model,
+    ) -> ApiResult<bool> {
+        let s3_config = storage.config();
+        let Some(key) = storage.object_key(&file.file_path) else {
+            return Ok(false);
+        };
+        let key = key.to_string();
+        let archive_key = format!("{}/{}", config.prefix, key);
+
+        FileUploadService::copy_object(s3_config, &key, &archive_key, Some(&config.storage_class))
+            .await?;
+        let has_variants = file.has_variants;
+        if has_variants {
+            for variant in ImageVariant::ALL {
+                FileUploadService::copy_object(
+                    s3_config,
+                    &variant.path_of(&key),
+                    &variant.path_of(&archive_key),
+                    Some(&config.storage_class),
+                )
+                .await?;
+            }
+        }
+        Self::update_file_path(db, storage, file, &archive_key).await?;
+        if let Err(e) = storage.delete(&key).await {
+            tracing::warn!("⚠️  归档后删除原文件 {} 失败: {}", key, e);
+        }
+        if has_variants {
+            FileUploadService::delete_variants(storage, &key).await;
+        }
+
+        MetricsService::inc_counter(
+            "gallery_archive_objects_total",
+            "相册归档任务移动的文件数",
+            &[("action", "archive")],
+            1.0,
+        );
+        Ok(true)
+    }
+
+    /// 从归档前缀移回原位置
+    async fn restore_file(
+        db: &DatabaseConnection,
+        storage: &S3Storage,
+        config: &ArchiveConfig,
+        file: files::Model,
+    ) -> ApiResult<()> {
+        let s3_config = storage.config();
+        let Some(archive_key) = storage.object_key(&file.file_path) else {
+            return Ok(());
+        };
+        let archive_key = archive_key.to_string();
+        let Some(key) = archive_key
+            .strip_prefix(&format!("{}/", config.prefix))
+            .map(str::to_string)
+        else {
+            return Ok(());
+        };
+
+        FileUploadService::copy_object(s3_config, &archive_key, &key, Some(HOT_STORAGE_CLASS))
+            .await?;
+        let has_variants = file.has_variants;
+        if has_variants {
+            for variant in ImageVariant::ALL {
+                FileUploadService::copy_object(
+                    s3_config,
+                    &variant.path_of(&archive_key),
+                    &variant.path_of(&key),
+                    Some(HOT_STORAGE_CLASS),
+                )
+                .await?;
+            }
+        }
+        Self::update_file_path(db, storage, file, &key).await?;
+        if let Err(e) = storage.delete(&archive_key).await {
+            tracing::warn!("⚠️  恢复后删除归档文件 {} 失败: {}", archive_key, e);
+        }
+        if has_variants {
+            FileUploadService::delete_variants(storage, &archive_key).await;
+        }
+
+        MetricsService::inc_counter(
+            "gallery_archive_objects_total",
+            "相册归档任务移动的文件数",
+            &[("action", "restore")],
+            1.0,
+        );
+        Ok(())
+    }
+
+    async fn update_file_path(
+        db: &DatabaseConnection,
+        storage: &S3Storage,
+        file: files::Model,
+        key: &str,
+    ) -> ApiResult<()> {
+        let mut active: files::ActiveModel = file.into();
+        active.file_path = Set(storage.file_path(key));
+        active.update(db.as_ref()).await?;
+        Ok(())
+    }
+}
@@ -1,25 +1,32 @@
-use crate::config::Config;
+use crate::config::{Config, EmailConfig};
 use crate::entities::users;
-use crate::services::email::sender::{build_email_message, build_smtp_transport};
-use crate::services::email::template::build_email_template;
+use crate::errors::{ApiError, ApiResult, ErrorCode, RateLimitNotice};
+use crate::schemas::users::{ApiKeyScope, SessionInfo};
+use crate::services::api_key::ApiKeyService;
+use crate::services::email::sender::{build_message_with_subject, build_smtp_transport};
+use crate::services::email::template::{build_email_changed_template, build_email_template};
 use crate::services::redis::RedisService;
-use crate::services::utils::generate_verification_code;
+use crate::services::utils::{client_ip, generate_verification_code};
 use anyhow::{Context, Result};
 use askama::Template;
-use chrono::{Duration, Utc};
+use axum::http::{header::USER_AGENT, HeaderMap};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use lettre::Transport;
 
-use sea_orm::{ActiveModelTrait, DatabaseConnection};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tracing::error;
 use utoipa::{
-    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    openapi::security::{
+        ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+    },
     Modify,
 };
+use uuid::Uuid;
 
 /// JWT令牌声明结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +37,23 @@ pub struct Claims {
     pub id: i32,
     /// 过期时间戳
     pub exp: usize,
+    /// 签发令牌的租户，未启用多租户前签发的令牌没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// 代入该用户身份的管理员 ID，普通令牌没有该字段；客户端应据此显示代入提示横幅
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<i32>,
+    /// 通过 API 密钥认证时密钥的权限范围，JWT 没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_scope: Option<ApiKeyScope>,
+    /// 令牌 ID，即会话 ID；会话管理上线前签发的令牌与 API 密钥没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+tokio::task_local! {
+    /// 当前请求由哪位管理员代入发起
+    static IMPERSONATOR: i32;
 }
 
 /// JWT数据传输对象
@@ -37,6 +61,7 @@ pub struct Claims {
 pub struct JwtData {
     pub user_id: i32,
     pub username: String,
+    pub tenant_id: String,
 }
 
 impl Claims {
@@ -48,8 +73,17 @@ impl Claims {
             sub: username,
             id: user_id,
             exp,
+            tenant: None,
+            impersonator: None,
+            api_key_scope: None,
+            jti: None,
         }
     }
+
+    /// 是否为管理员代入签发的令牌
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator.is_some()
+    }
 }
 
 /// OpenAPI安全配置插件
@@ -67,37 +101,200 @@ impl Modify for SecurityAddon {
                     .build(),
             ),
         );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+        components.add_security_scheme(
+            "internal_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Internal-Token"))),
+        );
+
+        // 接受登录令牌的接口同样接受 API 密钥，管理后台与密钥管理接口除外
+        let declares = |security: &[SecurityRequirement], scheme: &str| {
+            serde_json::to_value(security)
+                .ok()
+                .and_then(|value| value.as_array().cloned())
+                .unwrap_or_default()
+                .iter()
+                .any(|requirement| requirement.get(scheme).is_some())
+        };
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            if !ApiKeyService::accepts_path(path) {
+                continue;
+            }
+            for operation in [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.patch,
+                &mut item.delete,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                let Some(security) = operation.security.as_mut() else {
+                    continue;
+                };
+                if declares(security, "bearer_auth") && !declares(security, "api_key") {
+                    security.push(SecurityRequirement::new("api_key", Vec::<String>::new()));
+                }
+            }
+        }
+    }
+}
+
+/// 签发令牌时的客户端信息，记录在会话中供用户辨认自己的登录
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl SessionClient {
+    /// User-Agent 的最大保存长度
+    const USER_AGENT_MAX_LEN: usize = 255;
+
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            ip: client_ip(headers),
+            user_agent: headers
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.chars().take(Self::USER_AGENT_MAX_LEN).collect())
+                .filter(|value: &String| !value.is_empty()),
+        }
     }
 }
 
+/// 验证码校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeCheck {
+    /// 验证码正确，已被消耗
+    Valid,
+    /// 验证码错误、已过期或不存在
+    Invalid,
+    /// 本次输入错误且累计错误次数达到上限，验证码已作废
+    Exhausted,
+}
+
 /// 认证服务
 pub struct AuthService;
 
 impl AuthService {
     /// Redis黑名单键前缀
     const BLACKLIST_PREFIX: &'static str = "token:blacklist";
+    /// 用户已签发令牌集合键前缀，成员为会话 ID，分数为过期时间戳
+    ///
+    /// 会话管理上线前签发的令牌没有 ID，成员为令牌哈希，吊销时写入的黑名单键与令牌哈希一致。
+    const ISSUED_PREFIX: &'static str = "token:issued";
+    /// 会话信息哈希键前缀，与令牌同时过期
+    const SESSION_PREFIX: &'static str = "token:session";
+    /// 访问令牌有效期（秒），也是已签发令牌集合的保留时间
+    const ACCESS_TOKEN_TTL_SECS: i64 = 30 * 86400;
     /// 默认令牌过期时间（秒）
     const DEFAULT_TTL: u64 = 86400; // 24小时
-
-    /// 创建访问令牌
+    /// 验证码有效期（秒）
+    const CODE_TTL_SECS: u64 = 300;
+    /// 同一邮箱同一用途两次发送验证码的最短间隔（秒）
+    const CODE_RESEND_COOLDOWN_SECS: u64 = 60;
+    /// 验证码允许输错的次数，达到后作废
+    const CODE_MAX_ATTEMPTS: i64 = 5;
+
+    /// 创建访问令牌，并记为用户的一个会话以便查看与吊销
     ///
     /// # 参数
     /// * `data` - JWT数据
+    /// * `client` - 发起登录的客户端
     /// * `config` - 应用配置
-    pub fn create_access_token(data: &JwtData, config: &Config) -> Result<String> {
-        let exp = (Utc::now() + Duration::days(30)).timestamp() as usize;
+    pub async fn create_access_token(
+        data: &JwtData,
+        client: &SessionClient,
+        config: &Config,
+    ) -> Result<String> {
+        let exp = (Utc::now() + Duration::seconds(Self::ACCESS_TOKEN_TTL_SECS)).timestamp();
+        let jti = Uuid::new_v4().simple().to_string();
         let claims = Claims {
             sub: data.username.clone(),
             id: data.user_id,
-            exp,
+            exp: exp as usize,
+            tenant: Some(data.tenant_id.clone()),
+            impersonator: None,
+            api_key_scope: None,
+            jti: Some(jti.clone()),
         };
 
-        encode(
+        let token = encode(
             &Header::default(),
             &claims,
             &EncodingKey::from_secret(config.jwt.secret.as_ref()),
-        )
-        .map_err(Into::into)
+        )?;
+        Self::track_session(data.user_id, &jti, exp, client, false).await?;
+        Ok(token)
+    }
+
+    /// 查找可代入的用户
+    ///
+    /// 只能代入当前租户内已启用的普通用户，其他租户的用户视为不存在。
+    pub async fn impersonation_target(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+    ) -> ApiResult<users::Model> {
+        let target = users::Entity::find_by_id(user_id)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db)
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+        if target.role != users::RoleEnum::User {
+            return Err(ApiError::Forbidden("不能代入管理人员的身份".to_string()));
+        }
+        if !target.is_active {
+            return Err(ApiError::BadRequest("目标账户已停用".to_string()));
+        }
+        Ok(target)
+    }
+
+    /// 为管理员代入用户身份签发短期令牌，返回令牌与有效期（秒）
+    pub async fn create_impersonation_token(
+        data: &JwtData,
+        impersonator_id: i32,
+        config: &Config,
+    ) -> Result<(String, u64)> {
+        let ttl = config.jwt.impersonation_ttl_secs.clamp(60, 3600);
+        let exp = (Utc::now() + Duration::seconds(ttl as i64)).timestamp();
+        let jti = Uuid::new_v4().simple().to_string();
+        let claims = Claims {
+            sub: data.username.clone(),
+            id: data.user_id,
+            exp: exp as usize,
+            tenant: Some(data.tenant_id.clone()),
+            impersonator: Some(impersonator_id),
+            api_key_scope: None,
+            jti: Some(jti.clone()),
+        };
+
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(config.jwt.secret.as_ref()),
+        )?;
+        // 不记录管理员的 IP 与 User-Agent，用户只能看到这是一个代入会话
+        Self::track_session(data.user_id, &jti, exp, &SessionClient::default(), true).await?;
+        Ok((token, ttl))
+    }
+
+    /// 在代入上下文中执行请求，期间写入的操作记录都会带上管理员 ID
+    pub async fn with_impersonator<F: std::future::Future>(
+        impersonator_id: i32,
+        future: F,
+    ) -> F::Output {
+        IMPERSONATOR.scope(impersonator_id, future).await
+    }
+
+    /// 当前请求的代入管理员 ID
+    pub fn current_impersonator() -> Option<i32> {
+        IMPERSONATOR.try_with(|id| *id).ok()
     }
 
     /// 验证令牌有效性
@@ -105,7 +302,7 @@ impl AuthService {
     /// # 参数
     /// * `token` - 待验证的JWT令牌
     /// * `config` - 应用配置
-    pub async fn verify_token(token: &str, config: &Config) -> Result<Claims, String> {
+    pub async fn verify_token(token: &str, config: &Config) -> ApiResult<Claims> {
         // 解码令牌
         let claims = Self::decode_token(token, config)?;
 
@@ -113,7 +310,7 @@ impl AuthService {
         Self::check_token_expiry(&claims)?;
 
         // 检查黑名单
-        Self::check_blacklist(token).await?;
+        Self::check_blacklist(token, claims.jti.as_deref()).await?;
 
         Ok(claims)
     }
@@ -130,6 +327,106 @@ impl AuthService {
         })
     }
 
+    /// 吊销用户所有未过期的令牌，返回吊销的数量
+    ///
+    /// 只能覆盖记入已签发令牌集合的令牌，集合上线前签发的令牌不受影响。
+    pub async fn revoke_user_tokens(user_id: i32) -> Result<usize> {
+        Self::revoke_sessions_except(user_id, None).await
+    }
+
+    /// 吊销用户除 `keep` 以外的所有会话，返回吊销的数量
+    pub async fn revoke_sessions_except(user_id: i32, keep: Option<&str>) -> Result<usize> {
+        let redis = Self::get_redis_service()?;
+        let now = Utc::now().timestamp();
+
+        let sessions = redis
+            .zrange_from_score(&Self::build_issued_key(user_id), now + 1)
+            .await?;
+        let mut revoked = 0;
+        for (session_id, exp) in &sessions {
+            if Some(session_id.as_str()) == keep {
+                continue;
+            }
+            Self::revoke_tracked(&redis, user_id, session_id, *exp - now).await?;
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    /// 吊销用户的一个会话，会话不存在或已过期时返回 `false`
+    pub async fn revoke_session(user_id: i32, session_id: &str) -> Result<bool> {
+        let redis = Self::get_redis_service()?;
+        let now = Utc::now().timestamp();
+
+        let exp = redis
+            .zrange_from_score(&Self::build_issued_key(user_id), now + 1)
+            .await?
+            .into_iter()
+            .find_map(|(id, exp)| (id == session_id).then_some(exp));
+        let Some(exp) = exp else {
+            return Ok(false);
+        };
+        Self::revoke_tracked(&redis, user_id, session_id, exp - now).await?;
+        Ok(true)
+    }
+
+    /// 列出用户未过期的会话；`current` 为发起请求的会话 ID
+    pub async fn list_sessions(user_id: i32, current: Option<&str>) -> Result<Vec<SessionInfo>> {
+        let redis = Self::get_redis_service()?;
+        let now = Utc::now().timestamp();
+
+        let tracked = redis
+            .zrange_from_score(&Self::build_issued_key(user_id), now + 1)
+            .await?;
+        let mut sessions = Vec::with_capacity(tracked.len());
+        for (session_id, exp) in tracked {
+            let fields = redis.hgetall(&Self::build_session_key(&session_id)).await?;
+            let time = |field: &str| {
+                fields
+                    .get(field)
+                    .and_then(|value| value.parse().ok())
+                    .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            };
+            sessions.push(SessionInfo {
+                current: Some(session_id.as_str()) == current,
+                impersonated: fields.get("impersonated").is_some_and(|value| value == "1"),
+                ip: fields.get("ip").cloned(),
+                user_agent: fields.get("user_agent").cloned(),
+                created_at: time("created_at"),
+                last_seen_at: time("last_seen_at"),
+                expires_at: DateTime::from_timestamp(exp, 0).unwrap_or_default(),
+                id: session_id,
+            });
+        }
+        sessions.sort_by(|a, b| {
+            b.current
+                .cmp(&a.current)
+                .then_with(|| b.last_seen_at.cmp(&a.last_seen_at))
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
+        Ok(sessions)
+    }
+
+    /// 记录会话的最近活动时间与 IP，代入会话不记录
+    ///
+    /// 每个带令牌的请求都会调用，只写一次 Redis，由调用方放到后台执行。
+    pub async fn touch_session(claims: &Claims, ip: Option<String>) -> Result<()> {
+        let Some(jti) = claims.jti.as_deref() else {
+            return Ok(());
+        };
+        if claims.is_impersonated() {
+            return Ok(());
+        }
+        let redis = Self::get_redis_service()?;
+
+        let now = Utc::now().timestamp();
+        let mut fields = vec![("last_seen_at", (now - now % 60).to_string())];
+        fields.extend(ip.map(|ip| ("ip", ip)));
+        redis
+            .hset_expire_at(&Self::build_session_key(jti), &fields, claims.exp as i64)
+            .await
+    }
+
     /// 检查令牌是否在黑名单中
     pub async fn is_token_blacklisted(token: &str) -> Result<bool> {
         let redis = Self::get_redis_service()?;
@@ -174,9 +471,105 @@ impl AuthService {
         })
     }
 
+    /// 占用注册验证码的发送冷却，冷却中返回 429 与剩余秒数
+    pub async fn acquire_email_code_cooldown(email: &str) -> ApiResult<()> {
+        Self::acquire_cooldown(&format!("email_code:{email}")).await
+    }
+
+    /// 占用密码重置验证码的发送冷却，冷却中返回 429 与剩余秒数
+    pub async fn acquire_password_reset_cooldown(email: &str) -> ApiResult<()> {
+        Self::acquire_cooldown(&format!("password_reset_code:{email}")).await
+    }
+
+    /// 占用修改邮箱验证码的发送冷却，按用户计算，换一个新邮箱也不能绕过
+    pub async fn acquire_email_change_cooldown(user_id: i32) -> ApiResult<()> {
+        Self::acquire_cooldown(&format!("email_change_code:{user_id}")).await
+    }
+
+    async fn acquire_cooldown(code_key: &str) -> ApiResult<()> {
+        let redis = Self::get_redis_service().map_err(Self::code_service_unavailable)?;
+        let key = format!("{code_key}:cooldown");
+        let acquired = redis
+            .set_nx_ex(&key, "1", Self::CODE_RESEND_COOLDOWN_SECS)
+            .await
+            .map_err(Self::code_service_unavailable)?;
+        if acquired {
+            return Ok(());
+        }
+
+        let ttl = redis.ttl(&key).await.unwrap_or_default();
+        let retry_after = u64::try_from(ttl)
+            .unwrap_or(Self::CODE_RESEND_COOLDOWN_SECS)
+            .max(1);
+        Err(ApiError::RateLimited(RateLimitNotice { retry_after }))
+    }
+
+    /// 验证码依赖的 Redis 不可用，错误详情只写日志
+    fn code_service_unavailable(e: anyhow::Error) -> ApiError {
+        error!("验证码服务不可用: {}", e);
+        ApiError::ServiceUnavailable("验证码服务不可用".to_string())
+    }
+
+    /// 是否为站点管理员（admin 角色且账户已启用），可以管理站点内的任意服务器
+    pub fn is_site_admin(user: &users::Model) -> bool {
+        user.is_active && user.role == users::RoleEnum::Admin
+    }
+
     /// 发送邮件验证码
-    pub async fn send_email_code(email: &str, config: &Config) -> Result<()> {
+    pub async fn send_email_code(email: &str, config: &EmailConfig) -> Result<()> {
+        Self::send_code(email, config, "邮箱验证码", &format!("email_code:{email}")).await
+    }
+
+    /// 发送密码重置验证码，与注册验证码分开存储，互不覆盖
+    pub async fn send_password_reset_code(email: &str, config: &EmailConfig) -> Result<()> {
+        Self::send_code(
+            email,
+            config,
+            "密码重置验证码",
+            &format!("password_reset_code:{email}"),
+        )
+        .await
+    }
+
+    /// 发送修改邮箱验证码到新邮箱，验证码与用户及新邮箱绑定
+    pub async fn send_email_change_code(
+        user_id: i32,
+        new_email: &str,
+        config: &EmailConfig,
+    ) -> Result<()> {
+        Self::send_code(
+            new_email,
+            config,
+            "邮箱变更验证码",
+            &format!("email_change_code:{user_id}:{new_email}"),
+        )
+        .await
+    }
 
+    /// 通知旧邮箱账户邮箱已被修改
+    pub async fn send_email_changed_notice(
+        old_email: &str,
+        username: &str,
+        new_email: &str,
+        config: &EmailConfig,
+    ) -> Result<()> {
+        let body = build_email_changed_template(username, new_email, Utc::now())
+            .render()
+            .context("渲染邮件模板失败")?;
+        let message =
+            build_message_with_subject(&config.smtp_username, old_email, "账户邮箱已修改", body)
+                .context("构建邮件消息失败")?;
+        let smtp_transport = build_smtp_transport(config)?;
+
+        tokio::spawn(async move {
+            if let Err(e) = smtp_transport.send(&message) {
+                tracing::error!("发送邮件失败: {:?}", e);
+            }
+        });
+        Ok(())
+    }
+
+    async fn send_code(email: &str, config: &EmailConfig, subject: &str, key: &str) -> Result<()> {
         let code = generate_verification_code();
         let template = build_email_template(&code)
             .await
@@ -185,7 +578,7 @@ impl AuthService {
         let redis = Self::get_redis_service()?;
 
         let email_body = template.render().context("渲染邮件模板失败")?;
-        let message = build_email_message(&config.email.smtp_username, email, email_body)
+        let message = build_message_with_subject(&config.smtp_username, email, subject, email_body)
             .context("构建邮件消息失败")?;
 
         let smtp_transport = build_smtp_transport(config)?;
@@ -196,60 +589,78 @@ impl AuthService {
             }
         });
 
-        Self::store_verification_code(&redis, email, &code)
+        Self::store_verification_code(&redis, key, &code)
             .await
             .context("存储验证码到Redis失败")?;
 
         Ok(())
     }
 
-    /// 存储验证码到Redis
-    async fn store_verification_code(redis: &RedisService, email: &str, code: &str) -> Result<()> {
-        let key = format!("email_code:{email}");
+    /// 存储验证码到Redis，新验证码的错误次数重新计算
+    async fn store_verification_code(redis: &RedisService, key: &str, code: &str) -> Result<()> {
         redis
-            .set_ex(&key, code, 300)
+            .set_ex(key, code, Self::CODE_TTL_SECS)
             .await
-            .context("存储验证码到Redis失败")
+            .context("存储验证码到Redis失败")?;
+        redis
+            .del(&format!("{key}:attempts"))
+            .await
+            .context("重置验证码错误次数失败")
     }
 
     pub async fn verify_email_code(email: &str, input_code: &str) -> Result<bool> {
-        let redis = Self::get_redis_service()?;
-        let key = format!("email_code:{email}");
-
-        match redis.get(&key).await {
-            Ok(stored_code) => {
-                let is_valid = stored_code.as_deref() == Some(input_code);
-                if is_valid {
-                    // 验证成功后删除验证码
-                    let _ = redis.del(&key).await;
-                }
-                Ok(is_valid)
-            }
-            Err(_) => Ok(false), // 验证码不存在或已过期
-        }
+        Ok(Self::validate_email_code(email, input_code).await? == CodeCheck::Valid)
     }
 
     /// 验证码校验
-    pub async fn validate_email_code(email: &str, code: &str) -> Result<bool> {
+    pub async fn validate_email_code(email: &str, code: &str) -> Result<CodeCheck> {
+        Self::consume_code(&format!("email_code:{email}"), code).await
+    }
+
+    /// 密码重置验证码校验
+    pub async fn validate_password_reset_code(email: &str, code: &str) -> Result<CodeCheck> {
+        Self::consume_code(&format!("password_reset_code:{email}"), code).await
+    }
+
+    /// 修改邮箱验证码校验
+    pub async fn validate_email_change_code(
+        user_id: i32,
+        new_email: &str,
+        code: &str,
+    ) -> Result<CodeCheck> {
+        Self::consume_code(&format!("email_change_code:{user_id}:{new_email}"), code).await
+    }
+
+    /// 校验并消耗验证码，输错时累计次数，达到上限后作废验证码
+    async fn consume_code(key: &str, code: &str) -> Result<CodeCheck> {
         let redis = Self::get_redis_service()?;
-        let key = format!("email_code:{email}");
-
-        match redis.get(&key).await {
-            Ok(stored_code) => {
-                if let Some(stored_code) = stored_code {
-                    if stored_code == code {
-                        // 验证成功后删除验证码
-                        let _ = redis.del(&key).await;
-                        return Ok(true);
-                    }
-                }
-                Ok(false)
-            }
-            Err(e) => {
-                error!("获取验证码失败: {}", e);
-                Err(anyhow::anyhow!("获取验证码失败"))
-            }
+        let attempts_key = format!("{key}:attempts");
+
+        let stored_code = redis.get(key).await.map_err(|e| {
+            error!("获取验证码失败: {}", e);
+            anyhow::anyhow!("获取验证码失败")
+        })?;
+        let Some(stored_code) = stored_code else {
+            return Ok(CodeCheck::Invalid);
+        };
+        if stored_code == code {
+            // 验证成功后删除验证码
+            let _ = redis.batch_del(&[key.to_string(), attempts_key]).await;
+            return Ok(CodeCheck::Valid);
         }
+
+        let attempts = redis
+            .incr_ex(&attempts_key, Self::CODE_TTL_SECS)
+            .await
+            .context("记录验证码错误次数失败")?;
+        if attempts < Self::CODE_MAX_ATTEMPTS {
+            return Ok(CodeCheck::Invalid);
+        }
+        redis
+            .batch_del(&[key.to_string(), attempts_key])
+            .await
+            .context("作废验证码失败")?;
+        Ok(CodeCheck::Exhausted)
     }
 
     // ========== 私有辅助方法 ==========
@@ -260,7 +671,7 @@ impl AuthService {
     }
 
     /// 解码JWT令牌
-    fn decode_token(token: &str, config: &Config) -> Result<Claims, String> {
+    fn decode_token(token: &str, config: &Config) -> ApiResult<Claims> {
         let mut validation = Validation::new(Algorithm::HS256);
         validation.validate_exp = false; // 手动处理过期验证
 
@@ -270,35 +681,120 @@ impl AuthService {
             &validation,
         )
         .map(|data| data.claims)
-        .map_err(|err| match err.kind() {
-            jsonwebtoken::errors::ErrorKind::InvalidToken => "无效令牌".to_string(),
-            jsonwebtoken::errors::ErrorKind::InvalidSignature => "令牌签名无效".to_string(),
-            _ => "令牌验证失败".to_string(),
+        .map_err(|err| {
+            let message = match err.kind() {
+                jsonwebtoken::errors::ErrorKind::InvalidToken => "无效令牌",
+                jsonwebtoken::errors::ErrorKind::InvalidSignature => "令牌签名无效",
+                _ => "令牌验证失败",
+            };
+            ApiError::Unauthorized(message.to_string()).with_code(ErrorCode::TokenInvalid)
         })
     }
 
     /// 检查令牌是否过期
-    fn check_token_expiry(claims: &Claims) -> Result<(), String> {
+    fn check_token_expiry(claims: &Claims) -> ApiResult<()> {
         let now = Utc::now().timestamp() as usize;
         if claims.exp < now {
-            Err("令牌已过期".to_string())
+            Err(ApiError::Unauthorized("令牌已过期".to_string()).with_code(ErrorCode::TokenExpired))
         } else {
             Ok(())
         }
     }
 
-    /// 检查令牌黑名单状态
-    async fn check_blacklist(token: &str) -> Result<(), String> {
-        match Self::is_token_blacklisted(token).await {
-            Ok(true) => Err("令牌已被吊销".to_string()),
+    /// 检查令牌黑名单状态，令牌本身或所属会话被吊销都视为已吊销
+    async fn check_blacklist(token: &str, jti: Option<&str>) -> ApiResult<()> {
+        let mut keys = vec![Self::build_blacklist_key(token)];
+        keys.extend(jti.map(|jti| format!("{}:{}", Self::BLACKLIST_PREFIX, jti)));
+        let revoked = match Self::get_redis_service() {
+            Ok(redis) => redis
+                .batch_exists(&keys)
+                .await
+                .map(|hits| hits.into_iter().any(|hit| hit)),
+            Err(e) => Err(e),
+        };
+        match revoked {
+            Ok(true) => Err(ApiError::Unauthorized("令牌已被吊销".to_string())
+                .with_code(ErrorCode::TokenRevoked)),
             Ok(false) => Ok(()),
             Err(e) => {
                 error!("检查令牌黑名单失败: {}", e);
-                Err("服务暂时不可用".to_string())
+                Err(ApiError::Unauthorized("服务暂时不可用".to_string()))
             }
         }
     }
 
+    /// 记录签发给用户的会话，顺带清理集合中已过期的会话
+    async fn track_session(
+        user_id: i32,
+        jti: &str,
+        exp: i64,
+        client: &SessionClient,
+        impersonated: bool,
+    ) -> Result<()> {
+        let redis = Self::get_redis_service()?;
+        let now = Utc::now().timestamp();
+        redis
+            .zadd_prune_ex(
+                &Self::build_issued_key(user_id),
+                jti,
+                exp,
+                now,
+                Self::ACCESS_TOKEN_TTL_SECS as u64,
+            )
+            .await
+            .context("记录已签发令牌失败")?;
+
+        let mut fields = vec![
+            ("created_at", now.to_string()),
+            (
+                "impersonated",
+                if impersonated { "1" } else { "0" }.to_string(),
+            ),
+        ];
+        fields.extend(client.ip.clone().map(|ip| ("ip", ip)));
+        fields.extend(
+            client
+                .user_agent
+                .clone()
+                .map(|user_agent| ("user_agent", user_agent)),
+        );
+        redis
+            .hset_expire_at(&Self::build_session_key(jti), &fields, exp)
+            .await
+            .context("记录会话信息失败")
+    }
+
+    /// 吊销集合中的一个会话：加入黑名单直到令牌过期，并移除会话记录
+    async fn revoke_tracked(
+        redis: &RedisService,
+        user_id: i32,
+        session_id: &str,
+        ttl: i64,
+    ) -> Result<()> {
+        let blacklist_key = format!("{}:{}", Self::BLACKLIST_PREFIX, session_id);
+        redis
+            .set_ex(&blacklist_key, "1", ttl.max(1) as u64)
+            .await
+            .map_err(|e| {
+                error!("令牌黑名单操作失败: {}", e);
+                anyhow::anyhow!("令牌黑名单操作失败: {}", e)
+            })?;
+        redis
+            .zrem(&Self::build_issued_key(user_id), session_id)
+            .await?;
+        redis.del(&Self::build_session_key(session_id)).await
+    }
+
+    /// 构建会话信息Redis键
+    fn build_session_key(session_id: &str) -> String {
+        format!("{}:{}", Self::SESSION_PREFIX, session_id)
+    }
+
+    /// 构建已签发令牌集合Redis键
+    fn build_issued_key(user_id: i32) -> String {
+        format!("{}:{}", Self::ISSUED_PREFIX, user_id)
+    }
+
     /// 构建黑名单Redis键
     fn build_blacklist_key(token: &str) -> String {
         format!("{}:{}", Self::BLACKLIST_PREFIX, Self::hash_token(token))
@@ -0,0 +1,257 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        ban_records,
+        prelude::{BanRecords, Users},
+        users::{self, RoleEnum},
+    },
+    errors::{ApiError, ApiResult, BanNotice},
+    schemas::{
+        admin::{BanInfo, BanQuery, BanType, CreateBanRequest},
+        users::NotificationKind,
+    },
+    services::{
+        database::DatabaseConnection,
+        notification::{Notification, NotificationService},
+        redis::RedisService,
+    },
+};
+
+/// Redis 键前缀
+const CACHE_PREFIX: &str = "ban:active";
+/// 生效封禁的缓存时间，解封或新增封禁时会主动清除
+const CACHE_TTL_SECS: u64 = 60;
+
+/// 用户封禁与禁言
+///
+/// 封禁记录保存在 `ban_records`，`ended_at` 为空或晚于当前时间的记录视为生效。
+/// 鉴权中间件对每个带令牌的请求检查生效封禁，结果在 Redis 中缓存一分钟；
+/// 同时存在多条生效记录时以最严重的一条为准（封禁 > 临时封禁 > 禁言）。
+/// 新增封禁后会通知被封禁的用户，这类通知不能被用户关闭。
+pub struct BanService;
+
+impl BanService {
+    /// 封禁租户内的用户，替换该用户仍生效的封禁；不能封禁管理人员
+    pub async fn ban(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+        request: CreateBanRequest,
+    ) -> ApiResult<BanInfo> {
+        let user = Self::find_user(db, tenant_id, user_id).await?;
+        if user.role != RoleEnum::User {
+            return Err(ApiError::Forbidden("不能封禁管理人员".to_string()));
+        }
+
+        let now = Utc::now();
+        match (request.ban_type, request.ended_at) {
+            (BanType::TempBan, None) => {
+                return Err(ApiError::BadRequest("临时封禁需要指定解封时间".to_string()));
+            }
+            (BanType::Ban, Some(_)) => {
+                return Err(ApiError::BadRequest(
+                    "封禁不能指定解封时间，请使用临时封禁".to_string(),
+                ));
+            }
+            (_, Some(ended_at)) if ended_at <= now => {
+                return Err(ApiError::BadRequest("解封时间必须晚于当前时间".to_string()));
+            }
+            _ => {}
+        }
+
+        let reason = request
+            .reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+
+        let txn = db.begin().await?;
+        Self::end_active(&txn, user_id).await?;
+        let record = ban_records::ActiveModel {
+            user_id: Set(user_id),
+            ban_type: Set(request.ban_type.as_str().to_string()),
+            reason: Set(reason),
+            started_at: Set(now),
+            ended_at: Set(request.ended_at),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Self::invalidate(user_id).await;
+        NotificationService::notify(db, user_id, Self::ban_notice(request.ban_type, &record)).await;
+
+        Ok(Self::to_info(record, Some(user.username)))
+    }
+
+    /// 解除租户内用户所有生效的封禁，返回解除的条数
+    pub async fn lift(db: &DatabaseConnection, tenant_id: &str, user_id: i32) -> ApiResult<u64> {
+        Self::find_user(db, tenant_id, user_id).await?;
+        let lifted = Self::end_active(db.as_ref(), user_id).await?;
+        if lifted == 0 {
+            return Err(ApiError::NotFound("该用户没有生效的封禁".to_string()));
+        }
+        Self::invalidate(user_id).await;
+        Ok(lifted)
+    }
+
+    /// 其他租户的用户视为不存在
+    async fn find_user(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+    ) -> ApiResult<users::Model> {
+        Users::find_by_id(user_id)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)
+    }
+
+    fn ban_notice(ban_type: BanType, record: &ban_records::Model) -> Notification {
+        let title = match ban_type {
+            BanType::Mute => "您的账户已被禁言",
+            BanType::Ban => "您的账户已被封禁",
+            BanType::TempBan => "您的账户已被临时封禁",
+        };
+        let reason = record.reason.as_deref().unwrap_or("未说明");
+        let until = match record.ended_at {
+            Some(ended_at) => format!("将于 {} 自动解除", ended_at.format("%Y-%m-%d %H:%M UTC")),
+            None => "需由管理人员解除".to_string(),
+        };
+        Notification {
+            kind: NotificationKind::AccountBanned,
+            title: title.to_string(),
+            body: format!("原因：{reason}。本次处置{until}，如有异议请联系站点管理员。"),
+        }
+    }
+
+    /// 租户内用户的封禁记录
+    pub async fn list(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        query: &BanQuery,
+    ) -> ApiResult<(Vec<BanInfo>, u64)> {
+        let mut select = BanRecords::find()
+            .find_also_related(Users)
+            .filter(users::Column::TenantId.eq(tenant_id));
+        if query.active {
+            select = select.filter(Self::active_condition());
+        }
+        if let Some(user_id) = query.user_id {
+            select = select.filter(ban_records::Column::UserId.eq(user_id));
+        }
+        let paginator = select
+            .order_by_desc(ban_records::Column::Id)
+            .paginate(db.as_ref(), query.page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(query.page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(|(record, user)| Self::to_info(record, user.map(|user| user.username)))
+            .collect();
+
+        Ok((data, total))
+    }
+
+    /// 用户当前生效的封禁
+    ///
+    /// 优先读取 Redis 缓存；数据库查询失败时放行并记录警告，避免数据库抖动导致所有用户无法访问。
+    pub async fn active_ban(db: &DatabaseConnection, user_id: i32) -> Option<BanNotice> {
+        let redis = RedisService::instance();
+        let key = format!("{CACHE_PREFIX}:{user_id}");
+        if let Some(redis) = &redis {
+            if let Ok(Some(value)) = redis.get(&key).await {
+                if let Ok(cached) = serde_json::from_str::<Option<BanNotice>>(&value) {
+                    return cached;
+                }
+            }
+        }
+
+        let notice = match Self::load_active(db, user_id).await {
+            Ok(notice) => notice,
+            Err(e) => {
+                tracing::warn!("⚠️  查询用户 {} 的封禁状态失败: {}", user_id, e);
+                return None;
+            }
+        };
+        if let Some(redis) = &redis {
+            let value = serde_json::to_string(&notice).unwrap_or_else(|_| "null".to_string());
+            // 封禁快到期时缩短缓存时间，避免到期后仍被拦截
+            let ttl = notice
+                .as_ref()
+                .and_then(|notice| notice.expires_at)
+                .map(|expires_at| (expires_at - Utc::now()).num_seconds().max(1) as u64)
+                .map_or(CACHE_TTL_SECS, |secs| secs.min(CACHE_TTL_SECS));
+            if let Err(e) = redis.set_ex(&key, &value, ttl).await {
+                tracing::warn!("⚠️  缓存封禁状态失败: {}", e);
+            }
+        }
+        notice
+    }
+
+    async fn load_active(db: &DatabaseConnection, user_id: i32) -> ApiResult<Option<BanNotice>> {
+        let records = BanRecords::find()
+            .filter(ban_records::Column::UserId.eq(user_id))
+            .filter(Self::active_condition())
+            .all(db.as_ref())
+            .await?;
+
+        Ok(records
+            .into_iter()
+            .max_by_key(|record| match record.ban_type.as_str() {
+                "ban" => 2,
+                "temp_ban" => 1,
+                _ => 0,
+            })
+            .map(|record| BanNotice {
+                ban_type: record.ban_type,
+                reason: record.reason,
+                expires_at: record.ended_at,
+            }))
+    }
+
+    async fn end_active<C: ConnectionTrait>(db: &C, user_id: i32) -> ApiResult<u64> {
+        let result = BanRecords::update_many()
+            .col_expr(
+                ban_records::Column::EndedAt,
+                sea_query::Expr::value(Some(Utc::now())),
+            )
+            .filter(ban_records::Column::UserId.eq(user_id))
+            .filter(Self::active_condition())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    fn active_condition() -> Condition {
+        Condition::any()
+            .add(ban_records::Column::EndedAt.is_null())
+            .add(ban_records::Column::EndedAt.gt(Utc::now()))
+    }
+
+    async fn invalidate(user_id: i32) {
+        if let Some(redis) = RedisService::instance() {
+            if let Err(e) = redis.del(&format!("{CACHE_PREFIX}:{user_id}")).await {
+                tracing::warn!("⚠️  清除封禁状态缓存失败: {}", e);
+            }
+        }
+    }
+
+    fn to_info(record: ban_records::Model, username: Option<String>) -> BanInfo {
+        let active = record.ended_at.is_none_or(|ended_at| ended_at > Utc::now());
+        BanInfo {
+            id: record.id,
+            user_id: record.user_id,
+            username,
+            ban_type: record.ban_type,
+            reason: record.reason,
+            started_at: record.started_at,
+            ended_at: record.ended_at,
+            active,
+        }
+    }
+}
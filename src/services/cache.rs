@@ -0,0 +1,234 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    schemas::{
+        search::SuggestResponse,
+        servers::{ServerDetail, ServerTotalPlayers, TagListResponse},
+    },
+    services::{
+        events::DomainEvent, metrics::MetricsService, redis::RedisService, tenant::TenantService,
+    },
+};
+
+/// 服务器读缓存
+///
+/// 只缓存与调用方无关的公开数据（匿名详情、玩家总数、搜索建议、热门标签、MOTD 图片），过期时间较短；
+/// 数据变更后由 [`crate::services::live::LiveUpdateService`] 根据领域事件统一清除。
+/// 搜索建议按关键词缓存，无法按服务器清除，只依赖过期时间。
+pub struct ServerCacheService;
+
+impl ServerCacheService {
+    const DETAIL_PREFIX: &'static str = "cache:server:detail";
+    const PLAYERS_PREFIX: &'static str = "cache:server:players";
+    const SUGGEST_PREFIX: &'static str = "cache:search:suggest";
+    const TAGS_PREFIX: &'static str = "cache:tags";
+    const MOTD_IMAGE_PREFIX: &'static str = "cache:server:motd_image";
+    const CACHE_TTL_SECS: u64 = 60;
+    /// 输入联想的请求量大且允许短暂滞后，缓存时间更长
+    const SUGGEST_TTL_SECS: u64 = 300;
+    /// 标签统计需要扫描租户下全部服务器，变化也不频繁
+    const TAGS_TTL_SECS: u64 = 300;
+
+    pub async fn get_detail(server_id: i32) -> Option<ServerDetail> {
+        Self::get("detail", &format!("{}:{}", Self::DETAIL_PREFIX, server_id)).await
+    }
+
+    pub async fn set_detail(detail: &ServerDetail) {
+        Self::set(
+            &format!("{}:{}", Self::DETAIL_PREFIX, detail.id),
+            detail,
+            Self::CACHE_TTL_SECS,
+        )
+        .await;
+    }
+
+    /// `key` 由 [`crate::services::motd_image::MotdImageService::cache_key`] 生成，包含 MOTD 内容摘要，
+    /// MOTD 变化后不会命中旧图片，无需按事件清除
+    pub async fn get_motd_image(key: &str) -> Option<Vec<u8>> {
+        let redis = RedisService::instance()?;
+        let value = redis
+            .get_bytes(&format!("{}:{}", Self::MOTD_IMAGE_PREFIX, key))
+            .await
+            .ok()
+            .flatten();
+
+        MetricsService::inc_counter(
+            "server_cache_requests_total",
+            "服务器读缓存命中情况",
+            &[
+                ("cache", "motd_image"),
+                ("result", if value.is_some() { "hit" } else { "miss" }),
+            ],
+            1.0,
+        );
+        value
+    }
+
+    pub async fn set_motd_image(key: &str, png: &[u8], ttl_secs: u64) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        let key = format!("{}:{}", Self::MOTD_IMAGE_PREFIX, key);
+        if let Err(e) = redis.set_ex_bytes(&key, png, ttl_secs).await {
+            tracing::warn!("⚠️  写入服务器缓存 {} 失败: {}", key, e);
+        }
+    }
+
+    pub async fn get_players(tenant_id: &str) -> Option<ServerTotalPlayers> {
+        Self::get(
+            "players",
+            &format!("{}:{}", Self::PLAYERS_PREFIX, tenant_id),
+        )
+        .await
+    }
+
+    pub async fn set_players(tenant_id: &str, players: &ServerTotalPlayers) {
+        Self::set(
+            &format!("{}:{}", Self::PLAYERS_PREFIX, tenant_id),
+            players,
+            Self::CACHE_TTL_SECS,
+        )
+        .await;
+    }
+
+    /// `query` 应已规范化（去除首尾空白并转为小写）
+    pub async fn get_suggestions(
+        tenant_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Option<SuggestResponse> {
+        Self::get("suggest", &Self::suggest_key(tenant_id, query, limit)).await
+    }
+
+    pub async fn set_suggestions(
+        tenant_id: &str,
+        query: &str,
+        limit: usize,
+        suggestions: &SuggestResponse,
+    ) {
+        Self::set(
+            &Self::suggest_key(tenant_id, query, limit),
+            suggestions,
+            Self::SUGGEST_TTL_SECS,
+        )
+        .await;
+    }
+
+    fn suggest_key(tenant_id: &str, query: &str, limit: usize) -> String {
+        format!("{}:{}:{}:{}", Self::SUGGEST_PREFIX, tenant_id, limit, query)
+    }
+
+    pub async fn get_tags(tenant_id: &str) -> Option<TagListResponse> {
+        Self::get("tags", &format!("{}:{}", Self::TAGS_PREFIX, tenant_id)).await
+    }
+
+    pub async fn set_tags(tenant_id: &str, tags: &TagListResponse) {
+        Self::set(
+            &format!("{}:{}", Self::TAGS_PREFIX, tenant_id),
+            tags,
+            Self::TAGS_TTL_SECS,
+        )
+        .await;
+    }
+
+    /// 标签词表修改后清除该租户的标签统计
+    pub async fn invalidate_tags(tenant_id: &str) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        if let Err(e) = redis
+            .batch_del(&[format!("{}:{}", Self::TAGS_PREFIX, tenant_id)])
+            .await
+        {
+            tracing::warn!("⚠️  清除标签缓存失败: {}", e);
+        }
+    }
+
+    /// 清除事件涉及的缓存：服务器详情，状态刷新后的玩家总数，资料修改后的标签统计
+    pub async fn invalidate(event: &DomainEvent) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+
+        let mut keys: Vec<String> = event
+            .server_ids()
+            .iter()
+            .map(|id| format!("{}:{}", Self::DETAIL_PREFIX, id))
+            .collect();
+        if matches!(event, DomainEvent::StatsRefreshed { .. }) {
+            // 玩家总数按租户缓存，事件中没有服务器所属租户，全部清除
+            keys.extend(
+                TenantService::all()
+                    .into_iter()
+                    .map(|tenant| format!("{}:{}", Self::PLAYERS_PREFIX, tenant.id)),
+            );
+        }
+        if matches!(event, DomainEvent::ServerUpdated { .. }) {
+            keys.extend(
+                TenantService::all()
+                    .into_iter()
+                    .map(|tenant| format!("{}:{}", Self::TAGS_PREFIX, tenant.id)),
+            );
+        }
+        if keys.is_empty() {
+            return;
+        }
+
+        match redis.batch_del(&keys).await {
+            Ok(removed) => MetricsService::inc_counter(
+                "server_cache_invalidations_total",
+                "因数据变更清除的服务器缓存数",
+                &[("event", event.name())],
+                removed as f64,
+            ),
+            Err(e) => tracing::warn!("⚠️  清除服务器缓存失败: {}", e),
+        }
+    }
+
+    /// 清除全部服务器缓存，用于事件丢失后的兜底
+    pub async fn invalidate_all() {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        for prefix in [
+            Self::DETAIL_PREFIX,
+            Self::PLAYERS_PREFIX,
+            Self::SUGGEST_PREFIX,
+            Self::TAGS_PREFIX,
+        ] {
+            if let Err(e) = redis.del_pattern(&format!("{prefix}:*")).await {
+                tracing::warn!("⚠️  清除服务器缓存失败: {}", e);
+            }
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(cache: &'static str, key: &str) -> Option<T> {
+        let redis = RedisService::instance()?;
+        let value = match redis.get(key).await {
+            Ok(Some(cached)) => serde_json::from_str(&cached).ok(),
+            _ => None,
+        };
+
+        MetricsService::inc_counter(
+            "server_cache_requests_total",
+            "服务器读缓存命中情况",
+            &[
+                ("cache", cache),
+                ("result", if value.is_some() { "hit" } else { "miss" }),
+            ],
+            1.0,
+        );
+        value
+    }
+
+    async fn set<T: Serialize>(key: &str, value: &T, ttl_secs: u64) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        if let Ok(value) = serde_json::to_string(value) {
+            if let Err(e) = redis.set_ex(key, &value, ttl_secs).await {
+                tracing::warn!("⚠️  写入服务器缓存 {} 失败: {}", key, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::RwLock;
+
+use axum::http::Method;
+use once_cell::sync::Lazy;
+use rand::Rng;
+
+use crate::{
+    errors::{ApiError, ApiResult},
+    schemas::chaos::{ChaosRules, FaultKind, FaultRule},
+};
+
+/// 最多同时生效的规则数
+const MAX_RULES: usize = 50;
+/// 单次注入的最大延迟（毫秒）
+const MAX_DELAY_MS: u64 = 60_000;
+/// 不注入故障的路径前缀，保证随时可以关闭故障注入
+const EXEMPT_PREFIX: &str = "/v2/admin/chaos";
+
+/// 当前生效的规则，只保存在本进程内存中，重启后清空
+static RULES: Lazy<RwLock<Vec<FaultRule>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// 故障注入服务
+///
+/// 仅在启用 `chaos` 特性时编译。规则由管理员在运行时下发，按顺序匹配方法与路径前缀，
+/// 第一条命中的规则再按百分比决定本次请求是否注入故障。多实例部署时需要分别下发。
+pub struct ChaosService;
+
+impl ChaosService {
+    /// 当前生效的规则
+    pub fn list() -> ChaosRules {
+        let rules = RULES.read().unwrap_or_else(|e| e.into_inner());
+        ChaosRules {
+            rules: rules.clone(),
+        }
+    }
+
+    /// 校验并整体替换规则
+    pub fn replace(mut request: ChaosRules) -> ApiResult<ChaosRules> {
+        if request.rules.len() > MAX_RULES {
+            return Err(ApiError::BadRequest(format!(
+                "规则数量不能超过 {MAX_RULES}"
+            )));
+        }
+        for rule in &mut request.rules {
+            Self::validate(rule)?;
+        }
+
+        let mut rules = RULES.write().unwrap_or_else(|e| e.into_inner());
+        *rules = request.rules.clone();
+        tracing::warn!("⚠️  故障注入规则已更新，当前 {} 条", rules.len());
+        Ok(request)
+    }
+
+    /// 清空全部规则
+    pub fn clear() {
+        RULES.write().unwrap_or_else(|e| e.into_inner()).clear();
+        tracing::info!("故障注入规则已清空");
+    }
+
+    /// 为请求选择要注入的故障，未命中时返回 `None`
+    pub fn pick(method: &Method, path: &str) -> Option<FaultRule> {
+        if path.starts_with(EXEMPT_PREFIX) {
+            return None;
+        }
+
+        let rules = RULES.read().unwrap_or_else(|e| e.into_inner());
+        let rule = rules.iter().find(|rule| {
+            path.starts_with(&rule.path_prefix)
+                && rule.method.as_deref().is_none_or(|m| m == method.as_str())
+        })?;
+        (rand::rng().random_range(0.0..100.0) < rule.percentage).then(|| rule.clone())
+    }
+
+    /// 校验规则并统一方法名大小写
+    fn validate(rule: &mut FaultRule) -> ApiResult<()> {
+        if !rule.path_prefix.starts_with('/') {
+            return Err(ApiError::BadRequest(format!(
+                "路径前缀必须以 / 开头: {}",
+                rule.path_prefix
+            )));
+        }
+        if !rule.percentage.is_finite() || !(0.0..=100.0).contains(&rule.percentage) {
+            return Err(ApiError::BadRequest(
+                "percentage 需在 0~100 之间".to_string(),
+            ));
+        }
+        if let Some(method) = rule.method.as_mut() {
+            *method = method.trim().to_ascii_uppercase();
+            Method::from_bytes(method.as_bytes())
+                .map_err(|_| ApiError::BadRequest(format!("无效的 HTTP 方法: {method}")))?;
+        }
+
+        match rule.kind {
+            FaultKind::Delay => match rule.delay_ms {
+                Some(delay_ms) if delay_ms <= MAX_DELAY_MS => {}
+                Some(_) => {
+                    return Err(ApiError::BadRequest(format!(
+                        "delay_ms 不能超过 {MAX_DELAY_MS}"
+                    )))
+                }
+                None => {
+                    return Err(ApiError::BadRequest(
+                        "delay 类型的规则需要填写 delay_ms".to_string(),
+                    ))
+                }
+            },
+            FaultKind::Error => {
+                if rule
+                    .status
+                    .is_some_and(|status| !(400..=599).contains(&status))
+                {
+                    return Err(ApiError::BadRequest("status 需在 400~599 之间".to_string()));
+                }
+            }
+            FaultKind::Drop => {}
+        }
+        Ok(())
+    }
+}
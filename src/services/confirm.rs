@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use rand::{distr::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{ApiError, ApiResult, ErrorCode},
+    schemas::confirm::{ConfirmationRequired, DestructiveAction},
+    services::{metrics::MetricsService, redis::RedisService},
+};
+
+/// 令牌中记录的操作信息，执行时逐项比对
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingConfirmation {
+    action: DestructiveAction,
+    user_id: i32,
+    target: String,
+    impact: BTreeMap<String, u64>,
+}
+
+/// 破坏性操作的二次确认
+///
+/// 第一次调用只计算影响范围并签发短期令牌；带上令牌再次调用时，令牌必须属于同一用户、
+/// 同一操作与同一对象，且影响范围没有变化，才允许执行。令牌只能使用一次。
+pub struct ConfirmationService;
+
+impl ConfirmationService {
+    const KEY_PREFIX: &'static str = "confirm";
+    const TOKEN_TTL_SECS: u64 = 300;
+
+    /// 检查确认令牌
+    ///
+    /// 未传令牌时签发新令牌并返回 `Some`，调用方应直接把影响范围返回给客户端；
+    /// 令牌有效时返回 `None`，调用方继续执行删除。
+    pub async fn guard(
+        action: DestructiveAction,
+        user_id: i32,
+        target: &str,
+        impact: BTreeMap<String, u64>,
+        token: Option<&str>,
+    ) -> ApiResult<Option<ConfirmationRequired>> {
+        match token.map(str::trim).filter(|t| !t.is_empty()) {
+            None => Self::issue(action, user_id, target, impact).await.map(Some),
+            Some(token) => {
+                Self::consume(action, user_id, target, &impact, token).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn issue(
+        action: DestructiveAction,
+        user_id: i32,
+        target: &str,
+        impact: BTreeMap<String, u64>,
+    ) -> ApiResult<ConfirmationRequired> {
+        let redis = Self::redis()?;
+        let token: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+
+        let pending = PendingConfirmation {
+            action,
+            user_id,
+            target: target.to_string(),
+            impact: impact.clone(),
+        };
+        let value = serde_json::to_string(&pending)
+            .map_err(|e| ApiError::Internal(format!("确认信息序列化失败: {e}")))?;
+        redis
+            .set_ex(&Self::key(&token), &value, Self::TOKEN_TTL_SECS)
+            .await
+            .map_err(|e| ApiError::Internal(format!("保存确认令牌失败: {e}")))?;
+
+        Self::record(action, "issued");
+        Ok(ConfirmationRequired {
+            action,
+            confirm_token: token,
+            expires_at: Utc::now() + Duration::seconds(Self::TOKEN_TTL_SECS as i64),
+            impact,
+        })
+    }
+
+    async fn consume(
+        action: DestructiveAction,
+        user_id: i32,
+        target: &str,
+        impact: &BTreeMap<String, u64>,
+        token: &str,
+    ) -> ApiResult<()> {
+        let redis = Self::redis()?;
+        let key = Self::key(token);
+        // 读取与删除在同一条命令中完成，并发请求中只有一个能拿到令牌；无论结果如何，令牌都只能使用一次
+        let stored = redis
+            .get_del(&key)
+            .await
+            .map_err(|e| ApiError::Internal(format!("读取确认令牌失败: {e}")))?;
+
+        let pending = stored
+            .and_then(|value| serde_json::from_str::<PendingConfirmation>(&value).ok())
+            .filter(|p| p.action == action && p.user_id == user_id && p.target == target)
+            .ok_or_else(|| {
+                Self::record(action, "invalid");
+                ApiError::BadRequest("确认令牌无效或已过期".to_string())
+                    .with_code(ErrorCode::InvalidConfirmationToken)
+            })?;
+
+        if &pending.impact != impact {
+            Self::record(action, "stale");
+            return Err(ApiError::Conflict(
+                "待删除的内容已发生变化，请重新确认".to_string(),
+            ));
+        }
+
+        Self::record(action, "confirmed");
+        Ok(())
+    }
+
+    fn redis() -> ApiResult<std::sync::Arc<RedisService>> {
+        RedisService::instance()
+            .ok_or_else(|| ApiError::ServiceUnavailable("确认服务暂不可用".to_string()))
+    }
+
+    fn key(token: &str) -> String {
+        format!("{}:{}", Self::KEY_PREFIX, token)
+    }
+
+    fn record(action: DestructiveAction, outcome: &str) {
+        MetricsService::inc_counter(
+            "destructive_confirmations_total",
+            "破坏性操作确认令牌的签发与使用情况",
+            &[("action", action.as_str()), ("outcome", outcome)],
+            1.0,
+        );
+    }
+}
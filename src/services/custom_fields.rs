@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::*;
+use validator::Validate;
+
+use crate::{
+    entities::{prelude::ServerCustomField, server_custom_field},
+    errors::{ApiError, ApiResult},
+    schemas::servers::{CustomField, UpdateCustomFieldsRequest},
+    services::{
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+    },
+};
+
+/// 服务器自定义字段服务
+///
+/// 服主可以为服务器添加若干键值对（如 QQ 群、资源包地址），在详情中按顺序展示；
+/// 标记为可搜索的字段值会写入搜索索引。
+pub struct CustomFieldService;
+
+impl CustomFieldService {
+    /// 获取服务器的自定义字段，按显示顺序排列
+    pub async fn list(db: &DatabaseConnection, server_id: i32) -> ApiResult<Vec<CustomField>> {
+        let fields = ServerCustomField::find()
+            .filter(server_custom_field::Column::ServerId.eq(server_id))
+            .order_by_asc(server_custom_field::Column::Position)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(fields.into_iter().map(Self::to_schema).collect())
+    }
+
+    /// 用请求中的字段整体替换服务器的自定义字段
+    pub async fn replace(
+        db: &DatabaseConnection,
+        server_id: i32,
+        request: UpdateCustomFieldsRequest,
+    ) -> ApiResult<Vec<CustomField>> {
+        request
+            .validate()
+            .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+        let mut keys = HashSet::new();
+        let mut fields = Vec::with_capacity(request.fields.len());
+        for field in request.fields {
+            let key = field.key.trim().to_string();
+            let value = field.value.trim().to_string();
+            if key.is_empty() || value.is_empty() {
+                return Err(ApiError::BadRequest("字段名与字段值不能为空".to_string()));
+            }
+            if !keys.insert(key.clone()) {
+                return Err(ApiError::BadRequest(format!("字段名重复: {key}")));
+            }
+            fields.push(CustomField {
+                key,
+                value,
+                searchable: field.searchable,
+            });
+        }
+
+        let txn = db.begin().await?;
+        ServerCustomField::delete_many()
+            .filter(server_custom_field::Column::ServerId.eq(server_id))
+            .exec(&txn)
+            .await?;
+        if !fields.is_empty() {
+            let rows = fields.iter().enumerate().map(|(position, field)| {
+                server_custom_field::ActiveModel {
+                    server_id: Set(server_id),
+                    position: Set(position as i32),
+                    key: Set(field.key.clone()),
+                    value: Set(field.value.clone()),
+                    searchable: Set(field.searchable),
+                    ..Default::default()
+                }
+            });
+            ServerCustomField::insert_many(rows).exec(&txn).await?;
+        }
+        txn.commit().await?;
+
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+        Ok(fields)
+    }
+
+    /// 所有可搜索字段的值，按服务器分组，供搜索索引同步使用
+    pub async fn searchable_values<C: ConnectionTrait>(
+        db: &C,
+    ) -> ApiResult<HashMap<i32, Vec<String>>> {
+        let fields = ServerCustomField::find()
+            .filter(server_custom_field::Column::Searchable.eq(true))
+            .order_by_asc(server_custom_field::Column::ServerId)
+            .order_by_asc(server_custom_field::Column::Position)
+            .all(db)
+            .await?;
+
+        Ok(Self::group_values(fields))
+    }
+
+    /// 指定服务器的可搜索字段值，供增量同步使用
+    pub async fn searchable_values_of<C: ConnectionTrait>(
+        db: &C,
+        server_ids: &[i32],
+    ) -> ApiResult<HashMap<i32, Vec<String>>> {
+        let fields = ServerCustomField::find()
+            .filter(server_custom_field::Column::Searchable.eq(true))
+            .filter(server_custom_field::Column::ServerId.is_in(server_ids.iter().copied()))
+            .order_by_asc(server_custom_field::Column::ServerId)
+            .order_by_asc(server_custom_field::Column::Position)
+            .all(db)
+            .await?;
+
+        Ok(Self::group_values(fields))
+    }
+
+    fn group_values(fields: Vec<server_custom_field::Model>) -> HashMap<i32, Vec<String>> {
+        let mut values: HashMap<i32, Vec<String>> = HashMap::new();
+        for field in fields {
+            values.entry(field.server_id).or_default().push(field.value);
+        }
+        values
+    }
+
+    fn to_schema(model: server_custom_field::Model) -> CustomField {
+        CustomField {
+            key: model.key,
+            value: model.value,
+            searchable: model.searchable,
+        }
+    }
+}
@@ -1,34 +1,62 @@
+use once_cell::sync::Lazy;
 use sea_orm::{
     ConnectOptions, ConnectionTrait, Database, DatabaseConnection as SeaOrmDatabaseConnection,
     DbErr,
 };
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::info;
 
 use crate::config::DatabaseConfig;
+use crate::services::metrics::MetricsService;
 
 pub type DatabaseConnection = Arc<SeaOrmDatabaseConnection>;
 
 pub async fn establish_connection(config: &DatabaseConfig) -> Result<DatabaseConnection, DbErr> {
+    let sqlite = is_sqlite_url(&config.url);
+    #[cfg(not(feature = "sqlite"))]
+    if sqlite {
+        return Err(DbErr::Custom(
+            "使用 SQLite 需要以 sqlite 特性编译".to_string(),
+        ));
+    }
+
     let mut opt = ConnectOptions::new(&config.url);
 
-    opt.max_connections(config.max_connections)
-        .min_connections(config.min_connections)
-        .connect_timeout(Duration::from_secs(config.connect_timeout))
-        .acquire_timeout(Duration::from_secs(config.acquire_timeout))
-        .idle_timeout(Duration::from_secs(config.idle_timeout))
-        .max_lifetime(Duration::from_secs(28800))
-        .sqlx_logging(false);
-
-    info!(
-        "配置数据库连接池: 最小连接数={}, 最大连接数={}",
-        config.min_connections, config.max_connections
-    );
+    if sqlite {
+        // 内存数据库的每个连接都是独立的库，只能使用单个常驻连接
+        opt.max_connections(1)
+            .min_connections(1)
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .acquire_timeout(Duration::from_secs(config.acquire_timeout))
+            .sqlx_logging(false);
+        info!("使用 SQLite 数据库（仅用于本地开发与测试）");
+    } else {
+        opt.max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .acquire_timeout(Duration::from_secs(config.acquire_timeout))
+            .idle_timeout(Duration::from_secs(config.idle_timeout))
+            .max_lifetime(Duration::from_secs(28800))
+            .sqlx_logging(false);
+
+        info!(
+            "配置数据库连接池: 最小连接数={}, 最大连接数={}",
+            config.min_connections, config.max_connections
+        );
+    }
 
     let db = Database::connect(opt).await?;
     let connection = Arc::new(db);
 
+    #[cfg(feature = "sqlite")]
+    if sqlite {
+        create_sqlite_schema(&connection).await?;
+        info!("SQLite 表结构已就绪");
+    }
+
     if let Err(e) = warm_up_connection_pool(&connection).await {
         tracing::warn!("⚠️  连接池预热失败: {}", e);
     } else {
@@ -38,10 +66,158 @@ pub async fn establish_connection(config: &DatabaseConfig) -> Result<DatabaseCon
     Ok(connection)
 }
 
+fn is_sqlite_url(url: &str) -> bool {
+    url.starts_with("sqlite:")
+}
+
+/// 按实体定义在 SQLite 中建表（已存在的表跳过）
+///
+/// 生产环境的 MySQL 表结构由迁移脚本维护，这里只服务于本地开发与不依赖 MySQL 的集成测试。
+#[cfg(feature = "sqlite")]
+async fn create_sqlite_schema(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use crate::entities::prelude::*;
+    use sea_orm::{DatabaseBackend, Schema};
+
+    let backend = DatabaseBackend::Sqlite;
+    let schema = Schema::new(backend);
+    let mut statements = Vec::new();
+
+    macro_rules! create_tables {
+        ($($entity:ident),* $(,)?) => {
+            $(
+                statements.push(
+                    schema
+                        .create_table_from_entity($entity)
+                        .if_not_exists()
+                        .to_owned(),
+                );
+            )*
+        };
+    }
+    create_tables!(
+        Users,
+        Files,
+        Gallery,
+        GalleryImage,
+        Server,
+        UserServer,
+        ServerStats,
+        ServerLog,
+        ServerRevision,
+        ServerTranslation,
+        ServerUptime,
+        ServerEmbeddings,
+        BanRecords,
+        Ticket,
+        TicketLog,
+        Activity,
+        FeatureFlags,
+        RegistrationFlags,
+        SpamHolds,
+        ExternalIdentities,
+        ApiKeys,
+        StatusIncidents,
+        TagVocabulary,
+        UserFavorite,
+        UserPreferences,
+        NotificationOptOuts,
+        Notifications,
+    );
+
+    for statement in statements {
+        // 实体中为 MySQL 声明的 LONGTEXT 在 SQLite 中统一使用 TEXT
+        let sql = backend.build(&statement).sql.replace("LONGTEXT", "TEXT");
+        db.execute_unprepared(&sql).await?;
+    }
+
+    Ok(())
+}
+
+/// 读请求的一致性要求
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadConsistency {
+    /// 允许读取只读副本，可能存在复制延迟
+    #[default]
+    Eventual,
+    /// 必须读取主库，用于写后读
+    Strong,
+}
+
+/// 只读副本集合，按轮询方式分配
+#[derive(Default)]
+pub struct ReadReplicas {
+    replicas: Vec<DatabaseConnection>,
+    next: AtomicUsize,
+}
+
+impl ReadReplicas {
+    /// 按配置连接所有只读副本，连接失败的副本会被跳过
+    pub async fn connect(config: &DatabaseConfig) -> Self {
+        let mut replicas = Vec::with_capacity(config.replica_urls.len());
+
+        for (index, url) in config.replica_urls.iter().enumerate() {
+            let replica_config = DatabaseConfig {
+                url: url.clone(),
+                replica_urls: Vec::new(),
+                ..config.clone()
+            };
+            match establish_connection(&replica_config).await {
+                Ok(db) => {
+                    info!("只读副本 #{} 连接成功", index + 1);
+                    replicas.push(db);
+                }
+                Err(e) => tracing::warn!("⚠️  只读副本 #{} 连接失败，已跳过: {}", index + 1, e),
+            }
+        }
+
+        Self {
+            replicas,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// 轮询选取一个只读副本，未配置时返回 None
+    pub fn pick(&self) -> Option<&DatabaseConnection> {
+        if self.replicas.is_empty() {
+            return None;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.replicas.len();
+        self.replicas.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.replicas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+}
+
+/// 最近发生过写入的用户及写入时间，用于写后读固定走主库
+static RECENT_WRITERS: Lazy<Mutex<HashMap<i32, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 记录用户的一次写入
+pub fn mark_recent_write(user_id: i32, sticky_window: Duration) {
+    let mut writers = RECENT_WRITERS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    writers.retain(|_, written_at| now.duration_since(*written_at) < sticky_window);
+    writers.insert(user_id, now);
+}
+
+/// 用户是否在固定窗口内发生过写入
+pub fn wrote_recently(user_id: i32, sticky_window: Duration) -> bool {
+    let writers = RECENT_WRITERS.lock().unwrap_or_else(|e| e.into_inner());
+    writers
+        .get(&user_id)
+        .is_some_and(|written_at| written_at.elapsed() < sticky_window)
+}
+
 async fn warm_up_connection_pool(db: &DatabaseConnection) -> Result<(), DbErr> {
     use sea_orm::Statement;
 
-    let stmt = Statement::from_string(sea_orm::DatabaseBackend::MySql, "SELECT 1".to_owned());
+    let stmt = Statement::from_string(db.get_database_backend(), "SELECT 1".to_owned());
 
     for i in 1..=3 {
         match db.execute(stmt.clone()).await {
@@ -57,3 +233,122 @@ async fn warm_up_connection_pool(db: &DatabaseConnection) -> Result<(), DbErr> {
 
     Ok(())
 }
+
+/// 连接池状态快照
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStatus {
+    /// 当前已建立的连接数
+    pub size: u32,
+    /// 空闲连接数
+    pub idle: u32,
+    /// 正在使用的连接数
+    pub in_use: u32,
+    /// 最大连接数
+    pub max: u32,
+}
+
+impl PoolStatus {
+    /// 所有连接都已被占用，新的查询需要排队等待
+    pub fn is_saturated(&self) -> bool {
+        self.in_use >= self.max
+    }
+}
+
+/// 读取连接池状态，非 MySQL 连接（如测试中的 Mock 连接）返回 None
+pub fn pool_status(db: &DatabaseConnection) -> Option<PoolStatus> {
+    match db.as_ref() {
+        SeaOrmDatabaseConnection::SqlxMySqlPoolConnection(_) => {
+            let pool = db.get_mysql_connection_pool();
+            let size = pool.size();
+            let idle = pool.num_idle() as u32;
+            Some(PoolStatus {
+                size,
+                idle,
+                in_use: size.saturating_sub(idle),
+                max: pool.options().get_max_connections(),
+            })
+        }
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// 将连接池状态写入指标
+pub fn record_pool_metrics(status: &PoolStatus) {
+    MetricsService::set_gauge(
+        "db_pool_connections",
+        "当前已建立的数据库连接数",
+        &[],
+        status.size as f64,
+    );
+    MetricsService::set_gauge(
+        "db_pool_idle_connections",
+        "空闲的数据库连接数",
+        &[],
+        status.idle as f64,
+    );
+    MetricsService::set_gauge(
+        "db_pool_in_use_connections",
+        "正在使用的数据库连接数",
+        &[],
+        status.in_use as f64,
+    );
+    MetricsService::set_gauge(
+        "db_pool_max_connections",
+        "数据库连接池最大连接数",
+        &[],
+        status.max as f64,
+    );
+}
+
+/// 定期采样连接池状态与获取连接的等待时间
+///
+/// sqlx 不暴露排队等待的统计，这里每次采样实际获取一次连接并计时，
+/// 得到的等待时间可以近似反映业务查询的排队情况。
+pub async fn monitor_connection_pool(db: DatabaseConnection, interval: Duration) {
+    if !matches!(
+        db.as_ref(),
+        SeaOrmDatabaseConnection::SqlxMySqlPoolConnection(_)
+    ) {
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+
+        let start = Instant::now();
+        match db.get_mysql_connection_pool().acquire().await {
+            Ok(conn) => {
+                drop(conn);
+                MetricsService::observe(
+                    "db_pool_acquire_wait_seconds",
+                    "获取数据库连接的等待时间",
+                    &[],
+                    start.elapsed().as_secs_f64(),
+                );
+            }
+            Err(e) => {
+                tracing::warn!("⚠️  连接池采样获取连接失败: {}", e);
+                MetricsService::inc_counter(
+                    "db_pool_acquire_errors_total",
+                    "获取数据库连接失败次数",
+                    &[],
+                    1.0,
+                );
+            }
+        }
+
+        if let Some(status) = pool_status(&db) {
+            record_pool_metrics(&status);
+            if status.is_saturated() {
+                tracing::warn!(
+                    "⚠️  数据库连接池已耗尽: 使用中={}, 最大={}, 采样等待={}ms",
+                    status.in_use,
+                    status.max,
+                    start.elapsed().as_millis()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+
+use crate::{
+    config::DelistingConfig,
+    entities::{
+        prelude::{Server, ServerStats},
+        server, server_stats,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{DelistingInfo, DelistingListResponse, UpdateDelistingRequest},
+        users::NotificationKind,
+    },
+    services::{
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+        notification::{Notification, NotificationService},
+    },
+};
+
+/// 长期离线服务器下架策略
+///
+/// 连续 `offline_days` 天没有任何状态数据的服务器会被标记并通知服主；标记后再过
+/// `grace_days` 天仍未恢复则自动下架，不出现在列表、搜索与站点地图中，详情页仍可访问。
+/// 服务器重新上报状态后，下一轮检查会清除标记并重新上架。从未上报过状态的服务器不处理，
+/// 管理员可以为服务器设置豁免或手动重新上架。
+pub struct DelistingService;
+
+impl DelistingService {
+    /// 定期执行下架策略
+    pub async fn run_loop(
+        db: DatabaseConnection,
+        config: DelistingConfig,
+        interval: std::time::Duration,
+    ) {
+        if !config.enabled {
+            return;
+        }
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match Self::run_once(&db, &config).await {
+                Ok((flagged, delisted, relisted)) if flagged + delisted + relisted > 0 => {
+                    tracing::info!(
+                        "离线服务器检查完成: 标记 {} 个, 下架 {} 个, 重新上架 {} 个",
+                        flagged,
+                        delisted,
+                        relisted
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("⚠️  离线服务器检查失败: {}", e),
+            }
+        }
+    }
+
+    /// 执行一轮检查，返回 (标记数量, 下架数量, 重新上架数量)
+    pub async fn run_once(
+        db: &DatabaseConnection,
+        config: &DelistingConfig,
+    ) -> ApiResult<(usize, usize, usize)> {
+        let now = Utc::now();
+        let offline_before = now - Duration::days(config.offline_days);
+        let grace = Duration::days(config.grace_days);
+        let last_seen = Self::last_seen(db).await?;
+
+        let servers = Server::find()
+            .filter(server::Column::DeactivatedAt.is_null())
+            .all(db.as_ref())
+            .await?;
+
+        let (mut flagged, mut delisted, mut relisted) = (0, 0, 0);
+        for server in servers {
+            let Some(last) = last_seen.get(&server.id).copied() else {
+                continue;
+            };
+            let server_id = server.id;
+            let name = server.name.clone();
+
+            if last >= offline_before {
+                if server.offline_flagged_at.is_none() && server.delisted_at.is_none() {
+                    continue;
+                }
+                let was_delisted = server.delisted_at.is_some();
+                Self::set_state(db, server, None, None).await?;
+                if was_delisted {
+                    relisted += 1;
+                    Self::notify_owners(
+                        db,
+                        server_id,
+                        NotificationKind::ServerRelisted,
+                        "服务器已重新上架",
+                        &format!("服务器「{name}」已恢复上报状态，重新出现在服务器列表与搜索中。"),
+                    )
+                    .await;
+                }
+                continue;
+            }
+
+            if server.delisting_exempt || server.delisted_at.is_some() {
+                continue;
+            }
+            match server.offline_flagged_at {
+                None => {
+                    flagged += 1;
+                    Self::set_state(db, server, Some(now), None).await?;
+                    Self::notify_owners(
+                        db,
+                        server_id,
+                        NotificationKind::ServerOfflineWarning,
+                        "服务器长期离线提醒",
+                        &format!(
+                            "服务器「{name}」已连续 {} 天没有上报状态，若 {} 天内仍未恢复将自动从列表与搜索中下架。",
+                            config.offline_days, config.grace_days
+                        ),
+                    )
+                    .await;
+                }
+                Some(flagged_at) if flagged_at + grace <= now => {
+                    delisted += 1;
+                    Self::set_state(db, server, Some(flagged_at), Some(now)).await?;
+                    Self::notify_owners(
+                        db,
+                        server_id,
+                        NotificationKind::ServerDelisted,
+                        "服务器已下架",
+                        &format!(
+                            "服务器「{name}」长期离线，已从列表与搜索中下架。服务器恢复上报状态后会自动重新上架。"
+                        ),
+                    )
+                    .await;
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok((flagged, delisted, relisted))
+    }
+
+    /// 租户内已被标记、下架或豁免的服务器
+    pub async fn list(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+    ) -> ApiResult<DelistingListResponse> {
+        let servers = Server::find()
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(
+                Condition::any()
+                    .add(server::Column::OfflineFlaggedAt.is_not_null())
+                    .add(server::Column::DelistedAt.is_not_null())
+                    .add(server::Column::DelistingExempt.eq(true)),
+            )
+            .order_by_asc(server::Column::Id)
+            .all(db.as_ref())
+            .await?;
+        let last_seen = Self::last_seen(db).await?;
+
+        Ok(DelistingListResponse {
+            data: servers
+                .into_iter()
+                .map(|server| Self::to_info(server, &last_seen))
+                .collect(),
+        })
+    }
+
+    /// 管理员设置豁免或手动重新上架
+    pub async fn update(
+        db: &DatabaseConnection,
+        server_id: i32,
+        request: UpdateDelistingRequest,
+    ) -> ApiResult<DelistingInfo> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        let was_delisted = server.delisted_at.is_some();
+        let mut active: server::ActiveModel = server.into();
+        if let Some(exempt) = request.exempt {
+            active.delisting_exempt = Set(exempt);
+        }
+        if request.relist {
+            active.offline_flagged_at = Set(None);
+            active.delisted_at = Set(None);
+        }
+        let server = active.update(db.as_ref()).await?;
+        if was_delisted && server.delisted_at.is_none() {
+            EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+        }
+
+        let last_seen = Self::last_seen(db).await?;
+        Ok(Self::to_info(server, &last_seen))
+    }
+
+    async fn set_state(
+        db: &DatabaseConnection,
+        server: server::Model,
+        offline_flagged_at: Option<DateTime<Utc>>,
+        delisted_at: Option<DateTime<Utc>>,
+    ) -> ApiResult<()> {
+        let listing_changed = server.delisted_at.is_some() != delisted_at.is_some();
+        let server_id = server.id;
+        let mut active: server::ActiveModel = server.into();
+        active.offline_flagged_at = Set(offline_flagged_at);
+        active.delisted_at = Set(delisted_at);
+        active.update(db.as_ref()).await?;
+
+        if listing_changed {
+            EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+        }
+        Ok(())
+    }
+
+    /// 每个服务器最近一次上报状态的时间
+    async fn last_seen(db: &DatabaseConnection) -> ApiResult<HashMap<i32, DateTime<Utc>>> {
+        let rows: Vec<(i32, Option<DateTime<Utc>>)> = ServerStats::find()
+            .select_only()
+            .column(server_stats::Column::ServerId)
+            .column_as(server_stats::Column::Timestamp.max(), "last_seen")
+            .group_by(server_stats::Column::ServerId)
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(server_id, last_seen)| Some((server_id, last_seen?)))
+            .collect())
+    }
+
+    async fn notify_owners(
+        db: &DatabaseConnection,
+        server_id: i32,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+    ) {
+        NotificationService::notify_server_owners(
+            db,
+            server_id,
+            Notification {
+                kind,
+                title: title.to_string(),
+                body: body.to_string(),
+            },
+        )
+        .await;
+    }
+
+    fn to_info(server: server::Model, last_seen: &HashMap<i32, DateTime<Utc>>) -> DelistingInfo {
+        DelistingInfo {
+            server_id: server.id,
+            name: server.name,
+            last_seen_at: last_seen.get(&server.id).copied(),
+            offline_flagged_at: server.offline_flagged_at,
+            delisted_at: server.delisted_at,
+            exempt: server.delisting_exempt,
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::time::Instant;
+
+use chrono::{Duration, Utc};
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+use sea_orm::*;
+use serde_json::json;
+
+use crate::{
+    entities::{
+        files, gallery, gallery_image,
+        prelude::{Files, Gallery, GalleryImage, Server, ServerStats},
+        server, server_stats,
+    },
+    errors::ApiResult,
+    schemas::{
+        dev_tools::{SeedRequest, SeedSummary},
+        servers::{ServerStatus, ServerVisibility},
+    },
+    services::{database::DatabaseConnection, server::ServerService, tenant::TenantService},
+};
+
+/// 每批写入的最大行数，避免单条 SQL 过大
+const INSERT_BATCH_SIZE: usize = 1000;
+
+const TAG_POOL: [&str; 12] = [
+    "生存",
+    "纯净",
+    "生电",
+    "红石",
+    "RPG",
+    "PVP",
+    "小游戏",
+    "建筑",
+    "公益",
+    "模组",
+    "空岛",
+    "技术",
+];
+const VERSION_POOL: [&str; 6] = ["1.8.9", "1.12.2", "1.16.5", "1.19.4", "1.20.1", "1.21"];
+
+/// 合成数据生成服务（仅在 `dev-tools` 特性下编译）
+///
+/// 用于压测分页、搜索同步与状态聚合，生成的数据名称统一带有 `[合成]` 前缀。
+pub struct SyntheticDataService;
+
+impl SyntheticDataService {
+    /// 按请求生成服务器、历史状态与相册占位图
+    pub async fn generate(
+        db: &DatabaseConnection,
+        request: &SeedRequest,
+    ) -> ApiResult<SeedSummary> {
+        let start = Instant::now();
+        let mut rng = match request.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::seed_from_u64(rand::random()),
+        };
+        let batch_tag: u32 = rng.random();
+
+        let mut server_ids = Vec::with_capacity(request.count as usize);
+        let mut stats_rows = 0u64;
+        let mut gallery_images = 0u64;
+
+        for index in 0..request.count {
+            let gallery_id = if request.gallery_images_per_server > 0 {
+                let gallery = Gallery::insert(gallery::ActiveModel {
+                    created_at: Set(Utc::now()),
+                    ..Default::default()
+                })
+                .exec_with_returning(db.as_ref())
+                .await?;
+                Some(gallery.id)
+            } else {
+                None
+            };
+
+            let server_id = Self::insert_server(db, &mut rng, batch_tag, index, gallery_id).await?;
+            server_ids.push(server_id);
+
+            if let Some(gallery_id) = gallery_id {
+                gallery_images += Self::insert_gallery_images(
+                    db,
+                    batch_tag,
+                    server_id,
+                    gallery_id,
+                    request.gallery_images_per_server,
+                )
+                .await?;
+            }
+
+            stats_rows += Self::insert_stats_history(db, &mut rng, server_id, request).await?;
+        }
+
+        let summary = SeedSummary {
+            server_ids,
+            stats_rows,
+            gallery_images,
+            elapsed_ms: start.elapsed().as_millis(),
+        };
+
+        tracing::info!(
+            "合成数据生成完成: servers={}, stats_rows={}, gallery_images={}, elapsed_ms={}",
+            summary.server_ids.len(),
+            summary.stats_rows,
+            summary.gallery_images,
+            summary.elapsed_ms
+        );
+
+        Ok(summary)
+    }
+
+    async fn insert_server(
+        db: &DatabaseConnection,
+        rng: &mut StdRng,
+        batch_tag: u32,
+        index: u32,
+        gallery_id: Option<i32>,
+    ) -> ApiResult<i32> {
+        let tag_count = rng.random_range(1..=4);
+        let tags: Vec<&str> = TAG_POOL.choose_multiple(rng, tag_count).copied().collect();
+        let is_bedrock = rng.random_bool(0.2);
+        let auth_mode = ["OFFICIAL", "OFFLINE", "YGGDRASIL"]
+            .choose(rng)
+            .copied()
+            .unwrap_or("OFFICIAL");
+        let version = VERSION_POOL.choose(rng).copied().unwrap_or("1.20.1");
+
+        let name = format!("[合成] 服务器 {batch_tag:08x}-{index}");
+        let new_server = server::ActiveModel {
+            name: Set(name.clone()),
+            r#type: Set(if is_bedrock { "BEDROCK" } else { "JAVA" }.to_string()),
+            version: Set(version.to_string()),
+            desc: Set(format!(
+                "{name} 是用于压力测试的合成服务器，标签为 {}。这段简介会被重复几次以模拟真实长度。{}",
+                tags.join("、"),
+                "我们提供丰富的游戏内容和友好的社区环境。".repeat(rng.random_range(2..8))
+            )),
+            link: Set(format!("https://example.com/synthetic/{batch_tag:08x}/{index}")),
+            ip: Set(format!("synthetic-{batch_tag:08x}-{index}.example.com")),
+            is_member: Set(rng.random_bool(0.6)),
+            is_hide: Set(rng.random_bool(0.05)),
+            auth_mode: Set(auth_mode.to_string()),
+            tags: Set(json!(tags)),
+            cover_hash_id: Set(None),
+            gallery_id: Set(gallery_id),
+            slug: Set(Some(ServerService::generate_slug(&name))),
+            slug_edited: Set(false),
+            visibility: Set(ServerVisibility::Public.as_str().to_string()),
+            status: Set(ServerStatus::Published.as_str().to_string()),
+            tenant_id: Set(TenantService::default_tenant().id),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        let result = Server::insert(new_server).exec(db.as_ref()).await?;
+        Ok(result.last_insert_id)
+    }
+
+    async fn insert_gallery_images(
+        db: &DatabaseConnection,
+        batch_tag: u32,
+        server_id: i32,
+        gallery_id: i32,
+        count: u32,
+    ) -> ApiResult<u64> {
+        let mut file_models = Vec::with_capacity(count as usize);
+        let mut image_models = Vec::with_capacity(count as usize);
+
+        for i in 0..count {
+            let placeholder = format!("synthetic-{batch_tag:08x}-{server_id}-{i}");
+            let hash_value = files::Model::generate_file_hash(placeholder.as_bytes());
+
+            file_models.push(files::ActiveModel {
+                hash_value: Set(hash_value.clone()),
+                file_path: Set(format!(
+                    "https://placehold.co/960x540/webp?text={placeholder}"
+                )),
+                has_variants: Set(false),
+                uploaded_at: Set(Some(Utc::now())),
+                ..Default::default()
+            });
+            image_models.push(gallery_image::ActiveModel {
+                title: Set(format!("合成图片 {}", i + 1)),
+                description: Set(format!("服务器 {server_id} 的第 {} 张合成图片", i + 1)),
+                gallery_id: Set(gallery_id),
+                image_hash_id: Set(hash_value),
+                sort_order: Set(i as i32),
+                created_at: Set(Some(Utc::now())),
+                ..Default::default()
+            });
+        }
+
+        if file_models.is_empty() {
+            return Ok(0);
+        }
+
+        Files::insert_many(file_models).exec(db.as_ref()).await?;
+        GalleryImage::insert_many(image_models)
+            .exec(db.as_ref())
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    async fn insert_stats_history(
+        db: &DatabaseConnection,
+        rng: &mut StdRng,
+        server_id: i32,
+        request: &SeedRequest,
+    ) -> ApiResult<u64> {
+        let max_players: i64 = *[20, 50, 100, 200, 500, 1000].choose(rng).unwrap_or(&100);
+        let base_delay = rng.random_range(10.0..150.0);
+        let version = VERSION_POOL.choose(rng).copied().unwrap_or("1.20.1");
+        let now = Utc::now();
+
+        let rows: Vec<server_stats::ActiveModel> = (0..request.stats_per_server)
+            .map(|i| {
+                let timestamp =
+                    now - Duration::seconds(i as i64 * request.stats_interval_secs as i64);
+                // 模拟玩家数的昼夜波动
+                let hour = timestamp
+                    .format("%H")
+                    .to_string()
+                    .parse::<f64>()
+                    .unwrap_or(0.0);
+                let wave = ((hour - 8.0) / 24.0 * std::f64::consts::TAU).sin() * 0.5 + 0.5;
+                let online = ((max_players as f64) * wave * rng.random_range(0.3..0.9)) as i64;
+                // 少量离线样本
+                let offline = rng.random_bool(0.02);
+
+                let stat_data = if offline {
+                    None
+                } else {
+                    Some(json!({
+                        "players": {"online": online, "max": max_players},
+                        "delay": base_delay + rng.random_range(-5.0..25.0),
+                        "version": format!("Paper {version}"),
+                        "motd": {
+                            "plain": "合成服务器",
+                            "html": "<span style='color: green;'>合成服务器</span>",
+                            "minecraft": "§a合成服务器",
+                            "ansi": "\u{1b}[32m合成服务器\u{1b}[0m"
+                        },
+                        "icon": null
+                    }))
+                };
+
+                server_stats::ActiveModel {
+                    timestamp: Set(timestamp),
+                    stat_data: Set(stat_data),
+                    server_id: Set(server_id),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let total = rows.len() as u64;
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            let batch: Vec<_> = rows.by_ref().take(INSERT_BATCH_SIZE).collect();
+            ServerStats::insert_many(batch).exec(db.as_ref()).await?;
+        }
+
+        Ok(total)
+    }
+}
@@ -1,2 +1,3 @@
 pub mod sender;
+pub mod suppression;
 pub mod template;
\ No newline at end of file
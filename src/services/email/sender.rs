@@ -1,4 +1,4 @@
-use crate::config::Config;
+use crate::config::EmailConfig;
 use anyhow::{Context, Result};
 use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
@@ -7,24 +7,34 @@ use lettre::SmtpTransport;
 
 /// 构建邮件消息
 pub fn build_email_message(from_email: &str, to_email: &str, body: String) -> Result<Message> {
+    build_message_with_subject(from_email, to_email, "邮箱验证码", body)
+}
+
+/// 构建指定主题的邮件消息
+pub fn build_message_with_subject(
+    from_email: &str,
+    to_email: &str,
+    subject: &str,
+    body: String,
+) -> Result<Message> {
     Message::builder()
         .from(from_email.parse().context("解析发件人邮箱地址失败")?)
         .to(to_email.parse().context("解析收件人邮箱地址失败")?)
-        .subject("邮箱验证码")
+        .subject(subject)
         .header(ContentType::TEXT_HTML)
         .body(body)
         .context("构建邮件消息失败")
 }
 
 /// 构建SMTP传输对象
-pub fn build_smtp_transport(config: &Config) -> Result<SmtpTransport> {
+pub fn build_smtp_transport(config: &EmailConfig) -> Result<SmtpTransport> {
     let mut builder =
-        SmtpTransport::relay(&config.email.smtp_server).context("Failed to create SMTP relay")?;
-    builder = builder.port(config.email.smtp_port);
+        SmtpTransport::relay(&config.smtp_server).context("Failed to create SMTP relay")?;
+    builder = builder.port(config.smtp_port);
     Ok(builder
         .credentials(Credentials::new(
-            config.email.smtp_username.clone(),
-            config.email.smtp_password.clone(),
+            config.smtp_username.clone(),
+            config.smtp_password.clone(),
         ))
         .build())
 }
@@ -0,0 +1,167 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        email_suppressions,
+        prelude::{EmailSuppressions, Users},
+        users,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{AdminUserDetail, EmailDeliveryStatus},
+        internal::{EmailEvent, EmailEventKind, EmailEventsResponse},
+    },
+    services::database::DatabaseConnection,
+};
+
+/// 邮件退信与投诉处理
+///
+/// 邮件服务商回调的永久退信与投诉会把收件地址标记为无法投递，之后验证码与通知邮件
+/// 都不再发往该地址，避免持续退信影响发信域名的信誉。临时退信不做处理。
+/// 同一地址再次收到事件时只更新原因与时间，管理员可以在用户详情中查看并清除标记。
+pub struct EmailSuppressionService;
+
+impl EmailSuppressionService {
+    /// 处理服务商推送的投递事件
+    pub async fn record(
+        db: &DatabaseConnection,
+        events: Vec<EmailEvent>,
+    ) -> ApiResult<EmailEventsResponse> {
+        let mut suppressed = 0;
+        let mut ignored = 0;
+        for event in events {
+            let email = Self::normalize(&event.email);
+            if email.is_empty()
+                || !email.contains('@')
+                || (event.kind == EmailEventKind::Bounce && !event.permanent)
+            {
+                ignored += 1;
+                continue;
+            }
+
+            let now = Utc::now();
+            let detail = event.detail.filter(|d| !d.trim().is_empty());
+            match EmailSuppressions::find()
+                .filter(email_suppressions::Column::Email.eq(&email))
+                .one(db.as_ref())
+                .await?
+            {
+                Some(existing) => {
+                    let mut active: email_suppressions::ActiveModel = existing.into();
+                    active.reason = Set(event.kind.as_str().to_string());
+                    active.detail = Set(detail);
+                    active.updated_at = Set(now);
+                    active.update(db.as_ref()).await?;
+                }
+                None => {
+                    email_suppressions::ActiveModel {
+                        email: Set(email.clone()),
+                        reason: Set(event.kind.as_str().to_string()),
+                        detail: Set(detail),
+                        created_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .insert(db.as_ref())
+                    .await?;
+                }
+            }
+            tracing::info!("邮箱 {} 已标记为无法投递: {}", email, event.kind.as_str());
+            suppressed += 1;
+        }
+
+        Ok(EmailEventsResponse {
+            suppressed,
+            ignored,
+        })
+    }
+
+    /// 地址是否已被标记为无法投递，查询失败时按可投递处理
+    pub async fn is_suppressed(db: &DatabaseConnection, email: &str) -> bool {
+        match Self::find(db, email).await {
+            Ok(record) => record.is_some(),
+            Err(e) => {
+                tracing::warn!("⚠️  查询邮箱投递状态失败: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 管理员查看租户内的用户详情
+    pub async fn user_detail(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+    ) -> ApiResult<AdminUserDetail> {
+        let user = Users::find_by_id(user_id)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+        let record = Self::find(db, &user.email).await?;
+
+        Ok(AdminUserDetail {
+            id: user.id,
+            username: user.username,
+            display_name: user.display_name,
+            email: user.email,
+            role: user.role.to_value(),
+            is_active: user.is_active,
+            tenant_id: user.tenant_id,
+            created_at: user.created_at,
+            last_login: user.last_login,
+            email_status: Self::to_status(record),
+        })
+    }
+
+    /// 清除用户邮箱的无法投递标记（用户更换或修复邮箱后由管理员操作）
+    pub async fn clear_for_user(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+    ) -> ApiResult<()> {
+        let user = Users::find_by_id(user_id)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::user_not_found)?;
+
+        EmailSuppressions::delete_many()
+            .filter(email_suppressions::Column::Email.eq(Self::normalize(&user.email)))
+            .exec(db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    async fn find(
+        db: &DatabaseConnection,
+        email: &str,
+    ) -> ApiResult<Option<email_suppressions::Model>> {
+        Ok(EmailSuppressions::find()
+            .filter(email_suppressions::Column::Email.eq(Self::normalize(email)))
+            .one(db.as_ref())
+            .await?)
+    }
+
+    fn to_status(record: Option<email_suppressions::Model>) -> EmailDeliveryStatus {
+        match record {
+            Some(record) => EmailDeliveryStatus {
+                deliverable: false,
+                reason: Some(record.reason),
+                detail: record.detail,
+                suppressed_at: Some(record.updated_at),
+            },
+            None => EmailDeliveryStatus {
+                deliverable: true,
+                reason: None,
+                detail: None,
+                suppressed_at: None,
+            },
+        }
+    }
+
+    fn normalize(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+}
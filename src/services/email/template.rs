@@ -1,6 +1,6 @@
 use anyhow::Result;
 use askama::Template;
-use chrono::{Datelike, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
 
 use crate::services::utils::{get_sentence_from_queue, refill_sentence_queue};
 
@@ -37,3 +37,78 @@ pub async fn build_email_template(code: &str) -> Result<EmailTemplate> {
     };
     Ok(template)
 }
+
+/// 邮箱修改通知，发送到修改前的邮箱
+#[derive(Template)]
+#[template(path = "email_changed_notice.html")]
+pub struct EmailChangedTemplate {
+    /// 用户名
+    pub username: String,
+    /// 打码后的新邮箱
+    pub new_email: String,
+    /// 修改时间（北京时间）
+    pub changed_at: String,
+    /// 今年的年份
+    pub fullyear: String,
+}
+
+pub fn build_email_changed_template(
+    username: &str,
+    new_email: &str,
+    changed_at: DateTime<Utc>,
+) -> EmailChangedTemplate {
+    let beijing = FixedOffset::east_opt(8 * 3600).expect("固定时区偏移有效");
+    EmailChangedTemplate {
+        username: username.to_string(),
+        new_email: mask_email(new_email),
+        changed_at: changed_at
+            .with_timezone(&beijing)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        fullyear: Utc::now().year().to_string(),
+    }
+}
+
+/// 通知邮件中的一条通知
+pub struct NotificationEmailItem {
+    pub title: String,
+    pub body: String,
+}
+
+/// 用户通知邮件，立即发送时只有一条通知，摘要邮件包含周期内的全部通知
+#[derive(Template)]
+#[template(path = "email_notification.html")]
+pub struct NotificationEmailTemplate {
+    /// 称呼与引导语
+    pub greeting: String,
+    /// 通知列表
+    pub items: Vec<NotificationEmailItem>,
+    /// 页脚说明
+    pub footer: String,
+    /// 今年的年份
+    pub fullyear: String,
+}
+
+pub fn build_notification_template(
+    greeting: String,
+    footer: String,
+    items: Vec<NotificationEmailItem>,
+) -> NotificationEmailTemplate {
+    NotificationEmailTemplate {
+        greeting,
+        items,
+        footer,
+        fullyear: Utc::now().year().to_string(),
+    }
+}
+
+/// 邮箱打码，只保留用户名的前两个字符与域名，如 `ne***@example.com`
+fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let visible: String = local.chars().take(2).collect();
+            format!("{visible}***@{domain}")
+        }
+        None => "***".to_string(),
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::Client as HttpClient;
+use sea_orm::*;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::EmbeddingConfig,
+    entities::{
+        prelude::{Server, ServerEmbeddings},
+        server, server_embeddings,
+    },
+    errors::ApiResult,
+    services::{database::DatabaseConnection, metrics::MetricsService},
+};
+
+/// 单次请求向量服务的最大文本数
+const BATCH_SIZE: usize = 32;
+
+pub type EmbedFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
+
+/// 文本向量服务提供方
+pub trait EmbeddingProvider: Send + Sync {
+    /// 提供方名称，用于日志与指标
+    fn name(&self) -> &'static str;
+
+    /// 计算一批文本的向量，返回顺序与输入一致
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbedFuture<'a>;
+}
+
+/// OpenAI Embeddings 接口（及兼容实现）
+pub struct OpenAiEmbeddingProvider {
+    client: HttpClient,
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbedFuture<'a> {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .post(format!("{}/v1/embeddings", self.api_url))
+                .json(&json!({ "model": self.model, "input": texts }));
+            if let Some(ref key) = self.api_key {
+                request = request.bearer_auth(key);
+            }
+
+            let resp: Value = request.send().await?.error_for_status()?.json().await?;
+
+            let data = resp["data"]
+                .as_array()
+                .ok_or_else(|| anyhow!("向量响应缺少 data"))?;
+            let mut vectors = vec![Vec::new(); texts.len()];
+            for item in data {
+                let index = item["index"].as_u64().unwrap_or_default() as usize;
+                let vector = item["embedding"]
+                    .as_array()
+                    .ok_or_else(|| anyhow!("向量响应缺少 embedding"))?
+                    .iter()
+                    .filter_map(|v| v.as_f64().map(|v| v as f32))
+                    .collect();
+                if let Some(slot) = vectors.get_mut(index) {
+                    *slot = vector;
+                }
+            }
+            if vectors.iter().any(Vec::is_empty) {
+                return Err(anyhow!("向量响应数量与请求不一致"));
+            }
+
+            Ok(vectors)
+        })
+    }
+}
+
+static EMBEDDING_INSTANCE: OnceCell<Arc<EmbeddingService>> = OnceCell::new();
+/// 服务器 ID 到描述向量的映射
+type VectorMap = HashMap<i32, Vec<f32>>;
+/// 已加载的向量，按服务器 ID 索引；刷新后清空
+static VECTOR_CACHE: Lazy<Mutex<Option<Arc<VectorMap>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 服务器描述向量服务
+///
+/// 后台定期为描述发生变化的服务器计算向量并保存，供相似服务器推荐使用。
+/// 服务器数量在数千量级，相似度查询直接在内存中逐个计算余弦相似度。
+pub struct EmbeddingService {
+    provider: Box<dyn EmbeddingProvider>,
+    model: String,
+}
+
+impl EmbeddingService {
+    /// 根据配置初始化向量服务，未配置提供方时返回 `Ok(false)`
+    pub fn init(config: &EmbeddingConfig) -> Result<bool> {
+        let Some(ref provider) = config.provider else {
+            return Ok(false);
+        };
+
+        let provider: Box<dyn EmbeddingProvider> = match provider.as_str() {
+            "openai" => Box::new(OpenAiEmbeddingProvider {
+                client: HttpClient::new(),
+                api_url: config.api_url.clone(),
+                api_key: config.api_key.clone(),
+                model: config.model.clone(),
+            }),
+            other => return Err(anyhow!("未知的向量服务提供方: {}", other)),
+        };
+
+        EMBEDDING_INSTANCE
+            .set(Arc::new(EmbeddingService {
+                provider,
+                model: config.model.clone(),
+            }))
+            .map_err(|_| anyhow!("向量服务已初始化"))?;
+
+        Ok(true)
+    }
+
+    /// 获取全局向量服务实例
+    pub fn instance() -> Option<Arc<EmbeddingService>> {
+        EMBEDDING_INSTANCE.get().cloned()
+    }
+
+    /// 定期刷新服务器描述向量
+    pub async fn run_refresh_loop(self: Arc<Self>, db: DatabaseConnection, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.refresh(&db).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("已更新 {} 个服务器的描述向量", count),
+                Err(e) => tracing::warn!("⚠️  更新服务器描述向量失败: {}", e),
+            }
+        }
+    }
+
+    /// 为缺少向量或描述已变化的服务器计算向量
+    pub async fn refresh(&self, db: &DatabaseConnection) -> ApiResult<usize> {
+        let servers: Vec<(i32, String, String)> = Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .column(server::Column::Name)
+            .column(server::Column::Desc)
+            .filter(server::Column::DeactivatedAt.is_null())
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+        let existing: HashMap<i32, (String, String)> = ServerEmbeddings::find()
+            .select_only()
+            .column(server_embeddings::Column::ServerId)
+            .column(server_embeddings::Column::Model)
+            .column(server_embeddings::Column::SourceHash)
+            .into_tuple::<(i32, String, String)>()
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .map(|(id, model, hash)| (id, (model, hash)))
+            .collect();
+
+        let stale: Vec<(i32, String, String)> = servers
+            .into_iter()
+            .filter_map(|(id, name, desc)| {
+                let text = format!("{name}\n{desc}");
+                let hash = hex::encode(Sha256::digest(text.as_bytes()));
+                let fresh = existing
+                    .get(&id)
+                    .is_some_and(|(model, old)| *model == self.model && *old == hash);
+                (!fresh).then_some((id, text, hash))
+            })
+            .collect();
+
+        let mut updated = 0;
+        for batch in stale.chunks(BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|(_, text, _)| text.clone()).collect();
+            let vectors = match self.provider.embed(&texts).await {
+                Ok(vectors) => vectors,
+                Err(e) => {
+                    MetricsService::inc_counter(
+                        "embedding_requests_total",
+                        "描述向量计算请求数",
+                        &[("provider", self.provider.name()), ("result", "failure")],
+                        1.0,
+                    );
+                    tracing::warn!("⚠️  计算描述向量失败: {}", e);
+                    break;
+                }
+            };
+            MetricsService::inc_counter(
+                "embedding_requests_total",
+                "描述向量计算请求数",
+                &[("provider", self.provider.name()), ("result", "success")],
+                1.0,
+            );
+
+            for ((server_id, _, hash), vector) in batch.iter().zip(vectors) {
+                let model = server_embeddings::ActiveModel {
+                    server_id: Set(*server_id),
+                    model: Set(self.model.clone()),
+                    source_hash: Set(hash.clone()),
+                    vector: Set(json!(vector)),
+                    updated_at: Set(Utc::now()),
+                };
+                ServerEmbeddings::insert(model)
+                    .on_conflict(
+                        sea_query::OnConflict::column(server_embeddings::Column::ServerId)
+                            .update_columns([
+                                server_embeddings::Column::Model,
+                                server_embeddings::Column::SourceHash,
+                                server_embeddings::Column::Vector,
+                                server_embeddings::Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(db.as_ref())
+                    .await?;
+                updated += 1;
+            }
+        }
+
+        if updated > 0 {
+            *VECTOR_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+        Ok(updated)
+    }
+
+    /// 按余弦相似度查找与指定服务器最相近的服务器，服务器没有向量时返回 `None`
+    pub async fn nearest(
+        &self,
+        db: &DatabaseConnection,
+        server_id: i32,
+        limit: usize,
+    ) -> ApiResult<Option<Vec<(i32, f64)>>> {
+        let vectors = self.vectors(db).await?;
+        let Some(target) = vectors.get(&server_id) else {
+            return Ok(None);
+        };
+
+        let mut scored: Vec<(i32, f64)> = vectors
+            .iter()
+            .filter(|(id, _)| **id != server_id)
+            .map(|(id, vector)| (*id, Self::cosine(target, vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+
+        Ok(Some(scored))
+    }
+
+    async fn vectors(&self, db: &DatabaseConnection) -> ApiResult<Arc<VectorMap>> {
+        if let Some(cached) = VECTOR_CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return Ok(cached);
+        }
+
+        let rows = ServerEmbeddings::find()
+            .filter(server_embeddings::Column::Model.eq(&self.model))
+            .all(db.as_ref())
+            .await?;
+        let vectors: VectorMap = rows
+            .into_iter()
+            .filter_map(|row| {
+                serde_json::from_value::<Vec<f32>>(row.vector)
+                    .ok()
+                    .map(|vector| (row.server_id, vector))
+            })
+            .collect();
+
+        let vectors = Arc::new(vectors);
+        *VECTOR_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(vectors.clone());
+        Ok(vectors)
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f64 {
+        if a.len() != b.len() {
+            return 0.0;
+        }
+        let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+        for (x, y) in a.iter().zip(b) {
+            let (x, y) = (*x as f64, *y as f64);
+            dot += x * y;
+            norm_a += x * x;
+            norm_b += y * y;
+        }
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return 0.0;
+        }
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
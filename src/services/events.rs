@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// 事件通道容量，订阅者落后超过该数量时会丢失最早的事件
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// 领域事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+    /// 服务器状态已刷新
+    StatsRefreshed {
+        server_ids: Vec<i32>,
+        #[serde(with = "crate::schemas::datetime::rfc3339")]
+        refreshed_at: DateTime<Utc>,
+    },
+    /// 服务器资料已修改（编辑、回滚、标签合并）
+    ServerUpdated {
+        server_ids: Vec<i32>,
+        #[serde(with = "crate::schemas::datetime::rfc3339")]
+        updated_at: DateTime<Utc>,
+    },
+}
+
+impl DomainEvent {
+    /// 事件名称，与序列化后的 `type` 字段一致
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::StatsRefreshed { .. } => "StatsRefreshed",
+            DomainEvent::ServerUpdated { .. } => "ServerUpdated",
+        }
+    }
+
+    /// 事件涉及的服务器
+    pub fn server_ids(&self) -> &[i32] {
+        match self {
+            DomainEvent::StatsRefreshed { server_ids, .. }
+            | DomainEvent::ServerUpdated { server_ids, .. } => server_ids,
+        }
+    }
+
+    pub fn server_updated(server_ids: Vec<i32>) -> Self {
+        DomainEvent::ServerUpdated {
+            server_ids,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+static EVENT_SENDER: Lazy<broadcast::Sender<DomainEvent>> =
+    Lazy::new(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0);
+
+/// 进程内事件总线
+///
+/// 供缓存失效、WebSocket 推送等模块订阅，发布方不关心是否有订阅者。
+pub struct EventBus;
+
+impl EventBus {
+    /// 发布事件，没有订阅者时直接丢弃
+    pub fn publish(event: DomainEvent) {
+        let _ = EVENT_SENDER.send(event);
+    }
+
+    /// 订阅后续发布的事件
+    pub fn subscribe() -> broadcast::Receiver<DomainEvent> {
+        EVENT_SENDER.subscribe()
+    }
+}
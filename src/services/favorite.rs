@@ -0,0 +1,135 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        prelude::{Server, UserFavorite, UserServer},
+        server, user_favorite, user_server,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        servers::{ServerStatus, ServerVisibility},
+        users::FavoriteServer,
+    },
+    services::{database::DatabaseConnection, server::ServerService},
+};
+
+/// 服务器收藏服务
+///
+/// 收藏只对收藏者本人可见；隐藏或未发布的服务器只有其成员可以收藏，
+/// 服务器之后被隐藏、撤下或停用时，收藏保留但不再出现在非成员的收藏列表中。
+pub struct FavoriteService;
+
+impl FavoriteService {
+    /// 收藏服务器，已收藏时不重复记录
+    pub async fn add(db: &DatabaseConnection, user_id: i32, server_id: i32) -> ApiResult<()> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+        let listed =
+            ServerVisibility::of(&server).is_listed() && ServerStatus::of(&server).is_listed();
+        if !listed && !Self::is_member(db, user_id, server_id).await? {
+            return Err(ApiError::server_not_found());
+        }
+        if Self::is_favorited(db, user_id, server_id).await? {
+            return Ok(());
+        }
+
+        user_favorite::ActiveModel {
+            user_id: Set(user_id),
+            server_id: Set(server_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+        Ok(())
+    }
+
+    /// 取消收藏，未收藏时视为成功
+    pub async fn remove(db: &DatabaseConnection, user_id: i32, server_id: i32) -> ApiResult<()> {
+        UserFavorite::delete_many()
+            .filter(user_favorite::Column::UserId.eq(user_id))
+            .filter(user_favorite::Column::ServerId.eq(server_id))
+            .exec(db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    /// 用户是否已收藏服务器
+    pub async fn is_favorited(
+        db: &DatabaseConnection,
+        user_id: i32,
+        server_id: i32,
+    ) -> ApiResult<bool> {
+        let count = UserFavorite::find()
+            .filter(user_favorite::Column::UserId.eq(user_id))
+            .filter(user_favorite::Column::ServerId.eq(server_id))
+            .count(db.as_ref())
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// 按收藏时间倒序分页返回用户收藏的服务器
+    ///
+    /// 只返回属于当前租户且未停用的服务器，隐藏或未发布的服务器只在用户是其成员时返回。
+    pub async fn list(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        user_id: i32,
+        page: u64,
+        page_size: u64,
+    ) -> ApiResult<(Vec<FavoriteServer>, u64)> {
+        let memberships = sea_query::Query::select()
+            .column(user_server::Column::ServerId)
+            .from(UserServer)
+            .and_where(user_server::Column::UserId.eq(user_id))
+            .to_owned();
+        let paginator = UserFavorite::find()
+            .find_also_related(Server)
+            .filter(user_favorite::Column::UserId.eq(user_id))
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+                            .add(server::Column::Status.eq(ServerStatus::Published.as_str())),
+                    )
+                    .add(server::Column::Id.in_subquery(memberships)),
+            )
+            .order_by_desc(user_favorite::Column::CreatedAt)
+            .order_by_desc(user_favorite::Column::Id)
+            .paginate(db.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let (favorited_at, servers): (Vec<_>, Vec<_>) = paginator
+            .fetch_page(page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .filter_map(|(favorite, server)| server.map(|server| (favorite.created_at, server)))
+            .unzip();
+        let details = ServerService::list_details(db, Some(user_id), servers).await?;
+
+        let data = favorited_at
+            .into_iter()
+            .zip(details)
+            .map(|(favorited_at, server)| FavoriteServer {
+                favorited_at,
+                server,
+            })
+            .collect();
+        Ok((data, total))
+    }
+
+    async fn is_member(db: &DatabaseConnection, user_id: i32, server_id: i32) -> ApiResult<bool> {
+        let count = UserServer::find()
+            .filter(user_server::Column::UserId.eq(user_id))
+            .filter(user_server::Column::ServerId.eq(server_id))
+            .count(db.as_ref())
+            .await?;
+        Ok(count > 0)
+    }
+}
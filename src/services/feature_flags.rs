@@ -0,0 +1,210 @@
+use chrono::Utc;
+use sea_orm::*;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    entities::{feature_flags, prelude::FeatureFlags},
+    errors::{ApiError, ApiResult},
+    schemas::admin::{FeatureFlagInfo, UpdateFeatureFlagRequest},
+    services::{database::DatabaseConnection, metrics::MetricsService, redis::RedisService},
+};
+
+/// 新版搜索排序（未指定排序时成员服务器优先）
+pub const FLAG_SEARCH_RANKING_V2: &str = "search_ranking_v2";
+/// 新版服务器详情（内嵌管理员列表）
+pub const FLAG_SERVER_DETAIL_V2: &str = "server_detail_v2";
+/// 相似服务器推荐使用描述向量（需配置向量服务）
+pub const FLAG_SIMILAR_EMBEDDINGS: &str = "similar_servers_embeddings";
+
+/// 功能开关服务
+///
+/// 开关保存在数据库中，Redis 作为短时缓存；判断规则依次为：
+/// 白名单用户始终可见 → 开关关闭则不可见 → 按用户 ID 稳定分桶与灰度比例比较。
+/// 匿名用户只有在灰度比例为 100 时可见。未创建的开关视为关闭。
+pub struct FeatureFlagService;
+
+impl FeatureFlagService {
+    /// 缓存键前缀
+    const CACHE_PREFIX: &'static str = "feature_flag";
+    /// 缓存时长（秒），修改开关时会主动失效
+    const CACHE_TTL_SECS: u64 = 30;
+
+    /// 判断功能对该用户是否可见；查询失败时视为关闭
+    pub async fn is_enabled(db: &DatabaseConnection, key: &str, user_id: Option<i32>) -> bool {
+        let flag = match Self::load(db, key).await {
+            Ok(flag) => flag,
+            Err(e) => {
+                tracing::warn!("⚠️  读取功能开关 {} 失败: {}", key, e);
+                return false;
+            }
+        };
+
+        let enabled = flag.is_some_and(|flag| Self::evaluate(&flag, user_id));
+        MetricsService::inc_counter(
+            "feature_flag_evaluations_total",
+            "功能开关判断次数",
+            &[
+                ("flag", key),
+                ("result", if enabled { "on" } else { "off" }),
+            ],
+            1.0,
+        );
+        enabled
+    }
+
+    /// 获取全部开关
+    pub async fn list(db: &DatabaseConnection) -> ApiResult<Vec<FeatureFlagInfo>> {
+        let flags = FeatureFlags::find()
+            .order_by_asc(feature_flags::Column::Key)
+            .all(db.as_ref())
+            .await?;
+        Ok(flags.into_iter().map(Self::to_info).collect())
+    }
+
+    /// 创建或更新开关
+    pub async fn upsert(
+        db: &DatabaseConnection,
+        key: &str,
+        request: UpdateFeatureFlagRequest,
+        operator_id: i32,
+    ) -> ApiResult<FeatureFlagInfo> {
+        if key.is_empty()
+            || key.len() > 64
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(ApiError::BadRequest(
+                "开关标识只能包含小写字母、数字和下划线，长度 1~64".to_string(),
+            ));
+        }
+        if request.rollout_percentage > 100 {
+            return Err(ApiError::BadRequest(
+                "rollout_percentage 需在 0~100 之间".to_string(),
+            ));
+        }
+
+        let existing = FeatureFlags::find()
+            .filter(feature_flags::Column::Key.eq(key))
+            .one(db.as_ref())
+            .await?;
+
+        let mut active = match existing {
+            Some(flag) => flag.into_active_model(),
+            None => feature_flags::ActiveModel {
+                key: Set(key.to_string()),
+                ..Default::default()
+            },
+        };
+        active.description = Set(request.description);
+        active.enabled = Set(request.enabled);
+        active.rollout_percentage = Set(request.rollout_percentage as i32);
+        active.user_ids = Set(json!(request.user_ids));
+        active.updated_by_id = Set(Some(operator_id));
+        active.updated_at = Set(Utc::now());
+        let flag = active.save(db.as_ref()).await?.try_into_model()?;
+
+        Self::invalidate(key).await;
+        tracing::info!(
+            "功能开关已更新: key={}, enabled={}, rollout={}%, operator={}",
+            flag.key,
+            flag.enabled,
+            flag.rollout_percentage,
+            operator_id
+        );
+
+        Ok(Self::to_info(flag))
+    }
+
+    /// 删除开关，删除后视为关闭
+    pub async fn delete(db: &DatabaseConnection, key: &str) -> ApiResult<()> {
+        let result = FeatureFlags::delete_many()
+            .filter(feature_flags::Column::Key.eq(key))
+            .exec(db.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("功能开关不存在".to_string()));
+        }
+
+        Self::invalidate(key).await;
+        Ok(())
+    }
+
+    async fn load(db: &DatabaseConnection, key: &str) -> ApiResult<Option<feature_flags::Model>> {
+        let cache_key = format!("{}:{}", Self::CACHE_PREFIX, key);
+        let redis = RedisService::instance();
+
+        if let Some(redis) = &redis {
+            if let Ok(Some(cached)) = redis.get(&cache_key).await {
+                if let Ok(flag) = serde_json::from_str(&cached) {
+                    return Ok(flag);
+                }
+            }
+        }
+
+        let flag = FeatureFlags::find()
+            .filter(feature_flags::Column::Key.eq(key))
+            .one(db.as_ref())
+            .await?;
+
+        // 不存在的开关也缓存，避免每次请求都查库
+        if let Some(redis) = &redis {
+            if let Ok(value) = serde_json::to_string(&flag) {
+                if let Err(e) = redis.set_ex(&cache_key, &value, Self::CACHE_TTL_SECS).await {
+                    tracing::warn!("⚠️  缓存功能开关 {} 失败: {}", key, e);
+                }
+            }
+        }
+
+        Ok(flag)
+    }
+
+    async fn invalidate(key: &str) {
+        if let Some(redis) = RedisService::instance() {
+            let cache_key = format!("{}:{}", Self::CACHE_PREFIX, key);
+            if let Err(e) = redis.del(&cache_key).await {
+                tracing::warn!("⚠️  清除功能开关缓存 {} 失败: {}", key, e);
+            }
+        }
+    }
+
+    fn evaluate(flag: &feature_flags::Model, user_id: Option<i32>) -> bool {
+        if let Some(user_id) = user_id {
+            if Self::parse_user_ids(&flag.user_ids).contains(&user_id) {
+                return true;
+            }
+        }
+        if !flag.enabled {
+            return false;
+        }
+
+        match user_id {
+            Some(user_id) => Self::bucket(&flag.key, user_id) < flag.rollout_percentage,
+            None => flag.rollout_percentage >= 100,
+        }
+    }
+
+    /// 将用户稳定地映射到 0~99 的分桶，不同开关之间相互独立
+    fn bucket(key: &str, user_id: i32) -> i32 {
+        let digest = Sha256::digest(format!("{key}:{user_id}").as_bytes());
+        let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+        (value % 100) as i32
+    }
+
+    fn parse_user_ids(user_ids: &JsonValue) -> Vec<i32> {
+        serde_json::from_value(user_ids.clone()).unwrap_or_default()
+    }
+
+    fn to_info(flag: feature_flags::Model) -> FeatureFlagInfo {
+        FeatureFlagInfo {
+            user_ids: Self::parse_user_ids(&flag.user_ids),
+            key: flag.key,
+            description: flag.description,
+            enabled: flag.enabled,
+            rollout_percentage: flag.rollout_percentage.clamp(0, 100) as u8,
+            updated_by_id: flag.updated_by_id,
+            updated_at: flag.updated_at,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use serde_json::json;
+
+use crate::{
+    config::{SitemapConfig, TenantDefinition},
+    entities::{
+        files, gallery_image,
+        prelude::{Files, GalleryImage, Server},
+        server,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::servers::{FeedFormat, ServerStatus, ServerVisibility},
+    services::{database::DatabaseConnection, server::ServerService, sitemap::SitemapService},
+};
+
+/// 订阅源最多包含的条目数
+const MAX_ITEMS: u64 = 50;
+
+/// 渲染后的订阅源
+pub struct RenderedFeed {
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+/// 服务器相册订阅源
+///
+/// 按发布顺序倒序输出最近的相册图片，供社区成员在阅读器中订阅服务器动态。
+/// 只对列表中可见的服务器提供，链接使用与站点地图相同的站点地址。
+pub struct GalleryFeedService;
+
+impl GalleryFeedService {
+    pub async fn render(
+        db: &DatabaseConnection,
+        config: &SitemapConfig,
+        tenant: &TenantDefinition,
+        server_id: i32,
+        format: FeedFormat,
+    ) -> ApiResult<RenderedFeed> {
+        let server = Server::find_by_id(server_id)
+            .filter(server::Column::TenantId.eq(tenant.id.as_str()))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        let images = match server.gallery_id {
+            Some(gallery_id) => {
+                GalleryImage::find()
+                    .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+                    .order_by_desc(gallery_image::Column::Id)
+                    .limit(MAX_ITEMS)
+                    .all(db.as_ref())
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        let file_paths: HashMap<String, String> = Files::find()
+            .filter(
+                files::Column::HashValue
+                    .is_in(images.iter().map(|image| image.image_hash_id.clone())),
+            )
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .map(|file| (file.hash_value, file.file_path))
+            .collect();
+
+        let base_url = SitemapService::base_url(config, tenant);
+        let path = server.slug.clone().unwrap_or_else(|| server.id.to_string());
+        let feed = Feed {
+            title: format!("{} 的相册", server.name),
+            home_page_url: format!("{base_url}/servers/{path}"),
+            items: images
+                .into_iter()
+                .filter_map(|image| {
+                    let image_url =
+                        ServerService::build_image_url(file_paths.get(&image.image_hash_id)?);
+                    let image_url = if image_url.starts_with('/') {
+                        format!("{base_url}{image_url}")
+                    } else {
+                        image_url
+                    };
+                    Some(FeedItem {
+                        id: format!("server-{}-gallery-{}", server.id, image.id),
+                        url: format!("{base_url}/servers/{path}#gallery-{}", image.id),
+                        title: image.title,
+                        description: image.description,
+                        image_url,
+                        published_at: image.created_at,
+                    })
+                })
+                .collect(),
+        };
+
+        Ok(match format {
+            FeedFormat::Rss => RenderedFeed {
+                content_type: "application/rss+xml; charset=utf-8",
+                body: feed.to_rss(),
+            },
+            FeedFormat::Json => RenderedFeed {
+                content_type: "application/feed+json; charset=utf-8",
+                body: feed.to_json(),
+            },
+        })
+    }
+}
+
+struct Feed {
+    title: String,
+    home_page_url: String,
+    items: Vec<FeedItem>,
+}
+
+struct FeedItem {
+    id: String,
+    url: String,
+    title: String,
+    description: String,
+    image_url: String,
+    published_at: Option<DateTime<Utc>>,
+}
+
+impl Feed {
+    fn to_rss(&self) -> String {
+        let escape = SitemapService::escape;
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <rss version=\"2.0\">\n<channel>\n",
+        );
+        xml.push_str(&format!(
+            "  <title>{}</title>\n  <link>{}</link>\n  <description>{}</description>\n",
+            escape(&self.title),
+            escape(&self.home_page_url),
+            escape(&self.title),
+        ));
+        for item in &self.items {
+            xml.push_str("  <item>\n");
+            xml.push_str(&format!(
+                "    <title>{}</title>\n    <link>{}</link>\n    <guid isPermaLink=\"false\">{}</guid>\n",
+                escape(&item.title),
+                escape(&item.url),
+                escape(&item.id),
+            ));
+            xml.push_str(&format!(
+                "    <description>{}</description>\n    <enclosure url=\"{}\" length=\"0\" type=\"{}\"/>\n",
+                escape(&item.description),
+                escape(&item.image_url),
+                Self::image_type(&item.image_url),
+            ));
+            if let Some(published_at) = item.published_at {
+                xml.push_str(&format!(
+                    "    <pubDate>{}</pubDate>\n",
+                    published_at.to_rfc2822()
+                ));
+            }
+            xml.push_str("  </item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    fn to_json(&self) -> String {
+        let items: Vec<_> = self
+            .items
+            .iter()
+            .map(|item| {
+                let mut value = json!({
+                    "id": item.id,
+                    "url": item.url,
+                    "title": item.title,
+                    "content_text": item.description,
+                    "image": item.image_url,
+                });
+                if let Some(published_at) = item.published_at {
+                    value["date_published"] = json!(published_at.to_rfc3339());
+                }
+                value
+            })
+            .collect();
+
+        json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": self.title,
+            "home_page_url": self.home_page_url,
+            "items": items,
+        })
+        .to_string()
+    }
+
+    /// 按扩展名推断 MIME 类型，无法判断时使用通用图片类型
+    fn image_type(url: &str) -> &'static str {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        match path
+            .rsplit('.')
+            .next()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => "image/*",
+        }
+    }
+}
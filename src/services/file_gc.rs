@@ -0,0 +1,199 @@
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::{Query, SelectStatement},
+    *,
+};
+
+use crate::{
+    config::FileGcConfig,
+    entities::{
+        files, gallery_image,
+        prelude::{Files, GalleryImage, Server, Users},
+        server, users,
+    },
+    errors::ApiResult,
+    services::{
+        database::DatabaseConnection,
+        file_upload::FileUploadService,
+        metrics::MetricsService,
+        storage::{Storage, StorageBackend},
+    },
+};
+
+/// 主动释放文件时的保护时间：刚上传（含去重命中）的文件可能正要被写入引用
+const RELEASE_GRACE_MINUTES: i64 = 10;
+
+/// 未引用文件清理服务
+///
+/// 文件按内容哈希去重，同一文件可能同时被多个封面、画册图片或头像引用。
+/// 删除封面、画册图片或服务器时调用 [`FileGcService::release`]，只在文件不再被引用时删除；
+/// 其他途径留下的孤立文件（更换头像、替换封面等）由定期任务 [`FileGcService::run_loop`] 清理。
+///
+/// 删除记录的语句本身带有“未被引用且已过保护时间”的条件，记录删除成功后才删除存储中的对象，
+/// 与并发上传竞争时最多留下无记录的对象，不会出现记录指向已删除的对象。
+/// 未通过安全扫描的文件保留在隔离区供排查，不清理。
+pub struct FileGcService;
+
+impl FileGcService {
+    /// 定期清理未引用的文件
+    pub async fn run_loop(
+        db: DatabaseConnection,
+        storage: Storage,
+        config: FileGcConfig,
+        interval: std::time::Duration,
+    ) {
+        if !config.enabled {
+            return;
+        }
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match Self::run_once(&db, storage.as_ref(), &config).await {
+                Ok(removed) if removed > 0 => {
+                    tracing::info!("未引用文件清理完成: 删除 {} 个文件", removed)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("⚠️  未引用文件清理失败: {}", e),
+            }
+        }
+    }
+
+    /// 执行一轮清理，返回删除的文件数
+    pub async fn run_once(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        config: &FileGcConfig,
+    ) -> ApiResult<usize> {
+        let cutoff = Utc::now() - Duration::hours(config.grace_hours);
+        let candidates = Files::find()
+            .filter(Self::collectable(cutoff))
+            .limit(config.batch_size)
+            .all(db.as_ref())
+            .await?;
+
+        let mut removed = 0;
+        for file in candidates {
+            if Self::remove(db, Some(storage), file, cutoff, "gc").await? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 引用被移除后调用：文件不再被引用时立即删除，仍被引用或刚上传时保留
+    ///
+    /// 失败只记录日志，遗留的文件由定期任务清理。
+    pub async fn release(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        hash: &str,
+    ) {
+        let cutoff = Utc::now() - Duration::minutes(RELEASE_GRACE_MINUTES);
+        let file = match Files::find_by_id(hash)
+            .filter(Self::collectable(cutoff))
+            .one(db.as_ref())
+            .await
+        {
+            Ok(Some(file)) => file,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("⚠️  查询待释放文件 {} 失败: {}", hash, e);
+                return;
+            }
+        };
+        if let Err(e) = Self::remove(db, storage, file, cutoff, "release").await {
+            tracing::warn!("⚠️  释放文件 {} 失败: {}", hash, e);
+        }
+    }
+
+    /// 按条件删除记录，成功后删除存储中的对象及缩略图；未配置文件存储时保留记录
+    async fn remove(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        file: files::Model,
+        cutoff: chrono::DateTime<Utc>,
+        source: &'static str,
+    ) -> ApiResult<bool> {
+        let key = match storage {
+            Some(storage) => storage.object_key(&file.file_path),
+            // 无法删除对象时保留记录，避免对象失去记录后无法追溯
+            None => {
+                tracing::warn!("⚠️  未配置文件存储，文件 {} 未删除", file.hash_value);
+                return Ok(false);
+            }
+        };
+
+        let deleted = Files::delete_many()
+            .filter(files::Column::HashValue.eq(&file.hash_value))
+            .filter(Self::collectable(cutoff))
+            .exec(db.as_ref())
+            .await?
+            .rows_affected;
+        if deleted == 0 {
+            return Ok(false);
+        }
+
+        // 不在本存储中的文件（如外部地址）只删除记录
+        if let (Some(storage), Some(key)) = (storage, key) {
+            if let Err(e) = storage.delete(key).await {
+                tracing::warn!("⚠️  删除文件 {} 失败，对象已无记录: {}", key, e);
+            }
+            if file.has_variants {
+                FileUploadService::delete_variants(storage, key).await;
+            }
+        }
+
+        MetricsService::inc_counter(
+            "file_gc_removed_total",
+            "删除的未引用文件数",
+            &[("source", source)],
+            1.0,
+        );
+        Ok(true)
+    }
+
+    /// 可清理的文件：不被任何封面、图标、画册图片或头像引用，已过保护时间，且不在隔离区
+    fn collectable(cutoff: chrono::DateTime<Utc>) -> Condition {
+        Condition::all()
+            .add(
+                files::Column::HashValue
+                    .not_in_subquery(Self::references(Server, server::Column::CoverHashId)),
+            )
+            .add(
+                files::Column::HashValue
+                    .not_in_subquery(Self::references(Server, server::Column::IconHashId)),
+            )
+            .add(files::Column::HashValue.not_in_subquery(Self::references(
+                GalleryImage,
+                gallery_image::Column::ImageHashId,
+            )))
+            .add(
+                files::Column::HashValue
+                    .not_in_subquery(Self::references(Users, users::Column::AvatarHashId)),
+            )
+            .add(
+                files::Column::HashValue
+                    .not_in_subquery(Self::references(Users, users::Column::AvatarSmallHashId)),
+            )
+            .add(
+                Condition::any()
+                    .add(files::Column::UploadedAt.is_null())
+                    .add(files::Column::UploadedAt.lt(cutoff)),
+            )
+            .add(
+                Condition::any()
+                    .add(files::Column::ScanStatus.is_null())
+                    .add(files::Column::ScanStatus.ne("infected")),
+            )
+    }
+
+    /// 引用文件的列中的非空哈希；`NOT IN` 的子查询包含 NULL 时不会匹配任何行
+    fn references<E: EntityTrait, C: ColumnTrait>(entity: E, column: C) -> SelectStatement {
+        Query::select()
+            .column(column)
+            .from(entity)
+            .and_where(column.is_not_null())
+            .to_owned()
+    }
+}
@@ -1,16 +1,80 @@
 use anyhow::Result;
-use image::{GenericImageView, ImageFormat};
-use reqwest::Client as HttpClient;
+use chrono::Utc;
+use image::{imageops::FilterType, GenericImageView, ImageFormat};
+use rand::Rng;
+use reqwest::{RequestBuilder, StatusCode};
 use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
 use sea_orm::*;
 use std::io::Cursor;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::{
-    config::S3Config, entities::files, errors::{ApiError, ApiResult}, services::database::DatabaseConnection
+    config::S3Config,
+    entities::files,
+    errors::{ApiError, ApiResult, ErrorCode},
+    services::database::DatabaseConnection,
+    services::metrics::MetricsService,
+    services::storage::StorageBackend,
+    services::upload_scan::{ScanOutcome, UploadScanService},
 };
 
+/// 未通过安全扫描的文件在存储中的前缀，仅供管理员排查，不对外引用
+pub const QUARANTINE_PREFIX: &str = "quarantine";
+/// 头像标准尺寸（像素）
+pub const AVATAR_SIZE: u32 = 256;
+/// 头像小尺寸（像素），用于列表等小图场景
+pub const AVATAR_SMALL_SIZE: u32 = 64;
+/// 服务器图标的最小边长（像素）
+pub const ICON_MIN_SIZE: u32 = 64;
+/// 服务器图标的最大边长（像素）
+pub const ICON_MAX_SIZE: u32 = 512;
+/// 服务器图标文件的大小上限
+pub const ICON_MAX_BYTES: usize = 1024 * 1024;
+
+/// 封面与画册图片的缩略图规格
+///
+/// 缩略图与原图放在同一目录，文件名在扩展名前加上规格后缀
+/// （`uploads/<id>.webp` → `uploads/<id>.thumb.webp`），由原图地址即可推出，不单独记录。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageVariant {
+    /// 列表卡片等小图
+    Thumb,
+    /// 详情页等中等尺寸
+    Medium,
+}
+
+impl ImageVariant {
+    pub const ALL: [ImageVariant; 2] = [ImageVariant::Thumb, ImageVariant::Medium];
+
+    /// 缩放后的最大宽度（像素），原图更窄时保持原尺寸
+    pub const fn max_width(self) -> u32 {
+        match self {
+            ImageVariant::Thumb => 320,
+            ImageVariant::Medium => 960,
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            ImageVariant::Thumb => "thumb",
+            ImageVariant::Medium => "medium",
+        }
+    }
+
+    /// 由原图的地址或对象键得到该规格的地址
+    pub fn path_of(self, original: &str) -> String {
+        let file_name_start = original.rfind('/').map_or(0, |pos| pos + 1);
+        match original[file_name_start..].rfind('.') {
+            Some(dot) => {
+                let dot = file_name_start + dot;
+                format!("{}.{}{}", &original[..dot], self.suffix(), &original[dot..])
+            }
+            None => format!("{original}.{}", self.suffix()),
+        }
+    }
+}
+
 pub struct FileUploadService;
 
 impl FileUploadService {
@@ -68,17 +132,21 @@ impl FileUploadService {
         }
 
         // 尝试打开图片
-        let img = image::load_from_memory(content)
-            .map_err(|_| ApiError::BadRequest("图片文件无效".to_string()))?;
+        let img = image::load_from_memory(content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
 
         // 检查图片格式
-        let format = image::guess_format(content)
-            .map_err(|_| ApiError::BadRequest("无法识别图片格式".to_string()))?;
+        let format = image::guess_format(content).map_err(|_| {
+            ApiError::BadRequest("无法识别图片格式".to_string())
+                .with_code(ErrorCode::InvalidImageFormat)
+        })?;
 
         match format {
             ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP => {}
             _ => {
-                return Err(ApiError::BadRequest("图片文件格式无效".to_string()));
+                return Err(ApiError::BadRequest("图片文件格式无效".to_string())
+                    .with_code(ErrorCode::InvalidImageFormat));
             }
         }
 
@@ -87,16 +155,67 @@ impl FileUploadService {
         let actual_ratio = (width as f64) / (height as f64);
 
         if (actual_ratio - expected_ratio).abs() > 0.01 {
-            return Err(ApiError::BadRequest("图片比例最好为 512*300".to_string()));
+            return Err(ApiError::BadRequest("图片比例最好为 512*300".to_string())
+                .with_code(ErrorCode::InvalidImageRatio));
         }
 
         Ok((width, height))
     }
 
+    /// 验证服务器图标：正方形，边长在 [`ICON_MIN_SIZE`] 与 [`ICON_MAX_SIZE`] 之间
+    pub fn validate_icon(content: &[u8]) -> ApiResult<u32> {
+        if content.len() > ICON_MAX_BYTES {
+            return Err(ApiError::BadRequest(
+                "图标文件大小不能超过 1 MB".to_string(),
+            ));
+        }
+
+        let format = image::guess_format(content).map_err(|_| {
+            ApiError::BadRequest("无法识别图片格式".to_string())
+                .with_code(ErrorCode::InvalidImageFormat)
+        })?;
+        match format {
+            ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP => {}
+            _ => {
+                return Err(ApiError::BadRequest("图片文件格式无效".to_string())
+                    .with_code(ErrorCode::InvalidImageFormat));
+            }
+        }
+
+        let img = image::load_from_memory(content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
+        let (width, height) = img.dimensions();
+        if width != height {
+            return Err(ApiError::BadRequest("图标必须为正方形".to_string())
+                .with_code(ErrorCode::InvalidImageRatio));
+        }
+        if !(ICON_MIN_SIZE..=ICON_MAX_SIZE).contains(&width) {
+            return Err(ApiError::BadRequest(format!(
+                "图标边长必须在 {ICON_MIN_SIZE} 到 {ICON_MAX_SIZE} 像素之间"
+            )));
+        }
+
+        Ok(width)
+    }
+
+    /// 验证并上传服务器图标，图标尺寸较小，不生成缩略图
+    pub async fn validate_and_upload_icon(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        content: Vec<u8>,
+    ) -> ApiResult<files::Model> {
+        Self::validate_icon(&content)?;
+        let webp_content = Self::convert_to_webp(&content)?;
+        let (_url, file_model) = Self::upload_file(db, storage, webp_content, "icon.webp").await?;
+        Ok(file_model)
+    }
+
     /// 将图片转换为 WebP 格式
     pub fn convert_to_webp(content: &[u8]) -> ApiResult<Vec<u8>> {
-        let img = image::load_from_memory(content)
-            .map_err(|_| ApiError::BadRequest("图片文件无效".to_string()))?;
+        let img = image::load_from_memory(content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
 
         let mut webp_data = Vec::new();
         let mut cursor = Cursor::new(&mut webp_data);
@@ -107,16 +226,15 @@ impl FileUploadService {
         Ok(webp_data)
     }
 
-    /// 上传文件到 S3
-    pub async fn upload_file_to_s3(
+    /// 上传文件到存储，内容相同的文件只保存一份
+    pub async fn upload_file(
         db: &DatabaseConnection,
-        s3_config: &S3Config,
+        storage: &dyn StorageBackend,
         file_content: Vec<u8>,
         file_name: &str,
     ) -> ApiResult<(String, files::Model)> {
         let file_hash = files::Model::generate_file_hash(&file_content);
         let extension = Self::get_file_extension(file_name);
-        let s3_object_name = format!("uploads/{}{}", Uuid::new_v4(), extension);
 
         // 检查文件是否已存在
         if let Some(existing_file) = files::Entity::find()
@@ -125,41 +243,45 @@ impl FileUploadService {
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?
         {
+            if existing_file.scan_status.as_deref() == Some("infected") {
+                return Err(ApiError::BadRequest("文件未通过安全扫描".to_string())
+                    .with_code(ErrorCode::FileRejected));
+            }
+            // 刷新上传时间，避免清理任务在调用方写入引用前删除该文件
+            let mut active: files::ActiveModel = existing_file.into();
+            active.uploaded_at = Set(Some(Utc::now()));
+            let existing_file = active.update(db.as_ref()).await?;
             return Ok((existing_file.file_path.clone(), existing_file));
         }
 
-        // 创建 S3 配置
-        let credentials = Self::create_s3_credentials(s3_config);
-        let bucket = Self::create_s3_bucket(s3_config)
-            .map_err(|e| ApiError::Internal(format!("S3 bucket 配置失败: {e}")))?;
-
-        // 生成上传的预签名 URL
-        let action = bucket.put_object(Some(&credentials), &s3_object_name);
-
-        // 使用 HTTP 客户端上传文件
-        let http_client = HttpClient::new();
-        let response = http_client
-            .put(action.sign(Duration::from_secs(3600)))
-            .body(file_content.clone())
-            .send()
-            .await
-            .map_err(|e| ApiError::Internal(format!("文件上传失败: {e}")))?;
-
-        if !response.status().is_success() {
-            return Err(ApiError::Internal(format!(
-                "文件上传失败，状态码: {}",
-                response.status()
-            )));
+        // 安全扫描，命中病毒特征的文件转存到隔离区
+        let scan = UploadScanService::scan(&file_content).await;
+        if matches!(scan, ScanOutcome::Failed { .. }) && !UploadScanService::fail_open() {
+            return Err(ApiError::ServiceUnavailable(
+                "文件安全扫描暂不可用，请稍后再试".to_string(),
+            ));
         }
+        let infected = matches!(scan, ScanOutcome::Infected { .. });
+        let prefix = if infected {
+            QUARANTINE_PREFIX
+        } else {
+            "uploads"
+        };
+        let object_key = format!("{}/{}{}", prefix, Uuid::new_v4(), extension);
+
+        storage.put(&object_key, file_content).await?;
 
         // 保存文件信息到数据库
-        let file_path = format!(
-            "{}/{}/{}",
-            s3_config.endpoint_url, s3_config.bucket, s3_object_name
-        );
+        let file_path = storage.file_path(&object_key);
         let file_object = files::ActiveModel {
             hash_value: Set(file_hash),
             file_path: Set(file_path.clone()),
+            scan_status: Set(scan.status().map(str::to_string)),
+            scan_engine: Set(scan.engine().map(str::to_string)),
+            scan_detail: Set(scan.detail()),
+            scanned_at: Set(scan.status().map(|_| Utc::now())),
+            has_variants: Set(false),
+            uploaded_at: Set(Some(Utc::now())),
         };
 
         let created_file = files::Entity::insert(file_object)
@@ -167,13 +289,18 @@ impl FileUploadService {
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
 
+        if infected {
+            return Err(ApiError::BadRequest("文件未通过安全扫描".to_string())
+                .with_code(ErrorCode::FileRejected));
+        }
+
         Ok((file_path, created_file))
     }
 
     /// 验证并上传封面文件
     pub async fn validate_and_upload_cover(
         db: &DatabaseConnection,
-        s3_config: &S3Config,
+        storage: &dyn StorageBackend,
         content: Vec<u8>,
         _filename: &str,
     ) -> ApiResult<files::Model> {
@@ -183,17 +310,16 @@ impl FileUploadService {
         // 转换为 WebP
         let webp_content = Self::convert_to_webp(&content)?;
 
-        // 上传到 S3
-        let (_url, file_model) =
-            Self::upload_file_to_s3(db, s3_config, webp_content, "cover.webp").await?;
+        // 上传到存储
+        let (_url, file_model) = Self::upload_file(db, storage, webp_content, "cover.webp").await?;
 
-        Ok(file_model)
+        Self::ensure_variants(db, storage, file_model, content).await
     }
 
     /// 验证并上传画册图片文件
     pub async fn validate_and_upload_gallery(
         db: &DatabaseConnection,
-        s3_config: &S3Config,
+        storage: &dyn StorageBackend,
         content: Vec<u8>,
         _filename: &str,
     ) -> ApiResult<files::Model> {
@@ -205,28 +331,212 @@ impl FileUploadService {
         }
 
         // 尝试打开图片
-        let _img = image::load_from_memory(&content)
-            .map_err(|_| ApiError::BadRequest("图片文件无效".to_string()))?;
+        let _img = image::load_from_memory(&content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
 
         // 检查图片格式
-        let format = image::guess_format(&content)
-            .map_err(|_| ApiError::BadRequest("无法识别图片格式".to_string()))?;
+        let format = image::guess_format(&content).map_err(|_| {
+            ApiError::BadRequest("无法识别图片格式".to_string())
+                .with_code(ErrorCode::InvalidImageFormat)
+        })?;
 
         match format {
             ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP => {}
             _ => {
-                return Err(ApiError::BadRequest("图片文件格式无效".to_string()));
+                return Err(ApiError::BadRequest("图片文件格式无效".to_string())
+                    .with_code(ErrorCode::InvalidImageFormat));
             }
         }
 
         // 转换为 WebP
         let webp_content = Self::convert_to_webp(&content)?;
 
-        // 上传到 S3
+        // 上传到存储
         let (_url, file_model) =
-            Self::upload_file_to_s3(db, s3_config, webp_content, "gallery.webp").await?;
+            Self::upload_file(db, storage, webp_content, "gallery.webp").await?;
 
-        Ok(file_model)
+        Self::ensure_variants(db, storage, file_model, content).await
+    }
+
+    /// 为已上传的图片生成并上传缩略图，已有缩略图或不在本存储中的文件直接返回
+    ///
+    /// 缩略图上传失败时只记录日志，图片仍可使用，接口返回的缩略图地址退回原图。
+    async fn ensure_variants(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        file_model: files::Model,
+        content: Vec<u8>,
+    ) -> ApiResult<files::Model> {
+        if file_model.has_variants {
+            return Ok(file_model);
+        }
+        let Some(key) = storage.object_key(&file_model.file_path) else {
+            return Ok(file_model);
+        };
+        let key = key.to_string();
+
+        // 缩放比较耗时，放到阻塞线程池中执行
+        let variants = tokio::task::spawn_blocking(move || Self::process_variants(&content))
+            .await
+            .map_err(|_| ApiError::Internal("缩略图处理任务失败".to_string()))??;
+
+        for (variant, data) in variants {
+            let variant_key = variant.path_of(&key);
+            if let Err(e) = storage.put(&variant_key, data).await {
+                tracing::warn!("⚠️  上传缩略图 {} 失败: {}", variant_key, e);
+                return Ok(file_model);
+            }
+        }
+
+        let mut active: files::ActiveModel = file_model.into();
+        active.has_variants = Set(true);
+        Ok(active.update(db.as_ref()).await?)
+    }
+
+    fn process_variants(content: &[u8]) -> ApiResult<Vec<(ImageVariant, Vec<u8>)>> {
+        let img = image::load_from_memory(content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
+        let (width, _) = img.dimensions();
+
+        ImageVariant::ALL
+            .into_iter()
+            .map(|variant| {
+                let max_width = variant.max_width();
+                let resized = if width > max_width {
+                    img.resize(max_width, u32::MAX, FilterType::Lanczos3)
+                } else {
+                    img.clone()
+                };
+                let mut webp_data = Vec::new();
+                resized
+                    .write_to(&mut Cursor::new(&mut webp_data), ImageFormat::WebP)
+                    .map_err(|_| ApiError::Internal("图片格式转换失败".to_string()))?;
+                Ok((variant, webp_data))
+            })
+            .collect()
+    }
+
+    /// 按指定的对象键上传到 S3，不做去重与安全扫描
+    pub async fn put_object(s3_config: &S3Config, key: &str, content: Vec<u8>) -> ApiResult<()> {
+        let credentials = Self::create_s3_credentials(s3_config);
+        let bucket = Self::create_s3_bucket(s3_config)
+            .map_err(|e| ApiError::Internal(format!("S3 bucket 配置失败: {e}")))?;
+        let url = bucket
+            .put_object(Some(&credentials), key)
+            .sign(Duration::from_secs(3600));
+
+        Self::send_s3_request(s3_config, "put", key, || {
+            s3_config.http.put(url.clone()).body(content.clone())
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("文件上传失败: {e}")))
+    }
+
+    /// 处理并上传用户头像
+    ///
+    /// 非正方形图片居中裁剪为正方形，再缩放为 [`AVATAR_SIZE`] 与 [`AVATAR_SMALL_SIZE`]
+    /// 两种尺寸并转换为 WebP，返回 (标准尺寸文件, 小尺寸文件)。
+    pub async fn validate_and_upload_avatar(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        content: Vec<u8>,
+    ) -> ApiResult<(files::Model, files::Model)> {
+        // 检查文件大小（5MB 限制）
+        if content.len() > 5 * 1024 * 1024 {
+            return Err(ApiError::BadRequest(
+                "图片文件大小不能超过 5 MB".to_string(),
+            ));
+        }
+
+        // 裁剪与缩放比较耗时，放到阻塞线程池中执行
+        let (large, small) = tokio::task::spawn_blocking(move || Self::process_avatar(&content))
+            .await
+            .map_err(|_| ApiError::Internal("头像处理任务失败".to_string()))??;
+
+        let (_url, large) = Self::upload_file(db, storage, large, "avatar.webp").await?;
+        let (_url, small) = Self::upload_file(db, storage, small, "avatar-small.webp").await?;
+
+        Ok((large, small))
+    }
+
+    fn process_avatar(content: &[u8]) -> ApiResult<(Vec<u8>, Vec<u8>)> {
+        let format = image::guess_format(content).map_err(|_| {
+            ApiError::BadRequest("无法识别图片格式".to_string())
+                .with_code(ErrorCode::InvalidImageFormat)
+        })?;
+        match format {
+            ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP => {}
+            _ => {
+                return Err(ApiError::BadRequest("图片文件格式无效".to_string())
+                    .with_code(ErrorCode::InvalidImageFormat));
+            }
+        }
+
+        let img = image::load_from_memory(content).map_err(|_| {
+            ApiError::BadRequest("图片文件无效".to_string()).with_code(ErrorCode::InvalidImage)
+        })?;
+        let (width, height) = img.dimensions();
+        let side = width.min(height);
+        if side < AVATAR_SMALL_SIZE {
+            return Err(ApiError::BadRequest(format!(
+                "头像尺寸不能小于 {AVATAR_SMALL_SIZE}*{AVATAR_SMALL_SIZE}"
+            )));
+        }
+        let square = img.crop_imm((width - side) / 2, (height - side) / 2, side, side);
+
+        let encode = |size: u32| -> ApiResult<Vec<u8>> {
+            let mut webp_data = Vec::new();
+            square
+                .resize_exact(size, size, FilterType::Lanczos3)
+                .write_to(&mut Cursor::new(&mut webp_data), ImageFormat::WebP)
+                .map_err(|_| ApiError::Internal("图片格式转换失败".to_string()))?;
+            Ok(webp_data)
+        };
+
+        Ok((encode(AVATAR_SIZE)?, encode(AVATAR_SMALL_SIZE)?))
+    }
+
+    /// 从数据库中保存的文件地址解析出 S3 对象键
+    pub fn object_key_from_path<'a>(s3_config: &S3Config, file_path: &'a str) -> Option<&'a str> {
+        let prefix = format!("{}/{}/", s3_config.endpoint_url, s3_config.bucket);
+        file_path.strip_prefix(&prefix)
+    }
+
+    /// 在存储桶内复制对象，可同时指定目标对象的存储类型
+    pub async fn copy_object(
+        s3_config: &S3Config,
+        source_key: &str,
+        dest_key: &str,
+        storage_class: Option<&str>,
+    ) -> ApiResult<()> {
+        let credentials = Self::create_s3_credentials(s3_config);
+        let bucket = Self::create_s3_bucket(s3_config)
+            .map_err(|e| ApiError::Internal(format!("S3 配置错误: {e}")))?;
+
+        let copy_source = format!("/{}/{}", s3_config.bucket, source_key);
+        let mut action = bucket.put_object(Some(&credentials), dest_key);
+        action
+            .headers_mut()
+            .insert("x-amz-copy-source", copy_source.clone());
+        if let Some(class) = storage_class {
+            action.headers_mut().insert("x-amz-storage-class", class);
+        }
+        let url = action.sign(Duration::from_secs(300));
+
+        Self::send_s3_request(s3_config, "copy", dest_key, || {
+            let mut request = s3_config
+                .http
+                .put(url.clone())
+                .header("x-amz-copy-source", &copy_source);
+            if let Some(class) = storage_class {
+                request = request.header("x-amz-storage-class", class);
+            }
+            request
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("复制 S3 文件失败: {e}")))
     }
 
     /// 删除 S3 中的文件
@@ -238,17 +548,142 @@ impl FileUploadService {
         let delete_action = bucket.delete_object(Some(&credentials), hash_id);
         let url = delete_action.sign(Duration::from_secs(60));
 
-        let client = HttpClient::new();
-        let response = client
-            .delete(url.as_str())
-            .send()
-            .await
-            .map_err(|e| ApiError::Internal(format!("删除文件失败: {e}")))?;
+        Self::send_s3_request(s3_config, "delete", hash_id, || {
+            s3_config.http.delete(url.as_str())
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("删除 S3 文件失败: {e}")))
+    }
+
+    /// 删除原图旁的缩略图，失败只记录日志
+    pub async fn delete_variants(storage: &dyn StorageBackend, key: &str) {
+        for variant in ImageVariant::ALL {
+            let variant_key = variant.path_of(key);
+            if let Err(e) = storage.delete(&variant_key).await {
+                tracing::warn!("⚠️  删除缩略图 {} 失败: {}", variant_key, e);
+            }
+        }
+    }
+
+    /// 检查存储桶可访问且凭据有效，列出至多一个对象
+    pub async fn verify_bucket(s3_config: &S3Config) -> ApiResult<()> {
+        let credentials = Self::create_s3_credentials(s3_config);
+        let bucket = Self::create_s3_bucket(s3_config)
+            .map_err(|e| ApiError::Internal(format!("S3 配置错误: {e}")))?;
+
+        let mut action = bucket.list_objects_v2(Some(&credentials));
+        action.with_max_keys(1);
+        let url = action.sign(Duration::from_secs(60));
+
+        Self::send_s3_request(s3_config, "list", &s3_config.bucket, || {
+            s3_config.http.get(url.as_str())
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("存储桶 {} 不可访问: {e}", s3_config.bucket)))
+    }
+
+    /// 发送 S3 请求，遇到网络错误、限流或 5xx 时按指数退避并加入随机抖动重试
+    ///
+    /// `build` 在每次尝试时调用以构造新的请求；返回的错误信息用于拼接给调用方的错误。
+    async fn send_s3_request<F>(
+        s3_config: &S3Config,
+        operation: &'static str,
+        key: &str,
+        build: F,
+    ) -> std::result::Result<(), String>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        let outcome = loop {
+            let (retryable, error) = match build().send().await {
+                Ok(response) if response.status().is_success() => break Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    (
+                        Self::is_retryable_status(status),
+                        format!("状态码: {status}"),
+                    )
+                }
+                Err(e) => (e.is_timeout() || e.is_connect(), e.to_string()),
+            };
+            if !retryable || attempt >= s3_config.max_retries {
+                break Err((retryable, error));
+            }
+
+            attempt += 1;
+            let delay = Self::retry_delay(s3_config.retry_base_delay_ms, attempt);
+            tracing::warn!(
+                "⚠️  S3 {} 请求失败，{}ms 后进行第 {} 次重试: key={}, {}",
+                operation,
+                delay.as_millis(),
+                attempt,
+                key,
+                error
+            );
+            MetricsService::inc_counter(
+                "s3_retries_total",
+                "S3 请求重试次数",
+                &[("operation", operation)],
+                1.0,
+            );
+            tokio::time::sleep(delay).await;
+        };
 
-        if !response.status().is_success() {
-            return Err(ApiError::Internal("删除 S3 文件失败".to_string()));
+        let result = match &outcome {
+            Ok(()) => "success",
+            Err((true, _)) => "retries_exhausted",
+            Err((false, _)) => "fatal",
+        };
+        MetricsService::inc_counter(
+            "s3_requests_total",
+            "S3 请求数",
+            &[("operation", operation), ("result", result)],
+            1.0,
+        );
+        MetricsService::observe(
+            "s3_request_duration_seconds",
+            "S3 请求耗时（含重试）",
+            &[("operation", operation)],
+            start.elapsed().as_secs_f64(),
+        );
+
+        match outcome {
+            Ok(()) => {
+                tracing::debug!(
+                    "S3 {} 请求成功: key={}, 重试 {} 次, 耗时 {}ms",
+                    operation,
+                    key,
+                    attempt,
+                    start.elapsed().as_millis()
+                );
+                Ok(())
+            }
+            Err((_, error)) => {
+                tracing::warn!(
+                    "⚠️  S3 {} 请求失败: key={}, 重试 {} 次, {}",
+                    operation,
+                    key,
+                    attempt,
+                    error
+                );
+                Err(error)
+            }
         }
+    }
+
+    /// 超时、限流与服务端错误可以重试；其他 4xx（签名、权限、对象不存在等）重试也不会成功
+    fn is_retryable_status(status: StatusCode) -> bool {
+        (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+    }
 
-        Ok(())
+    /// 第 `attempt` 次重试的等待时间，在指数退避上限的一半到上限之间随机
+    fn retry_delay(base_ms: u64, attempt: u32) -> Duration {
+        let ceiling = base_ms.saturating_mul(1 << attempt.saturating_sub(1).min(10));
+        Duration::from_millis(rand::rng().random_range(ceiling / 2..=ceiling))
     }
 }
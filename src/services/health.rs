@@ -0,0 +1,118 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, Statement};
+
+use crate::{
+    schemas::meta::{ComponentState, DependencyHealth, DependencyStatus, HealthReport},
+    services::{
+        database::DatabaseConnection, redis::RedisService, search::client::MeilisearchClient,
+        storage::StorageBackend,
+    },
+};
+
+/// 单个依赖检查的超时时间，需短于编排系统的探针超时
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 就绪检查
+///
+/// 每次请求都实时检查依赖，不使用状态页的采样记录。只有数据库是关键依赖：
+/// Redis、搜索引擎与文件存储不可用时相关功能降级但实例仍可服务，
+/// 若因此摘除全部实例反而会造成整体不可用。
+pub struct HealthService;
+
+impl HealthService {
+    /// 并发检查数据库、Redis、搜索引擎与文件存储
+    pub async fn readiness(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+    ) -> HealthReport {
+        let (database, redis, search, storage) = tokio::join!(
+            Self::check("database", true, async {
+                let backend = db.get_database_backend();
+                db.execute(Statement::from_string(backend, "SELECT 1"))
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }),
+            Self::check("redis", false, async {
+                let redis = RedisService::instance().ok_or("Redis 未初始化")?;
+                let status = redis.health_check().await.map_err(|e| e.to_string())?;
+                match status.error {
+                    Some(error) if !status.connected => Err(error),
+                    _ => Ok(()),
+                }
+            }),
+            Self::check("search", false, async {
+                let client = MeilisearchClient::instance().map_err(|e| e.to_string())?;
+                client.health_check().await.map_err(|e| e.to_string())
+            }),
+            async {
+                match storage {
+                    Some(storage) => {
+                        Self::check("storage", false, async {
+                            storage.verify().await.map_err(|e| e.to_string())
+                        })
+                        .await
+                    }
+                    None => DependencyHealth {
+                        name: "storage".to_string(),
+                        status: DependencyStatus::Disabled,
+                        critical: false,
+                        latency_ms: None,
+                        error: None,
+                    },
+                }
+            },
+        );
+
+        let dependencies = vec![database, redis, search, storage];
+        let down = |critical: bool| {
+            dependencies
+                .iter()
+                .any(|d| d.critical == critical && d.status == DependencyStatus::Down)
+        };
+        let status = if down(true) {
+            ComponentState::Outage
+        } else if down(false) {
+            ComponentState::Degraded
+        } else {
+            ComponentState::Operational
+        };
+
+        HealthReport {
+            status,
+            checked_at: Utc::now(),
+            dependencies,
+        }
+    }
+
+    async fn check<F, E>(name: &str, critical: bool, check: F) -> DependencyHealth
+    where
+        F: Future<Output = Result<(), E>>,
+        E: ToString,
+    {
+        let start = Instant::now();
+        let error = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("检查超时（{} 秒）", CHECK_TIMEOUT.as_secs())),
+        };
+        if let Some(error) = &error {
+            tracing::warn!("⚠️  就绪检查 {} 失败: {}", name, error);
+        }
+
+        DependencyHealth {
+            name: name.to_string(),
+            status: if error.is_none() {
+                DependencyStatus::Up
+            } else {
+                DependencyStatus::Down
+            },
+            critical,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::services::{
+    cache::ServerCacheService,
+    events::{DomainEvent, EventBus},
+    metrics::MetricsService,
+};
+
+/// 推送通道容量，客户端落后超过该数量时会收到重新同步通知
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
+/// 推送给实时订阅客户端的消息
+#[derive(Debug, Clone)]
+pub enum LiveUpdate {
+    Event(DomainEvent),
+    /// 事件丢失，客户端需要重新拉取全部数据
+    Resync,
+}
+
+static LIVE_SENDER: Lazy<broadcast::Sender<LiveUpdate>> =
+    Lazy::new(|| broadcast::channel(LIVE_CHANNEL_CAPACITY).0);
+
+/// 实时更新分发服务
+///
+/// 服务器数据变更的唯一出口：订阅事件总线，先清除相关缓存，再推送给已订阅的客户端，
+/// 保证客户端收到通知后重新拉取时不会读到旧缓存。
+pub struct LiveUpdateService;
+
+impl LiveUpdateService {
+    /// 持续分发事件总线上的事件
+    pub async fn run_dispatch_loop() {
+        let mut events = EventBus::subscribe();
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    ServerCacheService::invalidate(&event).await;
+                    let _ = LIVE_SENDER.send(LiveUpdate::Event(event));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(
+                        "⚠️  实时更新分发落后，丢失 {} 个事件，清除全部缓存",
+                        skipped
+                    );
+                    ServerCacheService::invalidate_all().await;
+                    let _ = LIVE_SENDER.send(LiveUpdate::Resync);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// 订阅缓存清除后的实时更新
+    pub fn subscribe() -> LiveSubscription {
+        let receiver = LIVE_SENDER.subscribe();
+        Self::record_subscribers(LIVE_SENDER.receiver_count());
+        LiveSubscription { receiver }
+    }
+
+    fn record_subscribers(count: usize) {
+        MetricsService::set_gauge(
+            "live_update_subscribers",
+            "实时更新订阅中的客户端数",
+            &[],
+            count as f64,
+        );
+    }
+}
+
+/// 单个客户端的订阅，断开时自动更新订阅数
+pub struct LiveSubscription {
+    receiver: broadcast::Receiver<LiveUpdate>,
+}
+
+impl LiveSubscription {
+    /// 等待下一条更新，客户端处理过慢丢失消息时返回 [`LiveUpdate::Resync`]
+    pub async fn next(&mut self) -> Option<LiveUpdate> {
+        match self.receiver.recv().await {
+            Ok(update) => Some(update),
+            Err(RecvError::Lagged(_)) => Some(LiveUpdate::Resync),
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+impl Drop for LiveSubscription {
+    fn drop(&mut self) {
+        // 此时自身的接收端尚未释放
+        LiveUpdateService::record_subscribers(LIVE_SENDER.receiver_count().saturating_sub(1));
+    }
+}
@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use ammonia::{Builder, UrlRelative};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+
+/// 描述中最多保留的图片数，超出的图片在写入时移除
+const MAX_IMAGES: usize = 10;
+/// 允许出现在描述中的 HTML 标签，与渲染器支持的语法一一对应
+const ALLOWED_TAGS: [&str; 20] = [
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "p",
+    "br",
+    "hr",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "pre",
+    "code",
+    "strong",
+    "em",
+    "del",
+    "a",
+    "img",
+];
+/// 连同内容一起移除的元素，与 ammonia 的默认设置一致
+const CONTENT_TAGS: [&str; 2] = ["script", "style"];
+/// 链接与图片允许的协议，站内地址见 [`local_url`]
+const URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+static HTML_POLICY: Lazy<Builder<'static>> = Lazy::new(|| html_policy(true));
+/// 图片数已达上限后清理原文 HTML 使用，去掉其中的 `<img>`
+static HTML_POLICY_NO_IMAGES: Lazy<Builder<'static>> = Lazy::new(|| html_policy(false));
+/// 单个开始或结束标签，行内 HTML 以这种形式出现
+static SINGLE_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^<(/?)([a-zA-Z][a-zA-Z0-9]*)\b[^<>]*>$").unwrap());
+
+fn html_policy(images: bool) -> Builder<'static> {
+    let mut tags: HashSet<&str> = ALLOWED_TAGS.into_iter().collect();
+    if !images {
+        tags.remove("img");
+    }
+    let mut builder = Builder::default();
+    builder
+        .tags(tags)
+        .generic_attributes(HashSet::new())
+        .tag_attributes(HashMap::from([
+            ("a", HashSet::from(["href"])),
+            ("img", HashSet::from(["src", "alt"])),
+        ]))
+        .url_schemes(URL_SCHEMES.into_iter().collect())
+        .url_relative(UrlRelative::Custom(Box::new(local_url)))
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .set_tag_attribute_value("img", "loading", "lazy");
+    builder
+}
+
+/// 站内地址：以 `/` 开头的路径与页内锚点；`//` 与 `/\` 会被浏览器当作外部地址，不算在内
+fn local_url(url: &str) -> Option<Cow<'_, str>> {
+    let local = url.starts_with('#')
+        || (url.starts_with('/') && !url.starts_with("//") && !url.starts_with("/\\"));
+    local.then_some(Cow::Borrowed(url))
+}
+
+/// 服务器描述的 Markdown 处理
+///
+/// 描述以 Markdown 原文存储。写入前调用 [`MarkdownService::sanitize`]：原文中的 HTML
+/// 经 ammonia 按白名单清理，不安全的链接与图片以及超出数量的图片被移除，
+/// 自行渲染的客户端拿到的原文也是安全的；[`MarkdownService::render_html`] 用
+/// pulldown-cmark 解析，原文中的 HTML 一律转义，输出再经同一白名单清理，
+/// 链接只允许 http(s)、mailto 与站内地址。
+pub struct MarkdownService;
+
+impl MarkdownService {
+    /// 写入前清理描述
+    ///
+    /// 只改写有问题的片段，其余原文保持不变。
+    pub fn sanitize(markdown: &str) -> String {
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        let mut images = 0;
+        let mut in_code_block = false;
+        // 地址不安全的链接：整段替换为其中的文字
+        let mut unsafe_link: Option<(Range<usize>, String)> = None;
+        // 被整段移除的图片结束位置，其中的事件不再处理
+        let mut skip_until = 0;
+        // 行内出现的 `<script>` 等元素，到对应的结束标签为止内容一并移除
+        let mut content_tag: Option<String> = None;
+
+        for (event, range) in Parser::new_ext(markdown, Self::options()).into_offset_iter() {
+            if range.start < skip_until {
+                continue;
+            }
+            if let Some(name) = &content_tag {
+                match event {
+                    Event::InlineHtml(raw) => {
+                        if Self::single_tag(&raw).is_some_and(|(close, tag)| close && &tag == name)
+                        {
+                            content_tag = None;
+                        }
+                        edits.push((range, String::new()));
+                    }
+                    Event::Text(_) | Event::Code(_) => edits.push((range, String::new())),
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some((_, label)) = unsafe_link.as_mut() {
+                match event {
+                    Event::Text(text) | Event::Code(text) => label.push_str(&text),
+                    Event::End(TagEnd::Link) => {
+                        let (range, label) = unsafe_link.take().unwrap();
+                        edits.push((range, Self::escape_markdown(&label)));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::InlineHtml(raw)
+                    if Self::single_tag(&raw).is_some_and(|(close, tag)| {
+                        !close && CONTENT_TAGS.contains(&tag.as_str())
+                    }) =>
+                {
+                    content_tag = Self::single_tag(&raw).map(|(_, tag)| tag);
+                    edits.push((range, String::new()));
+                }
+                Event::Html(raw) | Event::InlineHtml(raw) => {
+                    let cleaned = Self::clean_html(&raw, &mut images);
+                    if cleaned != *raw {
+                        edits.push((range, cleaned));
+                    }
+                }
+                Event::Text(_) if !in_code_block => {
+                    // CommonMark 不当作 HTML 的尖括号（如 `<svg/onload=…>`）原样保留在文字里，
+                    // 转义后宽松的渲染器也不会把它当作标签
+                    let source = &markdown[range.clone()];
+                    let escaped = Self::escape_angle_brackets(source);
+                    if escaped != source {
+                        edits.push((range, escaped));
+                    }
+                }
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    images += 1;
+                    if images > MAX_IMAGES || !Self::is_safe_url(&dest_url) {
+                        skip_until = range.end;
+                        edits.push((range, String::new()));
+                    }
+                }
+                Event::Start(Tag::Link { dest_url, .. }) if !Self::is_safe_url(&dest_url) => {
+                    unsafe_link = Some((range, String::new()));
+                }
+                _ => {}
+            }
+        }
+
+        let mut sanitized = String::with_capacity(markdown.len());
+        let mut cursor = 0;
+        for (range, replacement) in edits {
+            sanitized.push_str(&markdown[cursor..range.start]);
+            sanitized.push_str(&replacement);
+            cursor = range.end;
+        }
+        sanitized.push_str(&markdown[cursor..]);
+        sanitized
+    }
+
+    /// 渲染为 HTML，结果可以直接插入页面
+    pub fn render_html(markdown: &str) -> String {
+        // 每层链接或图片的地址是否安全，不安全的只输出其中的文字
+        let mut safe_urls = Vec::new();
+        let events = Parser::new_ext(markdown, Self::options()).filter_map(|event| match event {
+            // 原文中的 HTML 按文字输出
+            Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+            // 块内的换行渲染为 `<br>`，与大多数服主预期的排版一致
+            Event::SoftBreak => Some(Event::HardBreak),
+            Event::Start(Tag::Link { ref dest_url, .. } | Tag::Image { ref dest_url, .. }) => {
+                let safe = Self::is_safe_url(dest_url);
+                safe_urls.push(safe);
+                safe.then_some(event)
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                safe_urls.pop().unwrap_or(true).then_some(event)
+            }
+            event => Some(event),
+        });
+        let mut rendered = String::new();
+        html::push_html(&mut rendered, events);
+        HTML_POLICY.clean(&rendered).to_string()
+    }
+
+    /// 取第一段文字作为一句话简介，去掉 Markdown 标记与 HTML 标签，
+    /// 超过 `max_chars` 个字符时截断并以省略号结尾；没有文字时返回空字符串
+    pub fn intro(markdown: &str, max_chars: usize) -> String {
+        let mut text = String::new();
+        let mut in_code_block = false;
+        let mut in_image = false;
+        for event in Parser::new_ext(markdown, Self::options()) {
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Start(Tag::Image { .. }) => in_image = true,
+                Event::End(TagEnd::Image) => in_image = false,
+                Event::Text(t) | Event::Code(t) if !in_code_block && !in_image => text.push_str(&t),
+                // 一行文字结束
+                Event::SoftBreak
+                | Event::HardBreak
+                | Event::Start(Tag::List(_))
+                | Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                    let plain = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !plain.is_empty() {
+                        return Self::truncate(plain, max_chars);
+                    }
+                    text.clear();
+                }
+                _ => {}
+            }
+        }
+        Self::truncate(
+            text.split_whitespace().collect::<Vec<_>>().join(" "),
+            max_chars,
+        )
+    }
+
+    fn options() -> Options {
+        Options::ENABLE_STRIKETHROUGH
+    }
+
+    fn truncate(plain: String, max_chars: usize) -> String {
+        if plain.chars().count() <= max_chars {
+            return plain;
+        }
+        let mut truncated: String = plain.chars().take(max_chars.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+
+    /// 清理原文中的一段 HTML，并累计其中保留的图片数
+    fn clean_html(raw: &str, images: &mut usize) -> String {
+        let policy = if *images >= MAX_IMAGES {
+            &*HTML_POLICY_NO_IMAGES
+        } else {
+            &*HTML_POLICY
+        };
+        let html = raw.trim_end();
+        let mut cleaned = policy.clean(html).to_string();
+        // 单个标签经 ammonia 清理后会补上闭合标签，这里还原为单个标签，
+        // 与原文中别处的另一半配对
+        if let Some((close, name)) = Self::single_tag(html) {
+            if close {
+                cleaned = if ALLOWED_TAGS.contains(&name.as_str()) {
+                    format!("</{name}>")
+                } else {
+                    String::new()
+                };
+            } else if let Some(open) = cleaned.strip_suffix(&format!("</{name}>")) {
+                cleaned = open.to_string();
+            }
+        }
+        *images += cleaned.matches("<img").count();
+        cleaned.push_str(&raw[html.len()..]);
+        cleaned
+    }
+
+    /// 单个标签：返回是否为结束标签及小写的标签名
+    fn single_tag(html: &str) -> Option<(bool, String)> {
+        SINGLE_TAG
+            .captures(html.trim_end())
+            .map(|tag| (&tag[1] == "/", tag[2].to_ascii_lowercase()))
+    }
+
+    /// 转义未被反斜杠转义的 `<`
+    fn escape_angle_brackets(source: &str) -> String {
+        let mut escaped = String::with_capacity(source.len());
+        let mut backslash = false;
+        for c in source.chars() {
+            if c == '<' && !backslash {
+                escaped.push_str("&lt;");
+            } else {
+                escaped.push(c);
+            }
+            backslash = c == '\\' && !backslash;
+        }
+        escaped
+    }
+
+    /// 转义标点，替换进原文的文字不会再被解析为 Markdown 或 HTML
+    fn escape_markdown(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_ascii_punctuation() {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// 链接与图片地址，与渲染时的 HTML 白名单一致；实体已由解析器还原
+    fn is_safe_url(url: &str) -> bool {
+        let lower = url.to_ascii_lowercase();
+        URL_SCHEMES
+            .iter()
+            .any(|scheme| lower.starts_with(&format!("{scheme}:")))
+            || local_url(url).is_some()
+    }
+}
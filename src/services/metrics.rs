@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Mutex, MutexGuard},
+};
+
+use once_cell::sync::Lazy;
+
+/// 直方图默认分桶（秒）
+const DEFAULT_BUCKETS: [f64; 11] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+enum Series {
+    Value(f64),
+    Histogram(Histogram),
+}
+
+struct Family {
+    help: &'static str,
+    kind: MetricKind,
+    /// 键为已渲染的标签串，如 `method="GET",status="200"`
+    series: BTreeMap<String, Series>,
+}
+
+static REGISTRY: Lazy<Mutex<BTreeMap<&'static str, Family>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// 指标服务
+///
+/// 进程内的轻量指标注册表，由 `/metrics` 以 Prometheus 文本格式导出。
+/// 指标在首次写入时自动注册，同名指标的类型必须一致。
+pub struct MetricsService;
+
+impl MetricsService {
+    /// 计数器累加
+    pub fn inc_counter(
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        Self::with_series(name, help, MetricKind::Counter, labels, |series| {
+            if let Series::Value(v) = series {
+                *v += value;
+            }
+        });
+    }
+
+    /// 设置仪表盘的当前值
+    pub fn set_gauge(name: &'static str, help: &'static str, labels: &[(&str, &str)], value: f64) {
+        Self::with_series(name, help, MetricKind::Gauge, labels, |series| {
+            if let Series::Value(v) = series {
+                *v = value;
+            }
+        });
+    }
+
+    /// 记录一次直方图观测值（秒）
+    pub fn observe(name: &'static str, help: &'static str, labels: &[(&str, &str)], value: f64) {
+        Self::with_series(name, help, MetricKind::Histogram, labels, |series| {
+            if let Series::Histogram(h) = series {
+                if h.buckets.is_empty() {
+                    h.buckets = vec![0; DEFAULT_BUCKETS.len()];
+                }
+                for (bucket, bound) in h.buckets.iter_mut().zip(DEFAULT_BUCKETS) {
+                    if value <= bound {
+                        *bucket += 1;
+                    }
+                }
+                h.sum += value;
+                h.count += 1;
+            }
+        });
+    }
+
+    /// 以 Prometheus 文本格式导出全部指标
+    pub fn render() -> String {
+        let registry = Self::registry();
+        let mut out = String::new();
+
+        for (name, family) in registry.iter() {
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", name, family.kind.as_str());
+
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(v) => {
+                        let _ = writeln!(out, "{}{} {}", name, Self::wrap_labels(labels), v);
+                    }
+                    Series::Histogram(h) => {
+                        for (bucket, bound) in h.buckets.iter().zip(DEFAULT_BUCKETS) {
+                            let le = Self::join_labels(labels, &format!("le=\"{bound}\""));
+                            let _ = writeln!(out, "{name}_bucket{{{le}}} {bucket}");
+                        }
+                        let le = Self::join_labels(labels, "le=\"+Inf\"");
+                        let _ = writeln!(out, "{}_bucket{{{}}} {}", name, le, h.count);
+                        let _ =
+                            writeln!(out, "{}_sum{} {}", name, Self::wrap_labels(labels), h.sum);
+                        let _ = writeln!(
+                            out,
+                            "{}_count{} {}",
+                            name,
+                            Self::wrap_labels(labels),
+                            h.count
+                        );
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    fn with_series(
+        name: &'static str,
+        help: &'static str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        update: impl FnOnce(&mut Series),
+    ) {
+        let mut registry = Self::registry();
+        let family = registry.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            series: BTreeMap::new(),
+        });
+
+        if family.kind != kind {
+            tracing::warn!("指标 {} 的类型不一致，已忽略本次写入", name);
+            return;
+        }
+
+        let series = family
+            .series
+            .entry(Self::render_labels(labels))
+            .or_insert_with(|| match kind {
+                MetricKind::Histogram => Series::Histogram(Histogram::default()),
+                _ => Series::Value(0.0),
+            });
+        update(series);
+    }
+
+    fn registry() -> MutexGuard<'static, BTreeMap<&'static str, Family>> {
+        // 指标写入不会在持锁期间 panic，中毒时直接沿用内部数据
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn render_labels(labels: &[(&str, &str)]) -> String {
+        labels
+            .iter()
+            .map(|(k, v)| {
+                let escaped = v
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                format!("{k}=\"{escaped}\"")
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn wrap_labels(labels: &str) -> String {
+        if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        }
+    }
+
+    fn join_labels(labels: &str, extra: &str) -> String {
+        if labels.is_empty() {
+            extra.to_string()
+        } else {
+            format!("{labels},{extra}")
+        }
+    }
+}
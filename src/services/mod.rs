@@ -1,11 +1,63 @@
+pub mod account;
+pub mod account_link;
+pub mod activity;
+pub mod api_key;
+pub mod archive;
 pub mod auth;
+pub mod ban;
+pub mod cache;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod confirm;
+pub mod custom_fields;
 pub mod database;
+pub mod delisting;
+#[cfg(feature = "dev-tools")]
+pub mod dev_tools;
 pub mod email;
+pub mod embeddings;
+pub mod events;
+pub mod favorite;
+pub mod feature_flags;
+pub mod feed;
+pub mod file_gc;
 pub mod file_upload;
+pub mod health;
+pub mod live;
+pub mod markdown;
+pub mod metrics;
+pub mod moderation;
+pub mod motd;
+pub mod motd_image;
+pub mod name_policy;
+pub mod notification;
+pub mod oauth;
+pub mod password;
+pub mod ping;
+pub mod player_index;
+pub mod preferences;
 pub mod redis;
+pub mod registration_guard;
+pub mod revision;
+pub mod sandbox;
 pub mod search;
 pub mod server;
+pub mod signing;
+pub mod similar;
+pub mod sitemap;
+pub mod spam_guard;
+pub mod stats_history;
+pub mod status;
+pub mod storage;
+pub mod tag_suggest;
+pub mod tags;
+pub mod tenant;
+pub mod timeline;
+pub mod translation;
+pub mod upload_scan;
+pub mod uptime;
 pub mod utils;
 pub use file_upload::FileUploadService;
 pub use redis::RedisService;
 pub use server::ServerService;
+pub use signing::SigningService;
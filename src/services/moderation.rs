@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        prelude::{Server, UserServer},
+        server, user_server,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{AdminServerInfo, AdminServerQuery},
+        servers::{ServerManagerRole, ServerStatus, ServerVisibility, UpdateServerRequest},
+        users::NotificationKind,
+    },
+    services::{
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+        notification::{Notification, NotificationService},
+        revision::ServerRevisionService,
+        server::ServerService,
+        storage::StorageBackend,
+    },
+};
+
+/// 站点管理员的服务器管理
+///
+/// 不要求是服务器成员，调用方需先确认操作者是站点管理员并且服务器属于当前租户。
+pub struct ServerModerationService;
+
+impl ServerModerationService {
+    /// 列出租户内的全部服务器，包括隐藏、下架与停用的服务器，按 ID 倒序
+    pub async fn list(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        query: &AdminServerQuery,
+    ) -> ApiResult<(Vec<AdminServerInfo>, u64)> {
+        let mut select = Server::find().filter(server::Column::TenantId.eq(tenant_id));
+        if let Some(keyword) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            select = select.filter(
+                Condition::any()
+                    .add(server::Column::Name.contains(keyword))
+                    .add(server::Column::Ip.contains(keyword))
+                    .add(server::Column::Slug.contains(keyword)),
+            );
+        }
+        if let Some(visibility) = query.visibility {
+            select = select.filter(server::Column::Visibility.eq(visibility.as_str()));
+        }
+        if let Some(status) = query.status {
+            select = select.filter(server::Column::Status.eq(status.as_str()));
+        }
+        match query.deactivated {
+            Some(true) => select = select.filter(server::Column::DeactivatedAt.is_not_null()),
+            Some(false) => select = select.filter(server::Column::DeactivatedAt.is_null()),
+            None => {}
+        }
+
+        let paginator = select
+            .order_by_desc(server::Column::Id)
+            .paginate(db.as_ref(), query.page_size);
+        let total = paginator.num_items().await?;
+        let servers = paginator.fetch_page(query.page.saturating_sub(1)).await?;
+
+        let server_ids: Vec<i32> = servers.iter().map(|server| server.id).collect();
+        let mut owners: HashMap<i32, Vec<i32>> = HashMap::new();
+        for owner in UserServer::find()
+            .filter(user_server::Column::ServerId.is_in(server_ids))
+            .filter(user_server::Column::Role.eq(ServerManagerRole::Owner.as_str()))
+            .all(db.as_ref())
+            .await?
+        {
+            owners
+                .entry(owner.server_id)
+                .or_default()
+                .push(owner.user_id);
+        }
+
+        let data = servers
+            .into_iter()
+            .map(|server| {
+                let owner_ids = owners.remove(&server.id).unwrap_or_default();
+                Self::to_info(server, owner_ids)
+            })
+            .collect();
+        Ok((data, total))
+    }
+
+    /// 强制编辑服务器，校验规则与服务器成员编辑相同
+    pub async fn force_update(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server_id: i32,
+        update_data: UpdateServerRequest,
+        admin_id: i32,
+    ) -> ApiResult<AdminServerInfo> {
+        let server = Self::find(db, server_id).await?;
+        let updated =
+            ServerService::apply_update(db, storage, server, update_data, admin_id).await?;
+        Self::info(db, updated).await
+    }
+
+    /// 设置服务器可见性
+    pub async fn set_visibility(
+        db: &DatabaseConnection,
+        server_id: i32,
+        visibility: ServerVisibility,
+        admin_id: i32,
+    ) -> ApiResult<AdminServerInfo> {
+        let server = Self::find(db, server_id).await?;
+        if ServerVisibility::of(&server) == visibility {
+            return Self::info(db, server).await;
+        }
+
+        let previous = server.clone();
+        let mut active: server::ActiveModel = server.into();
+        active.visibility = Set(visibility.as_str().to_string());
+        active.is_hide = Set(visibility != ServerVisibility::Public);
+
+        let txn = db.begin().await?;
+        let updated = active.update(&txn).await?;
+        ServerRevisionService::record(&txn, &previous, &updated, Some(admin_id), None).await?;
+        txn.commit().await?;
+        EventBus::publish(DomainEvent::server_updated(vec![updated.id]));
+
+        Self::info(db, updated).await
+    }
+
+    /// 审核服务器发布申请，通过后出现在列表与搜索中，驳回时原因会展示给服主
+    pub async fn review(
+        db: &DatabaseConnection,
+        server_id: i32,
+        approved: bool,
+        reason: Option<String>,
+    ) -> ApiResult<AdminServerInfo> {
+        let server = Self::find(db, server_id).await?;
+        if ServerStatus::of(&server) != ServerStatus::PendingReview {
+            return Err(ApiError::Conflict("服务器不在待审核状态".to_string()));
+        }
+
+        let name = server.name.clone();
+        let mut active: server::ActiveModel = server.into();
+        if approved {
+            active.status = Set(ServerStatus::Published.as_str().to_string());
+            active.review_note = Set(None);
+        } else {
+            active.status = Set(ServerStatus::Rejected.as_str().to_string());
+            active.review_note = Set(reason.clone());
+        }
+        let updated = active.update(db.as_ref()).await?;
+        if approved {
+            EventBus::publish(DomainEvent::server_updated(vec![updated.id]));
+        }
+
+        let (title, body) = if approved {
+            (
+                "服务器已通过审核",
+                format!("服务器「{name}」已通过审核，现已出现在服务器列表与搜索中。"),
+            )
+        } else {
+            (
+                "服务器未通过审核",
+                match reason.as_deref() {
+                    Some(reason) => {
+                        format!("服务器「{name}」未通过审核：{reason}。修改后可以重新提交。")
+                    }
+                    None => format!("服务器「{name}」未通过审核，修改后可以重新提交。"),
+                },
+            )
+        };
+        NotificationService::notify_server_owners(
+            db,
+            server_id,
+            Notification {
+                kind: NotificationKind::ServerReviewed,
+                title: title.to_string(),
+                body,
+            },
+        )
+        .await;
+
+        Self::info(db, updated).await
+    }
+
+    async fn find(db: &DatabaseConnection, server_id: i32) -> ApiResult<server::Model> {
+        Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)
+    }
+
+    async fn info(db: &DatabaseConnection, server: server::Model) -> ApiResult<AdminServerInfo> {
+        let owner_ids = UserServer::find()
+            .select_only()
+            .column(user_server::Column::UserId)
+            .filter(user_server::Column::ServerId.eq(server.id))
+            .filter(user_server::Column::Role.eq(ServerManagerRole::Owner.as_str()))
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+        Ok(Self::to_info(server, owner_ids))
+    }
+
+    fn to_info(server: server::Model, owner_ids: Vec<i32>) -> AdminServerInfo {
+        AdminServerInfo {
+            visibility: ServerVisibility::of(&server),
+            status: ServerStatus::of(&server),
+            id: server.id,
+            name: server.name,
+            ip: server.ip,
+            slug: server.slug,
+            review_note: server.review_note,
+            is_member: server.is_member,
+            owner_ids,
+            deactivated_at: server.deactivated_at,
+            delisted_at: server.delisted_at,
+            updated_at: server.updated_at,
+        }
+    }
+}
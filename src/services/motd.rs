@@ -0,0 +1,329 @@
+//! Minecraft MOTD 的解析与格式转换
+//!
+//! 不论 MOTD 来自本服务的探测、数据推送还是外部采集器写入的状态数据，
+//! 都以原始文本（§ 格式代码或 JSON 文本组件）为准重新生成纯文本、HTML 与 ANSI 格式，
+//! 不直接使用外部提供的 HTML。
+
+use serde_json::Value;
+
+use crate::schemas::servers::Motd;
+
+/// 颜色代码、名称、HTML 颜色与 ANSI 前景色
+const COLORS: [(char, &str, &str, u8); 16] = [
+    ('0', "black", "#000000", 30),
+    ('1', "dark_blue", "#0000AA", 34),
+    ('2', "dark_green", "#00AA00", 32),
+    ('3', "dark_aqua", "#00AAAA", 36),
+    ('4', "dark_red", "#AA0000", 31),
+    ('5', "dark_purple", "#AA00AA", 35),
+    ('6', "gold", "#FFAA00", 33),
+    ('7', "gray", "#AAAAAA", 37),
+    ('8', "dark_gray", "#555555", 90),
+    ('9', "blue", "#5555FF", 94),
+    ('a', "green", "#55FF55", 92),
+    ('b', "aqua", "#55FFFF", 96),
+    ('c', "red", "#FF5555", 91),
+    ('d', "light_purple", "#FF55FF", 95),
+    ('e', "yellow", "#FFFF55", 93),
+    ('f', "white", "#FFFFFF", 97),
+];
+
+/// 样式代码、文本组件字段、HTML 样式与 ANSI 代码
+const FORMATS: [(char, &str, &str, u8); 5] = [
+    ('k', "obfuscated", "", 5),
+    ('l', "bold", "font-weight: bold;", 1),
+    ('m', "strikethrough", "text-decoration: line-through;", 9),
+    ('n', "underlined", "text-decoration: underline;", 4),
+    ('o', "italic", "font-style: italic;", 3),
+];
+
+/// 由 Java 版状态中的 description 生成各格式的 MOTD
+///
+/// description 可能是带 § 格式代码的字符串，也可能是 JSON 文本组件。
+pub fn from_description(description: &Value) -> Motd {
+    let mut legacy = String::new();
+    flatten_component(description, &Style::default(), &mut legacy);
+    from_legacy(&legacy)
+}
+
+/// 由原始 MOTD 文本生成各格式的 MOTD
+///
+/// 以 `{` 或 `[` 开头且能解析为 JSON 文本组件时按文本组件处理，否则视为带 § 格式代码的字符串。
+pub fn parse(raw: &str) -> Motd {
+    let trimmed = raw.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Ok(description) = serde_json::from_str::<Value>(trimmed) {
+            let motd = from_description(&description);
+            if !motd.minecraft.is_empty() {
+                return motd;
+            }
+        }
+    }
+    from_legacy(raw)
+}
+
+/// 以原始文本重新生成外部提供的 MOTD，只保留 `minecraft` 字段（为空时退回 `plain`）
+pub fn normalize(motd: &Motd) -> Motd {
+    if motd.minecraft.is_empty() {
+        parse(&motd.plain)
+    } else {
+        parse(&motd.minecraft)
+    }
+}
+
+/// 由状态数据中的 `motd` 字段生成各格式的 MOTD
+///
+/// 兼容外部采集器写入的几种形式：本服务的 MOTD 对象、原始字符串，以及原样写入的文本组件。
+pub fn from_value(value: &Value) -> Motd {
+    match value {
+        Value::String(raw) => parse(raw),
+        Value::Object(fields)
+            if fields.contains_key("minecraft") || fields.contains_key("plain") =>
+        {
+            let field = |name: &str| {
+                fields
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            normalize(&Motd {
+                plain: field("plain"),
+                minecraft: field("minecraft"),
+                ..Default::default()
+            })
+        }
+        Value::Object(_) | Value::Array(_) => from_description(value),
+        _ => Motd::default(),
+    }
+}
+
+/// 由带 § 格式代码的字符串生成各格式的 MOTD
+pub fn from_legacy(legacy: &str) -> Motd {
+    let mut plain = String::new();
+    let mut html = String::new();
+    let mut ansi = String::new();
+    let mut color: Option<&str> = None;
+    let mut formats: Vec<&str> = Vec::new();
+    let mut span_open = false;
+
+    let mut chars = legacy.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            let Some(code) = chars.next().map(|code| code.to_ascii_lowercase()) else {
+                break;
+            };
+            if let Some(&(_, _, hex, ansi_code)) = COLORS.iter().find(|entry| entry.0 == code) {
+                color = Some(hex);
+                formats.clear();
+                ansi.push_str(&format!("\x1b[0m\x1b[{ansi_code}m"));
+            } else if let Some(&(_, _, css, ansi_code)) =
+                FORMATS.iter().find(|entry| entry.0 == code)
+            {
+                if !css.is_empty() {
+                    formats.push(css);
+                }
+                ansi.push_str(&format!("\x1b[{ansi_code}m"));
+            } else if code == 'r' {
+                color = None;
+                formats.clear();
+                ansi.push_str("\x1b[0m");
+            } else {
+                continue;
+            }
+            if span_open {
+                html.push_str("</span>");
+                span_open = false;
+            }
+            continue;
+        }
+
+        if !span_open && (color.is_some() || !formats.is_empty()) {
+            let mut style = String::new();
+            if let Some(hex) = color {
+                style.push_str(&format!("color: {hex};"));
+            }
+            for css in &formats {
+                if !style.is_empty() {
+                    style.push(' ');
+                }
+                style.push_str(css);
+            }
+            html.push_str(&format!("<span style='{style}'>"));
+            span_open = true;
+        }
+        plain.push(c);
+        ansi.push(c);
+        match c {
+            '\n' => html.push_str("<br>"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '&' => html.push_str("&amp;"),
+            '\'' => html.push_str("&#39;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(c),
+        }
+    }
+    if span_open {
+        html.push_str("</span>");
+    }
+    ansi.push_str("\x1b[0m");
+
+    Motd {
+        plain: plain.trim().to_string(),
+        html,
+        minecraft: legacy.to_string(),
+        ansi,
+    }
+}
+
+/// 带 § 格式代码的 MOTD 中样式相同的一段文字
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MotdRun {
+    pub text: String,
+    /// 颜色代码对应的 RGB，未指定颜色时为空
+    pub color: Option<[u8; 3]>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+}
+
+/// 把带 § 格式代码的字符串拆分为样式相同的片段，换行单独成段
+///
+/// 与 Minecraft 的规则一致：颜色代码会清除之前的样式，`§r` 全部重置。
+pub fn runs(legacy: &str) -> Vec<MotdRun> {
+    let mut runs = Vec::new();
+    let mut current = MotdRun::default();
+    let mut chars = legacy.chars();
+    while let Some(c) = chars.next() {
+        if c != '§' && c != '\n' {
+            current.text.push(c);
+            continue;
+        }
+        if !current.text.is_empty() {
+            let style = MotdRun {
+                text: String::new(),
+                ..current.clone()
+            };
+            runs.push(std::mem::replace(&mut current, style));
+        }
+        if c == '\n' {
+            runs.push(MotdRun {
+                text: "\n".to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(code) = chars.next().map(|code| code.to_ascii_lowercase()) else {
+            break;
+        };
+        if let Some(entry) = COLORS.iter().find(|entry| entry.0 == code) {
+            current = MotdRun {
+                color: parse_hex(entry.2),
+                ..Default::default()
+            };
+        } else if code == 'r' {
+            current = MotdRun::default();
+        } else {
+            match code {
+                'l' => current.bold = true,
+                'o' => current.italic = true,
+                'n' => current.underlined = true,
+                'm' => current.strikethrough = true,
+                _ => {}
+            }
+        }
+    }
+    if !current.text.is_empty() {
+        runs.push(current);
+    }
+    runs
+}
+
+/// 文本组件继承的样式
+#[derive(Default, Clone)]
+struct Style {
+    color: Option<char>,
+    formats: Vec<char>,
+}
+
+impl Style {
+    fn inherit(&self, component: &serde_json::Map<String, Value>) -> Self {
+        let mut style = self.clone();
+        if let Some(color) = component.get("color").and_then(Value::as_str) {
+            style.color = color_code(color).or(style.color);
+        }
+        for &(code, field, _, _) in &FORMATS {
+            match component.get(field).and_then(Value::as_bool) {
+                Some(true) if !style.formats.contains(&code) => style.formats.push(code),
+                Some(false) => style.formats.retain(|&existing| existing != code),
+                _ => {}
+            }
+        }
+        style
+    }
+
+    /// 以当前样式输出一段文本，样式为空且前文带有格式代码时先重置
+    fn emit(&self, text: &str, out: &mut String) {
+        if text.is_empty() {
+            return;
+        }
+        if self.color.is_some() || !self.formats.is_empty() || out.contains('§') {
+            out.push('§');
+            out.push(self.color.unwrap_or('r'));
+            for code in &self.formats {
+                out.push('§');
+                out.push(*code);
+            }
+        }
+        out.push_str(text);
+    }
+}
+
+fn flatten_component(value: &Value, parent: &Style, out: &mut String) {
+    match value {
+        Value::String(text) => parent.emit(text, out),
+        Value::Array(parts) => {
+            for part in parts {
+                flatten_component(part, parent, out);
+            }
+        }
+        Value::Object(component) => {
+            let style = parent.inherit(component);
+            let text = component
+                .get("text")
+                .or_else(|| component.get("translate"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            style.emit(text, out);
+            if let Some(extra) = component.get("extra") {
+                flatten_component(extra, &style, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 命名颜色或 `#RRGGBB` 转换为颜色代码，十六进制颜色取最接近的调色板颜色
+fn color_code(color: &str) -> Option<char> {
+    if let Some(&(code, ..)) = COLORS.iter().find(|entry| entry.1 == color) {
+        return Some(code);
+    }
+    let rgb = parse_hex(color)?;
+    COLORS
+        .iter()
+        .min_by_key(|entry| {
+            let palette = parse_hex(entry.2).unwrap_or_default();
+            (0..3)
+                .map(|i| (i32::from(rgb[i]) - i32::from(palette[i])).pow(2))
+                .sum::<i32>()
+        })
+        .map(|entry| entry.0)
+}
+
+fn parse_hex(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
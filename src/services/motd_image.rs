@@ -0,0 +1,292 @@
+use std::{collections::HashMap, io::Cursor, path::Path};
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use sea_orm::*;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    entities::{prelude::Server, server},
+    errors::{ApiError, ApiResult},
+    schemas::servers::{MotdTheme, ServerStatus, ServerVisibility},
+    services::{
+        database::DatabaseConnection,
+        motd::{self, MotdRun},
+        server::ServerService,
+    },
+};
+
+/// 点阵字体的字形高度（像素）
+const GLYPH_HEIGHT: usize = 16;
+/// 缺字时绘制的方框宽度
+const MISSING_GLYPH_WIDTH: u32 = 8;
+/// 最多绘制的行数，Minecraft 的 MOTD 为两行
+const MAX_LINES: usize = 2;
+
+/// 单个字形，每行 16 位，最高位为最左侧像素；半角字形只使用高 8 位
+#[derive(Debug, Clone, Copy)]
+struct Glyph {
+    wide: bool,
+    rows: [u16; GLYPH_HEIGHT],
+}
+
+impl Glyph {
+    fn width(&self) -> u32 {
+        if self.wide {
+            16
+        } else {
+            8
+        }
+    }
+
+    fn is_set(&self, x: u32, y: usize) -> bool {
+        self.rows[y] & (0x8000 >> x) != 0
+    }
+}
+
+/// GNU Unifont `.hex` 格式的点阵字体
+///
+/// 每行一个字形：`码位:点阵`，点阵为 32 位（8×16）或 64 位（16×16）十六进制数，
+/// 覆盖 BMP 内的中日韩文字，不需要额外的字体渲染依赖。
+#[derive(Debug, Default)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl BitmapFont {
+    /// 解析字体内容，格式错误时返回出错的行号
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut glyphs = HashMap::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || format!("第 {} 行格式无效", index + 1);
+            let (code, bitmap) = line.split_once(':').ok_or_else(invalid)?;
+            let c = u32::from_str_radix(code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(invalid)?;
+            let wide = match bitmap.len() {
+                32 => false,
+                64 => true,
+                _ => return Err(invalid()),
+            };
+            let digits = bitmap.len() / GLYPH_HEIGHT;
+            let mut rows = [0u16; GLYPH_HEIGHT];
+            for (y, row) in rows.iter_mut().enumerate() {
+                let value = u16::from_str_radix(&bitmap[y * digits..(y + 1) * digits], 16)
+                    .map_err(|_| invalid())?;
+                *row = if wide { value } else { value << 8 };
+            }
+            glyphs.insert(c, Glyph { wide, rows });
+        }
+        if glyphs.is_empty() {
+            return Err("字体中没有字形".to_string());
+        }
+        Ok(Self { glyphs })
+    }
+
+    /// 读取字体文件
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取字体文件 {} 失败: {e}", path.display()))?;
+        Self::parse(&source)
+    }
+
+    fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c)
+    }
+}
+
+/// MOTD 图片渲染服务
+///
+/// 以 Minecraft 的调色板把最近一次状态中的 MOTD 绘制为横幅图片，
+/// 供不能渲染 HTML 的场景（论坛签名、聊天机器人等）直接引用。
+pub struct MotdImageService;
+
+impl MotdImageService {
+    /// 图片宽度的下限与上限（像素）
+    pub const MIN_WIDTH: u32 = 256;
+    pub const MAX_WIDTH: u32 = 1280;
+
+    /// 可公开访问的服务器最近一次状态中的 MOTD（带 § 格式代码）
+    ///
+    /// 隐藏、未发布、停用或不属于当前租户的服务器视为不存在。
+    pub async fn latest_motd(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+    ) -> ApiResult<String> {
+        Server::find_by_id(server_id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        ServerService::latest_stats(db.as_ref(), &[server_id])
+            .await?
+            .into_iter()
+            .next()
+            .and_then(|stats| stats.stat_data)
+            .and_then(|data| ServerService::parse_server_stats(&data).ok())
+            .map(|stats| stats.motd.minecraft)
+            .ok_or_else(|| ApiError::NotFound("服务器暂无状态数据".to_string()))
+    }
+
+    /// 渲染结果的缓存键，包含 MOTD 内容的摘要，MOTD 变化后自然失效
+    pub fn cache_key(server_id: i32, legacy: &str, width: u32, theme: MotdTheme) -> String {
+        let digest = hex::encode(&Sha256::digest(legacy.as_bytes())[..8]);
+        format!("{server_id}:{digest}:{width}:{}", theme.as_str())
+    }
+
+    /// 把 MOTD 渲染为 PNG
+    ///
+    /// 字形按宽度等比放大（每 320 像素放大一倍），超出宽度的文字被截断；
+    /// 字体中没有的字符绘制为方框。
+    pub fn render(
+        font: &BitmapFont,
+        legacy: &str,
+        width: u32,
+        theme: MotdTheme,
+    ) -> ApiResult<Vec<u8>> {
+        let width = width.clamp(Self::MIN_WIDTH, Self::MAX_WIDTH);
+        let scale = (width / 320).max(1);
+        let padding = 8 * scale;
+        let line_height = (GLYPH_HEIGHT as u32 + 2) * scale;
+        let height = padding * 2 + line_height * MAX_LINES as u32;
+
+        let mut canvas = RgbaImage::from_pixel(width, height, Rgba(Self::background(theme)));
+        let mut lines: Vec<Vec<MotdRun>> = vec![Vec::new()];
+        for run in motd::runs(legacy) {
+            if run.text == "\n" {
+                lines.push(Vec::new());
+            } else if let Some(line) = lines.last_mut() {
+                line.push(run);
+            }
+        }
+        // 服务器列表中 MOTD 每行首尾的空格用于居中，图片中左对齐
+        let lines = lines
+            .into_iter()
+            .filter(|line| line.iter().any(|run| !run.text.trim().is_empty()))
+            .take(MAX_LINES);
+
+        for (index, line) in lines.enumerate() {
+            let mut x = padding;
+            let y = padding + line_height * index as u32;
+            let mut leading = true;
+            'line: for run in line {
+                let color = Self::text_color(theme, run.color);
+                for c in run.text.chars() {
+                    if leading && c.is_whitespace() {
+                        continue;
+                    }
+                    leading = false;
+                    if x >= width - padding {
+                        break 'line;
+                    }
+                    x += Self::draw_char(&mut canvas, font, c, &run, color, x, y, scale);
+                }
+            }
+        }
+
+        let mut png = Vec::new();
+        canvas
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(|_| ApiError::Internal("MOTD 图片编码失败".to_string()))?;
+        Ok(png)
+    }
+
+    /// 绘制一个字符，返回横向前进的像素数
+    #[allow(clippy::too_many_arguments)]
+    fn draw_char(
+        canvas: &mut RgbaImage,
+        font: &BitmapFont,
+        c: char,
+        run: &MotdRun,
+        color: [u8; 4],
+        x: u32,
+        y: u32,
+        scale: u32,
+    ) -> u32 {
+        let glyph = font.glyph(c).copied();
+        let glyph_width = glyph.map_or(MISSING_GLYPH_WIDTH, |glyph| glyph.width());
+        let bold_offset = if run.bold { scale } else { 0 };
+        let advance = glyph_width * scale + bold_offset;
+
+        for row in 0..GLYPH_HEIGHT {
+            // 斜体按行向右错开，顶部偏移最多
+            let shear = if run.italic {
+                (GLYPH_HEIGHT - 1 - row) as u32 * scale / 6
+            } else {
+                0
+            };
+            for column in 0..glyph_width {
+                let set = match glyph {
+                    Some(glyph) => glyph.is_set(column, row),
+                    None => {
+                        (1..GLYPH_HEIGHT - 1).contains(&row)
+                            && (row == 1
+                                || row == GLYPH_HEIGHT - 2
+                                || column == 0
+                                || column == glyph_width - 2)
+                            && column < glyph_width - 1
+                    }
+                };
+                if !set {
+                    continue;
+                }
+                let px = x + column * scale + shear;
+                let py = y + row as u32 * scale;
+                Self::fill(canvas, px, py, scale + bold_offset, scale, color);
+            }
+        }
+
+        let line_y = |row: u32| y + row * scale;
+        if run.underlined {
+            Self::fill(canvas, x, line_y(15), advance, scale, color);
+        }
+        if run.strikethrough {
+            Self::fill(canvas, x, line_y(8), advance, scale, color);
+        }
+        advance
+    }
+
+    fn background(theme: MotdTheme) -> [u8; 4] {
+        match theme {
+            MotdTheme::Dark => [0x20, 0x20, 0x20, 0xFF],
+            MotdTheme::Light => [0xF5, 0xF5, 0xF5, 0xFF],
+        }
+    }
+
+    /// 未指定颜色时与服务器列表一致使用灰色；浅色主题下把过亮的颜色减半，保证可读
+    fn text_color(theme: MotdTheme, color: Option<[u8; 3]>) -> [u8; 4] {
+        let [r, g, b] = match (theme, color) {
+            (MotdTheme::Dark, Some(rgb)) => rgb,
+            (MotdTheme::Dark, None) => [0xAA, 0xAA, 0xAA],
+            (MotdTheme::Light, None) => [0x33, 0x33, 0x33],
+            (MotdTheme::Light, Some(rgb)) => {
+                let luma =
+                    (299 * u32::from(rgb[0]) + 587 * u32::from(rgb[1]) + 114 * u32::from(rgb[2]))
+                        / 1000;
+                if luma > 160 {
+                    rgb.map(|channel| channel / 2)
+                } else {
+                    rgb
+                }
+            }
+        };
+        [r, g, b, 0xFF]
+    }
+
+    fn fill(canvas: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: [u8; 4]) {
+        for py in y..(y + height).min(canvas.height()) {
+            for px in x..(x + width).min(canvas.width()) {
+                canvas.put_pixel(px, py, Rgba(color));
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use sea_orm::*;
+
+use crate::{
+    config::NamePolicyConfig,
+    entities::{
+        prelude::Users,
+        users::{self, RoleEnum},
+    },
+    errors::{ApiError, ApiResult},
+    services::database::DatabaseConnection,
+};
+
+/// 名称保护服务
+///
+/// 注册与修改显示名称时调用：禁止使用保留名称，也禁止与管理人员的用户名或显示名称
+/// 过于相似，防止冒充官方。比较前会统一大小写、去掉分隔符并替换常见的形近字符。
+/// 管理员通过后台修改用户名称时不做此检查。
+pub struct NamePolicyService;
+
+impl NamePolicyService {
+    /// 检查名称是否可用；`user_id` 为名称的所有者，修改自己的名称时不与自己比较
+    pub async fn check(
+        db: &DatabaseConnection,
+        config: &NamePolicyConfig,
+        names: &[&str],
+        user_id: Option<i32>,
+    ) -> ApiResult<()> {
+        let names: Vec<String> = names
+            .iter()
+            .map(|name| Self::normalize(name))
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        for name in &names {
+            let reserved = config
+                .reserved_names
+                .iter()
+                .map(|reserved| Self::normalize(reserved))
+                .any(|reserved| !reserved.is_empty() && name.contains(&reserved));
+            if reserved {
+                return Err(ApiError::BadRequest("名称包含保留字，无法使用".to_string()));
+            }
+        }
+
+        let mut query = Users::find()
+            .select_only()
+            .column(users::Column::Username)
+            .column(users::Column::DisplayName)
+            .filter(users::Column::Role.is_in([RoleEnum::Admin, RoleEnum::Moderator]))
+            .filter(users::Column::IsActive.eq(true));
+        if let Some(user_id) = user_id {
+            query = query.filter(users::Column::Id.ne(user_id));
+        }
+        let staff: Vec<(String, String)> = query.into_tuple().all(db.as_ref()).await?;
+
+        let impersonating = staff
+            .iter()
+            .flat_map(|(username, display_name)| [username, display_name])
+            .map(|staff_name| Self::normalize(staff_name))
+            .filter(|staff_name| !staff_name.is_empty())
+            .any(|staff_name| {
+                names.iter().any(|name| {
+                    *name == staff_name
+                        || (staff_name.chars().count() >= 4
+                            && Self::edit_distance(name, &staff_name)
+                                <= config.staff_similarity_distance)
+                })
+            });
+        if impersonating {
+            return Err(ApiError::BadRequest(
+                "名称与管理人员过于相似，无法使用".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 统一大小写，去掉分隔符，并把形近字符替换为同一字母
+    fn normalize(name: &str) -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .map(|c| match c {
+                '0' | 'о' => 'o',
+                '1' | 'i' => 'l',
+                '3' | 'е' => 'e',
+                '4' | 'а' => 'a',
+                '5' => 's',
+                '7' => 't',
+                'р' => 'p',
+                'с' => 'c',
+                'х' => 'x',
+                'у' => 'y',
+                c => c,
+            })
+            .collect()
+    }
+
+    fn edit_distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+        for (i, ca) in a.chars().enumerate() {
+            let mut current = vec![i + 1; b.len() + 1];
+            for (j, cb) in b.iter().enumerate() {
+                let cost = usize::from(ca != *cb);
+                current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+            }
+            prev = current;
+        }
+
+        prev[b.len()]
+    }
+}
@@ -0,0 +1,431 @@
+use anyhow::{anyhow, Result};
+use askama::Template;
+use chrono::Utc;
+use lettre::Transport;
+use once_cell::sync::OnceCell;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    config::{EmailConfig, NotificationConfig},
+    entities::{
+        notifications,
+        prelude::{Notifications, UserServer, Users},
+        user_server,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        servers::ServerManagerRole,
+        users::{DigestFrequency, NotificationInfo, NotificationKind, UserPreferences},
+    },
+    services::{
+        database::DatabaseConnection,
+        email::{
+            sender::{build_message_with_subject, build_smtp_transport},
+            suppression::EmailSuppressionService,
+            template::{
+                build_notification_template, NotificationEmailItem, NotificationEmailTemplate,
+            },
+        },
+        metrics::MetricsService,
+        preferences::PreferenceService,
+        redis::RedisService,
+    },
+};
+
+/// 一条发给用户的通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    /// 通知类型，用户可以按类型关闭
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+struct NotificationSettings {
+    /// 未配置发信服务时只发送站内通知
+    email: Option<EmailConfig>,
+    notification: NotificationConfig,
+}
+
+static NOTIFICATION_SETTINGS: OnceCell<NotificationSettings> = OnceCell::new();
+
+/// 用户通知服务
+///
+/// 发送任何通知前都先读取用户偏好：关闭的渠道与类型不发送。站内通知保存在 `notifications`，
+/// 每个用户只保留最近的若干条；邮件使用通知模板，按摘要频率立即发送，或先进入待发送队列，
+/// 由 [`NotificationService::run_digest_loop`] 按天/周汇总发送。未初始化时不发送任何通知。
+pub struct NotificationService;
+
+impl NotificationService {
+    const DIGEST_PREFIX: &'static str = "notifications:digest";
+    const DIGEST_SENT_PREFIX: &'static str = "notifications:digest_sent";
+    /// 每个用户待汇总的通知上限，超出时丢弃最早的
+    const DIGEST_QUEUE_SIZE: usize = 200;
+
+    pub fn init(email: Option<&EmailConfig>, notification: &NotificationConfig) -> Result<()> {
+        NOTIFICATION_SETTINGS
+            .set(NotificationSettings {
+                email: email.cloned(),
+                notification: notification.clone(),
+            })
+            .map_err(|_| anyhow!("通知服务已初始化"))
+    }
+
+    /// 按用户偏好发送通知，失败只记录日志，不影响调用方的操作
+    pub async fn notify(db: &DatabaseConnection, user_id: i32, notification: Notification) {
+        if let Err(e) = Self::deliver(db, user_id, &notification).await {
+            tracing::warn!(
+                "⚠️  发送通知失败: user_id={}, kind={}, error={}",
+                user_id,
+                notification.kind.as_str(),
+                e
+            );
+        }
+    }
+
+    /// 通知服务器的所有服主
+    pub async fn notify_server_owners(
+        db: &DatabaseConnection,
+        server_id: i32,
+        notification: Notification,
+    ) {
+        let owners: Vec<i32> = match UserServer::find()
+            .select_only()
+            .column(user_server::Column::UserId)
+            .filter(user_server::Column::ServerId.eq(server_id))
+            .filter(user_server::Column::Role.eq(ServerManagerRole::Owner.as_str()))
+            .into_tuple()
+            .all(db.as_ref())
+            .await
+        {
+            Ok(owners) => owners,
+            Err(e) => {
+                tracing::warn!("⚠️  查询服务器 {} 的服主失败: {}", server_id, e);
+                return;
+            }
+        };
+
+        for user_id in owners {
+            Self::notify(db, user_id, notification.clone()).await;
+        }
+    }
+
+    async fn deliver(
+        db: &DatabaseConnection,
+        user_id: i32,
+        notification: &Notification,
+    ) -> ApiResult<()> {
+        let Some(settings) = NOTIFICATION_SETTINGS.get() else {
+            return Ok(());
+        };
+        let preferences = PreferenceService::get(db, &settings.notification, user_id).await?;
+        if notification.kind.mutable() && preferences.muted_kinds.contains(&notification.kind) {
+            return Ok(());
+        }
+        let payload = serde_json::to_string(notification)
+            .map_err(|e| ApiError::Internal(format!("通知序列化失败: {e}")))?;
+
+        if preferences.channels.in_app {
+            Self::store(db, user_id, notification, settings.notification.inbox_size).await?;
+            Self::record("in_app", notification.kind.as_str());
+        }
+
+        if preferences.channels.email {
+            match preferences.digest_frequency {
+                DigestFrequency::Instant => {
+                    Self::send_email(
+                        db,
+                        settings,
+                        user_id,
+                        &preferences,
+                        std::slice::from_ref(notification),
+                        false,
+                    )
+                    .await?;
+                    Self::record("email", notification.kind.as_str());
+                }
+                DigestFrequency::Daily | DigestFrequency::Weekly => {
+                    Self::push(
+                        &format!("{}:{}", Self::DIGEST_PREFIX, user_id),
+                        &payload,
+                        Self::DIGEST_QUEUE_SIZE,
+                    )
+                    .await;
+                    Self::record("digest_queued", notification.kind.as_str());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 分页获取用户的站内通知，按时间倒序
+    pub async fn list(
+        db: &DatabaseConnection,
+        user_id: i32,
+        unread: bool,
+        page: u64,
+        page_size: u64,
+    ) -> ApiResult<(Vec<NotificationInfo>, u64)> {
+        let mut select = Notifications::find().filter(notifications::Column::UserId.eq(user_id));
+        if unread {
+            select = select.filter(notifications::Column::ReadAt.is_null());
+        }
+        let paginator = select
+            .order_by_desc(notifications::Column::Id)
+            .paginate(db.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(|item| NotificationInfo {
+                id: item.id,
+                kind: item.kind,
+                title: item.title,
+                body: item.body,
+                read_at: item.read_at,
+                created_at: item.created_at,
+            })
+            .collect();
+
+        Ok((data, total))
+    }
+
+    /// 把通知标记为已读，未指定 ID 时标记全部，返回本次标记的条数
+    ///
+    /// 不属于该用户或已读的通知会被忽略。
+    pub async fn mark_read(
+        db: &DatabaseConnection,
+        user_id: i32,
+        ids: Option<Vec<i32>>,
+    ) -> ApiResult<u64> {
+        let mut update = Notifications::update_many()
+            .col_expr(
+                notifications::Column::ReadAt,
+                sea_query::Expr::value(Some(Utc::now())),
+            )
+            .filter(notifications::Column::UserId.eq(user_id))
+            .filter(notifications::Column::ReadAt.is_null());
+        if let Some(ids) = ids {
+            if ids.is_empty() {
+                return Ok(0);
+            }
+            update = update.filter(notifications::Column::Id.is_in(ids));
+        }
+        Ok(update.exec(db.as_ref()).await?.rows_affected)
+    }
+
+    /// 用户的未读通知数
+    pub async fn unread_count(db: &DatabaseConnection, user_id: i32) -> ApiResult<u64> {
+        Ok(Notifications::find()
+            .filter(notifications::Column::UserId.eq(user_id))
+            .filter(notifications::Column::ReadAt.is_null())
+            .count(db.as_ref())
+            .await?)
+    }
+
+    /// 保存站内通知，并删除超出保留条数的旧通知
+    async fn store(
+        db: &DatabaseConnection,
+        user_id: i32,
+        notification: &Notification,
+        inbox_size: usize,
+    ) -> ApiResult<()> {
+        notifications::ActiveModel {
+            user_id: Set(user_id),
+            kind: Set(notification.kind.as_str().to_string()),
+            title: Set(notification.title.clone()),
+            body: Set(notification.body.clone()),
+            read_at: Set(None),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+
+        let oldest_kept: Option<i32> = Notifications::find()
+            .select_only()
+            .column(notifications::Column::Id)
+            .filter(notifications::Column::UserId.eq(user_id))
+            .order_by_desc(notifications::Column::Id)
+            .offset(inbox_size.saturating_sub(1) as u64)
+            .into_tuple()
+            .one(db.as_ref())
+            .await?;
+        if let Some(oldest_kept) = oldest_kept {
+            Notifications::delete_many()
+                .filter(notifications::Column::UserId.eq(user_id))
+                .filter(notifications::Column::Id.lt(oldest_kept))
+                .exec(db.as_ref())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 定期发送摘要邮件
+    ///
+    /// 每位用户在一个摘要周期内最多收到一封；发送前重新读取偏好，
+    /// 用户已关闭邮件通知时直接丢弃待发送的通知。
+    pub async fn run_digest_loop(db: DatabaseConnection, interval: Duration) {
+        let Some(settings) = NOTIFICATION_SETTINGS.get() else {
+            return;
+        };
+        tracing::info!("启动通知摘要任务，检查间隔: {} 秒", interval.as_secs());
+
+        loop {
+            sleep(interval).await;
+            if let Err(e) = Self::flush_digests(&db, settings).await {
+                tracing::warn!("⚠️  发送通知摘要失败: {}", e);
+            }
+        }
+    }
+
+    async fn flush_digests(db: &DatabaseConnection, settings: &NotificationSettings) -> Result<()> {
+        let Some(redis) = RedisService::instance() else {
+            return Ok(());
+        };
+
+        for key in redis
+            .scan_keys(&format!("{}:*", Self::DIGEST_PREFIX))
+            .await?
+        {
+            let Some(user_id) = key.rsplit(':').next().and_then(|id| id.parse::<i32>().ok()) else {
+                continue;
+            };
+
+            let preferences = PreferenceService::get(db, &settings.notification, user_id)
+                .await
+                .map_err(|e| anyhow!("读取用户偏好失败: {}", e))?;
+            if !preferences.channels.email {
+                redis.del(&key).await?;
+                continue;
+            }
+            if let Some(period) = preferences.digest_frequency.interval_secs() {
+                let sent_key = format!("{}:{}", Self::DIGEST_SENT_PREFIX, user_id);
+                let now = Utc::now().timestamp().to_string();
+                if !redis.set_nx_ex(&sent_key, &now, period).await? {
+                    continue;
+                }
+            }
+
+            let pending: Vec<Notification> = redis
+                .lrange(&key, 0, -1)
+                .await?
+                .iter()
+                .filter_map(|item| serde_json::from_str(item).ok())
+                .collect();
+            redis.del(&key).await?;
+            if pending.is_empty() {
+                continue;
+            }
+
+            match Self::send_email(db, settings, user_id, &preferences, &pending, true).await {
+                Ok(()) => Self::record("email_digest", "digest"),
+                Err(e) => tracing::warn!("⚠️  发送通知摘要失败: user_id={}, error={}", user_id, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// 渲染通知邮件，返回主题与模板；摘要邮件以通知条数为主题，否则使用通知标题
+    fn render_email(
+        preferences: &UserPreferences,
+        username: &str,
+        pending: &[Notification],
+        digest: bool,
+    ) -> (String, NotificationEmailTemplate) {
+        let zh = preferences.locale.starts_with("zh");
+        let subject = match (digest, pending.first()) {
+            (false, Some(notification)) => notification.title.clone(),
+            _ if zh => format!("您有 {} 条新通知", pending.len()),
+            _ => format!("You have {} new notifications", pending.len()),
+        };
+        let (greeting, footer) = match (zh, digest) {
+            (true, true) => (
+                format!("{username}，以下是近期的通知汇总："),
+                "通知渠道、摘要频率与接收的通知类型可以在账户的偏好设置中调整。",
+            ),
+            (true, false) => (
+                format!("{username}，您有一条新通知："),
+                "通知渠道、摘要频率与接收的通知类型可以在账户的偏好设置中调整。",
+            ),
+            (false, true) => (
+                format!("Hi {username}, here is a summary of your recent notifications:"),
+                "You can change notification channels, digest frequency and notification types in your account preferences.",
+            ),
+            (false, false) => (
+                format!("Hi {username}, you have a new notification:"),
+                "You can change notification channels, digest frequency and notification types in your account preferences.",
+            ),
+        };
+
+        let items = pending
+            .iter()
+            .map(|notification| NotificationEmailItem {
+                title: notification.title.clone(),
+                body: notification.body.clone(),
+            })
+            .collect();
+        (
+            subject,
+            build_notification_template(greeting, footer.to_string(), items),
+        )
+    }
+
+    async fn send_email(
+        db: &DatabaseConnection,
+        settings: &NotificationSettings,
+        user_id: i32,
+        preferences: &UserPreferences,
+        pending: &[Notification],
+        digest: bool,
+    ) -> ApiResult<()> {
+        let Some(email) = &settings.email else {
+            return Ok(());
+        };
+        let Some(user) = Users::find_by_id(user_id).one(db.as_ref()).await? else {
+            return Ok(());
+        };
+        if EmailSuppressionService::is_suppressed(db, &user.email).await {
+            return Ok(());
+        }
+
+        let (subject, template) = Self::render_email(preferences, &user.username, pending, digest);
+        let body = template
+            .render()
+            .map_err(|e| ApiError::Internal(format!("渲染通知邮件失败: {e}")))?;
+        let message = build_message_with_subject(&email.smtp_username, &user.email, &subject, body)
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        let transport =
+            build_smtp_transport(email).map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = transport.send(&message) {
+                tracing::error!("发送通知邮件失败: {:?}", e);
+            }
+        });
+        Ok(())
+    }
+
+    async fn push(key: &str, payload: &str, max_len: usize) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        if let Err(e) = redis.lpush_trim(key, payload, max_len).await {
+            tracing::warn!("⚠️  写入通知队列 {} 失败: {}", key, e);
+        }
+    }
+
+    fn record(channel: &str, kind: &str) {
+        MetricsService::inc_counter(
+            "notifications_sent_total",
+            "按渠道发送的用户通知数",
+            &[("channel", channel), ("kind", kind)],
+            1.0,
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use rand::{distr::Alphanumeric, Rng};
+use reqwest::{header::ACCEPT, Client as HttpClient};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    config::{Config, OAuthClientConfig, OAuthConfig},
+    entities::{
+        external_identities,
+        prelude::{ExternalIdentities, Users},
+        users::{self, RoleEnum},
+    },
+    errors::{ApiError, ApiResult, ErrorCode},
+    schemas::{
+        admin::BanType,
+        auth::{
+            AuthToken, OAuthAuthorizeResponse, OAuthCallbackQuery, OAuthCallbackResponse,
+            OAuthOutcome, OAuthProvider, DISPLAY_NAME_REGEX,
+        },
+        users::LinkStatus,
+    },
+    services::{
+        account_link::AccountLinkService,
+        auth::{AuthService, JwtData, SessionClient},
+        ban::BanService,
+        database::DatabaseConnection,
+        name_policy::NamePolicyService,
+        password::PasswordService,
+        redis::RedisService,
+        registration_guard::RegistrationGuardService,
+    },
+};
+
+/// 调用第三方平台接口的客户端，GitHub 要求请求带 User-Agent
+static HTTP_CLIENT: Lazy<HttpClient> = Lazy::new(|| {
+    HttpClient::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("server-api-rt/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("HTTP 客户端配置有效")
+});
+
+/// 发起授权时保存的请求信息，回调时取出并删除
+#[derive(Debug, Serialize, Deserialize)]
+struct OAuthState {
+    provider: OAuthProvider,
+    tenant_id: String,
+    /// 发起绑定的用户，登录时为空；回调时必须由该用户携带令牌完成
+    user_id: Option<i32>,
+}
+
+/// 第三方平台返回的账户资料
+#[derive(Debug)]
+struct OAuthProfile {
+    external_id: String,
+    login: String,
+    name: Option<String>,
+    email: Option<String>,
+    /// 平台是否确认邮箱属于该账户，只有已验证的邮箱才会自动关联到同邮箱的本站账户或用于注册
+    email_verified: bool,
+}
+
+/// 第三方登录服务（GitHub / Microsoft，OAuth 2.0 授权码模式）
+///
+/// 授权请求的 state 保存在 Redis 中，只能使用一次。第三方账户以 `external_identities`
+/// 中的有效绑定与本站用户对应，与其他外部账户绑定一样可以在账户设置中查看与解除。
+/// 登录时按以下顺序处理：已绑定的账户直接登录；平台确认过的邮箱与本站账户一致时自动绑定后登录；
+/// 否则在开放注册的站点用平台确认过的邮箱创建新账户，新账户的密码随机生成，可以通过重置密码设置。
+/// 从账户设置发起的绑定只能由发起绑定的用户完成，回调时需要携带该用户的令牌。
+pub struct OAuthService;
+
+impl OAuthService {
+    const STATE_PREFIX: &'static str = "oauth:state";
+    const STATE_LEN: usize = 32;
+    const GITHUB_SCOPE: &'static str = "read:user user:email";
+    const MICROSOFT_SCOPE: &'static str = "openid profile email User.Read";
+    /// 生成用户名时最多尝试的次数，之后换用随机用户名
+    const USERNAME_ATTEMPTS: usize = 5;
+
+    /// 解析路径中的平台名称
+    pub fn provider(name: &str) -> ApiResult<OAuthProvider> {
+        OAuthProvider::parse(name)
+            .ok_or_else(|| ApiError::BadRequest(format!("不支持的登录方式: {name}")))
+    }
+
+    /// 生成第三方授权地址；`user_id` 不为空时回调会把第三方账户绑定到该用户
+    pub async fn authorize(
+        config: &OAuthConfig,
+        provider: OAuthProvider,
+        tenant_id: &str,
+        user_id: Option<i32>,
+    ) -> ApiResult<OAuthAuthorizeResponse> {
+        let client = Self::client(config, provider)?;
+        let redis = Self::redis()?;
+
+        let state: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(Self::STATE_LEN)
+            .map(char::from)
+            .collect();
+        let payload = serde_json::to_string(&OAuthState {
+            provider,
+            tenant_id: tenant_id.to_string(),
+            user_id,
+        })
+        .map_err(|e| ApiError::Internal(format!("授权请求序列化失败: {e}")))?;
+        redis
+            .set_ex(
+                &format!("{}:{}", Self::STATE_PREFIX, state),
+                &payload,
+                config.state_ttl_secs,
+            )
+            .await?;
+
+        let redirect_uri = Self::redirect_uri(config, provider);
+        let (endpoint, scope) = match provider {
+            OAuthProvider::Github => (
+                "https://github.com/login/oauth/authorize".to_string(),
+                Self::GITHUB_SCOPE,
+            ),
+            OAuthProvider::Microsoft => (
+                format!(
+                    "https://login.microsoftonline.com/{}/oauth2/v2.0/authorize",
+                    config.microsoft_tenant
+                ),
+                Self::MICROSOFT_SCOPE,
+            ),
+        };
+        let mut url = url::Url::parse(&endpoint)
+            .map_err(|e| ApiError::Internal(format!("授权地址无效: {e}")))?;
+        url.query_pairs_mut()
+            .append_pair("client_id", &client.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("response_type", "code")
+            .append_pair("scope", scope)
+            .append_pair("state", &state);
+
+        Ok(OAuthAuthorizeResponse {
+            authorize_url: url.into(),
+            state,
+            expires_in: config.state_ttl_secs,
+        })
+    }
+
+    /// 处理第三方平台回调：登录、注册，或绑定到发起绑定的用户
+    ///
+    /// `allow_registration` 为当前站点是否开放注册，只影响新账户的创建；`caller_id` 为
+    /// 携带令牌的当前用户，绑定时必须与发起绑定的用户一致，避免授权链接被他人打开后
+    /// 把对方的第三方账户绑定到发起者名下。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn callback(
+        db: &DatabaseConnection,
+        config: &Config,
+        provider: OAuthProvider,
+        tenant_id: &str,
+        allow_registration: bool,
+        caller_id: Option<i32>,
+        query: OAuthCallbackQuery,
+        client: SessionClient,
+    ) -> ApiResult<OAuthCallbackResponse> {
+        let oauth_client = Self::client(&config.oauth, provider)?;
+        let state = Self::take_state(&query.state)
+            .await?
+            .filter(|state| state.provider == provider && state.tenant_id == tenant_id)
+            .ok_or_else(|| ApiError::BadRequest("授权请求无效或已过期".to_string()))?;
+        if state.user_id.is_some() && state.user_id != caller_id {
+            return Err(ApiError::Forbidden(
+                "请登录发起绑定的账户后完成绑定".to_string(),
+            ));
+        }
+        if let Some(error) = query.error {
+            return Err(ApiError::BadRequest(format!("第三方授权失败: {error}")));
+        }
+        let code = query
+            .code
+            .filter(|code| !code.is_empty())
+            .ok_or_else(|| ApiError::BadRequest("缺少授权码".to_string()))?;
+
+        let access_token =
+            Self::exchange_code(&config.oauth, oauth_client, provider, &code).await?;
+        let profile = Self::fetch_profile(provider, &access_token).await?;
+
+        match state.user_id {
+            Some(user_id) => {
+                let identity = Self::link(db, provider, user_id, &profile.external_id).await?;
+                Ok(OAuthCallbackResponse {
+                    outcome: OAuthOutcome::Linked,
+                    token: None,
+                    identity: AccountLinkService::to_info(identity),
+                })
+            }
+            None => {
+                Self::login(
+                    db,
+                    config,
+                    provider,
+                    tenant_id,
+                    allow_registration,
+                    profile,
+                    client,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn login(
+        db: &DatabaseConnection,
+        config: &Config,
+        provider: OAuthProvider,
+        tenant_id: &str,
+        allow_registration: bool,
+        profile: OAuthProfile,
+        client: SessionClient,
+    ) -> ApiResult<OAuthCallbackResponse> {
+        let linked = ExternalIdentities::find()
+            .filter(external_identities::Column::Provider.eq(provider.as_str()))
+            .filter(external_identities::Column::ExternalId.eq(&profile.external_id))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Active.as_str()))
+            .find_also_related(Users)
+            .one(db.as_ref())
+            .await?;
+
+        let (outcome, user, identity) = match linked {
+            Some((identity, Some(user))) => (OAuthOutcome::LoggedIn, user, identity),
+            _ => {
+                let existing = match &profile.email {
+                    Some(email) => {
+                        Users::find()
+                            .filter(users::Column::Email.eq(email))
+                            .one(db.as_ref())
+                            .await?
+                    }
+                    None => None,
+                };
+                match existing {
+                    Some(user) if profile.email_verified && user.tenant_id == tenant_id => {
+                        let identity =
+                            Self::link(db, provider, user.id, &profile.external_id).await?;
+                        (OAuthOutcome::LoggedIn, user, identity)
+                    }
+                    Some(_) => {
+                        return Err(ApiError::Conflict(
+                            "该邮箱已注册，请使用密码登录后在账户设置中绑定".to_string(),
+                        ));
+                    }
+                    None if !allow_registration => {
+                        return Err(ApiError::Forbidden("当前站点未开放注册".to_string())
+                            .with_code(ErrorCode::RegistrationClosed));
+                    }
+                    None => {
+                        let (user, identity) =
+                            Self::register(db, config, provider, tenant_id, &profile).await?;
+                        (OAuthOutcome::Registered, user, identity)
+                    }
+                }
+            }
+        };
+
+        if user.tenant_id != tenant_id {
+            return Err(ApiError::Conflict(
+                "该第三方账户已绑定其他站点的账户".to_string(),
+            ));
+        }
+        // 禁言不影响登录，只限制写操作
+        if let Some(ban) = BanService::active_ban(db, user.id).await {
+            if ban.ban_type != BanType::Mute.as_str() {
+                return Err(ApiError::Banned(ban));
+            }
+        }
+
+        let jwt_data = JwtData {
+            user_id: user.id,
+            username: user.username.clone(),
+            tenant_id: tenant_id.to_string(),
+        };
+        let access_token = AuthService::create_access_token(&jwt_data, &client, config).await?;
+
+        let db = db.clone();
+        let guard_config = config.registration_guard.clone();
+        let registered = outcome == OAuthOutcome::Registered;
+        let client_ip = client.ip;
+        tokio::spawn(async move {
+            if let Err(e) = AuthService::update_last_login(&db, user.id, client_ip.clone()).await {
+                tracing::warn!("⚠️  更新最后登录时间失败: {}", e);
+            }
+            if registered {
+                if let Err(e) = RegistrationGuardService::inspect(
+                    &db,
+                    &guard_config,
+                    &user,
+                    client_ip.as_deref(),
+                )
+                .await
+                {
+                    tracing::warn!("⚠️  可疑注册检查失败: {}", e);
+                }
+            }
+        });
+
+        Ok(OAuthCallbackResponse {
+            outcome,
+            token: Some(AuthToken {
+                access_token,
+                expires_in: config.jwt.expiration,
+            }),
+            identity: AccountLinkService::to_info(identity),
+        })
+    }
+
+    /// 把第三方账户绑定到用户，同一平台下一个用户只能绑定一个第三方账户
+    async fn link(
+        db: &DatabaseConnection,
+        provider: OAuthProvider,
+        user_id: i32,
+        external_id: &str,
+    ) -> ApiResult<external_identities::Model> {
+        let existing = ExternalIdentities::find()
+            .filter(external_identities::Column::Provider.eq(provider.as_str()))
+            .filter(external_identities::Column::Status.eq(LinkStatus::Active.as_str()))
+            .filter(
+                Condition::any()
+                    .add(external_identities::Column::ExternalId.eq(external_id))
+                    .add(external_identities::Column::UserId.eq(user_id)),
+            )
+            .all(db.as_ref())
+            .await?;
+        if existing.iter().any(|link| link.user_id != user_id) {
+            return Err(ApiError::Conflict("该第三方账户已绑定其他用户".to_string()));
+        }
+        if !existing.is_empty() {
+            return Err(ApiError::Conflict(
+                "已绑定该平台账户，请先解除绑定".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        Ok(external_identities::ActiveModel {
+            user_id: Set(user_id),
+            provider: Set(provider.as_str().to_string()),
+            external_id: Set(Some(external_id.to_string())),
+            status: Set(LinkStatus::Active.as_str().to_string()),
+            created_at: Set(now),
+            confirmed_at: Set(Some(now)),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?)
+    }
+
+    /// 用第三方账户资料创建新用户并绑定，只接受平台确认过的邮箱
+    async fn register(
+        db: &DatabaseConnection,
+        config: &Config,
+        provider: OAuthProvider,
+        tenant_id: &str,
+        profile: &OAuthProfile,
+    ) -> ApiResult<(users::Model, external_identities::Model)> {
+        let email = profile
+            .email
+            .clone()
+            .filter(|_| profile.email_verified)
+            .ok_or_else(|| {
+                ApiError::BadRequest(
+                    "第三方账户没有已验证的邮箱，请使用邮箱注册后在账户设置中绑定".to_string(),
+                )
+            })?;
+        let username = Self::pick_username(db, config, &profile.login).await?;
+        let display_name = Self::pick_display_name(db, config, profile, &username).await;
+
+        let random_password: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let hashed_password =
+            PasswordService::hash(random_password, config.password.bcrypt_cost).await?;
+
+        let txn = db.begin().await?;
+        let user = users::ActiveModel {
+            username: Set(username),
+            email: Set(email),
+            hashed_password: Set(hashed_password),
+            display_name: Set(display_name),
+            role: Set(RoleEnum::User),
+            is_active: Set(true),
+            tenant_id: Set(tenant_id.to_string()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        let now = Utc::now();
+        let identity = external_identities::ActiveModel {
+            user_id: Set(user.id),
+            provider: Set(provider.as_str().to_string()),
+            external_id: Set(Some(profile.external_id.clone())),
+            status: Set(LinkStatus::Active.as_str().to_string()),
+            created_at: Set(now),
+            confirmed_at: Set(Some(now)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok((user, identity))
+    }
+
+    /// 由第三方用户名生成本站用户名，已被占用时追加随机数字
+    async fn pick_username(
+        db: &DatabaseConnection,
+        config: &Config,
+        login: &str,
+    ) -> ApiResult<String> {
+        let mut base: String = login
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .take(15)
+            .collect();
+        if base.len() < 3
+            || NamePolicyService::check(db, &config.name_policy, &[&base], None)
+                .await
+                .is_err()
+        {
+            base = "player".to_string();
+        }
+
+        for attempt in 0..Self::USERNAME_ATTEMPTS {
+            let candidate = if attempt == 0 {
+                base.clone()
+            } else {
+                format!("{base}_{}", rand::rng().random_range(1000..10000))
+            };
+            let taken = Users::find()
+                .filter(users::Column::Username.eq(&candidate))
+                .one(db.as_ref())
+                .await?
+                .is_some();
+            if !taken {
+                return Ok(candidate);
+            }
+        }
+
+        let suffix: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(8)
+            .map(char::from)
+            .collect();
+        Ok(format!("player_{}", suffix.to_lowercase()))
+    }
+
+    /// 依次尝试第三方显示名称、第三方用户名与本站用户名，选第一个符合显示名称规则的
+    async fn pick_display_name(
+        db: &DatabaseConnection,
+        config: &Config,
+        profile: &OAuthProfile,
+        username: &str,
+    ) -> String {
+        let candidates = profile
+            .name
+            .iter()
+            .map(|name| name.trim())
+            .chain([profile.login.as_str(), username]);
+        for candidate in candidates {
+            let length = candidate.chars().count();
+            if !(2..=16).contains(&length) || !DISPLAY_NAME_REGEX.is_match(candidate) {
+                continue;
+            }
+            if NamePolicyService::check(db, &config.name_policy, &[candidate], None)
+                .await
+                .is_ok()
+            {
+                return candidate.to_string();
+            }
+        }
+        format!("Player{}", rand::rng().random_range(1000..10000))
+    }
+
+    async fn exchange_code(
+        config: &OAuthConfig,
+        client: &OAuthClientConfig,
+        provider: OAuthProvider,
+        code: &str,
+    ) -> ApiResult<String> {
+        let redirect_uri = Self::redirect_uri(config, provider);
+        let mut form = vec![
+            ("client_id", client.client_id.as_str()),
+            ("client_secret", client.client_secret.as_str()),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ];
+        let endpoint = match provider {
+            OAuthProvider::Github => "https://github.com/login/oauth/access_token".to_string(),
+            OAuthProvider::Microsoft => {
+                form.push(("scope", Self::MICROSOFT_SCOPE));
+                format!(
+                    "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+                    config.microsoft_tenant
+                )
+            }
+        };
+
+        let response: Value = HTTP_CLIENT
+            .post(endpoint)
+            .header(ACCEPT, "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| Self::unavailable(provider, e))?
+            .json()
+            .await
+            .map_err(|e| Self::unavailable(provider, e))?;
+
+        response["access_token"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                tracing::warn!(
+                    "⚠️  {} 授权码兑换失败: {}",
+                    provider.as_str(),
+                    response["error"].as_str().unwrap_or("unknown")
+                );
+                ApiError::BadRequest("授权码无效或已过期".to_string())
+            })
+    }
+
+    async fn fetch_profile(provider: OAuthProvider, access_token: &str) -> ApiResult<OAuthProfile> {
+        match provider {
+            OAuthProvider::Github => {
+                let user =
+                    Self::get_json(provider, "https://api.github.com/user", access_token).await?;
+                let emails =
+                    Self::get_json(provider, "https://api.github.com/user/emails", access_token)
+                        .await?;
+                let primary = emails.as_array().and_then(|emails| {
+                    emails
+                        .iter()
+                        .find(|email| email["primary"].as_bool() == Some(true))
+                });
+
+                Ok(OAuthProfile {
+                    external_id: Self::required(provider, &user["id"])?,
+                    login: user["login"].as_str().unwrap_or_default().to_string(),
+                    name: user["name"].as_str().map(str::to_string),
+                    email: primary
+                        .and_then(|email| email["email"].as_str())
+                        .or_else(|| user["email"].as_str())
+                        .map(str::to_string),
+                    email_verified: primary
+                        .is_some_and(|email| email["verified"].as_bool() == Some(true)),
+                })
+            }
+            OAuthProvider::Microsoft => {
+                let user = Self::get_json(
+                    provider,
+                    "https://graph.microsoft.com/v1.0/me",
+                    access_token,
+                )
+                .await?;
+                let email = user["mail"]
+                    .as_str()
+                    .or_else(|| user["userPrincipalName"].as_str())
+                    .filter(|email| email.contains('@'))
+                    .map(str::to_string);
+
+                Ok(OAuthProfile {
+                    external_id: Self::required(provider, &user["id"])?,
+                    login: email
+                        .as_deref()
+                        .and_then(|email| email.split('@').next())
+                        .unwrap_or_default()
+                        .to_string(),
+                    name: user["displayName"].as_str().map(str::to_string),
+                    email,
+                    // Microsoft 个人账户的邮箱不保证经过验证，不用于关联已有账户
+                    email_verified: false,
+                })
+            }
+        }
+    }
+
+    async fn get_json(provider: OAuthProvider, url: &str, access_token: &str) -> ApiResult<Value> {
+        HTTP_CLIENT
+            .get(url)
+            .bearer_auth(access_token)
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Self::unavailable(provider, e))?
+            .json()
+            .await
+            .map_err(|e| Self::unavailable(provider, e))
+    }
+
+    /// 账户 ID 可能是数字（GitHub）或字符串（Microsoft），统一保存为字符串
+    fn required(provider: OAuthProvider, value: &Value) -> ApiResult<String> {
+        match value {
+            Value::String(id) if !id.is_empty() => Ok(id.clone()),
+            Value::Number(id) => Ok(id.to_string()),
+            _ => Err(ApiError::ServiceUnavailable(format!(
+                "{} 返回的账户资料缺少账户 ID",
+                provider.as_str()
+            ))),
+        }
+    }
+
+    async fn take_state(state: &str) -> ApiResult<Option<OAuthState>> {
+        let payload = Self::redis()?
+            .get_del(&format!("{}:{}", Self::STATE_PREFIX, state))
+            .await?;
+        Ok(payload.and_then(|payload| serde_json::from_str(&payload).ok()))
+    }
+
+    fn client(config: &OAuthConfig, provider: OAuthProvider) -> ApiResult<&OAuthClientConfig> {
+        match provider {
+            OAuthProvider::Github => config.github.as_ref(),
+            OAuthProvider::Microsoft => config.microsoft.as_ref(),
+        }
+        .ok_or_else(|| ApiError::FeatureDisabled(format!("未配置 {} 登录", provider.as_str())))
+    }
+
+    fn redis() -> ApiResult<std::sync::Arc<RedisService>> {
+        RedisService::instance()
+            .ok_or_else(|| ApiError::ServiceUnavailable("第三方登录暂时不可用".to_string()))
+    }
+
+    fn redirect_uri(config: &OAuthConfig, provider: OAuthProvider) -> String {
+        config.redirect_url.replace("{provider}", provider.as_str())
+    }
+
+    fn unavailable(provider: OAuthProvider, error: reqwest::Error) -> ApiError {
+        tracing::warn!("⚠️  请求 {} 失败: {}", provider.as_str(), error);
+        ApiError::ServiceUnavailable(format!("{} 暂时无法访问，请稍后重试", provider.as_str()))
+    }
+}
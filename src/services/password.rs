@@ -0,0 +1,35 @@
+use bcrypt::HashParts;
+use tokio::task;
+
+use crate::errors::{ApiError, ApiResult};
+
+/// 密码哈希服务
+///
+/// bcrypt 计算耗时与成本成指数关系，都放到阻塞线程池中执行，不占用异步运行时。
+/// 成本由 `BCRYPT_COST` 配置，早期注册的账户使用的是成本 12，登录成功后会按当前成本重新哈希。
+pub struct PasswordService;
+
+impl PasswordService {
+    /// 按指定成本计算密码哈希
+    pub async fn hash(password: String, cost: u32) -> ApiResult<String> {
+        task::spawn_blocking(move || bcrypt::hash(password, cost))
+            .await
+            .map_err(|_| ApiError::InternalServerError("密码加密任务失败".to_string()))?
+            .map_err(|e| ApiError::InternalServerError(format!("密码加密失败: {}", e)))
+    }
+
+    /// 校验密码与哈希是否匹配
+    pub async fn verify(password: String, hashed_password: String) -> ApiResult<bool> {
+        task::spawn_blocking(move || bcrypt::verify(password, &hashed_password))
+            .await
+            .map_err(|_| ApiError::InternalServerError("密码校验任务失败".to_string()))?
+            .map_err(|_| ApiError::InternalServerError("密码校验失败".to_string()))
+    }
+
+    /// 哈希的成本与当前配置不同时需要重新计算；无法解析的哈希不处理
+    pub fn needs_rehash(hashed_password: &str, cost: u32) -> bool {
+        hashed_password
+            .parse::<HashParts>()
+            .is_ok_and(|parts| parts.get_cost() != cost)
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use tokio::{net::UdpSocket, time::timeout};
+
+/// RakNet 离线消息标识
+const OFFLINE_MESSAGE_ID: [u8; 16] = [
+    0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, 0x56, 0x78,
+];
+const UNCONNECTED_PING: u8 = 0x01;
+const UNCONNECTED_PONG: u8 = 0x1c;
+
+/// 基岩版 RakNet Unconnected Ping 的结果
+#[derive(Debug)]
+pub struct BedrockStatus {
+    pub version: String,
+    pub online: i64,
+    pub max: i64,
+    /// 两行 MOTD，以换行连接
+    pub motd: String,
+    pub latency: Duration,
+}
+
+/// 发送 Unconnected Ping 并解析服务器返回的状态字符串
+pub async fn ping(addr: SocketAddr, limit: Duration) -> Result<BedrockStatus> {
+    timeout(limit, exchange(addr)).await.context("连接超时")?
+}
+
+async fn exchange(addr: SocketAddr) -> Result<BedrockStatus> {
+    let bind: SocketAddr = if addr.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(bind).await.context("创建 UDP 套接字失败")?;
+    socket.connect(addr).await.context("连接失败")?;
+
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let mut packet = Vec::with_capacity(33);
+    packet.push(UNCONNECTED_PING);
+    packet.extend_from_slice(&timestamp.to_be_bytes());
+    packet.extend_from_slice(&OFFLINE_MESSAGE_ID);
+    packet.extend_from_slice(&rand::random::<i64>().to_be_bytes());
+
+    let started = Instant::now();
+    socket.send(&packet).await.context("发送数据失败")?;
+    let mut buf = vec![0; 2048];
+    let len = socket.recv(&mut buf).await.context("读取数据失败")?;
+    let latency = started.elapsed();
+
+    parse_pong(&buf[..len], latency)
+}
+
+/// Pong 格式：ID(1) 时间戳(8) 服务器 GUID(8) 离线消息标识(16) 字符串长度(2) 状态字符串
+fn parse_pong(packet: &[u8], latency: Duration) -> Result<BedrockStatus> {
+    const HEADER_LEN: usize = 1 + 8 + 8 + 16 + 2;
+    if packet.len() < HEADER_LEN || packet[0] != UNCONNECTED_PONG {
+        bail!("Pong 响应无效");
+    }
+    if packet[17..33] != OFFLINE_MESSAGE_ID {
+        bail!("Pong 响应无效");
+    }
+    let len = u16::from_be_bytes([packet[33], packet[34]]) as usize;
+    let body = packet
+        .get(HEADER_LEN..HEADER_LEN + len)
+        .context("Pong 响应长度无效")?;
+    let body = String::from_utf8_lossy(body);
+
+    // MCPE;第一行 MOTD;协议版本;游戏版本;在线人数;最大人数;服务器 ID;第二行 MOTD;游戏模式;...
+    let fields: Vec<&str> = body.split(';').collect();
+    if fields.len() < 6 {
+        bail!("Pong 状态字符串字段不足");
+    }
+    let motd = match fields.get(7).filter(|line| !line.is_empty()) {
+        Some(second) => format!("{}\n{}", fields[1], second),
+        None => fields[1].to_string(),
+    };
+    Ok(BedrockStatus {
+        version: fields[3].to_string(),
+        online: fields[4].parse().context("在线人数无效")?,
+        max: fields[5].parse().context("最大人数无效")?,
+        motd,
+        latency,
+    })
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use sea_orm::*;
+
+use crate::{
+    config::PingConfig,
+    entities::{prelude::Server, server},
+    errors::ApiResult,
+    schemas::{
+        internal::StatsBatchItem,
+        servers::{AddressHost, ApiServerType, ServerAddress, ServerStats},
+    },
+    services::{
+        database::DatabaseConnection,
+        motd,
+        ping::{bedrock, java},
+        player_index::PlayerIndexService,
+        server::ServerService,
+    },
+};
+
+/// 单次写入的状态条数，与批量上报接口的上限一致
+const INGEST_CHUNK_SIZE: usize = 2000;
+
+/// 内置服务器状态采集
+///
+/// 定期直接向服务器发起 Java 版 Server List Ping 或基岩版 RakNet Ping，结果与
+/// 批量上报接口走同一条写入路径，部署时无需额外的采集程序。已配置推送密钥的服务器
+/// 由服主自行推送状态，不会重复采集；域名只做 A/AAAA 解析，不查询 SRV 记录，
+/// 只探测公网地址。
+pub struct PingService;
+
+impl PingService {
+    /// 定期采集全部服务器的状态
+    pub async fn run_loop(db: DatabaseConnection, config: PingConfig, interval: Duration) {
+        if !config.enabled {
+            return;
+        }
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match Self::run_once(&db, &config).await {
+                Ok((online, offline)) => {
+                    tracing::debug!(
+                        "服务器状态采集完成: 在线 {} 个, 离线 {} 个",
+                        online,
+                        offline
+                    )
+                }
+                Err(e) => tracing::warn!("⚠️  服务器状态采集失败: {}", e),
+            }
+        }
+    }
+
+    /// 执行一轮采集，返回 (在线数量, 离线数量)
+    pub async fn run_once(
+        db: &DatabaseConnection,
+        config: &PingConfig,
+    ) -> ApiResult<(usize, usize)> {
+        let servers = Server::find()
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::PushSecret.is_null())
+            .all(db.as_ref())
+            .await?;
+
+        let collected_at = Utc::now();
+        let items: Vec<StatsBatchItem> = stream::iter(servers)
+            .map(|server| async move {
+                let stats = match Self::ping_server(&server, config.timeout).await {
+                    Ok(stats) => {
+                        if !server.player_search_opt_out {
+                            let sample = stats.sample.as_deref().unwrap_or_default();
+                            PlayerIndexService::index(&server.tenant_id, server.id, sample).await;
+                        }
+                        Some(stats)
+                    }
+                    Err(e) => {
+                        tracing::debug!("服务器 {} ({}) 探测失败: {:#}", server.id, server.ip, e);
+                        None
+                    }
+                };
+                StatsBatchItem {
+                    server_id: server.id,
+                    stats,
+                    collected_at: Some(collected_at),
+                }
+            })
+            .buffer_unordered(config.concurrency.max(1))
+            .collect()
+            .await;
+
+        let online = items.iter().filter(|item| item.stats.is_some()).count();
+        let offline = items.len() - online;
+        let mut items = items;
+        while !items.is_empty() {
+            let rest = items.split_off(items.len().min(INGEST_CHUNK_SIZE));
+            ServerService::ingest_stats_batch(db, items).await?;
+            items = rest;
+        }
+        Ok((online, offline))
+    }
+
+    /// 按服务器类型探测，失败视为离线
+    pub async fn ping_server(server: &server::Model, limit: Duration) -> Result<ServerStats> {
+        let address = ServerAddress::parse(&server.ip).context("服务器地址格式无效")?;
+        if server.r#type.eq_ignore_ascii_case("BEDROCK") {
+            let port = address.port_or_default(&ApiServerType::Bedrock);
+            let addr = Self::resolve(&address.host, port, limit).await?;
+            let status = bedrock::ping(addr, limit).await?;
+            Ok(ServerStats {
+                players: Self::players(status.online, status.max),
+                delay: status.latency.as_secs_f64() * 1000.0,
+                version: status.version,
+                motd: motd::from_legacy(&status.motd),
+                icon: None,
+                sample: None,
+            })
+        } else {
+            let port = address.port_or_default(&ApiServerType::Java);
+            let addr = Self::resolve(&address.host, port, limit).await?;
+            let host = match &address.host {
+                AddressHost::Domain(domain) => domain.clone(),
+                AddressHost::Ip(ip) => ip.to_string(),
+            };
+            let status = java::ping(addr, &host, port, limit).await?;
+            Ok(ServerStats {
+                players: Self::players(status.online, status.max),
+                delay: status.latency.as_secs_f64() * 1000.0,
+                version: status.version,
+                motd: motd::from_description(&status.description),
+                icon: status.favicon,
+                sample: Some(status.sample),
+            })
+        }
+    }
+
+    /// 解析为可以探测的地址，只接受公网地址
+    ///
+    /// 域名解析到的内网地址同样拒绝，避免借服务器地址探测部署环境的内部网络。
+    pub async fn resolve(host: &AddressHost, port: u16, limit: Duration) -> Result<SocketAddr> {
+        let addr = match host {
+            AddressHost::Ip(ip) => SocketAddr::new(*ip, port),
+            AddressHost::Domain(domain) => {
+                tokio::time::timeout(limit, tokio::net::lookup_host((domain.as_str(), port)))
+                    .await
+                    .context("域名解析超时")?
+                    .context("域名解析失败")?
+                    .find(|addr| Self::is_public(addr.ip()))
+                    .context("域名没有可用的公网地址")?
+            }
+        };
+        anyhow::ensure!(Self::is_public(addr.ip()), "服务器地址不是公网地址");
+        Ok(addr)
+    }
+
+    /// 是否为公网地址：排除本机、内网、链路本地、未指定、组播、广播与
+    /// 运营商级 NAT 地址，IPv4 映射的 IPv6 地址按 IPv4 判断
+    pub fn is_public(ip: IpAddr) -> bool {
+        match ip.to_canonical() {
+            IpAddr::V4(ip) => {
+                let [a, b, ..] = ip.octets();
+                !(ip.is_loopback()
+                    || ip.is_private()
+                    || ip.is_link_local()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_broadcast()
+                    || a == 0
+                    || (a == 100 && (64..128).contains(&b)))
+            }
+            IpAddr::V6(ip) => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        }
+    }
+
+    fn players(online: i64, max: i64) -> HashMap<String, i64> {
+        HashMap::from([("online".to_string(), online), ("max".to_string(), max)])
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+
+/// 状态响应的最大长度，带图标的响应通常只有几十 KB
+const MAX_PACKET_LEN: usize = 2 * 1024 * 1024;
+/// 握手中的协议版本，-1 表示只查询状态
+const STATUS_PROTOCOL_VERSION: i32 = -1;
+
+/// Java 版 Server List Ping 的结果
+#[derive(Debug)]
+pub struct JavaStatus {
+    pub version: String,
+    pub online: i64,
+    pub max: i64,
+    pub sample: Vec<String>,
+    /// MOTD，可能是带 § 格式代码的字符串或 JSON 文本组件
+    pub description: Value,
+    /// `data:image/png;base64,...` 格式的图标
+    pub favicon: Option<String>,
+    pub latency: Duration,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    version: Option<StatusVersion>,
+    players: Option<StatusPlayers>,
+    #[serde(default)]
+    description: Value,
+    favicon: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StatusVersion {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct StatusPlayers {
+    max: i64,
+    online: i64,
+    #[serde(default)]
+    sample: Vec<SamplePlayer>,
+}
+
+#[derive(Deserialize)]
+struct SamplePlayer {
+    name: String,
+}
+
+/// 执行握手、状态请求与延迟测量
+///
+/// `host` 写入握手包，部分代理端据此路由到不同的后端服务器。
+pub async fn ping(addr: SocketAddr, host: &str, port: u16, limit: Duration) -> Result<JavaStatus> {
+    timeout(limit, exchange(addr, host, port))
+        .await
+        .context("连接超时")?
+}
+
+async fn exchange(addr: SocketAddr, host: &str, port: u16) -> Result<JavaStatus> {
+    let mut stream = TcpStream::connect(addr).await.context("连接失败")?;
+    stream.set_nodelay(true).ok();
+
+    let mut handshake = Vec::with_capacity(host.len() + 16);
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, STATUS_PROTOCOL_VERSION);
+    write_varint(&mut handshake, host.len() as i32);
+    handshake.extend_from_slice(host.as_bytes());
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    send_packet(&mut stream, &handshake).await?;
+    send_packet(&mut stream, &[0x00]).await?;
+
+    let started = Instant::now();
+    let packet = read_packet(&mut stream).await?;
+    let status_latency = started.elapsed();
+    let mut cursor = packet.as_slice();
+    if read_varint_slice(&mut cursor)? != 0x00 {
+        bail!("状态响应的数据包类型无效");
+    }
+    let len = read_varint_slice(&mut cursor)? as usize;
+    if len > cursor.len() {
+        bail!("状态响应长度无效");
+    }
+    let response: StatusResponse =
+        serde_json::from_slice(&cursor[..len]).context("状态响应不是有效的 JSON")?;
+
+    // 部分服务器不响应 Ping 包，此时以状态请求的往返时间作为延迟
+    let latency = match measure_latency(&mut stream).await {
+        Ok(latency) => latency,
+        Err(_) => status_latency,
+    };
+
+    let players = response.players.unwrap_or(StatusPlayers {
+        max: 0,
+        online: 0,
+        sample: Vec::new(),
+    });
+    Ok(JavaStatus {
+        version: response
+            .version
+            .map(|version| version.name)
+            .unwrap_or_else(|| "Unknown".to_string()),
+        online: players.online,
+        max: players.max,
+        sample: players
+            .sample
+            .into_iter()
+            .map(|player| player.name)
+            .collect(),
+        description: response.description,
+        favicon: response.favicon,
+        latency,
+    })
+}
+
+async fn measure_latency(stream: &mut TcpStream) -> Result<Duration> {
+    let payload = chrono::Utc::now().timestamp_millis();
+    let mut packet = vec![0x01];
+    packet.extend_from_slice(&payload.to_be_bytes());
+
+    let started = Instant::now();
+    send_packet(stream, &packet).await?;
+    let pong = read_packet(stream).await?;
+    if pong.len() != 9 || pong[0] != 0x01 || pong[1..] != payload.to_be_bytes() {
+        bail!("Pong 响应无效");
+    }
+    Ok(started.elapsed())
+}
+
+async fn send_packet(stream: &mut TcpStream, body: &[u8]) -> Result<()> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    write_varint(&mut packet, body.len() as i32);
+    packet.extend_from_slice(body);
+    stream.write_all(&packet).await.context("发送数据失败")
+}
+
+async fn read_packet<R: AsyncRead + Unpin>(stream: &mut R) -> Result<Vec<u8>> {
+    let len = read_varint(stream).await?;
+    if len <= 0 || len as usize > MAX_PACKET_LEN {
+        bail!("数据包长度无效: {len}");
+    }
+    let mut packet = vec![0; len as usize];
+    stream
+        .read_exact(&mut packet)
+        .await
+        .context("读取数据失败")?;
+    Ok(packet)
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+}
+
+async fn read_varint<R: AsyncRead + Unpin>(stream: &mut R) -> Result<i32> {
+    let mut value = 0u32;
+    for position in 0..5 {
+        let byte = stream.read_u8().await.context("读取数据失败")?;
+        value |= u32::from(byte & 0x7F) << (7 * position);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    bail!("VarInt 过长")
+}
+
+fn read_varint_slice(cursor: &mut &[u8]) -> Result<i32> {
+    let mut value = 0u32;
+    for position in 0..5 {
+        let (&byte, rest) = cursor.split_first().context("数据包不完整")?;
+        *cursor = rest;
+        value |= u32::from(byte & 0x7F) << (7 * position);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    bail!("VarInt 过长")
+}
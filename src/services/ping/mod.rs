@@ -0,0 +1,3 @@
+pub mod bedrock;
+pub mod collector;
+pub mod java;
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{prelude::Server, server},
+    errors::{ApiError, ApiResult},
+    schemas::{
+        search::{PlayerSearchResponse, PlayerServer},
+        servers::{ServerStatus, ServerVisibility},
+    },
+    services::{database::DatabaseConnection, redis::RedisService},
+};
+
+/// Redis 键前缀
+const KEY_PREFIX: &str = "player_index";
+/// 索引有效期，服务器停止推送后玩家会在该时间后自然消失
+const INDEX_TTL_SECS: u64 = 600;
+/// 每次推送最多索引的玩家数
+const MAX_SAMPLE_SIZE: usize = 200;
+
+/// 在线玩家名称索引
+///
+/// 服务器通过 push-stats 推送在线玩家样本时，按租户把小写玩家名映射到服务器写入 Redis，
+/// 只保留当前在线的玩家，不写入数据库。只支持完整名称查询，避免按前缀枚举玩家；
+/// 退出玩家搜索、隐藏或停用的服务器不会出现在结果中。
+pub struct PlayerIndexService;
+
+impl PlayerIndexService {
+    /// 用最新的在线玩家样本替换服务器的索引，Redis 不可用时跳过
+    pub async fn index(tenant_id: &str, server_id: i32, sample: &[String]) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+
+        let names: HashSet<String> = sample
+            .iter()
+            .filter_map(|name| Self::normalize(name))
+            .take(MAX_SAMPLE_SIZE)
+            .collect();
+
+        let server_key = Self::server_key(tenant_id, server_id);
+        let previous: HashSet<String> = match redis.get(&server_key).await {
+            Ok(Some(value)) => serde_json::from_str(&value).unwrap_or_default(),
+            Ok(None) => HashSet::new(),
+            Err(e) => {
+                tracing::warn!("⚠️  读取玩家索引失败: {}", e);
+                return;
+            }
+        };
+
+        let left: Vec<String> = previous
+            .difference(&names)
+            .map(|name| Self::name_key(tenant_id, name, server_id))
+            .collect();
+        if let Err(e) = redis.batch_del(&left).await {
+            tracing::warn!("⚠️  清理玩家索引失败: {}", e);
+        }
+
+        let value = serde_json::to_string(&names).unwrap_or_else(|_| "[]".to_string());
+        if let Err(e) = redis.set_ex(&server_key, &value, INDEX_TTL_SECS).await {
+            tracing::warn!("⚠️  写入玩家索引失败: {}", e);
+            return;
+        }
+        let indexed_at = Utc::now().timestamp().to_string();
+        for name in &names {
+            let key = Self::name_key(tenant_id, name, server_id);
+            if let Err(e) = redis.set_ex(&key, &indexed_at, INDEX_TTL_SECS).await {
+                tracing::warn!("⚠️  写入玩家索引失败: {}", e);
+                return;
+            }
+        }
+    }
+
+    /// 移除服务器的全部玩家索引（退出玩家搜索时调用）
+    pub async fn clear(tenant_id: &str, server_id: i32) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+
+        let pattern = format!("{KEY_PREFIX}:{tenant_id}:name:*:{server_id}");
+        if let Err(e) = redis.del_pattern(&pattern).await {
+            tracing::warn!("⚠️  清理玩家索引失败: {}", e);
+        }
+        if let Err(e) = redis.del(&Self::server_key(tenant_id, server_id)).await {
+            tracing::warn!("⚠️  清理玩家索引失败: {}", e);
+        }
+    }
+
+    /// 查询玩家当前所在的服务器
+    pub async fn search(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        name: &str,
+    ) -> ApiResult<PlayerSearchResponse> {
+        let normalized = Self::normalize(name)
+            .ok_or_else(|| ApiError::BadRequest("玩家名称格式无效".to_string()))?;
+        let redis = RedisService::instance()
+            .ok_or_else(|| ApiError::ServiceUnavailable("玩家搜索暂不可用".to_string()))?;
+
+        let pattern = format!("{KEY_PREFIX}:{tenant_id}:name:{normalized}:*");
+        let server_ids: Vec<i32> = redis
+            .scan_keys(&pattern)
+            .await
+            .map_err(|e| ApiError::Internal(format!("查询玩家索引失败: {e}")))?
+            .iter()
+            .filter_map(|key| key.rsplit(':').next()?.parse().ok())
+            .collect();
+        if server_ids.is_empty() {
+            return Ok(PlayerSearchResponse {
+                name: name.to_string(),
+                servers: Vec::new(),
+            });
+        }
+
+        let servers = Server::find()
+            .filter(server::Column::Id.is_in(server_ids))
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::DelistedAt.is_null())
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .filter(server::Column::PlayerSearchOptOut.eq(false))
+            .order_by_asc(server::Column::Id)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(PlayerSearchResponse {
+            name: name.to_string(),
+            servers: servers
+                .into_iter()
+                .map(|server| PlayerServer {
+                    id: server.id,
+                    name: server.name,
+                    slug: server.slug,
+                })
+                .collect(),
+        })
+    }
+
+    /// 统一为小写，不是合法玩家名（3-16 位字母、数字、下划线）的样本条目视为无效
+    fn normalize(name: &str) -> Option<String> {
+        let name = name.trim();
+        let valid = (3..=16).contains(&name.len())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        valid.then(|| name.to_ascii_lowercase())
+    }
+
+    fn server_key(tenant_id: &str, server_id: i32) -> String {
+        format!("{KEY_PREFIX}:{tenant_id}:server:{server_id}")
+    }
+
+    fn name_key(tenant_id: &str, name: &str, server_id: i32) -> String {
+        format!("{KEY_PREFIX}:{tenant_id}:name:{name}:{server_id}")
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    config::NotificationConfig,
+    entities::{
+        notification_opt_outs,
+        prelude::{NotificationOptOuts, UserPreferences as UserPreferencesEntity},
+        user_preferences,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::users::{
+        DigestFrequency, NotificationChannels, NotificationKind, UpdatePreferencesRequest,
+        UserPreferences,
+    },
+    services::database::DatabaseConnection,
+};
+
+/// 用户偏好设置服务
+///
+/// 未保存过偏好的用户使用默认值：开启邮件与站内通知、立即发送、配置中的默认语言。
+/// 按类型关闭的通知单独保存在 `notification_opt_outs`，每行一个关闭的类型。
+pub struct PreferenceService;
+
+impl PreferenceService {
+    /// 获取用户偏好，未保存过时返回默认值
+    pub async fn get(
+        db: &DatabaseConnection,
+        config: &NotificationConfig,
+        user_id: i32,
+    ) -> ApiResult<UserPreferences> {
+        let stored = UserPreferencesEntity::find_by_id(user_id)
+            .one(db.as_ref())
+            .await?;
+        let mut preferences = match stored {
+            Some(model) => Self::to_schema(model),
+            None => Self::defaults(config),
+        };
+        preferences.muted_kinds = Self::muted_kinds(db.as_ref(), user_id).await?;
+        Ok(preferences)
+    }
+
+    /// 更新用户偏好，未传的字段保持不变
+    pub async fn update(
+        db: &DatabaseConnection,
+        config: &NotificationConfig,
+        user_id: i32,
+        request: UpdatePreferencesRequest,
+    ) -> ApiResult<UserPreferences> {
+        if let Some(kind) = request
+            .muted_kinds
+            .iter()
+            .flatten()
+            .find(|kind| !kind.mutable())
+        {
+            return Err(ApiError::BadRequest(format!(
+                "通知类型 {} 不能关闭",
+                kind.as_str()
+            )));
+        }
+
+        let current = Self::get(db, config, user_id).await?;
+        let channels = request.channels.unwrap_or(current.channels);
+        let digest_frequency = request.digest_frequency.unwrap_or(current.digest_frequency);
+        let locale = request.locale.unwrap_or(current.locale);
+
+        let model = user_preferences::ActiveModel {
+            user_id: Set(user_id),
+            email_enabled: Set(channels.email),
+            in_app_enabled: Set(channels.in_app),
+            digest_frequency: Set(digest_frequency.as_str().to_string()),
+            locale: Set(locale),
+            updated_at: Set(Utc::now()),
+        };
+        let txn = db.begin().await?;
+        UserPreferencesEntity::insert(model)
+            .on_conflict(
+                sea_query::OnConflict::column(user_preferences::Column::UserId)
+                    .update_columns([
+                        user_preferences::Column::EmailEnabled,
+                        user_preferences::Column::InAppEnabled,
+                        user_preferences::Column::DigestFrequency,
+                        user_preferences::Column::Locale,
+                        user_preferences::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        if let Some(mut muted_kinds) = request.muted_kinds {
+            muted_kinds.sort_by_key(|kind| kind.as_str());
+            muted_kinds.dedup();
+            NotificationOptOuts::delete_many()
+                .filter(notification_opt_outs::Column::UserId.eq(user_id))
+                .exec(&txn)
+                .await?;
+            if !muted_kinds.is_empty() {
+                let now = Utc::now();
+                NotificationOptOuts::insert_many(muted_kinds.into_iter().map(|kind| {
+                    notification_opt_outs::ActiveModel {
+                        user_id: Set(user_id),
+                        kind: Set(kind.as_str().to_string()),
+                        created_at: Set(now),
+                    }
+                }))
+                .exec_without_returning(&txn)
+                .await?;
+            }
+        }
+        txn.commit().await?;
+
+        Self::get(db, config, user_id).await
+    }
+
+    fn defaults(config: &NotificationConfig) -> UserPreferences {
+        UserPreferences {
+            channels: NotificationChannels {
+                email: true,
+                in_app: true,
+            },
+            digest_frequency: DigestFrequency::Instant,
+            muted_kinds: Vec::new(),
+            locale: config.default_locale.clone(),
+        }
+    }
+
+    fn to_schema(model: user_preferences::Model) -> UserPreferences {
+        UserPreferences {
+            channels: NotificationChannels {
+                email: model.email_enabled,
+                in_app: model.in_app_enabled,
+            },
+            digest_frequency: DigestFrequency::parse(&model.digest_frequency)
+                .unwrap_or(DigestFrequency::Instant),
+            muted_kinds: Vec::new(),
+            locale: model.locale,
+        }
+    }
+
+    async fn muted_kinds<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> ApiResult<Vec<NotificationKind>> {
+        let kinds: Vec<String> = NotificationOptOuts::find()
+            .select_only()
+            .column(notification_opt_outs::Column::Kind)
+            .filter(notification_opt_outs::Column::UserId.eq(user_id))
+            .order_by_asc(notification_opt_outs::Column::Kind)
+            .into_tuple()
+            .all(db)
+            .await?;
+        Ok(kinds
+            .iter()
+            .filter_map(|kind| NotificationKind::parse(kind))
+            .collect())
+    }
+}
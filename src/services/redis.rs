@@ -1,6 +1,7 @@
 use anyhow::Result;
 use redis::aio::ConnectionManager;
 use redis::{Client, RedisResult};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 use tracing::error;
@@ -12,6 +13,38 @@ pub struct RedisService {
     manager: ConnectionManager,
 }
 
+/// 键存在时自减，不会凭空创建没有过期时间的计数
+const DECR_EXISTING_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 1 then
+    return redis.call('DECR', KEYS[1])
+end
+return 0
+"#;
+
+/// 令牌桶脚本：按上次更新时间补充令牌后尝试取出一个，桶满后闲置的键自动过期
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+redis.replicate_commands()
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2]) / 60000
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or capacity
+local ts = tonumber(state[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+local allowed = 0
+local wait = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+else
+  wait = math.ceil((1 - tokens) / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / rate) + 1000)
+return {allowed, math.floor(tokens), wait}
+"#;
+
 // 全局 Redis 实例
 static REDIS_INSTANCE: OnceCell<Arc<RedisService>> = OnceCell::const_new();
 
@@ -96,6 +129,37 @@ impl RedisService {
         result.map_err(|e| anyhow::anyhow!("Redis GET 失败: {}", e))
     }
 
+    /// 获取并删除键的值，用于只能使用一次的凭证
+    pub async fn get_del(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<Option<String>> =
+            redis::cmd("GETDEL").arg(key).query_async(&mut conn).await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis GETDEL 失败: {}", e))
+    }
+
+    /// 设置二进制值，带过期时间（秒）
+    pub async fn set_ex_bytes(&self, key: &str, value: &[u8], expire_seconds: u64) -> Result<()> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<()> = redis::cmd("SETEX")
+            .arg(key)
+            .arg(expire_seconds)
+            .arg(value)
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis SETEX 失败: {}", e))
+    }
+
+    /// 获取二进制值
+    pub async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<Option<Vec<u8>>> =
+            redis::cmd("GET").arg(key).query_async(&mut conn).await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis GET 失败: {}", e))
+    }
+
     /// 检查键是否存在
     pub async fn exists(&self, key: &str) -> Result<bool> {
         let mut conn = self.manager.clone();
@@ -174,6 +238,52 @@ impl RedisService {
         result.map_err(|e| anyhow::anyhow!("Redis EXPIRE 失败: {}", e))
     }
 
+    /// 自增计数，首次创建时设置过期时间（秒），返回自增后的值
+    pub async fn incr_ex(&self, key: &str, expire_seconds: u64) -> Result<i64> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<i64> = redis::cmd("INCR").arg(key).query_async(&mut conn).await;
+        let count = result.map_err(|e| anyhow::anyhow!("Redis INCR 失败: {}", e))?;
+
+        if count == 1 {
+            self.expire(key, expire_seconds).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// 撤销一次 [`incr_ex`](Self::incr_ex)，计数已过期或被删除时不做任何事，返回自减后的值
+    pub async fn decr_existing(&self, key: &str) -> Result<i64> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<i64> = redis::Script::new(DECR_EXISTING_SCRIPT)
+            .key(key)
+            .invoke_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis DECR 失败: {}", e))
+    }
+
+    /// 令牌桶取一个令牌，时间取 Redis 服务器时钟，多实例共享同一个桶
+    ///
+    /// 返回 `(是否放行, 剩余令牌数, 需等待的毫秒数)`，放行时等待为 0
+    pub async fn take_token(
+        &self,
+        key: &str,
+        capacity: u32,
+        refill_per_minute: u32,
+    ) -> Result<(bool, u64, u64)> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<(i64, i64, i64)> = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(capacity.max(1))
+            .arg(refill_per_minute.max(1))
+            .invoke_async(&mut conn)
+            .await;
+        let (allowed, remaining, wait_ms) =
+            result.map_err(|e| anyhow::anyhow!("Redis 令牌桶脚本执行失败: {}", e))?;
+
+        Ok((allowed == 1, remaining.max(0) as u64, wait_ms.max(0) as u64))
+    }
+
     /// 批量删除匹配模式的键
     pub async fn del_pattern(&self, pattern: &str) -> Result<u64> {
         let keys = self.scan_keys(pattern).await?;
@@ -247,6 +357,137 @@ impl RedisService {
         }
     }
 
+    /// 向列表头部写入元素并裁剪到指定长度，用作定长环形缓冲
+    pub async fn lpush_trim(&self, key: &str, value: &str, max_len: usize) -> Result<()> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<()> = redis::pipe()
+            .atomic()
+            .cmd("LPUSH")
+            .arg(key)
+            .arg(value)
+            .ignore()
+            .cmd("LTRIM")
+            .arg(key)
+            .arg(0)
+            .arg(max_len.saturating_sub(1))
+            .ignore()
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis LPUSH 失败: {}", e))
+    }
+
+    /// 向有序集合写入成员，同时移除分数低于 `prune_below` 的成员并刷新过期时间
+    pub async fn zadd_prune_ex(
+        &self,
+        key: &str,
+        member: &str,
+        score: i64,
+        prune_below: i64,
+        expire_seconds: u64,
+    ) -> Result<()> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<()> = redis::pipe()
+            .atomic()
+            .cmd("ZADD")
+            .arg(key)
+            .arg(score)
+            .arg(member)
+            .ignore()
+            .cmd("ZREMRANGEBYSCORE")
+            .arg(key)
+            .arg("-inf")
+            .arg(format!("({prune_below}"))
+            .ignore()
+            .cmd("EXPIRE")
+            .arg(key)
+            .arg(expire_seconds)
+            .ignore()
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis ZADD 失败: {}", e))
+    }
+
+    /// 从有序集合中移除成员，返回成员是否存在
+    pub async fn zrem(&self, key: &str, member: &str) -> Result<bool> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<bool> = redis::cmd("ZREM")
+            .arg(key)
+            .arg(member)
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis ZREM 失败: {}", e))
+    }
+
+    /// 写入哈希字段，并让键在 `expire_at`（Unix 时间戳）过期
+    pub async fn hset_expire_at(
+        &self,
+        key: &str,
+        fields: &[(&str, String)],
+        expire_at: i64,
+    ) -> Result<()> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.manager.clone();
+        let mut hset = redis::cmd("HSET");
+        hset.arg(key);
+        for (field, value) in fields {
+            hset.arg(*field).arg(value);
+        }
+        let result: RedisResult<()> = redis::pipe()
+            .atomic()
+            .add_command(hset)
+            .ignore()
+            .cmd("EXPIREAT")
+            .arg(key)
+            .arg(expire_at)
+            .ignore()
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis HSET 失败: {}", e))
+    }
+
+    /// 读取哈希的全部字段，键不存在时返回空表
+    pub async fn hgetall(&self, key: &str) -> Result<HashMap<String, String>> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<HashMap<String, String>> =
+            redis::cmd("HGETALL").arg(key).query_async(&mut conn).await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis HGETALL 失败: {}", e))
+    }
+
+    /// 读取有序集合中分数不低于 `min_score` 的成员及其分数
+    pub async fn zrange_from_score(&self, key: &str, min_score: i64) -> Result<Vec<(String, i64)>> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<Vec<(String, i64)>> = redis::cmd("ZRANGEBYSCORE")
+            .arg(key)
+            .arg(min_score)
+            .arg("+inf")
+            .arg("WITHSCORES")
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis ZRANGEBYSCORE 失败: {}", e))
+    }
+
+    /// 读取列表中的元素，`stop` 为 -1 时读取到末尾
+    pub async fn lrange(&self, key: &str, start: isize, stop: isize) -> Result<Vec<String>> {
+        let mut conn = self.manager.clone();
+        let result: RedisResult<Vec<String>> = redis::cmd("LRANGE")
+            .arg(key)
+            .arg(start)
+            .arg(stop)
+            .query_async(&mut conn)
+            .await;
+
+        result.map_err(|e| anyhow::anyhow!("Redis LRANGE 失败: {}", e))
+    }
+
     /// 获取 Redis 信息
     pub async fn info(&self) -> Result<String> {
         let mut conn = self.manager.clone();
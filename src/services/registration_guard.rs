@@ -0,0 +1,356 @@
+use std::{collections::BTreeMap, net::Ipv6Addr};
+
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use serde_json::json;
+
+use crate::{
+    config::RegistrationGuardConfig,
+    entities::{
+        prelude::{RegistrationFlags, Users},
+        registration_flags, users,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::admin::{
+        FlagReasonPrecision, RegistrationFlagInfo, RegistrationFlagStats, RegistrationFlagStatus,
+    },
+    services::{database::DatabaseConnection, metrics::MetricsService, redis::RedisService},
+};
+
+/// 同一 IP 短时间内大量注册
+pub const REASON_IP_BURST: &str = "ip_burst";
+/// 同前缀、数字结尾的连续用户名
+pub const REASON_SEQUENTIAL_USERNAME: &str = "sequential_username";
+/// 邮箱域名在标记列表中
+pub const REASON_FLAGGED_DOMAIN: &str = "flagged_domain";
+
+/// 可疑注册识别服务
+///
+/// 只做标记供人工审核，不会阻止注册；审核结果用于统计各规则的准确率。
+pub struct RegistrationGuardService;
+
+impl RegistrationGuardService {
+    /// IP 注册计数键前缀
+    const IP_COUNTER_PREFIX: &'static str = "registration:ip";
+
+    /// 检查新注册的用户，命中规则时写入标记
+    pub async fn inspect(
+        db: &DatabaseConnection,
+        config: &RegistrationGuardConfig,
+        user: &users::Model,
+        ip: Option<&str>,
+    ) -> ApiResult<()> {
+        let mut reasons = Vec::new();
+
+        if let Some(ip) = ip {
+            if Self::is_ip_burst(config, ip).await {
+                reasons.push(REASON_IP_BURST);
+            }
+        }
+        if Self::is_sequential_username(db, config, &user.username).await? {
+            reasons.push(REASON_SEQUENTIAL_USERNAME);
+        }
+        if Self::is_flagged_domain(config, &user.email) {
+            reasons.push(REASON_FLAGGED_DOMAIN);
+        }
+
+        if reasons.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            "注册被标记为可疑: user_id={}, reasons={:?}",
+            user.id,
+            reasons
+        );
+        for reason in &reasons {
+            MetricsService::inc_counter(
+                "registration_flags_total",
+                "被标记的可疑注册数",
+                &[("reason", *reason)],
+                1.0,
+            );
+        }
+
+        registration_flags::ActiveModel {
+            user_id: Set(user.id),
+            reasons: Set(json!(reasons)),
+            ip: Set(ip.map(str::to_string)),
+            status: Set(RegistrationFlagStatus::Pending.as_str().to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// 分页获取租户内的标记列表
+    pub async fn list_flags(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        status: RegistrationFlagStatus,
+        page: u64,
+        page_size: u64,
+    ) -> ApiResult<(Vec<RegistrationFlagInfo>, u64)> {
+        let paginator = RegistrationFlags::find()
+            .filter(registration_flags::Column::Status.eq(status.as_str()))
+            .find_also_related(Users)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .order_by_desc(registration_flags::Column::Id)
+            .paginate(db.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(|(flag, user)| Self::to_info(flag, user))
+            .collect();
+
+        Ok((data, total))
+    }
+
+    /// 审核标记；确认可疑时停用该账户。被标记的用户不属于该租户时视为标记不存在
+    pub async fn review_flag(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        flag_id: i32,
+        reviewer_id: i32,
+        confirmed: bool,
+    ) -> ApiResult<RegistrationFlagInfo> {
+        let txn = db.begin().await?;
+
+        let flag = RegistrationFlags::find_by_id(flag_id)
+            .inner_join(Users)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("标记不存在".to_string()))?;
+        if flag.status != RegistrationFlagStatus::Pending.as_str() {
+            return Err(ApiError::Conflict("该标记已处理".to_string()));
+        }
+
+        let status = if confirmed {
+            RegistrationFlagStatus::Confirmed
+        } else {
+            RegistrationFlagStatus::Dismissed
+        };
+        let reasons = Self::parse_reasons(&flag.reasons);
+
+        let mut active: registration_flags::ActiveModel = flag.into();
+        active.status = Set(status.as_str().to_string());
+        active.reviewed_by_id = Set(Some(reviewer_id));
+        active.reviewed_at = Set(Some(Utc::now()));
+        let flag = active.update(&txn).await?;
+
+        let user = Users::find_by_id(flag.user_id).one(&txn).await?;
+        let user = match (confirmed, user) {
+            (true, Some(user)) => {
+                let mut active: users::ActiveModel = user.into();
+                active.is_active = Set(false);
+                Some(active.update(&txn).await?)
+            }
+            (_, user) => user,
+        };
+
+        txn.commit().await?;
+
+        for reason in &reasons {
+            MetricsService::inc_counter(
+                "registration_flag_reviews_total",
+                "已审核的可疑注册标记数",
+                &[("reason", reason.as_str()), ("outcome", status.as_str())],
+                1.0,
+            );
+        }
+        // 审核后刷新准确率指标
+        if let Err(e) = Self::stats(db).await {
+            tracing::warn!("⚠️  刷新注册标记准确率失败: {}", e);
+        }
+
+        Ok(Self::to_info(flag, user))
+    }
+
+    /// 统计各规则的标记准确率，同时更新指标
+    pub async fn stats(db: &DatabaseConnection) -> ApiResult<RegistrationFlagStats> {
+        let flags: Vec<(String, JsonValue)> = RegistrationFlags::find()
+            .select_only()
+            .column(registration_flags::Column::Status)
+            .column(registration_flags::Column::Reasons)
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let (mut pending, mut confirmed, mut dismissed) = (0, 0, 0);
+        let mut by_reason: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+
+        for (status, reasons) in flags {
+            let is_confirmed = match status.as_str() {
+                "confirmed" => {
+                    confirmed += 1;
+                    true
+                }
+                "dismissed" => {
+                    dismissed += 1;
+                    false
+                }
+                _ => {
+                    pending += 1;
+                    continue;
+                }
+            };
+            for reason in Self::parse_reasons(&reasons) {
+                let entry = by_reason.entry(reason).or_default();
+                if is_confirmed {
+                    entry.0 += 1;
+                } else {
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        let by_reason: Vec<FlagReasonPrecision> = by_reason
+            .into_iter()
+            .map(|(reason, (confirmed, dismissed))| {
+                let precision = Self::precision(confirmed, dismissed);
+                if let Some(precision) = precision {
+                    MetricsService::set_gauge(
+                        "registration_flag_precision",
+                        "可疑注册标记准确率（已确认 / 已审核）",
+                        &[("reason", reason.as_str())],
+                        precision,
+                    );
+                }
+                FlagReasonPrecision {
+                    reason,
+                    confirmed,
+                    dismissed,
+                    precision,
+                }
+            })
+            .collect();
+
+        Ok(RegistrationFlagStats {
+            pending,
+            confirmed,
+            dismissed,
+            precision: Self::precision(confirmed, dismissed),
+            by_reason,
+        })
+    }
+
+    async fn is_ip_burst(config: &RegistrationGuardConfig, ip: &str) -> bool {
+        let Some(redis) = RedisService::instance() else {
+            return false;
+        };
+
+        let key = format!("{}:{}", Self::IP_COUNTER_PREFIX, Self::ip_bucket(ip));
+        match redis.incr_ex(&key, config.ip_burst_window_secs).await {
+            Ok(count) => count > config.ip_burst_threshold,
+            Err(e) => {
+                tracing::warn!("⚠️  注册 IP 计数失败: {}", e);
+                false
+            }
+        }
+    }
+
+    /// IPv6 按 /64 前缀计数，同一用户通常拥有整个 /64 网段，按单个地址计数无法识别批量注册
+    fn ip_bucket(ip: &str) -> String {
+        match ip.parse::<Ipv6Addr>() {
+            Ok(ip) => {
+                let prefix = u128::from(ip) & !((1u128 << 64) - 1);
+                format!("{}/64", Ipv6Addr::from(prefix))
+            }
+            Err(_) => ip.to_string(),
+        }
+    }
+
+    async fn is_sequential_username(
+        db: &DatabaseConnection,
+        config: &RegistrationGuardConfig,
+        username: &str,
+    ) -> ApiResult<bool> {
+        let base = username.trim_end_matches(|c: char| c.is_ascii_digit());
+        if base.len() == username.len() || base.chars().count() < 2 {
+            return Ok(false);
+        }
+
+        let escaped = base
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let recent: Vec<String> = Users::find()
+            .select_only()
+            .column(users::Column::Username)
+            .filter(users::Column::Username.like(format!("{escaped}%")))
+            .filter(users::Column::CreatedAt.gte(Utc::now() - Duration::hours(24)))
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let similar = recent
+            .iter()
+            .filter_map(|name| name.strip_prefix(base))
+            .filter(|suffix| !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()))
+            .count() as u64;
+
+        Ok(similar >= config.sequential_username_threshold)
+    }
+
+    fn is_flagged_domain(config: &RegistrationGuardConfig, email: &str) -> bool {
+        let Some((_, domain)) = email.rsplit_once('@') else {
+            return false;
+        };
+        let domain = domain.to_lowercase();
+
+        config
+            .flagged_email_domains
+            .iter()
+            .any(|flagged| domain == *flagged || domain.ends_with(&format!(".{flagged}")))
+    }
+
+    fn parse_reasons(reasons: &JsonValue) -> Vec<String> {
+        reasons
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn precision(confirmed: u64, dismissed: u64) -> Option<f64> {
+        let reviewed = confirmed + dismissed;
+        (reviewed > 0).then(|| confirmed as f64 / reviewed as f64)
+    }
+
+    fn to_info(
+        flag: registration_flags::Model,
+        user: Option<users::Model>,
+    ) -> RegistrationFlagInfo {
+        let status = match flag.status.as_str() {
+            "confirmed" => RegistrationFlagStatus::Confirmed,
+            "dismissed" => RegistrationFlagStatus::Dismissed,
+            _ => RegistrationFlagStatus::Pending,
+        };
+        let (username, email) = user
+            .map(|user| (user.username, user.email))
+            .unwrap_or_default();
+
+        RegistrationFlagInfo {
+            id: flag.id,
+            user_id: flag.user_id,
+            username,
+            email,
+            reasons: Self::parse_reasons(&flag.reasons),
+            ip: flag.ip,
+            status,
+            created_at: flag.created_at,
+            reviewed_by_id: flag.reviewed_by_id,
+            reviewed_at: flag.reviewed_at,
+        }
+    }
+}
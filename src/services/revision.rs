@@ -0,0 +1,157 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        prelude::{Server, ServerRevision as ServerRevisionEntity},
+        server, server_revision,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::servers::ServerRevision,
+    services::{
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+        server::ServerService,
+    },
+};
+
+/// 每个服务器保留的最大版本数，超出时删除最早的版本
+const MAX_REVISIONS_PER_SERVER: i32 = 50;
+
+/// 服务器信息修订服务
+///
+/// 名称、描述或标签发生变化时保存完整快照。服务器第一次被修改时会先补一份修改前的
+/// 初始版本，保证任何一次修改都能回滚。
+pub struct ServerRevisionService;
+
+impl ServerRevisionService {
+    /// 记录一次修改；内容未变化时不产生新版本
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        previous: &server::Model,
+        current: &server::Model,
+        editor_id: Option<i32>,
+        rolled_back_from: Option<i32>,
+    ) -> ApiResult<()> {
+        if previous.name == current.name
+            && previous.desc == current.desc
+            && previous.tags == current.tags
+        {
+            return Ok(());
+        }
+
+        let latest: Option<i32> = ServerRevisionEntity::find()
+            .select_only()
+            .column_as(server_revision::Column::Revision.max(), "revision")
+            .filter(server_revision::Column::ServerId.eq(current.id))
+            .into_tuple::<Option<i32>>()
+            .one(db)
+            .await?
+            .flatten();
+
+        let next = match latest {
+            Some(latest) => latest + 1,
+            None => {
+                Self::insert(db, previous, 1, None, None).await?;
+                2
+            }
+        };
+        Self::insert(db, current, next, editor_id, rolled_back_from).await?;
+
+        // 清理过旧的版本
+        ServerRevisionEntity::delete_many()
+            .filter(server_revision::Column::ServerId.eq(current.id))
+            .filter(server_revision::Column::Revision.lte(next - MAX_REVISIONS_PER_SERVER))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 获取服务器的修订版本，按版本号倒序
+    pub async fn list(db: &DatabaseConnection, server_id: i32) -> ApiResult<Vec<ServerRevision>> {
+        let revisions = ServerRevisionEntity::find()
+            .filter(server_revision::Column::ServerId.eq(server_id))
+            .order_by_desc(server_revision::Column::Revision)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(revisions.into_iter().map(Self::to_schema).collect())
+    }
+
+    /// 将服务器的名称、描述与标签回滚到指定版本，调用方需已确认操作者是服主
+    pub async fn rollback(
+        db: &DatabaseConnection,
+        server_id: i32,
+        revision_id: i32,
+        user_id: i32,
+    ) -> ApiResult<()> {
+        let txn = db.begin().await?;
+
+        let server = Server::find_by_id(server_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+        let revision = ServerRevisionEntity::find_by_id(revision_id)
+            .filter(server_revision::Column::ServerId.eq(server_id))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("版本不存在".to_string()))?;
+
+        let previous = server.clone();
+        let mut active: server::ActiveModel = server.into();
+        active.name = Set(revision.name);
+        active.desc = Set(revision.desc);
+        active.tags = Set(revision.tags);
+        let current = active.update(&txn).await?;
+
+        Self::record(
+            &txn,
+            &previous,
+            &current,
+            Some(user_id),
+            Some(revision.revision),
+        )
+        .await?;
+
+        txn.commit().await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+        Ok(())
+    }
+
+    async fn insert<C: ConnectionTrait>(
+        db: &C,
+        snapshot: &server::Model,
+        revision: i32,
+        editor_id: Option<i32>,
+        rolled_back_from: Option<i32>,
+    ) -> ApiResult<()> {
+        server_revision::ActiveModel {
+            server_id: Set(snapshot.id),
+            revision: Set(revision),
+            name: Set(snapshot.name.clone()),
+            desc: Set(snapshot.desc.clone()),
+            tags: Set(snapshot.tags.clone()),
+            editor_id: Set(editor_id),
+            rolled_back_from: Set(rolled_back_from),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(())
+    }
+
+    fn to_schema(revision: server_revision::Model) -> ServerRevision {
+        ServerRevision {
+            id: revision.id,
+            revision: revision.revision,
+            tags: ServerService::parse_server_tags(&revision.tags),
+            name: revision.name,
+            desc: revision.desc,
+            editor_id: revision.editor_id,
+            rolled_back_from: revision.rolled_back_from,
+            created_at: revision.created_at,
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use validator::Validate;
+
+use crate::{
+    errors::{ApiError, ApiResult},
+    handlers::servers::ListQuery,
+    schemas::{
+        pagination::Paginated,
+        search::{SearchParams, SearchResponse, ServerResult},
+        servers::{
+            ApiAuthMode, ApiServerType, GalleryImage, GameVersion, ImageUrls, IpFamily,
+            ManagerInfo, Motd, ServerDetail, ServerGallery, ServerManagersResponse, ServerStats,
+            ServerTotalPlayers, ServerVisibility, UpdateServerRequest, VersionRange,
+        },
+    },
+    services::{file_upload::ImageVariant, server::PaginatedServerResult},
+};
+
+/// 沙盒模式下固定的邮箱验证码
+pub const SANDBOX_EMAIL_CODE: &str = "123456";
+
+/// 沙盒服务
+///
+/// 提供确定性的固定数据，不访问数据库、不发送邮件、不上传 S3，
+/// 供启动器等第三方开发者联调使用。
+pub struct SandboxService;
+
+/// (id, 名称, 类型, 认证方式, 版本, 是否成员, 是否隐藏, 标签, 在线人数, 最大人数)
+type FixtureRow = (
+    i32,
+    &'static str,
+    ApiServerType,
+    ApiAuthMode,
+    &'static str,
+    bool,
+    bool,
+    &'static [&'static str],
+    i64,
+    i64,
+);
+
+const FIXTURES: [FixtureRow; 6] = [
+    (
+        1,
+        "沙盒生存服",
+        ApiServerType::Java,
+        ApiAuthMode::Official,
+        "1.20.1",
+        true,
+        false,
+        &["生存", "纯净"],
+        42,
+        100,
+    ),
+    (
+        2,
+        "沙盒生电服",
+        ApiServerType::Java,
+        ApiAuthMode::Yggdrasil,
+        "1.21",
+        true,
+        false,
+        &["生电", "技术"],
+        18,
+        50,
+    ),
+    (
+        3,
+        "沙盒小游戏服",
+        ApiServerType::Java,
+        ApiAuthMode::Offline,
+        "1.8.9",
+        false,
+        false,
+        &["小游戏", "PVP"],
+        256,
+        1000,
+    ),
+    (
+        4,
+        "沙盒基岩服",
+        ApiServerType::Bedrock,
+        ApiAuthMode::Official,
+        "1.21.0",
+        true,
+        false,
+        &["生存"],
+        7,
+        30,
+    ),
+    (
+        5,
+        "沙盒隐藏服",
+        ApiServerType::Java,
+        ApiAuthMode::Official,
+        "1.19.4",
+        true,
+        true,
+        &["RPG"],
+        0,
+        20,
+    ),
+    (
+        6,
+        "沙盒公益服",
+        ApiServerType::Java,
+        ApiAuthMode::Official,
+        "1.20.4",
+        false,
+        false,
+        &["公益", "生存"],
+        3,
+        20,
+    ),
+];
+
+impl SandboxService {
+    /// 获取全部固定服务器数据
+    pub fn fixtures() -> Vec<ServerDetail> {
+        FIXTURES.iter().map(Self::build_detail).collect()
+    }
+
+    /// 按与正式接口相同的规则过滤并分页
+    pub fn list_servers(list_query: &ListQuery) -> PaginatedServerResult {
+        let mut servers: Vec<ServerDetail> = Self::fixtures()
+            .into_iter()
+            .filter(|s| !list_query.is_member || s.is_member)
+            .filter(|s| {
+                list_query
+                    .r#type
+                    .as_ref()
+                    .is_none_or(|types| types.contains(&Self::type_str(&s.r#type).to_string()))
+            })
+            .filter(|s| {
+                list_query.auth_mode.as_ref().is_none_or(|modes| {
+                    modes.contains(&Self::auth_mode_str(&s.auth_mode).to_string())
+                })
+            })
+            .filter(|s| {
+                list_query.tags.as_ref().is_none_or(|required| {
+                    s.tags
+                        .as_ref()
+                        .is_some_and(|tags| required.iter().any(|t| tags.contains(t)))
+                })
+            })
+            .filter(|s| {
+                list_query
+                    .compatible_with
+                    .as_deref()
+                    .is_none_or(|wanted| Self::is_compatible(s, wanted))
+            })
+            .collect();
+
+        let total = servers.len() as i64;
+
+        // 沙盒中未指定种子时也使用固定种子，保证结果可复现
+        let mut rng = StdRng::seed_from_u64(list_query.seed.unwrap_or(114514) as u64);
+        servers.shuffle(&mut rng);
+
+        let start = ((list_query.page - 1) * list_query.page_size) as usize;
+        let data = servers
+            .into_iter()
+            .skip(start)
+            .take(list_query.page_size as usize)
+            .collect();
+
+        PaginatedServerResult { data, total }
+    }
+
+    /// 与正式接口的兼容版本筛选一致：有版本范围时按范围判断，否则按 `version` 精确匹配
+    fn is_compatible(server: &ServerDetail, wanted: &str) -> bool {
+        let Some(version) = GameVersion::parse(wanted) else {
+            return false;
+        };
+        match &server.version_range {
+            Some(range) => range.contains(&version),
+            None => server.version == version.to_string(),
+        }
+    }
+
+    /// 获取服务器详情
+    pub fn get_server_detail(server_id: i32) -> ApiResult<ServerDetail> {
+        Self::fixtures()
+            .into_iter()
+            .find(|s| s.id == server_id)
+            .ok_or_else(ApiError::server_not_found)
+    }
+
+    /// 校验更新请求并返回合并后的结果，不做任何持久化
+    pub fn update_server(
+        server_id: i32,
+        update_data: UpdateServerRequest,
+    ) -> ApiResult<ServerDetail> {
+        let mut server = Self::get_server_detail(server_id)?;
+
+        update_data
+            .validate()
+            .map_err(|e| ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+        server.name = update_data.name;
+        server.ip = if server.is_hide {
+            None
+        } else {
+            Some(update_data.ip)
+        };
+        server.ip_family = server.ip.as_deref().and_then(IpFamily::of);
+        server.desc = update_data.desc;
+        server.tags = Some(update_data.tags);
+        server.version = update_data.version;
+        match update_data.version_range.as_deref().map(str::trim) {
+            Some("") => server.version_range = None,
+            Some(input) => {
+                server.version_range =
+                    Some(VersionRange::parse(input).map_err(ApiError::BadRequest)?)
+            }
+            None => {}
+        }
+        server.link = update_data.link;
+        server.permission = "owner".to_string();
+        if update_data.cover.is_some() {
+            let cover_url = format!("https://sandbox.example.com/static/covers/{server_id}.webp");
+            server.cover_urls = Some(Self::image_urls(&cover_url));
+            server.cover_url = Some(cover_url);
+        }
+
+        Ok(server)
+    }
+
+    /// 获取服务器管理员列表
+    pub fn get_server_managers(server_id: i32) -> ApiResult<ServerManagersResponse> {
+        Self::get_server_detail(server_id)?;
+
+        Ok(ServerManagersResponse {
+            owners: vec![ManagerInfo {
+                id: 1,
+                display_name: "沙盒服主".to_string(),
+                is_active: true,
+                avatar_url: Some("https://sandbox.example.com/static/avatars/1.webp".to_string()),
+            }],
+            admins: vec![ManagerInfo {
+                id: 2,
+                display_name: "沙盒管理".to_string(),
+                is_active: true,
+                avatar_url: Some("https://sandbox.example.com/static/avatars/2.webp".to_string()),
+            }],
+        })
+    }
+
+    /// 获取服务器相册
+    pub fn get_server_gallery(server_id: i32) -> ApiResult<ServerGallery> {
+        let server = Self::get_server_detail(server_id)?;
+
+        let gallery_images = (1..=3)
+            .map(|i| {
+                let image_url =
+                    format!("https://sandbox.example.com/static/gallery/{server_id}-{i}.webp");
+                GalleryImage {
+                    id: server_id * 10 + i,
+                    title: format!("沙盒图片 {i}"),
+                    description: format!("{} 的第 {i} 张示例图片", server.name),
+                    image_urls: Self::image_urls(&image_url),
+                    image_url,
+                    sort_order: i - 1,
+                }
+            })
+            .collect();
+
+        Ok(ServerGallery {
+            id: server.id,
+            name: server.name,
+            gallery_images,
+        })
+    }
+
+    /// 获取全部服务器在线人数
+    pub fn total_players() -> ServerTotalPlayers {
+        let total_players = FIXTURES.iter().map(|row| row.8 as i32).sum();
+        ServerTotalPlayers { total_players }
+    }
+
+    /// 在固定数据中进行简单的关键词搜索
+    pub fn search_servers(params: &SearchParams) -> SearchResponse {
+        let query = params
+            .query
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let hits: Vec<ServerResult> = Self::fixtures()
+            .into_iter()
+            .filter(|s| {
+                query.is_empty()
+                    || s.name.to_lowercase().contains(&query)
+                    || s.desc.to_lowercase().contains(&query)
+            })
+            .map(|s| ServerResult {
+                id: s.id,
+                name: s.name,
+                ip: s.ip,
+                r#type: s.r#type,
+                version: s.version,
+                version_range: s.version_range,
+                desc: s.desc,
+                link: s.link,
+                is_member: s.is_member,
+                auth_mode: s.auth_mode,
+                is_hide: s.is_hide,
+                tags: s.tags,
+                slug: s.slug,
+                online_players: s
+                    .stats
+                    .as_ref()
+                    .and_then(|st| st.players.get("online").copied()),
+                max_players: s
+                    .stats
+                    .as_ref()
+                    .and_then(|st| st.players.get("max").copied()),
+                cover_url: s.cover_url,
+            })
+            .collect();
+
+        let total = hits.len() as u64;
+        let (page, page_size) = params.pagination();
+        let hits = hits
+            .into_iter()
+            .skip(((page - 1) * page_size) as usize)
+            .take(page_size as usize)
+            .collect();
+
+        SearchResponse {
+            page: Paginated::new(hits, page, page_size, total),
+            processing_time_ms: 0,
+        }
+    }
+
+    fn build_detail(row: &FixtureRow) -> ServerDetail {
+        let (id, name, server_type, auth_mode, version, is_member, is_hide, tags, online, max) =
+            row.clone();
+
+        let mut players = HashMap::new();
+        players.insert("online".to_string(), online);
+        players.insert("max".to_string(), max);
+        let cover_url = format!("https://sandbox.example.com/static/covers/{id}.webp");
+
+        ServerDetail {
+            id,
+            name: name.to_string(),
+            ip: if is_hide {
+                None
+            } else {
+                Some(format!("sandbox-{id}.example.com:25565"))
+            },
+            ip_family: None,
+            r#type: server_type,
+            version: version.to_string(),
+            version_range: None,
+            desc: format!("{name}是沙盒环境中的示例服务器，数据固定不变，仅用于接口联调测试。"),
+            desc_html: None,
+            link: format!("https://sandbox.example.com/servers/{id}"),
+            is_member,
+            auth_mode,
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            is_hide,
+            visibility: if is_hide {
+                ServerVisibility::ListedWithoutIp
+            } else {
+                ServerVisibility::Public
+            },
+            stats: Some(ServerStats {
+                players,
+                delay: 20.0 + id as f64 * 7.5,
+                version: format!("Paper {version}"),
+                motd: Motd {
+                    plain: format!("欢迎来到{name}"),
+                    html: format!("<span style='color: green;'>欢迎来到{name}</span>"),
+                    minecraft: format!("§a欢迎来到{name}"),
+                    ansi: format!("\u{1b}[32m欢迎来到{name}\u{1b}[0m"),
+                },
+                icon: None,
+                sample: None,
+            }),
+            permission: "guest".to_string(),
+            cover_urls: Some(Self::image_urls(&cover_url)),
+            cover_url: Some(cover_url),
+            icon_url: None,
+            slug: Some(format!("sandbox-server-{id}")),
+            managers: None,
+            translations: Vec::new(),
+            custom_fields: Vec::new(),
+            private: None,
+            is_favorited: None,
+        }
+    }
+
+    fn image_urls(original: &str) -> ImageUrls {
+        ImageUrls {
+            thumb: ImageVariant::Thumb.path_of(original),
+            medium: ImageVariant::Medium.path_of(original),
+            original: original.to_string(),
+        }
+    }
+
+    fn type_str(server_type: &ApiServerType) -> &'static str {
+        match server_type {
+            ApiServerType::Java => "JAVA",
+            ApiServerType::Bedrock => "BEDROCK",
+        }
+    }
+
+    fn auth_mode_str(auth_mode: &ApiAuthMode) -> &'static str {
+        match auth_mode {
+            ApiAuthMode::Official => "OFFICIAL",
+            ApiAuthMode::Offline => "OFFLINE",
+            ApiAuthMode::Yggdrasil => "YGGDRASIL",
+        }
+    }
+}
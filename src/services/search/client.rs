@@ -1,19 +1,56 @@
-use crate::entities::server::Entity as Server;
-use crate::schemas::search::{SearchFilters, SearchParams, SearchResponse, ServerResult};
-use crate::schemas::servers::{ApiAuthMode, ApiServerType};
+use crate::entities::files::{self, Entity as Files};
+use crate::entities::server::{self, Entity as Server};
+use crate::schemas::pagination::Paginated;
+use crate::schemas::search::{
+    SearchFacets, SearchFacetsResponse, SearchFilters, SearchParams, SearchResponse,
+    SearchSuggestion, ServerResult,
+};
+use crate::schemas::servers::{
+    ApiAuthMode, ApiServerType, GameVersion, ServerStatus, ServerVisibility,
+};
+use crate::services::custom_fields::CustomFieldService;
+use crate::services::search::tasks::{IndexOperation, SearchTaskMonitor};
+use crate::services::server::ServerService;
+use crate::services::tenant::TenantService;
 use anyhow::Result;
-use axum::extract::Query as AxumQuery;
+use chrono::{DateTime, Utc};
 use meilisearch_sdk::client::*;
-use sea_orm::{DatabaseConnection, EntityTrait};
+use meilisearch_sdk::documents::DocumentsQuery;
+use meilisearch_sdk::indexes::Index;
+use meilisearch_sdk::search::Selectors;
+use meilisearch_sdk::settings::{FacetingSettings, MinWordSizeForTypos, TypoToleranceSettings};
+use meilisearch_sdk::task_info::TaskInfo;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::OnceCell;
-use tokio::time::{sleep, Duration};
+
+/// 读取索引文档 ID 时每页的数量
+const DOCUMENT_ID_PAGE_SIZE: usize = 1000;
+/// 查询封面与最新状态时每批的服务器数
+const EXTRAS_BATCH_SIZE: usize = 1000;
+/// 返回分面统计的字段
+const FACET_ATTRIBUTES: [&str; 4] = ["tags", "type", "auth_mode", "version"];
+/// 每个分面字段最多返回的取值数，标签数量可能超过 Meilisearch 默认的 100
+const MAX_VALUES_PER_FACET: usize = 500;
+/// 搜索建议匹配的字段
+const SUGGEST_SEARCH_ON: [&str; 2] = ["name", "slug"];
+/// 搜索建议返回的字段
+const SUGGEST_RETRIEVE: [&str; 4] = ["id", "name", "slug", "cover_url"];
+
+/// 服务器表以外写入搜索文档的数据：封面文件路径（按文件哈希）与最新状态中的玩家数
+#[derive(Default)]
+struct DocumentExtras {
+    covers: HashMap<String, String>,
+    players: HashMap<i32, (Option<i64>, Option<i64>)>,
+}
 
 /// Meilisearch 客户端
 /// 用于与 Meilisearch 进行交互
 #[derive(Debug)]
 pub struct MeilisearchClient {
-    client: Arc<Client>,
+    pub(super) client: Arc<Client>,
 }
 
 static MEILISEARCH_INSTANCE: OnceCell<Arc<MeilisearchClient>> = OnceCell::const_new();
@@ -88,11 +125,29 @@ impl SearchFilters {
             }
         }
 
+        // 兼容版本过滤：版本区间包含该版本，或版本列表（未设置范围时为 `version`）中有该版本
+        if let Some(version) = self.compatible_with.as_deref().and_then(GameVersion::parse) {
+            let code = version.code(false);
+            filters.push(format!(
+                "(compatible_versions = '{version}' OR (version_min_code <= {code} AND version_max_code >= {code}))"
+            ));
+        }
+
         filters.join(" AND ")
     }
 }
 
 impl SearchParams {
+    /// 页码与每页数量，兼容旧的 `limit` / `offset` 参数
+    pub fn pagination(&self) -> (u64, u64) {
+        let page_size = self.page_size.or(self.limit).unwrap_or(10).clamp(1, 100);
+        let page = self
+            .page
+            .unwrap_or_else(|| self.offset.unwrap_or(0) / page_size + 1)
+            .max(1);
+        (u64::from(page), u64::from(page_size))
+    }
+
     /// 解析搜索参数，构建结构化过滤器
     pub fn parse_filters(&self) -> Result<SearchFilters> {
         let mut filters = SearchFilters::default();
@@ -130,6 +185,12 @@ impl SearchParams {
             filters.is_member = Some(is_member);
         }
 
+        if let Some(value) = &self.compatible_with {
+            let version = GameVersion::parse(value)
+                .ok_or_else(|| anyhow::anyhow!("无效的游戏版本号: {value:?}"))?;
+            filters.compatible_with = Some(version.to_string());
+        }
+
         Ok(filters)
     }
 }
@@ -161,72 +222,326 @@ impl MeilisearchClient {
             .ok_or_else(|| anyhow::anyhow!("Meilisearch 客户端未初始化"))
     }
 
-    /// 同步服务器数据到搜索索引
+    /// 检查 Meilisearch 服务是否可用
+    pub async fn health_check(&self) -> Result<()> {
+        self.client
+            .health()
+            .await
+            .map_err(|e| anyhow::anyhow!("Meilisearch 健康检查失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 同步服务器数据到搜索索引，并跟踪索引任务的执行结果
     pub async fn sync_server_search(&self, db: &DatabaseConnection) -> Result<()> {
+        for task in self.sync_documents(db).await? {
+            SearchTaskMonitor::track(task, IndexOperation::SyncServers, 0);
+        }
+        Ok(())
+    }
+
+    /// 全量同步：按租户提交全部服务器文档，并删除索引中不应存在的文档，返回 Meilisearch 任务信息
+    ///
+    /// 可见性为 `hidden`、因长期离线被下架或已停用的服务器不写入索引；索引中已有的这些文档，
+    /// 以及数据库中已删除的服务器的文档会被删除。
+    pub async fn sync_documents(&self, db: &DatabaseConnection) -> Result<Vec<TaskInfo>> {
         let servers = Server::find()
             .all(db)
             .await
             .map_err(|e| anyhow::anyhow!("查询服务器数据失败: {}", e))?;
+        let custom_fields = CustomFieldService::searchable_values(db)
+            .await
+            .map_err(|e| anyhow::anyhow!("查询服务器自定义字段失败: {}", e))?;
+
+        let mut tasks = Vec::new();
+        for tenant in TenantService::all() {
+            let listed: Vec<_> = servers
+                .iter()
+                .filter(|server| server.tenant_id == tenant.id && Self::is_searchable(server))
+                .collect();
+            let index = self.client.index(&tenant.search_index);
+
+            let listed_ids: HashSet<i32> = listed.iter().map(|server| server.id).collect();
+            let stale: Vec<i32> = Self::document_ids(&index)
+                .await?
+                .into_iter()
+                .filter(|id| !listed_ids.contains(id))
+                .collect();
+            if !stale.is_empty() {
+                let task = index
+                    .delete_documents(&stale)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("删除多余的搜索文档失败: {}", e))?;
+                tracing::info!(
+                    "已从 Meilisearch 索引 {} 删除 {} 条多余的服务器记录",
+                    tenant.search_index,
+                    stale.len()
+                );
+                tasks.push(task);
+            }
+
+            if listed.is_empty() {
+                continue;
+            }
+            let extras = Self::document_extras(db, &listed).await?;
+            let documents: Vec<_> = listed
+                .into_iter()
+                .map(|server| Self::server_document(server, custom_fields.get(&server.id), &extras))
+                .collect();
+
+            let task = index
+                .add_documents(&documents, Some("id"))
+                .await
+                .map_err(|e| anyhow::anyhow!("同步搜索索引失败: {}", e))?;
+
+            tracing::info!(
+                "已提交 {} 条服务器记录到 Meilisearch 索引 {}, task_uid={}",
+                documents.len(),
+                tenant.search_index,
+                task.task_uid
+            );
+            tasks.push(task);
+        }
+        Ok(tasks)
+    }
+
+    /// 同步指定服务器的文档：可搜索的写入索引，其余（隐藏、下架、停用或已删除）从索引中删除
+    pub async fn sync_servers(
+        &self,
+        db: &DatabaseConnection,
+        server_ids: &[i32],
+    ) -> Result<Vec<TaskInfo>> {
+        if server_ids.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let documents: Vec<_> = servers
+        let servers = Server::find()
+            .filter(server::Column::Id.is_in(server_ids.iter().copied()))
+            .all(db)
+            .await
+            .map_err(|e| anyhow::anyhow!("查询服务器数据失败: {}", e))?;
+        let custom_fields = CustomFieldService::searchable_values_of(db, server_ids)
+            .await
+            .map_err(|e| anyhow::anyhow!("查询服务器自定义字段失败: {}", e))?;
+        let found: HashSet<i32> = servers.iter().map(|server| server.id).collect();
+        // 已删除的服务器无法得知所属租户，从所有索引中删除
+        let deleted: Vec<i32> = server_ids
             .iter()
-            .map(|server| {
-                serde_json::json!({
-                    "id": server.id,
-                    "name": server.name,
-                    "type": server.r#type,
-                    "version": server.version,
-                    "desc": server.desc,
-                    "link": server.link,
-                    "ip": server.ip,
-                    "is_member": server.is_member,
-                    "is_hide": server.is_hide,
-                    "auth_mode": server.auth_mode,
-                    "tags": server.tags,
-                })
-            })
+            .copied()
+            .filter(|id| !found.contains(id))
             .collect();
 
-        self.client
-            .index("servers")
-            .add_documents(&documents, Some("id"))
+        let mut tasks = Vec::new();
+        for tenant in TenantService::all() {
+            let (listed, unlisted): (Vec<_>, Vec<_>) = servers
+                .iter()
+                .filter(|server| server.tenant_id == tenant.id)
+                .partition(|server| Self::is_searchable(server));
+            let index = self.client.index(&tenant.search_index);
+
+            let removed: Vec<i32> = unlisted
+                .iter()
+                .map(|server| server.id)
+                .chain(deleted.iter().copied())
+                .collect();
+            if !removed.is_empty() {
+                let task = index
+                    .delete_documents(&removed)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("删除搜索文档失败: {}", e))?;
+                tasks.push(task);
+            }
+
+            if listed.is_empty() {
+                continue;
+            }
+            let extras = Self::document_extras(db, &listed).await?;
+            let documents: Vec<_> = listed
+                .into_iter()
+                .map(|server| Self::server_document(server, custom_fields.get(&server.id), &extras))
+                .collect();
+            let task = index
+                .add_documents(&documents, Some("id"))
+                .await
+                .map_err(|e| anyhow::anyhow!("同步搜索索引失败: {}", e))?;
+            tasks.push(task);
+        }
+
+        tracing::debug!("已增量同步 {} 个服务器的搜索文档", server_ids.len());
+        Ok(tasks)
+    }
+
+    /// 修改时间不早于 `since` 的服务器 ID
+    pub async fn changed_since(db: &DatabaseConnection, since: DateTime<Utc>) -> Result<Vec<i32>> {
+        Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::UpdatedAt.gte(since))
+            .into_tuple()
+            .all(db)
             .await
-            .map_err(|e| anyhow::anyhow!("同步搜索索引失败: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("查询修改过的服务器失败: {}", e))
+    }
 
-        tracing::info!("已同步 {} 条服务器记录到 Meilisearch 索引", documents.len());
-        Ok(())
+    /// 服务器是否应出现在搜索结果中
+    fn is_searchable(server: &server::Model) -> bool {
+        server.deactivated_at.is_none()
+            && server.delisted_at.is_none()
+            && ServerVisibility::of(server).is_listed()
+            && ServerStatus::of(server).is_listed()
     }
 
-    /// 定期同步搜索索引
-    pub async fn sync_meilisearch_loop(
-        &self,
-        db: &DatabaseConnection,
-        interval_secs: u64,
-    ) -> Result<()> {
-        tracing::info!("开始定期同步搜索索引，间隔: {} 秒", interval_secs);
+    /// 索引中已有的全部文档 ID
+    async fn document_ids(index: &Index) -> Result<HashSet<i32>> {
+        #[derive(Deserialize)]
+        struct DocumentId {
+            id: i32,
+        }
+
+        let mut ids = HashSet::new();
+        let mut offset = 0;
         loop {
-            match self.sync_server_search(db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    tracing::error!("同步搜索索引失败: {}", e);
-                }
+            let page = DocumentsQuery::new(index)
+                .with_fields(["id"])
+                .with_offset(offset)
+                .with_limit(DOCUMENT_ID_PAGE_SIZE)
+                .execute::<DocumentId>()
+                .await
+                .map_err(|e| anyhow::anyhow!("读取搜索文档 ID 失败: {}", e))?;
+            let count = page.results.len();
+            ids.extend(page.results.into_iter().map(|document| document.id));
+            if count < DOCUMENT_ID_PAGE_SIZE {
+                break;
+            }
+            offset += count;
+        }
+        Ok(ids)
+    }
+
+    /// 查询服务器的封面文件与最新状态，分批查询避免条件过长
+    async fn document_extras(
+        db: &DatabaseConnection,
+        servers: &[&server::Model],
+    ) -> Result<DocumentExtras> {
+        let mut extras = DocumentExtras::default();
+        for batch in servers.chunks(EXTRAS_BATCH_SIZE) {
+            let server_ids: Vec<i32> = batch.iter().map(|server| server.id).collect();
+            let stats = ServerService::latest_stats(db, &server_ids)
+                .await
+                .map_err(|e| anyhow::anyhow!("查询服务器状态失败: {}", e))?;
+            // 同一时间戳有多条时取 ID 最大的一条，latest_stats 已按 ID 倒序
+            for stats in stats {
+                let players = stats
+                    .stat_data
+                    .as_ref()
+                    .and_then(|data| data.get("players"));
+                let count = |key: &str| players.and_then(|p| p.get(key)).and_then(|v| v.as_i64());
+                extras
+                    .players
+                    .entry(stats.server_id)
+                    .or_insert((count("online"), count("max")));
+            }
+
+            let cover_hashes: Vec<String> = batch
+                .iter()
+                .filter_map(|server| server.cover_hash_id.clone())
+                .collect();
+            if cover_hashes.is_empty() {
+                continue;
             }
-            sleep(Duration::from_secs(interval_secs)).await;
+            let cover_files = Files::find()
+                .filter(files::Column::HashValue.is_in(cover_hashes))
+                .all(db)
+                .await
+                .map_err(|e| anyhow::anyhow!("查询服务器封面失败: {}", e))?;
+            extras.covers.extend(
+                cover_files
+                    .into_iter()
+                    .map(|file| (file.hash_value, file.file_path)),
+            );
         }
+        Ok(extras)
+    }
+
+    fn server_document(
+        server: &server::Model,
+        custom_fields: Option<&Vec<String>>,
+        extras: &DocumentExtras,
+    ) -> serde_json::Value {
+        let visibility = ServerVisibility::of(server);
+        let (online_players, max_players) =
+            extras.players.get(&server.id).copied().unwrap_or_default();
+        let version_range = ServerService::version_range_of(server);
+        let compatible_versions = match &version_range {
+            Some(range) => range.versions.clone(),
+            None => vec![server.version.clone()],
+        };
+        serde_json::json!({
+            "id": server.id,
+            "name": server.name,
+            "type": server.r#type,
+            "version": server.version,
+            "version_range": version_range,
+            "compatible_versions": compatible_versions,
+            "version_min_code": server.version_min_code,
+            "version_max_code": server.version_max_code,
+            "desc": server.desc,
+            "link": server.link,
+            "ip": visibility.public_ip(server.ip.clone()),
+            "is_member": server.is_member,
+            "is_hide": visibility != ServerVisibility::Public,
+            "visibility": visibility.as_str(),
+            "auth_mode": server.auth_mode,
+            "tags": server.tags,
+            "slug": server.slug,
+            "custom_fields": custom_fields.cloned().unwrap_or_default(),
+            "cover_url": server
+                .cover_hash_id
+                .as_ref()
+                .and_then(|hash| extras.covers.get(hash)),
+            "online_players": online_players,
+            "max_players": max_players,
+        })
     }
 
     /// 初始化 Meilisearch 索引并设置相关配置
     pub async fn init_meilisearch_index(&self) -> Result<()> {
-        let index = self.client.index("servers");
+        for task in self.apply_index_settings().await? {
+            SearchTaskMonitor::track(task, IndexOperation::UpdateSettings, 0);
+        }
+
+        tracing::info!("Meilisearch 索引配置完成");
+        Ok(())
+    }
+
+    /// 为每个租户的索引提交配置，返回各配置项的 Meilisearch 任务信息
+    pub async fn apply_index_settings(&self) -> Result<Vec<TaskInfo>> {
+        let mut tasks = Vec::new();
+        for tenant in TenantService::all() {
+            tasks.extend(self.apply_settings_to(&tenant.search_index).await?);
+        }
+        Ok(tasks)
+    }
+
+    async fn apply_settings_to(&self, index_uid: &str) -> Result<Vec<TaskInfo>> {
+        let index = self.client.index(index_uid);
 
         // 可搜索字段
-        index
-            .set_searchable_attributes(["name", "desc", "ip", "tags", "type", "version"])
+        let searchable = index
+            .set_searchable_attributes([
+                "name",
+                "slug",
+                "desc",
+                "ip",
+                "tags",
+                "type",
+                "version",
+                "custom_fields",
+            ])
             .await
             .map_err(|e| anyhow::anyhow!("设置可搜索字段失败: {}", e))?;
 
         // 可过滤字段
-        index
+        let filterable = index
             .set_filterable_attributes([
                 "type",
                 "tags",
@@ -234,27 +549,57 @@ impl MeilisearchClient {
                 "is_member",
                 "is_hide",
                 "version",
+                "compatible_versions",
+                "version_min_code",
+                "version_max_code",
             ])
             .await
             .map_err(|e| anyhow::anyhow!("设置可过滤字段失败: {}", e))?;
 
         // 设置排序字段
-        index
+        let sortable = index
             .set_sortable_attributes(["id", "name", "is_member"])
             .await
             .map_err(|e| anyhow::anyhow!("设置排序字段失败: {}", e))?;
 
-        tracing::info!("Meilisearch 索引配置完成");
-        Ok(())
+        // 分面统计
+        let faceting = index
+            .set_faceting(&FacetingSettings {
+                max_values_per_facet: MAX_VALUES_PER_FACET,
+                sort_facet_values_by: None,
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("设置分面统计失败: {}", e))?;
+
+        // 拼写容错：4 个字符起容许 1 处错误，8 个字符起容许 2 处，输入前几个字符时不做模糊匹配；
+        // 短链接与 IP 只精确匹配
+        let typo_tolerance = index
+            .set_typo_tolerance(&TypoToleranceSettings {
+                enabled: Some(true),
+                disable_on_attributes: Some(vec!["slug".to_string(), "ip".to_string()]),
+                disable_on_words: None,
+                min_word_size_for_typos: Some(MinWordSizeForTypos {
+                    one_typo: Some(4),
+                    two_typos: Some(8),
+                }),
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("设置拼写容错失败: {}", e))?;
+
+        Ok(vec![
+            searchable,
+            filterable,
+            sortable,
+            faceting,
+            typo_tolerance,
+        ])
     }
 
-    /// 搜索服务器
-    pub async fn search_servers(
-        AxumQuery(params): AxumQuery<SearchParams>,
-    ) -> Result<SearchResponse> {
+    /// 在指定索引中搜索服务器
+    pub async fn search_servers(params: SearchParams, index_uid: &str) -> Result<SearchResponse> {
         let start_time = std::time::Instant::now();
         let client = Self::instance()?;
-        let index = client.client.index("servers");
+        let index = client.client.index(index_uid);
 
         // 解析过滤器
         let filters = params.parse_filters()?;
@@ -271,9 +616,10 @@ impl MeilisearchClient {
         }
 
         // 设置分页
-        let limit = params.limit.unwrap_or(10).min(100) as usize; // 限制最大返回数量
-        let offset = params.offset.unwrap_or(0) as usize;
-        search_request.with_limit(limit).with_offset(offset);
+        let (page, page_size) = params.pagination();
+        search_request
+            .with_limit(page_size as usize)
+            .with_offset(((page - 1) * page_size) as usize);
 
         // 设置过滤器
         if !filter_string.is_empty() {
@@ -300,38 +646,151 @@ impl MeilisearchClient {
         let processing_time = start_time.elapsed().as_millis();
 
         Ok(SearchResponse {
-            hits: results.hits.into_iter().map(|h| h.result).collect(),
-            total: results.estimated_total_hits.unwrap_or(0),
-            limit,
-            offset,
+            page: Paginated::new(
+                results.hits.into_iter().map(|h| h.result).collect(),
+                page,
+                page_size,
+                results.estimated_total_hits.unwrap_or(0) as u64,
+            ),
             processing_time_ms: processing_time,
         })
     }
 
-    /// 获取搜索统计信息
-    pub async fn get_search_stats(&self) -> Result<String> {
-        let index = self.client.index("servers");
-        let stats = index
-            .get_stats()
+    /// 统计符合关键词与过滤条件的服务器在标签、类型、认证模式与版本上的分布
+    pub async fn search_facets(
+        params: SearchParams,
+        index_uid: &str,
+    ) -> Result<SearchFacetsResponse> {
+        let start_time = std::time::Instant::now();
+        let client = Self::instance()?;
+        let index = client.client.index(index_uid);
+
+        let filter_string = params.parse_filters()?.to_filter_string();
+
+        // 只需要分面统计，不返回文档
+        let mut search_request = index.search();
+        if let Some(query) = &params.query {
+            if !query.trim().is_empty() {
+                search_request.with_query(query);
+            }
+        }
+        if !filter_string.is_empty() {
+            search_request.with_filter(&filter_string);
+        }
+        search_request
+            .with_limit(0)
+            .with_facets(Selectors::Some(&FACET_ATTRIBUTES));
+
+        let results = search_request
+            .execute::<ServerResult>()
             .await
-            .map_err(|e| anyhow::anyhow!("获取索引统计失败: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("分面统计失败: {}", e))?;
+
+        let mut distribution = results.facet_distribution.unwrap_or_default();
+        let mut take = |attribute: &str| {
+            distribution
+                .remove(attribute)
+                .unwrap_or_default()
+                .into_iter()
+                .collect()
+        };
+        let facets = SearchFacets {
+            tags: take("tags"),
+            server_type: take("type"),
+            auth_mode: take("auth_mode"),
+            version: take("version"),
+        };
 
-        let stats_json = serde_json::json!({
-            "number_of_documents": stats.number_of_documents,
-            "is_indexing": stats.is_indexing,
-            "field_distribution": stats.field_distribution
-        });
+        Ok(SearchFacetsResponse {
+            total: results.estimated_total_hits.unwrap_or(0),
+            facets,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// 按名称与短链接给出搜索建议，Meilisearch 对最后一个词按前缀匹配
+    pub async fn suggest(
+        query: &str,
+        limit: usize,
+        index_uid: &str,
+    ) -> Result<Vec<SearchSuggestion>> {
+        #[derive(Deserialize)]
+        struct SuggestionDocument {
+            id: i32,
+            name: String,
+            #[serde(default)]
+            slug: Option<String>,
+            #[serde(default)]
+            cover_url: Option<String>,
+        }
 
-        Ok(stats_json.to_string())
+        let client = Self::instance()?;
+        let index = client.client.index(index_uid);
+        let results = index
+            .search()
+            .with_query(query)
+            .with_limit(limit)
+            .with_attributes_to_search_on(&SUGGEST_SEARCH_ON)
+            .with_attributes_to_retrieve(Selectors::Some(&SUGGEST_RETRIEVE))
+            .execute::<SuggestionDocument>()
+            .await
+            .map_err(|e| anyhow::anyhow!("搜索建议失败: {}", e))?;
+
+        Ok(results
+            .hits
+            .into_iter()
+            .map(|hit| SearchSuggestion {
+                id: hit.result.id,
+                name: hit.result.name,
+                slug: hit.result.slug,
+                icon: hit.result.cover_url,
+            })
+            .collect())
     }
 
-    /// 清空索引
-    pub async fn clear_index(&self) -> Result<()> {
-        let index = self.client.index("servers");
-        index
-            .delete_all_documents()
+    /// 获取搜索统计信息，按索引分组
+    pub async fn get_search_stats(&self) -> Result<String> {
+        let mut stats_json = serde_json::Map::new();
+        for tenant in TenantService::all() {
+            let stats = self
+                .client
+                .index(&tenant.search_index)
+                .get_stats()
+                .await
+                .map_err(|e| anyhow::anyhow!("获取索引统计失败: {}", e))?;
+
+            stats_json.insert(
+                tenant.search_index,
+                serde_json::json!({
+                    "number_of_documents": stats.number_of_documents,
+                    "is_indexing": stats.is_indexing,
+                    "field_distribution": stats.field_distribution
+                }),
+            );
+        }
+
+        Ok(serde_json::Value::Object(stats_json).to_string())
+    }
+
+    /// 从租户索引中删除服务器文档，不等待索引任务完成
+    pub async fn delete_server_document(&self, tenant_id: &str, server_id: i32) -> Result<()> {
+        self.client
+            .index(TenantService::search_index(tenant_id))
+            .delete_document(server_id)
             .await
-            .map_err(|e| anyhow::anyhow!("清空索引失败: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("删除搜索文档失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 清空所有租户的索引
+    pub async fn clear_index(&self) -> Result<()> {
+        for tenant in TenantService::all() {
+            self.client
+                .index(&tenant.search_index)
+                .delete_all_documents()
+                .await
+                .map_err(|e| anyhow::anyhow!("清空索引失败: {}", e))?;
+        }
         tracing::info!("已清空搜索索引");
         Ok(())
     }
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::services::{
+    database::DatabaseConnection,
+    events::{DomainEvent, EventBus},
+    search::{
+        client::MeilisearchClient,
+        tasks::{IndexOperation, SearchTaskMonitor},
+    },
+};
+
+/// 收到修改事件后等待的时间，合并短时间内的连续修改
+const DEBOUNCE: Duration = Duration::from_secs(1);
+/// 增量同步查询修改时间时向前多查的秒数，容忍各实例与数据库之间的时钟偏差
+const WATERMARK_OVERLAP_SECS: i64 = 30;
+/// 单次增量同步提交的服务器数上限
+const BATCH_SIZE: usize = 500;
+
+/// 搜索索引同步任务
+///
+/// - 本实例发布的服务器修改事件：去抖后立即同步涉及的服务器，新增、编辑、隐藏与删除无需等待下一轮
+/// - 本实例发布的状态刷新事件：涉及的服务器在下一轮增量同步时更新文档中的玩家数，
+///   状态刷新频繁，不单独去抖提交
+/// - 每隔 `interval`：同步修改时间晚于上一轮的服务器，覆盖其他实例或批量更新产生的修改
+/// - 每隔 `full_interval`：全量同步并删除索引中多余的文档，启动时先执行一次
+///
+/// 增量同步的任务失败时由 [`SearchTaskMonitor`] 按全量同步重试。
+pub struct SearchSyncService;
+
+impl SearchSyncService {
+    /// 全量同步读取全部服务器，使用 `replica`；增量同步读取刚修改的数据，使用主库 `db`
+    pub async fn run_loop(
+        client: Arc<MeilisearchClient>,
+        db: DatabaseConnection,
+        replica: DatabaseConnection,
+        interval: Duration,
+        full_interval: Duration,
+    ) {
+        tracing::info!(
+            "开始同步搜索索引，增量间隔 {} 秒，全量间隔 {} 秒",
+            interval.as_secs(),
+            full_interval.as_secs()
+        );
+        let mut events = EventBus::subscribe();
+        let mut incremental = tokio::time::interval(interval);
+        incremental.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut full = tokio::time::interval(full_interval);
+        full.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut watermark = Utc::now();
+        let mut pending: BTreeSet<i32> = BTreeSet::new();
+        let mut stats_pending: BTreeSet<i32> = BTreeSet::new();
+        let debounce = tokio::time::sleep(DEBOUNCE);
+        tokio::pin!(debounce);
+        let mut debounce_armed = false;
+
+        loop {
+            tokio::select! {
+                _ = full.tick() => {
+                    let started = Utc::now();
+                    match client.sync_documents(&replica).await {
+                        Ok(tasks) => {
+                            for task in tasks {
+                                SearchTaskMonitor::track(task, IndexOperation::SyncServers, 0);
+                            }
+                            watermark = started;
+                        }
+                        Err(e) => tracing::error!("全量同步搜索索引失败: {}", e),
+                    }
+                }
+                _ = incremental.tick() => {
+                    let started = Utc::now();
+                    let since = watermark - chrono::Duration::seconds(WATERMARK_OVERLAP_SECS);
+                    match MeilisearchClient::changed_since(&db, since).await {
+                        Ok(changed) => {
+                            pending.extend(changed);
+                            pending.append(&mut stats_pending);
+                            if Self::flush(&client, &db, &mut pending).await {
+                                watermark = started;
+                            }
+                        }
+                        Err(e) => tracing::warn!("⚠️  增量同步搜索索引失败: {}", e),
+                    }
+                }
+                event = events.recv() => match event {
+                    Ok(DomainEvent::ServerUpdated { server_ids, .. }) => {
+                        pending.extend(server_ids);
+                        if !debounce_armed {
+                            debounce.as_mut().reset(Instant::now() + DEBOUNCE);
+                            debounce_armed = true;
+                        }
+                    }
+                    Ok(DomainEvent::StatsRefreshed { server_ids, .. }) => {
+                        stats_pending.extend(server_ids);
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("⚠️  搜索同步落后，丢失 {} 个事件，提前执行全量同步", skipped);
+                        full.reset_immediately();
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = &mut debounce, if debounce_armed => {
+                    debounce_armed = false;
+                    Self::flush(&client, &db, &mut pending).await;
+                }
+            }
+        }
+    }
+
+    /// 分批同步待同步的服务器，失败的留到下一轮增量同步，全部成功时返回 `true`
+    async fn flush(
+        client: &MeilisearchClient,
+        db: &DatabaseConnection,
+        pending: &mut BTreeSet<i32>,
+    ) -> bool {
+        let server_ids: Vec<i32> = std::mem::take(pending).into_iter().collect();
+        let mut ok = true;
+        for batch in server_ids.chunks(BATCH_SIZE) {
+            match client.sync_servers(db, batch).await {
+                Ok(tasks) => {
+                    for task in tasks {
+                        SearchTaskMonitor::track(task, IndexOperation::SyncServers, 0);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️  同步 {} 个服务器的搜索文档失败: {}", batch.len(), e);
+                    pending.extend(batch);
+                    ok = false;
+                }
+            }
+        }
+        ok
+    }
+}
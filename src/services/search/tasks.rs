@@ -0,0 +1,275 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use meilisearch_sdk::task_info::TaskInfo;
+use once_cell::sync::Lazy;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::{
+    config::MeilisearchConfig,
+    entities::{prelude::Server, server},
+    services::{
+        database::DatabaseConnection, metrics::MetricsService, search::client::MeilisearchClient,
+        tenant::TenantService,
+    },
+};
+
+/// 最多跟踪的任务数，超出时丢弃最早的任务
+const MAX_TRACKED_TASKS: usize = 1000;
+
+/// 产生索引任务的操作，失败时据此重新提交
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexOperation {
+    /// 同步服务器文档
+    SyncServers,
+    /// 更新索引配置
+    UpdateSettings,
+}
+
+impl IndexOperation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IndexOperation::SyncServers => "sync_servers",
+            IndexOperation::UpdateSettings => "update_settings",
+        }
+    }
+}
+
+struct TrackedTask {
+    task: TaskInfo,
+    operation: IndexOperation,
+    /// 第几次重试，首次提交为 0
+    attempt: u32,
+    enqueued_at: Instant,
+    /// 是否已发出排队过久告警，避免重复告警
+    stale_alerted: bool,
+}
+
+static TRACKED_TASKS: Lazy<Mutex<Vec<TrackedTask>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Meilisearch 索引任务监控
+///
+/// Meilisearch 的写操作只返回任务 ID，真正的执行结果需要轮询获取。
+/// 这里记录提交的任务，定期查询状态：失败的任务按操作重新提交，
+/// 超过重试次数、排队过久或索引文档数与数据库差异过大时记录告警与指标。
+pub struct SearchTaskMonitor;
+
+impl SearchTaskMonitor {
+    /// 记录一个已提交的任务
+    pub fn track(task: TaskInfo, operation: IndexOperation, attempt: u32) {
+        let mut tasks = TRACKED_TASKS.lock().unwrap_or_else(|e| e.into_inner());
+        if tasks.len() >= MAX_TRACKED_TASKS {
+            let dropped = tasks.remove(0);
+            tracing::warn!(
+                "⚠️  跟踪的 Meilisearch 任务过多，丢弃 task_uid={}",
+                dropped.task.task_uid
+            );
+        }
+        tasks.push(TrackedTask {
+            task,
+            operation,
+            attempt,
+            enqueued_at: Instant::now(),
+            stale_alerted: false,
+        });
+    }
+
+    /// 定期轮询任务状态并检查索引滞后
+    pub async fn run_monitor_loop(
+        client: Arc<MeilisearchClient>,
+        db: DatabaseConnection,
+        config: MeilisearchConfig,
+        interval: Duration,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            Self::poll_tasks(&client, &db, &config).await;
+            if let Err(e) = Self::check_index_lag(&client, &db, &config).await {
+                tracing::warn!("⚠️  检查搜索索引滞后失败: {}", e);
+            }
+        }
+    }
+
+    /// 查询所有跟踪中的任务，完成的移除，失败的重试
+    pub async fn poll_tasks(
+        client: &MeilisearchClient,
+        db: &DatabaseConnection,
+        config: &MeilisearchConfig,
+    ) {
+        // 不在持锁期间发起请求
+        let tasks = std::mem::take(&mut *TRACKED_TASKS.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut pending = Vec::new();
+
+        for mut tracked in tasks {
+            let task = match client.client.get_task(&tracked.task).await {
+                Ok(task) => task,
+                Err(e) => {
+                    tracing::warn!(
+                        "⚠️  查询 Meilisearch 任务 {} 失败: {}",
+                        tracked.task.task_uid,
+                        e
+                    );
+                    pending.push(tracked);
+                    continue;
+                }
+            };
+
+            if task.is_success() {
+                MetricsService::observe(
+                    "meilisearch_task_duration_seconds",
+                    "Meilisearch 索引任务从提交到完成的耗时",
+                    &[("operation", tracked.operation.as_str())],
+                    tracked.enqueued_at.elapsed().as_secs_f64(),
+                );
+                continue;
+            }
+
+            if task.is_failure() {
+                let error = task.unwrap_failure();
+                MetricsService::inc_counter(
+                    "meilisearch_task_failures_total",
+                    "失败的 Meilisearch 索引任务数",
+                    &[("operation", tracked.operation.as_str())],
+                    1.0,
+                );
+                tracing::warn!(
+                    "⚠️  Meilisearch 任务失败: task_uid={}, operation={}, attempt={}, error={}",
+                    tracked.task.task_uid,
+                    tracked.operation.as_str(),
+                    tracked.attempt,
+                    error.error_message
+                );
+                Self::retry(client, db, config, &tracked).await;
+                continue;
+            }
+
+            let waited = tracked.enqueued_at.elapsed();
+            if !tracked.stale_alerted && waited.as_secs() >= config.task_stale_secs {
+                tracked.stale_alerted = true;
+                Self::alert(
+                    "task_stale",
+                    &format!(
+                        "Meilisearch 任务 {} 已排队 {} 秒仍未完成",
+                        tracked.task.task_uid,
+                        waited.as_secs()
+                    ),
+                );
+            }
+            pending.push(tracked);
+        }
+
+        let mut tasks = TRACKED_TASKS.lock().unwrap_or_else(|e| e.into_inner());
+        // 轮询期间新提交的任务保留在后面
+        pending.append(&mut tasks);
+        *tasks = pending;
+
+        let oldest = tasks
+            .iter()
+            .map(|t| t.enqueued_at.elapsed().as_secs_f64())
+            .fold(0.0, f64::max);
+        MetricsService::set_gauge(
+            "meilisearch_pending_tasks",
+            "未完成的 Meilisearch 索引任务数",
+            &[],
+            tasks.len() as f64,
+        );
+        MetricsService::set_gauge(
+            "meilisearch_oldest_pending_task_seconds",
+            "最早的未完成 Meilisearch 索引任务已等待的时长",
+            &[],
+            oldest,
+        );
+    }
+
+    /// 比较索引文档数与数据库中的服务器数
+    pub async fn check_index_lag(
+        client: &MeilisearchClient,
+        db: &DatabaseConnection,
+        config: &MeilisearchConfig,
+    ) -> Result<u64> {
+        let tenants = TenantService::all();
+        let mut indexed = 0u64;
+        let mut is_indexing = false;
+        for tenant in &tenants {
+            let stats = client
+                .client
+                .index(&tenant.search_index)
+                .get_stats()
+                .await
+                .map_err(|e| anyhow::anyhow!("获取索引统计失败: {}", e))?;
+            indexed += stats.number_of_documents as u64;
+            is_indexing |= stats.is_indexing;
+        }
+        let expected = Server::find()
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::TenantId.is_in(tenants.iter().map(|tenant| tenant.id.clone())))
+            .count(db.as_ref())
+            .await
+            .map_err(|e| anyhow::anyhow!("统计服务器数量失败: {}", e))?;
+
+        let lag = expected.abs_diff(indexed);
+        MetricsService::set_gauge(
+            "meilisearch_index_lag_documents",
+            "搜索索引文档数与数据库服务器数的差值",
+            &[],
+            lag as f64,
+        );
+        if lag > config.lag_alert_threshold && !is_indexing {
+            Self::alert(
+                "index_lag",
+                &format!(
+                    "搜索索引落后于数据库: 索引 {} 条, 数据库 {} 条",
+                    indexed, expected
+                ),
+            );
+        }
+
+        Ok(lag)
+    }
+
+    async fn retry(
+        client: &MeilisearchClient,
+        db: &DatabaseConnection,
+        config: &MeilisearchConfig,
+        tracked: &TrackedTask,
+    ) {
+        if tracked.attempt >= config.task_max_retries {
+            Self::alert(
+                "retries_exhausted",
+                &format!(
+                    "Meilisearch 任务 {} ({}) 重试 {} 次后仍失败",
+                    tracked.task.task_uid,
+                    tracked.operation.as_str(),
+                    tracked.attempt
+                ),
+            );
+            return;
+        }
+
+        let attempt = tracked.attempt + 1;
+        let result = match tracked.operation {
+            IndexOperation::SyncServers => client.sync_documents(db).await,
+            IndexOperation::UpdateSettings => client.apply_index_settings().await,
+        };
+        match result {
+            Ok(tasks) => {
+                for task in tasks {
+                    Self::track(task, tracked.operation, attempt);
+                }
+            }
+            Err(e) => tracing::warn!("⚠️  重新提交 Meilisearch 任务失败: {}", e),
+        }
+    }
+
+    fn alert(kind: &str, message: &str) {
+        MetricsService::inc_counter(
+            "meilisearch_alerts_total",
+            "搜索索引告警次数",
+            &[("kind", kind)],
+            1.0,
+        );
+        tracing::error!("🚨 {}", message);
+    }
+}
@@ -1,23 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::entities::{files, server, server_stats};
+use crate::entities::{files, server, server_stats, ticket};
 use crate::{
-    config::S3Config,
     entities::prelude::{
-        Files, Gallery, GalleryImage as GalleryImageEntity, Server,
-        ServerStats as ServerStatsEntity, UserServer, Users,
+        Files, Gallery, GalleryImage as GalleryImageEntity, Server, ServerRevision,
+        ServerStats as ServerStatsEntity, Ticket, UserFavorite, UserServer, Users,
     },
-    entities::{gallery, gallery_image, user_server},
+    entities::{gallery, gallery_image, server_revision, user_favorite, user_server, users},
     errors::ApiResult,
     handlers::servers::ListQuery,
+    schemas::internal::{StatsBatchItem, StatsBatchRejection, StatsBatchResponse},
     schemas::servers::{
-        ApiAuthMode, ApiServerType, GalleryImage, GalleryImageSchema, ManagerInfo, Motd,
-        ServerDetail, ServerGallery, ServerManagerRole, ServerManagersResponse, ServerStats,
-        UpdateServerRequest,
+        AddManagerRequest, ApiAuthMode, ApiServerType, CreateServerRequest, GalleryImage,
+        GalleryImageSchema, GameVersion, ImageUrls, IpFamily, ManagerInfo, ServerAddress,
+        ServerDetail, ServerGallery, ServerManagerRole, ServerManagersResponse, ServerPing,
+        ServerPrivateDetail, ServerRegistration, ServerStats, ServerStatus, ServerSummary,
+        ServerVisibility, UpdateGalleryImageRequest, UpdateServerRequest, VersionRange,
+    },
+    services::{
+        custom_fields::CustomFieldService,
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+        file_gc::FileGcService,
+        file_upload::{FileUploadService, ImageVariant},
+        markdown::MarkdownService,
+        motd,
+        player_index::PlayerIndexService,
+        revision::ServerRevisionService,
+        signing::SigningService,
+        storage::{self, StorageBackend},
+        tags::TagService,
+        tenant::TenantService,
+        timeline::{ServerTimelineService, StatsObservation},
+        translation::TranslationService,
+        uptime::{ServerUptimeService, UptimeObservation},
     },
-    services::{database::DatabaseConnection, file_upload::FileUploadService},
 };
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use chrono::Utc;
 use sea_orm::JsonValue;
@@ -26,20 +45,52 @@ use sea_orm::{ActiveModelTrait, Set};
 use serde_json::Value;
 use validator::Validate;
 
+/// 概要中一句话简介的最大字符数
+const SUMMARY_INTRO_CHARS: usize = 80;
+
 pub struct PaginatedServerResult {
     pub data: Vec<ServerDetail>,
     pub total: i64,
 }
 
+/// 服务器详情的视图
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerDetailView {
+    /// 公开信息，任何调用方可见
+    Public,
+    /// 公开信息加上管理信息，仅服务器成员可见
+    Private,
+}
+
 pub struct ServerService;
 
 impl ServerService {
+    /// 单次批量上报的最大条数
+    const MAX_STATS_BATCH_SIZE: usize = 2000;
+    /// 每条 INSERT 语句写入的最大行数
+    const STATS_INSERT_CHUNK: usize = 500;
+    /// 上报的采集时间允许超前当前时间的秒数，容忍采集器的时钟偏差
+    const STATS_CLOCK_SKEW_SECS: i64 = 300;
+    /// 新建工单的状态，即待处理
+    const TICKET_STATUS_PENDING: i16 = 0;
+
+    /// 分页获取服务器列表
+    ///
+    /// 筛选、计数与分页都在数据库中完成。排序为按种子打乱的伪随机顺序：排序键是服务器 ID
+    /// 的仿射变换 `(id * a + b) mod p`，`a`、`b` 由种子决定，同一种子翻页时顺序固定，
+    /// 不需要把全部服务器读入内存洗牌。
     pub async fn get_servers_with_filters(
         db: &DatabaseConnection,
+        tenant_id: &str,
         user_id: Option<i32>,
         list_query: &ListQuery,
     ) -> ApiResult<PaginatedServerResult> {
-        let mut query = Server::find();
+        let mut query = Server::find()
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::DelistedAt.is_null())
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()));
 
         if list_query.is_member {
             query = query.filter(server::Column::IsMember.eq(list_query.is_member));
@@ -53,56 +104,61 @@ impl ServerService {
             query = query.filter(server::Column::AuthMode.is_in(auth_modes));
         }
 
-        let mut servers = query
-            .order_by_asc(server::Column::Id)
-            .all(db.as_ref())
-            .await?;
-
-        if servers.is_empty() {
-            return Ok(PaginatedServerResult {
-                data: vec![],
-                total: 0,
-            });
-        }
-
         if let Some(required_tags) = &list_query.tags {
-            servers.retain(|server| Self::server_has_required_tags(&server.tags, required_tags));
+            query = query.filter(Self::any_tag_condition(
+                db.get_database_backend(),
+                required_tags,
+            ));
         }
 
-        let total = servers.len() as i64;
-
-        let mut rng = if let Some(seed_val) = list_query.seed {
-            StdRng::seed_from_u64(seed_val as u64)
-        } else {
-            StdRng::seed_from_u64(rand::random())
-        };
-        servers.shuffle(&mut rng);
-
-        let start = ((list_query.page - 1) * list_query.page_size) as usize;
-        let take = list_query.page_size as usize;
+        if let Some(value) = &list_query.compatible_with {
+            let version = GameVersion::parse(value).ok_or_else(|| {
+                crate::errors::ApiError::BadRequest(format!("无效的游戏版本号: {value:?}"))
+            })?;
+            query = query.filter(Self::compatible_condition(
+                db.get_database_backend(),
+                &version,
+            ));
+        }
 
-        if start >= servers.len() {
+        let total = query.clone().count(db.as_ref()).await? as i64;
+        let offset = (list_query.page - 1).saturating_mul(list_query.page_size);
+        if total == 0 || offset >= total as u64 {
             return Ok(PaginatedServerResult {
                 data: vec![],
                 total,
             });
         }
 
-        let page_servers: Vec<_> = servers.into_iter().skip(start).take(take).collect();
-        let server_ids: Vec<i32> = page_servers.iter().map(|s| s.id).collect();
+        let seed = list_query.seed.unwrap_or_else(rand::random);
+        let page_servers = query
+            .order_by(Self::shuffle_key(seed), Order::Asc)
+            .order_by_asc(server::Column::Id)
+            .offset(offset)
+            .limit(list_query.page_size)
+            .all(db.as_ref())
+            .await?;
+        let server_list = Self::list_details(db, user_id, page_servers).await?;
+
+        Ok(PaginatedServerResult {
+            data: server_list,
+            total,
+        })
+    }
 
+    /// 把一组服务器转换为列表条目，补上最新状态、封面与调用方的权限
+    pub async fn list_details(
+        db: &DatabaseConnection,
+        user_id: Option<i32>,
+        servers: Vec<server::Model>,
+    ) -> ApiResult<Vec<ServerDetail>> {
+        let server_ids: Vec<i32> = servers.iter().map(|s| s.id).collect();
         if server_ids.is_empty() {
-            return Ok(PaginatedServerResult {
-                data: vec![],
-                total,
-            });
+            return Ok(vec![]);
         }
 
         let (server_statses, user_servers, cover_files) = tokio::try_join!(
-            ServerStatsEntity::find()
-                .filter(server_stats::Column::ServerId.is_in(server_ids.clone()))
-                .order_by_desc(server_stats::Column::Timestamp)
-                .all(db.as_ref()),
+            Self::latest_stats(db.as_ref(), &server_ids),
             async {
                 if let Some(uid) = user_id {
                     UserServer::find()
@@ -115,9 +171,10 @@ impl ServerService {
                 }
             },
             async {
-                let cover_hashes: Vec<String> = page_servers
+                let cover_hashes: Vec<String> = servers
                     .iter()
-                    .filter_map(|s| s.cover_hash_id.as_ref())
+                    .flat_map(|s| [&s.cover_hash_id, &s.icon_hash_id])
+                    .flatten()
                     .cloned()
                     .collect();
 
@@ -136,26 +193,20 @@ impl ServerService {
         let user_permissions = Self::build_user_permissions_map(&user_servers);
         let cover_file_map = Self::build_cover_file_map(&cover_files);
 
-        let server_list = Self::convert_servers_to_details(
-            page_servers,
-            &stats_map,
-            &user_permissions,
-            &cover_file_map,
-        )?;
-
-        Ok(PaginatedServerResult {
-            data: server_list,
-            total,
-        })
+        Self::convert_servers_to_details(servers, &stats_map, &user_permissions, &cover_file_map)
     }
 
+    /// 获取服务器详情
+    ///
+    /// 公开视图对任何调用方都一样（仅 `permission` 随登录用户变化）；管理视图额外返回
+    /// [`ServerPrivateDetail`]，未登录时返回 401，登录但不是服务器成员时返回 403。
     pub async fn get_server_detail(
         db: &DatabaseConnection,
         user_id: Option<i32>,
         server_id: i32,
-        require_login: bool,
+        view: ServerDetailView,
     ) -> ApiResult<ServerDetail> {
-        if require_login && user_id.is_none() {
+        if view == ServerDetailView::Private && user_id.is_none() {
             return Err(crate::errors::ApiError::Unauthorized(
                 "未登录，禁止访问".to_string(),
             ));
@@ -164,9 +215,9 @@ impl ServerService {
         let server = Server::find_by_id(server_id)
             .one(db.as_ref())
             .await?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("服务器不存在".to_string()))?;
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
 
-        let (server_stats, user_server, cover_file) = tokio::try_join!(
+        let (server_stats, user_server, cover_file, icon_file) = tokio::try_join!(
             ServerStatsEntity::find()
                 .filter(server_stats::Column::ServerId.eq(server.id))
                 .order_by_desc(server_stats::Column::Timestamp)
@@ -191,15 +242,31 @@ impl ServerService {
                 } else {
                     Ok(None)
                 }
+            },
+            async {
+                if let Some(ref icon_hash) = server.icon_hash_id {
+                    Files::find_by_id(icon_hash).one(db.as_ref()).await
+                } else {
+                    Ok(None)
+                }
             }
         )?;
 
         let user_role = user_server.map(|us| us.role);
-        if require_login && user_role.is_none() {
-            return Err(crate::errors::ApiError::Unauthorized(
-                "无权限访问该服务器".to_string(),
-            ));
+        let visibility = ServerVisibility::of(&server);
+        let listed = visibility.is_listed() && ServerStatus::of(&server).is_listed();
+        if !listed && user_role.is_none() {
+            return Err(crate::errors::ApiError::server_not_found());
         }
+        let private = match view {
+            ServerDetailView::Public => None,
+            ServerDetailView::Private if user_role.is_none() => {
+                return Err(crate::errors::ApiError::Forbidden(
+                    "只有服务器成员可以查看管理信息".to_string(),
+                ));
+            }
+            ServerDetailView::Private => Some(Self::private_detail(db, &server).await?),
+        };
 
         let stats = if let Some(stats_model) = server_stats {
             if let Some(ref stat_data) = stats_model.stat_data {
@@ -211,28 +278,35 @@ impl ServerService {
             None
         };
 
-        let cover_url = if let (Some(_hash), Some(file_model)) = (&server.cover_hash_id, cover_file)
-        {
-            Some(file_model.file_path)
-        } else {
-            None
-        };
+        let (cover_url, cover_urls) =
+            if let (Some(_hash), Some(file_model)) = (&server.cover_hash_id, cover_file) {
+                let urls = Self::build_image_urls(&file_model);
+                (Some(file_model.file_path), Some(urls))
+            } else {
+                (None, None)
+            };
+        let icon_url = icon_file.map(|file| file.file_path);
+
+        let translations = TranslationService::translations_for(db, &server).await?;
+        let custom_fields = CustomFieldService::list(db, server.id).await?;
+        let version_range = Self::version_range_of(&server);
 
         Ok(ServerDetail {
             id: server.id,
             name: server.name,
-            ip: if server.is_hide {
-                None
-            } else {
-                Some(server.ip)
-            },
+            ip_family: visibility
+                .public_ip(server.ip.clone())
+                .and_then(|ip| IpFamily::of(&ip)),
+            ip: visibility.public_ip(server.ip),
             r#type: match server.r#type.as_str() {
                 "JAVA" => ApiServerType::Java,
                 "BEDROCK" => ApiServerType::Bedrock,
                 _ => ApiServerType::Java,
             },
+            version_range,
             version: server.version,
             desc: server.desc,
+            desc_html: None,
             link: server.link,
             is_member: server.is_member,
             auth_mode: match server.auth_mode.as_str() {
@@ -242,10 +316,42 @@ impl ServerService {
                 _ => ApiAuthMode::Official,
             },
             tags: Self::parse_server_tags(&server.tags),
-            is_hide: server.is_hide,
+            is_hide: visibility != ServerVisibility::Public,
+            visibility,
             stats,
             permission: user_role.unwrap_or_else(|| "guest".to_string()),
             cover_url,
+            cover_urls,
+            icon_url,
+            slug: server.slug,
+            managers: None,
+            translations,
+            custom_fields,
+            private,
+            is_favorited: None,
+        })
+    }
+
+    async fn private_detail(
+        db: &DatabaseConnection,
+        server: &server::Model,
+    ) -> ApiResult<ServerPrivateDetail> {
+        let pending_tickets = Ticket::find()
+            .filter(ticket::Column::ServerId.eq(server.id))
+            .filter(ticket::Column::Status.eq(Self::TICKET_STATUS_PENDING))
+            .count(db.as_ref())
+            .await?;
+
+        Ok(ServerPrivateDetail {
+            ip: server.ip.clone(),
+            pending_tickets,
+            push_secret_configured: server.push_secret.is_some(),
+            slug_editable: !server.slug_edited,
+            player_search_opt_out: server.player_search_opt_out,
+            delisted_at: server.delisted_at,
+            deactivated_at: server.deactivated_at,
+            status: ServerStatus::of(server),
+            review_note: server.review_note.clone(),
         })
     }
 
@@ -266,14 +372,152 @@ impl ServerService {
             .collect()
     }
 
-    fn build_cover_file_map(cover_files: &[files::Model]) -> HashMap<String, String> {
+    fn build_cover_file_map(cover_files: &[files::Model]) -> HashMap<String, &files::Model> {
         cover_files
             .iter()
-            .map(|file_model| (file_model.hash_value.clone(), file_model.file_path.clone()))
+            .map(|file_model| (file_model.hash_value.clone(), file_model))
             .collect()
     }
 
-    fn server_has_required_tags(server_tags_json: &JsonValue, required_tags: &[String]) -> bool {
+    /// 服务器标签包含任一所需标签
+    ///
+    /// MySQL 使用 `JSON_CONTAINS`，本地开发与测试使用的 SQLite 没有该函数，改用 `json_each`。
+    fn any_tag_condition(backend: DbBackend, required_tags: &[String]) -> Condition {
+        required_tags
+            .iter()
+            .fold(Condition::any(), |condition, tag| match backend {
+                DbBackend::Sqlite => condition.add(sea_query::Expr::cust_with_values(
+                    "EXISTS (SELECT 1 FROM json_each(tags) WHERE json_each.value = ?)",
+                    [tag.clone()],
+                )),
+                _ => condition.add(sea_query::Expr::cust_with_values(
+                    "JSON_CONTAINS(tags, ?)",
+                    [Value::String(tag.clone()).to_string()],
+                )),
+            })
+    }
+
+    /// 兼容指定版本的服务器：版本区间包含该版本、版本列表中有该版本，
+    /// 或没有设置版本范围且 `version` 与之相同
+    fn compatible_condition(backend: DbBackend, version: &GameVersion) -> Condition {
+        let code = version.code(false);
+        let listed = match backend {
+            DbBackend::Sqlite => sea_query::Expr::cust_with_values(
+                "EXISTS (SELECT 1 FROM json_each(version_range, '$.versions') WHERE json_each.value = ?)",
+                [version.to_string()],
+            ),
+            _ => sea_query::Expr::cust_with_values(
+                "JSON_CONTAINS(version_range, ?, '$.versions')",
+                [Value::String(version.to_string()).to_string()],
+            ),
+        };
+        Condition::any()
+            .add(
+                Condition::all()
+                    .add(server::Column::VersionMinCode.lte(code))
+                    .add(server::Column::VersionMaxCode.gte(code)),
+            )
+            .add(
+                Condition::all()
+                    .add(server::Column::VersionRange.is_not_null())
+                    .add(listed),
+            )
+            .add(
+                Condition::all()
+                    .add(server::Column::VersionRange.is_null())
+                    .add(server::Column::Version.eq(version.to_string())),
+            )
+    }
+
+    /// 解析请求中的版本范围，空字符串表示清除
+    fn parse_version_range(input: &str) -> ApiResult<Option<VersionRange>> {
+        if input.trim().is_empty() {
+            return Ok(None);
+        }
+        VersionRange::parse(input)
+            .map(Some)
+            .map_err(crate::errors::ApiError::BadRequest)
+    }
+
+    /// 写入版本范围及用于筛选的区间编码
+    fn set_version_range(
+        active: &mut server::ActiveModel,
+        range: Option<&VersionRange>,
+    ) -> ApiResult<()> {
+        let json = range
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| crate::errors::ApiError::Internal(format!("版本范围序列化失败: {e}")))?;
+        let bounds = range.and_then(VersionRange::bounds);
+        active.version_range = Set(json);
+        active.version_min_code = Set(bounds.map(|(min, _)| min));
+        active.version_max_code = Set(bounds.map(|(_, max)| max));
+        Ok(())
+    }
+
+    /// 服务器记录中的版本范围
+    pub(crate) fn version_range_of(server: &server::Model) -> Option<VersionRange> {
+        server
+            .version_range
+            .clone()
+            .and_then(|json| serde_json::from_value(json).ok())
+    }
+
+    /// 按种子打乱的排序键
+    ///
+    /// 先用种子决定的仿射变换 `x = (id * a + b) mod p` 把 ID 分散到 [0, p)，再以
+    /// `(x * x mod p) * c + d mod p` 打乱相邻 ID 的顺序。模数取 2^31-1，各项都小于 2^31，
+    /// 中间结果不会超出 BIGINT 范围；平方可能产生相同的键，由调用方按 ID 兜底排序。
+    fn shuffle_key(seed: i64) -> sea_query::SimpleExpr {
+        const MODULUS: i64 = 2_147_483_647;
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let [a, b, c, d] = [1, 0, 1, 0].map(|low| rng.random_range(low..MODULUS));
+        sea_query::Expr::cust_with_values(
+            "((((id * ? + ?) % ?) * ((id * ? + ?) % ?) % ?) * ? + ?) % ?",
+            [a, b, MODULUS, a, b, MODULUS, MODULUS, c, d, MODULUS],
+        )
+    }
+
+    /// 每个服务器采集时间最新的一条状态
+    pub(crate) async fn latest_stats<C: ConnectionTrait>(
+        db: &C,
+        server_ids: &[i32],
+    ) -> Result<Vec<server_stats::Model>, DbErr> {
+        let latest: Vec<(i32, Option<chrono::DateTime<Utc>>)> = ServerStatsEntity::find()
+            .select_only()
+            .column(server_stats::Column::ServerId)
+            .column_as(server_stats::Column::Timestamp.max(), "timestamp")
+            .filter(server_stats::Column::ServerId.is_in(server_ids.iter().copied()))
+            .group_by(server_stats::Column::ServerId)
+            .into_tuple()
+            .all(db)
+            .await?;
+        let condition = latest
+            .into_iter()
+            .filter_map(|(server_id, timestamp)| Some((server_id, timestamp?)))
+            .fold(Condition::any(), |condition, (server_id, timestamp)| {
+                condition.add(
+                    Condition::all()
+                        .add(server_stats::Column::ServerId.eq(server_id))
+                        .add(server_stats::Column::Timestamp.eq(timestamp)),
+                )
+            });
+        if condition.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        ServerStatsEntity::find()
+            .filter(condition)
+            .order_by_desc(server_stats::Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// 判断服务器标签是否包含任一所需标签
+    pub fn server_has_required_tags(
+        server_tags_json: &JsonValue,
+        required_tags: &[String],
+    ) -> bool {
         if server_tags_json.is_null()
             || (server_tags_json.is_array() && server_tags_json.as_array().unwrap().is_empty())
         {
@@ -294,11 +538,12 @@ impl ServerService {
         }
     }
 
-    fn convert_servers_to_details(
+    /// 将服务器实体转换为列表中的服务器详情
+    pub fn convert_servers_to_details(
         servers: Vec<server::Model>,
         stats_map: &HashMap<i32, &server_stats::Model>,
         user_permissions: &HashMap<i32, String>,
-        cover_file_map: &HashMap<String, String>,
+        cover_file_map: &HashMap<String, &files::Model>,
     ) -> ApiResult<Vec<ServerDetail>> {
         let server_list = servers
             .into_iter()
@@ -322,27 +567,49 @@ impl ServerService {
                     .cloned()
                     .unwrap_or_else(|| "guest".to_string());
 
-                let cover_url = Self::build_cover_url(&server.cover_hash_id, cover_file_map);
+                let cover_file = server
+                    .cover_hash_id
+                    .as_ref()
+                    .and_then(|hash| cover_file_map.get(hash));
+                let cover_url = cover_file.map(|file| file.file_path.clone());
+                let cover_urls = cover_file.map(|file| Self::build_image_urls(file));
+                let icon_url = server
+                    .icon_hash_id
+                    .as_ref()
+                    .and_then(|hash| cover_file_map.get(hash))
+                    .map(|file| file.file_path.clone());
+                let visibility = ServerVisibility::of(&server);
+                let version_range = Self::version_range_of(&server);
 
                 ServerDetail {
                     id: server.id,
                     name: server.name,
-                    ip: if server.is_hide {
-                        None
-                    } else {
-                        Some(server.ip)
-                    },
+                    ip_family: visibility
+                        .public_ip(server.ip.clone())
+                        .and_then(|ip| IpFamily::of(&ip)),
+                    ip: visibility.public_ip(server.ip),
                     r#type: server_type,
+                    version_range,
                     version: server.version,
                     desc: server.desc,
+                    desc_html: None,
                     link: server.link,
                     is_member: server.is_member,
                     auth_mode,
                     tags,
-                    is_hide: server.is_hide,
+                    is_hide: visibility != ServerVisibility::Public,
+                    visibility,
                     stats,
                     permission,
                     cover_url,
+                    cover_urls,
+                    icon_url,
+                    slug: server.slug,
+                    managers: None,
+                    translations: Vec::new(),
+                    custom_fields: Vec::new(),
+                    private: None,
+                    is_favorited: None,
                 }
             })
             .collect();
@@ -350,7 +617,8 @@ impl ServerService {
         Ok(server_list)
     }
 
-    fn parse_server_tags(tags_json: &JsonValue) -> Option<Vec<String>> {
+    /// 解析服务器标签 JSON
+    pub fn parse_server_tags(tags_json: &JsonValue) -> Option<Vec<String>> {
         if tags_json.is_null() {
             return None;
         }
@@ -362,17 +630,7 @@ impl ServerService {
         })
     }
 
-    fn build_cover_url(
-        cover_hash: &Option<String>,
-        cover_file_map: &HashMap<String, String>,
-    ) -> Option<String> {
-        cover_hash
-            .as_ref()
-            .and_then(|hash| cover_file_map.get(hash))
-            .cloned()
-    }
-
-    fn build_image_url(file_path: &str) -> String {
+    pub(crate) fn build_image_url(file_path: &str) -> String {
         if file_path.starts_with("http://") || file_path.starts_with("https://") {
             file_path.to_string()
         } else {
@@ -380,7 +638,25 @@ impl ServerService {
         }
     }
 
-    fn parse_server_stats(stat_data: &Value) -> ApiResult<ServerStats> {
+    /// 图片各尺寸的访问地址，没有缩略图的文件都使用原图地址
+    pub(crate) fn build_image_urls(file: &files::Model) -> ImageUrls {
+        let original = Self::build_image_url(&file.file_path);
+        let variant = |variant: ImageVariant| {
+            if file.has_variants {
+                Self::build_image_url(&variant.path_of(&file.file_path))
+            } else {
+                original.clone()
+            }
+        };
+        ImageUrls {
+            thumb: variant(ImageVariant::Thumb),
+            medium: variant(ImageVariant::Medium),
+            original,
+        }
+    }
+
+    /// 解析 server_stats 中的状态 JSON
+    pub fn parse_server_stats(stat_data: &Value) -> ApiResult<ServerStats> {
         let players = stat_data
             .get("players")
             .and_then(|p| p.as_object())
@@ -402,31 +678,10 @@ impl ServerService {
             .unwrap_or("Unknown")
             .to_string();
 
+        // 状态数据可能由外部采集器直接写入，其余格式一律由原始文本重新生成
         let motd = stat_data
             .get("motd")
-            .and_then(|m| m.as_object())
-            .map(|motd_obj| Motd {
-                plain: motd_obj
-                    .get("plain")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                html: motd_obj
-                    .get("html")
-                    .and_then(|h| h.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                minecraft: motd_obj
-                    .get("minecraft")
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                ansi: motd_obj
-                    .get("ansi")
-                    .and_then(|a| a.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-            })
+            .map(motd::from_value)
             .unwrap_or_default();
 
         let icon = stat_data
@@ -440,12 +695,117 @@ impl ServerService {
             version,
             motd,
             icon,
+            sample: None,
+        })
+    }
+
+    /// 创建服务器，创建者成为服主
+    pub async fn create_server(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        tenant_id: &str,
+        mut request: CreateServerRequest,
+        current_user_id: i32,
+    ) -> ApiResult<ServerDetail> {
+        request.desc = MarkdownService::sanitize(&request.desc);
+        request
+            .validate()
+            .map_err(|e| crate::errors::ApiError::Validation(format!("参数验证失败: {e}")))?;
+        if request.name.trim().is_empty() {
+            return Err(crate::errors::ApiError::BadRequest(
+                "服务器名称不能为空".to_string(),
+            ));
+        }
+        request.r#type.parse::<ApiServerType>().map_err(|_| {
+            crate::errors::ApiError::BadRequest(format!("未知的服务器类型: {}", request.r#type))
+        })?;
+        request.auth_mode.parse::<ApiAuthMode>().map_err(|_| {
+            crate::errors::ApiError::BadRequest(format!(
+                "未知的正版验证模式: {}",
+                request.auth_mode
+            ))
+        })?;
+        TagService::ensure_allowed(db, tenant_id, &request.tags).await?;
+
+        let cover_hash = match &request.cover {
+            Some(cover_data) => {
+                let filename = cover_data
+                    .metadata
+                    .file_name
+                    .as_deref()
+                    .unwrap_or("cover.jpg");
+                let file_model = FileUploadService::validate_and_upload_cover(
+                    db,
+                    storage::require(storage)?,
+                    cover_data.contents.to_vec(),
+                    filename,
+                )
+                .await?;
+                Some(file_model.hash_value)
+            }
+            None => None,
+        };
+
+        let version_range = match request.version_range.as_deref() {
+            Some(input) => Self::parse_version_range(input)?,
+            None => None,
+        };
+
+        let tags_json = serde_json::to_value(&request.tags)
+            .map_err(|e| crate::errors::ApiError::Internal(format!("标签序列化失败: {e}")))?;
+        let mut new_server = server::ActiveModel {
+            name: Set(request.name.trim().to_string()),
+            r#type: Set(request.r#type.clone()),
+            version: Set(request.version.clone()),
+            desc: Set(request.desc.clone()),
+            link: Set(request.link.clone()),
+            ip: Set(ServerAddress::parse(&request.ip)
+                .map(|address| address.to_string())
+                .unwrap_or_else(|| request.ip.clone())),
+            is_member: Set(false),
+            is_hide: Set(false),
+            auth_mode: Set(request.auth_mode.clone()),
+            tags: Set(tags_json),
+            cover_hash_id: Set(cover_hash),
+            gallery_id: Set(None),
+            slug: Set(Some(Self::generate_slug(&request.name))),
+            slug_edited: Set(false),
+            visibility: Set(ServerVisibility::Public.as_str().to_string()),
+            status: Set(ServerStatus::Draft.as_str().to_string()),
+            player_search_opt_out: Set(false),
+            delisting_exempt: Set(false),
+            tenant_id: Set(tenant_id.to_string()),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+        Self::set_version_range(&mut new_server, version_range.as_ref())?;
+
+        let txn = db.begin().await?;
+        let server_id = Server::insert(new_server).exec(&txn).await?.last_insert_id;
+        UserServer::insert(user_server::ActiveModel {
+            role: Set(ServerManagerRole::Owner.as_str().to_string()),
+            server_id: Set(server_id),
+            user_id: Set(current_user_id),
+            ..Default::default()
         })
+        .exec(&txn)
+        .await?;
+        txn.commit().await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        Self::get_server_detail(
+            db,
+            Some(current_user_id),
+            server_id,
+            ServerDetailView::Private,
+        )
+        .await
     }
 
+    /// 更新服务器资料，调用方需已确认操作者是服务器管理员
     pub async fn update_server_by_id(
         db: &DatabaseConnection,
-        s3_config: &crate::config::S3Config,
+        storage: Option<&dyn StorageBackend>,
         server_id: i32,
         update_data: UpdateServerRequest,
         current_user_id: i32,
@@ -454,10 +814,31 @@ impl ServerService {
             .one(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("未找到该服务器".to_string()))?;
+            .ok_or_else(|| {
+                crate::errors::ApiError::NotFound("未找到该服务器".to_string())
+                    .with_code(crate::errors::ErrorCode::ServerNotFound)
+            })?;
+
+        let updated_server =
+            Self::apply_update(db, storage, server, update_data, current_user_id).await?;
 
-        Self::check_server_edit_permission(db, server_id, current_user_id).await?;
+        Self::get_server_detail(
+            db,
+            Some(current_user_id),
+            updated_server.id,
+            ServerDetailView::Private,
+        )
+        .await
+    }
 
+    /// 校验并写入编辑内容，记录修订并发布修改事件；不检查权限，由调用方负责
+    pub(crate) async fn apply_update(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server: server::Model,
+        mut update_data: UpdateServerRequest,
+        current_user_id: i32,
+    ) -> ApiResult<server::Model> {
         if update_data.name.trim().is_empty()
             && update_data.ip.trim().is_empty()
             && update_data.desc.trim().is_empty()
@@ -467,9 +848,30 @@ impl ServerService {
             ));
         }
 
+        update_data.desc = MarkdownService::sanitize(&update_data.desc);
         update_data
             .validate()
-            .map_err(|e| crate::errors::ApiError::BadRequest(format!("参数验证失败: {e}")))?;
+            .map_err(|e| crate::errors::ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+        let new_slug = match update_data.slug.as_deref().map(str::trim) {
+            Some(slug) if !slug.is_empty() && server.slug.as_deref() != Some(slug) => {
+                Self::check_slug_available(db, &server, slug).await?;
+                Some(slug.to_string())
+            }
+            _ => None,
+        };
+
+        let visibility = match update_data.visibility.as_deref() {
+            Some(value) => Some(ServerVisibility::parse(value).ok_or_else(|| {
+                crate::errors::ApiError::BadRequest(format!("未知的可见性: {value}"))
+            })?),
+            None => None,
+        };
+        let version_range = update_data
+            .version_range
+            .as_deref()
+            .map(Self::parse_version_range)
+            .transpose()?;
 
         let original_cover_hash = server.cover_hash_id.clone();
         let cover_hash = if let Some(ref cover_data) = update_data.cover {
@@ -480,7 +882,7 @@ impl ServerService {
                 .unwrap_or("cover.jpg");
             let file_model = FileUploadService::validate_and_upload_cover(
                 db,
-                s3_config,
+                storage::require(storage)?,
                 cover_data.contents.to_vec(),
                 filename,
             )
@@ -490,12 +892,25 @@ impl ServerService {
             original_cover_hash
         };
 
+        // 只检查新增的标签，词表收紧前已有的标签可以保留
+        let existing_tags = Self::parse_server_tags(&server.tags).unwrap_or_default();
+        let added_tags: Vec<String> = update_data
+            .tags
+            .iter()
+            .filter(|tag| !existing_tags.contains(tag))
+            .cloned()
+            .collect();
+        TagService::ensure_allowed(db, &server.tenant_id, &added_tags).await?;
+
         let tags_json = serde_json::to_value(&update_data.tags)
             .map_err(|e| crate::errors::ApiError::Internal(format!("标签序列化失败: {e}")))?;
 
+        let previous = server.clone();
         let mut server_active: server::ActiveModel = server.into();
         server_active.name = Set(update_data.name.clone());
-        server_active.ip = Set(update_data.ip.clone());
+        server_active.ip = Set(ServerAddress::parse(&update_data.ip)
+            .map(|address| address.to_string())
+            .unwrap_or_else(|| update_data.ip.clone()));
         server_active.desc = Set(update_data.desc.clone());
         server_active.tags = Set(tags_json);
         server_active.version = Set(update_data.version.clone());
@@ -503,41 +918,154 @@ impl ServerService {
         if let Some(hash) = cover_hash {
             server_active.cover_hash_id = Set(Some(hash));
         }
+        if let Some(slug) = new_slug {
+            server_active.slug = Set(Some(slug));
+            server_active.slug_edited = Set(true);
+        }
+        if let Some(visibility) = visibility {
+            server_active.visibility = Set(visibility.as_str().to_string());
+            server_active.is_hide = Set(visibility != ServerVisibility::Public);
+        }
+        if let Some(opt_out) = update_data.player_search_opt_out {
+            server_active.player_search_opt_out = Set(opt_out);
+        }
+        if let Some(version_range) = version_range {
+            Self::set_version_range(&mut server_active, version_range.as_ref())?;
+        }
 
+        let txn = db.begin().await?;
         let updated_server = server_active
-            .update(db.as_ref())
+            .update(&txn)
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
+        ServerRevisionService::record(
+            &txn,
+            &previous,
+            &updated_server,
+            Some(current_user_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
+        if updated_server.player_search_opt_out && !previous.player_search_opt_out {
+            PlayerIndexService::clear(&updated_server.tenant_id, updated_server.id).await;
+        }
+        EventBus::publish(DomainEvent::server_updated(vec![updated_server.id]));
+
+        Ok(updated_server)
+    }
+
+    /// 根据服务器名称生成短链接：名称中的字母数字部分加随机后缀，
+    /// 后缀保证唯一性，也避免通过短链接推算出服务器 ID
+    pub fn generate_slug(name: &str) -> String {
+        let mut base = String::new();
+        for c in name.chars() {
+            if c.is_ascii_alphanumeric() {
+                base.push(c.to_ascii_lowercase());
+            } else if !base.is_empty() && !base.ends_with('-') {
+                base.push('-');
+            }
+            if base.len() >= 40 {
+                break;
+            }
+        }
+        let base = base.trim_end_matches('-');
+
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        let mut rng = rand::rng();
+        let suffix: String = (0..6)
+            .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+            .collect();
 
-        Self::get_server_detail(db, Some(current_user_id), updated_server.id, true).await
+        if base.is_empty() {
+            format!("server-{suffix}")
+        } else {
+            format!("{base}-{suffix}")
+        }
     }
 
-    async fn check_server_edit_permission(
+    /// 根据短链接查找服务器 ID
+    pub async fn find_id_by_slug(
         db: &DatabaseConnection,
-        server_id: i32,
-        user_id: i32,
-    ) -> ApiResult<()> {
-        let user_server = UserServer::find()
-            .filter(user_server::Column::UserId.eq(user_id))
-            .filter(user_server::Column::ServerId.eq(server_id))
+        tenant_id: &str,
+        slug: &str,
+    ) -> ApiResult<i32> {
+        Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::Slug.eq(slug))
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .into_tuple::<i32>()
             .one(db.as_ref())
-            .await
-            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)
+    }
 
-        match user_server {
-            Some(us) => {
-                if us.role == "admin" || us.role == "owner" {
-                    Ok(())
-                } else {
-                    Err(crate::errors::ApiError::Authorization(
-                        "无权限编辑该服务器".to_string(),
-                    ))
-                }
+    /// 为尚未分配短链接的服务器生成短链接，返回处理数量
+    pub async fn backfill_slugs(db: &DatabaseConnection) -> ApiResult<usize> {
+        let servers: Vec<(i32, String)> = Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .column(server::Column::Name)
+            .filter(server::Column::Slug.is_null())
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let mut assigned = 0;
+        for (id, name) in servers {
+            let result = Server::update_many()
+                .col_expr(
+                    server::Column::Slug,
+                    sea_query::Expr::value(Self::generate_slug(&name)),
+                )
+                .col_expr(
+                    server::Column::UpdatedAt,
+                    sea_query::Expr::value(Utc::now()),
+                )
+                .filter(server::Column::Id.eq(id))
+                .filter(server::Column::Slug.is_null())
+                .exec(db.as_ref())
+                .await;
+            match result {
+                Ok(_) => assigned += 1,
+                Err(e) => tracing::warn!("⚠️  为服务器 {} 生成短链接失败: {}", id, e),
             }
-            None => Err(crate::errors::ApiError::Authorization(
-                "无权限编辑该服务器".to_string(),
-            )),
         }
+
+        Ok(assigned)
+    }
+
+    /// 校验手动设置的短链接：只能修改一次、不能是纯数字、不能与其他服务器重复
+    async fn check_slug_available(
+        db: &DatabaseConnection,
+        server: &server::Model,
+        slug: &str,
+    ) -> ApiResult<()> {
+        if server.slug_edited {
+            return Err(crate::errors::ApiError::Conflict(
+                "短链接只能修改一次".to_string(),
+            ));
+        }
+        if slug.chars().all(|c| c.is_ascii_digit()) {
+            return Err(crate::errors::ApiError::BadRequest(
+                "短链接不能是纯数字".to_string(),
+            ));
+        }
+
+        let taken = Server::find()
+            .filter(server::Column::Slug.eq(slug))
+            .filter(server::Column::Id.ne(server.id))
+            .count(db.as_ref())
+            .await?;
+        if taken > 0 {
+            return Err(crate::errors::ApiError::Conflict(
+                "短链接已被占用".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 
     pub async fn get_server_gallery(
@@ -559,7 +1087,7 @@ impl ServerService {
             })?
             .ok_or_else(|| {
                 tracing::warn!("服务器不存在: server_id={}", server_id);
-                crate::errors::ApiError::NotFound("服务器不存在".to_string())
+                crate::errors::ApiError::server_not_found()
             })?;
 
         let gallery_images = Self::get_server_gallery_images(db, &server).await?;
@@ -577,6 +1105,39 @@ impl ServerService {
         })
     }
 
+    /// 由公开详情与相册组装服务器概要
+    pub fn summary_of(detail: ServerDetail, gallery: Vec<GalleryImage>) -> ServerSummary {
+        let (icon, ping) = match detail.stats {
+            Some(stats) => (
+                detail.icon_url.or(stats.icon),
+                Some(ServerPing {
+                    motd: stats.motd,
+                    delay: stats.delay,
+                    online_players: stats.players.get("online").copied(),
+                    max_players: stats.players.get("max").copied(),
+                    motd_image_url: None,
+                }),
+            ),
+            None => (detail.icon_url, None),
+        };
+
+        ServerSummary {
+            id: detail.id,
+            name: detail.name,
+            intro: MarkdownService::intro(&detail.desc, SUMMARY_INTRO_CHARS),
+            icon,
+            registration: ServerRegistration {
+                is_member: detail.is_member,
+                tags: detail.tags.unwrap_or_default(),
+                version: detail.version,
+                version_range: detail.version_range,
+            },
+            desc: detail.desc,
+            gallery,
+            ping,
+        }
+    }
+
     async fn get_server_gallery_images(
         db: &DatabaseConnection,
         server: &server::Model,
@@ -598,6 +1159,8 @@ impl ServerService {
 
         let gallery_images = GalleryImageEntity::find()
             .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+            .order_by_asc(gallery_image::Column::SortOrder)
+            .order_by_asc(gallery_image::Column::Id)
             .all(db.as_ref())
             .await
             .map_err(|e| {
@@ -630,22 +1193,23 @@ impl ServerService {
                 crate::errors::ApiError::Database(format!("查询图片文件失败: {e}"))
             })?;
 
-        let file_map: HashMap<String, String> = image_files
+        let file_map: HashMap<String, &files::Model> = image_files
             .iter()
-            .map(|file_model| (file_model.hash_value.clone(), file_model.file_path.clone()))
+            .map(|file_model| (file_model.hash_value.clone(), file_model))
             .collect();
 
         let mut gallery_list = Vec::new();
         let mut missing_files = Vec::new();
 
         for gallery_image in gallery_images {
-            if let Some(file_path) = file_map.get(&gallery_image.image_hash_id) {
-                let image_url = Self::build_image_url(file_path);
+            if let Some(file) = file_map.get(&gallery_image.image_hash_id) {
                 gallery_list.push(GalleryImage {
                     id: gallery_image.id,
                     title: gallery_image.title,
                     description: gallery_image.description,
-                    image_url,
+                    image_url: Self::build_image_url(&file.file_path),
+                    image_urls: Self::build_image_urls(file),
+                    sort_order: gallery_image.sort_order,
                 });
             } else {
                 missing_files.push(gallery_image.image_hash_id.clone());
@@ -676,7 +1240,7 @@ impl ServerService {
         let _server = Server::find_by_id(server_id)
             .one(db.as_ref())
             .await?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("服务器不存在".to_string()))?;
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
 
         let managers = UserServer::find()
             .filter(user_server::Column::ServerId.eq(server_id))
@@ -684,12 +1248,15 @@ impl ServerService {
             .all(db.as_ref())
             .await?;
 
+        // 列表中使用小尺寸头像，旧头像没有小尺寸时退回标准尺寸
         let avatar_hashes: Vec<String> = managers
             .iter()
             .filter_map(|(_, user_opt)| {
-                user_opt
-                    .as_ref()
-                    .and_then(|user| user.avatar_hash_id.clone())
+                user_opt.as_ref().and_then(|user| {
+                    user.avatar_small_hash_id
+                        .clone()
+                        .or_else(|| user.avatar_hash_id.clone())
+                })
             })
             .collect();
 
@@ -712,24 +1279,15 @@ impl ServerService {
 
         for (user_server_relation, user_opt) in managers {
             if let Some(user) = user_opt {
-                let avatar_url = if let Some(avatar_hash_id) = &user.avatar_hash_id {
-                    let file_path = avatar_file_map.get(avatar_hash_id).ok_or_else(|| {
-                        crate::errors::ApiError::Internal(format!(
-                            "头像文件不存在: {avatar_hash_id}"
-                        ))
-                    })?;
-
-                    file_path.clone()
-                } else {
-                    return Err(crate::errors::ApiError::Internal(
-                        "用户缺少头像信息".to_string(),
-                    ));
-                };
+                let avatar_url = user
+                    .avatar_small_hash_id
+                    .as_ref()
+                    .or(user.avatar_hash_id.as_ref())
+                    .and_then(|hash| avatar_file_map.get(hash))
+                    .map(|path| Self::build_image_url(path));
 
-                let role = match user_server_relation.role.as_str() {
-                    "owner" => ServerManagerRole::Owner,
-                    "admin" => ServerManagerRole::Admin,
-                    _ => continue,
+                let Some(role) = ServerManagerRole::parse(&user_server_relation.role) else {
+                    continue;
                 };
 
                 let manager_info = ManagerInfo {
@@ -749,51 +1307,245 @@ impl ServerService {
         Ok(ServerManagersResponse { owners, admins })
     }
 
-    pub async fn has_server_edit_permission(
+    /// 用户在服务器上的管理角色，不是管理员时为 `None`
+    pub async fn server_role(
         db: &DatabaseConnection,
-        user_id: i32,
         server_id: i32,
-    ) -> ApiResult<bool> {
-        let user_server = UserServer::find()
+        user_id: i32,
+    ) -> ApiResult<Option<ServerManagerRole>> {
+        Ok(UserServer::find()
             .filter(user_server::Column::UserId.eq(user_id))
             .filter(user_server::Column::ServerId.eq(server_id))
-            .filter(user_server::Column::Role.is_in(["owner", "admin"]))
             .one(db.as_ref())
-            .await
-            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
+            .await?
+            .and_then(|us| ServerManagerRole::parse(&us.role)))
+    }
+
+    /// 添加服务器管理员，调用方需已确认操作者是服主
+    ///
+    /// 被添加的用户必须属于同一租户且账户已启用，已是管理员时返回冲突。
+    pub async fn add_manager(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+        request: &AddManagerRequest,
+    ) -> ApiResult<i32> {
+        let user = Users::find()
+            .filter(users::Column::Username.eq(request.username.trim()))
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .filter(users::Column::IsActive.eq(true))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::user_not_found)?;
+        if Self::server_role(db, server_id, user.id).await?.is_some() {
+            return Err(crate::errors::ApiError::Conflict(
+                "该用户已是服务器管理员".to_string(),
+            ));
+        }
 
-        Ok(user_server.is_some())
+        user_server::ActiveModel {
+            user_id: Set(user.id),
+            server_id: Set(server_id),
+            role: Set(request.role.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        Ok(user.id)
     }
 
-    pub async fn add_gallery_image(
+    /// 修改管理员角色，调用方需已确认操作者是服主，服务器至少保留一名服主
+    pub async fn update_manager_role(
         db: &DatabaseConnection,
-        s3_config: &S3Config,
         server_id: i32,
-        gallery_data: &GalleryImageSchema,
+        user_id: i32,
+        role: ServerManagerRole,
     ) -> ApiResult<()> {
-        let server = Server::find_by_id(server_id)
-            .one(db.as_ref())
-            .await
-            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("服务器不存在".to_string()))?;
+        let membership = Self::find_membership(db, server_id, user_id).await?;
+        if membership.role == role.as_str() {
+            return Ok(());
+        }
+        if membership.role == ServerManagerRole::Owner.as_str() {
+            Self::ensure_other_owner(db, server_id, user_id).await?;
+        }
 
-        gallery_data
-            .validate()
-            .map_err(|e| crate::errors::ApiError::BadRequest(format!("参数验证失败: {e}")))?;
+        let mut active: user_server::ActiveModel = membership.into();
+        active.role = Set(role.as_str().to_string());
+        active.update(db.as_ref()).await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
 
-        let gallery_id = if let Some(gallery_id) = server.gallery_id {
-            gallery_id
-        } else {
-            let new_gallery = gallery::ActiveModel {
-                created_at: Set(Utc::now()),
-                ..Default::default()
-            };
-            let gallery = Gallery::insert(new_gallery)
-                .exec_with_returning(db.as_ref())
-                .await
-                .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
+        Ok(())
+    }
 
-            let mut server_active: server::ActiveModel = server.into();
+    /// 移除管理员
+    ///
+    /// 服主可以移除任何管理员，其他管理员只能移除自己（退出管理），服务器至少保留一名服主。
+    /// `actor_role` 为操作者在该服务器上的角色，调用方需已确认操作者是服务器管理员
+    pub async fn remove_manager(
+        db: &DatabaseConnection,
+        server_id: i32,
+        actor_id: i32,
+        actor_role: ServerManagerRole,
+        user_id: i32,
+    ) -> ApiResult<()> {
+        if actor_id != user_id && actor_role != ServerManagerRole::Owner {
+            return Err(crate::errors::ApiError::Forbidden(
+                "只有服务器所有者可以管理管理员".to_string(),
+            ));
+        }
+
+        let membership = Self::find_membership(db, server_id, user_id).await?;
+        if membership.role == ServerManagerRole::Owner.as_str() {
+            Self::ensure_other_owner(db, server_id, user_id).await?;
+        }
+
+        UserServer::delete_by_id(membership.id)
+            .exec(db.as_ref())
+            .await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        Ok(())
+    }
+
+    /// 把服主身份转让给另一名管理员，转让后原服主成为管理员，调用方需已确认操作者是服主
+    pub async fn transfer_ownership(
+        db: &DatabaseConnection,
+        server_id: i32,
+        actor_id: i32,
+        user_id: i32,
+    ) -> ApiResult<()> {
+        if actor_id == user_id {
+            return Err(crate::errors::ApiError::BadRequest(
+                "不能把服务器转让给自己".to_string(),
+            ));
+        }
+        let target = Self::find_membership(db, server_id, user_id).await?;
+        let actor = Self::find_membership(db, server_id, actor_id).await?;
+
+        let txn = db.begin().await?;
+        let mut target: user_server::ActiveModel = target.into();
+        target.role = Set(ServerManagerRole::Owner.as_str().to_string());
+        target.update(&txn).await?;
+        let mut actor: user_server::ActiveModel = actor.into();
+        actor.role = Set(ServerManagerRole::Admin.as_str().to_string());
+        actor.update(&txn).await?;
+        txn.commit().await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        Ok(())
+    }
+
+    /// 提交审核，草稿、未通过或已归档的服务器才能提交，调用方需已确认操作者是服务器管理员
+    pub async fn submit_for_review(
+        db: &DatabaseConnection,
+        server_id: i32,
+    ) -> ApiResult<ServerStatus> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+        let status = ServerStatus::of(&server);
+        if status == ServerStatus::PendingReview {
+            return Err(crate::errors::ApiError::Conflict(
+                "服务器已在等待审核".to_string(),
+            ));
+        }
+        if !status.can_submit() {
+            return Err(crate::errors::ApiError::Conflict(
+                "服务器已发布，无需提交审核".to_string(),
+            ));
+        }
+
+        let mut active: server::ActiveModel = server.into();
+        active.status = Set(ServerStatus::PendingReview.as_str().to_string());
+        active.review_note = Set(None);
+        active.update(db.as_ref()).await?;
+        Ok(ServerStatus::PendingReview)
+    }
+
+    /// 归档服务器，从列表与搜索中撤下，调用方需已确认操作者是服主
+    pub async fn archive(db: &DatabaseConnection, server_id: i32) -> ApiResult<ServerStatus> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+        let status = ServerStatus::of(&server);
+        if status == ServerStatus::Archived {
+            return Ok(status);
+        }
+
+        let mut active: server::ActiveModel = server.into();
+        active.status = Set(ServerStatus::Archived.as_str().to_string());
+        active.update(db.as_ref()).await?;
+        if status.is_listed() {
+            EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+        }
+        Ok(ServerStatus::Archived)
+    }
+
+    async fn find_membership(
+        db: &DatabaseConnection,
+        server_id: i32,
+        user_id: i32,
+    ) -> ApiResult<user_server::Model> {
+        UserServer::find()
+            .filter(user_server::Column::UserId.eq(user_id))
+            .filter(user_server::Column::ServerId.eq(server_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| crate::errors::ApiError::NotFound("该用户不是服务器管理员".to_string()))
+    }
+
+    async fn ensure_other_owner(
+        db: &DatabaseConnection,
+        server_id: i32,
+        user_id: i32,
+    ) -> ApiResult<()> {
+        let other_owners = UserServer::find()
+            .filter(user_server::Column::ServerId.eq(server_id))
+            .filter(user_server::Column::Role.eq(ServerManagerRole::Owner.as_str()))
+            .filter(user_server::Column::UserId.ne(user_id))
+            .count(db.as_ref())
+            .await?;
+        if other_owners == 0 {
+            return Err(crate::errors::ApiError::Conflict(
+                "服务器至少需要保留一名所有者，请先转让所有权".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn add_gallery_image(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        server_id: i32,
+        gallery_data: &GalleryImageSchema,
+    ) -> ApiResult<()> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await
+            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        gallery_data
+            .validate()
+            .map_err(|e| crate::errors::ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+        let gallery_id = if let Some(gallery_id) = server.gallery_id {
+            gallery_id
+        } else {
+            let new_gallery = gallery::ActiveModel {
+                created_at: Set(Utc::now()),
+                ..Default::default()
+            };
+            let gallery = Gallery::insert(new_gallery)
+                .exec_with_returning(db.as_ref())
+                .await
+                .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
+
+            let mut server_active: server::ActiveModel = server.into();
             server_active.gallery_id = Set(Some(gallery.id));
             Server::update(server_active)
                 .exec(db.as_ref())
@@ -812,14 +1564,27 @@ impl ServerService {
             .unwrap_or("image.jpg");
 
         let image_file =
-            FileUploadService::validate_and_upload_gallery(db, s3_config, image_content, filename)
+            FileUploadService::validate_and_upload_gallery(db, storage, image_content, filename)
                 .await?;
 
+        // 新图片排在最后
+        let last_sort_order = GalleryImageEntity::find()
+            .select_only()
+            .expr(gallery_image::Column::SortOrder.max())
+            .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+            .into_tuple::<Option<i32>>()
+            .one(db.as_ref())
+            .await
+            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
+            .flatten();
+
         let gallery_image = gallery_image::ActiveModel {
             gallery_id: Set(gallery_id),
             title: Set(gallery_data.title.clone()),
             description: Set(gallery_data.description.clone()),
             image_hash_id: Set(image_file.hash_value),
+            sort_order: Set(last_sort_order.map_or(0, |order| order + 1)),
+            created_at: Set(Some(Utc::now())),
             ..Default::default()
         };
 
@@ -831,29 +1596,159 @@ impl ServerService {
         Ok(())
     }
 
-    pub async fn delete_gallery_image(
+    /// 服务器封面的文件地址，隐藏、未发布、停用或不属于当前租户的服务器视为不存在
+    pub async fn cover_url(
         db: &DatabaseConnection,
-        s3_config: &S3Config,
+        tenant_id: &str,
         server_id: i32,
-        image_id: i32,
+    ) -> ApiResult<String> {
+        let server = Server::find_by_id(server_id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+        let cover_hash = server.cover_hash_id.ok_or_else(|| {
+            crate::errors::ApiError::NotFound("服务器没有封面".to_string())
+                .with_code(crate::errors::ErrorCode::CoverNotFound)
+        })?;
+        let file = Files::find_by_id(&cover_hash)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| {
+                crate::errors::ApiError::NotFound("封面文件不存在".to_string())
+                    .with_code(crate::errors::ErrorCode::CoverNotFound)
+            })?;
+
+        Ok(Self::build_image_url(&file.file_path))
+    }
+
+    /// 移除服务器封面，文件不再被任何服务器、画册或头像引用时一并删除，
+    /// 调用方需已确认操作者是服务器管理员
+    pub async fn delete_cover(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server_id: i32,
+        current_user_id: i32,
     ) -> ApiResult<()> {
-        use crate::services::file_upload::FileUploadService;
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let cover_hash = server.cover_hash_id.clone().ok_or_else(|| {
+            crate::errors::ApiError::NotFound("服务器没有封面".to_string())
+                .with_code(crate::errors::ErrorCode::CoverNotFound)
+        })?;
+
+        let previous = server.clone();
+        let mut server_active: server::ActiveModel = server.into();
+        server_active.cover_hash_id = Set(None);
+
+        let txn = db.begin().await?;
+        let updated_server = server_active.update(&txn).await?;
+        ServerRevisionService::record(
+            &txn,
+            &previous,
+            &updated_server,
+            Some(current_user_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        FileGcService::release(db, storage, &cover_hash).await;
+
+        Ok(())
+    }
 
+    /// 上传服务器图标并替换原有图标，返回新图标地址；旧图标文件不再被引用时一并删除，
+    /// 调用方需已确认操作者是服务器管理员
+    pub async fn update_icon(
+        db: &DatabaseConnection,
+        storage: &dyn StorageBackend,
+        server_id: i32,
+        content: Vec<u8>,
+    ) -> ApiResult<String> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let file = FileUploadService::validate_and_upload_icon(db, storage, content).await?;
+        let previous_icon = server.icon_hash_id.clone();
+        let mut server_active: server::ActiveModel = server.into();
+        server_active.icon_hash_id = Set(Some(file.hash_value.clone()));
+        server_active.update(db.as_ref()).await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        if let Some(hash) = previous_icon.filter(|hash| *hash != file.hash_value) {
+            FileGcService::release(db, Some(storage), &hash).await;
+        }
+
+        Ok(file.file_path)
+    }
+
+    /// 移除服务器图标，文件不再被任何服务器、画册或头像引用时一并删除，
+    /// 调用方需已确认操作者是服务器管理员
+    pub async fn delete_icon(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server_id: i32,
+    ) -> ApiResult<()> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let icon_hash = server.icon_hash_id.clone().ok_or_else(|| {
+            crate::errors::ApiError::NotFound("服务器没有图标".to_string())
+                .with_code(crate::errors::ErrorCode::IconNotFound)
+        })?;
+
+        let mut server_active: server::ActiveModel = server.into();
+        server_active.icon_hash_id = Set(None);
+        server_active.update(db.as_ref()).await?;
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
+        FileGcService::release(db, storage, &icon_hash).await;
+
+        Ok(())
+    }
+
+    /// 查询服务器的画册 ID，服务器或画册不存在时返回 404
+    async fn find_gallery_id(db: &DatabaseConnection, server_id: i32) -> ApiResult<i32> {
         let server = Server::find_by_id(server_id)
             .one(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("服务器不存在".to_string()))?;
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
 
-        let gallery_id = server
-            .gallery_id
-            .ok_or_else(|| crate::errors::ApiError::NotFound("该服务器没有画册".to_string()))?;
+        server.gallery_id.ok_or_else(|| {
+            crate::errors::ApiError::NotFound("该服务器没有画册".to_string())
+                .with_code(crate::errors::ErrorCode::GalleryNotFound)
+        })
+    }
+
+    /// 查询属于指定服务器画册的图片
+    async fn find_gallery_image(
+        db: &DatabaseConnection,
+        server_id: i32,
+        image_id: i32,
+    ) -> ApiResult<gallery_image::Model> {
+        let gallery_id = Self::find_gallery_id(db, server_id).await?;
 
         let gallery_image = GalleryImageEntity::find_by_id(image_id)
             .one(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
-            .ok_or_else(|| crate::errors::ApiError::NotFound("图片不存在".to_string()))?;
+            .ok_or_else(|| {
+                crate::errors::ApiError::NotFound("图片不存在".to_string())
+                    .with_code(crate::errors::ErrorCode::ImageNotFound)
+            })?;
 
         if gallery_image.gallery_id != gallery_id {
             return Err(crate::errors::ApiError::Forbidden(
@@ -861,27 +1756,529 @@ impl ServerService {
             ));
         }
 
-        FileUploadService::delete_file(s3_config, &gallery_image.image_hash_id).await?;
+        Ok(gallery_image)
+    }
 
-        Files::delete_by_id(&gallery_image.image_hash_id)
-            .exec(db.as_ref())
+    /// 编辑画册图片的标题与描述，未传的字段保持不变
+    pub async fn update_gallery_image(
+        db: &DatabaseConnection,
+        server_id: i32,
+        image_id: i32,
+        request: &UpdateGalleryImageRequest,
+    ) -> ApiResult<GalleryImage> {
+        request
+            .validate()
+            .map_err(|e| crate::errors::ApiError::Validation(format!("参数验证失败: {e}")))?;
+
+        let gallery_image = Self::find_gallery_image(db, server_id, image_id).await?;
+        let file = Files::find_by_id(&gallery_image.image_hash_id)
+            .one(db.as_ref())
+            .await
+            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
+            .ok_or_else(|| crate::errors::ApiError::NotFound("图片文件不存在".to_string()))?;
+
+        let mut active: gallery_image::ActiveModel = gallery_image.into();
+        if let Some(title) = &request.title {
+            active.title = Set(title.clone());
+        }
+        if let Some(description) = &request.description {
+            active.description = Set(description.clone());
+        }
+        let updated = active
+            .update(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
 
+        Ok(GalleryImage {
+            id: updated.id,
+            title: updated.title,
+            description: updated.description,
+            image_url: Self::build_image_url(&file.file_path),
+            image_urls: Self::build_image_urls(&file),
+            sort_order: updated.sort_order,
+        })
+    }
+
+    /// 按给定顺序重排画册图片，`image_ids` 必须恰好包含画册中的全部图片
+    pub async fn reorder_gallery(
+        db: &DatabaseConnection,
+        server_id: i32,
+        image_ids: &[i32],
+    ) -> ApiResult<ServerGallery> {
+        let gallery_id = Self::find_gallery_id(db, server_id).await?;
+
+        let current: HashSet<i32> = GalleryImageEntity::find()
+            .select_only()
+            .column(gallery_image::Column::Id)
+            .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+            .into_tuple::<i32>()
+            .all(db.as_ref())
+            .await
+            .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?
+            .into_iter()
+            .collect();
+        let requested: HashSet<i32> = image_ids.iter().copied().collect();
+        if requested.len() != image_ids.len() {
+            return Err(crate::errors::ApiError::BadRequest(
+                "图片 ID 不能重复".to_string(),
+            ));
+        }
+        if requested != current {
+            return Err(crate::errors::ApiError::BadRequest(
+                "图片 ID 列表必须恰好包含画册中的全部图片".to_string(),
+            ));
+        }
+
+        let txn = db.begin().await?;
+        for (index, image_id) in image_ids.iter().enumerate() {
+            GalleryImageEntity::update_many()
+                .col_expr(
+                    gallery_image::Column::SortOrder,
+                    sea_query::Expr::value(index as i32),
+                )
+                .filter(gallery_image::Column::Id.eq(*image_id))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        Self::get_server_gallery(db, server_id).await
+    }
+
+    pub async fn delete_gallery_image(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server_id: i32,
+        image_id: i32,
+    ) -> ApiResult<()> {
+        let gallery_image = Self::find_gallery_image(db, server_id, image_id).await?;
+
         GalleryImageEntity::delete_by_id(image_id)
             .exec(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
 
+        // 同一文件可能被其他画册或封面引用，只在不再被引用时删除
+        FileGcService::release(db, storage, &gallery_image.image_hash_id).await;
+
+        Ok(())
+    }
+
+    /// 删除服务器会连带删除或影响的数据条数
+    ///
+    /// 不统计状态记录：服务器持续推送数据，确认期间条数一定会变化。
+    pub async fn deletion_impact(
+        db: &DatabaseConnection,
+        server_id: i32,
+    ) -> ApiResult<BTreeMap<String, u64>> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let (gallery_images, managers, revisions, tickets, favorites) = tokio::try_join!(
+            async {
+                match server.gallery_id {
+                    Some(gallery_id) => {
+                        GalleryImageEntity::find()
+                            .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+                            .count(db.as_ref())
+                            .await
+                    }
+                    None => Ok(0),
+                }
+            },
+            UserServer::find()
+                .filter(user_server::Column::ServerId.eq(server_id))
+                .count(db.as_ref()),
+            ServerRevision::find()
+                .filter(server_revision::Column::ServerId.eq(server_id))
+                .count(db.as_ref()),
+            Ticket::find()
+                .filter(ticket::Column::ServerId.eq(server_id))
+                .count(db.as_ref()),
+            UserFavorite::find()
+                .filter(user_favorite::Column::ServerId.eq(server_id))
+                .count(db.as_ref()),
+        )?;
+
+        Ok(BTreeMap::from([
+            ("servers".to_string(), 1),
+            ("gallery_images".to_string(), gallery_images),
+            ("managers".to_string(), managers),
+            ("revisions".to_string(), revisions),
+            ("tickets_detached".to_string(), tickets),
+            ("favorites".to_string(), favorites),
+        ]))
+    }
+
+    /// 删除服务器及其画册；管理员关系、状态、修订记录与收藏随外键级联删除，工单保留但与服务器解除关联，
+    /// 封面、图标与画册图片文件不再被引用时一并删除
+    pub async fn delete_server(
+        db: &DatabaseConnection,
+        storage: Option<&dyn StorageBackend>,
+        server_id: i32,
+    ) -> ApiResult<()> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let mut file_hashes: Vec<String> = match server.gallery_id {
+            Some(gallery_id) => GalleryImageEntity::find()
+                .filter(gallery_image::Column::GalleryId.eq(gallery_id))
+                .all(db.as_ref())
+                .await?
+                .into_iter()
+                .map(|image| image.image_hash_id)
+                .collect(),
+            None => Vec::new(),
+        };
+        file_hashes.extend(server.cover_hash_id.clone());
+        file_hashes.extend(server.icon_hash_id.clone());
+
+        let txn = db.begin().await?;
+        Server::delete_by_id(server_id).exec(&txn).await?;
+        if let Some(gallery_id) = server.gallery_id {
+            Gallery::delete_by_id(gallery_id).exec(&txn).await?;
+        }
+        txn.commit().await?;
+
+        for hash in &file_hashes {
+            FileGcService::release(db, storage, hash).await;
+        }
+        if let Ok(client) = crate::services::search::client::MeilisearchClient::instance() {
+            if let Err(e) = client
+                .delete_server_document(&server.tenant_id, server_id)
+                .await
+            {
+                tracing::warn!("⚠️  {}", e);
+            }
+        }
+        EventBus::publish(DomainEvent::server_updated(vec![server_id]));
+
         Ok(())
     }
 
+    /// 批量删除画册图片的影响范围，所有图片都必须属于该服务器
+    pub async fn gallery_deletion_impact(
+        db: &DatabaseConnection,
+        server_id: i32,
+        image_ids: &[i32],
+    ) -> ApiResult<BTreeMap<String, u64>> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+        let gallery_id = server.gallery_id.ok_or_else(|| {
+            crate::errors::ApiError::NotFound("该服务器没有画册".to_string())
+                .with_code(crate::errors::ErrorCode::GalleryNotFound)
+        })?;
+
+        let images = GalleryImageEntity::find()
+            .filter(gallery_image::Column::Id.is_in(image_ids.to_vec()))
+            .all(db.as_ref())
+            .await?;
+        if images.len() != image_ids.len() {
+            return Err(crate::errors::ApiError::NotFound("图片不存在".to_string())
+                .with_code(crate::errors::ErrorCode::ImageNotFound));
+        }
+        if images.iter().any(|image| image.gallery_id != gallery_id) {
+            return Err(crate::errors::ApiError::Forbidden(
+                "图片不属于该服务器".to_string(),
+            ));
+        }
+
+        Ok(BTreeMap::from([(
+            "gallery_images".to_string(),
+            images.len() as u64,
+        )]))
+    }
+
+    /// 接收服务器推送的状态数据，校验签名后写入 server_stats
+    pub async fn push_server_stats(
+        db: &DatabaseConnection,
+        signing: &crate::config::SigningConfig,
+        server_id: i32,
+        headers: &axum::http::HeaderMap,
+        body: &[u8],
+    ) -> ApiResult<()> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let secret = server.push_secret.as_deref().ok_or_else(|| {
+            crate::errors::ApiError::Forbidden("该服务器未启用数据推送".to_string())
+        })?;
+
+        SigningService::verify_headers(
+            server_id,
+            headers,
+            body,
+            secret,
+            signing.replay_window_secs,
+        )
+        .await?;
+
+        let mut stats: ServerStats = serde_json::from_slice(body)
+            .map_err(|e| crate::errors::ApiError::BadRequest(format!("状态数据格式无效: {e}")))?;
+        Self::check_stats(Some(&stats), None, Utc::now())
+            .map_err(crate::errors::ApiError::BadRequest)?;
+        stats.motd = motd::normalize(&stats.motd);
+        let stat_data = serde_json::to_value(&stats)
+            .map_err(|e| crate::errors::ApiError::Internal(format!("状态数据序列化失败: {e}")))?;
+
+        let now = Utc::now();
+        let new_stats = server_stats::ActiveModel {
+            timestamp: Set(now),
+            stat_data: Set(Some(stat_data)),
+            server_id: Set(server_id),
+            ..Default::default()
+        };
+        ServerStatsEntity::insert(new_stats)
+            .exec(db.as_ref())
+            .await?;
+
+        let observation = StatsObservation::new(server_id, now, &stats);
+        if let Err(e) = ServerTimelineService::record(db.as_ref(), vec![observation]).await {
+            tracing::warn!("⚠️  记录服务器 {} 的时间线失败: {}", server_id, e);
+        }
+        let observation = UptimeObservation {
+            server_id,
+            observed_at: now,
+            online: true,
+        };
+        if let Err(e) = ServerUptimeService::record(db.as_ref(), vec![observation]).await {
+            tracing::warn!("⚠️  记录服务器 {} 的在线状态失败: {}", server_id, e);
+        }
+
+        if !server.player_search_opt_out {
+            let sample = stats.sample.as_deref().unwrap_or_default();
+            PlayerIndexService::index(&server.tenant_id, server_id, sample).await;
+        }
+
+        EventBus::publish(DomainEvent::StatsRefreshed {
+            server_ids: vec![server_id],
+            refreshed_at: now,
+        });
+
+        Ok(())
+    }
+
+    /// 检查上报的状态数据，返回拒绝原因
+    ///
+    /// 采集时间不能晚于 `now`（允许少量时钟偏差），延迟与玩家数不能为负数。
+    pub fn check_stats(
+        stats: Option<&ServerStats>,
+        collected_at: Option<chrono::DateTime<Utc>>,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<(), String> {
+        if collected_at
+            .is_some_and(|at| at > now + chrono::Duration::seconds(Self::STATS_CLOCK_SKEW_SECS))
+        {
+            return Err("采集时间不能晚于当前时间".to_string());
+        }
+        let Some(stats) = stats else {
+            return Ok(());
+        };
+        if !stats.delay.is_finite() || stats.delay < 0.0 {
+            return Err("延迟必须为非负数".to_string());
+        }
+        if let Some((key, _)) = stats.players.iter().find(|(_, &count)| count < 0) {
+            return Err(format!("玩家数 {key} 不能为负数"));
+        }
+        Ok(())
+    }
+
+    /// 批量写入采集器上报的服务器状态
+    ///
+    /// 不存在的服务器与未通过 [`Self::check_stats`] 的条目会被拒绝，
+    /// 其余条目按批次通过 `insert_many` 写入。
+    pub async fn ingest_stats_batch(
+        db: &DatabaseConnection,
+        items: Vec<StatsBatchItem>,
+    ) -> ApiResult<StatsBatchResponse> {
+        if items.len() > Self::MAX_STATS_BATCH_SIZE {
+            return Err(crate::errors::ApiError::BadRequest(format!(
+                "单次最多上报 {} 条状态数据",
+                Self::MAX_STATS_BATCH_SIZE
+            )));
+        }
+
+        let mut server_ids: Vec<i32> = items.iter().map(|item| item.server_id).collect();
+        server_ids.sort_unstable();
+        server_ids.dedup();
+
+        let existing: HashSet<i32> = Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::Id.is_in(server_ids))
+            .into_tuple::<i32>()
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .collect();
+
+        let now = Utc::now();
+        let mut rejected = Vec::new();
+        let mut refreshed = Vec::new();
+        let mut rows = Vec::with_capacity(items.len());
+        let mut observations = Vec::new();
+        let mut uptime_observations = Vec::new();
+
+        for mut item in items {
+            if !existing.contains(&item.server_id) {
+                rejected.push(StatsBatchRejection {
+                    server_id: item.server_id,
+                    reason: "服务器不存在".to_string(),
+                });
+                continue;
+            }
+            if let Err(reason) = Self::check_stats(item.stats.as_ref(), item.collected_at, now) {
+                rejected.push(StatsBatchRejection {
+                    server_id: item.server_id,
+                    reason,
+                });
+                continue;
+            }
+
+            if let Some(stats) = item.stats.as_mut() {
+                stats.motd = motd::normalize(&stats.motd);
+            }
+            let stat_data = match item.stats.as_ref().map(serde_json::to_value).transpose() {
+                Ok(stat_data) => stat_data,
+                Err(e) => {
+                    rejected.push(StatsBatchRejection {
+                        server_id: item.server_id,
+                        reason: format!("状态数据序列化失败: {e}"),
+                    });
+                    continue;
+                }
+            };
+
+            let collected_at = item.collected_at.unwrap_or(now);
+            if let Some(stats) = &item.stats {
+                observations.push(StatsObservation::new(item.server_id, collected_at, stats));
+            }
+            uptime_observations.push(UptimeObservation {
+                server_id: item.server_id,
+                observed_at: collected_at,
+                online: item.stats.is_some(),
+            });
+            refreshed.push(item.server_id);
+            rows.push(server_stats::ActiveModel {
+                timestamp: Set(collected_at),
+                stat_data: Set(stat_data),
+                server_id: Set(item.server_id),
+                ..Default::default()
+            });
+        }
+
+        let accepted = rows.len();
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            let batch: Vec<_> = rows.by_ref().take(Self::STATS_INSERT_CHUNK).collect();
+            ServerStatsEntity::insert_many(batch)
+                .exec(db.as_ref())
+                .await?;
+        }
+        if let Err(e) = ServerTimelineService::record(db.as_ref(), observations).await {
+            tracing::warn!("⚠️  记录批量状态的时间线失败: {}", e);
+        }
+        match ServerUptimeService::record(db.as_ref(), uptime_observations).await {
+            Ok(went_offline) if !went_offline.is_empty() => {
+                let db = db.clone();
+                tokio::spawn(async move {
+                    ServerUptimeService::notify_offline(&db, went_offline).await;
+                });
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️  记录批量状态的在线状态失败: {}", e),
+        }
+
+        if !refreshed.is_empty() {
+            refreshed.sort_unstable();
+            refreshed.dedup();
+            EventBus::publish(DomainEvent::StatsRefreshed {
+                server_ids: refreshed,
+                refreshed_at: now,
+            });
+        }
+
+        Ok(StatsBatchResponse { accepted, rejected })
+    }
+
+    /// 重新生成服务器的数据推送密钥
+    pub async fn rotate_push_secret(db: &DatabaseConnection, server_id: i32) -> ApiResult<String> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(crate::errors::ApiError::server_not_found)?;
+
+        let secret = SigningService::generate_secret();
+        let mut server_active: server::ActiveModel = server.into();
+        server_active.push_secret = Set(Some(secret.clone()));
+        server_active.update(db.as_ref()).await?;
+
+        Ok(secret)
+    }
+
+    /// 服务器是否属于指定租户，未启用多租户时不做检查
+    pub async fn ensure_in_tenant(
+        db: &DatabaseConnection,
+        server_id: i32,
+        tenant_id: &str,
+    ) -> ApiResult<()> {
+        if !TenantService::is_enabled() {
+            return Ok(());
+        }
+
+        Server::find_by_id(server_id)
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .into_tuple::<i32>()
+            .one(db.as_ref())
+            .await?
+            .map(|_| ())
+            .ok_or_else(crate::errors::ApiError::server_not_found)
+    }
+
+    /// 过滤出属于指定租户且未停用的服务器 ID
+    pub async fn filter_active_ids(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_ids: &[i32],
+    ) -> ApiResult<Vec<i32>> {
+        Ok(Server::find()
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::Id.is_in(server_ids.iter().copied()))
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .order_by_asc(server::Column::Id)
+            .into_tuple::<i32>()
+            .all(db.as_ref())
+            .await?)
+    }
+
     pub async fn total_players(
         db: &DatabaseConnection,
+        tenant_id: &str,
     ) -> ApiResult<crate::schemas::servers::ServerTotalPlayers> {
         let server_statses = ServerStatsEntity::find()
             .select_only()
             .column(server_stats::Column::StatData)
+            .filter(
+                server_stats::Column::ServerId.in_subquery(
+                    sea_query::Query::select()
+                        .column(server::Column::Id)
+                        .from(Server)
+                        .and_where(server::Column::TenantId.eq(tenant_id))
+                        .to_owned(),
+                ),
+            )
             .all(db.as_ref())
             .await
             .map_err(|e| crate::errors::ApiError::Database(e.to_string()))?;
@@ -0,0 +1,146 @@
+use axum::http::HeaderMap;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::{distr::Alphanumeric, Rng};
+use sha2::Sha256;
+
+use crate::{
+    errors::{ApiError, ApiResult, ErrorCode},
+    services::redis::RedisService,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 签名请求头
+pub const SIGNATURE_HEADER: &str = "x-signature";
+/// 签名时间戳请求头（Unix 秒）
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+/// 签名值前缀
+const SIGNATURE_PREFIX: &str = "sha256=";
+/// 已使用签名的 Redis 键前缀
+const NONCE_KEY_PREFIX: &str = "signing:nonce";
+
+/// 签名服务
+///
+/// 签名内容为 `{timestamp}.{body}`，使用每个服务器独立的密钥做 HMAC-SHA256。
+/// 时间戳必须在重放窗口内，且同一签名只能使用一次。
+pub struct SigningService;
+
+impl SigningService {
+    /// 生成新的签名密钥
+    pub fn generate_secret() -> String {
+        rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(48)
+            .map(char::from)
+            .collect()
+    }
+
+    /// 计算签名，返回 `sha256=<hex>` 格式
+    pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        let mac = Self::build_mac(secret, timestamp, body);
+        format!(
+            "{}{}",
+            SIGNATURE_PREFIX,
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+
+    /// 校验签名（常量时间比较）并检查时间戳是否在重放窗口内
+    pub fn verify(
+        secret: &str,
+        timestamp: i64,
+        body: &[u8],
+        signature: &str,
+        replay_window_secs: u64,
+    ) -> ApiResult<()> {
+        let now = Utc::now().timestamp();
+        if now.abs_diff(timestamp) > replay_window_secs {
+            return Err(ApiError::Unauthorized("签名已过期".to_string())
+                .with_code(ErrorCode::SignatureExpired));
+        }
+
+        let expected = Self::decode_signature(signature)?;
+        Self::build_mac(secret, timestamp, body)
+            .verify_slice(&expected)
+            .map_err(|_| {
+                ApiError::Unauthorized("签名校验失败".to_string())
+                    .with_code(ErrorCode::SignatureInvalid)
+            })
+    }
+
+    /// 已使用签名的登记键
+    ///
+    /// 按服务器区分，签名取解码后的 MAC 重新编码，大小写不同的同一签名得到同一个键。
+    pub fn nonce_key(server_id: i32, signature: &str) -> ApiResult<String> {
+        let mac = Self::decode_signature(signature)?;
+        Ok(format!(
+            "{NONCE_KEY_PREFIX}:{server_id}:{}",
+            hex::encode(mac)
+        ))
+    }
+
+    /// 从请求头中读取签名与时间戳并校验，校验通过后登记签名，拒绝重放
+    pub async fn verify_headers(
+        server_id: i32,
+        headers: &HeaderMap,
+        body: &[u8],
+        secret: &str,
+        replay_window_secs: u64,
+    ) -> ApiResult<()> {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .ok_or_else(|| {
+                ApiError::Unauthorized("缺少签名".to_string())
+                    .with_code(ErrorCode::SignatureInvalid)
+            })?;
+        let timestamp = headers
+            .get(TIMESTAMP_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.parse::<i64>().ok())
+            .ok_or_else(|| {
+                ApiError::Unauthorized("缺少或无效的签名时间戳".to_string())
+                    .with_code(ErrorCode::SignatureInvalid)
+            })?;
+
+        Self::verify(secret, timestamp, body, signature, replay_window_secs)?;
+        Self::claim_nonce(&Self::nonce_key(server_id, signature)?, replay_window_secs).await
+    }
+
+    /// 登记已使用的签名，重放窗口内再次出现同一签名时拒绝
+    async fn claim_nonce(key: &str, replay_window_secs: u64) -> ApiResult<()> {
+        let redis = RedisService::instance()
+            .ok_or_else(|| ApiError::ServiceUnavailable("签名校验暂不可用".to_string()))?;
+        // 时间戳允许前后各偏差一个窗口，签名记录需要保留两个窗口
+        let fresh = redis
+            .set_nx_ex(key, "1", replay_window_secs.saturating_mul(2).max(1))
+            .await
+            .map_err(|e| ApiError::Internal(format!("登记签名失败: {e}")))?;
+        if !fresh {
+            return Err(ApiError::Unauthorized("签名已被使用".to_string())
+                .with_code(ErrorCode::SignatureReplayed));
+        }
+        Ok(())
+    }
+
+    /// 去掉 `sha256=` 前缀并解码为 MAC 字节
+    fn decode_signature(signature: &str) -> ApiResult<Vec<u8>> {
+        signature
+            .strip_prefix(SIGNATURE_PREFIX)
+            .and_then(|hex| hex::decode(hex).ok())
+            .ok_or_else(|| {
+                ApiError::Unauthorized("签名格式无效".to_string())
+                    .with_code(ErrorCode::SignatureInvalid)
+            })
+    }
+
+    fn build_mac(secret: &str, timestamp: i64, body: &[u8]) -> HmacSha256 {
+        // HMAC 接受任意长度的密钥，这里不会失败
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC 密钥长度不受限制");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        mac
+    }
+}
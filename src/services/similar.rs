@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::*;
+
+use crate::{
+    entities::{prelude::Server, server},
+    errors::{ApiError, ApiResult},
+    schemas::servers::{ServerStatus, ServerVisibility, SimilarServer, SimilarServersResponse},
+    services::{database::DatabaseConnection, embeddings::EmbeddingService, server::ServerService},
+};
+
+/// 相似服务器推荐服务
+///
+/// 开启向量推荐且服务器已有描述向量时按余弦相似度排序；
+/// 否则（或向量服务不可用时）退回按标签 Jaccard 重合度排序。
+pub struct SimilarServerService;
+
+impl SimilarServerService {
+    /// 查找与指定服务器相似的服务器，只在同一租户内查找，不包含隐藏与停用的服务器
+    pub async fn find_similar(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+        limit: usize,
+        use_embeddings: bool,
+    ) -> ApiResult<SimilarServersResponse> {
+        let target = Self::visible(tenant_id)
+            .filter(server::Column::Id.eq(server_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        if use_embeddings {
+            if let Some(service) = EmbeddingService::instance() {
+                // 多取一些，过滤隐藏与停用的服务器后仍能凑够数量
+                match service.nearest(db, server_id, limit * 2 + 10).await {
+                    Ok(Some(scored)) => {
+                        let data = Self::load_scored(db, tenant_id, scored, limit).await?;
+                        if !data.is_empty() {
+                            return Ok(SimilarServersResponse {
+                                method: "embedding".to_string(),
+                                data,
+                            });
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("⚠️  按描述向量查找相似服务器失败: {}", e),
+                }
+            }
+        }
+
+        let data = Self::by_tags(db, &target, limit).await?;
+        Ok(SimilarServersResponse {
+            method: "tags".to_string(),
+            data,
+        })
+    }
+
+    async fn by_tags(
+        db: &DatabaseConnection,
+        target: &server::Model,
+        limit: usize,
+    ) -> ApiResult<Vec<SimilarServer>> {
+        let target_tags: HashSet<String> = ServerService::parse_server_tags(&target.tags)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        if target_tags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let candidates = Self::visible(&target.tenant_id)
+            .filter(server::Column::Id.ne(target.id))
+            .all(db.as_ref())
+            .await?;
+
+        let mut scored: Vec<(f64, server::Model)> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                let tags: HashSet<String> = ServerService::parse_server_tags(&candidate.tags)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                let shared = tags.intersection(&target_tags).count();
+                if shared == 0 {
+                    return None;
+                }
+                let union = tags.union(&target_tags).count();
+                Some((shared as f64 / union as f64, candidate))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+        scored.truncate(limit);
+
+        Ok(scored
+            .into_iter()
+            .map(|(score, server)| Self::to_similar(server, score))
+            .collect())
+    }
+
+    async fn load_scored(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        scored: Vec<(i32, f64)>,
+        limit: usize,
+    ) -> ApiResult<Vec<SimilarServer>> {
+        let ids: Vec<i32> = scored.iter().map(|(id, _)| *id).collect();
+        let mut servers: HashMap<i32, server::Model> = Self::visible(tenant_id)
+            .filter(server::Column::Id.is_in(ids))
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .map(|server| (server.id, server))
+            .collect();
+
+        Ok(scored
+            .into_iter()
+            .filter_map(|(id, score)| {
+                servers
+                    .remove(&id)
+                    .map(|server| Self::to_similar(server, score))
+            })
+            .take(limit)
+            .collect())
+    }
+
+    fn visible(tenant_id: &str) -> Select<Server> {
+        Server::find()
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::DelistedAt.is_null())
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+    }
+
+    fn to_similar(server: server::Model, score: f64) -> SimilarServer {
+        SimilarServer {
+            id: server.id,
+            tags: ServerService::parse_server_tags(&server.tags),
+            name: server.name,
+            slug: server.slug,
+            score: (score * 100.0).round() / 100.0,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use sea_orm::*;
+
+use crate::{
+    config::{SitemapConfig, TenantDefinition},
+    entities::{prelude::Server, server},
+    errors::ApiResult,
+    schemas::servers::{ServerStatus, ServerVisibility},
+    services::{
+        database::DatabaseConnection,
+        tenant::{TenantMode, TenantService},
+    },
+};
+
+/// 站点地图服务
+///
+/// 只收录可见性不是 `hidden` 且未停用的服务器，与列表、搜索的可见范围一致。
+pub struct SitemapService;
+
+impl SitemapService {
+    /// 生成租户的站点地图 XML
+    pub async fn render(
+        db: &DatabaseConnection,
+        config: &SitemapConfig,
+        tenant: &TenantDefinition,
+    ) -> ApiResult<String> {
+        let servers = Server::find()
+            .filter(server::Column::TenantId.eq(tenant.id.as_str()))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::DelistedAt.is_null())
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .order_by_asc(server::Column::Id)
+            .all(db.as_ref())
+            .await?;
+
+        let base_url = Self::base_url(config, tenant);
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for server in &servers {
+            let path = server.slug.clone().unwrap_or_else(|| server.id.to_string());
+            xml.push_str(&format!(
+                "  <url><loc>{}</loc></url>\n",
+                Self::escape(&format!("{base_url}/servers/{path}"))
+            ));
+        }
+        xml.push_str("</urlset>\n");
+        Ok(xml)
+    }
+
+    /// host 模式下使用租户的第一个域名，path 模式下非默认租户带上 `/t/{tenant}` 前缀
+    pub(crate) fn base_url(config: &SitemapConfig, tenant: &TenantDefinition) -> String {
+        match TenantService::mode() {
+            TenantMode::Host => match tenant.hosts.first() {
+                Some(host) => format!("https://{host}"),
+                None => config.base_url.clone(),
+            },
+            TenantMode::Path if tenant.id != TenantService::default_tenant().id => {
+                format!(
+                    "{}{}{}",
+                    config.base_url,
+                    TenantService::PATH_PREFIX,
+                    tenant.id
+                )
+            }
+            _ => config.base_url.clone(),
+        }
+    }
+
+    pub(crate) fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+}
@@ -0,0 +1,349 @@
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::SpamGuardConfig,
+    entities::{
+        prelude::{SpamHolds, Users},
+        spam_holds, users,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{SpamContentType, SpamHoldInfo, SpamHoldStatus},
+        users::NotificationKind,
+    },
+    services::{
+        database::DatabaseConnection,
+        metrics::MetricsService,
+        notification::{Notification, NotificationService},
+        redis::RedisService,
+    },
+};
+
+/// 窗口期内发布过相同内容
+pub const REASON_DUPLICATE_CONTENT: &str = "duplicate_content";
+/// 链接数量过多
+pub const REASON_LINK_DENSITY: &str = "link_density";
+/// 新注册账户
+pub const REASON_NEW_ACCOUNT: &str = "new_account";
+/// 发布过于频繁
+pub const REASON_RATE_EXCEEDED: &str = "rate_exceeded";
+
+/// 单条内容中链接数达到该值即视为链接过多
+const MAX_LINKS: usize = 3;
+/// 链接占词数的比例上限
+const MAX_LINK_RATIO: f64 = 0.2;
+/// 扣留记录中保存的内容摘要长度（字符）
+const EXCERPT_CHARS: usize = 500;
+
+/// 垃圾内容检测结果
+#[derive(Debug, Clone)]
+pub struct SpamVerdict {
+    /// 评分（0~100）
+    pub score: i32,
+    /// 命中的规则
+    pub reasons: Vec<&'static str>,
+    /// 是否需要扣留待审核
+    pub held: bool,
+}
+
+/// 垃圾内容检测服务
+///
+/// 对评价、工单、公告等用户发布的内容按重复内容、链接密度、账户年龄与发布频率打分，
+/// 达到阈值的内容调用方应暂不公开，并通过 [`SpamGuardService::hold`] 进入管理后台的审核队列。
+pub struct SpamGuardService;
+
+impl SpamGuardService {
+    /// 发布频率计数键前缀
+    const RATE_PREFIX: &'static str = "spam:rate";
+    /// 内容指纹键前缀
+    const DUPLICATE_PREFIX: &'static str = "spam:dup";
+
+    /// 为即将发布的内容打分
+    pub async fn evaluate(
+        config: &SpamGuardConfig,
+        content_type: SpamContentType,
+        author: &users::Model,
+        text: &str,
+    ) -> SpamVerdict {
+        let mut score = 0;
+        let mut reasons = Vec::new();
+
+        if Self::is_duplicate(config, author.id, text).await {
+            score += 40;
+            reasons.push(REASON_DUPLICATE_CONTENT);
+        }
+        if Self::is_link_heavy(text) {
+            score += 30;
+            reasons.push(REASON_LINK_DENSITY);
+        }
+        if author.created_at > Utc::now() - Duration::hours(config.new_account_hours) {
+            score += 20;
+            reasons.push(REASON_NEW_ACCOUNT);
+        }
+        if Self::is_rate_exceeded(config, content_type, author.id).await {
+            score += 30;
+            reasons.push(REASON_RATE_EXCEEDED);
+        }
+
+        let score = score.min(100);
+        let held = score >= config.hold_threshold;
+        MetricsService::observe(
+            "spam_score",
+            "用户发布内容的垃圾内容评分",
+            &[("content_type", content_type.as_str())],
+            score as f64,
+        );
+        if held {
+            MetricsService::inc_counter(
+                "spam_held_total",
+                "被自动扣留待审核的内容数",
+                &[("content_type", content_type.as_str())],
+                1.0,
+            );
+        }
+
+        SpamVerdict {
+            score,
+            reasons,
+            held,
+        }
+    }
+
+    /// 将被扣留的内容加入审核队列
+    pub async fn hold(
+        db: &DatabaseConnection,
+        content_type: SpamContentType,
+        content_id: Option<i32>,
+        author_id: i32,
+        verdict: &SpamVerdict,
+        text: &str,
+    ) -> ApiResult<spam_holds::Model> {
+        tracing::info!(
+            "内容被扣留待审核: type={}, author_id={}, score={}, reasons={:?}",
+            content_type.as_str(),
+            author_id,
+            verdict.score,
+            verdict.reasons
+        );
+
+        let hold = spam_holds::ActiveModel {
+            content_type: Set(content_type.as_str().to_string()),
+            content_id: Set(content_id),
+            author_id: Set(author_id),
+            score: Set(verdict.score),
+            reasons: Set(json!(verdict.reasons)),
+            excerpt: Set(text.chars().take(EXCERPT_CHARS).collect()),
+            status: Set(SpamHoldStatus::Pending.as_str().to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db.as_ref())
+        .await?;
+
+        Ok(hold)
+    }
+
+    /// 分页获取租户内的扣留列表，按内容作者所属租户区分
+    pub async fn list_holds(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        status: SpamHoldStatus,
+        content_type: Option<SpamContentType>,
+        page: u64,
+        page_size: u64,
+    ) -> ApiResult<(Vec<SpamHoldInfo>, u64)> {
+        let mut query = SpamHolds::find().filter(spam_holds::Column::Status.eq(status.as_str()));
+        if let Some(content_type) = content_type {
+            query = query.filter(spam_holds::Column::ContentType.eq(content_type.as_str()));
+        }
+
+        let paginator = query
+            .find_also_related(Users)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .order_by_desc(spam_holds::Column::Score)
+            .order_by_desc(spam_holds::Column::Id)
+            .paginate(db.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(page.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(|(hold, user)| Self::to_info(hold, user))
+            .collect();
+
+        Ok((data, total))
+    }
+
+    /// 审核扣留内容，作者不属于该租户时视为记录不存在
+    pub async fn review_hold(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        hold_id: i32,
+        reviewer_id: i32,
+        approved: bool,
+    ) -> ApiResult<SpamHoldInfo> {
+        let hold = SpamHolds::find_by_id(hold_id)
+            .inner_join(Users)
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::NotFound("扣留记录不存在".to_string()))?;
+        if hold.status != SpamHoldStatus::Pending.as_str() {
+            return Err(ApiError::Conflict("该内容已审核".to_string()));
+        }
+
+        let status = if approved {
+            SpamHoldStatus::Approved
+        } else {
+            SpamHoldStatus::Rejected
+        };
+        let mut active: spam_holds::ActiveModel = hold.into();
+        active.status = Set(status.as_str().to_string());
+        active.reviewed_by_id = Set(Some(reviewer_id));
+        active.reviewed_at = Set(Some(Utc::now()));
+        let hold = active.update(db.as_ref()).await?;
+
+        MetricsService::inc_counter(
+            "spam_hold_reviews_total",
+            "已审核的扣留内容数",
+            &[
+                ("content_type", hold.content_type.as_str()),
+                ("outcome", status.as_str()),
+            ],
+            1.0,
+        );
+
+        let (title, body) = if approved {
+            (
+                "您提交的内容已通过审核",
+                "此前被暂时扣留的内容已审核通过并正常展示。",
+            )
+        } else {
+            (
+                "您提交的内容未通过审核",
+                "此前被暂时扣留的内容经审核后未予展示。",
+            )
+        };
+        NotificationService::notify(
+            db,
+            hold.author_id,
+            Notification {
+                kind: NotificationKind::SpamHoldReviewed,
+                title: title.to_string(),
+                body: body.to_string(),
+            },
+        )
+        .await;
+
+        let user = Users::find_by_id(hold.author_id).one(db.as_ref()).await?;
+        Ok(Self::to_info(hold, user))
+    }
+
+    async fn is_duplicate(config: &SpamGuardConfig, author_id: i32, text: &str) -> bool {
+        let Some(redis) = RedisService::instance() else {
+            return false;
+        };
+
+        // 忽略大小写与空白差异
+        let normalized: String = text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if normalized.is_empty() {
+            return false;
+        }
+        let fingerprint = hex::encode(Sha256::digest(normalized.as_bytes()));
+
+        // 全站维度：不同账户批量发布相同内容同样视为重复
+        let key = format!("{}:{}", Self::DUPLICATE_PREFIX, fingerprint);
+        match redis
+            .set_nx_ex(&key, &author_id.to_string(), config.duplicate_window_secs)
+            .await
+        {
+            Ok(is_new) => !is_new,
+            Err(e) => {
+                tracing::warn!("⚠️  内容指纹记录失败: {}", e);
+                false
+            }
+        }
+    }
+
+    fn is_link_heavy(text: &str) -> bool {
+        let words = text.split_whitespace().count();
+        let links = text
+            .split_whitespace()
+            .filter(|word| {
+                let word = word.to_lowercase();
+                word.contains("http://") || word.contains("https://") || word.contains("www.")
+            })
+            .count();
+
+        links >= MAX_LINKS || (words > 0 && links as f64 / words as f64 > MAX_LINK_RATIO)
+    }
+
+    async fn is_rate_exceeded(
+        config: &SpamGuardConfig,
+        content_type: SpamContentType,
+        author_id: i32,
+    ) -> bool {
+        let Some(redis) = RedisService::instance() else {
+            return false;
+        };
+
+        let key = format!(
+            "{}:{}:{}",
+            Self::RATE_PREFIX,
+            content_type.as_str(),
+            author_id
+        );
+        match redis.incr_ex(&key, config.rate_window_secs).await {
+            Ok(count) => count > config.rate_limit,
+            Err(e) => {
+                tracing::warn!("⚠️  发布频率计数失败: {}", e);
+                false
+            }
+        }
+    }
+
+    fn to_info(hold: spam_holds::Model, user: Option<users::Model>) -> SpamHoldInfo {
+        let content_type = match hold.content_type.as_str() {
+            "review" => SpamContentType::Review,
+            "announcement" => SpamContentType::Announcement,
+            _ => SpamContentType::Ticket,
+        };
+        let status = match hold.status.as_str() {
+            "approved" => SpamHoldStatus::Approved,
+            "rejected" => SpamHoldStatus::Rejected,
+            _ => SpamHoldStatus::Pending,
+        };
+        let reasons = hold
+            .reasons
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        SpamHoldInfo {
+            id: hold.id,
+            content_type,
+            content_id: hold.content_id,
+            author_id: hold.author_id,
+            author_username: user.map(|user| user.username).unwrap_or_default(),
+            score: hold.score,
+            reasons,
+            excerpt: hold.excerpt,
+            status,
+            created_at: hold.created_at,
+            reviewed_by_id: hold.reviewed_by_id,
+            reviewed_at: hold.reviewed_at,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+use serde_json::Value;
+
+use crate::{
+    entities::{
+        prelude::{Server, ServerStats as ServerStatsEntity},
+        server, server_stats,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::servers::{
+        ServerStatus, ServerVisibility, StatsHistoryPoint, StatsHistoryRange, StatsHistoryResponse,
+    },
+    services::database::DatabaseConnection,
+};
+
+/// 不指定聚合区间时最多返回的原始采样数，超出时按该数量自动聚合
+const MAX_RAW_SAMPLES: usize = 2000;
+
+/// 服务器在线人数历史
+///
+/// 从 server_stats 读取时间范围内的采样，只在数据库中提取 players 字段，避免读取图标等
+/// 大字段。离线采样（状态为空）计入在线率，不计入人数；聚合时没有任何采样的区间不返回。
+pub struct StatsHistoryService;
+
+impl StatsHistoryService {
+    /// 获取服务器的历史状态，只返回同一租户内未隐藏、未停用的服务器
+    pub async fn history(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+        range: StatsHistoryRange,
+        buckets: Option<u32>,
+    ) -> ApiResult<StatsHistoryResponse> {
+        Server::find_by_id(server_id)
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .into_tuple::<i32>()
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        let since = Utc::now() - range.duration();
+        let rows: Vec<(DateTime<Utc>, Option<Value>)> = ServerStatsEntity::find()
+            .select_only()
+            .column(server_stats::Column::Timestamp)
+            .column_as(
+                sea_query::Expr::cust("JSON_EXTRACT(stat_data, '$.players')"),
+                "players",
+            )
+            .filter(server_stats::Column::ServerId.eq(server_id))
+            .filter(server_stats::Column::Timestamp.gte(since))
+            .order_by_asc(server_stats::Column::Timestamp)
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+        let samples: Vec<Sample> = rows
+            .into_iter()
+            .map(|(timestamp, players)| Sample::new(timestamp, players.as_ref()))
+            .collect();
+
+        let buckets = match buckets {
+            Some(buckets) => Some(buckets),
+            None if samples.len() > MAX_RAW_SAMPLES => Some(MAX_RAW_SAMPLES as u32),
+            None => None,
+        };
+        let Some(buckets) = buckets else {
+            return Ok(StatsHistoryResponse {
+                range,
+                bucket_seconds: None,
+                data: samples.into_iter().map(Sample::into_point).collect(),
+            });
+        };
+
+        let bucket_seconds =
+            (range.duration().num_seconds() + i64::from(buckets) - 1) / i64::from(buckets);
+        Ok(StatsHistoryResponse {
+            range,
+            bucket_seconds: Some(bucket_seconds),
+            data: Self::aggregate(&samples, since, bucket_seconds, buckets as usize),
+        })
+    }
+
+    fn aggregate(
+        samples: &[Sample],
+        since: DateTime<Utc>,
+        bucket_seconds: i64,
+        buckets: usize,
+    ) -> Vec<StatsHistoryPoint> {
+        let mut totals: Vec<Bucket> = (0..buckets).map(|_| Bucket::default()).collect();
+        for sample in samples {
+            let index = ((sample.timestamp - since).num_seconds().max(0) / bucket_seconds) as usize;
+            totals[index.min(buckets - 1)].add(sample);
+        }
+
+        totals
+            .into_iter()
+            .enumerate()
+            .filter(|(_, bucket)| bucket.samples > 0)
+            .map(|(index, bucket)| StatsHistoryPoint {
+                timestamp: since + Duration::seconds(bucket_seconds * index as i64),
+                online: (bucket.online_samples > 0).then(|| {
+                    (bucket.online_sum as f64 / bucket.online_samples as f64).round() as i64
+                }),
+                peak: bucket.peak,
+                max: bucket.max,
+                uptime: bucket.online_samples as f64 / bucket.samples as f64,
+            })
+            .collect()
+    }
+}
+
+/// 一条采样，离线时人数为空
+struct Sample {
+    timestamp: DateTime<Utc>,
+    online: Option<i64>,
+    max: Option<i64>,
+}
+
+impl Sample {
+    fn new(timestamp: DateTime<Utc>, players: Option<&Value>) -> Self {
+        let field = |name: &str| players.and_then(|players| players.get(name)?.as_i64());
+        let online = field("online");
+        Self {
+            timestamp,
+            online,
+            max: online.and(field("max")),
+        }
+    }
+
+    fn into_point(self) -> StatsHistoryPoint {
+        StatsHistoryPoint {
+            timestamp: self.timestamp,
+            online: self.online,
+            peak: self.online,
+            max: self.max,
+            uptime: if self.online.is_some() { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+#[derive(Default)]
+struct Bucket {
+    samples: u64,
+    online_samples: u64,
+    online_sum: i64,
+    peak: Option<i64>,
+    max: Option<i64>,
+}
+
+impl Bucket {
+    fn add(&mut self, sample: &Sample) {
+        self.samples += 1;
+        if let Some(online) = sample.online {
+            self.online_samples += 1;
+            self.online_sum += online;
+            self.peak = self.peak.max(Some(online));
+        }
+        self.max = self.max.max(sample.max);
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use sea_orm::*;
+use serde_json::json;
+
+use crate::{
+    config::{Config, StatusConfig},
+    entities::{prelude::StatusIncidents, status_incidents},
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{IncidentInfo, UpdateIncidentRequest},
+        meta::{
+            Capability, ComponentState, ComponentStatus, ErrorRateWindow, HealthSample,
+            IncidentSeverity, StatusIncident, StatusPageResponse,
+        },
+    },
+    services::{
+        database::DatabaseConnection, redis::RedisService, search::client::MeilisearchClient,
+    },
+};
+
+/// 参与深度健康检查的依赖组件
+const COMPONENTS: &[&str] = &["database", "redis", "search"];
+/// 健康采样在 Redis 中的键前缀
+const HISTORY_PREFIX: &str = "status:health";
+/// 状态页返回的采样条数，可用比例仍按全部已保存采样计算
+const HISTORY_IN_RESPONSE: usize = 90;
+/// 检查耗时超过该值视为性能下降
+const DEGRADED_LATENCY_MS: u64 = 1000;
+/// 单个依赖检查的超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// 错误率统计窗口
+const ERROR_RATE_WINDOWS: &[(&str, u64)] = &[("5m", 300), ("1h", 3600)];
+/// 最近 5 分钟错误率超过该值时整体状态视为性能下降
+const DEGRADED_ERROR_RATE: f64 = 0.05;
+/// 状态页数据缓存时长
+const PAGE_TTL: Duration = Duration::from_secs(10);
+
+/// 按分钟聚合的请求计数：(分钟时间戳, 请求数, 5xx 数)
+static REQUEST_BUCKETS: Lazy<Mutex<VecDeque<(i64, u64, u64)>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+/// 本实例最近一次采样结果，Redis 不可用时仍能给出当前状态
+static LAST_SAMPLES: Lazy<Mutex<HashMap<&'static str, HealthSample>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static PAGE_CACHE: Lazy<Mutex<Option<(Instant, StatusPageResponse)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// 状态页服务
+///
+/// 错误率由请求日志中间件按分钟计入本实例内存；依赖健康由后台定期深度检查，
+/// 结果写入 Redis 定长列表供各实例共享；故障信息由管理员维护。
+pub struct StatusService;
+
+impl StatusService {
+    /// 记录一次请求的响应状态码
+    pub fn record_request(status: u16) {
+        let minute = Utc::now().timestamp() / 60;
+        let oldest = minute - Self::max_window_minutes();
+        let mut buckets = REQUEST_BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+
+        match buckets.back_mut() {
+            Some(bucket) if bucket.0 == minute => {
+                bucket.1 += 1;
+                bucket.2 += u64::from(status >= 500);
+            }
+            _ => buckets.push_back((minute, 1, u64::from(status >= 500))),
+        }
+        while buckets.front().is_some_and(|bucket| bucket.0 < oldest) {
+            buckets.pop_front();
+        }
+    }
+
+    /// 定期对依赖做深度健康检查并写入采样记录
+    pub async fn run_sample_loop(db: DatabaseConnection, config: StatusConfig, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let samples = Self::deep_check(&db).await;
+
+            let redis = RedisService::instance();
+            for (component, sample) in samples {
+                if !sample.healthy {
+                    tracing::warn!("⚠️  依赖健康检查失败: {}", component);
+                }
+                if let Some(redis) = &redis {
+                    let value = match serde_json::to_string(&sample) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+                    let key = format!("{HISTORY_PREFIX}:{component}");
+                    if let Err(e) = redis.lpush_trim(&key, &value, config.history_size).await {
+                        tracing::warn!("⚠️  保存 {} 健康采样失败: {}", component, e);
+                    }
+                }
+                LAST_SAMPLES
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(component, sample);
+            }
+        }
+    }
+
+    /// 依次检查数据库、Redis 与搜索引擎
+    async fn deep_check(db: &DatabaseConnection) -> Vec<(&'static str, HealthSample)> {
+        let mut samples = Vec::with_capacity(COMPONENTS.len());
+        for component in COMPONENTS {
+            let start = Instant::now();
+            let healthy = match *component {
+                "database" => {
+                    matches!(
+                        tokio::time::timeout(CHECK_TIMEOUT, db.ping()).await,
+                        Ok(Ok(()))
+                    )
+                }
+                "redis" => match RedisService::instance() {
+                    Some(redis) => matches!(
+                        tokio::time::timeout(CHECK_TIMEOUT, redis.ping()).await,
+                        Ok(Ok(()))
+                    ),
+                    None => false,
+                },
+                _ => match MeilisearchClient::instance() {
+                    Ok(client) => matches!(
+                        tokio::time::timeout(CHECK_TIMEOUT, client.health_check()).await,
+                        Ok(Ok(()))
+                    ),
+                    Err(_) => false,
+                },
+            };
+            samples.push((
+                *component,
+                HealthSample {
+                    checked_at: Utc::now(),
+                    healthy,
+                    latency_ms: start.elapsed().as_millis() as u64,
+                },
+            ));
+        }
+        samples
+    }
+
+    /// 汇总状态页数据
+    pub async fn status_page(
+        db: &DatabaseConnection,
+        config: &Config,
+    ) -> ApiResult<StatusPageResponse> {
+        if let Some((built_at, page)) = PAGE_CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            if built_at.elapsed() < PAGE_TTL {
+                return Ok(page.clone());
+            }
+        }
+
+        let error_rates = Self::error_rates();
+        let components = Self::components().await;
+        let incidents: Vec<StatusIncident> = StatusIncidents::find()
+            .filter(status_incidents::Column::Active.eq(true))
+            .order_by_desc(status_incidents::Column::StartedAt)
+            .all(db.as_ref())
+            .await?
+            .into_iter()
+            .map(|incident| StatusIncident {
+                severity: IncidentSeverity::parse(&incident.severity),
+                components: Self::parse_components(&incident.components),
+                key: incident.key,
+                title: incident.title,
+                message: incident.message,
+                started_at: incident.started_at,
+                updated_at: incident.updated_at,
+            })
+            .collect();
+
+        let mut status = ComponentState::Operational;
+        let recent_error_rate = error_rates.first().map_or(0.0, |rate| rate.error_rate);
+        if recent_error_rate > DEGRADED_ERROR_RATE
+            || !incidents.is_empty()
+            || components
+                .iter()
+                .any(|c| c.status == ComponentState::Degraded)
+        {
+            status = ComponentState::Degraded;
+        }
+        if components
+            .iter()
+            .any(|c| c.status == ComponentState::Outage)
+            || incidents
+                .iter()
+                .any(|incident| incident.severity == IncidentSeverity::Critical)
+        {
+            status = ComponentState::Outage;
+        }
+
+        let page = StatusPageResponse {
+            status,
+            generated_at: Utc::now(),
+            error_rates,
+            components,
+            capabilities: vec![
+                Capability {
+                    name: "image_uploads".to_string(),
+                    enabled: config.storage.is_some(),
+                },
+                Capability {
+                    name: "email".to_string(),
+                    enabled: config.email.is_some(),
+                },
+            ],
+            incidents,
+        };
+        *PAGE_CACHE.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((Instant::now(), page.clone()));
+        Ok(page)
+    }
+
+    fn error_rates() -> Vec<ErrorRateWindow> {
+        let now_minute = Utc::now().timestamp() / 60;
+        let buckets = REQUEST_BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+
+        ERROR_RATE_WINDOWS
+            .iter()
+            .map(|(window, secs)| {
+                let oldest = now_minute - (*secs as i64 / 60);
+                let (total, errors) = buckets
+                    .iter()
+                    .filter(|bucket| bucket.0 > oldest)
+                    .fold((0, 0), |(total, errors), bucket| {
+                        (total + bucket.1, errors + bucket.2)
+                    });
+                ErrorRateWindow {
+                    window: (*window).to_string(),
+                    window_secs: *secs,
+                    total_requests: total,
+                    error_requests: errors,
+                    error_rate: if total == 0 {
+                        0.0
+                    } else {
+                        errors as f64 / total as f64
+                    },
+                }
+            })
+            .collect()
+    }
+
+    async fn components() -> Vec<ComponentStatus> {
+        let redis = RedisService::instance();
+        let last_samples = LAST_SAMPLES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        let mut components = Vec::with_capacity(COMPONENTS.len());
+        for component in COMPONENTS {
+            let mut samples: Vec<HealthSample> = Vec::new();
+            if let Some(redis) = &redis {
+                match redis
+                    .lrange(&format!("{HISTORY_PREFIX}:{component}"), 0, -1)
+                    .await
+                {
+                    Ok(values) => {
+                        samples = values
+                            .iter()
+                            .filter_map(|value| serde_json::from_str(value).ok())
+                            .collect();
+                    }
+                    Err(e) => tracing::warn!("⚠️  读取 {} 健康采样失败: {}", component, e),
+                }
+            }
+
+            let latest = last_samples
+                .get(component)
+                .filter(|sample| {
+                    samples
+                        .first()
+                        .is_none_or(|first| sample.checked_at > first.checked_at)
+                })
+                .or(samples.first())
+                .cloned();
+            let status = match &latest {
+                None => ComponentState::Unknown,
+                Some(sample) if !sample.healthy => ComponentState::Outage,
+                Some(sample) if sample.latency_ms > DEGRADED_LATENCY_MS => ComponentState::Degraded,
+                Some(_) => ComponentState::Operational,
+            };
+            let uptime_ratio = (!samples.is_empty()).then(|| {
+                let healthy = samples.iter().filter(|sample| sample.healthy).count();
+                (healthy as f64 / samples.len() as f64 * 1000.0).round() / 1000.0
+            });
+            samples.truncate(HISTORY_IN_RESPONSE);
+
+            components.push(ComponentStatus {
+                name: (*component).to_string(),
+                status,
+                latency_ms: latest.as_ref().map(|sample| sample.latency_ms),
+                uptime_ratio,
+                last_checked_at: latest.as_ref().map(|sample| sample.checked_at),
+                history: samples,
+            });
+        }
+        components
+    }
+
+    /// 列出全部故障，进行中的在前
+    pub async fn list_incidents(db: &DatabaseConnection) -> ApiResult<Vec<IncidentInfo>> {
+        let incidents = StatusIncidents::find()
+            .order_by_desc(status_incidents::Column::Active)
+            .order_by_desc(status_incidents::Column::StartedAt)
+            .all(db.as_ref())
+            .await?;
+        Ok(incidents.into_iter().map(Self::to_info).collect())
+    }
+
+    /// 创建或更新故障，进行中的故障改为非进行中时记录恢复时间
+    pub async fn upsert_incident(
+        db: &DatabaseConnection,
+        key: &str,
+        request: UpdateIncidentRequest,
+        operator_id: i32,
+    ) -> ApiResult<IncidentInfo> {
+        if key.is_empty()
+            || key.len() > 64
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(ApiError::BadRequest(
+                "故障标识只能包含小写字母、数字和短横线，长度 1~64".to_string(),
+            ));
+        }
+        let title = request.title.trim().to_string();
+        if title.is_empty() || title.chars().count() > 100 {
+            return Err(ApiError::BadRequest("标题长度需在 1~100 之间".to_string()));
+        }
+        if let Some(unknown) = request
+            .components
+            .iter()
+            .find(|component| !COMPONENTS.contains(&component.as_str()))
+        {
+            return Err(ApiError::BadRequest(format!("未知的组件: {unknown}")));
+        }
+
+        let existing = StatusIncidents::find()
+            .filter(status_incidents::Column::Key.eq(key))
+            .one(db.as_ref())
+            .await?;
+
+        let now = Utc::now();
+        let was_active = existing.as_ref().is_some_and(|incident| incident.active);
+        let mut active = match existing {
+            Some(incident) => incident.into_active_model(),
+            None => status_incidents::ActiveModel {
+                key: Set(key.to_string()),
+                started_at: Set(now),
+                ..Default::default()
+            },
+        };
+        if request.active && !was_active {
+            // 重新开启的故障视为新的一次
+            active.started_at = Set(now);
+            active.resolved_at = Set(None);
+        } else if !request.active && was_active {
+            active.resolved_at = Set(Some(now));
+        }
+        active.title = Set(title);
+        active.message = Set(request.message.filter(|message| !message.trim().is_empty()));
+        active.severity = Set(request.severity.as_str().to_string());
+        active.components = Set(json!(request.components));
+        active.active = Set(request.active);
+        active.updated_by_id = Set(Some(operator_id));
+        active.updated_at = Set(now);
+        let incident = active.save(db.as_ref()).await?.try_into_model()?;
+
+        Self::invalidate();
+        tracing::info!(
+            "状态页故障已更新: key={}, severity={}, active={}, operator={}",
+            incident.key,
+            incident.severity,
+            incident.active,
+            operator_id
+        );
+
+        Ok(Self::to_info(incident))
+    }
+
+    /// 删除故障记录
+    pub async fn delete_incident(db: &DatabaseConnection, key: &str) -> ApiResult<()> {
+        let result = StatusIncidents::delete_many()
+            .filter(status_incidents::Column::Key.eq(key))
+            .exec(db.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("故障不存在".to_string()));
+        }
+
+        Self::invalidate();
+        Ok(())
+    }
+
+    fn invalidate() {
+        *PAGE_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn max_window_minutes() -> i64 {
+        ERROR_RATE_WINDOWS
+            .iter()
+            .map(|(_, secs)| *secs as i64 / 60)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn parse_components(components: &JsonValue) -> Vec<String> {
+        serde_json::from_value(components.clone()).unwrap_or_default()
+    }
+
+    fn to_info(incident: status_incidents::Model) -> IncidentInfo {
+        IncidentInfo {
+            severity: IncidentSeverity::parse(&incident.severity),
+            components: Self::parse_components(&incident.components),
+            key: incident.key,
+            title: incident.title,
+            message: incident.message,
+            active: incident.active,
+            started_at: incident.started_at,
+            resolved_at: incident.resolved_at,
+            updated_by_id: incident.updated_by_id,
+            updated_at: incident.updated_at,
+        }
+    }
+}
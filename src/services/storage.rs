@@ -0,0 +1,191 @@
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    config::{LocalStorageConfig, S3Config, StorageConfig},
+    errors::{ApiError, ApiResult},
+    services::file_upload::FileUploadService,
+};
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = ApiResult<T>> + Send + 'a>>;
+
+/// 文件存储后端
+///
+/// 上传的文件按对象键（如 `uploads/<id>.webp`）写入存储，数据库中保存的是
+/// [`file_path`](StorageBackend::file_path) 返回的地址，由地址可以解析回对象键。
+pub trait StorageBackend: Send + Sync {
+    /// 存储名称，用于日志与健康检查
+    fn name(&self) -> &'static str;
+
+    /// 写入对象，已存在时覆盖
+    fn put<'a>(&'a self, key: &'a str, content: Vec<u8>) -> StorageFuture<'a, ()>;
+
+    /// 删除对象，对象不存在时视为成功
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+
+    /// 检查存储可写入且凭据有效
+    fn verify(&self) -> StorageFuture<'_, ()>;
+
+    /// 对象在数据库中记录的地址
+    fn file_path(&self, key: &str) -> String;
+
+    /// 从数据库中记录的地址解析出对象键，不在本存储中的地址（如外部链接）返回 `None`
+    fn object_key<'a>(&self, file_path: &'a str) -> Option<&'a str>;
+}
+
+/// 应用共享的存储实例
+pub type Storage = Arc<dyn StorageBackend>;
+
+/// 按配置创建存储实例
+pub fn build(config: &StorageConfig) -> Storage {
+    match config {
+        StorageConfig::S3(s3_config) => Arc::new(S3Storage::new(s3_config.clone())),
+        StorageConfig::Local(local) => Arc::new(LocalStorage::new(local)),
+    }
+}
+
+/// 取出文件存储，未配置时返回功能未启用
+pub fn require(storage: Option<&dyn StorageBackend>) -> ApiResult<&dyn StorageBackend> {
+    storage.ok_or_else(|| ApiError::FeatureDisabled("未配置文件存储，图片上传不可用".to_string()))
+}
+
+/// S3 兼容的对象存储，文件地址为 `<endpoint>/<bucket>/<key>`
+pub struct S3Storage {
+    config: S3Config,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &S3Config {
+        &self.config
+    }
+}
+
+impl StorageBackend for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, content: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(FileUploadService::put_object(&self.config, key, content))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(FileUploadService::delete_file(&self.config, key))
+    }
+
+    fn verify(&self) -> StorageFuture<'_, ()> {
+        Box::pin(FileUploadService::verify_bucket(&self.config))
+    }
+
+    fn file_path(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.config.endpoint_url, self.config.bucket, key
+        )
+    }
+
+    fn object_key<'a>(&self, file_path: &'a str) -> Option<&'a str> {
+        FileUploadService::object_key_from_path(&self.config, file_path)
+    }
+}
+
+/// 本地磁盘存储，文件地址即对象键，由 `/static` 路由对外提供
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(config: &LocalStorageConfig) -> Self {
+        Self {
+            root: config.root.clone(),
+        }
+    }
+
+    /// 对象键对应的磁盘路径；键中含有 `..`、绝对路径等可能越出存储目录的部分时返回 `None`
+    pub fn resolve(&self, key: &str) -> Option<PathBuf> {
+        let relative = Path::new(key);
+        let safe = !key.is_empty()
+            && !key.contains('\\')
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        safe.then(|| self.root.join(relative))
+    }
+}
+
+impl StorageBackend for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, content: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self
+                .resolve(key)
+                .ok_or_else(|| ApiError::Internal(format!("无效的对象键: {key}")))?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| ApiError::Internal(format!("创建存储目录失败: {e}")))?;
+            }
+
+            // 先写临时文件再改名，读取方不会看到写了一半的文件
+            let temp = path.with_file_name(format!(".{}.tmp", Uuid::new_v4()));
+            let written = async {
+                tokio::fs::write(&temp, content).await?;
+                tokio::fs::rename(&temp, &path).await
+            }
+            .await;
+            if let Err(e) = written {
+                let _ = tokio::fs::remove_file(&temp).await;
+                return Err(ApiError::Internal(format!("写入文件 {key} 失败: {e}")));
+            }
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self
+                .resolve(key)
+                .ok_or_else(|| ApiError::Internal(format!("无效的对象键: {key}")))?;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(ApiError::Internal(format!("删除文件 {key} 失败: {e}"))),
+            }
+        })
+    }
+
+    fn verify(&self) -> StorageFuture<'_, ()> {
+        Box::pin(async move {
+            let probe = self.root.join(format!(".{}.probe", Uuid::new_v4()));
+            let result = async {
+                tokio::fs::create_dir_all(&self.root).await?;
+                tokio::fs::write(&probe, b"ok").await?;
+                tokio::fs::remove_file(&probe).await
+            }
+            .await;
+            result.map_err(|e| {
+                ApiError::Internal(format!("存储目录 {} 不可写入: {e}", self.root.display()))
+            })
+        })
+    }
+
+    fn file_path(&self, key: &str) -> String {
+        key.to_string()
+    }
+
+    fn object_key<'a>(&self, file_path: &'a str) -> Option<&'a str> {
+        let external = file_path.starts_with("http://") || file_path.starts_with("https://");
+        (!external).then_some(file_path)
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use sea_orm::*;
+
+use crate::{
+    entities::{prelude::Server, server},
+    errors::{ApiError, ApiResult},
+    schemas::servers::TagSuggestion,
+    services::{database::DatabaseConnection, server::ServerService},
+};
+
+/// 最多推荐的标签数，与服务器标签数量上限一致
+const MAX_SUGGESTIONS: usize = 7;
+/// 共现统计缓存时长
+const STATS_TTL: Duration = Duration::from_secs(600);
+/// 共现比例低于该值的标签不推荐
+const MIN_CO_OCCURRENCE: f64 = 0.3;
+/// 参与共现统计的最少服务器数，样本太少时结果没有意义
+const MIN_TAG_USAGE: u32 = 3;
+
+/// 标签与描述关键词的对应关系，关键词按小写匹配
+const KEYWORD_TAGS: &[(&str, &[&str])] = &[
+    ("生存", &["生存", "survival"]),
+    ("PVP", &["pvp", "对战", "竞技"]),
+    ("生电", &["生电", "红石", "redstone", "刷怪塔", "机器"]),
+    ("RPG", &["rpg", "角色扮演", "职业", "副本", "技能"]),
+    (
+        "小游戏",
+        &["小游戏", "起床战争", "bedwars", "skywars", "minigame"],
+    ),
+    ("空岛", &["空岛", "skyblock"]),
+    ("建筑", &["建筑", "创造", "creative", "地标"]),
+    ("模组", &["模组", "mod", "forge", "fabric", "整合包"]),
+    ("原版", &["原版", "纯净", "vanilla"]),
+    ("粘液科技", &["粘液科技", "slimefun"]),
+    ("基岩版", &["基岩", "bedrock", "手机版"]),
+    ("互通", &["互通", "geyser", "跨平台"]),
+    ("白名单", &["白名单", "whitelist"]),
+    ("无政府", &["无政府", "anarchy"]),
+    ("公益", &["公益", "不收费", "零氪"]),
+    ("养老", &["养老", "休闲", "慢节奏"]),
+];
+
+/// 全站标签共现统计
+#[derive(Default)]
+struct TagStats {
+    /// 每个标签被多少个服务器使用
+    usage: HashMap<String, u32>,
+    /// 两个标签同时出现的服务器数
+    pairs: HashMap<(String, String), u32>,
+}
+
+/// 统计结果及其计算时间
+type CachedTagStats = (Instant, Arc<TagStats>);
+
+static TAG_STATS: Lazy<Mutex<Option<CachedTagStats>>> = Lazy::new(|| Mutex::new(None));
+
+/// 标签推荐服务
+///
+/// 先按关键词词典从描述中找出相关标签，再以这些标签和已有标签为种子，
+/// 根据全站服务器的标签共现比例补充常一起使用的标签。
+pub struct TagSuggestionService;
+
+impl TagSuggestionService {
+    /// 为服务器推荐标签，未传入的描述与标签取服务器当前值
+    pub async fn suggest_for_server(
+        db: &DatabaseConnection,
+        server_id: i32,
+        desc: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> ApiResult<Vec<TagSuggestion>> {
+        let server = Server::find_by_id(server_id)
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+        let desc = desc.unwrap_or(server.desc);
+        let tags = tags
+            .unwrap_or_else(|| ServerService::parse_server_tags(&server.tags).unwrap_or_default());
+
+        Self::suggest(db, &desc, &tags).await
+    }
+
+    /// 根据描述与已有标签推荐标签
+    pub async fn suggest(
+        db: &DatabaseConnection,
+        desc: &str,
+        existing: &[String],
+    ) -> ApiResult<Vec<TagSuggestion>> {
+        let existing: HashSet<&str> = existing.iter().map(String::as_str).collect();
+        let desc = desc.to_lowercase();
+
+        // 标签 -> (分数, 来源)
+        let mut scores: BTreeMap<String, (f64, &'static str)> = BTreeMap::new();
+        for (tag, keywords) in KEYWORD_TAGS {
+            let hits = keywords
+                .iter()
+                .map(|keyword| desc.matches(keyword).count())
+                .sum::<usize>();
+            if hits > 0 && !existing.contains(tag) {
+                // 命中次数越多越相关，3 次以上视为满分
+                let score = 0.6 + 0.4 * (hits.min(3) as f64 / 3.0);
+                scores.insert(String::from(*tag), (score, "keyword"));
+            }
+        }
+
+        let stats = Self::stats(db).await?;
+        let seeds: Vec<String> = existing
+            .iter()
+            .map(|tag| String::from(*tag))
+            .chain(scores.keys().cloned())
+            .collect();
+        for seed in &seeds {
+            let Some(&seed_usage) = stats.usage.get(seed) else {
+                continue;
+            };
+            if seed_usage < MIN_TAG_USAGE {
+                continue;
+            }
+            for ((a, b), &count) in &stats.pairs {
+                let other = if a == seed {
+                    b
+                } else if b == seed {
+                    a
+                } else {
+                    continue;
+                };
+                if existing.contains(other.as_str()) {
+                    continue;
+                }
+
+                let ratio = count as f64 / seed_usage as f64;
+                if ratio < MIN_CO_OCCURRENCE {
+                    continue;
+                }
+                // 共现推荐的权重低于关键词命中
+                let score = ratio * 0.8;
+                let entry = scores
+                    .entry(other.clone())
+                    .or_insert((score, "co_occurrence"));
+                if score > entry.0 && entry.1 == "co_occurrence" {
+                    entry.0 = score;
+                }
+            }
+        }
+
+        let mut suggestions: Vec<TagSuggestion> = scores
+            .into_iter()
+            .map(|(tag, (score, source))| TagSuggestion {
+                tag,
+                score: (score * 100.0).round() / 100.0,
+                source: source.to_string(),
+            })
+            .collect();
+        suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+        suggestions.truncate(MAX_SUGGESTIONS);
+
+        Ok(suggestions)
+    }
+
+    /// 获取共现统计，过期后重新计算
+    async fn stats(db: &DatabaseConnection) -> ApiResult<Arc<TagStats>> {
+        if let Some((built_at, stats)) =
+            TAG_STATS.lock().unwrap_or_else(|e| e.into_inner()).as_ref()
+        {
+            if built_at.elapsed() < STATS_TTL {
+                return Ok(stats.clone());
+            }
+        }
+
+        let rows: Vec<JsonValue> = Server::find()
+            .select_only()
+            .column(server::Column::Tags)
+            .filter(server::Column::DeactivatedAt.is_null())
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let mut stats = TagStats::default();
+        for tags in rows {
+            let mut tags = ServerService::parse_server_tags(&tags).unwrap_or_default();
+            tags.sort();
+            tags.dedup();
+            for (i, tag) in tags.iter().enumerate() {
+                *stats.usage.entry(tag.clone()).or_default() += 1;
+                for other in &tags[i + 1..] {
+                    *stats.pairs.entry((tag.clone(), other.clone())).or_default() += 1;
+                }
+            }
+        }
+
+        let stats = Arc::new(stats);
+        *TAG_STATS.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((Instant::now(), stats.clone()));
+        Ok(stats)
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::*;
+use serde_json::json;
+
+use crate::{
+    entities::{
+        prelude::{Server, TagVocabulary},
+        server, tag_vocabulary,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        admin::{MergeTagsResponse, TagVocabularyEntry, UpsertTagVocabularyRequest},
+        servers::{ServerStatus, ServerVisibility, TagListResponse, TagUsage},
+        users::ActivityAction,
+    },
+    services::{
+        activity::ActivityService,
+        cache::ServerCacheService,
+        database::DatabaseConnection,
+        events::{DomainEvent, EventBus},
+        revision::ServerRevisionService,
+        server::ServerService,
+    },
+};
+
+/// 标签管理服务
+pub struct TagService;
+
+impl TagService {
+    /// 单个标签的最大字符数，与创建服务器时的校验一致
+    const MAX_TAG_CHARS: usize = 4;
+
+    /// 租户内正在使用的标签及使用次数，附带标签词表
+    ///
+    /// 只统计列表中可见的服务器；结果按租户缓存，服务器资料修改或词表变更时清除。
+    pub async fn popular(db: &DatabaseConnection, tenant_id: &str) -> ApiResult<TagListResponse> {
+        if let Some(cached) = ServerCacheService::get_tags(tenant_id).await {
+            return Ok(cached);
+        }
+
+        let rows: Vec<serde_json::Value> = Server::find()
+            .select_only()
+            .column(server::Column::Tags)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::DelistedAt.is_null())
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for tags in rows {
+            let mut tags = ServerService::parse_server_tags(&tags).unwrap_or_default();
+            tags.sort();
+            tags.dedup();
+            for tag in tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut data: Vec<TagUsage> = counts
+            .into_iter()
+            .map(|(name, count)| TagUsage { name, count })
+            .collect();
+        data.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        let response = TagListResponse {
+            data,
+            vocabulary: Self::vocabulary_names(db, tenant_id).await?,
+        };
+        ServerCacheService::set_tags(tenant_id, &response).await;
+        Ok(response)
+    }
+
+    /// 词表不为空时，检查标签都在词表中
+    pub async fn ensure_allowed(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        tags: &[String],
+    ) -> ApiResult<()> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        let vocabulary = Self::vocabulary_names(db, tenant_id).await?;
+        if vocabulary.is_empty() {
+            return Ok(());
+        }
+        let unknown: Vec<&str> = tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !vocabulary.iter().any(|name| name == tag))
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::BadRequest(format!(
+                "标签不在可选范围内: {}",
+                unknown.join(", ")
+            )))
+        }
+    }
+
+    /// 租户的标签词表
+    pub async fn list_vocabulary(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+    ) -> ApiResult<Vec<TagVocabularyEntry>> {
+        let entries = TagVocabulary::find()
+            .filter(tag_vocabulary::Column::TenantId.eq(tenant_id))
+            .order_by_asc(tag_vocabulary::Column::Name)
+            .all(db.as_ref())
+            .await?;
+        Ok(entries.into_iter().map(Self::to_entry).collect())
+    }
+
+    /// 添加标签到词表，已存在时更新说明
+    pub async fn upsert_vocabulary(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        name: &str,
+        request: UpsertTagVocabularyRequest,
+        operator_id: i32,
+    ) -> ApiResult<TagVocabularyEntry> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > Self::MAX_TAG_CHARS {
+            return Err(ApiError::BadRequest("tags 长度限制为 1~4".to_string()));
+        }
+
+        let existing = Self::find_vocabulary(db, tenant_id, name).await?;
+        let mut active = match existing {
+            Some(entry) => entry.into_active_model(),
+            None => tag_vocabulary::ActiveModel {
+                tenant_id: Set(tenant_id.to_string()),
+                name: Set(name.to_string()),
+                ..Default::default()
+            },
+        };
+        active.description = Set(request
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty()));
+        active.updated_by_id = Set(Some(operator_id));
+        active.updated_at = Set(Utc::now());
+        let entry = active.save(db.as_ref()).await?.try_into_model()?;
+
+        ServerCacheService::invalidate_tags(tenant_id).await;
+        ActivityService::record(
+            db,
+            operator_id,
+            ActivityAction::TagVocabularyUpdated,
+            None,
+            Some(json!({ "operation": "upsert", "tag": entry.name })),
+        )
+        .await;
+        Ok(Self::to_entry(entry))
+    }
+
+    /// 从词表中移除标签；已使用该标签的服务器不受影响
+    pub async fn delete_vocabulary(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        name: &str,
+        operator_id: i32,
+    ) -> ApiResult<()> {
+        let entry = Self::find_vocabulary(db, tenant_id, name.trim())
+            .await?
+            .ok_or_else(|| ApiError::NotFound("标签不在词表中".to_string()))?;
+        let name = entry.name.clone();
+        entry.delete(db.as_ref()).await?;
+
+        ServerCacheService::invalidate_tags(tenant_id).await;
+        ActivityService::record(
+            db,
+            operator_id,
+            ActivityAction::TagVocabularyUpdated,
+            None,
+            Some(json!({ "operation": "delete", "tag": name })),
+        )
+        .await;
+        Ok(())
+    }
+
+    async fn vocabulary_names(db: &DatabaseConnection, tenant_id: &str) -> ApiResult<Vec<String>> {
+        Ok(TagVocabulary::find()
+            .select_only()
+            .column(tag_vocabulary::Column::Name)
+            .filter(tag_vocabulary::Column::TenantId.eq(tenant_id))
+            .order_by_asc(tag_vocabulary::Column::Name)
+            .into_tuple()
+            .all(db.as_ref())
+            .await?)
+    }
+
+    async fn find_vocabulary(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        name: &str,
+    ) -> ApiResult<Option<tag_vocabulary::Model>> {
+        Ok(TagVocabulary::find()
+            .filter(tag_vocabulary::Column::TenantId.eq(tenant_id))
+            .filter(tag_vocabulary::Column::Name.eq(name))
+            .one(db.as_ref())
+            .await?)
+    }
+
+    fn to_entry(entry: tag_vocabulary::Model) -> TagVocabularyEntry {
+        TagVocabularyEntry {
+            name: entry.name,
+            description: entry.description,
+            updated_by_id: entry.updated_by_id,
+            updated_at: entry.updated_at,
+        }
+    }
+
+    /// 将 `from` 中的标签在所有服务器上替换为 `to`
+    ///
+    /// 在同一事务内完成，每个受影响的服务器都会产生一个修订版本；
+    /// 完成后发布修改事件（由搜索同步任务更新索引）并记录操作日志。
+    pub async fn merge_tags(
+        db: &DatabaseConnection,
+        from: Vec<String>,
+        to: String,
+        operator_id: i32,
+    ) -> ApiResult<MergeTagsResponse> {
+        let to = to.trim().to_string();
+        if to.is_empty() || to.chars().count() > Self::MAX_TAG_CHARS {
+            return Err(ApiError::BadRequest("tags 长度限制为 1~4".to_string()));
+        }
+        let mut from: Vec<String> = from
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty() && *tag != to)
+            .collect();
+        from.sort();
+        from.dedup();
+        if from.is_empty() {
+            return Err(ApiError::BadRequest(
+                "请至少指定一个与目标不同的标签".to_string(),
+            ));
+        }
+
+        let txn = db.begin().await?;
+
+        let servers = Server::find().all(&txn).await?;
+        let mut affected_ids = Vec::new();
+        for server in servers {
+            let tags = ServerService::parse_server_tags(&server.tags).unwrap_or_default();
+            if !tags.iter().any(|tag| from.contains(tag)) {
+                continue;
+            }
+
+            // 保持原有顺序，替换后去重
+            let mut merged: Vec<String> = Vec::with_capacity(tags.len());
+            for tag in tags {
+                let tag = if from.contains(&tag) { to.clone() } else { tag };
+                if !merged.contains(&tag) {
+                    merged.push(tag);
+                }
+            }
+
+            let previous = server.clone();
+            let mut active: server::ActiveModel = server.into();
+            active.tags = Set(json!(merged));
+            let updated = active.update(&txn).await?;
+            ServerRevisionService::record(&txn, &previous, &updated, Some(operator_id), None)
+                .await?;
+            affected_ids.push(updated.id);
+        }
+
+        txn.commit().await?;
+        let affected = affected_ids.len() as u64;
+        if affected > 0 {
+            EventBus::publish(DomainEvent::server_updated(affected_ids));
+        }
+
+        tracing::info!(
+            "标签已合并: {:?} -> {}, 影响 {} 个服务器",
+            from,
+            to,
+            affected
+        );
+        ActivityService::record(
+            db,
+            operator_id,
+            ActivityAction::TagsMerged,
+            None,
+            Some(json!({ "from": from, "to": to, "affected_servers": affected })),
+        )
+        .await;
+
+        Ok(MergeTagsResponse {
+            from,
+            to,
+            affected_servers: affected,
+        })
+    }
+}
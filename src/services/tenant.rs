@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+
+use crate::config::{TenantConfig, TenantDefinition};
+
+/// 多租户模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantMode {
+    /// 单租户部署，所有数据归属默认租户
+    Off,
+    /// 按请求的 Host 解析租户
+    Host,
+    /// 按 `/t/{tenant}` 路径前缀解析租户
+    Path,
+}
+
+impl TenantMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" | "" => Some(TenantMode::Off),
+            "host" => Some(TenantMode::Host),
+            "path" => Some(TenantMode::Path),
+            _ => None,
+        }
+    }
+}
+
+struct TenantRegistry {
+    mode: TenantMode,
+    default_tenant: String,
+    tenants: Vec<TenantDefinition>,
+}
+
+static TENANT_REGISTRY: OnceCell<TenantRegistry> = OnceCell::new();
+
+/// 租户服务
+///
+/// 一个部署可以同时承载多个服务器列表社区，服务器与用户通过 `tenant_id` 隔离，
+/// 每个租户可以单独配置名称、搜索索引与是否开放注册。未初始化时视为单租户部署。
+pub struct TenantService;
+
+impl TenantService {
+    /// 路径模式下的租户前缀
+    pub const PATH_PREFIX: &'static str = "/t/";
+
+    /// 根据配置初始化租户信息
+    pub fn init(config: &TenantConfig) -> Result<()> {
+        let mode = TenantMode::parse(&config.mode)
+            .ok_or_else(|| anyhow!("未知的多租户模式: {}", config.mode))?;
+
+        if mode == TenantMode::Host {
+            for tenant in &config.tenants {
+                if tenant.id != config.default_tenant && tenant.hosts.is_empty() {
+                    return Err(anyhow!("租户 {} 未配置域名", tenant.id));
+                }
+            }
+        }
+
+        let mut indices: Vec<&str> = config
+            .tenants
+            .iter()
+            .map(|tenant| tenant.search_index.as_str())
+            .collect();
+        indices.sort_unstable();
+        if indices.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(anyhow!("多个租户使用了相同的搜索索引"));
+        }
+
+        TENANT_REGISTRY
+            .set(TenantRegistry {
+                mode,
+                default_tenant: config.default_tenant.clone(),
+                tenants: config.tenants.clone(),
+            })
+            .map_err(|_| anyhow!("租户信息已初始化"))
+    }
+
+    pub fn mode() -> TenantMode {
+        TENANT_REGISTRY
+            .get()
+            .map_or(TenantMode::Off, |registry| registry.mode)
+    }
+
+    pub fn is_enabled() -> bool {
+        Self::mode() != TenantMode::Off
+    }
+
+    /// 默认租户
+    pub fn default_tenant() -> TenantDefinition {
+        TENANT_REGISTRY
+            .get()
+            .and_then(|registry| Self::find_in(registry, &registry.default_tenant))
+            .cloned()
+            .unwrap_or_else(|| TenantDefinition {
+                id: "default".to_string(),
+                hosts: vec![],
+                name: None,
+                search_index: "servers".to_string(),
+                allow_registration: true,
+            })
+    }
+
+    /// 按租户标识查找
+    pub fn find(id: &str) -> Option<TenantDefinition> {
+        match TENANT_REGISTRY.get() {
+            Some(registry) => Self::find_in(registry, id).cloned(),
+            None => Some(Self::default_tenant()).filter(|tenant| tenant.id == id),
+        }
+    }
+
+    /// 按请求域名查找，域名不带端口
+    pub fn resolve_host(host: &str) -> Option<TenantDefinition> {
+        let host = host.to_lowercase();
+        TENANT_REGISTRY.get().and_then(|registry| {
+            registry
+                .tenants
+                .iter()
+                .find(|tenant| tenant.hosts.contains(&host))
+                .cloned()
+        })
+    }
+
+    /// 全部租户
+    pub fn all() -> Vec<TenantDefinition> {
+        TENANT_REGISTRY
+            .get()
+            .map(|registry| registry.tenants.clone())
+            .unwrap_or_else(|| vec![Self::default_tenant()])
+    }
+
+    /// 租户使用的搜索索引，未知租户使用默认租户的索引
+    pub fn search_index(tenant_id: &str) -> String {
+        Self::find(tenant_id)
+            .unwrap_or_else(Self::default_tenant)
+            .search_index
+    }
+
+    fn find_in<'a>(registry: &'a TenantRegistry, id: &str) -> Option<&'a TenantDefinition> {
+        registry.tenants.iter().find(|tenant| tenant.id == id)
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        prelude::{Server, ServerTimeline as ServerTimelineEntity},
+        server, server_timeline,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::servers::{
+        ServerStats, ServerStatus, ServerTimelineEntry, ServerTimelineResponse, ServerVisibility,
+        TimelineField,
+    },
+    services::database::DatabaseConnection,
+};
+
+/// 每个服务器每个字段保留的最大记录数，超出时删除最早的记录
+const MAX_ENTRIES_PER_FIELD: u64 = 100;
+
+/// 一条状态数据中与时间线相关的字段
+pub struct StatsObservation {
+    pub server_id: i32,
+    pub observed_at: DateTime<Utc>,
+    values: Vec<(TimelineField, String)>,
+}
+
+impl StatsObservation {
+    pub fn new(server_id: i32, observed_at: DateTime<Utc>, stats: &ServerStats) -> Self {
+        let mut values = Vec::with_capacity(2);
+        let motd = stats.motd.plain.trim();
+        if !motd.is_empty() {
+            values.push((TimelineField::Motd, motd.to_string()));
+        }
+        let version = stats.version.trim();
+        if !version.is_empty() && version != "Unknown" {
+            values.push((TimelineField::Version, version.to_string()));
+        }
+        Self {
+            server_id,
+            observed_at,
+            values,
+        }
+    }
+}
+
+/// 服务器 MOTD 与版本变化时间线
+///
+/// 写入状态数据时与该服务器最近一次记录的值比较，只在值发生变化时追加记录，
+/// 因此时间线反映的是服务器升级版本或更换宣传语的时间点。
+pub struct ServerTimelineService;
+
+impl ServerTimelineService {
+    /// 按采集时间顺序比较并记录变化，同一批次内的多条数据依次比较
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        mut observations: Vec<StatsObservation>,
+    ) -> ApiResult<()> {
+        observations.retain(|observation| !observation.values.is_empty());
+        if observations.is_empty() {
+            return Ok(());
+        }
+        observations.sort_by_key(|observation| observation.observed_at);
+
+        let server_ids: HashSet<i32> = observations.iter().map(|o| o.server_id).collect();
+        let mut latest = Self::latest_values(db, server_ids).await?;
+
+        let mut rows = Vec::new();
+        let mut touched = HashSet::new();
+        for observation in observations {
+            for (field, value) in observation.values {
+                let key = (observation.server_id, field.as_str().to_string());
+                let previous = latest.get(&key);
+                if previous == Some(&value) {
+                    continue;
+                }
+
+                rows.push(server_timeline::ActiveModel {
+                    server_id: Set(observation.server_id),
+                    field: Set(key.1.clone()),
+                    previous: Set(previous.cloned()),
+                    current: Set(value.clone()),
+                    changed_at: Set(observation.observed_at),
+                    ..Default::default()
+                });
+                touched.insert(key.clone());
+                latest.insert(key, value);
+            }
+        }
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        ServerTimelineEntity::insert_many(rows).exec(db).await?;
+        for (server_id, field) in touched {
+            Self::trim(db, server_id, &field).await?;
+        }
+        Ok(())
+    }
+
+    /// 获取服务器的时间线，只返回同一租户内未隐藏、未停用的服务器
+    pub async fn list(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+        field: Option<TimelineField>,
+        limit: u64,
+    ) -> ApiResult<ServerTimelineResponse> {
+        Server::find_by_id(server_id)
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .into_tuple::<i32>()
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        let mut query =
+            ServerTimelineEntity::find().filter(server_timeline::Column::ServerId.eq(server_id));
+        if let Some(field) = field {
+            query = query.filter(server_timeline::Column::Field.eq(field.as_str()));
+        }
+        let entries = query
+            .order_by_desc(server_timeline::Column::ChangedAt)
+            .order_by_desc(server_timeline::Column::Id)
+            .limit(limit)
+            .all(db.as_ref())
+            .await?;
+
+        Ok(ServerTimelineResponse {
+            data: entries
+                .into_iter()
+                .filter_map(|entry| {
+                    Some(ServerTimelineEntry {
+                        field: TimelineField::parse(&entry.field)?,
+                        previous: entry.previous,
+                        current: entry.current,
+                        changed_at: entry.changed_at,
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    /// 每个服务器每个字段最近一次记录的值
+    async fn latest_values<C: ConnectionTrait>(
+        db: &C,
+        server_ids: HashSet<i32>,
+    ) -> ApiResult<HashMap<(i32, String), String>> {
+        let latest_ids: Vec<i32> = ServerTimelineEntity::find()
+            .select_only()
+            .column_as(server_timeline::Column::Id.max(), "id")
+            .filter(server_timeline::Column::ServerId.is_in(server_ids))
+            .group_by(server_timeline::Column::ServerId)
+            .group_by(server_timeline::Column::Field)
+            .into_tuple::<Option<i32>>()
+            .all(db)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        if latest_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        Ok(ServerTimelineEntity::find()
+            .filter(server_timeline::Column::Id.is_in(latest_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|entry| ((entry.server_id, entry.field), entry.current))
+            .collect())
+    }
+
+    /// 删除超出保留数量的旧记录
+    async fn trim<C: ConnectionTrait>(db: &C, server_id: i32, field: &str) -> ApiResult<()> {
+        let cutoff: Option<i32> = ServerTimelineEntity::find()
+            .select_only()
+            .column(server_timeline::Column::Id)
+            .filter(server_timeline::Column::ServerId.eq(server_id))
+            .filter(server_timeline::Column::Field.eq(field))
+            .order_by_desc(server_timeline::Column::Id)
+            .offset(MAX_ENTRIES_PER_FIELD)
+            .limit(1)
+            .into_tuple::<i32>()
+            .one(db)
+            .await?;
+
+        if let Some(cutoff) = cutoff {
+            ServerTimelineEntity::delete_many()
+                .filter(server_timeline::Column::ServerId.eq(server_id))
+                .filter(server_timeline::Column::Field.eq(field))
+                .filter(server_timeline::Column::Id.lte(cutoff))
+                .exec(db)
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::Client as HttpClient;
+use sea_orm::*;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::TranslationConfig,
+    entities::{
+        prelude::{Server, ServerTranslation},
+        server, server_translation,
+    },
+    errors::ApiResult,
+    schemas::servers::DescriptionTranslation,
+    services::{database::DatabaseConnection, metrics::MetricsService},
+};
+
+pub type TranslateFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// 机器翻译服务提供方
+pub trait TranslationProvider: Send + Sync {
+    /// 提供方名称，用于日志与指标
+    fn name(&self) -> &'static str;
+
+    /// 将文本从 `source` 语言翻译为 `target` 语言
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        source: &'a str,
+        target: &'a str,
+    ) -> TranslateFuture<'a>;
+}
+
+/// LibreTranslate（可自建）
+pub struct LibreTranslateProvider {
+    client: HttpClient,
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl TranslationProvider for LibreTranslateProvider {
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        source: &'a str,
+        target: &'a str,
+    ) -> TranslateFuture<'a> {
+        Box::pin(async move {
+            let mut body = json!({
+                "q": text,
+                "source": source,
+                "target": target,
+                "format": "text",
+            });
+            if let Some(ref key) = self.api_key {
+                body["api_key"] = json!(key);
+            }
+
+            let resp: Value = self
+                .client
+                .post(format!("{}/translate", self.api_url))
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            resp["translatedText"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("翻译响应缺少 translatedText"))
+        })
+    }
+}
+
+/// DeepL API
+pub struct DeepLProvider {
+    client: HttpClient,
+    api_url: String,
+    api_key: String,
+}
+
+impl TranslationProvider for DeepLProvider {
+    fn name(&self) -> &'static str {
+        "deepl"
+    }
+
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        source: &'a str,
+        target: &'a str,
+    ) -> TranslateFuture<'a> {
+        Box::pin(async move {
+            let resp: Value = self
+                .client
+                .post(format!("{}/v2/translate", self.api_url))
+                .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+                .json(&json!({
+                    "text": [text],
+                    "source_lang": source.to_uppercase(),
+                    "target_lang": target.to_uppercase(),
+                }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            resp["translations"][0]["text"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("翻译响应缺少 translations"))
+        })
+    }
+}
+
+static TRANSLATION_INSTANCE: OnceCell<Arc<TranslationService>> = OnceCell::new();
+/// 正在翻译的服务器，避免同一服务器被重复提交
+static IN_FLIGHT: Lazy<Mutex<HashSet<i32>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 服务器描述自动翻译
+///
+/// 服务器描述缺少某个目标语言的版本，或原文变化导致机器译文过期时，在后台调用
+/// 翻译服务补全。人工提供的译文不会被覆盖；机器译文在响应中带有 `machine_translated`
+/// 标记，由前端标注。未配置翻译服务时不做任何处理。
+pub struct TranslationService {
+    provider: Box<dyn TranslationProvider>,
+    source_locale: String,
+    target_locales: Vec<String>,
+}
+
+impl TranslationService {
+    /// 根据配置初始化翻译服务，未配置提供方时返回 `Ok(false)`
+    pub fn init(config: &TranslationConfig) -> Result<bool> {
+        let Some(ref provider) = config.provider else {
+            return Ok(false);
+        };
+
+        let client = HttpClient::new();
+        let provider: Box<dyn TranslationProvider> = match provider.as_str() {
+            "libretranslate" => Box::new(LibreTranslateProvider {
+                client,
+                api_url: config.api_url.clone(),
+                api_key: config.api_key.clone(),
+            }),
+            "deepl" => Box::new(DeepLProvider {
+                client,
+                api_url: config.api_url.clone(),
+                api_key: config
+                    .api_key
+                    .clone()
+                    .ok_or_else(|| anyhow!("DeepL 需要配置 TRANSLATION_API_KEY"))?,
+            }),
+            other => return Err(anyhow!("未知的翻译服务提供方: {}", other)),
+        };
+
+        let service = TranslationService {
+            provider,
+            source_locale: config.source_locale.clone(),
+            target_locales: config
+                .target_locales
+                .iter()
+                .filter(|locale| **locale != config.source_locale)
+                .cloned()
+                .collect(),
+        };
+        TRANSLATION_INSTANCE
+            .set(Arc::new(service))
+            .map_err(|_| anyhow!("翻译服务已初始化"))?;
+
+        Ok(true)
+    }
+
+    /// 获取全局翻译服务实例
+    pub fn instance() -> Option<Arc<TranslationService>> {
+        TRANSLATION_INSTANCE.get().cloned()
+    }
+
+    /// 获取服务器描述的可用语言版本
+    ///
+    /// 已过期的机器译文不返回；存在缺失或过期的语言时在后台补全。
+    pub async fn translations_for(
+        db: &DatabaseConnection,
+        server: &server::Model,
+    ) -> ApiResult<Vec<DescriptionTranslation>> {
+        let rows = ServerTranslation::find()
+            .filter(server_translation::Column::ServerId.eq(server.id))
+            .order_by_asc(server_translation::Column::Locale)
+            .all(db.as_ref())
+            .await?;
+
+        let hash = Self::source_hash(&server.desc);
+        let translations: Vec<DescriptionTranslation> = rows
+            .into_iter()
+            .filter(|row| !row.machine_translated || row.source_hash == hash)
+            .map(|row| DescriptionTranslation {
+                locale: row.locale,
+                desc: row.desc,
+                machine_translated: row.machine_translated,
+            })
+            .collect();
+
+        if let Some(service) = Self::instance() {
+            let missing = service
+                .target_locales
+                .iter()
+                .any(|locale| !translations.iter().any(|t| &t.locale == locale));
+            if missing && !server.desc.trim().is_empty() {
+                Self::schedule(db.clone(), server.id);
+            }
+        }
+
+        Ok(translations)
+    }
+
+    /// 在后台补全服务器描述的翻译
+    pub fn schedule(db: DatabaseConnection, server_id: i32) {
+        let Some(service) = Self::instance() else {
+            return;
+        };
+        if !IN_FLIGHT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(server_id)
+        {
+            return;
+        }
+
+        tokio::spawn(async move {
+            match service.fill_missing(&db, server_id).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("已为服务器 {} 生成 {} 个机器翻译", server_id, count),
+                Err(e) => tracing::warn!("⚠️  服务器 {} 描述翻译失败: {}", server_id, e),
+            }
+            IN_FLIGHT
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&server_id);
+        });
+    }
+
+    async fn fill_missing(&self, db: &DatabaseConnection, server_id: i32) -> ApiResult<usize> {
+        let Some(server) = Server::find_by_id(server_id).one(db.as_ref()).await? else {
+            return Ok(0);
+        };
+        if server.desc.trim().is_empty() {
+            return Ok(0);
+        }
+
+        let existing = ServerTranslation::find()
+            .filter(server_translation::Column::ServerId.eq(server_id))
+            .all(db.as_ref())
+            .await?;
+        let hash = Self::source_hash(&server.desc);
+
+        let mut translated = 0;
+        for locale in &self.target_locales {
+            let current = existing.iter().find(|row| &row.locale == locale);
+            if current.is_some_and(|row| !row.machine_translated || row.source_hash == hash) {
+                continue;
+            }
+
+            let text = match self
+                .provider
+                .translate(&server.desc, &self.source_locale, locale)
+                .await
+            {
+                Ok(text) => {
+                    Self::record_metric(self.provider.name(), "success");
+                    text
+                }
+                Err(e) => {
+                    Self::record_metric(self.provider.name(), "failure");
+                    tracing::warn!("⚠️  翻译服务器 {} 描述到 {} 失败: {}", server_id, locale, e);
+                    continue;
+                }
+            };
+
+            let mut active = match current {
+                Some(row) => row.clone().into(),
+                None => server_translation::ActiveModel {
+                    server_id: Set(server_id),
+                    locale: Set(locale.clone()),
+                    ..Default::default()
+                },
+            };
+            active.desc = Set(text);
+            active.machine_translated = Set(true);
+            active.source_hash = Set(hash.clone());
+            active.updated_at = Set(Utc::now());
+            active.save(db.as_ref()).await?;
+            translated += 1;
+        }
+
+        Ok(translated)
+    }
+
+    fn source_hash(desc: &str) -> String {
+        hex::encode(Sha256::digest(desc.as_bytes()))
+    }
+
+    fn record_metric(provider: &str, result: &str) {
+        MetricsService::inc_counter(
+            "translation_requests_total",
+            "服务器描述机器翻译请求数",
+            &[("provider", provider), ("result", result)],
+            1.0,
+        );
+    }
+}
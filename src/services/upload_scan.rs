@@ -0,0 +1,307 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::{config::UploadScanConfig, services::metrics::MetricsService};
+
+/// clamd INSTREAM 单个数据块的大小
+const CLAMAV_CHUNK_SIZE: usize = 64 * 1024;
+/// 扫描服务响应的最大长度，超过视为异常
+const MAX_RESPONSE_LEN: usize = 16 * 1024;
+
+pub type ScanFuture<'a> = Pin<Box<dyn Future<Output = Result<ScanVerdict>> + Send + 'a>>;
+
+/// 扫描结论
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// 命中的病毒特征名
+    Infected(String),
+}
+
+/// 文件扫描器
+pub trait VirusScanner: Send + Sync {
+    /// 扫描器名称，记录在文件扫描结果中
+    fn name(&self) -> &'static str;
+
+    fn scan<'a>(&'a self, content: &'a [u8]) -> ScanFuture<'a>;
+}
+
+/// ClamAV（clamd）扫描器，使用 INSTREAM 命令
+pub struct ClamAvScanner {
+    address: String,
+}
+
+impl VirusScanner for ClamAvScanner {
+    fn name(&self) -> &'static str {
+        "clamav"
+    }
+
+    fn scan<'a>(&'a self, content: &'a [u8]) -> ScanFuture<'a> {
+        Box::pin(async move {
+            let mut stream = TcpStream::connect(&self.address).await?;
+            stream.write_all(b"zINSTREAM\0").await?;
+            for chunk in content.chunks(CLAMAV_CHUNK_SIZE) {
+                stream
+                    .write_all(&(chunk.len() as u32).to_be_bytes())
+                    .await?;
+                stream.write_all(chunk).await?;
+            }
+            stream.write_all(&0u32.to_be_bytes()).await?;
+            stream.flush().await?;
+
+            let response = read_until(&mut stream, b"\0").await?;
+            let response = String::from_utf8_lossy(&response);
+            let response = response.trim_end_matches('\0').trim();
+
+            // 响应格式：`stream: OK` / `stream: <特征名> FOUND` / `... ERROR`
+            if response.ends_with(" OK") {
+                Ok(ScanVerdict::Clean)
+            } else if let Some(found) = response.strip_suffix(" FOUND") {
+                let signature = found.strip_prefix("stream: ").unwrap_or(found);
+                Ok(ScanVerdict::Infected(signature.to_string()))
+            } else {
+                Err(anyhow!("clamd 返回异常: {}", response))
+            }
+        })
+    }
+}
+
+/// ICAP 扫描器，以 RESPMOD 方式提交文件内容
+pub struct IcapScanner {
+    address: String,
+    service: String,
+}
+
+impl VirusScanner for IcapScanner {
+    fn name(&self) -> &'static str {
+        "icap"
+    }
+
+    fn scan<'a>(&'a self, content: &'a [u8]) -> ScanFuture<'a> {
+        Box::pin(async move {
+            let http_headers = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n",
+                content.len()
+            );
+            let icap_headers = format!(
+                "RESPMOD icap://{}/{} ICAP/1.0\r\nHost: {}\r\nAllow: 204\r\nEncapsulated: res-hdr=0, res-body={}\r\nConnection: close\r\n\r\n",
+                self.address,
+                self.service,
+                self.address,
+                http_headers.len()
+            );
+
+            let mut stream = TcpStream::connect(&self.address).await?;
+            stream.write_all(icap_headers.as_bytes()).await?;
+            stream.write_all(http_headers.as_bytes()).await?;
+            stream
+                .write_all(format!("{:x}\r\n", content.len()).as_bytes())
+                .await?;
+            stream.write_all(content).await?;
+            stream.write_all(b"\r\n0\r\n\r\n").await?;
+            stream.flush().await?;
+
+            let response = read_until(&mut stream, b"\r\n\r\n").await?;
+            let response = String::from_utf8_lossy(&response);
+            let mut lines = response.lines();
+            let status = lines
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .ok_or_else(|| anyhow!("ICAP 响应格式错误"))?;
+
+            match status {
+                // 204 表示无需修改，即内容干净
+                "204" => Ok(ScanVerdict::Clean),
+                // 200 表示服务端替换了内容，通常是拦截页面
+                "200" => {
+                    let signature = lines
+                        .filter_map(|line| line.split_once(':'))
+                        .find(|(name, _)| {
+                            name.eq_ignore_ascii_case("X-Infection-Found")
+                                || name.eq_ignore_ascii_case("X-Virus-ID")
+                        })
+                        .map(|(_, value)| {
+                            let value = value.trim();
+                            value
+                                .split(';')
+                                .find_map(|part| part.trim().strip_prefix("Threat="))
+                                .unwrap_or(value)
+                                .to_string()
+                        })
+                        .unwrap_or_else(|| "unknown".to_string());
+                    Ok(ScanVerdict::Infected(signature))
+                }
+                other => Err(anyhow!("ICAP 返回异常状态: {}", other)),
+            }
+        })
+    }
+}
+
+/// 读取响应直到出现结束标记或连接关闭
+async fn read_until(stream: &mut TcpStream, terminator: &[u8]) -> Result<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..n]);
+        if response
+            .windows(terminator.len())
+            .any(|window| window == terminator)
+        {
+            break;
+        }
+        if response.len() > MAX_RESPONSE_LEN {
+            return Err(anyhow!("扫描服务响应过长"));
+        }
+    }
+    Ok(response)
+}
+
+/// 一次扫描的结果
+#[derive(Debug, Clone)]
+pub enum ScanOutcome {
+    /// 未启用扫描
+    Skipped,
+    Clean {
+        engine: &'static str,
+    },
+    Infected {
+        engine: &'static str,
+        signature: String,
+    },
+    /// 扫描服务不可用或返回异常
+    Failed {
+        engine: &'static str,
+        error: String,
+    },
+}
+
+impl ScanOutcome {
+    /// 写入文件记录的扫描状态
+    pub fn status(&self) -> Option<&'static str> {
+        match self {
+            ScanOutcome::Skipped => None,
+            ScanOutcome::Clean { .. } => Some("clean"),
+            ScanOutcome::Infected { .. } => Some("infected"),
+            ScanOutcome::Failed { .. } => Some("error"),
+        }
+    }
+
+    pub fn engine(&self) -> Option<&'static str> {
+        match self {
+            ScanOutcome::Skipped => None,
+            ScanOutcome::Clean { engine }
+            | ScanOutcome::Infected { engine, .. }
+            | ScanOutcome::Failed { engine, .. } => Some(engine),
+        }
+    }
+
+    pub fn detail(&self) -> Option<String> {
+        match self {
+            ScanOutcome::Infected { signature, .. } => Some(signature.clone()),
+            ScanOutcome::Failed { error, .. } => Some(error.clone()),
+            _ => None,
+        }
+    }
+}
+
+static SCAN_INSTANCE: OnceCell<Arc<UploadScanService>> = OnceCell::new();
+
+/// 上传文件安全扫描服务
+///
+/// 所有上传的文件在写入 S3 之前经过扫描；命中病毒特征的文件转存到隔离区并拒绝上传，
+/// 扫描结果记录在文件记录中。扫描服务不可用时按配置决定放行或拒绝。
+pub struct UploadScanService {
+    scanner: Box<dyn VirusScanner>,
+    timeout: Duration,
+    fail_open: bool,
+}
+
+impl UploadScanService {
+    /// 根据配置初始化扫描服务，未配置扫描方式时返回 `Ok(false)`
+    pub fn init(config: &UploadScanConfig) -> Result<bool> {
+        let Some(ref backend) = config.backend else {
+            return Ok(false);
+        };
+
+        let scanner: Box<dyn VirusScanner> = match backend.as_str() {
+            "clamav" => Box::new(ClamAvScanner {
+                address: config.address.clone(),
+            }),
+            "icap" => Box::new(IcapScanner {
+                address: config.address.clone(),
+                service: config.icap_service.clone(),
+            }),
+            other => return Err(anyhow!("未知的扫描方式: {}", other)),
+        };
+
+        SCAN_INSTANCE
+            .set(Arc::new(UploadScanService {
+                scanner,
+                timeout: Duration::from_secs(config.timeout_secs.max(1)),
+                fail_open: config.fail_open,
+            }))
+            .map_err(|_| anyhow!("扫描服务已初始化"))?;
+
+        Ok(true)
+    }
+
+    /// 扫描服务不可用时是否放行
+    pub fn fail_open() -> bool {
+        SCAN_INSTANCE.get().is_none_or(|service| service.fail_open)
+    }
+
+    /// 扫描上传内容，未启用扫描时返回 [`ScanOutcome::Skipped`]
+    pub async fn scan(content: &[u8]) -> ScanOutcome {
+        let Some(service) = SCAN_INSTANCE.get() else {
+            return ScanOutcome::Skipped;
+        };
+        let engine = service.scanner.name();
+
+        let outcome = match tokio::time::timeout(service.timeout, service.scanner.scan(content))
+            .await
+        {
+            Ok(Ok(ScanVerdict::Clean)) => ScanOutcome::Clean { engine },
+            Ok(Ok(ScanVerdict::Infected(signature))) => ScanOutcome::Infected { engine, signature },
+            Ok(Err(e)) => ScanOutcome::Failed {
+                engine,
+                error: e.to_string(),
+            },
+            Err(_) => ScanOutcome::Failed {
+                engine,
+                error: "扫描超时".to_string(),
+            },
+        };
+
+        MetricsService::inc_counter(
+            "upload_scans_total",
+            "上传文件安全扫描次数",
+            &[
+                ("engine", engine),
+                ("result", outcome.status().unwrap_or("skipped")),
+            ],
+            1.0,
+        );
+        match &outcome {
+            ScanOutcome::Infected { signature, .. } => {
+                tracing::warn!("⚠️  上传文件命中病毒特征: {}", signature)
+            }
+            ScanOutcome::Failed { error, .. } => {
+                tracing::warn!("⚠️  上传文件扫描失败: {}", error)
+            }
+            _ => {}
+        }
+
+        outcome
+    }
+}
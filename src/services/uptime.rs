@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+
+use crate::{
+    entities::{
+        prelude::{Server, ServerUptime as ServerUptimeEntity},
+        server, server_uptime,
+    },
+    errors::{ApiError, ApiResult},
+    schemas::{
+        servers::{ServerStatus, ServerUptimeResponse, ServerVisibility, StatsHistoryRange},
+        users::NotificationKind,
+    },
+    services::{
+        database::DatabaseConnection,
+        notification::{Notification, NotificationService},
+        redis::RedisService,
+    },
+};
+
+/// 一条状态数据对应的在线状态，状态为空的采样视为离线
+pub struct UptimeObservation {
+    pub server_id: i32,
+    pub observed_at: DateTime<Utc>,
+    pub online: bool,
+}
+
+/// 服务器在线状态变化记录与在线率
+///
+/// server_uptime 只保存在线与离线之间的切换，计算在线率时读取窗口内的切换加上窗口开始前的
+/// 最后一次切换即可，不需要扫描 server_stats。两次切换之间视为一直保持前一次的状态。
+/// 服务器从在线变为离线时通知服主，同一服务器在冷却时间内只通知一次。
+pub struct ServerUptimeService;
+
+impl ServerUptimeService {
+    /// 保留的时间范围，与最长的在线率窗口一致
+    const RETENTION_DAYS: i64 = 30;
+    /// 只对这段时间内发生的离线发送通知，补报的历史数据不触发
+    const OFFLINE_ALERT_WINDOW_MINUTES: i64 = 30;
+    /// 同一服务器两次离线通知的最小间隔（秒），避免状态反复切换时频繁打扰
+    const OFFLINE_ALERT_COOLDOWN_SECS: u64 = 3600;
+    const OFFLINE_ALERT_PREFIX: &'static str = "uptime:offline_alert";
+
+    /// 按采集时间顺序比较并记录切换，同一批次内的多条数据依次比较
+    ///
+    /// 返回从在线变为离线的切换，首次记录的离线状态不算在内。
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        mut observations: Vec<UptimeObservation>,
+    ) -> ApiResult<Vec<UptimeObservation>> {
+        if observations.is_empty() {
+            return Ok(Vec::new());
+        }
+        observations.sort_by_key(|observation| observation.observed_at);
+
+        let server_ids: HashSet<i32> = observations.iter().map(|o| o.server_id).collect();
+        let mut latest = Self::latest_states(db, server_ids).await?;
+
+        let mut rows = Vec::new();
+        let mut touched = HashSet::new();
+        let mut went_offline = Vec::new();
+        for observation in observations {
+            let previous = latest.get(&observation.server_id);
+            if previous.is_some_and(|(online, changed_at)| {
+                *online == observation.online || *changed_at > observation.observed_at
+            }) {
+                continue;
+            }
+            let was_online = previous.is_some();
+
+            rows.push(server_uptime::ActiveModel {
+                server_id: Set(observation.server_id),
+                online: Set(observation.online),
+                changed_at: Set(observation.observed_at),
+                ..Default::default()
+            });
+            touched.insert(observation.server_id);
+            latest.insert(
+                observation.server_id,
+                (observation.online, observation.observed_at),
+            );
+            if was_online && !observation.online {
+                went_offline.push(observation);
+            }
+        }
+        if rows.is_empty() {
+            return Ok(went_offline);
+        }
+
+        ServerUptimeEntity::insert_many(rows).exec(db).await?;
+        let cutoff = Utc::now() - Duration::days(Self::RETENTION_DAYS);
+        for server_id in touched {
+            Self::trim(db, server_id, cutoff).await?;
+        }
+        Ok(went_offline)
+    }
+
+    /// 通知服主服务器已离线
+    ///
+    /// 未配置 Redis 时无法判断冷却时间，不发送通知。
+    pub async fn notify_offline(db: &DatabaseConnection, went_offline: Vec<UptimeObservation>) {
+        let Some(redis) = RedisService::instance() else {
+            return;
+        };
+        let since = Utc::now() - Duration::minutes(Self::OFFLINE_ALERT_WINDOW_MINUTES);
+
+        for observation in went_offline {
+            if observation.observed_at < since {
+                continue;
+            }
+            let key = format!("{}:{}", Self::OFFLINE_ALERT_PREFIX, observation.server_id);
+            match redis
+                .set_nx_ex(&key, "1", Self::OFFLINE_ALERT_COOLDOWN_SECS)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("⚠️  写入离线通知冷却时间失败: {}", e);
+                    continue;
+                }
+            }
+
+            let name: Option<String> = match Server::find_by_id(observation.server_id)
+                .select_only()
+                .column(server::Column::Name)
+                .into_tuple()
+                .one(db.as_ref())
+                .await
+            {
+                Ok(name) => name,
+                Err(e) => {
+                    tracing::warn!("⚠️  查询服务器 {} 失败: {}", observation.server_id, e);
+                    continue;
+                }
+            };
+            let Some(name) = name else {
+                continue;
+            };
+
+            NotificationService::notify_server_owners(
+                db,
+                observation.server_id,
+                Notification {
+                    kind: NotificationKind::ServerOffline,
+                    title: "服务器已离线".to_string(),
+                    body: format!(
+                        "服务器「{name}」于 {} 起无法连接，请检查服务器运行状态。",
+                        observation.observed_at.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                },
+            )
+            .await;
+        }
+    }
+
+    /// 获取服务器的在线率与当前状态，只返回同一租户内未隐藏、未停用的服务器
+    pub async fn uptime(
+        db: &DatabaseConnection,
+        tenant_id: &str,
+        server_id: i32,
+    ) -> ApiResult<ServerUptimeResponse> {
+        Server::find_by_id(server_id)
+            .select_only()
+            .column(server::Column::Id)
+            .filter(server::Column::TenantId.eq(tenant_id))
+            .filter(server::Column::DeactivatedAt.is_null())
+            .filter(server::Column::Visibility.ne(ServerVisibility::Hidden.as_str()))
+            .filter(server::Column::Status.eq(ServerStatus::Published.as_str()))
+            .into_tuple::<i32>()
+            .one(db.as_ref())
+            .await?
+            .ok_or_else(ApiError::server_not_found)?;
+
+        let now = Utc::now();
+        let since = now - StatsHistoryRange::Month.duration();
+        let before: Option<(bool, DateTime<Utc>)> = ServerUptimeEntity::find()
+            .select_only()
+            .column(server_uptime::Column::Online)
+            .column(server_uptime::Column::ChangedAt)
+            .filter(server_uptime::Column::ServerId.eq(server_id))
+            .filter(server_uptime::Column::ChangedAt.lt(since))
+            .order_by_desc(server_uptime::Column::ChangedAt)
+            .order_by_desc(server_uptime::Column::Id)
+            .into_tuple()
+            .one(db.as_ref())
+            .await?;
+        let within: Vec<(bool, DateTime<Utc>)> = ServerUptimeEntity::find()
+            .select_only()
+            .column(server_uptime::Column::Online)
+            .column(server_uptime::Column::ChangedAt)
+            .filter(server_uptime::Column::ServerId.eq(server_id))
+            .filter(server_uptime::Column::ChangedAt.gte(since))
+            .order_by_asc(server_uptime::Column::ChangedAt)
+            .order_by_asc(server_uptime::Column::Id)
+            .into_tuple()
+            .all(db.as_ref())
+            .await?;
+        let transitions: Vec<(bool, DateTime<Utc>)> = before.into_iter().chain(within).collect();
+
+        let current = transitions.last().copied();
+        let ratio =
+            |range: StatsHistoryRange| Self::ratio(&transitions, now - range.duration(), now);
+        Ok(ServerUptimeResponse {
+            online: current.map(|(online, _)| online),
+            since: current.map(|(_, changed_at)| changed_at),
+            online_streak_seconds: current
+                .filter(|(online, _)| *online)
+                .map(|(_, changed_at)| (now - changed_at).num_seconds().max(0)),
+            uptime_24h: ratio(StatsHistoryRange::Day),
+            uptime_7d: ratio(StatsHistoryRange::Week),
+            uptime_30d: ratio(StatsHistoryRange::Month),
+        })
+    }
+
+    /// 窗口内在线时长占有记录时长的比例，窗口内没有任何记录时为空
+    fn ratio(
+        transitions: &[(bool, DateTime<Utc>)],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Option<f64> {
+        let mut covered = 0;
+        let mut online = 0;
+        for (index, (state, changed_at)) in transitions.iter().enumerate() {
+            let next = transitions
+                .get(index + 1)
+                .map_or(end, |(_, changed_at)| *changed_at);
+            let seconds = (next.min(end) - (*changed_at).max(start)).num_seconds();
+            if seconds <= 0 {
+                continue;
+            }
+            covered += seconds;
+            if *state {
+                online += seconds;
+            }
+        }
+        (covered > 0).then(|| online as f64 / covered as f64)
+    }
+
+    /// 每个服务器最近一次切换后的状态与切换时间
+    async fn latest_states<C: ConnectionTrait>(
+        db: &C,
+        server_ids: HashSet<i32>,
+    ) -> ApiResult<HashMap<i32, (bool, DateTime<Utc>)>> {
+        let latest_ids: Vec<i32> = ServerUptimeEntity::find()
+            .select_only()
+            .column_as(server_uptime::Column::Id.max(), "id")
+            .filter(server_uptime::Column::ServerId.is_in(server_ids))
+            .group_by(server_uptime::Column::ServerId)
+            .into_tuple::<Option<i32>>()
+            .all(db)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        if latest_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        Ok(ServerUptimeEntity::find()
+            .filter(server_uptime::Column::Id.is_in(latest_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|entry| (entry.server_id, (entry.online, entry.changed_at)))
+            .collect())
+    }
+
+    /// 删除保留范围之前的记录，保留范围开始前的最后一次切换用于确定窗口起点的状态
+    async fn trim<C: ConnectionTrait>(
+        db: &C,
+        server_id: i32,
+        cutoff: DateTime<Utc>,
+    ) -> ApiResult<()> {
+        let boundary: Option<DateTime<Utc>> = ServerUptimeEntity::find()
+            .select_only()
+            .column(server_uptime::Column::ChangedAt)
+            .filter(server_uptime::Column::ServerId.eq(server_id))
+            .filter(server_uptime::Column::ChangedAt.lt(cutoff))
+            .order_by_desc(server_uptime::Column::ChangedAt)
+            .limit(1)
+            .into_tuple()
+            .one(db)
+            .await?;
+
+        if let Some(boundary) = boundary {
+            ServerUptimeEntity::delete_many()
+                .filter(server_uptime::Column::ServerId.eq(server_id))
+                .filter(server_uptime::Column::ChangedAt.lt(boundary))
+                .exec(db)
+                .await?;
+        }
+        Ok(())
+    }
+}
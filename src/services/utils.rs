@@ -1,7 +1,11 @@
+use axum::http::HeaderMap;
 use rand::Rng;
 use reqwest::Client;
 use serde_json::Value;
-use std::sync::Arc;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 use tokio::sync::{Mutex, RwLock};
 
 lazy_static::lazy_static! {
@@ -13,14 +17,14 @@ lazy_static::lazy_static! {
 
 const QUEUE_SIZE: usize = 10; // 队列大小
 
-pub async fn maintain_sentence_queue() {
+pub async fn maintain_sentence_queue(interval: std::time::Duration) {
     tokio::spawn(async move {
         loop {
             // 补充队列
             refill_sentence_queue().await;
 
-            // 检查间隔：每5秒检查一次队列状态
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            // 按配置的间隔检查队列状态
+            tokio::time::sleep(interval).await;
         }
     });
 }
@@ -92,3 +96,63 @@ pub fn generate_verification_code() -> String {
         .map(|_| rng.random_range(0..10).to_string())
         .collect()
 }
+
+/// 从 `X-Forwarded-For` 中获取客户端 IP
+///
+/// 请求进入路由前由 [`client_ip_middleware`](crate::middleware::client_ip_middleware)
+/// 把该头改写为可信的客户端 IP，客户端自行填写的值不会出现在这里。
+/// 能解析为 IP 时去掉端口与 IPv6 的方括号（`[2001:db8::1]:443` → `2001:db8::1`），
+/// IPv4 映射地址（`::ffff:1.2.3.4`）还原为 IPv4，保证同一客户端在不同代理下得到相同的值。
+pub fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(normalize_ip)
+        .filter(|s| !s.is_empty())
+}
+
+/// 根据连接的对端地址与可信代理层数确定客户端 IP
+///
+/// 每层代理把它看到的对端地址追加到 `X-Forwarded-For` 末尾，只有最近的 `trusted_hops`
+/// 层追加的部分可信：把对端地址接在末尾后从右往左跳过 `trusted_hops` 个地址，
+/// 得到的即最外层可信代理看到的客户端。对端地址未知或该位置不是合法 IP 时返回 `None`。
+pub fn trusted_client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_hops: usize,
+) -> Option<IpAddr> {
+    let mut chain: Vec<Option<IpAddr>> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .flat_map(|h| {
+            // 客户端填写的部分可能不是合法的头部值，按字节解析以免连带丢掉代理追加的地址
+            String::from_utf8_lossy(h.as_bytes())
+                .split(',')
+                .map(parse_ip)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    chain.push(Some(peer?));
+    chain[chain.len().saturating_sub(trusted_hops + 1)].map(|ip| ip.to_canonical())
+}
+
+fn normalize_ip(value: &str) -> String {
+    parse_ip(value)
+        .map(|ip| ip.to_canonical().to_string())
+        .unwrap_or_else(|| value.trim().to_string())
+}
+
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    value
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| {
+            value
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|ip| ip.parse::<IpAddr>().ok())
+        })
+}
@@ -0,0 +1,48 @@
+//! API 密钥权限范围测试
+//!
+//! 只读密钥只能调用安全方法，管理服务器的密钥还能调用 `/v2/servers` 下的写接口，
+//! 两者都不能访问管理后台与密钥管理接口。
+
+use axum::http::Method;
+use server_api_rt::schemas::users::ApiKeyScope;
+use server_api_rt::services::api_key::ApiKeyService;
+
+#[test]
+fn read_only_keys_allow_safe_methods() {
+    let scope = ApiKeyScope::ReadOnly;
+    assert!(ApiKeyService::permits(scope, &Method::GET, "/v2/servers/1"));
+    assert!(ApiKeyService::permits(scope, &Method::GET, "/v2/users/me"));
+    assert!(!ApiKeyService::permits(scope, &Method::PUT, "/v2/servers/1"));
+    assert!(!ApiKeyService::permits(scope, &Method::PATCH, "/v2/users/me"));
+}
+
+#[test]
+fn manage_server_keys_write_only_servers() {
+    let scope = ApiKeyScope::ManageServer;
+    assert!(ApiKeyService::permits(scope, &Method::PUT, "/v2/servers/1"));
+    assert!(ApiKeyService::permits(scope, &Method::POST, "/v2/servers"));
+    assert!(ApiKeyService::permits(scope, &Method::POST, "/v2/servers/1/gallery"));
+    assert!(ApiKeyService::permits(scope, &Method::PATCH, "/v2/servers/1/gallery/3/"));
+    assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/users/me/password"));
+    assert!(!ApiKeyService::permits(scope, &Method::DELETE, "/v2/users/me"));
+}
+
+#[test]
+fn manage_server_keys_cannot_take_over_servers() {
+    let scope = ApiKeyScope::ManageServer;
+    assert!(!ApiKeyService::permits(scope, &Method::DELETE, "/v2/servers/1"));
+    assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/servers/1/managers"));
+    assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/servers/1/managers/2/transfer"));
+    assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/servers/1/push-secret"));
+    assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/servers/1/archive"));
+    assert!(!ApiKeyService::permits(scope, &Method::PUT, "/v2/servers/1/gallery/order/extra"));
+}
+
+#[test]
+fn keys_never_reach_admin_or_key_management() {
+    for scope in [ApiKeyScope::ReadOnly, ApiKeyScope::ManageServer] {
+        assert!(!ApiKeyService::permits(scope, &Method::GET, "/v2/admin/servers"));
+        assert!(!ApiKeyService::permits(scope, &Method::GET, "/v2/users/me/api-keys"));
+        assert!(!ApiKeyService::permits(scope, &Method::POST, "/v2/users/me/api-keys"));
+    }
+}
@@ -0,0 +1,83 @@
+//! 用户封禁测试
+//!
+//! 封禁、解封与封禁记录只作用于当前租户内的用户；解封时间按 RFC 3339 解析。
+
+use std::sync::Arc;
+
+use sea_orm::{DatabaseBackend, MockDatabase};
+use server_api_rt::entities::users;
+use server_api_rt::errors::ErrorCode;
+use server_api_rt::schemas::admin::{BanQuery, BanType, CreateBanRequest};
+use server_api_rt::services::ban::BanService;
+
+fn request() -> CreateBanRequest {
+    serde_json::from_str(r#"{"ban_type": "ban"}"#).unwrap()
+}
+
+#[tokio::test]
+async fn refuses_users_of_other_tenants() {
+    // 目标用户属于其他租户时按租户过滤后查不到
+    let db = Arc::new(
+        MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([Vec::<users::Model>::new(), Vec::new()])
+            .into_connection(),
+    );
+
+    let err = BanService::ban(&db, "tenant-b", 7, request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UserNotFound);
+    let err = BanService::lift(&db, "tenant-b", 7).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UserNotFound);
+
+    let db = Arc::try_unwrap(db).unwrap();
+    let log = format!("{:?}", db.into_transaction_log());
+    assert_eq!(log.matches("`tenant_id` = ?").count(), 2, "{log}");
+    assert!(log.contains("tenant-b"), "{log}");
+    // 没有写入或结束任何封禁记录
+    assert!(!log.contains("ban_records"), "{log}");
+}
+
+#[tokio::test]
+async fn lists_only_bans_of_current_tenant() {
+    let db = Arc::new(MockDatabase::new(DatabaseBackend::MySql).into_connection());
+    let query: BanQuery = serde_json::from_str("{}").unwrap();
+
+    // 查询结果为空时报错无关紧要，只检查生成的 SQL
+    let _ = BanService::list(&db, "tenant-b", &query).await;
+
+    let db = Arc::try_unwrap(db).unwrap();
+    let log = format!("{:?}", db.into_transaction_log());
+    assert!(log.contains("`users`.`tenant_id` = ?"), "{log}");
+    assert!(log.contains("tenant-b"), "{log}");
+}
+
+#[test]
+fn parses_ended_at_as_rfc3339() {
+    let request: CreateBanRequest = serde_json::from_str(
+        r#"{"ban_type": "temp_ban", "ended_at": "2025-01-01T08:00:00+08:00"}"#,
+    )
+    .unwrap();
+    assert_eq!(request.ban_type, BanType::TempBan);
+    assert_eq!(
+        request.ended_at.unwrap().to_rfc3339(),
+        "2025-01-01T00:00:00+00:00"
+    );
+
+    // 缺省与 null 都表示不设解封时间
+    assert!(request_ended_at(r#"{"ban_type": "mute"}"#).is_none());
+    assert!(request_ended_at(r#"{"ban_type": "mute", "ended_at": null}"#).is_none());
+
+    // 不带时区的时间会被拒绝
+    let err = serde_json::from_str::<CreateBanRequest>(
+        r#"{"ban_type": "temp_ban", "ended_at": "2025-01-01T08:00:00"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("RFC 3339"), "{err}");
+}
+
+fn request_ended_at(json: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    serde_json::from_str::<CreateBanRequest>(json)
+        .unwrap()
+        .ended_at
+}
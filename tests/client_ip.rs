@@ -0,0 +1,107 @@
+//! 客户端 IP 测试
+//!
+//! `X-Forwarded-For` 由客户端随意填写，只有可信代理追加的地址才能用于限流与会话记录。
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::ConnectInfo,
+    http::{HeaderMap, HeaderValue, Request},
+    middleware::from_fn_with_state,
+    routing::get,
+    Extension, Router,
+};
+use server_api_rt::middleware::client_ip_middleware;
+use server_api_rt::services::utils::{client_ip, trusted_client_ip};
+use tower::ServiceExt;
+
+fn forwarded(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+    headers
+}
+
+fn ip(input: &str) -> Option<IpAddr> {
+    Some(input.parse().unwrap())
+}
+
+#[test]
+fn takes_address_added_by_outermost_trusted_proxy() {
+    let proxy = ip("127.0.0.1");
+    // 客户端伪造的 1.1.1.1 在代理追加的地址之前
+    let headers = forwarded("1.1.1.1, 203.0.113.10");
+    assert_eq!(trusted_client_ip(&headers, proxy, 1), ip("203.0.113.10"));
+    assert_eq!(trusted_client_ip(&headers, proxy, 2), ip("1.1.1.1"));
+    // 不经代理时只认连接的对端地址
+    assert_eq!(trusted_client_ip(&headers, proxy, 0), proxy);
+
+    // 代理层数多于地址数时取最左边的地址
+    assert_eq!(
+        trusted_client_ip(&forwarded("203.0.113.10"), proxy, 3),
+        ip("203.0.113.10")
+    );
+    assert_eq!(trusted_client_ip(&HeaderMap::new(), proxy, 1), proxy);
+}
+
+#[test]
+fn unknown_peer_or_invalid_entry_yields_none() {
+    let headers = forwarded("203.0.113.10");
+    assert_eq!(trusted_client_ip(&headers, None, 1), None);
+    assert_eq!(
+        trusted_client_ip(&forwarded("203.0.113.10, unknown"), ip("127.0.0.1"), 1),
+        None
+    );
+
+    // 代理追加的地址统一格式
+    assert_eq!(
+        trusted_client_ip(&forwarded("[::ffff:203.0.113.10]:443"), ip("::1"), 1),
+        ip("203.0.113.10")
+    );
+}
+
+async fn seen_ip(peer: &str, xff: Option<&'static str>, trusted_hops: usize) -> String {
+    let app = Router::new()
+        .route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                format!(
+                    "{}|{}",
+                    client_ip(&headers).unwrap_or_default(),
+                    headers.contains_key("x-real-ip")
+                )
+            }),
+        )
+        .layer(from_fn_with_state(trusted_hops, client_ip_middleware))
+        // 与 `into_make_service_with_connect_info` 一样在请求扩展中放入对端地址
+        .layer(Extension(ConnectInfo(peer.parse::<SocketAddr>().unwrap())));
+
+    let mut request = Request::builder().uri("/").header("x-real-ip", "9.9.9.9");
+    if let Some(xff) = xff {
+        request = request.header("x-forwarded-for", xff);
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), 1024).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn middleware_rewrites_forwarded_headers() {
+    assert_eq!(
+        seen_ip("127.0.0.1:50000", Some("1.1.1.1, 203.0.113.10"), 1).await,
+        "203.0.113.10|false"
+    );
+    // 直接对外提供服务时忽略客户端填写的头
+    assert_eq!(
+        seen_ip("198.51.100.7:50000", Some("1.1.1.1"), 0).await,
+        "198.51.100.7|false"
+    );
+    // 无法确定时按匿名请求处理
+    assert_eq!(
+        seen_ip("127.0.0.1:50000", Some("1.1.1.1, garbage"), 1).await,
+        "|false"
+    );
+}
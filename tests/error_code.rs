@@ -0,0 +1,43 @@
+//! 错误码测试
+
+use axum::{body::to_bytes, http::StatusCode, response::IntoResponse};
+use server_api_rt::errors::{ApiError, ErrorCode};
+
+async fn render(error: ApiError) -> (StatusCode, serde_json::Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn attached_code_keeps_status_and_message() {
+    let error =
+        ApiError::Conflict("用户名已被占用".to_string()).with_code(ErrorCode::UsernameTaken);
+    assert_eq!(error.code(), ErrorCode::UsernameTaken);
+
+    let (status, body) = render(error).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "用户名已被占用");
+    assert_eq!(body["code"], "USERNAME_TAKEN");
+    assert_eq!(body["status"], 409);
+
+    let (status, body) = render(ApiError::server_not_found()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "SERVER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn message_does_not_decide_code() {
+    // 错误码只由构造方式决定，相同的错误信息不会被映射为具体错误码
+    let (status, body) = render(ApiError::NotFound("服务器不存在".to_string())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let error = ApiError::BadRequest("图片文件无效".to_string())
+        .with_code(ErrorCode::InvalidImage)
+        .with_code(ErrorCode::InvalidImageFormat);
+    let (status, body) = render(error).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_IMAGE_FORMAT");
+}
@@ -0,0 +1,68 @@
+//! 代入用户身份测试
+//!
+//! 只能代入当前租户内已启用的普通用户。
+
+use chrono::Utc;
+use sea_orm::{DatabaseBackend, MockDatabase};
+use server_api_rt::entities::users::{self, RoleEnum};
+use server_api_rt::errors::ErrorCode;
+use server_api_rt::services::auth::AuthService;
+
+fn user(role: RoleEnum, is_active: bool) -> users::Model {
+    users::Model {
+        id: 7,
+        username: "player".to_string(),
+        email: "player@example.com".to_string(),
+        display_name: "玩家".to_string(),
+        hashed_password: String::new(),
+        role,
+        is_active,
+        created_at: Utc::now(),
+        last_login: None,
+        last_login_ip: None,
+        avatar_hash_id: None,
+        avatar_small_hash_id: None,
+        tenant_id: "tenant-a".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn refuses_users_of_other_tenants() {
+    // 目标用户属于其他租户时按租户过滤后查不到
+    let db = MockDatabase::new(DatabaseBackend::MySql)
+        .append_query_results([Vec::<users::Model>::new()])
+        .into_connection();
+
+    let err = AuthService::impersonation_target(&db, "tenant-b", 7)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UserNotFound);
+
+    let log = format!("{:?}", db.into_transaction_log());
+    assert!(log.contains("`tenant_id` = ?"), "{log}");
+    assert!(log.contains("tenant-b"), "{log}");
+}
+
+#[tokio::test]
+async fn refuses_staff_and_inactive_users() {
+    let db = MockDatabase::new(DatabaseBackend::MySql)
+        .append_query_results([
+            vec![user(RoleEnum::Moderator, true)],
+            vec![user(RoleEnum::User, false)],
+            vec![user(RoleEnum::User, true)],
+        ])
+        .into_connection();
+
+    let err = AuthService::impersonation_target(&db, "tenant-a", 7)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Forbidden);
+    let err = AuthService::impersonation_target(&db, "tenant-a", 7)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::BadRequest);
+    let target = AuthService::impersonation_target(&db, "tenant-a", 7)
+        .await
+        .unwrap();
+    assert_eq!(target.id, 7);
+}
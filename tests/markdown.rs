@@ -0,0 +1,129 @@
+//! 服务器描述 Markdown 清理与渲染测试
+//!
+//! 写入时按白名单清理原文中的 HTML，移除不安全的链接与多余图片；渲染时转义原文中的 HTML，
+//! 只输出白名单内的标签与安全的链接地址。
+
+use server_api_rt::services::markdown::MarkdownService;
+
+#[test]
+fn sanitize_strips_script_and_iframe() {
+    let desc = "欢迎<script>alert(1)</script>来玩\n<IFRAME src=\"https://evil.example\"></iframe>结束<script>";
+    assert_eq!(MarkdownService::sanitize(desc), "欢迎来玩\n结束");
+}
+
+#[test]
+fn sanitize_removes_event_handlers_and_script_urls() {
+    let desc = "<img src=\"/a.png\" onerror=\"alert(1)\"> [点我](javascript:alert(1))";
+    assert_eq!(
+        MarkdownService::sanitize(desc),
+        "<img src=\"/a.png\" loading=\"lazy\"> 点我"
+    );
+}
+
+#[test]
+fn sanitize_handles_parser_differentials() {
+    // 不符合 CommonMark 标签语法、但浏览器会当作标签解析的写法
+    assert_eq!(
+        MarkdownService::sanitize("<svg/onload=alert(1)>\n\n文字 <img/src=x/onerror=alert(1)>"),
+        "&lt;svg/onload=alert(1)>\n\n文字 &lt;img/src=x/onerror=alert(1)>"
+    );
+    // 实体编码的协议
+    assert_eq!(
+        MarkdownService::sanitize("<a href=\"&#106;avascript:alert(1)\">点我</a>"),
+        "<a rel=\"nofollow noopener noreferrer\">点我</a>"
+    );
+    assert_eq!(
+        MarkdownService::sanitize("[*点我!*](&#106;avascript:alert(1)) ![x](//evil.example/x.png)"),
+        "点我\\! "
+    );
+}
+
+#[test]
+fn sanitize_limits_images() {
+    let desc: String = (0..12)
+        .map(|i| format!("![图{i}](/img/{i}.png)\n"))
+        .collect();
+    let sanitized = MarkdownService::sanitize(&desc);
+    assert_eq!(sanitized.matches("![").count(), 10);
+    assert!(sanitized.contains("![图9]"));
+    assert!(!sanitized.contains("![图10]"));
+}
+
+#[test]
+fn sanitize_keeps_plain_markdown() {
+    let desc = "# 标题\n\n- 生存\n- **PVP**\n\n[官网](https://example.com)";
+    assert_eq!(MarkdownService::sanitize(desc), desc);
+}
+
+#[test]
+fn render_escapes_raw_html() {
+    assert_eq!(
+        MarkdownService::render_html("<b>粗体</b> & \"引号\""),
+        "<p>&lt;b&gt;粗体&lt;/b&gt; &amp; \"引号\"</p>\n"
+    );
+}
+
+#[test]
+fn render_blocks() {
+    let html = MarkdownService::render_html(
+        "## 介绍\n\n第一行\n第二行\n\n- 生存\n- 建筑\n\n1. 注册\n2. 进服\n\n> 引用\n\n---\n\n```\n<code>\n```",
+    );
+    assert_eq!(
+        html,
+        "<h2>介绍</h2>\n\
+         <p>第一行<br>\n第二行</p>\n\
+         <ul>\n<li>生存</li>\n<li>建筑</li>\n</ul>\n\
+         <ol>\n<li>注册</li>\n<li>进服</li>\n</ol>\n\
+         <blockquote>\n<p>引用</p>\n</blockquote>\n\
+         <hr>\n\
+         <pre><code>&lt;code&gt;\n</code></pre>\n"
+    );
+}
+
+#[test]
+fn render_inline() {
+    assert_eq!(
+        MarkdownService::render_html("**粗** *斜* ~~删~~ `a<b` my_server_name"),
+        "<p><strong>粗</strong> <em>斜</em> <del>删</del> <code>a&lt;b</code> my_server_name</p>\n"
+    );
+}
+
+#[test]
+fn render_links_and_images() {
+    assert_eq!(
+        MarkdownService::render_html("[官网](https://example.com \"标题\") ![封面](/c.png)"),
+        "<p><a href=\"https://example.com\" rel=\"nofollow noopener noreferrer\">官网</a> \
+         <img src=\"/c.png\" alt=\"封面\" loading=\"lazy\"></p>\n"
+    );
+}
+
+#[test]
+fn render_drops_unsafe_urls() {
+    assert_eq!(
+        MarkdownService::render_html(
+            "[点我](javascript:void) ![x](data:image/png;base64,AA) [外链](//evil.example)"
+        ),
+        "<p>点我 x 外链</p>\n"
+    );
+    assert_eq!(
+        MarkdownService::render_html("<svg/onload=alert(1)> [x](&#106;avascript:alert(1))"),
+        "<p>&lt;svg/onload=alert(1)&gt; x</p>\n"
+    );
+}
+
+#[test]
+fn intro_takes_first_text_line() {
+    let desc = "```\ncode\n```\n\n![横幅](/img/banner.png)\n\n# **超好玩**的 [生存服](https://example.com)\n\n第二段";
+    assert_eq!(MarkdownService::intro(desc, 80), "超好玩的 生存服");
+    assert_eq!(
+        MarkdownService::intro("> <b>纯净</b> 生存 & 建筑", 80),
+        "纯净 生存 & 建筑"
+    );
+    assert_eq!(MarkdownService::intro("---\n\n", 80), "");
+}
+
+#[test]
+fn intro_truncates_long_lines() {
+    assert_eq!(MarkdownService::intro("一二三四五六", 4), "一二三…");
+    assert_eq!(MarkdownService::intro("一二三四", 4), "一二三四");
+}
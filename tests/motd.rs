@@ -0,0 +1,77 @@
+//! MOTD 格式转换测试
+//!
+//! 覆盖 § 格式代码与 JSON 文本组件两种输入，以及外部写入的状态数据的重新生成。
+
+use serde_json::json;
+use server_api_rt::schemas::servers::Motd;
+use server_api_rt::services::motd;
+
+#[test]
+fn converts_legacy_codes() {
+    let motd = motd::from_legacy("§aHello §lWorld§r!");
+    assert_eq!(motd.plain, "Hello World!");
+    assert_eq!(motd.minecraft, "§aHello §lWorld§r!");
+    assert_eq!(
+        motd.html,
+        "<span style='color: #55FF55;'>Hello </span>\
+         <span style='color: #55FF55; font-weight: bold;'>World</span>!"
+    );
+    assert_eq!(
+        motd.ansi,
+        "\x1b[0m\x1b[92mHello \x1b[1mWorld\x1b[0m!\x1b[0m"
+    );
+}
+
+#[test]
+fn escapes_html() {
+    let motd = motd::from_legacy("<script>&</script>");
+    assert_eq!(motd.html, "&lt;script&gt;&amp;&lt;/script&gt;");
+}
+
+#[test]
+fn flattens_text_components() {
+    let motd = motd::from_description(&json!({
+        "text": "A",
+        "color": "red",
+        "extra": [{"text": "B", "bold": true}, "C"]
+    }));
+    assert_eq!(motd.plain, "ABC");
+    assert_eq!(motd.minecraft, "§cA§c§lB§cC");
+}
+
+#[test]
+fn parse_detects_json_components() {
+    assert_eq!(
+        motd::parse(r#"{"text":"Hi","color":"gold"}"#).minecraft,
+        "§6Hi"
+    );
+    // 形似 JSON 但无法解析时按普通文本处理
+    assert_eq!(motd::parse("[生存] 欢迎").plain, "[生存] 欢迎");
+}
+
+#[test]
+fn regenerates_external_stat_data() {
+    let motd = motd::from_value(&json!({
+        "plain": "伪造",
+        "html": "<img src=x onerror=alert(1)>",
+        "minecraft": "§e欢迎",
+        "ansi": ""
+    }));
+    assert_eq!(motd.plain, "欢迎");
+    assert_eq!(motd.html, "<span style='color: #FFFF55;'>欢迎</span>");
+
+    assert_eq!(motd::from_value(&json!("§b欢迎")).plain, "欢迎");
+    assert_eq!(motd::from_value(&json!({"text": "组件"})).plain, "组件");
+    assert_eq!(motd::from_value(&json!(null)).html, "");
+}
+
+#[test]
+fn normalize_falls_back_to_plain() {
+    let motd = motd::normalize(&Motd {
+        plain: "只有纯文本".to_string(),
+        html: "<b>x</b>".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(motd.plain, "只有纯文本");
+    assert_eq!(motd.html, "只有纯文本");
+}
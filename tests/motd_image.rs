@@ -0,0 +1,57 @@
+//! MOTD 图片渲染测试
+//!
+//! 使用只包含少量字形的 Unifont `.hex` 字体，检查格式代码的解析与绘制结果。
+
+use server_api_rt::schemas::servers::MotdTheme;
+use server_api_rt::services::motd;
+use server_api_rt::services::motd_image::{BitmapFont, MotdImageService};
+
+/// 一个实心的半角字形
+const FONT: &str = "0041:FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF\n0020:00000000000000000000000000000000\n";
+
+#[test]
+fn runs_follow_color_codes() {
+    let runs = motd::runs("§cA§lB\nC");
+    let texts: Vec<&str> = runs.iter().map(|run| run.text.as_str()).collect();
+    assert_eq!(texts, ["A", "B", "\n", "C"]);
+    assert_eq!(runs[0].color, Some([0xFF, 0x55, 0x55]));
+    assert!(!runs[0].bold);
+    assert!(runs[1].bold);
+}
+
+#[test]
+fn rejects_malformed_fonts() {
+    assert!(BitmapFont::parse("0041:FFFF").is_err());
+    assert!(BitmapFont::parse("zz:FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF").is_err());
+    assert!(BitmapFont::parse("# 只有注释").is_err());
+}
+
+#[test]
+fn renders_colored_png() {
+    let font = BitmapFont::parse(FONT).unwrap();
+    let png = MotdImageService::render(&font, "§cA", 320, MotdTheme::Dark).unwrap();
+    let image = image::load_from_memory(&png).unwrap().to_rgba8();
+
+    assert_eq!(image.width(), 320);
+    // 左上角为背景，第一个字形从内边距处开始
+    assert_eq!(image.get_pixel(0, 0).0, [0x20, 0x20, 0x20, 0xFF]);
+    assert_eq!(image.get_pixel(8, 8).0, [0xFF, 0x55, 0x55, 0xFF]);
+}
+
+#[test]
+fn clamps_width_and_keys_by_content() {
+    let font = BitmapFont::parse(FONT).unwrap();
+    let png = MotdImageService::render(&font, "A", 10_000, MotdTheme::Light).unwrap();
+    let image = image::load_from_memory(&png).unwrap();
+    assert_eq!(image.width(), MotdImageService::MAX_WIDTH);
+
+    let key = MotdImageService::cache_key(1, "A", 640, MotdTheme::Dark);
+    assert_ne!(
+        key,
+        MotdImageService::cache_key(1, "B", 640, MotdTheme::Dark)
+    );
+    assert_ne!(
+        key,
+        MotdImageService::cache_key(1, "A", 640, MotdTheme::Light)
+    );
+}
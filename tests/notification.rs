@@ -0,0 +1,46 @@
+//! 通知类型与通知邮件模板测试
+//!
+//! 通知类型以字符串形式保存在 `notification_opt_outs` 中，需要能原样解析回来；
+//! 邮件模板会转义通知内容，服务器名称等用户输入不会被当作 HTML。
+
+use askama::Template;
+use server_api_rt::schemas::users::NotificationKind;
+use server_api_rt::services::email::template::{
+    build_notification_template, NotificationEmailItem,
+};
+
+#[test]
+fn kinds_round_trip_through_storage_names() {
+    for kind in NotificationKind::ALL {
+        assert_eq!(NotificationKind::parse(kind.as_str()), Some(kind));
+        let json = serde_json::to_value(kind).unwrap();
+        assert_eq!(json, kind.as_str());
+    }
+    assert_eq!(NotificationKind::parse("ticket_replied"), None);
+}
+
+#[test]
+fn only_account_notices_cannot_be_muted() {
+    assert!(!NotificationKind::AccountBanned.mutable());
+    assert!(NotificationKind::ServerOffline.mutable());
+    assert!(NotificationKind::SpamHoldReviewed.mutable());
+}
+
+#[test]
+fn notification_email_escapes_content() {
+    let html = build_notification_template(
+        "alice，您有一条新通知：".to_string(),
+        "通知类型可以在账户的偏好设置中调整。".to_string(),
+        vec![NotificationEmailItem {
+            title: "服务器已离线".to_string(),
+            body: "服务器「<b>Lobby</b>」无法连接".to_string(),
+        }],
+    )
+    .render()
+    .unwrap();
+
+    assert!(html.contains("alice，您有一条新通知："));
+    assert!(html.contains("服务器已离线"));
+    assert!(html.contains("&#60;b&#62;Lobby&#60;/b&#62;"));
+    assert!(!html.contains("<b>Lobby</b>"));
+}
@@ -0,0 +1,20 @@
+//! 第三方登录平台名称测试
+//!
+//! 平台名称既出现在回调路径中，也作为 `external_identities.provider` 保存，两者必须一致。
+
+use server_api_rt::schemas::auth::OAuthProvider;
+use server_api_rt::services::oauth::OAuthService;
+
+#[test]
+fn providers_round_trip_through_path_names() {
+    for provider in [OAuthProvider::Github, OAuthProvider::Microsoft] {
+        assert_eq!(OAuthProvider::parse(provider.as_str()), Some(provider));
+        assert_eq!(OAuthService::provider(provider.as_str()).unwrap(), provider);
+    }
+}
+
+#[test]
+fn unknown_providers_are_rejected() {
+    assert!(OAuthService::provider("gitlab").is_err());
+    assert!(OAuthService::provider("").is_err());
+}
@@ -0,0 +1,30 @@
+//! 密码哈希测试
+//!
+//! 哈希与校验互通；成本与配置不同的哈希在登录时需要重新计算，无法解析的哈希保持原样。
+
+use server_api_rt::services::password::PasswordService;
+
+#[tokio::test]
+async fn hash_round_trips() {
+    let hashed = PasswordService::hash("Password123".to_string(), 4)
+        .await
+        .unwrap();
+    assert!(
+        PasswordService::verify("Password123".to_string(), hashed.clone())
+            .await
+            .unwrap()
+    );
+    assert!(!PasswordService::verify("Password124".to_string(), hashed)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn rehash_only_when_cost_differs() {
+    let hashed = PasswordService::hash("Password123".to_string(), 5)
+        .await
+        .unwrap();
+    assert!(!PasswordService::needs_rehash(&hashed, 5));
+    assert!(PasswordService::needs_rehash(&hashed, 4));
+    assert!(!PasswordService::needs_rehash("not-a-bcrypt-hash", 4));
+}
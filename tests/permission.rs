@@ -0,0 +1,26 @@
+//! 服务器角色要求测试
+//!
+//! 服务器管理员要求同时接受管理员与服主，服主要求只接受服主。
+
+use server_api_rt::middleware::{Admin, Owner, ServerRole};
+use server_api_rt::schemas::servers::ServerManagerRole;
+
+#[test]
+fn manager_requirement_accepts_admins_and_owners() {
+    assert!(<Admin as ServerRole>::allows(ServerManagerRole::Admin));
+    assert!(<Admin as ServerRole>::allows(ServerManagerRole::Owner));
+}
+
+#[test]
+fn owner_requirement_rejects_admins() {
+    assert!(<Owner as ServerRole>::allows(ServerManagerRole::Owner));
+    assert!(!<Owner as ServerRole>::allows(ServerManagerRole::Admin));
+}
+
+#[test]
+fn stored_roles_round_trip() {
+    for role in [ServerManagerRole::Owner, ServerManagerRole::Admin] {
+        assert_eq!(ServerManagerRole::parse(role.as_str()), Some(role));
+    }
+    assert_eq!(ServerManagerRole::parse("member"), None);
+}
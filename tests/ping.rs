@@ -0,0 +1,74 @@
+//! 内置状态采集的地址校验测试
+//!
+//! 只探测公网地址，字面 IP 与域名解析结果都要检查。
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use server_api_rt::schemas::servers::AddressHost;
+use server_api_rt::services::ping::collector::PingService;
+
+fn ip(input: &str) -> IpAddr {
+    input.parse().unwrap()
+}
+
+#[test]
+fn rejects_non_public_addresses() {
+    for input in [
+        "127.0.0.1",
+        "10.0.0.1",
+        "172.16.5.4",
+        "192.168.1.1",
+        "169.254.169.254",
+        "0.0.0.0",
+        "224.0.0.1",
+        "255.255.255.255",
+        "100.64.0.1",
+        "::1",
+        "::",
+        "ff02::1",
+        "fd00::1",
+        "fe80::1",
+        "::ffff:127.0.0.1",
+        "::ffff:192.168.1.1",
+    ] {
+        assert!(
+            !PingService::is_public(ip(input)),
+            "{input} 不应视为公网地址"
+        );
+    }
+}
+
+#[test]
+fn accepts_public_addresses() {
+    for input in [
+        "1.1.1.1",
+        "203.0.113.9",
+        "100.128.0.1",
+        "2606:4700::1111",
+        "::ffff:8.8.8.8",
+    ] {
+        assert!(PingService::is_public(ip(input)), "{input} 应视为公网地址");
+    }
+}
+
+#[tokio::test]
+async fn resolve_rejects_private_targets() {
+    let limit = Duration::from_secs(2);
+
+    let err = PingService::resolve(&AddressHost::Ip(ip("192.168.1.1")), 25565, limit)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("公网"), "{err}");
+
+    // 域名解析到本机地址同样拒绝
+    let err = PingService::resolve(&AddressHost::Domain("localhost".to_string()), 25565, limit)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("公网"), "{err}");
+
+    let addr = PingService::resolve(&AddressHost::Ip(ip("1.1.1.1")), 19132, limit)
+        .await
+        .unwrap();
+    assert_eq!(addr.to_string(), "1.1.1.1:19132");
+}
@@ -0,0 +1,144 @@
+//! 服务器地址解析与校验测试
+//!
+//! 覆盖创建、编辑服务器时 `ip` 字段接受的格式：域名、IPv4、IPv6（带端口时加方括号），
+//! 以及未写端口时 Java 版与基岩版各自的默认端口。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use server_api_rt::schemas::servers::{AddressHost, ApiServerType, IpFamily, ServerAddress};
+
+fn parse(input: &str) -> ServerAddress {
+    ServerAddress::parse(input).unwrap_or_else(|| panic!("{input} 应当是有效地址"))
+}
+
+#[test]
+fn accepts_hostname_with_and_without_port() {
+    let address = parse("mc.example.com:25565");
+    assert_eq!(
+        address.host,
+        AddressHost::Domain("mc.example.com".to_string())
+    );
+    assert_eq!(address.port, Some(25565));
+
+    let address = parse("Play.Example.COM");
+    assert_eq!(
+        address.host,
+        AddressHost::Domain("play.example.com".to_string())
+    );
+    assert_eq!(address.port, None);
+
+    assert_eq!(
+        parse("localhost").host,
+        AddressHost::Domain("localhost".to_string())
+    );
+}
+
+#[test]
+fn accepts_ipv4_with_and_without_port() {
+    let address = parse("203.0.113.7:19133");
+    assert_eq!(
+        address.host,
+        AddressHost::Ip(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)))
+    );
+    assert_eq!(address.port, Some(19133));
+    assert_eq!(parse("203.0.113.7").port, None);
+}
+
+#[test]
+fn accepts_ipv6_bare_and_bracketed() {
+    let ip = IpAddr::V6("2001:db8::1".parse::<Ipv6Addr>().unwrap());
+
+    let address = parse("2001:db8::1");
+    assert_eq!(address.host, AddressHost::Ip(ip));
+    assert_eq!(address.port, None);
+
+    let address = parse("[2001:db8::1]");
+    assert_eq!(address.host, AddressHost::Ip(ip));
+    assert_eq!(address.port, None);
+
+    let address = parse("[2001:db8::1]:25565");
+    assert_eq!(address.host, AddressHost::Ip(ip));
+    assert_eq!(address.port, Some(25565));
+}
+
+#[test]
+fn rejects_invalid_addresses() {
+    for input in [
+        "",
+        ":25565",
+        "mc.example.com:",
+        "mc.example.com:0",
+        "mc.example.com:65536",
+        "mc.example.com:abc",
+        "mc..example.com",
+        "-mc.example.com",
+        "mc_example.com",
+        "mc.example.com/path",
+        "[2001:db8::1",
+        "[2001:db8::1]25565",
+        "[mc.example.com]:25565",
+    ] {
+        assert!(
+            ServerAddress::parse(input).is_none(),
+            "{input:?} 不应通过校验"
+        );
+    }
+}
+
+#[test]
+fn default_port_depends_on_server_type() {
+    assert_eq!(ApiServerType::Java.default_port(), 25565);
+    assert_eq!(ApiServerType::Bedrock.default_port(), 19132);
+
+    let address = parse("mc.example.com");
+    assert_eq!(address.port_or_default(&ApiServerType::Java), 25565);
+    assert_eq!(address.port_or_default(&ApiServerType::Bedrock), 19132);
+
+    let address = parse("mc.example.com:30000");
+    assert_eq!(address.port_or_default(&ApiServerType::Java), 30000);
+    assert_eq!(address.port_or_default(&ApiServerType::Bedrock), 30000);
+}
+
+#[test]
+fn formats_normalized_address() {
+    assert_eq!(
+        parse(" MC.Example.com:25565 ").to_string(),
+        "mc.example.com:25565"
+    );
+    assert_eq!(
+        parse("[2001:DB8::1]:25565").to_string(),
+        "[2001:db8::1]:25565"
+    );
+    assert_eq!(parse("2001:db8::1").to_string(), "2001:db8::1");
+}
+
+#[test]
+fn reports_ip_family_for_literals_only() {
+    assert_eq!(IpFamily::of("203.0.113.7:25565"), Some(IpFamily::Ipv4));
+    assert_eq!(IpFamily::of("[2001:db8::1]:25565"), Some(IpFamily::Ipv6));
+    assert_eq!(IpFamily::of("mc.example.com"), None);
+}
+
+#[test]
+fn update_request_accepts_hostname() {
+    use validator::Validate;
+
+    let mut request = server_api_rt::schemas::servers::UpdateServerRequest {
+        name: "我的世界服务器".to_string(),
+        ip: "mc.example.com:25565".to_string(),
+        desc: "简介".repeat(60),
+        tags: vec!["生存".to_string()],
+        version: "1.20.1".to_string(),
+        version_range: None,
+        link: "https://example.com".to_string(),
+        cover: None,
+        slug: None,
+        visibility: None,
+        player_search_opt_out: None,
+    };
+    assert!(request.validate().is_ok());
+
+    request.ip = "mc.example.com:99999".to_string();
+    let errors = request.validate().unwrap_err();
+    assert!(errors.field_errors().contains_key("ip"));
+}
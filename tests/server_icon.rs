@@ -0,0 +1,42 @@
+//! 服务器图标校验测试
+//!
+//! 图标与 16:9 的封面分开上传，只接受边长在限制范围内的正方形图片。
+
+use std::io::Cursor;
+
+use image::{ImageFormat, RgbaImage};
+use server_api_rt::services::file_upload::{FileUploadService, ICON_MAX_SIZE, ICON_MIN_SIZE};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    RgbaImage::new(width, height)
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    data
+}
+
+#[test]
+fn accepts_square_icons_within_limits() {
+    for side in [ICON_MIN_SIZE, 128, ICON_MAX_SIZE] {
+        assert_eq!(
+            FileUploadService::validate_icon(&png(side, side)).unwrap(),
+            side
+        );
+    }
+}
+
+#[test]
+fn rejects_non_square_or_out_of_range_icons() {
+    for (width, height) in [
+        (512, 288),
+        (128, 127),
+        (ICON_MIN_SIZE - 1, ICON_MIN_SIZE - 1),
+        (ICON_MAX_SIZE + 1, ICON_MAX_SIZE + 1),
+    ] {
+        assert!(
+            FileUploadService::validate_icon(&png(width, height)).is_err(),
+            "{width}x{height} 不应通过校验"
+        );
+    }
+    assert!(FileUploadService::validate_icon(b"not an image").is_err());
+}
@@ -0,0 +1,40 @@
+//! 服务器发布状态测试
+//!
+//! 状态以字符串形式保存在 `server.status` 中，需要能原样解析回来；
+//! 只有已发布的服务器出现在列表与搜索中。
+
+use server_api_rt::schemas::servers::ServerStatus;
+
+const ALL: [ServerStatus; 5] = [
+    ServerStatus::Draft,
+    ServerStatus::PendingReview,
+    ServerStatus::Published,
+    ServerStatus::Rejected,
+    ServerStatus::Archived,
+];
+
+#[test]
+fn statuses_round_trip_through_storage_names() {
+    for status in ALL {
+        assert_eq!(ServerStatus::parse(status.as_str()), Some(status));
+        let json = serde_json::to_value(status).unwrap();
+        assert_eq!(json, status.as_str());
+    }
+    assert_eq!(ServerStatus::parse("pending"), None);
+}
+
+#[test]
+fn only_published_servers_are_listed() {
+    for status in ALL {
+        assert_eq!(status.is_listed(), status == ServerStatus::Published);
+    }
+}
+
+#[test]
+fn pending_and_published_servers_cannot_be_submitted() {
+    assert!(ServerStatus::Draft.can_submit());
+    assert!(ServerStatus::Rejected.can_submit());
+    assert!(ServerStatus::Archived.can_submit());
+    assert!(!ServerStatus::PendingReview.can_submit());
+    assert!(!ServerStatus::Published.can_submit());
+}
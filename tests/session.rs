@@ -0,0 +1,35 @@
+//! 会话客户端信息测试
+//!
+//! 会话记录登录时的 IP 与 User-Agent，User-Agent 由客户端随意填写，保存前需要截断。
+
+use axum::http::{HeaderMap, HeaderValue};
+use server_api_rt::services::auth::SessionClient;
+
+#[test]
+fn client_is_read_from_forwarded_headers() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-forwarded-for",
+        HeaderValue::from_static("203.0.113.10, 10.0.0.1"),
+    );
+    headers.insert("user-agent", HeaderValue::from_static("Mozilla/5.0"));
+
+    let client = SessionClient::from_headers(&headers);
+    assert_eq!(client.ip.as_deref(), Some("203.0.113.10"));
+    assert_eq!(client.user_agent.as_deref(), Some("Mozilla/5.0"));
+}
+
+#[test]
+fn long_or_missing_user_agents_are_normalized() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "user-agent",
+        HeaderValue::from_str(&"a".repeat(1000)).unwrap(),
+    );
+    let client = SessionClient::from_headers(&headers);
+    assert_eq!(client.user_agent.map(|ua| ua.len()), Some(255));
+
+    let client = SessionClient::from_headers(&HeaderMap::new());
+    assert!(client.ip.is_none());
+    assert!(client.user_agent.is_none());
+}
@@ -0,0 +1,71 @@
+//! 请求签名测试
+
+use chrono::Utc;
+use server_api_rt::errors::ErrorCode;
+use server_api_rt::services::SigningService;
+
+const SECRET: &str = "test-secret";
+const BODY: &[u8] = br#"{"players":{"online":3,"max":100}}"#;
+const WINDOW: u64 = 300;
+
+#[test]
+fn accepts_valid_signature() {
+    let timestamp = Utc::now().timestamp();
+    let signature = SigningService::sign(SECRET, timestamp, BODY);
+
+    assert!(signature.starts_with("sha256="));
+    assert!(SigningService::verify(SECRET, timestamp, BODY, &signature, WINDOW).is_ok());
+}
+
+#[test]
+fn rejects_bad_signature() {
+    let timestamp = Utc::now().timestamp();
+    let signature = SigningService::sign(SECRET, timestamp, BODY);
+
+    // 密钥或请求体不一致
+    let err =
+        SigningService::verify("other-secret", timestamp, BODY, &signature, WINDOW).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SignatureInvalid);
+    let err = SigningService::verify(SECRET, timestamp, b"{}", &signature, WINDOW).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SignatureInvalid);
+
+    // 格式错误
+    let raw = signature.trim_start_matches("sha256=");
+    let err = SigningService::verify(SECRET, timestamp, BODY, raw, WINDOW).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SignatureInvalid);
+    let err = SigningService::verify(SECRET, timestamp, BODY, "sha256=zz", WINDOW).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SignatureInvalid);
+}
+
+#[test]
+fn rejects_stale_timestamp() {
+    let now = Utc::now().timestamp();
+    for timestamp in [now - WINDOW as i64 - 10, now + WINDOW as i64 + 10] {
+        let signature = SigningService::sign(SECRET, timestamp, BODY);
+        let err = SigningService::verify(SECRET, timestamp, BODY, &signature, WINDOW).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::SignatureExpired);
+    }
+}
+
+#[test]
+fn replayed_signature_maps_to_same_nonce() {
+    let timestamp = Utc::now().timestamp();
+    let signature = SigningService::sign(SECRET, timestamp, BODY);
+    let key = SigningService::nonce_key(1, &signature).unwrap();
+
+    // 改变十六进制大小写仍能通过校验，登记键必须相同，否则可以绕过重放检查
+    let hex = signature.trim_start_matches("sha256=");
+    let variants = [
+        format!("sha256={}", hex.to_uppercase()),
+        format!("sha256={}{}", &hex[..8].to_uppercase(), &hex[8..]),
+    ];
+    for variant in &variants {
+        assert!(SigningService::verify(SECRET, timestamp, BODY, variant, WINDOW).is_ok());
+        assert_eq!(SigningService::nonce_key(1, variant).unwrap(), key);
+    }
+
+    // 不同服务器的签名各自登记
+    assert_ne!(SigningService::nonce_key(2, &signature).unwrap(), key);
+    let err = SigningService::nonce_key(1, hex).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SignatureInvalid);
+}
@@ -0,0 +1,53 @@
+//! 状态上报校验测试
+//!
+//! 外部采集器批量上报与数据推送共用同一套校验。
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+use server_api_rt::schemas::internal::StatsBatchItem;
+use server_api_rt::schemas::servers::ServerStats;
+use server_api_rt::services::server::ServerService;
+
+fn stats(delay: f64, online: i64) -> ServerStats {
+    serde_json::from_value(json!({
+        "players": {"online": online, "max": 100},
+        "delay": delay,
+        "version": "Paper 1.20.1",
+        "motd": {"plain": "", "html": "", "minecraft": "§a欢迎", "ansi": ""},
+        "icon": null
+    }))
+    .unwrap()
+}
+
+#[test]
+fn accepts_valid_and_offline_rows() {
+    let now = Utc::now();
+    assert!(ServerService::check_stats(Some(&stats(12.5, 3)), Some(now), now).is_ok());
+    assert!(ServerService::check_stats(None, Some(now - Duration::days(1)), now).is_ok());
+    // 允许少量时钟偏差
+    assert!(ServerService::check_stats(None, Some(now + Duration::seconds(60)), now).is_ok());
+}
+
+#[test]
+fn rejects_invalid_rows() {
+    let now = Utc::now();
+    let future = Some(now + Duration::hours(1));
+    assert!(ServerService::check_stats(None, future, now).is_err());
+    assert!(ServerService::check_stats(Some(&stats(-1.0, 3)), None, now).is_err());
+    let mut nan = stats(0.0, 3);
+    nan.delay = f64::NAN;
+    assert!(ServerService::check_stats(Some(&nan), None, now).is_err());
+    assert!(ServerService::check_stats(Some(&stats(10.0, -3)), None, now).is_err());
+}
+
+#[test]
+fn accepts_raw_row_field_names() {
+    let item: StatsBatchItem = serde_json::from_value(json!({
+        "server_id": 1,
+        "stat_data": serde_json::to_value(stats(10.0, 1)).unwrap(),
+        "timestamp": "2025-01-01T00:00:00Z"
+    }))
+    .unwrap();
+    assert!(item.stats.is_some());
+    assert!(item.collected_at.is_some());
+}
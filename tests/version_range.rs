@@ -0,0 +1,95 @@
+//! 游戏版本范围解析与兼容判断测试
+//!
+//! 覆盖创建、编辑服务器时 `version_range` 接受的两种写法（区间与列表）、规范化结果，
+//! 以及按版本筛选时区间上限省略修订号的含义。
+
+use server_api_rt::schemas::servers::{GameVersion, VersionRange};
+
+fn version(input: &str) -> GameVersion {
+    GameVersion::parse(input).unwrap_or_else(|| panic!("{input} 应当是有效版本号"))
+}
+
+#[test]
+fn parses_game_versions() {
+    assert_eq!(
+        version("1.20.1"),
+        GameVersion {
+            major: 1,
+            minor: 20,
+            patch: Some(1)
+        }
+    );
+    assert_eq!(version(" 1.21 ").to_string(), "1.21");
+
+    for invalid in [
+        "",
+        "1",
+        "1.",
+        "1.20.1.2",
+        "1.x",
+        "v1.20",
+        "1.-1",
+        "1.1000",
+        "Paper 1.20.1",
+    ] {
+        assert!(
+            GameVersion::parse(invalid).is_none(),
+            "{invalid:?} 不应被解析"
+        );
+    }
+}
+
+#[test]
+fn upper_bound_without_patch_covers_all_patches() {
+    let range = VersionRange::parse(r#"{"min": "1.8", "max": "1.20"}"#).unwrap();
+
+    assert!(range.contains(&version("1.8")));
+    assert!(range.contains(&version("1.12.2")));
+    assert!(range.contains(&version("1.20.6")));
+    assert!(!range.contains(&version("1.7.10")));
+    assert!(!range.contains(&version("1.21")));
+}
+
+#[test]
+fn open_ended_ranges() {
+    let from = VersionRange::parse(r#"{"min": "1.16.5"}"#).unwrap();
+    assert!(from.contains(&version("1.21.4")));
+    assert!(!from.contains(&version("1.16.4")));
+
+    let until = VersionRange::parse(r#"{"max": "1.12.2"}"#).unwrap();
+    assert!(until.contains(&version("1.7.10")));
+    assert!(!until.contains(&version("1.13")));
+}
+
+#[test]
+fn version_lists_are_sorted_and_deduplicated() {
+    let range =
+        VersionRange::parse(r#"{"versions": ["1.20.1", " 1.8.9", "1.12.2", "1.20.1"]}"#).unwrap();
+
+    assert_eq!(range.versions, vec!["1.8.9", "1.12.2", "1.20.1"]);
+    assert_eq!(range.bounds(), None);
+    assert!(range.contains(&version("1.12.2")));
+    assert!(!range.contains(&version("1.12.1")));
+}
+
+#[test]
+fn rejects_invalid_ranges() {
+    for invalid in [
+        "not json",
+        "{}",
+        r#"{"min": "1.20", "max": "1.8"}"#,
+        r#"{"min": "1.8", "versions": ["1.20.1"]}"#,
+        r#"{"versions": ["1.20.1", "latest"]}"#,
+    ] {
+        assert!(
+            VersionRange::parse(invalid).is_err(),
+            "{invalid} 不应通过校验"
+        );
+    }
+
+    let too_many: Vec<String> = (0..=VersionRange::MAX_VERSIONS)
+        .map(|minor| format!("\"1.{minor}\""))
+        .collect();
+    let input = format!(r#"{{"versions": [{}]}}"#, too_many.join(","));
+    assert!(VersionRange::parse(&input).is_err());
+}